@@ -62,7 +62,7 @@ pub struct InfoResponse {
 }
 
 /// Fan status response containing all fan RPMs and PWMs
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct FanStatusResponse {
     /// Map of fan ID to current RPM
     #[serde(
@@ -76,6 +76,14 @@ pub struct FanStatusResponse {
         deserialize_with = "deserialize_u8_map"
     )]
     pub pwms: HashMap<u8, u32>,
+    /// Map of fan ID to its safety limits, for fans that have any
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_u8_keyed_map",
+        deserialize_with = "deserialize_u8_keyed_map"
+    )]
+    pub limits: HashMap<u8, crate::FanLimits>,
 }
 
 /// Single fan RPM response
@@ -173,6 +181,60 @@ pub struct UpdateZoneRequest {
     pub description: Option<String>,
 }
 
+/// Outcome of applying a value to one fan of a zone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZoneFanOutcome {
+    /// The value was applied
+    Success,
+    /// The controller rejected the value or the fan is invalid
+    Failure,
+    /// Not attempted because the fan's controller is disconnected
+    SkippedDisconnected,
+}
+
+/// Result of applying a value to one fan of a zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneFanResult {
+    /// Controller the fan belongs to
+    pub controller: String,
+    /// Fan ID on that controller
+    pub fan_id: u8,
+    /// What happened to this fan
+    pub outcome: ZoneFanOutcome,
+    /// Error message for failed or skipped fans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Zone apply response with a per-fan report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneApplyResponse {
+    /// Zone name
+    pub zone: String,
+    /// Control mode that was applied
+    pub mode: crate::ControlMode,
+    /// Applied value
+    pub value: u16,
+    /// One result per zone fan, in zone order
+    pub results: Vec<ZoneFanResult>,
+}
+
+impl ZoneApplyResponse {
+    /// Number of fans the value was applied to.
+    pub fn applied_count(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|r| r.outcome == ZoneFanOutcome::Success)
+            .count()
+    }
+
+    /// Whether every fan of the zone was applied.
+    pub fn is_complete(&self) -> bool {
+        self.applied_count() == self.results.len()
+    }
+}
+
 /// Thermal curve response containing all curves
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThermalCurveResponse {
@@ -197,6 +259,9 @@ pub struct AddCurveRequest {
     /// Optional description
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Optional smoothing, hysteresis and ramp limits
+    #[serde(flatten)]
+    pub tuning: crate::CurveTuning,
 }
 
 /// Thermal curve update request
@@ -207,6 +272,9 @@ pub struct UpdateCurveRequest {
     /// Optional description
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Tuning settings to change; settings left out keep their current value
+    #[serde(flatten)]
+    pub tuning: crate::CurveTuning,
 }
 
 /// Interpolation response
@@ -218,6 +286,381 @@ pub struct InterpolateResponse {
     pub pwm: u8,
 }
 
+/// PID controller response containing all PID controllers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PidControllerResponse {
+    /// Map of PID controller name to definition
+    pub controllers: HashMap<String, crate::PidController>,
+}
+
+/// Single PID controller response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SinglePidResponse {
+    /// PID controller definition
+    pub pid: crate::PidController,
+}
+
+/// PID controller addition request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddPidRequest {
+    /// PID controller name
+    pub name: String,
+    /// Target temperature in degrees Celsius
+    pub setpoint_c: f32,
+    /// Proportional gain
+    pub kp: f32,
+    /// Integral gain
+    pub ki: f32,
+    /// Derivative gain
+    pub kd: f32,
+    /// Lowest PWM output (default: 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_pwm: Option<u8>,
+    /// Highest PWM output (default: 100)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_pwm: Option<u8>,
+    /// Optional description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// PID controller update request
+///
+/// Settings left unset keep their current value. Running control loops pick
+/// up the new settings on their next poll and keep their accumulated state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdatePidRequest {
+    /// Target temperature in degrees Celsius
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setpoint_c: Option<f32>,
+    /// Proportional gain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kp: Option<f32>,
+    /// Integral gain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ki: Option<f32>,
+    /// Derivative gain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kd: Option<f32>,
+    /// Lowest PWM output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_pwm: Option<u8>,
+    /// Highest PWM output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_pwm: Option<u8>,
+    /// Description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// Curve binding with the runtime state of its control loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BindingStatus {
+    /// Binding definition
+    #[serde(flatten)]
+    pub binding: crate::CurveBinding,
+    /// Whether the control loop for this binding is running
+    pub running: bool,
+    /// Last temperature read from the sensor
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Last PWM value applied to the fans
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pwm: Option<u8>,
+    /// Error from the last control step, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Internal state of the PID controller, for PID bindings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid_state: Option<crate::PidState>,
+}
+
+/// Curve binding response containing all bindings of a controller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurveBindingResponse {
+    /// Map of binding name to binding status
+    pub bindings: HashMap<String, BindingStatus>,
+}
+
+/// Single curve binding response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SingleBindingResponse {
+    /// Binding status
+    pub binding: BindingStatus,
+}
+
+/// Curve binding addition request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddBindingRequest {
+    /// Binding name
+    pub name: String,
+    /// Thermal curve name (mutually exclusive with `pid`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve: Option<String>,
+    /// PID controller name (mutually exclusive with `curve`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<String>,
+    /// Temperature sensor ID or file path
+    pub sensor: String,
+    /// Zone to drive (mutually exclusive with `fans`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    /// Fan IDs to drive (mutually exclusive with `zone`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fans: Vec<u8>,
+    /// Optional poll interval in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval_secs: Option<u64>,
+}
+
+/// Kind of temperature sensor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SensorKind {
+    /// Linux hwmon sensor discovered under the sysfs hwmon root
+    Hwmon,
+    /// Custom sensor reading a number from a file
+    File,
+    /// Custom sensor running a program
+    Command,
+    /// Virtual sensor combining other sensors
+    Virtual,
+}
+
+/// Temperature sensor reading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorInfo {
+    /// Sensor ID, usable as the `sensor` of a curve binding (e.g., "k10temp/temp1")
+    pub id: String,
+    /// Kind of sensor
+    pub kind: SensorKind,
+    /// Name of the hwmon chip providing the sensor (e.g., "k10temp", "nvme")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chip: Option<String>,
+    /// Sensor label, if any (e.g., "Tctl", "Composite")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Where the temperature is read from (file path, command line or
+    /// virtual sensor formula)
+    pub source: String,
+    /// Current temperature in degrees Celsius
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f32>,
+    /// Error from reading the sensor, if it failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Input currently dominating a virtual sensor's reading
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dominant: Option<String>,
+}
+
+/// Sensor list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorListResponse {
+    /// Custom sensors in configuration order, then hwmon sensors
+    pub sensors: Vec<SensorInfo>,
+}
+
+/// Kind of fan alarm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlarmKind {
+    /// A fan expected to spin reports (almost) no RPM
+    Stall,
+    /// A fan expected to spin is slower than `[alarms] low_rpm`
+    LowRpm,
+    /// A fan set to 0% PWM is spinning
+    UnexpectedSpin,
+}
+
+/// Active fan alarm
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmInfo {
+    /// Controller ID
+    pub controller: String,
+    /// Fan ID
+    pub fan: u8,
+    /// Kind of alarm
+    pub kind: AlarmKind,
+    /// Last RPM read from the fan
+    pub rpm: u32,
+    /// PWM percentage the fan was set to, if set by PWM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pwm: Option<u32>,
+    /// Target RPM the fan was set to, if set by RPM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_rpm: Option<u32>,
+    /// When the alarm was raised (Unix timestamp)
+    pub since: u64,
+    /// Human-readable description
+    pub message: String,
+}
+
+/// Active alarm list response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmListResponse {
+    /// Active alarms, by controller and fan ID
+    pub alarms: Vec<AlarmInfo>,
+}
+
+/// Persisted configuration that can change at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigResource {
+    /// Fan aliases
+    Aliases,
+    /// Fan profiles
+    Profiles,
+    /// Global zones
+    Zones,
+    /// Thermal curves
+    ThermalCurves,
+    /// PID controllers
+    PidControllers,
+    /// Curve bindings
+    CurveBindings,
+    /// CFM mappings
+    CfmMappings,
+    /// Fan safety limits
+    FanLimits,
+    /// Settings from `config.toml`, changed by a reload
+    Config,
+}
+
+/// Event pushed on the live telemetry stream (`GET /api/v0/events`)
+///
+/// Sent as Server-Sent Events named after the `type` field, with the JSON
+/// event as data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    /// Fan readings of a controller, sampled by the daemon
+    FanStatus {
+        /// Controller ID
+        controller: String,
+        /// Fan RPMs and last set PWMs
+        status: FanStatusResponse,
+        /// Unix timestamp of the sample, in seconds
+        timestamp: u64,
+    },
+    /// Temperature sensor readings
+    Sensors {
+        /// Sensors with their current readings
+        sensors: Vec<SensorInfo>,
+    },
+    /// Connection state of a controller, sent when it changes
+    ConnectionState {
+        /// Controller ID
+        controller: String,
+        /// New state ("connected", "disconnected" or "reconnecting")
+        state: String,
+    },
+    /// Persisted configuration changed
+    ConfigChanged {
+        /// Controller whose data changed, or `None` for global data (zones)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        controller: Option<String>,
+        /// What changed
+        resource: ConfigResource,
+    },
+}
+
+impl TelemetryEvent {
+    /// Event name, as used for the Server-Sent Event
+    pub fn name(&self) -> &'static str {
+        match self {
+            TelemetryEvent::FanStatus { .. } => "fan_status",
+            TelemetryEvent::Sensors { .. } => "sensors",
+            TelemetryEvent::ConnectionState { .. } => "connection_state",
+            TelemetryEvent::ConfigChanged { .. } => "config_changed",
+        }
+    }
+
+    /// Controller the event is about, if any
+    pub fn controller(&self) -> Option<&str> {
+        match self {
+            TelemetryEvent::FanStatus { controller, .. }
+            | TelemetryEvent::ConnectionState { controller, .. } => Some(controller),
+            TelemetryEvent::ConfigChanged { controller, .. } => controller.as_deref(),
+            TelemetryEvent::Sensors { .. } => None,
+        }
+    }
+}
+
+/// Fan readings averaged over one history step
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryPoint {
+    /// Unix timestamp of the start of the step, in seconds
+    pub timestamp: u64,
+    /// Map of fan ID to average RPM
+    #[serde(
+        serialize_with = "serialize_u8_map",
+        deserialize_with = "deserialize_u8_map"
+    )]
+    pub rpms: HashMap<u8, u32>,
+    /// Map of fan ID to average PWM percentage, for fans that had been set
+    #[serde(
+        serialize_with = "serialize_u8_map",
+        deserialize_with = "deserialize_u8_map"
+    )]
+    pub pwms: HashMap<u8, u32>,
+}
+
+/// Fan history response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryResponse {
+    /// Controller ID
+    pub controller: String,
+    /// Unix timestamp of the start of the range, in seconds
+    pub from: u64,
+    /// Unix timestamp of the end of the range, in seconds
+    pub to: u64,
+    /// Time covered by each point, in seconds
+    pub step_secs: u64,
+    /// Points with readings, oldest first
+    pub points: Vec<HistoryPoint>,
+}
+
+/// What started a configuration reload
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReloadTrigger {
+    /// SIGHUP sent to the daemon
+    Signal,
+    /// Changed files seen by `[reload] watch`
+    Watch,
+    /// `POST /api/v0/config/reload`
+    Api,
+}
+
+/// Outcome of a configuration reload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadReport {
+    /// What started the reload
+    pub trigger: ReloadTrigger,
+    /// When the reload ran (Unix timestamp)
+    pub timestamp: u64,
+    /// Whether the new configuration is in use
+    pub success: bool,
+    /// Why the new configuration was rejected, if it was
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// What the reload changed, such as `[alarms]` or `controller 'main': profiles`
+    #[serde(default)]
+    pub changes: Vec<String>,
+    /// Changes read but not applied, which need a restart
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// Configuration reload status response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadStatusResponse {
+    /// Last reload since the daemon started, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_reload: Option<ReloadReport>,
+}
+
 /// CFM mappings list response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CfmListResponse {
@@ -227,6 +670,23 @@ pub struct CfmListResponse {
         deserialize_with = "deserialize_u8_f32_map"
     )]
     pub mappings: HashMap<u8, f32>,
+    /// Map of port ID to airflow model, for ports that have one
+    #[serde(
+        default,
+        skip_serializing_if = "HashMap::is_empty",
+        serialize_with = "serialize_u8_keyed_map",
+        deserialize_with = "deserialize_u8_keyed_map"
+    )]
+    pub models: HashMap<u8, crate::CfmModel>,
+}
+
+impl From<CfmListResponse> for crate::config::CfmMappingData {
+    fn from(response: CfmListResponse) -> Self {
+        Self {
+            mappings: response.mappings,
+            models: response.models,
+        }
+    }
 }
 
 /// Single CFM mapping response
@@ -236,13 +696,305 @@ pub struct CfmGetResponse {
     pub port: u8,
     /// CFM value at 100% PWM
     pub cfm_at_100: f32,
+    /// Airflow tables, RPM at 100% PWM and direction, when set
+    #[serde(flatten)]
+    pub model: crate::CfmModel,
 }
 
 /// CFM mapping set request
+///
+/// Replaces the port's mapping, including its airflow model.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SetCfmRequest {
     /// CFM value at 100% PWM
     pub cfm_at_100: f32,
+    /// Optional airflow tables, RPM at 100% PWM and direction
+    #[serde(flatten)]
+    pub model: crate::CfmModel,
+}
+
+/// Estimated airflow of one fan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanAirflow {
+    /// Controller the fan belongs to
+    pub controller: String,
+    /// Fan ID on that controller
+    pub fan_id: u8,
+    /// Current PWM percentage, if read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pwm: Option<u32>,
+    /// Current RPM, if read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rpm: Option<u32>,
+    /// Estimated airflow, if the fan has a CFM mapping and was read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cfm: Option<f32>,
+    /// How the airflow was estimated
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<crate::CfmSource>,
+    /// Whether the fan is an intake or an exhaust, if set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<crate::AirflowDirection>,
+    /// Why the fan could not be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Airflow of one controller's fans
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ControllerAirflowResponse {
+    /// Controller ID
+    pub controller: String,
+    /// One entry per fan, in fan ID order
+    pub fans: Vec<FanAirflow>,
+    /// Sum of the estimated airflow of the fans
+    pub total_cfm: f32,
+}
+
+/// Whether a zone pushes more air in than out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AirflowPressure {
+    /// More intake than exhaust airflow
+    Positive,
+    /// More exhaust than intake airflow
+    Negative,
+    /// Intake and exhaust within 5% of each other
+    Balanced,
+}
+
+/// Airflow totals of a zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneAirflow {
+    /// Zone name
+    pub zone: String,
+    /// Airflow of the intake fans
+    pub intake_cfm: f32,
+    /// Airflow of the exhaust fans
+    pub exhaust_cfm: f32,
+    /// Airflow of the fans without a direction, left out of the balance
+    pub undirected_cfm: f32,
+    /// Intake minus exhaust airflow
+    pub balance_cfm: f32,
+    /// Pressure the balance leads to
+    pub pressure: AirflowPressure,
+    /// One entry per zone fan, in zone order
+    pub fans: Vec<FanAirflow>,
+}
+
+impl ZoneAirflow {
+    /// Sum the airflow of a zone's fans by direction.
+    pub fn new(zone: impl Into<String>, fans: Vec<FanAirflow>) -> Self {
+        let total = |direction: Option<crate::AirflowDirection>| -> f32 {
+            fans.iter()
+                .filter(|fan| fan.direction == direction)
+                .filter_map(|fan| fan.cfm)
+                .sum()
+        };
+        let intake_cfm = total(Some(crate::AirflowDirection::Intake));
+        let exhaust_cfm = total(Some(crate::AirflowDirection::Exhaust));
+        let balance_cfm = intake_cfm - exhaust_cfm;
+
+        let pressure = if balance_cfm.abs() <= 0.05 * (intake_cfm + exhaust_cfm) {
+            AirflowPressure::Balanced
+        } else if balance_cfm > 0.0 {
+            AirflowPressure::Positive
+        } else {
+            AirflowPressure::Negative
+        };
+
+        Self {
+            zone: zone.into(),
+            intake_cfm,
+            exhaust_cfm,
+            undirected_cfm: total(None),
+            balance_cfm,
+            pressure,
+            fans,
+        }
+    }
+}
+
+/// Airflow of every zone
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZoneAirflowResponse {
+    /// One entry per zone, sorted by name
+    pub zones: Vec<ZoneAirflow>,
+}
+
+/// Default PWM step of a calibration sweep, in percent
+pub const DEFAULT_CALIBRATION_STEP: u32 = 10;
+
+/// Default time a fan is given to settle at each calibration step, in seconds
+pub const DEFAULT_CALIBRATION_SETTLE_SECS: u64 = 3;
+
+/// Longest time a fan can be given to settle at each calibration step, in seconds
+pub const MAX_CALIBRATION_SETTLE_SECS: u64 = 60;
+
+fn default_calibration_step() -> u32 {
+    DEFAULT_CALIBRATION_STEP
+}
+
+fn default_calibration_settle_secs() -> u64 {
+    DEFAULT_CALIBRATION_SETTLE_SECS
+}
+
+/// Request to calibrate the fans of a controller
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalibrationRequest {
+    /// Fans to calibrate; all fans of the controller when empty
+    #[serde(default)]
+    pub fans: Vec<u8>,
+    /// PWM step of the sweep, in percent (1-50)
+    #[serde(default = "default_calibration_step")]
+    pub step: u32,
+    /// Time each fan is given to settle at each step, in seconds
+    #[serde(default = "default_calibration_settle_secs")]
+    pub settle_secs: u64,
+}
+
+impl Default for CalibrationRequest {
+    fn default() -> Self {
+        Self {
+            fans: Vec::new(),
+            step: DEFAULT_CALIBRATION_STEP,
+            settle_secs: DEFAULT_CALIBRATION_SETTLE_SECS,
+        }
+    }
+}
+
+impl CalibrationRequest {
+    /// Validate the sweep parameters.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(1..=50).contains(&self.step) {
+            return Err(format!("Step must be 1-50% PWM, got {}", self.step));
+        }
+        if self.settle_secs > MAX_CALIBRATION_SETTLE_SECS {
+            return Err(format!(
+                "Settle time must be at most {} seconds, got {}",
+                MAX_CALIBRATION_SETTLE_SECS, self.settle_secs
+            ));
+        }
+        Ok(())
+    }
+
+    /// PWM values of the sweep, from 100% down to 0%.
+    pub fn sweep(&self) -> Vec<u32> {
+        let step = self.step.max(1);
+        let mut pwms: Vec<u32> = (0..=100).rev().step_by(step as usize).collect();
+        if pwms.last() != Some(&0) {
+            pwms.push(0);
+        }
+        pwms
+    }
+}
+
+/// State of a calibration job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CalibrationState {
+    /// Sweeping the fans
+    Running,
+    /// All fans calibrated and the results saved
+    Completed,
+    /// Stopped on request before the end
+    Cancelled,
+    /// Stopped by an error
+    Failed,
+}
+
+impl std::fmt::Display for CalibrationState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalibrationState::Running => write!(f, "running"),
+            CalibrationState::Completed => write!(f, "completed"),
+            CalibrationState::Cancelled => write!(f, "cancelled"),
+            CalibrationState::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+/// Progress of a calibration job
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationStatus {
+    /// Controller ID
+    pub controller: String,
+    /// Job state
+    pub state: CalibrationState,
+    /// Fans being calibrated
+    pub fans: Vec<u8>,
+    /// PWM step of the sweep, in percent
+    pub step: u32,
+    /// Settle time at each step, in seconds
+    pub settle_secs: u64,
+    /// PWM the fans are being measured at, while running
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_pwm: Option<u32>,
+    /// Steps measured so far
+    pub completed_steps: u32,
+    /// Steps of the whole job; the search for start PWMs may end early
+    pub total_steps: u32,
+    /// When the job started (Unix timestamp)
+    pub started_at: u64,
+    /// When the job ended (Unix timestamp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+    /// Why the job failed, if it did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl CalibrationStatus {
+    /// Check if the job is still running.
+    pub fn is_running(&self) -> bool {
+        self.state == CalibrationState::Running
+    }
+
+    /// Share of the steps done, in percent.
+    pub fn percent(&self) -> u32 {
+        if self.total_steps == 0 {
+            return 100;
+        }
+        (self.completed_steps * 100 / self.total_steps).min(100)
+    }
+}
+
+/// Calibration job status response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationStatusResponse {
+    /// Running or last calibration job of the controller, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub calibration: Option<CalibrationStatus>,
+}
+
+/// Stored calibration results of a controller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationResultsResponse {
+    /// Controller ID
+    pub controller: String,
+    /// Map of fan ID to calibration results, for calibrated fans
+    #[serde(
+        default,
+        serialize_with = "serialize_u8_keyed_map",
+        deserialize_with = "deserialize_u8_keyed_map"
+    )]
+    pub fans: HashMap<u8, crate::FanCalibration>,
+}
+
+/// Safety limits of a controller's fans
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanLimitsResponse {
+    /// Controller ID
+    pub controller: String,
+    /// How values outside the limits are handled
+    pub enforcement: crate::LimitEnforcement,
+    /// Map of fan ID to limits, for fans that have any
+    #[serde(
+        default,
+        serialize_with = "serialize_u8_keyed_map",
+        deserialize_with = "deserialize_u8_keyed_map"
+    )]
+    pub limits: HashMap<u8, crate::FanLimits>,
 }
 
 /// System information response
@@ -411,6 +1163,36 @@ where
     deserializer.deserialize_map(U8F32MapVisitor)
 }
 
+// Custom serialization for HashMap<u8, V> with values other than numbers and strings
+fn serialize_u8_keyed_map<S, V>(map: &HashMap<u8, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+    V: Serialize,
+{
+    use serde::ser::SerializeMap;
+    let mut ser_map = serializer.serialize_map(Some(map.len()))?;
+    for (k, v) in map {
+        ser_map.serialize_entry(&k.to_string(), v)?;
+    }
+    ser_map.end()
+}
+
+// Custom deserialization for HashMap<u8, V> with values other than numbers and strings
+fn deserialize_u8_keyed_map<'de, D, V>(deserializer: D) -> Result<HashMap<u8, V>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    V: Deserialize<'de>,
+{
+    let raw = HashMap::<String, V>::deserialize(deserializer)?;
+    raw.into_iter()
+        .map(|(key, value)| {
+            key.parse::<u8>()
+                .map(|port_id| (port_id, value))
+                .map_err(|_| serde::de::Error::custom(format!("Invalid port ID: {}", key)))
+        })
+        .collect()
+}
+
 // ============================================================================
 // Controller Management Types (Multi-Controller Support)
 // ============================================================================
@@ -431,6 +1213,12 @@ pub struct ControllerInfo {
     pub mock_mode: bool,
     /// Connection status
     pub connected: bool,
+    /// Device node the controller was last connected through
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_path: Option<String>,
+    /// USB serial number of the board, if it reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
 }
 
 /// Response for listing all controllers
@@ -442,11 +1230,99 @@ pub struct ControllersListResponse {
     pub controllers: Vec<ControllerInfo>,
 }
 
+/// Request to connect and register a controller while the server runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddControllerRequest {
+    /// Controller entry, with the fields of a `[[controllers]]` entry
+    #[serde(flatten)]
+    pub controller: crate::ControllerConfig,
+    /// Also add the entry to `[[controllers]]` in `config.toml`
+    #[serde(default)]
+    pub persist: bool,
+}
+
+/// Why a serial port was considered by discovery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscoverySource {
+    /// Named by the `OPENFAN_COMPORT` environment variable
+    Env,
+    /// USB VID/PID of a known OpenFAN board
+    UsbId,
+    /// Common device path
+    CommonPath,
+}
+
+/// Serial port that may be an OpenFAN board
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredPort {
+    /// Device path (e.g. `/dev/ttyACM0`)
+    pub port: String,
+    /// Why the port was considered
+    pub source: DiscoverySource,
+    /// USB VID:PID (e.g. "2E8A:000A"), for USB ports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usb_id: Option<String>,
+    /// USB serial number, if the device reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+    /// USB product string, if the device reports one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
+    /// Controller already using this port; such ports are not probed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub controller: Option<String>,
+    /// Firmware info returned by the probe
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub firmware: Option<String>,
+    /// Error from probing the port
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response for controller discovery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoverResponse {
+    /// Candidate ports in discovery order
+    pub ports: Vec<DiscoveredPort>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::types::ControlMode;
 
+    #[test]
+    fn test_zone_apply_response() {
+        let response = ZoneApplyResponse {
+            zone: "intake".to_string(),
+            mode: ControlMode::Pwm,
+            value: 60,
+            results: vec![
+                ZoneFanResult {
+                    controller: "main".to_string(),
+                    fan_id: 0,
+                    outcome: ZoneFanOutcome::Success,
+                    error: None,
+                },
+                ZoneFanResult {
+                    controller: "gpu".to_string(),
+                    fan_id: 0,
+                    outcome: ZoneFanOutcome::SkippedDisconnected,
+                    error: Some("Device disconnected".to_string()),
+                },
+            ],
+        };
+        assert_eq!(response.applied_count(), 1);
+        assert!(!response.is_complete());
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["mode"], "pwm");
+        assert_eq!(json["results"][0]["outcome"], "success");
+        assert!(json["results"][0].get("error").is_none());
+        assert_eq!(json["results"][1]["outcome"], "skipped_disconnected");
+    }
+
     #[test]
     fn test_api_response_success() {
         let response = ApiResponse::success("test data");
@@ -531,7 +1407,11 @@ mod tests {
         pwms.insert(0, 50);
         pwms.insert(1, 75);
 
-        let response = FanStatusResponse { rpms, pwms };
+        let response = FanStatusResponse {
+            rpms,
+            pwms,
+            ..Default::default()
+        };
         let json = serde_json::to_string(&response).unwrap();
 
         assert!(json.contains("1200"));
@@ -539,4 +1419,297 @@ mod tests {
         assert!(json.contains("50"));
         assert!(json.contains("75"));
     }
+
+    #[test]
+    fn test_binding_status_flattens_binding() {
+        let status = BindingStatus {
+            binding: crate::CurveBinding::for_fans("cpu", "Balanced", "/tmp/temp", vec![0, 1]),
+            running: true,
+            temperature: Some(42.5),
+            pwm: Some(38),
+            error: None,
+            pid_state: None,
+        };
+
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["name"], "cpu");
+        assert_eq!(json["curve"], "Balanced");
+        assert_eq!(json["running"], true);
+        assert!(json.get("error").is_none());
+        assert!(json.get("pid").is_none());
+
+        let parsed: BindingStatus = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.binding, status.binding);
+        assert_eq!(parsed.pwm, Some(38));
+    }
+
+    #[test]
+    fn test_alarm_info_serialization() {
+        let alarm = AlarmInfo {
+            controller: "main".to_string(),
+            fan: 2,
+            kind: AlarmKind::LowRpm,
+            rpm: 250,
+            pwm: Some(60),
+            target_rpm: None,
+            since: 1_700_000_000,
+            message: "Fan 2 at 250 RPM with 60% PWM".to_string(),
+        };
+
+        let json = serde_json::to_value(&alarm).unwrap();
+        assert_eq!(json["kind"], "low_rpm");
+        assert_eq!(json["pwm"], 60);
+        assert!(json.get("target_rpm").is_none());
+
+        let parsed: AlarmInfo = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.kind, AlarmKind::LowRpm);
+        assert_eq!(parsed.fan, 2);
+    }
+
+    #[test]
+    fn test_telemetry_event_serialization() {
+        let event = TelemetryEvent::FanStatus {
+            controller: "main".to_string(),
+            status: FanStatusResponse {
+                rpms: HashMap::from([(0, 1200)]),
+                pwms: HashMap::from([(0, 50)]),
+                ..Default::default()
+            },
+            timestamp: 1_700_000_000,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], event.name());
+        assert_eq!(json["status"]["rpms"]["0"], 1200);
+
+        let parsed: TelemetryEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.controller(), Some("main"));
+
+        let event = TelemetryEvent::ConfigChanged {
+            controller: None,
+            resource: ConfigResource::Zones,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "config_changed");
+        assert_eq!(json["resource"], "zones");
+        assert!(json.get("controller").is_none());
+    }
+
+    #[test]
+    fn test_reload_report_serialization() {
+        let report = ReloadReport {
+            trigger: ReloadTrigger::Signal,
+            timestamp: 1_700_000_000,
+            success: false,
+            error: Some("Failed to parse config file".to_string()),
+            changes: Vec::new(),
+            warnings: Vec::new(),
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["trigger"], "signal");
+        assert_eq!(json["error"], "Failed to parse config file");
+
+        let status: ReloadStatusResponse = serde_json::from_str("{}").unwrap();
+        assert!(status.last_reload.is_none());
+    }
+
+    #[test]
+    fn test_set_cfm_request_model() {
+        let request: SetCfmRequest = serde_json::from_str(
+            r#"{"cfm_at_100": 60.0, "pwm_points": [{"at": 20, "cfm": 0.0}, {"at": 100, "cfm": 60.0}], "direction": "intake"}"#,
+        )
+        .unwrap();
+        assert_eq!(request.model.pwm_points.len(), 2);
+        assert_eq!(
+            request.model.direction,
+            Some(crate::AirflowDirection::Intake)
+        );
+
+        // A bare CFM@100% has no model
+        let request: SetCfmRequest = serde_json::from_str(r#"{"cfm_at_100": 45.0}"#).unwrap();
+        assert!(request.model.is_empty());
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json.get("pwm_points").is_none());
+    }
+
+    #[test]
+    fn test_cfm_list_response_models_roundtrip() {
+        let mut models = HashMap::new();
+        models.insert(
+            2,
+            crate::CfmModel {
+                max_rpm: Some(1800),
+                ..Default::default()
+            },
+        );
+        let response = ApiResponse::success(CfmListResponse {
+            mappings: HashMap::from([(2, 55.0)]),
+            models,
+        });
+
+        let json = serde_json::to_string(&response).unwrap();
+        let parsed: ApiResponse<CfmListResponse> = serde_json::from_str(&json).unwrap();
+        let ApiResponse::Success { data } = parsed else {
+            panic!("expected success");
+        };
+        assert_eq!(data.models[&2].max_rpm, Some(1800));
+    }
+
+    #[test]
+    fn test_calibration_request_defaults_and_sweep() {
+        let request: CalibrationRequest = serde_json::from_str("{}").unwrap();
+        assert_eq!(request, CalibrationRequest::default());
+        assert!(request.validate().is_ok());
+        assert_eq!(
+            request.sweep(),
+            vec![100, 90, 80, 70, 60, 50, 40, 30, 20, 10, 0]
+        );
+
+        let request = CalibrationRequest {
+            step: 30,
+            ..Default::default()
+        };
+        assert_eq!(request.sweep(), vec![100, 70, 40, 10, 0]);
+
+        let request = CalibrationRequest {
+            step: 0,
+            ..Default::default()
+        };
+        assert!(request.validate().is_err());
+        let request = CalibrationRequest {
+            settle_secs: 61,
+            ..Default::default()
+        };
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn test_calibration_results_roundtrip() {
+        let calibration = crate::FanCalibration {
+            points: vec![
+                crate::CalibrationPoint::new(0, 0),
+                crate::CalibrationPoint::new(100, 1800),
+            ],
+            min_start_pwm: Some(30),
+            min_stable_pwm: Some(20),
+            calibrated_at: 1_700_000_000,
+        };
+        let response = ApiResponse::success(CalibrationResultsResponse {
+            controller: "main".to_string(),
+            fans: HashMap::from([(3, calibration.clone())]),
+        });
+
+        let json = serde_json::to_string(&response).unwrap();
+        let parsed: ApiResponse<CalibrationResultsResponse> = serde_json::from_str(&json).unwrap();
+        let ApiResponse::Success { data } = parsed else {
+            panic!("expected success");
+        };
+        assert_eq!(data.fans[&3], calibration);
+    }
+
+    #[test]
+    fn test_fan_limits_response_roundtrip() {
+        let limits = crate::FanLimits {
+            min_pwm: Some(30),
+            max_pwm: None,
+            min_rpm: Some(900),
+            allow_zero: false,
+        };
+        let response = ApiResponse::success(FanLimitsResponse {
+            controller: "main".to_string(),
+            enforcement: crate::LimitEnforcement::Reject,
+            limits: HashMap::from([(2, limits)]),
+        });
+
+        let json = serde_json::to_string(&response).unwrap();
+        assert!(json.contains(r#""enforcement":"reject""#));
+        let parsed: ApiResponse<FanLimitsResponse> = serde_json::from_str(&json).unwrap();
+        let ApiResponse::Success { data } = parsed else {
+            panic!("expected success");
+        };
+        assert_eq!(data.limits[&2], limits);
+
+        // Fan status only carries limits when some fan has them
+        let status = ApiResponse::success(FanStatusResponse {
+            rpms: HashMap::from([(2, 1000)]),
+            pwms: HashMap::from([(2, 40)]),
+            limits: HashMap::from([(2, limits)]),
+        });
+        let json = serde_json::to_string(&status).unwrap();
+        let parsed: ApiResponse<FanStatusResponse> = serde_json::from_str(&json).unwrap();
+        let ApiResponse::Success { data } = parsed else {
+            panic!("expected success");
+        };
+        assert_eq!(data.limits[&2], limits);
+        let json = serde_json::to_string(&FanStatusResponse::default()).unwrap();
+        assert!(!json.contains("limits"));
+    }
+
+    #[test]
+    fn test_zone_airflow_totals() {
+        let fan = |fan_id, cfm, direction| FanAirflow {
+            controller: "main".to_string(),
+            fan_id,
+            pwm: Some(50),
+            rpm: Some(1000),
+            cfm,
+            source: cfm.map(|_| crate::CfmSource::Linear),
+            direction,
+            error: None,
+        };
+        let intake = Some(crate::AirflowDirection::Intake);
+        let exhaust = Some(crate::AirflowDirection::Exhaust);
+
+        let zone = ZoneAirflow::new(
+            "case",
+            vec![
+                fan(0, Some(30.0), intake),
+                fan(1, Some(25.0), intake),
+                fan(2, Some(40.0), exhaust),
+                fan(3, Some(10.0), None),
+                fan(4, None, exhaust),
+            ],
+        );
+        assert_eq!(zone.intake_cfm, 55.0);
+        assert_eq!(zone.exhaust_cfm, 40.0);
+        assert_eq!(zone.undirected_cfm, 10.0);
+        assert_eq!(zone.balance_cfm, 15.0);
+        assert_eq!(zone.pressure, AirflowPressure::Positive);
+
+        let zone = ZoneAirflow::new(
+            "case",
+            vec![fan(0, Some(20.0), intake), fan(1, Some(40.0), exhaust)],
+        );
+        assert_eq!(zone.pressure, AirflowPressure::Negative);
+
+        let zone = ZoneAirflow::new(
+            "case",
+            vec![fan(0, Some(40.0), intake), fan(1, Some(39.0), exhaust)],
+        );
+        assert_eq!(zone.pressure, AirflowPressure::Balanced);
+        assert_eq!(
+            ZoneAirflow::new("empty", vec![]).pressure,
+            AirflowPressure::Balanced
+        );
+    }
+
+    #[test]
+    fn test_add_controller_request_deserialization() {
+        let request: AddControllerRequest =
+            serde_json::from_str(r#"{"id": "gpu", "device": "/dev/ttyACM1", "board": "custom:4"}"#)
+                .unwrap();
+        assert_eq!(request.controller.id, "gpu");
+        assert_eq!(
+            request.controller.board,
+            crate::BoardType::Custom { fan_count: 4 }
+        );
+        assert!(!request.persist);
+
+        let json = serde_json::to_value(AddControllerRequest {
+            persist: true,
+            ..request
+        })
+        .unwrap();
+        assert_eq!(json["device"], "/dev/ttyACM1");
+        assert_eq!(json["persist"], true);
+    }
 }
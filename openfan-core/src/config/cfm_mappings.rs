@@ -2,9 +2,16 @@
 //!
 //! Stored in `{data_dir}/cfm_mappings.toml`
 //!
-//! Provides optional per-port PWM→CFM conversion for display purposes.
-//! CFM (Cubic Feet per Minute) values are calculated using linear interpolation:
+//! Provides optional per-port airflow estimates for display purposes.
+//! Each mapped port has a CFM (Cubic Feet per Minute) value at 100% PWM.
+//! Without more information, airflow is assumed linear in PWM:
 //! `cfm = (pwm / 100.0) * cfm_at_100`
+//!
+//! Real fans stall below some PWM and flatten near the top, so a port can also
+//! have a [`CfmModel`]: PWM→CFM or RPM→CFM tables, interpolated linearly
+//! between points, and the RPM at 100% PWM for estimates scaled from the
+//! measured RPM. The model also tells whether the fan is an intake or an
+//! exhaust, for zone airflow totals.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -12,9 +19,194 @@ use std::collections::HashMap;
 /// Maximum allowed CFM value (reasonable upper limit for PC fans)
 pub const MAX_CFM: f32 = 500.0;
 
+/// Maximum RPM accepted in CFM models
+pub const MAX_CFM_RPM: u32 = 30000;
+
+/// Which way a fan moves air through the case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AirflowDirection {
+    /// Pulls air into the case
+    Intake,
+    /// Pushes air out of the case
+    Exhaust,
+}
+
+impl std::fmt::Display for AirflowDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AirflowDirection::Intake => write!(f, "intake"),
+            AirflowDirection::Exhaust => write!(f, "exhaust"),
+        }
+    }
+}
+
+impl std::str::FromStr for AirflowDirection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "intake" | "in" => Ok(AirflowDirection::Intake),
+            "exhaust" | "out" => Ok(AirflowDirection::Exhaust),
+            _ => Err(format!(
+                "Invalid airflow direction '{}': expected 'intake' or 'exhaust'",
+                s
+            )),
+        }
+    }
+}
+
+/// A point of an airflow table, mapping PWM or RPM to CFM.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CfmPoint {
+    /// PWM percentage or RPM, depending on the table
+    pub at: u32,
+    /// Airflow in CFM at that point
+    pub cfm: f32,
+}
+
+impl CfmPoint {
+    /// Create a new airflow point.
+    pub fn new(at: u32, cfm: f32) -> Self {
+        Self { at, cfm }
+    }
+}
+
+/// How an airflow estimate was obtained, from most to least accurate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CfmSource {
+    /// Interpolated from the measured RPM in the RPM→CFM table
+    RpmTable,
+    /// Scaled from CFM@100% by the measured RPM over the RPM at 100% PWM
+    RpmScaled,
+    /// Interpolated from the PWM in the PWM→CFM table
+    PwmTable,
+    /// Linear in PWM from CFM@100%
+    Linear,
+}
+
+impl std::fmt::Display for CfmSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CfmSource::RpmTable => write!(f, "rpm table"),
+            CfmSource::RpmScaled => write!(f, "rpm"),
+            CfmSource::PwmTable => write!(f, "pwm table"),
+            CfmSource::Linear => write!(f, "linear"),
+        }
+    }
+}
+
+/// Airflow estimate for a port.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CfmEstimate {
+    /// Estimated airflow in CFM
+    pub cfm: f32,
+    /// How the estimate was obtained
+    pub source: CfmSource,
+}
+
+/// Non-linear airflow model of a port.
+///
+/// Every part is optional. Tables need at least 2 points, sorted by PWM or
+/// RPM in ascending order.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CfmModel {
+    /// PWM→CFM points, PWM in percent
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pwm_points: Vec<CfmPoint>,
+    /// RPM→CFM points, used when the fan's RPM is known
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rpm_points: Vec<CfmPoint>,
+    /// RPM at 100% PWM, to scale CFM@100% by the measured RPM
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_rpm: Option<u32>,
+    /// Whether the fan is an intake or an exhaust
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub direction: Option<AirflowDirection>,
+}
+
+impl CfmModel {
+    /// Check if the model holds nothing.
+    pub fn is_empty(&self) -> bool {
+        *self == CfmModel::default()
+    }
+
+    /// Validate the tables and the RPM at 100% PWM.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        validate_points("PWM", &self.pwm_points, 100)?;
+        validate_points("RPM", &self.rpm_points, MAX_CFM_RPM)?;
+        if let Some(max_rpm) = self.max_rpm
+            && !(1..=MAX_CFM_RPM).contains(&max_rpm)
+        {
+            return Err(format!(
+                "Max RPM {} is outside valid range (1 to {})",
+                max_rpm, MAX_CFM_RPM
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Check that a table has enough points, in order and in range.
+fn validate_points(name: &str, points: &[CfmPoint], max_at: u32) -> Result<(), String> {
+    if points.is_empty() {
+        return Ok(());
+    }
+    if points.len() < 2 {
+        return Err(format!("{} table needs at least 2 points", name));
+    }
+    for point in points {
+        if point.at > max_at {
+            return Err(format!(
+                "{} table point {} exceeds maximum of {}",
+                name, point.at, max_at
+            ));
+        }
+        if !(point.cfm.is_finite() && (0.0..=MAX_CFM).contains(&point.cfm)) {
+            return Err(format!(
+                "{} table CFM {} is outside valid range (0 to {})",
+                name, point.cfm, MAX_CFM
+            ));
+        }
+    }
+    if points.windows(2).any(|pair| pair[0].at >= pair[1].at) {
+        return Err(format!(
+            "{} table points must be sorted in ascending order without duplicates",
+            name
+        ));
+    }
+    Ok(())
+}
+
+/// Interpolate CFM in a table, holding the end values outside of it.
+///
+/// Returns `None` for tables with fewer than 2 points.
+fn interpolate(points: &[CfmPoint], at: u32) -> Option<f32> {
+    let (first, last) = match points {
+        [first, .., last] => (first, last),
+        _ => return None,
+    };
+    if at <= first.at {
+        return Some(first.cfm);
+    }
+    if at >= last.at {
+        return Some(last.cfm);
+    }
+    points.windows(2).find_map(|pair| {
+        let (p1, p2) = (&pair[0], &pair[1]);
+        (at >= p1.at && at < p2.at).then(|| {
+            let ratio = (at - p1.at) as f32 / (p2.at - p1.at) as f32;
+            p1.cfm + ratio * (p2.cfm - p1.cfm)
+        })
+    })
+}
+
 /// CFM mapping data stored in cfm_mappings.toml
 ///
-/// Maps port IDs to their CFM@100% values for display purposes.
+/// Maps port IDs to their CFM@100% values and optional airflow models.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CfmMappingData {
     /// Port ID to CFM@100% mapping
@@ -26,6 +218,14 @@ pub struct CfmMappingData {
         default
     )]
     pub mappings: HashMap<u8, f32>,
+    /// Port ID to airflow model mapping
+    #[serde(
+        serialize_with = "serialize_mappings",
+        deserialize_with = "deserialize_mappings",
+        default,
+        skip_serializing_if = "HashMap::is_empty"
+    )]
+    pub models: HashMap<u8, CfmModel>,
 }
 
 impl CfmMappingData {
@@ -44,11 +244,26 @@ impl CfmMappingData {
         self.mappings.insert(port, cfm_at_100);
     }
 
-    /// Remove CFM mapping for a port.
+    /// Get the airflow model of a port.
+    pub fn model(&self, port: u8) -> Option<&CfmModel> {
+        self.models.get(&port)
+    }
+
+    /// Set the airflow model of a port, removing it when empty.
+    pub fn set_model(&mut self, port: u8, model: CfmModel) {
+        if model.is_empty() {
+            self.models.remove(&port);
+        } else {
+            self.models.insert(port, model);
+        }
+    }
+
+    /// Remove CFM mapping for a port, with its airflow model.
     ///
     /// Returns `true` if a mapping was removed, `false` if none existed.
     pub fn remove(&mut self, port: u8) -> bool {
-        self.mappings.remove(&port).is_some()
+        let model = self.models.remove(&port).is_some();
+        self.mappings.remove(&port).is_some() || model
     }
 
     /// Check if a port has a CFM mapping.
@@ -56,9 +271,18 @@ impl CfmMappingData {
         self.mappings.contains_key(&port)
     }
 
-    /// Check if any mappings exist.
+    /// Get the highest port with a CFM mapping or an airflow model.
+    pub fn max_port(&self) -> Option<u8> {
+        self.mappings
+            .keys()
+            .chain(self.models.keys())
+            .max()
+            .copied()
+    }
+
+    /// Check if any mappings or models exist.
     pub fn is_empty(&self) -> bool {
-        self.mappings.is_empty()
+        self.mappings.is_empty() && self.models.is_empty()
     }
 
     /// Get the number of mappings.
@@ -69,11 +293,56 @@ impl CfmMappingData {
     /// Calculate CFM from PWM for a port.
     ///
     /// Returns `None` if no mapping exists for the port.
-    /// Uses linear interpolation: `cfm = (pwm / 100.0) * cfm_at_100`
+    /// Uses the PWM→CFM table of the port's model if it has one, otherwise
+    /// linear interpolation: `cfm = (pwm / 100.0) * cfm_at_100`
     pub fn calculate_cfm(&self, port: u8, pwm: u32) -> Option<f32> {
-        self.mappings
-            .get(&port)
-            .map(|cfm_at_100| (pwm as f32 / 100.0) * cfm_at_100)
+        self.estimate(port, pwm, None).map(|estimate| estimate.cfm)
+    }
+
+    /// Estimate the airflow of a port from its PWM and, if known, its RPM.
+    ///
+    /// Uses the first of these the port has data for:
+    /// 1. the RPM→CFM table, with the RPM
+    /// 2. CFM@100% scaled by the RPM over the model's RPM at 100% PWM
+    /// 3. the PWM→CFM table, with the PWM
+    /// 4. CFM@100% scaled linearly by the PWM
+    ///
+    /// Returns `None` if the port has no mapping and no usable table.
+    pub fn estimate(&self, port: u8, pwm: u32, rpm: Option<u32>) -> Option<CfmEstimate> {
+        let cfm_at_100 = self.get(port);
+        let model = self.models.get(&port);
+
+        if let (Some(rpm), Some(model)) = (rpm, model) {
+            if let Some(cfm) = interpolate(&model.rpm_points, rpm) {
+                return Some(CfmEstimate {
+                    cfm,
+                    source: CfmSource::RpmTable,
+                });
+            }
+            if let (Some(max_rpm), Some(cfm_at_100)) = (model.max_rpm, cfm_at_100)
+                && max_rpm > 0
+            {
+                return Some(CfmEstimate {
+                    cfm: rpm as f32 / max_rpm as f32 * cfm_at_100,
+                    source: CfmSource::RpmScaled,
+                });
+            }
+        }
+        if let Some(cfm) = model.and_then(|model| interpolate(&model.pwm_points, pwm)) {
+            return Some(CfmEstimate {
+                cfm,
+                source: CfmSource::PwmTable,
+            });
+        }
+        cfm_at_100.map(|cfm_at_100| CfmEstimate {
+            cfm: (pwm as f32 / 100.0) * cfm_at_100,
+            source: CfmSource::Linear,
+        })
+    }
+
+    /// Get the airflow direction of a port, if set.
+    pub fn direction(&self, port: u8) -> Option<AirflowDirection> {
+        self.models.get(&port).and_then(|model| model.direction)
     }
 
     /// Parse CfmMappingData from TOML string.
@@ -100,10 +369,48 @@ impl CfmMappingData {
     }
 }
 
-// Custom serialization: HashMap<u8, f32> -> HashMap<String, f32> for TOML
-fn serialize_mappings<S>(mappings: &HashMap<u8, f32>, serializer: S) -> Result<S::Ok, S::Error>
+/// Parse airflow table points from CLI format: "20:0,50:25,100:45"
+///
+/// Each pair is a PWM percentage or RPM, then the CFM at that point. Points
+/// are sorted, and validated against `max_at`.
+pub fn parse_cfm_points(input: &str, max_at: u32) -> Result<Vec<CfmPoint>, String> {
+    let mut points = Vec::new();
+
+    for pair in input.split(',') {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+
+        let (at, cfm) = pair
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid point format '{}': expected 'at:cfm'", pair))?;
+        let at: u32 = at
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid point '{}': must be a whole number", at))?;
+        let cfm: f32 = cfm
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid CFM '{}': must be a number", cfm))?;
+
+        points.push(CfmPoint::new(at, cfm));
+    }
+
+    points.sort_by_key(|point| point.at);
+    validate_points("Airflow", &points, max_at)?;
+    if points.is_empty() {
+        return Err("At least 2 points are required".to_string());
+    }
+
+    Ok(points)
+}
+
+// Custom serialization: HashMap<u8, V> -> HashMap<String, V> for TOML
+fn serialize_mappings<S, V>(mappings: &HashMap<u8, V>, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
+    V: Serialize,
 {
     use serde::ser::SerializeMap;
     let mut map = serializer.serialize_map(Some(mappings.len()))?;
@@ -113,13 +420,14 @@ where
     map.end()
 }
 
-// Custom deserialization: HashMap<String, f32> -> HashMap<u8, f32>
-fn deserialize_mappings<'de, D>(deserializer: D) -> Result<HashMap<u8, f32>, D::Error>
+// Custom deserialization: HashMap<String, V> -> HashMap<u8, V>
+fn deserialize_mappings<'de, D, V>(deserializer: D) -> Result<HashMap<u8, V>, D::Error>
 where
     D: serde::Deserializer<'de>,
+    V: Deserialize<'de>,
 {
     use serde::de::Error;
-    let string_map: HashMap<String, f32> = HashMap::deserialize(deserializer)?;
+    let string_map: HashMap<String, V> = HashMap::deserialize(deserializer)?;
 
     string_map
         .into_iter()
@@ -243,6 +551,189 @@ mod tests {
         assert!(CfmMappingData::validate_cfm(MAX_CFM + 1.0).is_err());
     }
 
+    #[test]
+    fn test_estimate_from_tables() {
+        let mut data = CfmMappingData::new();
+        data.set(0, 60.0);
+        data.set_model(
+            0,
+            CfmModel {
+                pwm_points: vec![
+                    CfmPoint::new(20, 0.0),
+                    CfmPoint::new(60, 40.0),
+                    CfmPoint::new(100, 60.0),
+                ],
+                rpm_points: vec![CfmPoint::new(0, 0.0), CfmPoint::new(2000, 60.0)],
+                ..Default::default()
+            },
+        );
+
+        // Stalled below the first point, flatter near the top
+        assert_eq!(data.calculate_cfm(0, 10), Some(0.0));
+        assert_eq!(data.calculate_cfm(0, 40), Some(20.0));
+        assert_eq!(data.calculate_cfm(0, 80), Some(50.0));
+
+        // A known RPM takes precedence over the PWM
+        let estimate = data.estimate(0, 80, Some(1000)).unwrap();
+        assert_eq!(estimate.cfm, 30.0);
+        assert_eq!(estimate.source, CfmSource::RpmTable);
+        assert_eq!(data.estimate(0, 80, Some(2500)).unwrap().cfm, 60.0);
+        assert_eq!(
+            data.estimate(0, 80, None).unwrap().source,
+            CfmSource::PwmTable
+        );
+    }
+
+    #[test]
+    fn test_estimate_scaled_by_rpm() {
+        let mut data = CfmMappingData::new();
+        data.set(0, 45.0);
+        data.set_model(
+            0,
+            CfmModel {
+                max_rpm: Some(1800),
+                ..Default::default()
+            },
+        );
+
+        let estimate = data.estimate(0, 30, Some(900)).unwrap();
+        assert_eq!(estimate.cfm, 22.5);
+        assert_eq!(estimate.source, CfmSource::RpmScaled);
+        assert_eq!(data.estimate(0, 30, Some(0)).unwrap().cfm, 0.0);
+
+        // Without an RPM, the linear model is used
+        let estimate = data.estimate(0, 40, None).unwrap();
+        assert_eq!(estimate.cfm, 18.0);
+        assert_eq!(estimate.source, CfmSource::Linear);
+
+        // A model without CFM@100% cannot be scaled
+        data.mappings.clear();
+        assert_eq!(data.estimate(0, 30, Some(900)), None);
+    }
+
+    #[test]
+    fn test_remove_drops_model() {
+        let mut data = CfmMappingData::new();
+        data.set(0, 45.0);
+        data.set_model(
+            0,
+            CfmModel {
+                direction: Some(AirflowDirection::Intake),
+                ..Default::default()
+            },
+        );
+        data.set_model(
+            3,
+            CfmModel {
+                direction: Some(AirflowDirection::Exhaust),
+                ..Default::default()
+            },
+        );
+        assert_eq!(data.direction(0), Some(AirflowDirection::Intake));
+        assert_eq!(data.max_port(), Some(3));
+
+        assert!(data.remove(0));
+        assert!(data.model(0).is_none());
+        assert!(data.remove(3));
+        assert!(data.is_empty());
+
+        // An empty model is not stored
+        data.set_model(1, CfmModel::default());
+        assert!(data.model(1).is_none());
+    }
+
+    #[test]
+    fn test_model_roundtrip() {
+        let mut original = CfmMappingData::new();
+        original.set(2, 50.0);
+        let model = CfmModel {
+            pwm_points: vec![CfmPoint::new(20, 0.0), CfmPoint::new(100, 50.0)],
+            rpm_points: vec![],
+            max_rpm: Some(1500),
+            direction: Some(AirflowDirection::Exhaust),
+        };
+        original.set_model(2, model.clone());
+
+        let toml_str = original.to_toml().unwrap();
+        let restored = CfmMappingData::from_toml(&toml_str).unwrap();
+        assert_eq!(restored.get(2), Some(50.0));
+        assert_eq!(restored.model(2), Some(&model));
+
+        // Files without models still load
+        let legacy = CfmMappingData::from_toml("[mappings]\n0 = 45.0\n").unwrap();
+        assert!(legacy.models.is_empty());
+        assert!(!legacy.to_toml().unwrap().contains("models"));
+    }
+
+    #[test]
+    fn test_validate_model() {
+        let valid = CfmModel {
+            pwm_points: vec![CfmPoint::new(0, 0.0), CfmPoint::new(100, 45.0)],
+            max_rpm: Some(2000),
+            ..Default::default()
+        };
+        assert!(valid.validate().is_ok());
+        assert!(CfmModel::default().validate().is_ok());
+
+        let invalid = [
+            CfmModel {
+                pwm_points: vec![CfmPoint::new(50, 20.0)],
+                ..Default::default()
+            },
+            CfmModel {
+                pwm_points: vec![CfmPoint::new(50, 20.0), CfmPoint::new(120, 45.0)],
+                ..Default::default()
+            },
+            CfmModel {
+                rpm_points: vec![CfmPoint::new(2000, 60.0), CfmPoint::new(1000, 30.0)],
+                ..Default::default()
+            },
+            CfmModel {
+                rpm_points: vec![CfmPoint::new(0, -1.0), CfmPoint::new(1000, 30.0)],
+                ..Default::default()
+            },
+            CfmModel {
+                max_rpm: Some(0),
+                ..Default::default()
+            },
+        ];
+        for model in invalid {
+            assert!(model.validate().is_err(), "{:?} should be invalid", model);
+        }
+    }
+
+    #[test]
+    fn test_parse_cfm_points() {
+        let points = parse_cfm_points("100:45, 20:0,60:30", 100).unwrap();
+        assert_eq!(
+            points,
+            vec![
+                CfmPoint::new(20, 0.0),
+                CfmPoint::new(60, 30.0),
+                CfmPoint::new(100, 45.0),
+            ]
+        );
+
+        assert!(parse_cfm_points("", 100).is_err());
+        assert!(parse_cfm_points("50:20", 100).is_err());
+        assert!(parse_cfm_points("50-20,100:45", 100).is_err());
+        assert!(parse_cfm_points("50:20,150:45", 100).is_err());
+        assert!(parse_cfm_points("500:10,1800:60", MAX_CFM_RPM).is_ok());
+    }
+
+    #[test]
+    fn test_airflow_direction_parse() {
+        assert_eq!(
+            "intake".parse::<AirflowDirection>(),
+            Ok(AirflowDirection::Intake)
+        );
+        assert_eq!(
+            "Exhaust".parse::<AirflowDirection>(),
+            Ok(AirflowDirection::Exhaust)
+        );
+        assert!("sideways".parse::<AirflowDirection>().is_err());
+    }
+
     #[test]
     fn test_empty_toml() {
         let toml_str = "";
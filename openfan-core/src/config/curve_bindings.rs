@@ -0,0 +1,424 @@
+//! Curve binding data - mutable via API
+//!
+//! Stored in `{data_dir}/controllers/{id}/curve_bindings.toml`
+//!
+//! A binding ties a thermal curve or a PID controller to a temperature sensor
+//! and a set of fans. The daemon's control loop periodically reads the sensor,
+//! runs the reading through the curve or PID controller and applies the
+//! resulting PWM to the bound fans.
+
+use super::zones::ZoneData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Default poll interval for a binding, in seconds.
+pub const DEFAULT_BINDING_INTERVAL_SECS: u64 = 2;
+
+/// Maximum allowed poll interval for a binding, in seconds.
+pub const MAX_BINDING_INTERVAL_SECS: u64 = 3600;
+
+fn default_interval_secs() -> u64 {
+    DEFAULT_BINDING_INTERVAL_SECS
+}
+
+/// A binding between a thermal curve or PID controller, a temperature sensor and fans.
+///
+/// Exactly one of `curve` or `pid` must be set, and exactly one of `zone` or
+/// `fans`. When bound to a zone, only
+/// the zone's fans that belong to the owning controller are driven.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurveBinding {
+    /// Binding name (unique per controller)
+    pub name: String,
+    /// Name of the thermal curve on the same controller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curve: Option<String>,
+    /// Name of the PID controller on the same controller (when not using a curve)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<String>,
+    /// Temperature sensor to read: a sensor ID (e.g. "k10temp/temp1") or a file path
+    pub sensor: String,
+    /// Zone whose fans are driven
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<String>,
+    /// Explicit fan IDs to drive (when not bound to a zone)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fans: Vec<u8>,
+    /// Poll interval in seconds
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+}
+
+impl CurveBinding {
+    /// Create a binding that drives all fans of a zone.
+    pub fn for_zone(
+        name: impl Into<String>,
+        curve: impl Into<String>,
+        sensor: impl Into<String>,
+        zone: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            curve: Some(curve.into()),
+            pid: None,
+            sensor: sensor.into(),
+            zone: Some(zone.into()),
+            fans: Vec::new(),
+            interval_secs: DEFAULT_BINDING_INTERVAL_SECS,
+        }
+    }
+
+    /// Create a binding that drives an explicit list of fans.
+    pub fn for_fans(
+        name: impl Into<String>,
+        curve: impl Into<String>,
+        sensor: impl Into<String>,
+        fans: Vec<u8>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            curve: Some(curve.into()),
+            pid: None,
+            sensor: sensor.into(),
+            zone: None,
+            fans,
+            interval_secs: DEFAULT_BINDING_INTERVAL_SECS,
+        }
+    }
+
+    /// Drive the binding with a PID controller instead of its curve.
+    pub fn using_pid(mut self, pid: impl Into<String>) -> Self {
+        self.curve = None;
+        self.pid = Some(pid.into());
+        self
+    }
+
+    /// Describe the binding's control strategy for logs (e.g. "curve 'Balanced'").
+    pub fn strategy(&self) -> String {
+        match (&self.curve, &self.pid) {
+            (Some(curve), _) => format!("curve '{}'", curve),
+            (None, Some(pid)) => format!("PID '{}'", pid),
+            (None, None) => "no strategy".to_string(),
+        }
+    }
+
+    /// Set the poll interval in seconds.
+    pub fn with_interval(mut self, interval_secs: u64) -> Self {
+        self.interval_secs = interval_secs;
+        self
+    }
+
+    /// Resolve the fan IDs this binding drives on the given controller.
+    ///
+    /// Returns `None` if the binding refers to a zone that does not exist.
+    pub fn resolve_fans(&self, controller_id: &str, zones: &ZoneData) -> Option<Vec<u8>> {
+        match &self.zone {
+            Some(zone) => zones
+                .get(zone)
+                .map(|z| z.fans_for_controller(controller_id)),
+            None => Some(self.fans.clone()),
+        }
+    }
+
+    /// Validate the binding.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Binding name cannot be empty".to_string());
+        }
+        match (&self.curve, &self.pid) {
+            (Some(_), Some(_)) => {
+                return Err("Binding must use either a curve or a PID, not both".to_string());
+            }
+            (None, None) => {
+                return Err("Binding must use a curve or a PID".to_string());
+            }
+            (Some(curve), None) if curve.trim().is_empty() => {
+                return Err("Binding curve cannot be empty".to_string());
+            }
+            (None, Some(pid)) if pid.trim().is_empty() => {
+                return Err("Binding PID cannot be empty".to_string());
+            }
+            _ => {}
+        }
+        if self.sensor.trim().is_empty() {
+            return Err("Binding sensor cannot be empty".to_string());
+        }
+
+        match (&self.zone, self.fans.is_empty()) {
+            (Some(_), false) => {
+                return Err("Binding must target either a zone or fans, not both".to_string());
+            }
+            (None, true) => {
+                return Err("Binding must target a zone or at least one fan".to_string());
+            }
+            (Some(zone), true) if zone.trim().is_empty() => {
+                return Err("Binding zone cannot be empty".to_string());
+            }
+            _ => {}
+        }
+
+        if self.interval_secs == 0 || self.interval_secs > MAX_BINDING_INTERVAL_SECS {
+            return Err(format!(
+                "Binding interval {}s is outside valid range (1 to {})",
+                self.interval_secs, MAX_BINDING_INTERVAL_SECS
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Curve binding data stored in curve_bindings.toml
+///
+/// Maps binding names to their definitions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CurveBindingData {
+    /// Binding name to binding definition mapping
+    #[serde(default)]
+    pub bindings: HashMap<String, CurveBinding>,
+}
+
+impl CurveBindingData {
+    /// Get a binding by name.
+    pub fn get(&self, name: &str) -> Option<&CurveBinding> {
+        self.bindings.get(name)
+    }
+
+    /// Insert a binding.
+    pub fn insert(&mut self, name: String, binding: CurveBinding) {
+        self.bindings.insert(name, binding);
+    }
+
+    /// Remove a binding by name.
+    pub fn remove(&mut self, name: &str) -> Option<CurveBinding> {
+        self.bindings.remove(name)
+    }
+
+    /// Check if a binding exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.bindings.contains_key(name)
+    }
+
+    /// Get all binding names.
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.bindings.keys()
+    }
+
+    /// Get all bindings that use a given curve.
+    pub fn bindings_for_curve<'a>(
+        &'a self,
+        curve: &'a str,
+    ) -> impl Iterator<Item = &'a CurveBinding> + 'a {
+        self.bindings
+            .values()
+            .filter(move |b| b.curve.as_deref() == Some(curve))
+    }
+
+    /// Get all bindings that use a given PID controller.
+    pub fn bindings_for_pid<'a>(
+        &'a self,
+        pid: &'a str,
+    ) -> impl Iterator<Item = &'a CurveBinding> + 'a {
+        self.bindings
+            .values()
+            .filter(move |b| b.pid.as_deref() == Some(pid))
+    }
+
+    /// Validate every binding.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut names: Vec<_> = self.bindings.keys().collect();
+        names.sort_unstable();
+        for name in names {
+            self.bindings[name]
+                .validate()
+                .map_err(|e| format!("binding '{}': {}", name, e))?;
+        }
+        Ok(())
+    }
+
+    /// Parse CurveBindingData from TOML string.
+    pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Serialize CurveBindingData to TOML string.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Zone, ZoneFan};
+
+    #[test]
+    fn test_binding_constructors() {
+        let binding = CurveBinding::for_zone("cpu", "Balanced", "/sys/temp", "cpu-zone");
+        assert_eq!(binding.zone, Some("cpu-zone".to_string()));
+        assert!(binding.fans.is_empty());
+        assert_eq!(binding.interval_secs, DEFAULT_BINDING_INTERVAL_SECS);
+
+        let binding =
+            CurveBinding::for_fans("case", "Silent", "/sys/temp", vec![0, 1]).with_interval(5);
+        assert!(binding.zone.is_none());
+        assert_eq!(binding.fans, vec![0, 1]);
+        assert_eq!(binding.interval_secs, 5);
+    }
+
+    #[test]
+    fn test_binding_validate() {
+        assert!(
+            CurveBinding::for_fans("a", "Balanced", "s", vec![0])
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            CurveBinding::for_zone("a", "Balanced", "s", "z")
+                .validate()
+                .is_ok()
+        );
+
+        // Missing fields
+        assert!(
+            CurveBinding::for_fans("", "Balanced", "s", vec![0])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            CurveBinding::for_fans("a", "", "s", vec![0])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            CurveBinding::for_fans("a", "Balanced", " ", vec![0])
+                .validate()
+                .is_err()
+        );
+
+        // No target
+        let err = CurveBinding::for_fans("a", "Balanced", "s", vec![])
+            .validate()
+            .unwrap_err();
+        assert!(err.contains("at least one fan"));
+
+        // Both targets
+        let mut binding = CurveBinding::for_zone("a", "Balanced", "s", "z");
+        binding.fans = vec![0];
+        assert!(binding.validate().unwrap_err().contains("not both"));
+
+        // Curve or PID
+        let binding = CurveBinding::for_fans("a", "Balanced", "s", vec![0]).using_pid("loop");
+        assert!(binding.validate().is_ok());
+        assert_eq!(binding.curve, None);
+        let mut binding = CurveBinding::for_fans("a", "Balanced", "s", vec![0]);
+        binding.pid = Some("loop".to_string());
+        assert!(binding.validate().unwrap_err().contains("not both"));
+        binding.curve = None;
+        binding.pid = None;
+        assert!(binding.validate().unwrap_err().contains("curve or a PID"));
+
+        // Interval out of range
+        let binding = CurveBinding::for_fans("a", "Balanced", "s", vec![0]).with_interval(0);
+        assert!(binding.validate().unwrap_err().contains("interval"));
+        let binding = CurveBinding::for_fans("a", "Balanced", "s", vec![0])
+            .with_interval(MAX_BINDING_INTERVAL_SECS + 1);
+        assert!(binding.validate().is_err());
+    }
+
+    #[test]
+    fn test_binding_resolve_fans() {
+        let mut zones = ZoneData::default();
+        zones.insert(
+            "intake".to_string(),
+            Zone::new(
+                "intake",
+                vec![
+                    ZoneFan::new("main", 0),
+                    ZoneFan::new("gpu", 2),
+                    ZoneFan::new("main", 3),
+                ],
+            ),
+        );
+
+        let binding = CurveBinding::for_zone("a", "Balanced", "s", "intake");
+        assert_eq!(binding.resolve_fans("main", &zones), Some(vec![0, 3]));
+        assert_eq!(binding.resolve_fans("gpu", &zones), Some(vec![2]));
+        assert_eq!(binding.resolve_fans("other", &zones), Some(vec![]));
+
+        let binding = CurveBinding::for_zone("a", "Balanced", "s", "missing");
+        assert_eq!(binding.resolve_fans("main", &zones), None);
+
+        let binding = CurveBinding::for_fans("a", "Balanced", "s", vec![4, 5]);
+        assert_eq!(binding.resolve_fans("main", &zones), Some(vec![4, 5]));
+    }
+
+    #[test]
+    fn test_binding_data_operations() {
+        let mut data = CurveBindingData::default();
+        data.insert(
+            "cpu".to_string(),
+            CurveBinding::for_fans("cpu", "Balanced", "s", vec![0]),
+        );
+        data.insert(
+            "case".to_string(),
+            CurveBinding::for_fans("case", "Silent", "s", vec![1]),
+        );
+
+        assert!(data.contains("cpu"));
+        assert_eq!(data.names().count(), 2);
+        assert_eq!(data.bindings_for_curve("Balanced").count(), 1);
+        assert_eq!(data.bindings_for_pid("Balanced").count(), 0);
+
+        assert!(data.remove("cpu").is_some());
+        assert!(!data.contains("cpu"));
+        assert!(data.get("case").is_some());
+    }
+
+    #[test]
+    fn test_binding_data_toml_roundtrip() {
+        let mut data = CurveBindingData::default();
+        data.insert(
+            "cpu".to_string(),
+            CurveBinding::for_zone(
+                "cpu",
+                "Balanced",
+                "/sys/class/thermal/thermal_zone0/temp",
+                "cpu",
+            ),
+        );
+        data.insert(
+            "case".to_string(),
+            CurveBinding::for_fans("case", "Silent", "/tmp/temp", vec![2, 3]).with_interval(10),
+        );
+        data.insert(
+            "loop".to_string(),
+            CurveBinding::for_zone("loop", "", "/tmp/temp", "radiator").using_pid("water"),
+        );
+
+        let toml_str = data.to_toml().unwrap();
+        let parsed = CurveBindingData::from_toml(&toml_str).unwrap();
+
+        assert_eq!(parsed.get("cpu"), data.get("cpu"));
+        assert_eq!(parsed.get("case"), data.get("case"));
+        assert_eq!(parsed.get("loop"), data.get("loop"));
+        assert!(!toml_str.contains("curve = \"\""));
+    }
+
+    #[test]
+    fn test_binding_default_interval_from_toml() {
+        let toml_str = r#"
+[bindings.cpu]
+name = "cpu"
+curve = "Balanced"
+sensor = "/tmp/temp"
+fans = [0, 1]
+"#;
+        let data = CurveBindingData::from_toml(toml_str).unwrap();
+        let binding = data.get("cpu").unwrap();
+        assert_eq!(binding.interval_secs, DEFAULT_BINDING_INTERVAL_SECS);
+        assert!(binding.zone.is_none());
+    }
+}
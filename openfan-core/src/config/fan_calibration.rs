@@ -0,0 +1,187 @@
+//! Fan calibration results - written by the daemon
+//!
+//! Stored in `{data_dir}/controllers/{id}/fan_calibration.toml` by the
+//! calibration job, which sweeps each fan's PWM and measures its RPM.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// RPM measured at a PWM percentage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalibrationPoint {
+    /// PWM percentage (0-100)
+    pub pwm: u32,
+    /// RPM measured once the fan settled
+    pub rpm: u32,
+}
+
+impl CalibrationPoint {
+    /// Create a new calibration point.
+    pub fn new(pwm: u32, rpm: u32) -> Self {
+        Self { pwm, rpm }
+    }
+}
+
+/// Measured behavior of a single fan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FanCalibration {
+    /// RPM at each PWM step, by ascending PWM
+    pub points: Vec<CalibrationPoint>,
+    /// Lowest PWM that starts the fan from standstill, if any did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_start_pwm: Option<u32>,
+    /// Lowest PWM that keeps the fan spinning once started, if any did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_stable_pwm: Option<u32>,
+    /// When the fan was calibrated (Unix timestamp)
+    pub calibrated_at: u64,
+}
+
+impl FanCalibration {
+    /// Highest RPM measured during the sweep.
+    pub fn max_rpm(&self) -> u32 {
+        self.points.iter().map(|point| point.rpm).max().unwrap_or(0)
+    }
+
+    /// Check if the fan never spun during the sweep.
+    pub fn is_stopped(&self) -> bool {
+        self.min_stable_pwm.is_none() && self.min_start_pwm.is_none()
+    }
+}
+
+/// Calibration results of the fans of a controller
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FanCalibrationData {
+    /// Fan ID to calibration results
+    ///
+    /// Keys are stringified fan IDs ("0", "1", etc.) for TOML compatibility.
+    #[serde(
+        serialize_with = "serialize_calibrations",
+        deserialize_with = "deserialize_calibrations",
+        default
+    )]
+    pub fans: HashMap<u8, FanCalibration>,
+}
+
+impl FanCalibrationData {
+    /// Get the calibration results of a fan.
+    pub fn get(&self, fan_id: u8) -> Option<&FanCalibration> {
+        self.fans.get(&fan_id)
+    }
+
+    /// Set the calibration results of a fan, replacing earlier ones.
+    pub fn insert(&mut self, fan_id: u8, calibration: FanCalibration) {
+        self.fans.insert(fan_id, calibration);
+    }
+
+    /// Check if no fan was calibrated.
+    pub fn is_empty(&self) -> bool {
+        self.fans.is_empty()
+    }
+
+    /// Parse FanCalibrationData from TOML string.
+    pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Serialize FanCalibrationData to TOML string.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+}
+
+// Custom serialization: HashMap<u8, FanCalibration> -> HashMap<String, FanCalibration> for TOML
+fn serialize_calibrations<S>(
+    fans: &HashMap<u8, FanCalibration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    use serde::ser::SerializeMap;
+    let mut sorted: Vec<_> = fans.iter().collect();
+    sorted.sort_by_key(|(fan_id, _)| **fan_id);
+    let mut map = serializer.serialize_map(Some(sorted.len()))?;
+    for (k, v) in sorted {
+        map.serialize_entry(&k.to_string(), v)?;
+    }
+    map.end()
+}
+
+// Custom deserialization: HashMap<String, FanCalibration> -> HashMap<u8, FanCalibration>
+fn deserialize_calibrations<'de, D>(
+    deserializer: D,
+) -> Result<HashMap<u8, FanCalibration>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    let string_map: HashMap<String, FanCalibration> = HashMap::deserialize(deserializer)?;
+
+    string_map
+        .into_iter()
+        .map(|(k, v)| {
+            k.parse::<u8>()
+                .map(|id| (id, v))
+                .map_err(|_| D::Error::custom(format!("invalid fan ID: {}", k)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calibration() -> FanCalibration {
+        FanCalibration {
+            points: vec![
+                CalibrationPoint::new(0, 0),
+                CalibrationPoint::new(20, 0),
+                CalibrationPoint::new(40, 700),
+                CalibrationPoint::new(100, 1800),
+            ],
+            min_start_pwm: Some(40),
+            min_stable_pwm: Some(30),
+            calibrated_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn test_fan_calibration_roundtrip() {
+        let mut data = FanCalibrationData::default();
+        data.insert(2, calibration());
+
+        let toml_str = data.to_toml().unwrap();
+        assert!(toml_str.contains("[fans.2]"));
+        assert!(toml_str.contains("min_start_pwm = 40"));
+
+        let restored = FanCalibrationData::from_toml(&toml_str).unwrap();
+        assert_eq!(restored, data);
+        assert_eq!(restored.get(2).unwrap().max_rpm(), 1800);
+    }
+
+    #[test]
+    fn test_fan_calibration_stopped_fan() {
+        let stopped = FanCalibration {
+            points: vec![CalibrationPoint::new(0, 0), CalibrationPoint::new(100, 0)],
+            min_start_pwm: None,
+            min_stable_pwm: None,
+            calibrated_at: 0,
+        };
+        assert!(stopped.is_stopped());
+        assert!(!calibration().is_stopped());
+
+        let mut data = FanCalibrationData::default();
+        data.insert(0, stopped.clone());
+        let restored = FanCalibrationData::from_toml(&data.to_toml().unwrap()).unwrap();
+        assert_eq!(restored.get(0), Some(&stopped));
+    }
+
+    #[test]
+    fn test_fan_calibration_invalid() {
+        assert!(FanCalibrationData::from_toml("").unwrap().is_empty());
+        assert!(
+            FanCalibrationData::from_toml("[fans.cpu]\npoints = []\ncalibrated_at = 0\n").is_err()
+        );
+    }
+}
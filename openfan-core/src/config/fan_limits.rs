@@ -0,0 +1,442 @@
+//! Per-fan safety limits - mutable via API
+//!
+//! Stored in `{data_dir}/controllers/{id}/fan_limits.toml`. The daemon keeps
+//! every value it sends to a fan within the fan's limits, whichever way the
+//! value was set.
+
+use crate::board::BoardInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// How values outside a fan's limits are handled
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitEnforcement {
+    /// Move the value into the limits and apply it
+    #[default]
+    Clamp,
+    /// Refuse to apply the value
+    Reject,
+}
+
+impl fmt::Display for LimitEnforcement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Clamp => write!(f, "clamp"),
+            Self::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+/// Safety limits of a single fan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FanLimits {
+    /// Lowest PWM percentage the fan runs at, apart from 0% when allowed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_pwm: Option<u32>,
+    /// Highest PWM percentage the fan runs at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_pwm: Option<u32>,
+    /// Lowest RPM target the fan is set to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_rpm: Option<u32>,
+    /// Whether the fan may be stopped with 0% PWM or a 0 RPM target
+    #[serde(default = "default_allow_zero")]
+    pub allow_zero: bool,
+}
+
+fn default_allow_zero() -> bool {
+    true
+}
+
+impl Default for FanLimits {
+    fn default() -> Self {
+        Self {
+            min_pwm: None,
+            max_pwm: None,
+            min_rpm: None,
+            allow_zero: true,
+        }
+    }
+}
+
+impl FanLimits {
+    /// Check if the limits allow every value.
+    pub fn is_unrestricted(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Validate the limits.
+    ///
+    /// PWM limits must be within 0-100 with the minimum not above the maximum,
+    /// and a fan that may not be stopped needs a minimum PWM to run at instead.
+    /// The minimum RPM may not be above the highest target of `board`, when
+    /// it is known.
+    pub fn validate(&self, board: Option<&BoardInfo>) -> Result<(), String> {
+        for (name, value) in [("min_pwm", self.min_pwm), ("max_pwm", self.max_pwm)] {
+            if let Some(value) = value
+                && value > 100
+            {
+                return Err(format!("{} must be 0-100, got {}", name, value));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_pwm, self.max_pwm)
+            && min > max
+        {
+            return Err(format!(
+                "min_pwm ({}) must not be above max_pwm ({})",
+                min, max
+            ));
+        }
+        if !self.allow_zero && self.min_pwm.unwrap_or(0) == 0 {
+            return Err("allow_zero = false requires a min_pwm above 0".to_string());
+        }
+        if let (Some(min), Some(board)) = (self.min_rpm, board)
+            && min > board.max_target_rpm
+        {
+            return Err(format!(
+                "min_rpm ({}) must not be above the board's {} RPM maximum target",
+                min, board.max_target_rpm
+            ));
+        }
+        Ok(())
+    }
+
+    /// Describe why a PWM percentage is outside the limits, if it is.
+    pub fn check_pwm(&self, pwm: u32) -> Option<String> {
+        if pwm == 0 {
+            return (!self.allow_zero).then(|| "0% PWM is not allowed".to_string());
+        }
+        if let Some(min) = self.min_pwm
+            && pwm < min
+        {
+            return Some(format!("{}% PWM is below the {}% minimum", pwm, min));
+        }
+        if let Some(max) = self.max_pwm
+            && pwm > max
+        {
+            return Some(format!("{}% PWM is above the {}% maximum", pwm, max));
+        }
+        None
+    }
+
+    /// Describe why an RPM target is outside the limits, if it is.
+    pub fn check_rpm(&self, rpm: u32) -> Option<String> {
+        if rpm == 0 {
+            return (!self.allow_zero).then(|| "0 RPM target is not allowed".to_string());
+        }
+        match self.min_rpm {
+            Some(min) if rpm < min => Some(format!("{} RPM is below the {} RPM minimum", rpm, min)),
+            _ => None,
+        }
+    }
+
+    /// Move a PWM percentage into the limits.
+    ///
+    /// 0% stays 0% when the fan may be stopped, and is raised to the minimum
+    /// PWM otherwise.
+    pub fn clamp_pwm(&self, pwm: u32) -> u32 {
+        if pwm == 0 && self.allow_zero {
+            return 0;
+        }
+        let pwm = pwm.max(self.min_pwm.unwrap_or(0));
+        pwm.min(self.max_pwm.unwrap_or(100))
+    }
+
+    /// Move an RPM target into the limits.
+    ///
+    /// A 0 RPM target stays 0 when the fan may be stopped. Otherwise it is
+    /// raised to the minimum RPM, or to `lowest_target` when the fan has none.
+    pub fn clamp_rpm(&self, rpm: u32, lowest_target: u32) -> u32 {
+        if rpm == 0 && self.allow_zero {
+            return 0;
+        }
+        let rpm = rpm.max(self.min_rpm.unwrap_or(0));
+        if rpm == 0 && !self.allow_zero {
+            lowest_target
+        } else {
+            rpm
+        }
+    }
+}
+
+impl fmt::Display for FanLimits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        match (self.min_pwm, self.max_pwm) {
+            (None, None) => {}
+            (min, max) => parts.push(format!("PWM {}-{}%", min.unwrap_or(0), max.unwrap_or(100))),
+        }
+        if let Some(min_rpm) = self.min_rpm {
+            parts.push(format!("RPM >= {}", min_rpm));
+        }
+        if !self.allow_zero {
+            parts.push("never stopped".to_string());
+        }
+        if parts.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", parts.join(", "))
+        }
+    }
+}
+
+/// Safety limits of the fans of a controller
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FanLimitsData {
+    /// Fan ID to limits; fans without an entry are unrestricted
+    ///
+    /// Keys are stringified fan IDs ("0", "1", etc.) for TOML compatibility.
+    #[serde(
+        serialize_with = "serialize_limits",
+        deserialize_with = "deserialize_limits",
+        default
+    )]
+    pub fans: HashMap<u8, FanLimits>,
+}
+
+impl FanLimitsData {
+    /// Get the limits of a fan, unrestricted if none are set.
+    pub fn get(&self, fan_id: u8) -> FanLimits {
+        self.fans.get(&fan_id).copied().unwrap_or_default()
+    }
+
+    /// Set the limits of a fan; unrestricted limits remove its entry.
+    pub fn set(&mut self, fan_id: u8, limits: FanLimits) {
+        if limits.is_unrestricted() {
+            self.fans.remove(&fan_id);
+        } else {
+            self.fans.insert(fan_id, limits);
+        }
+    }
+
+    /// Remove the limits of a fan.
+    ///
+    /// Returns `true` if limits were removed, `false` if none existed.
+    pub fn remove(&mut self, fan_id: u8) -> bool {
+        self.fans.remove(&fan_id).is_some()
+    }
+
+    /// Check if no fan has limits.
+    pub fn is_empty(&self) -> bool {
+        self.fans.is_empty()
+    }
+
+    /// Validate the limits of every fan, against `board` when it is known.
+    pub fn validate(&self, board: Option<&BoardInfo>) -> Result<(), String> {
+        let mut fan_ids: Vec<_> = self.fans.keys().copied().collect();
+        fan_ids.sort_unstable();
+        for fan_id in fan_ids {
+            self.fans[&fan_id]
+                .validate(board)
+                .map_err(|e| format!("fan {}: {}", fan_id, e))?;
+        }
+        Ok(())
+    }
+
+    /// Parse FanLimitsData from TOML string.
+    pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Serialize FanLimitsData to TOML string.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+}
+
+// Custom serialization: HashMap<u8, FanLimits> -> HashMap<String, FanLimits> for TOML
+fn serialize_limits<S>(fans: &HashMap<u8, FanLimits>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    use serde::ser::SerializeMap;
+    let mut sorted: Vec<_> = fans.iter().collect();
+    sorted.sort_by_key(|(fan_id, _)| **fan_id);
+    let mut map = serializer.serialize_map(Some(sorted.len()))?;
+    for (k, v) in sorted {
+        map.serialize_entry(&k.to_string(), v)?;
+    }
+    map.end()
+}
+
+// Custom deserialization: HashMap<String, FanLimits> -> HashMap<u8, FanLimits>
+fn deserialize_limits<'de, D>(deserializer: D) -> Result<HashMap<u8, FanLimits>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    let string_map: HashMap<String, FanLimits> = HashMap::deserialize(deserializer)?;
+
+    string_map
+        .into_iter()
+        .map(|(k, v)| {
+            k.parse::<u8>()
+                .map(|id| (id, v))
+                .map_err(|_| D::Error::custom(format!("invalid fan ID: {}", k)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::BoardType;
+
+    fn pump() -> FanLimits {
+        FanLimits {
+            min_pwm: Some(40),
+            max_pwm: Some(90),
+            min_rpm: Some(1200),
+            allow_zero: false,
+        }
+    }
+
+    #[test]
+    fn test_fan_limits_roundtrip() {
+        let mut data = FanLimitsData::default();
+        data.set(1, pump());
+        data.set(3, FanLimits::default());
+
+        let toml_str = data.to_toml().unwrap();
+        assert!(toml_str.contains("[fans.1]"));
+        assert!(toml_str.contains("allow_zero = false"));
+        assert!(!toml_str.contains("[fans.3]"));
+
+        let restored = FanLimitsData::from_toml(&toml_str).unwrap();
+        assert_eq!(restored, data);
+        assert_eq!(restored.get(1), pump());
+        assert!(restored.get(3).is_unrestricted());
+
+        let partial = FanLimitsData::from_toml("[fans.0]\nmax_pwm = 80\n").unwrap();
+        assert!(partial.get(0).allow_zero);
+        assert_eq!(partial.get(0).max_pwm, Some(80));
+        assert!(FanLimitsData::from_toml("[fans.cpu]\nmin_pwm = 20\n").is_err());
+    }
+
+    #[test]
+    fn test_fan_limits_clamp_and_check() {
+        let limits = pump();
+        assert_eq!(limits.clamp_pwm(0), 40);
+        assert_eq!(limits.clamp_pwm(10), 40);
+        assert_eq!(limits.clamp_pwm(60), 60);
+        assert_eq!(limits.clamp_pwm(100), 90);
+        assert_eq!(limits.clamp_rpm(500, 500), 1200);
+        assert_eq!(limits.clamp_rpm(2000, 500), 2000);
+
+        assert_eq!(limits.check_pwm(0).unwrap(), "0% PWM is not allowed");
+        assert!(
+            limits
+                .check_pwm(10)
+                .unwrap()
+                .contains("below the 40% minimum")
+        );
+        assert!(
+            limits
+                .check_pwm(95)
+                .unwrap()
+                .contains("above the 90% maximum")
+        );
+        assert!(limits.check_pwm(60).is_none());
+        assert!(limits.check_rpm(800).unwrap().contains("1200 RPM minimum"));
+        assert!(limits.check_rpm(1200).is_none());
+
+        // A fan allowed to stop keeps 0% but is raised when spinning
+        let floor = FanLimits {
+            min_pwm: Some(25),
+            ..FanLimits::default()
+        };
+        assert_eq!(floor.clamp_pwm(0), 0);
+        assert!(floor.check_pwm(0).is_none());
+        assert_eq!(floor.clamp_pwm(5), 25);
+
+        assert_eq!(FanLimits::default().clamp_pwm(100), 100);
+        assert_eq!(FanLimits::default().clamp_pwm(0), 0);
+    }
+
+    #[test]
+    fn test_fan_limits_zero_rpm_target() {
+        // A fan that may not be stopped refuses a 0 RPM target, with or
+        // without a minimum RPM
+        let never_stopped = FanLimits {
+            min_pwm: Some(30),
+            allow_zero: false,
+            ..FanLimits::default()
+        };
+        assert_eq!(
+            never_stopped.check_rpm(0).unwrap(),
+            "0 RPM target is not allowed"
+        );
+        assert_eq!(never_stopped.clamp_rpm(0, 500), 500);
+        assert!(never_stopped.check_rpm(800).is_none());
+        assert_eq!(never_stopped.clamp_rpm(800, 500), 800);
+        assert_eq!(pump().clamp_rpm(0, 500), 1200);
+        assert!(pump().check_rpm(0).unwrap().contains("not allowed"));
+
+        // Others may be given a 0 RPM target
+        assert!(FanLimits::default().check_rpm(0).is_none());
+        assert_eq!(FanLimits::default().clamp_rpm(0, 500), 0);
+
+        // Even with a minimum RPM, which only applies while spinning
+        let floor = FanLimits {
+            min_rpm: Some(1200),
+            ..FanLimits::default()
+        };
+        assert!(floor.check_rpm(0).is_none());
+        assert_eq!(floor.clamp_rpm(0, 500), 0);
+        assert!(floor.check_rpm(800).unwrap().contains("1200 RPM minimum"));
+        assert_eq!(floor.clamp_rpm(800, 500), 1200);
+    }
+
+    #[test]
+    fn test_fan_limits_validate() {
+        let board = BoardType::OpenFanStandard.to_board_info();
+        assert!(pump().validate(Some(&board)).is_ok());
+        assert!(FanLimits::default().validate(Some(&board)).is_ok());
+
+        let mut limits = pump();
+        limits.max_pwm = Some(101);
+        assert!(limits.validate(None).unwrap_err().contains("max_pwm"));
+
+        let mut limits = pump();
+        limits.min_pwm = Some(95);
+        assert!(
+            limits
+                .validate(None)
+                .unwrap_err()
+                .contains("must not be above")
+        );
+
+        // The minimum RPM must be a target the board can reach
+        let mut fast = pump();
+        fast.min_rpm = Some(board.max_target_rpm + 1);
+        assert!(fast.validate(None).is_ok());
+        assert!(
+            fast.validate(Some(&board))
+                .unwrap_err()
+                .contains("maximum target")
+        );
+        fast.min_rpm = Some(board.max_target_rpm);
+        assert!(fast.validate(Some(&board)).is_ok());
+
+        let mut limits = pump();
+        limits.min_pwm = None;
+        assert!(limits.validate(None).unwrap_err().contains("allow_zero"));
+
+        let mut data = FanLimitsData::default();
+        data.fans.insert(2, limits);
+        assert!(data.validate(None).unwrap_err().starts_with("fan 2:"));
+    }
+
+    #[test]
+    fn test_fan_limits_display() {
+        assert_eq!(pump().to_string(), "PWM 40-90%, RPM >= 1200, never stopped");
+        let cap = FanLimits {
+            max_pwm: Some(70),
+            ..FanLimits::default()
+        };
+        assert_eq!(cap.to_string(), "PWM 0-70%");
+        assert_eq!(FanLimits::default().to_string(), "none");
+    }
+}
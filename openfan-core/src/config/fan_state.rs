@@ -0,0 +1,148 @@
+//! Last applied fan state - written by the daemon
+//!
+//! Stored in `{data_dir}/controllers/{id}/fan_state.toml` when
+//! `[startup] restore_state` is enabled, and restored on the next start.
+
+use crate::types::{ControlMode, FanProfile};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Last PWM or target RPM applied to each fan of a controller
+///
+/// A fan appears in at most one of the maps, depending on how it was last set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FanStateData {
+    /// Fan ID to PWM percentage
+    ///
+    /// Keys are stringified fan IDs ("0", "1", etc.) for TOML compatibility.
+    #[serde(
+        serialize_with = "serialize_fan_values",
+        deserialize_with = "deserialize_fan_values",
+        default
+    )]
+    pub pwm: HashMap<u8, u32>,
+
+    /// Fan ID to target RPM
+    ///
+    /// Keys are stringified fan IDs ("0", "1", etc.) for TOML compatibility.
+    #[serde(
+        serialize_with = "serialize_fan_values",
+        deserialize_with = "deserialize_fan_values",
+        default
+    )]
+    pub rpm: HashMap<u8, u32>,
+}
+
+impl FanStateData {
+    /// Create fan state from PWM and target RPM values.
+    pub fn new(pwm: HashMap<u8, u32>, rpm: HashMap<u8, u32>) -> Self {
+        Self { pwm, rpm }
+    }
+
+    /// Fan state a profile sets on a board with `fan_count` fans.
+    ///
+    /// Profile values beyond the board's fans are ignored.
+    pub fn from_profile(profile: &FanProfile, fan_count: usize) -> Self {
+        let values: HashMap<u8, u32> = profile
+            .values
+            .iter()
+            .take(fan_count)
+            .enumerate()
+            .map(|(fan_id, &value)| (fan_id as u8, value))
+            .collect();
+        match profile.control_mode {
+            ControlMode::Pwm => Self::new(values, HashMap::new()),
+            ControlMode::Rpm => Self::new(HashMap::new(), values),
+        }
+    }
+
+    /// Check if no fan has a recorded value.
+    pub fn is_empty(&self) -> bool {
+        self.pwm.is_empty() && self.rpm.is_empty()
+    }
+
+    /// Parse FanStateData from TOML string.
+    pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Serialize FanStateData to TOML string.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+}
+
+// Custom serialization: HashMap<u8, u32> -> HashMap<String, u32> for TOML
+fn serialize_fan_values<S>(values: &HashMap<u8, u32>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    use serde::ser::SerializeMap;
+    let mut map = serializer.serialize_map(Some(values.len()))?;
+    for (k, v) in values {
+        map.serialize_entry(&k.to_string(), v)?;
+    }
+    map.end()
+}
+
+// Custom deserialization: HashMap<String, u32> -> HashMap<u8, u32>
+fn deserialize_fan_values<'de, D>(deserializer: D) -> Result<HashMap<u8, u32>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::de::Error;
+    let string_map: HashMap<String, u32> = HashMap::deserialize(deserializer)?;
+
+    string_map
+        .into_iter()
+        .map(|(k, v)| {
+            k.parse::<u8>()
+                .map(|id| (id, v))
+                .map_err(|_| D::Error::custom(format!("invalid fan ID: {}", k)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fan_state_roundtrip() {
+        let state = FanStateData::new(
+            HashMap::from([(0, 40), (1, 100)]),
+            HashMap::from([(3, 1200)]),
+        );
+
+        let toml_str = state.to_toml().unwrap();
+        assert!(toml_str.contains("[pwm]"));
+        assert!(toml_str.contains("[rpm]"));
+
+        let restored = FanStateData::from_toml(&toml_str).unwrap();
+        assert_eq!(restored, state);
+    }
+
+    #[test]
+    fn test_fan_state_from_profile() {
+        let profile = FanProfile::new(ControlMode::Rpm, vec![1000; 10]);
+        let state = FanStateData::from_profile(&profile, 4);
+        assert!(state.pwm.is_empty());
+        assert_eq!(state.rpm.len(), 4);
+        assert_eq!(state.rpm.get(&3), Some(&1000));
+
+        let profile = FanProfile::new(ControlMode::Pwm, vec![50, 60]);
+        let state = FanStateData::from_profile(&profile, 4);
+        assert_eq!(state.pwm, HashMap::from([(0, 50), (1, 60)]));
+    }
+
+    #[test]
+    fn test_fan_state_partial_and_invalid() {
+        let state = FanStateData::from_toml("[pwm]\n2 = 75\n").unwrap();
+        assert_eq!(state.pwm.get(&2), Some(&75));
+        assert!(state.rpm.is_empty());
+        assert!(!state.is_empty());
+
+        assert!(FanStateData::from_toml("").unwrap().is_empty());
+        assert!(FanStateData::from_toml("[pwm]\nfan = 75\n").is_err());
+    }
+}
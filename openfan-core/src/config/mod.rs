@@ -12,7 +12,12 @@
 //! - [`ProfileData`] - Fan profiles, mutable via API
 //! - [`ZoneData`] - Fan zones for grouped control, mutable via API
 //! - [`ThermalCurveData`] - Temperature-to-PWM curves, mutable via API
-//! - [`CfmMappingData`] - CFM display mappings, mutable via API
+//! - [`PidControllerData`] - PID controllers for closed-loop control, mutable via API
+//! - [`CurveBindingData`] - Curve-to-sensor-and-fan bindings for closed-loop control, mutable via API
+//! - [`CfmMappingData`] - CFM display mappings and airflow models, mutable via API
+//! - [`FanStateData`] - Last applied fan state, written by the daemon for restore on startup
+//! - [`FanCalibrationData`] - Measured PWM→RPM behavior of each fan, written by the calibration job
+//! - [`FanLimitsData`] - Per-fan PWM and RPM safety limits, mutable via API
 //!
 //! Each mutable data type is stored in its own TOML file within the data directory.
 //!
@@ -21,19 +26,39 @@
 
 mod aliases;
 mod cfm_mappings;
+mod curve_bindings;
+mod fan_calibration;
+mod fan_limits;
+mod fan_state;
 mod paths;
+mod pid_controllers;
 mod profiles;
 mod static_config;
 mod thermal_curves;
 mod zones;
 
 pub use aliases::AliasData;
-pub use cfm_mappings::CfmMappingData;
+pub use cfm_mappings::{
+    AirflowDirection, CfmEstimate, CfmMappingData, CfmModel, CfmPoint, CfmSource, MAX_CFM,
+    MAX_CFM_RPM, parse_cfm_points,
+};
+pub use curve_bindings::{
+    CurveBinding, CurveBindingData, DEFAULT_BINDING_INTERVAL_SECS, MAX_BINDING_INTERVAL_SECS,
+};
+pub use fan_calibration::{CalibrationPoint, FanCalibration, FanCalibrationData};
+pub use fan_limits::{FanLimits, FanLimitsData, LimitEnforcement};
+pub use fan_state::FanStateData;
 pub use paths::{default_config_path, default_data_dir};
+pub use pid_controllers::{PidController, PidControllerData, PidState};
 pub use profiles::ProfileData;
 pub use static_config::{
-    ControllerConfig, DEFAULT_SAFE_BOOT_PROFILE, ProfileName, ReconnectConfig, ServerConfig,
-    ShutdownConfig, StaticConfig,
+    AggregateFunction, AggregateInput, AlarmsConfig, ApiTokenConfig, AuthConfig, ControllerConfig,
+    CustomSensorConfig, DEFAULT_HWMON_ROOT, DEFAULT_SAFE_BOOT_PROFILE, FanLimitsConfig,
+    HistoryConfig, ProfileName, ReconnectConfig, ReloadConfig, SensorSource, SensorsConfig,
+    ServerConfig, ShutdownConfig, StartupConfig, StaticConfig, TelemetryConfig, TokenScope,
+};
+pub use thermal_curves::{
+    CurvePoint, CurveState, CurveTuning, MAX_HYSTERESIS_C, MAX_SMOOTHING_SECS, ThermalCurve,
+    ThermalCurveData, parse_points,
 };
-pub use thermal_curves::{CurvePoint, ThermalCurve, ThermalCurveData, parse_points};
-pub use zones::{Zone, ZoneData, ZoneFan};
+pub use zones::{Zone, ZoneData, ZoneFan, ZoneFanProblem, ZoneFanProblemKind};
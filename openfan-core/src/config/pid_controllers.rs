@@ -0,0 +1,384 @@
+//! PID controller data - mutable via API
+//!
+//! Stored in `{data_dir}/controllers/{id}/pid_controllers.toml`
+//!
+//! A PID controller is an alternative to a thermal curve for closed-loop
+//! control: instead of mapping temperatures to PWM values, it adjusts the PWM
+//! to hold a sensor at a target temperature. Like curves, PID controllers are
+//! referenced by name from curve bindings.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+fn default_max_pwm() -> u8 {
+    100
+}
+
+/// PID controller definition
+///
+/// The error is `temperature - setpoint`, so the output rises when the sensor
+/// is hotter than the setpoint. The output is clamped to `min_pwm..=max_pwm`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PidController {
+    /// Controller name
+    pub name: String,
+    /// Target temperature in degrees Celsius
+    pub setpoint_c: f32,
+    /// Proportional gain, in PWM percent per degree
+    pub kp: f32,
+    /// Integral gain, in PWM percent per degree-second
+    pub ki: f32,
+    /// Derivative gain, in PWM percent per degree/second
+    pub kd: f32,
+    /// Lowest PWM output (default: 0)
+    #[serde(default)]
+    pub min_pwm: u8,
+    /// Highest PWM output (default: 100)
+    #[serde(default = "default_max_pwm")]
+    pub max_pwm: u8,
+    /// Optional description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl PidController {
+    /// Create a PID controller with the full PWM output range.
+    pub fn new(name: impl Into<String>, setpoint_c: f32, kp: f32, ki: f32, kd: f32) -> Self {
+        Self {
+            name: name.into(),
+            setpoint_c,
+            kp,
+            ki,
+            kd,
+            min_pwm: 0,
+            max_pwm: 100,
+            description: None,
+        }
+    }
+
+    /// Set the PWM output range.
+    pub fn with_output_range(mut self, min_pwm: u8, max_pwm: u8) -> Self {
+        self.min_pwm = min_pwm;
+        self.max_pwm = max_pwm;
+        self
+    }
+
+    /// Set the description.
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Validate the PID controller.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-50.0..=150.0).contains(&self.setpoint_c) {
+            return Err(format!(
+                "Setpoint {} is outside valid range (-50 to 150)",
+                self.setpoint_c
+            ));
+        }
+
+        for (name, gain) in [("Kp", self.kp), ("Ki", self.ki), ("Kd", self.kd)] {
+            if !(gain.is_finite() && gain >= 0.0) {
+                return Err(format!("{} {} must be a non-negative number", name, gain));
+            }
+        }
+
+        if self.max_pwm > 100 {
+            return Err(format!(
+                "Maximum PWM {} exceeds maximum of 100",
+                self.max_pwm
+            ));
+        }
+        if self.min_pwm > self.max_pwm {
+            return Err(format!(
+                "Minimum PWM {} is greater than maximum PWM {}",
+                self.min_pwm, self.max_pwm
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Runtime state of a PID controller driven by a stream of temperature samples.
+///
+/// Anti-windup: the integral stops accumulating while the output is saturated
+/// in the direction the error is pushing it, so it does not keep growing while
+/// the fans are already at their limit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PidState {
+    /// Accumulated error, in degree-seconds
+    pub integral: f32,
+    /// Error of the last update (`temperature - setpoint`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<f32>,
+    /// Proportional term of the last update
+    pub p_term: f32,
+    /// Integral term of the last update
+    pub i_term: f32,
+    /// Derivative term of the last update
+    pub d_term: f32,
+    /// PWM output of the last update
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<u8>,
+}
+
+impl PidState {
+    /// Create an empty state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a temperature sample and return the PWM to apply.
+    ///
+    /// `elapsed_secs` is the time since the previous update; the integral and
+    /// derivative terms are skipped on the first update and when it is zero.
+    pub fn update(&mut self, pid: &PidController, temp_c: f32, elapsed_secs: f32) -> u8 {
+        let error = temp_c - pid.setpoint_c;
+        let (min, max) = (pid.min_pwm as f32, pid.max_pwm as f32);
+
+        let derivative = match self.last_error {
+            Some(last) if elapsed_secs > 0.0 => (error - last) / elapsed_secs,
+            _ => 0.0,
+        };
+        let p_term = pid.kp * error;
+        let d_term = pid.kd * derivative;
+
+        let mut integral = self.integral;
+        if self.last_error.is_some() && elapsed_secs > 0.0 {
+            let candidate = integral + error * elapsed_secs;
+            let unclamped = p_term + pid.ki * candidate + d_term;
+            let saturated = (unclamped > max && error > 0.0) || (unclamped < min && error < 0.0);
+            if !saturated {
+                integral = candidate;
+            }
+        }
+        let i_term = pid.ki * integral;
+
+        let output = (p_term + i_term + d_term).clamp(min, max).round() as u8;
+
+        self.integral = integral;
+        self.last_error = Some(error);
+        self.p_term = p_term;
+        self.i_term = i_term;
+        self.d_term = d_term;
+        self.output = Some(output);
+        output
+    }
+}
+
+/// PID controller data stored in pid_controllers.toml
+///
+/// Maps controller names to their definitions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PidControllerData {
+    /// Controller name to PID definition mapping
+    #[serde(default)]
+    pub controllers: HashMap<String, PidController>,
+}
+
+impl PidControllerData {
+    /// Get a PID controller by name.
+    pub fn get(&self, name: &str) -> Option<&PidController> {
+        self.controllers.get(name)
+    }
+
+    /// Insert a PID controller.
+    pub fn insert(&mut self, name: String, pid: PidController) {
+        self.controllers.insert(name, pid);
+    }
+
+    /// Remove a PID controller by name.
+    pub fn remove(&mut self, name: &str) -> Option<PidController> {
+        self.controllers.remove(name)
+    }
+
+    /// Check if a PID controller exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.controllers.contains_key(name)
+    }
+
+    /// Get all PID controller names.
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.controllers.keys()
+    }
+
+    /// Validate every PID controller.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut names: Vec<_> = self.controllers.keys().collect();
+        names.sort_unstable();
+        for name in names {
+            self.controllers[name]
+                .validate()
+                .map_err(|e| format!("PID '{}': {}", name, e))?;
+        }
+        Ok(())
+    }
+
+    /// Parse PidControllerData from TOML string.
+    pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+
+    /// Serialize PidControllerData to TOML string.
+    pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
+        toml::to_string_pretty(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_validate() {
+        assert!(
+            PidController::new("loop", 40.0, 5.0, 0.1, 0.0)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            PidController::new("loop", 200.0, 5.0, 0.1, 0.0)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            PidController::new("loop", 40.0, -1.0, 0.1, 0.0)
+                .validate()
+                .unwrap_err()
+                .contains("Kp")
+        );
+        assert!(
+            PidController::new("loop", 40.0, 5.0, f32::NAN, 0.0)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            PidController::new("loop", 40.0, 5.0, 0.1, 0.0)
+                .with_output_range(20, 101)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            PidController::new("loop", 40.0, 5.0, 0.1, 0.0)
+                .with_output_range(60, 40)
+                .validate()
+                .unwrap_err()
+                .contains("greater")
+        );
+    }
+
+    #[test]
+    fn test_pid_proportional() {
+        let pid = PidController::new("loop", 40.0, 10.0, 0.0, 0.0).with_output_range(20, 100);
+        let mut state = PidState::new();
+
+        // Below setpoint: clamped to the minimum
+        assert_eq!(state.update(&pid, 35.0, 0.0), 20);
+        assert_eq!(state.last_error, Some(-5.0));
+        // 3 degrees over: 30%
+        assert_eq!(state.update(&pid, 43.0, 1.0), 30);
+        assert_eq!(state.p_term, 30.0);
+        // Far over: clamped to the maximum
+        assert_eq!(state.update(&pid, 60.0, 1.0), 100);
+    }
+
+    #[test]
+    fn test_pid_integral_accumulates() {
+        let pid = PidController::new("loop", 40.0, 0.0, 1.0, 0.0);
+        let mut state = PidState::new();
+
+        // First sample only records the error
+        assert_eq!(state.update(&pid, 45.0, 0.0), 0);
+        assert_eq!(state.integral, 0.0);
+
+        assert_eq!(state.update(&pid, 45.0, 2.0), 10);
+        assert_eq!(state.update(&pid, 45.0, 2.0), 20);
+        assert_eq!(state.integral, 20.0);
+
+        // The integral holds the output at the setpoint
+        assert_eq!(state.update(&pid, 40.0, 2.0), 20);
+        assert_eq!(state.i_term, 20.0);
+    }
+
+    #[test]
+    fn test_pid_anti_windup() {
+        let pid = PidController::new("loop", 40.0, 0.0, 1.0, 0.0).with_output_range(0, 50);
+        let mut state = PidState::new();
+
+        state.update(&pid, 50.0, 0.0);
+        for _ in 0..20 {
+            state.update(&pid, 50.0, 1.0);
+        }
+        // Stopped at the step that would have exceeded the maximum
+        assert_eq!(state.integral, 50.0);
+        assert_eq!(state.output, Some(50));
+
+        // Unwinds immediately once the temperature drops below the setpoint
+        assert_eq!(state.update(&pid, 30.0, 1.0), 40);
+    }
+
+    #[test]
+    fn test_pid_derivative() {
+        let pid = PidController::new("loop", 40.0, 0.0, 0.0, 10.0);
+        let mut state = PidState::new();
+
+        assert_eq!(state.update(&pid, 40.0, 0.0), 0);
+        // Rising 1 degree/second
+        assert_eq!(state.update(&pid, 42.0, 2.0), 10);
+        assert_eq!(state.d_term, 10.0);
+        // Steady: no derivative contribution
+        assert_eq!(state.update(&pid, 42.0, 2.0), 0);
+    }
+
+    #[test]
+    fn test_pid_data_operations() {
+        let mut data = PidControllerData::default();
+        data.insert(
+            "loop".to_string(),
+            PidController::new("loop", 40.0, 5.0, 0.1, 0.0).with_description("Water loop"),
+        );
+
+        assert!(data.contains("loop"));
+        assert_eq!(data.get("loop").unwrap().setpoint_c, 40.0);
+        assert_eq!(data.names().count(), 1);
+
+        let removed = data.remove("loop").unwrap();
+        assert_eq!(removed.description.as_deref(), Some("Water loop"));
+        assert!(!data.contains("loop"));
+    }
+
+    #[test]
+    fn test_pid_data_toml_roundtrip() {
+        let mut data = PidControllerData::default();
+        data.insert(
+            "loop".to_string(),
+            PidController::new("loop", 38.5, 4.0, 0.05, 1.0).with_output_range(25, 90),
+        );
+
+        let toml_str = data.to_toml().unwrap();
+        let parsed = PidControllerData::from_toml(&toml_str).unwrap();
+        assert_eq!(parsed.get("loop"), data.get("loop"));
+    }
+
+    #[test]
+    fn test_pid_toml_defaults() {
+        let toml_str = r#"
+            [controllers.loop]
+            name = "loop"
+            setpoint_c = 40.0
+            kp = 5.0
+            ki = 0.1
+            kd = 0.0
+        "#;
+
+        let data = PidControllerData::from_toml(toml_str).unwrap();
+        let pid = data.get("loop").unwrap();
+        assert_eq!(pid.min_pwm, 0);
+        assert_eq!(pid.max_pwm, 100);
+        assert!(pid.description.is_none());
+    }
+}
@@ -1,6 +1,6 @@
-//! Static configuration loaded once at startup
+//! Static configuration loaded from `config.toml`
 //!
-//! This configuration is read-only after the daemon starts.
+//! Read at startup, and again when the daemon reloads its configuration.
 
 use serde::{Deserialize, Serialize};
 use std::borrow::Borrow;
@@ -8,12 +8,19 @@ use std::fmt;
 use std::ops::Deref;
 use std::path::PathBuf;
 
+use super::fan_limits::LimitEnforcement;
 use super::paths::default_data_dir;
 use crate::board::BoardType;
 
 /// Default profile name applied during shutdown for thermal safety
 pub const DEFAULT_SAFE_BOOT_PROFILE: &str = "100% PWM";
 
+/// Default sysfs directory containing Linux hwmon chips
+pub const DEFAULT_HWMON_ROOT: &str = "/sys/class/hwmon";
+
+/// Most points a history file may hold, which bounds its size
+const MAX_HISTORY_POINTS: u64 = 1_000_000;
+
 // Default value helpers for serde
 fn default_true() -> bool {
     true
@@ -33,6 +40,33 @@ fn default_ten() -> u64 {
 fn default_shutdown_profile() -> ProfileName {
     ProfileName::new(DEFAULT_SAFE_BOOT_PROFILE)
 }
+fn default_five() -> u64 {
+    5
+}
+fn default_scale() -> f64 {
+    1.0
+}
+fn default_weight() -> f64 {
+    1.0
+}
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
+}
+fn default_hwmon_root() -> PathBuf {
+    PathBuf::from(DEFAULT_HWMON_ROOT)
+}
+fn default_spin_pwm() -> u32 {
+    20
+}
+fn default_stall_rpm() -> u32 {
+    100
+}
+fn default_retention_hours() -> u64 {
+    168
+}
+fn default_token_scope() -> TokenScope {
+    TokenScope::Read
+}
 
 /// Profile name identifier for referencing saved profiles
 ///
@@ -146,8 +180,14 @@ pub struct ShutdownConfig {
     pub enabled: bool,
 
     /// Name of the profile to apply on shutdown (default: "100% PWM")
+    ///
+    /// Controllers can override it with `shutdown_profile` in `[[controllers]]`.
     #[serde(default = "default_shutdown_profile")]
     pub profile: ProfileName,
+
+    /// Time allowed to apply the profile to all controllers, in seconds (default: 5)
+    #[serde(default = "default_five")]
+    pub timeout_secs: u64,
 }
 
 impl Default for ShutdownConfig {
@@ -155,6 +195,565 @@ impl Default for ShutdownConfig {
         Self {
             enabled: true,
             profile: default_shutdown_profile(),
+            timeout_secs: 5,
+        }
+    }
+}
+
+/// Fan monitoring configuration for stall and failure alarms
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmsConfig {
+    /// Monitor fan RPMs and raise alarms (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Time between RPM samples, in seconds (default: 5)
+    #[serde(default = "default_five")]
+    pub interval_secs: u64,
+
+    /// Time a fault must last before its alarm is raised, in seconds (default: 10)
+    ///
+    /// Restarted when the fan is given a new PWM or target RPM, so fans have
+    /// time to spin up or down.
+    #[serde(default = "default_ten")]
+    pub grace_secs: u64,
+
+    /// PWM percentage from which a fan is expected to spin (default: 20)
+    #[serde(default = "default_spin_pwm")]
+    pub spin_pwm: u32,
+
+    /// RPM at or below which a fan expected to spin is stalled, and above which
+    /// a fan set to 0% PWM is spinning unexpectedly (default: 100)
+    #[serde(default = "default_stall_rpm")]
+    pub stall_rpm: u32,
+
+    /// RPM below which a fan expected to spin is too slow (default: 0, disabled)
+    #[serde(default)]
+    pub low_rpm: u32,
+
+    /// Profile applied to a controller when one of its alarms is raised
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failsafe_profile: Option<ProfileName>,
+}
+
+impl Default for AlarmsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 5,
+            grace_secs: 10,
+            spin_pwm: default_spin_pwm(),
+            stall_rpm: default_stall_rpm(),
+            low_rpm: 0,
+            failsafe_profile: None,
+        }
+    }
+}
+
+/// Live telemetry configuration
+///
+/// A background task per controller samples the fans while anyone is
+/// subscribed and pushes the readings to event stream subscribers. The last
+/// readings also serve fan status requests and alarms until they are stale.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// Sample fans in the background (default: true)
+    ///
+    /// When disabled, the event stream only carries configuration changes and
+    /// fan status requests read the hardware, at most once per interval.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Time between samples, in seconds (default: 1)
+    #[serde(default = "default_one")]
+    pub interval_secs: u64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_secs: 1,
+        }
+    }
+}
+
+/// Fan history configuration
+///
+/// Telemetry samples are averaged into points of `resolution_secs` and kept
+/// in one fixed-size file per controller, holding `retention_hours` of points.
+/// Requires `[telemetry]` sampling.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryConfig {
+    /// Record fan readings (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Time covered by one recorded point, in seconds (default: 10)
+    #[serde(default = "default_ten")]
+    pub resolution_secs: u64,
+
+    /// Time after which points are overwritten, in hours (default: 168)
+    ///
+    /// Together with the resolution and the fan count, sets the file size:
+    /// a 10-fan controller takes about 5 MB with the defaults.
+    #[serde(default = "default_retention_hours")]
+    pub retention_hours: u64,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            resolution_secs: 10,
+            retention_hours: default_retention_hours(),
+        }
+    }
+}
+
+impl HistoryConfig {
+    /// Time after which points are overwritten, in seconds
+    pub fn retention_secs(&self) -> u64 {
+        self.retention_hours.saturating_mul(3600)
+    }
+
+    /// Number of points each history file holds
+    pub fn capacity(&self) -> u64 {
+        self.retention_secs() / self.resolution_secs.max(1)
+    }
+
+    /// Validate the resolution and retention, which set the file size.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.resolution_secs == 0 {
+            return Err("resolution_secs must be at least 1".to_string());
+        }
+        if self.retention_hours == 0 {
+            return Err("retention_hours must be at least 1".to_string());
+        }
+        if self.resolution_secs > self.retention_secs() {
+            return Err(format!(
+                "resolution_secs ({}) is longer than retention_hours ({})",
+                self.resolution_secs, self.retention_hours
+            ));
+        }
+        if self.capacity() > MAX_HISTORY_POINTS {
+            return Err(format!(
+                "retention_hours ({}) at resolution_secs ({}) is {} points per file, \
+                 more than {}: raise resolution_secs or lower retention_hours",
+                self.retention_hours,
+                self.resolution_secs,
+                self.capacity(),
+                MAX_HISTORY_POINTS
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Configuration reload
+///
+/// `config.toml` and the data files are reloaded on SIGHUP and on
+/// `POST /api/v0/config/reload`; with `watch`, editing them is enough.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReloadConfig {
+    /// Reload when `config.toml` or a data file changes (default: false)
+    #[serde(default)]
+    pub watch: bool,
+
+    /// Time between checks for changed files, in seconds (default: 5)
+    #[serde(default = "default_five")]
+    pub interval_secs: u64,
+}
+
+impl Default for ReloadConfig {
+    fn default() -> Self {
+        Self {
+            watch: false,
+            interval_secs: 5,
+        }
+    }
+}
+
+/// Per-fan safety limit enforcement
+///
+/// The limits themselves are set per fan, in each controller's
+/// `fan_limits.toml`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FanLimitsConfig {
+    /// Whether values outside a fan's limits are clamped into them or
+    /// rejected (default: clamp)
+    #[serde(default)]
+    pub enforcement: LimitEnforcement,
+}
+
+/// Startup configuration for the initial fan state
+///
+/// Without it, fans keep whatever the firmware applied until a client sets them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupConfig {
+    /// Set the fans when the daemon starts (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Name of the profile to apply on startup (default: "100% PWM")
+    ///
+    /// Controllers can override it with `startup_profile` in `[[controllers]]`.
+    #[serde(default = "default_shutdown_profile")]
+    pub profile: ProfileName,
+
+    /// Save the fan state on every change and restore it on startup instead of
+    /// the profile, when a state was saved (default: false)
+    #[serde(default)]
+    pub restore_state: bool,
+}
+
+impl Default for StartupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            profile: default_shutdown_profile(),
+            restore_state: false,
+        }
+    }
+}
+
+/// Source of a custom temperature sensor
+///
+/// The value read from the source is multiplied by `scale` to obtain degrees
+/// Celsius (e.g. use `scale = 0.001` for a file in millidegrees).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum SensorSource {
+    /// Read a number from a file
+    File {
+        /// Path of the file to read
+        path: PathBuf,
+        /// Multiplier applied to the value read (default: 1.0)
+        #[serde(default = "default_scale")]
+        scale: f64,
+    },
+    /// Run a program and parse the first number printed on stdout
+    Command {
+        /// Program to run (looked up in `PATH` if not absolute)
+        command: String,
+        /// Arguments passed to the program
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+        /// Kill the program if it runs longer than this (default: 5)
+        #[serde(default = "default_five")]
+        timeout_secs: u64,
+        /// Reuse the last reading for this many seconds (default: 0, no caching)
+        #[serde(default)]
+        cache_ttl_secs: u64,
+        /// Multiplier applied to the value read (default: 1.0)
+        #[serde(default = "default_scale")]
+        scale: f64,
+    },
+    /// Combine the readings of other sensors
+    Virtual {
+        /// How the input readings are combined (default: max)
+        #[serde(default)]
+        function: AggregateFunction,
+        /// Sensors to combine (hwmon, file or command sensors)
+        inputs: Vec<AggregateInput>,
+    },
+}
+
+impl SensorSource {
+    /// Short name of the source kind ("file", "command" or "virtual").
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SensorSource::File { .. } => "file",
+            SensorSource::Command { .. } => "command",
+            SensorSource::Virtual { .. } => "virtual",
+        }
+    }
+}
+
+/// Function combining the inputs of a virtual sensor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AggregateFunction {
+    /// Hottest input
+    #[default]
+    Max,
+    /// Coolest input
+    Min,
+    /// Mean of all inputs
+    Average,
+    /// Mean of all inputs, weighted by their `weight`
+    Weighted,
+}
+
+impl AggregateFunction {
+    /// Name of the function as written in `config.toml` (e.g., "max").
+    pub fn name(&self) -> &'static str {
+        match self {
+            AggregateFunction::Max => "max",
+            AggregateFunction::Min => "min",
+            AggregateFunction::Average => "average",
+            AggregateFunction::Weighted => "weighted",
+        }
+    }
+
+    /// Combine `(value, weight)` pairs, returning the result and the index of
+    /// the dominant input.
+    ///
+    /// The dominant input is the one selected by `max`/`min`, or the one
+    /// contributing the most to an average (the hottest input, or the largest
+    /// `weight * value` when weighted). Returns `None` if `values` is empty or
+    /// the weights sum to zero.
+    pub fn combine(&self, values: &[(f64, f64)]) -> Option<(f64, usize)> {
+        let argmax = |key: &dyn Fn(&(f64, f64)) -> f64| {
+            values
+                .iter()
+                .enumerate()
+                .max_by(|(_, a), (_, b)| key(a).total_cmp(&key(b)))
+                .map(|(i, _)| i)
+        };
+        let hottest = argmax(&|(value, _)| *value)?;
+
+        match self {
+            AggregateFunction::Max => Some((values[hottest].0, hottest)),
+            AggregateFunction::Min => {
+                let coolest = argmax(&|(value, _)| -*value)?;
+                Some((values[coolest].0, coolest))
+            }
+            AggregateFunction::Average => {
+                let sum: f64 = values.iter().map(|(value, _)| value).sum();
+                Some((sum / values.len() as f64, hottest))
+            }
+            AggregateFunction::Weighted => {
+                let total: f64 = values.iter().map(|(_, weight)| weight).sum();
+                if total <= 0.0 {
+                    return None;
+                }
+                let sum: f64 = values.iter().map(|(value, weight)| value * weight).sum();
+                let dominant = argmax(&|(value, weight)| value * weight)?;
+                Some((sum / total, dominant))
+            }
+        }
+    }
+}
+
+/// Input of a virtual sensor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AggregateInput {
+    /// ID of the input sensor (e.g., "k10temp/temp1")
+    pub sensor: String,
+    /// Degrees Celsius added to the reading before combining (default: 0.0)
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub offset: f64,
+    /// Weight of the input for the `weighted` function (default: 1.0)
+    #[serde(default = "default_weight")]
+    pub weight: f64,
+}
+
+impl AggregateInput {
+    /// Create an input with no offset and a weight of 1.0
+    pub fn new(sensor: impl Into<String>) -> Self {
+        Self {
+            sensor: sensor.into(),
+            offset: 0.0,
+            weight: 1.0,
+        }
+    }
+
+    /// Set the offset in degrees Celsius
+    pub fn with_offset(mut self, offset: f64) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Set the weight used by the `weighted` function
+    pub fn with_weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+/// Custom temperature sensor defined in `config.toml`
+///
+/// ```toml
+/// [[sensors.custom]]
+/// id = "nvme0"
+/// kind = "command"
+/// command = "/usr/local/bin/nvme-temp"
+/// args = ["/dev/nvme0"]
+/// timeout_secs = 5
+/// cache_ttl_secs = 30
+///
+/// [[sensors.custom]]
+/// id = "ambient"
+/// kind = "file"
+/// path = "/run/ambient/temp"
+/// scale = 0.001
+///
+/// [[sensors.custom]]
+/// id = "case"
+/// kind = "virtual"
+/// function = "max"
+/// inputs = [
+///     { sensor = "k10temp/temp1" },
+///     { sensor = "amdgpu/temp1", offset = -10.0 },
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomSensorConfig {
+    /// Sensor ID, used to reference the sensor from curve bindings
+    pub id: String,
+
+    /// Optional human-readable label
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+
+    /// Where the temperature is read from
+    #[serde(flatten)]
+    pub source: SensorSource,
+}
+
+impl CustomSensorConfig {
+    /// Validate the sensor definition.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("Custom sensor ID cannot be empty".to_string());
+        }
+        // Slashes are reserved for hwmon sensor IDs ("chip/tempN")
+        if self.id.contains('/') {
+            return Err(format!("Custom sensor ID '{}' cannot contain '/'", self.id));
+        }
+
+        let scale = match &self.source {
+            SensorSource::File { scale, .. } => *scale,
+            SensorSource::Command {
+                command,
+                timeout_secs,
+                scale,
+                ..
+            } => {
+                if command.trim().is_empty() {
+                    return Err(format!("Custom sensor '{}' has an empty command", self.id));
+                }
+                if *timeout_secs == 0 {
+                    return Err(format!(
+                        "Custom sensor '{}' timeout must be at least 1 second",
+                        self.id
+                    ));
+                }
+                *scale
+            }
+            SensorSource::Virtual { function, inputs } => {
+                return self.validate_inputs(*function, inputs);
+            }
+        };
+        if !scale.is_finite() || scale == 0.0 {
+            return Err(format!(
+                "Custom sensor '{}' scale must be a non-zero number",
+                self.id
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn validate_inputs(
+        &self,
+        function: AggregateFunction,
+        inputs: &[AggregateInput],
+    ) -> Result<(), String> {
+        if inputs.is_empty() {
+            return Err(format!("Virtual sensor '{}' has no inputs", self.id));
+        }
+        for input in inputs {
+            if input.sensor.trim().is_empty() {
+                return Err(format!(
+                    "Virtual sensor '{}' has an input with an empty sensor ID",
+                    self.id
+                ));
+            }
+            if input.sensor == self.id {
+                return Err(format!("Virtual sensor '{}' cannot use itself", self.id));
+            }
+            if !input.offset.is_finite() {
+                return Err(format!(
+                    "Virtual sensor '{}' input '{}' offset must be a number",
+                    self.id, input.sensor
+                ));
+            }
+            if !input.weight.is_finite() || input.weight < 0.0 {
+                return Err(format!(
+                    "Virtual sensor '{}' input '{}' weight must be a non-negative number",
+                    self.id, input.sensor
+                ));
+            }
+        }
+        if function == AggregateFunction::Weighted && inputs.iter().all(|input| input.weight == 0.0)
+        {
+            return Err(format!(
+                "Virtual sensor '{}' weights cannot all be zero",
+                self.id
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Temperature sensor configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorsConfig {
+    /// Directory containing hwmon chips (default: "/sys/class/hwmon")
+    ///
+    /// Each `hwmonN` subdirectory is scanned for `temp*_input` files.
+    #[serde(default = "default_hwmon_root")]
+    pub hwmon_root: PathBuf,
+
+    /// Custom file, command and virtual sensors
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom: Vec<CustomSensorConfig>,
+}
+
+impl SensorsConfig {
+    /// Validate all custom sensor definitions and check for duplicate IDs.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut seen = std::collections::HashSet::new();
+        for sensor in &self.custom {
+            sensor.validate()?;
+            if !seen.insert(sensor.id.as_str()) {
+                return Err(format!("Duplicate custom sensor ID: {}", sensor.id));
+            }
+        }
+
+        // Virtual sensors only combine physical sensors, which rules out cycles
+        for sensor in &self.custom {
+            let SensorSource::Virtual { inputs, .. } = &sensor.source else {
+                continue;
+            };
+            for input in inputs {
+                if self.custom.iter().any(|other| {
+                    other.id == input.sensor && matches!(other.source, SensorSource::Virtual { .. })
+                }) {
+                    return Err(format!(
+                        "Virtual sensor '{}' cannot use virtual sensor '{}' as an input",
+                        sensor.id, input.sensor
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for SensorsConfig {
+    fn default() -> Self {
+        Self {
+            hwmon_root: default_hwmon_root(),
+            custom: Vec::new(),
         }
     }
 }
@@ -162,21 +761,40 @@ impl Default for ShutdownConfig {
 /// Controller configuration for multi-controller setups
 ///
 /// Each controller entry defines a physical fan controller device
-/// with its serial port path and board type.
+/// with its serial port path or USB serial number, and board type.
+///
+/// Device nodes like `/dev/ttyACM0` depend on enumeration order and can
+/// change across reboots and replugs. A USB serial number (or a
+/// `/dev/serial/by-id/...` path) keeps pointing at the same board.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ControllerConfig {
     /// Unique identifier for this controller (e.g., "main", "gpu", "chassis")
     pub id: String,
 
-    /// Serial device path (e.g., "/dev/ttyACM0", "/dev/ttyUSB0", "COM3")
+    /// Serial device path (e.g., "/dev/ttyACM0", "/dev/serial/by-id/...", "COM3")
+    ///
+    /// Leave empty when the board is identified by `serial_number`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub device: String,
 
+    /// USB serial number of the board, resolved to its current serial port
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+
     /// Board type: "standard" or "custom:N" where N is fan count 1-16
     pub board: BoardType,
 
     /// Optional human-readable description
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+
+    /// Profile applied on startup, overriding `[startup] profile`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup_profile: Option<ProfileName>,
+
+    /// Profile applied on shutdown, overriding `[shutdown] profile`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shutdown_profile: Option<ProfileName>,
 }
 
 impl ControllerConfig {
@@ -185,8 +803,28 @@ impl ControllerConfig {
         Self {
             id: id.into(),
             device: device.into(),
+            serial_number: None,
+            board,
+            description: None,
+            startup_profile: None,
+            shutdown_profile: None,
+        }
+    }
+
+    /// Create a controller configuration identified by USB serial number
+    pub fn with_serial_number(
+        id: impl Into<String>,
+        serial_number: impl Into<String>,
+        board: BoardType,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            device: String::new(),
+            serial_number: Some(serial_number.into()),
             board,
             description: None,
+            startup_profile: None,
+            shutdown_profile: None,
         }
     }
 
@@ -200,8 +838,35 @@ impl ControllerConfig {
         Self {
             id: id.into(),
             device: device.into(),
+            serial_number: None,
             board,
             description: Some(description.into()),
+            startup_profile: None,
+            shutdown_profile: None,
+        }
+    }
+
+    /// Validate the controller definition.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("Controller ID cannot be empty".to_string());
+        }
+        match (&self.serial_number, self.device.is_empty()) {
+            (Some(serial_number), _) if serial_number.trim().is_empty() => Err(format!(
+                "Controller '{}': serial_number cannot be empty",
+                self.id
+            )),
+            (Some(_), false) => Err(format!(
+                "Controller '{}': set either device or serial_number, not both",
+                self.id
+            )),
+            (None, true) => Err(format!(
+                "Controller '{}': either device or serial_number is required",
+                self.id
+            )),
+            _ => Ok(()),
         }
     }
 }
@@ -215,6 +880,10 @@ pub struct ServerConfig {
     pub port: u16,
     /// Communication timeout in seconds
     pub communication_timeout: u64,
+    /// Origins allowed to call the API from a browser, e.g.
+    /// `https://dashboard.example.com` (default: none)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_origins: Vec<String>,
 }
 
 impl Default for ServerConfig {
@@ -223,13 +892,108 @@ impl Default for ServerConfig {
             bind_address: "127.0.0.1".to_string(),
             port: 3000,
             communication_timeout: 1,
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Check that each allowed origin is a scheme and host, with no path.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        for origin in &self.allowed_origins {
+            let host = origin
+                .strip_prefix("https://")
+                .or_else(|| origin.strip_prefix("http://"));
+            let valid = host.is_some_and(|host| {
+                !host.is_empty() && host.bytes().all(|b| b.is_ascii_graphic() && b != b'/')
+            });
+            if !valid {
+                return Err(format!(
+                    "Allowed origin '{}' must be http(s)://host[:port], e.g. https://example.com",
+                    origin
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Access granted by an API token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    /// Read fan status, configuration, telemetry and history
+    Read,
+    /// Also set fans and change profiles, aliases, curves, bindings and zones
+    Control,
+}
+
+/// API token accepted by the server
+///
+/// Only the SHA-256 of the token is stored; clients send the token itself as
+/// `Authorization: Bearer <token>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiTokenConfig {
+    /// Name of the token, logged when it is used
+    pub name: String,
+
+    /// SHA-256 of the token, as 64 hexadecimal digits
+    pub sha256: String,
+
+    /// Access granted by the token (default: read)
+    #[serde(default = "default_token_scope")]
+    pub scope: TokenScope,
+}
+
+/// REST API authentication configuration
+///
+/// ```toml
+/// [[auth.tokens]]
+/// name = "grafana"
+/// sha256 = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+/// scope = "read"
+/// ```
+///
+/// Without tokens, the API is open to anyone who can reach the server.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuthConfig {
+    /// Tokens accepted by the server; any of them is required once one is set
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tokens: Vec<ApiTokenConfig>,
+}
+
+impl AuthConfig {
+    /// Whether requests must carry a token.
+    pub fn enabled(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    /// Validate the token hashes and check for duplicate names.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut seen = std::collections::HashSet::new();
+        for token in &self.tokens {
+            if token.sha256.len() != 64 || !token.sha256.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(format!(
+                    "Token '{}': sha256 must be 64 hexadecimal digits",
+                    token.name
+                ));
+            }
+            if !seen.insert(token.name.as_str()) {
+                return Err(format!("Duplicate token name: {}", token.name));
+            }
         }
+        Ok(())
     }
 }
 
 /// Static configuration for the OpenFAN daemon.
 ///
-/// This is loaded once at startup and remains immutable during runtime.
+/// This is loaded at startup and replaced as a whole when the daemon reloads
+/// its configuration; `server` and `data_dir` only change on restart.
 /// Located at `~/.config/openfan/config.toml` by default.
 ///
 /// # Multi-Controller Setup
@@ -245,7 +1009,7 @@ impl Default for ServerConfig {
 ///
 /// [[controllers]]
 /// id = "gpu"
-/// device = "/dev/ttyUSB0"
+/// serial_number = "E6614103E7635A2F"
 /// board = "custom:4"
 /// description = "GPU cooling"
 /// ```
@@ -258,6 +1022,10 @@ pub struct StaticConfig {
     #[serde(default)]
     pub server: ServerConfig,
 
+    /// REST API authentication
+    #[serde(default)]
+    pub auth: AuthConfig,
+
     /// Directory for mutable data files (aliases, profiles, etc.)
     ///
     /// Defaults to `~/.local/share/openfan` (XDG data directory).
@@ -268,10 +1036,38 @@ pub struct StaticConfig {
     #[serde(default)]
     pub reconnect: ReconnectConfig,
 
+    /// Startup configuration for the initial fan state
+    #[serde(default)]
+    pub startup: StartupConfig,
+
     /// Shutdown configuration for safe boot profile
     #[serde(default)]
     pub shutdown: ShutdownConfig,
 
+    /// Temperature sensor configuration
+    #[serde(default)]
+    pub sensors: SensorsConfig,
+
+    /// Fan stall and failure alarm configuration
+    #[serde(default)]
+    pub alarms: AlarmsConfig,
+
+    /// Live telemetry configuration
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+
+    /// Fan history configuration
+    #[serde(default)]
+    pub history: HistoryConfig,
+
+    /// Configuration reload
+    #[serde(default)]
+    pub reload: ReloadConfig,
+
+    /// Per-fan safety limit enforcement
+    #[serde(default)]
+    pub fan_limits: FanLimitsConfig,
+
     /// Controller configurations for multi-controller setups
     ///
     /// Each entry in this array defines a physical fan controller.
@@ -284,9 +1080,17 @@ impl Default for StaticConfig {
     fn default() -> Self {
         Self {
             server: ServerConfig::default(),
+            auth: AuthConfig::default(),
             data_dir: default_data_dir(),
             reconnect: ReconnectConfig::default(),
+            startup: StartupConfig::default(),
             shutdown: ShutdownConfig::default(),
+            sensors: SensorsConfig::default(),
+            alarms: AlarmsConfig::default(),
+            telemetry: TelemetryConfig::default(),
+            history: HistoryConfig::default(),
+            reload: ReloadConfig::default(),
+            fan_limits: FanLimitsConfig::default(),
             controllers: Vec::new(),
         }
     }
@@ -310,6 +1114,20 @@ impl StaticConfig {
     pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
         toml::to_string_pretty(self)
     }
+
+    /// Validate all controller definitions and check for duplicate IDs.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate_controllers(&self) -> Result<(), String> {
+        let mut seen = std::collections::HashSet::new();
+        for controller in &self.controllers {
+            controller.validate()?;
+            if !seen.insert(controller.id.as_str()) {
+                return Err(format!("Duplicate controller ID: {}", controller.id));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -472,6 +1290,7 @@ mod tests {
         let config = ShutdownConfig::default();
         assert!(config.enabled);
         assert_eq!(config.profile.as_str(), DEFAULT_SAFE_BOOT_PROFILE);
+        assert_eq!(config.timeout_secs, 5);
     }
 
     #[test]
@@ -517,6 +1336,73 @@ mod tests {
         let config = StaticConfig::from_toml(toml_str).unwrap();
         assert!(config.shutdown.enabled); // default
         assert_eq!(config.shutdown.profile.as_str(), "Custom Profile");
+        assert_eq!(config.shutdown.timeout_secs, 5); // default
+    }
+
+    #[test]
+    fn test_controller_shutdown_profile_override() {
+        let toml_str = r#"
+            [shutdown]
+            timeout_secs = 2
+
+            [[controllers]]
+            id = "main"
+            device = "/dev/ttyACM0"
+            board = "standard"
+
+            [[controllers]]
+            id = "gpu"
+            device = "/dev/ttyACM1"
+            board = "custom:4"
+            shutdown_profile = "50% PWM"
+        "#;
+
+        let config = StaticConfig::from_toml(toml_str).unwrap();
+        assert_eq!(config.shutdown.timeout_secs, 2);
+        assert!(config.controllers[0].shutdown_profile.is_none());
+        assert_eq!(
+            config.controllers[1]
+                .shutdown_profile
+                .as_ref()
+                .map(ProfileName::as_str),
+            Some("50% PWM")
+        );
+    }
+
+    #[test]
+    fn test_static_config_startup_defaults_when_missing() {
+        let config = StaticConfig::from_toml("").unwrap();
+        assert!(!config.startup.enabled);
+        assert_eq!(config.startup.profile.as_str(), DEFAULT_SAFE_BOOT_PROFILE);
+        assert!(!config.startup.restore_state);
+    }
+
+    #[test]
+    fn test_static_config_with_startup_section() {
+        let toml_str = r#"
+            [startup]
+            enabled = true
+            profile = "50% PWM"
+            restore_state = true
+
+            [[controllers]]
+            id = "main"
+            device = "/dev/ttyACM0"
+            board = "standard"
+            startup_profile = "1000 RPM"
+        "#;
+
+        let config = StaticConfig::from_toml(toml_str).unwrap();
+        assert!(config.startup.enabled);
+        assert_eq!(config.startup.profile.as_str(), "50% PWM");
+        assert!(config.startup.restore_state);
+        assert_eq!(
+            config.controllers[0]
+                .startup_profile
+                .as_ref()
+                .map(ProfileName::as_str),
+            Some("1000 RPM")
+        );
     }
 
     #[test]
@@ -531,6 +1417,452 @@ mod tests {
         assert_eq!(config.shutdown.profile.as_str(), DEFAULT_SAFE_BOOT_PROFILE);
     }
 
+    #[test]
+    fn test_static_config_alarms_defaults_when_missing() {
+        let config = StaticConfig::from_toml("").unwrap();
+        assert!(config.alarms.enabled);
+        assert_eq!(config.alarms.interval_secs, 5);
+        assert_eq!(config.alarms.grace_secs, 10);
+        assert_eq!(config.alarms.spin_pwm, 20);
+        assert_eq!(config.alarms.stall_rpm, 100);
+        assert_eq!(config.alarms.low_rpm, 0);
+        assert!(config.alarms.failsafe_profile.is_none());
+    }
+
+    #[test]
+    fn test_static_config_with_alarms_section() {
+        let toml_str = r#"
+            [alarms]
+            grace_secs = 30
+            low_rpm = 400
+            failsafe_profile = "100% PWM"
+        "#;
+
+        let config = StaticConfig::from_toml(toml_str).unwrap();
+        assert!(config.alarms.enabled); // default
+        assert_eq!(config.alarms.grace_secs, 30);
+        assert_eq!(config.alarms.low_rpm, 400);
+        assert_eq!(
+            config
+                .alarms
+                .failsafe_profile
+                .as_ref()
+                .map(ProfileName::as_str),
+            Some("100% PWM")
+        );
+    }
+
+    #[test]
+    fn test_static_config_with_telemetry_section() {
+        let config = StaticConfig::from_toml("").unwrap();
+        assert!(config.telemetry.enabled);
+        assert_eq!(config.telemetry.interval_secs, 1);
+
+        let config = StaticConfig::from_toml("[telemetry]\ninterval_secs = 3\n").unwrap();
+        assert!(config.telemetry.enabled); // default
+        assert_eq!(config.telemetry.interval_secs, 3);
+    }
+
+    #[test]
+    fn test_static_config_with_history_section() {
+        let config = StaticConfig::from_toml("").unwrap();
+        assert!(config.history.enabled);
+        assert_eq!(config.history.resolution_secs, 10);
+        assert_eq!(config.history.retention_hours, 168);
+
+        let config = StaticConfig::from_toml(
+            "[history]
+resolution_secs = 60
+",
+        )
+        .unwrap();
+        assert!(config.history.enabled); // default
+        assert_eq!(config.history.resolution_secs, 60);
+        assert_eq!(config.history.retention_hours, 168); // default
+    }
+
+    #[test]
+    fn test_history_config_validation() {
+        let history = |resolution_secs, retention_hours| HistoryConfig {
+            enabled: true,
+            resolution_secs,
+            retention_hours,
+        };
+        assert!(HistoryConfig::default().validate().is_ok());
+        assert_eq!(HistoryConfig::default().capacity(), 60_480);
+        assert!(history(1, 1).validate().is_ok());
+        // A year of points at 1 minute
+        assert!(history(60, 24 * 365).validate().is_ok());
+
+        assert!(history(0, 168).validate().is_err());
+        assert!(history(10, 0).validate().is_err());
+        assert!(history(7200, 1).validate().is_err());
+        assert!(
+            history(1, 24 * 365)
+                .validate()
+                .unwrap_err()
+                .contains("points per file")
+        );
+        // Overflowing retention is still bounded
+        assert!(history(10, u64::MAX).validate().is_err());
+    }
+
+    #[test]
+    fn test_static_config_with_reload_section() {
+        let config = StaticConfig::from_toml("").unwrap();
+        assert!(!config.reload.watch);
+        assert_eq!(config.reload.interval_secs, 5);
+
+        let config = StaticConfig::from_toml("[reload]\nwatch = true\n").unwrap();
+        assert!(config.reload.watch);
+        assert_eq!(config.reload.interval_secs, 5); // default
+    }
+
+    #[test]
+    fn test_static_config_with_fan_limits_section() {
+        let config = StaticConfig::from_toml("").unwrap();
+        assert_eq!(config.fan_limits.enforcement, LimitEnforcement::Clamp);
+
+        let config = StaticConfig::from_toml("[fan_limits]\nenforcement = \"reject\"\n").unwrap();
+        assert_eq!(config.fan_limits.enforcement, LimitEnforcement::Reject);
+
+        assert!(StaticConfig::from_toml("[fan_limits]\nenforcement = \"ignore\"\n").is_err());
+    }
+
+    #[test]
+    fn test_static_config_with_auth_section() {
+        let config = StaticConfig::from_toml("").unwrap();
+        assert!(!config.auth.enabled());
+
+        let config = StaticConfig::from_toml(
+            r#"
+            [[auth.tokens]]
+            name = "grafana"
+            sha256 = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+
+            [[auth.tokens]]
+            name = "admin"
+            sha256 = "60303AE22B998861BCE3B28F33EEC1BE758A213C86C93C076DBE9F558C11C752"
+            scope = "control"
+        "#,
+        )
+        .unwrap();
+        assert!(config.auth.enabled());
+        assert_eq!(config.auth.tokens[0].scope, TokenScope::Read); // default
+        assert_eq!(config.auth.tokens[1].scope, TokenScope::Control);
+        assert!(config.auth.validate().is_ok());
+
+        let mut auth = config.auth.clone();
+        auth.tokens[1].name = "grafana".to_string();
+        assert!(
+            auth.validate()
+                .unwrap_err()
+                .contains("Duplicate token name")
+        );
+
+        let mut auth = config.auth;
+        auth.tokens[0].sha256 = "secret".to_string();
+        assert!(
+            auth.validate()
+                .unwrap_err()
+                .contains("64 hexadecimal digits")
+        );
+    }
+
+    #[test]
+    fn test_static_config_allowed_origins() {
+        let config = StaticConfig::from_toml("").unwrap();
+        assert!(config.server.allowed_origins.is_empty());
+
+        let config = StaticConfig::from_toml(
+            r#"
+            [server]
+            bind_address = "127.0.0.1"
+            port = 3000
+            communication_timeout = 1
+            allowed_origins = ["https://dashboard.example.com", "http://localhost:8080"]
+        "#,
+        )
+        .unwrap();
+        assert_eq!(config.server.allowed_origins.len(), 2);
+        assert!(config.server.validate().is_ok());
+
+        for origin in [
+            "*",
+            "dashboard.example.com",
+            "https://",
+            "https://example.com/",
+        ] {
+            let mut server = config.server.clone();
+            server.allowed_origins = vec![origin.to_string()];
+            assert!(
+                server.validate().unwrap_err().contains(origin),
+                "{}",
+                origin
+            );
+        }
+    }
+
+    #[test]
+    fn test_static_config_sensors_defaults_when_missing() {
+        let config = StaticConfig::from_toml("").unwrap();
+        assert_eq!(config.sensors.hwmon_root, PathBuf::from(DEFAULT_HWMON_ROOT));
+    }
+
+    #[test]
+    fn test_static_config_with_sensors_section() {
+        let toml_str = r#"
+            [sensors]
+            hwmon_root = "/tmp/fake-hwmon"
+        "#;
+
+        let config = StaticConfig::from_toml(toml_str).unwrap();
+        assert_eq!(config.sensors.hwmon_root, PathBuf::from("/tmp/fake-hwmon"));
+    }
+
+    #[test]
+    fn test_static_config_with_custom_sensors() {
+        let toml_str = r#"
+            [[sensors.custom]]
+            id = "ambient"
+            kind = "file"
+            path = "/run/ambient/temp"
+            scale = 0.001
+
+            [[sensors.custom]]
+            id = "nvme0"
+            label = "NVMe via smartctl"
+            kind = "command"
+            command = "nvme-temp"
+            args = ["/dev/nvme0"]
+            cache_ttl_secs = 30
+        "#;
+
+        let config = StaticConfig::from_toml(toml_str).unwrap();
+        assert!(config.sensors.validate().is_ok());
+        assert_eq!(config.sensors.custom.len(), 2);
+
+        let ambient = &config.sensors.custom[0];
+        assert_eq!(ambient.source.kind(), "file");
+        assert_eq!(
+            ambient.source,
+            SensorSource::File {
+                path: PathBuf::from("/run/ambient/temp"),
+                scale: 0.001
+            }
+        );
+
+        let nvme = &config.sensors.custom[1];
+        assert_eq!(nvme.label.as_deref(), Some("NVMe via smartctl"));
+        assert_eq!(
+            nvme.source,
+            SensorSource::Command {
+                command: "nvme-temp".to_string(),
+                args: vec!["/dev/nvme0".to_string()],
+                timeout_secs: 5,
+                cache_ttl_secs: 30,
+                scale: 1.0,
+            }
+        );
+
+        // Roundtrip
+        let parsed = StaticConfig::from_toml(&config.to_toml().unwrap()).unwrap();
+        assert_eq!(parsed.sensors.custom, config.sensors.custom);
+    }
+
+    #[test]
+    fn test_static_config_custom_sensor_unknown_kind() {
+        let toml_str = r#"
+            [[sensors.custom]]
+            id = "x"
+            kind = "magic"
+        "#;
+        assert!(StaticConfig::from_toml(toml_str).is_err());
+    }
+
+    #[test]
+    fn test_sensors_config_validate() {
+        let file = |id: &str, scale: f64| CustomSensorConfig {
+            id: id.to_string(),
+            label: None,
+            source: SensorSource::File {
+                path: PathBuf::from("/tmp/temp"),
+                scale,
+            },
+        };
+        let command = |command: &str, timeout_secs: u64| CustomSensorConfig {
+            id: "cmd".to_string(),
+            label: None,
+            source: SensorSource::Command {
+                command: command.to_string(),
+                args: Vec::new(),
+                timeout_secs,
+                cache_ttl_secs: 0,
+                scale: 1.0,
+            },
+        };
+
+        assert!(file("a", 1.0).validate().is_ok());
+        assert!(file("", 1.0).validate().is_err());
+        assert!(
+            file("k10temp/temp1", 1.0)
+                .validate()
+                .unwrap_err()
+                .contains("'/'")
+        );
+        assert!(file("a", 0.0).validate().is_err());
+        assert!(file("a", f64::NAN).validate().is_err());
+        assert!(command("echo", 1).validate().is_ok());
+        assert!(command(" ", 1).validate().is_err());
+        assert!(command("echo", 0).validate().is_err());
+
+        let config = SensorsConfig {
+            custom: vec![file("a", 1.0), file("a", 1.0)],
+            ..Default::default()
+        };
+        assert!(config.validate().unwrap_err().contains("Duplicate"));
+    }
+
+    #[test]
+    fn test_static_config_virtual_sensor() {
+        let toml_str = r#"
+            [[sensors.custom]]
+            id = "case"
+            kind = "virtual"
+            inputs = [
+                { sensor = "k10temp/temp1" },
+                { sensor = "amdgpu/temp1", offset = -10.0 },
+            ]
+
+            [[sensors.custom]]
+            id = "blend"
+            kind = "virtual"
+            function = "weighted"
+            inputs = [
+                { sensor = "k10temp/temp1", weight = 3.0 },
+                { sensor = "amdgpu/temp1" },
+            ]
+        "#;
+
+        let config = StaticConfig::from_toml(toml_str).unwrap();
+        assert!(config.sensors.validate().is_ok());
+
+        let case = &config.sensors.custom[0];
+        assert_eq!(case.source.kind(), "virtual");
+        assert_eq!(
+            case.source,
+            SensorSource::Virtual {
+                function: AggregateFunction::Max,
+                inputs: vec![
+                    AggregateInput::new("k10temp/temp1"),
+                    AggregateInput::new("amdgpu/temp1").with_offset(-10.0),
+                ],
+            }
+        );
+
+        let blend = &config.sensors.custom[1];
+        assert_eq!(
+            blend.source,
+            SensorSource::Virtual {
+                function: AggregateFunction::Weighted,
+                inputs: vec![
+                    AggregateInput::new("k10temp/temp1").with_weight(3.0),
+                    AggregateInput::new("amdgpu/temp1"),
+                ],
+            }
+        );
+
+        // Roundtrip
+        let parsed = StaticConfig::from_toml(&config.to_toml().unwrap()).unwrap();
+        assert_eq!(parsed.sensors.custom, config.sensors.custom);
+    }
+
+    #[test]
+    fn test_virtual_sensor_validate() {
+        let sensor = |id: &str, function: AggregateFunction, inputs: Vec<AggregateInput>| {
+            CustomSensorConfig {
+                id: id.to_string(),
+                label: None,
+                source: SensorSource::Virtual { function, inputs },
+            }
+        };
+        let max = |inputs| sensor("case", AggregateFunction::Max, inputs);
+
+        assert!(max(vec![AggregateInput::new("cpu")]).validate().is_ok());
+        assert!(max(vec![]).validate().unwrap_err().contains("no inputs"));
+        assert!(max(vec![AggregateInput::new(" ")]).validate().is_err());
+        assert!(
+            max(vec![AggregateInput::new("case")])
+                .validate()
+                .unwrap_err()
+                .contains("itself")
+        );
+        assert!(
+            max(vec![AggregateInput::new("cpu").with_offset(f64::NAN)])
+                .validate()
+                .is_err()
+        );
+        assert!(
+            max(vec![AggregateInput::new("cpu").with_weight(-1.0)])
+                .validate()
+                .is_err()
+        );
+        // Zero weights are only a problem for the weighted function
+        assert!(
+            max(vec![AggregateInput::new("cpu").with_weight(0.0)])
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            sensor(
+                "case",
+                AggregateFunction::Weighted,
+                vec![AggregateInput::new("cpu").with_weight(0.0)]
+            )
+            .validate()
+            .is_err()
+        );
+
+        // Virtual sensors cannot be nested
+        let config = SensorsConfig {
+            custom: vec![
+                max(vec![AggregateInput::new("cpu")]),
+                sensor(
+                    "outer",
+                    AggregateFunction::Min,
+                    vec![AggregateInput::new("case")],
+                ),
+            ],
+            ..Default::default()
+        };
+        assert!(
+            config
+                .validate()
+                .unwrap_err()
+                .contains("virtual sensor 'case'")
+        );
+    }
+
+    #[test]
+    fn test_aggregate_function_combine() {
+        let values = [(60.0, 1.0), (70.0, 1.0), (40.0, 2.0)];
+
+        assert_eq!(AggregateFunction::Max.combine(&values), Some((70.0, 1)));
+        assert_eq!(AggregateFunction::Min.combine(&values), Some((40.0, 2)));
+        assert_eq!(
+            AggregateFunction::Average.combine(&values),
+            Some((170.0 / 3.0, 1))
+        );
+        // (60 + 70 + 80) / 4, with the 2x-weighted input dominating
+        assert_eq!(
+            AggregateFunction::Weighted.combine(&values),
+            Some((52.5, 2))
+        );
+
+        assert_eq!(AggregateFunction::Max.combine(&[]), None);
+        assert_eq!(AggregateFunction::Weighted.combine(&[(50.0, 0.0)]), None);
+    }
+
     // ProfileName tests - all test actual implementations we wrote
     #[test]
     fn test_profile_name_new() {
@@ -731,5 +2063,80 @@ mod tests {
         assert!(toml_str.contains("id = \"main\""));
         assert!(toml_str.contains("device = \"/dev/ttyACM0\""));
         assert!(toml_str.contains("board = \"standard\""));
+        assert!(!toml_str.contains("serial_number"));
+    }
+
+    #[test]
+    fn test_static_config_controller_by_serial_number() {
+        let toml_str = r#"
+            [[controllers]]
+            id = "main"
+            serial_number = "E6614103E7635A2F"
+            board = "standard"
+        "#;
+
+        let config = StaticConfig::from_toml(toml_str).unwrap();
+        assert!(config.validate_controllers().is_ok());
+        assert!(config.controllers[0].device.is_empty());
+        assert_eq!(
+            config.controllers[0].serial_number.as_deref(),
+            Some("E6614103E7635A2F")
+        );
+
+        let toml_str = config.to_toml().unwrap();
+        assert!(toml_str.contains("serial_number = \"E6614103E7635A2F\""));
+        assert!(!toml_str.contains("device"));
+    }
+
+    #[test]
+    fn test_controller_config_validate() {
+        let board = BoardType::OpenFanStandard;
+        assert!(
+            ControllerConfig::new("main", "/dev/ttyACM0", board)
+                .validate()
+                .is_ok()
+        );
+        assert!(
+            ControllerConfig::with_serial_number("main", "E661", board)
+                .validate()
+                .is_ok()
+        );
+
+        assert!(
+            ControllerConfig::new("", "/dev/ttyACM0", board)
+                .validate()
+                .is_err()
+        );
+        assert!(ControllerConfig::new("main", "", board).validate().is_err());
+        assert!(
+            ControllerConfig::with_serial_number("main", " ", board)
+                .validate()
+                .is_err()
+        );
+
+        let mut both = ControllerConfig::with_serial_number("main", "E661", board);
+        both.device = "/dev/ttyACM0".to_string();
+        let err = both.validate().unwrap_err();
+        assert!(err.contains("not both"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_controllers_duplicate_id() {
+        let mut config = StaticConfig::default();
+        config.controllers.push(ControllerConfig::new(
+            "main",
+            "/dev/ttyACM0",
+            BoardType::OpenFanStandard,
+        ));
+        config
+            .controllers
+            .push(ControllerConfig::with_serial_number(
+                "main",
+                "E661",
+                BoardType::OpenFanStandard,
+            ));
+
+        let err = config.validate_controllers().unwrap_err();
+        assert!(err.contains("Duplicate controller ID: main"), "{}", err);
     }
 }
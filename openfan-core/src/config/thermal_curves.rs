@@ -4,6 +4,10 @@
 //!
 //! Thermal curves define temperature-to-PWM mappings for dynamic fan control.
 //! Linear interpolation is used between defined points.
+//!
+//! When a curve drives fans from live sensor data, its optional
+//! [`CurveTuning`] (input smoothing, hysteresis and ramp limits) is applied by
+//! a [`CurveState`] to avoid fans hunting around breakpoints.
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -24,6 +28,93 @@ impl CurvePoint {
     }
 }
 
+/// Maximum hysteresis in degrees Celsius.
+pub const MAX_HYSTERESIS_C: f32 = 50.0;
+
+/// Maximum temperature smoothing window in seconds.
+pub const MAX_SMOOTHING_SECS: f32 = 3600.0;
+
+/// Dynamic behavior of a curve when driven by live sensor data.
+///
+/// Every setting is optional; unset or zero disables it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct CurveTuning {
+    /// Hysteresis in degrees Celsius: PWM only decreases once the temperature
+    /// has dropped this far below the level that set it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hysteresis_c: Option<f32>,
+    /// Maximum PWM increase in percentage points per second
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ramp_up_pwm_per_sec: Option<f32>,
+    /// Maximum PWM decrease in percentage points per second
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ramp_down_pwm_per_sec: Option<f32>,
+    /// Exponential moving average window (time constant) in seconds for the
+    /// input temperature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smoothing_secs: Option<f32>,
+}
+
+impl CurveTuning {
+    /// Return a copy with the settings present in `update` overriding ours.
+    pub fn merged_with(&self, update: &CurveTuning) -> CurveTuning {
+        CurveTuning {
+            hysteresis_c: update.hysteresis_c.or(self.hysteresis_c),
+            ramp_up_pwm_per_sec: update.ramp_up_pwm_per_sec.or(self.ramp_up_pwm_per_sec),
+            ramp_down_pwm_per_sec: update.ramp_down_pwm_per_sec.or(self.ramp_down_pwm_per_sec),
+            smoothing_secs: update.smoothing_secs.or(self.smoothing_secs),
+        }
+    }
+
+    /// Validate the tuning values.
+    ///
+    /// Returns Ok if valid, or an error message if invalid.
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(h) = self.hysteresis_c
+            && !(0.0..=MAX_HYSTERESIS_C).contains(&h)
+        {
+            return Err(format!(
+                "Hysteresis {} is outside valid range (0 to {})",
+                h, MAX_HYSTERESIS_C
+            ));
+        }
+
+        for (name, rate) in [
+            ("Ramp-up rate", self.ramp_up_pwm_per_sec),
+            ("Ramp-down rate", self.ramp_down_pwm_per_sec),
+        ] {
+            if let Some(rate) = rate
+                && !(rate.is_finite() && rate >= 0.0)
+            {
+                return Err(format!("{} {} must be a non-negative number", name, rate));
+            }
+        }
+
+        if let Some(window) = self.smoothing_secs
+            && !(0.0..=MAX_SMOOTHING_SECS).contains(&window)
+        {
+            return Err(format!(
+                "Smoothing window {} is outside valid range (0 to {})",
+                window, MAX_SMOOTHING_SECS
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check whether any setting is active.
+    pub fn is_active(&self) -> bool {
+        [
+            self.hysteresis_c,
+            self.ramp_up_pwm_per_sec,
+            self.ramp_down_pwm_per_sec,
+            self.smoothing_secs,
+        ]
+        .iter()
+        .any(|v| v.is_some_and(|v| v > 0.0))
+    }
+}
+
 /// A thermal curve defining temperature-to-PWM mapping.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ThermalCurve {
@@ -34,6 +125,9 @@ pub struct ThermalCurve {
     /// Optional description
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    /// Smoothing, hysteresis and ramp limits applied by the control loop
+    #[serde(flatten)]
+    pub tuning: CurveTuning,
 }
 
 impl ThermalCurve {
@@ -45,6 +139,7 @@ impl ThermalCurve {
             name: name.into(),
             points,
             description: None,
+            tuning: CurveTuning::default(),
         }
     }
 
@@ -58,9 +153,16 @@ impl ThermalCurve {
             name: name.into(),
             points,
             description: Some(description.into()),
+            tuning: CurveTuning::default(),
         }
     }
 
+    /// Set the smoothing, hysteresis and ramp limits.
+    pub fn with_tuning(mut self, tuning: CurveTuning) -> Self {
+        self.tuning = tuning;
+        self
+    }
+
     /// Interpolate PWM value for a given temperature.
     ///
     /// - Returns the minimum PWM if temperature is below the first point
@@ -139,7 +241,7 @@ impl ThermalCurve {
             }
         }
 
-        Ok(())
+        self.tuning.validate()
     }
 
     /// Sort points by temperature (ascending).
@@ -149,6 +251,85 @@ impl ThermalCurve {
     }
 }
 
+/// Runtime state for driving a curve from a stream of temperature samples.
+///
+/// Applies the curve's [`CurveTuning`] in order: the input temperature is
+/// smoothed, hysteresis holds the temperature used for lookup while it falls
+/// by less than the threshold, and the resulting PWM is rate-limited.
+#[derive(Debug, Clone, Default)]
+pub struct CurveState {
+    /// Smoothed input temperature
+    smoothed: Option<f32>,
+    /// Temperature used for the last curve lookup
+    anchor: Option<f32>,
+    /// Last output PWM, kept fractional so slow ramps accumulate
+    output: Option<f32>,
+}
+
+impl CurveState {
+    /// Create an empty state; the first sample is applied unfiltered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a temperature sample taken `elapsed_secs` after the previous one
+    /// and return the PWM to apply.
+    pub fn update(&mut self, curve: &ThermalCurve, temp: f32, elapsed_secs: f32) -> u8 {
+        let tuning = &curve.tuning;
+        let elapsed_secs = elapsed_secs.max(0.0);
+
+        // Exponential moving average, time-based so it is independent of the
+        // poll interval
+        let smoothed = match (self.smoothed, tuning.smoothing_secs) {
+            (Some(prev), Some(window)) if window > 0.0 => {
+                let alpha = 1.0 - (-elapsed_secs / window).exp();
+                prev + alpha * (temp - prev)
+            }
+            _ => temp,
+        };
+        self.smoothed = Some(smoothed);
+
+        // Hysteresis: follow rising temperatures immediately, falling ones
+        // only once they drop by at least the threshold
+        let hysteresis = tuning.hysteresis_c.unwrap_or(0.0);
+        let anchor = match self.anchor {
+            Some(anchor) if smoothed < anchor && anchor - smoothed < hysteresis => anchor,
+            _ => smoothed,
+        };
+        self.anchor = Some(anchor);
+
+        let target = curve.interpolate(anchor) as f32;
+
+        // Ramp limits
+        let output = match self.output {
+            Some(prev) => {
+                let delta = target - prev;
+                let limit = if delta > 0.0 {
+                    tuning.ramp_up_pwm_per_sec
+                } else {
+                    tuning.ramp_down_pwm_per_sec
+                };
+                match limit {
+                    Some(rate) if rate > 0.0 => {
+                        let max_step = rate * elapsed_secs;
+                        prev + delta.clamp(-max_step, max_step)
+                    }
+                    _ => target,
+                }
+            }
+            None => target,
+        };
+        self.output = Some(output);
+
+        output.round().clamp(0.0, 100.0) as u8
+    }
+
+    /// Smoothed input temperature from the last update.
+    pub fn smoothed_temperature(&self) -> Option<f32> {
+        self.smoothed
+    }
+}
+
 /// Thermal curve data stored in thermal_curves.toml
 ///
 /// Maps curve names to their definitions.
@@ -242,6 +423,18 @@ impl ThermalCurveData {
         self.curves.keys()
     }
 
+    /// Validate every curve.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut names: Vec<_> = self.curves.keys().collect();
+        names.sort_unstable();
+        for name in names {
+            self.curves[name]
+                .validate()
+                .map_err(|e| format!("curve '{}': {}", name, e))?;
+        }
+        Ok(())
+    }
+
     /// Parse ThermalCurveData from TOML string.
     pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
         toml::from_str(content)
@@ -588,4 +781,194 @@ mod tests {
     fn test_parse_points_too_few() {
         assert!(parse_points("30:25").is_err());
     }
+
+    fn linear_curve(tuning: CurveTuning) -> ThermalCurve {
+        // 1% PWM per degree between 0C and 100C
+        ThermalCurve::new(
+            "linear",
+            vec![CurvePoint::new(0.0, 0), CurvePoint::new(100.0, 100)],
+        )
+        .with_tuning(tuning)
+    }
+
+    #[test]
+    fn test_curve_tuning_validate() {
+        assert!(CurveTuning::default().validate().is_ok());
+
+        let valid = CurveTuning {
+            hysteresis_c: Some(3.0),
+            ramp_up_pwm_per_sec: Some(10.0),
+            ramp_down_pwm_per_sec: Some(0.0),
+            smoothing_secs: Some(5.0),
+        };
+        assert!(valid.validate().is_ok());
+
+        let invalid = [
+            CurveTuning {
+                hysteresis_c: Some(-1.0),
+                ..Default::default()
+            },
+            CurveTuning {
+                hysteresis_c: Some(MAX_HYSTERESIS_C + 1.0),
+                ..Default::default()
+            },
+            CurveTuning {
+                ramp_up_pwm_per_sec: Some(-5.0),
+                ..Default::default()
+            },
+            CurveTuning {
+                ramp_down_pwm_per_sec: Some(f32::NAN),
+                ..Default::default()
+            },
+            CurveTuning {
+                smoothing_secs: Some(MAX_SMOOTHING_SECS + 1.0),
+                ..Default::default()
+            },
+        ];
+        for tuning in invalid {
+            assert!(tuning.validate().is_err(), "{:?} should be invalid", tuning);
+            assert!(linear_curve(tuning).validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_curve_tuning_merged_with() {
+        let base = CurveTuning {
+            hysteresis_c: Some(2.0),
+            smoothing_secs: Some(10.0),
+            ..Default::default()
+        };
+        let update = CurveTuning {
+            hysteresis_c: Some(0.0),
+            ramp_up_pwm_per_sec: Some(5.0),
+            ..Default::default()
+        };
+        let merged = base.merged_with(&update);
+        assert_eq!(merged.hysteresis_c, Some(0.0));
+        assert_eq!(merged.ramp_up_pwm_per_sec, Some(5.0));
+        assert_eq!(merged.ramp_down_pwm_per_sec, None);
+        assert_eq!(merged.smoothing_secs, Some(10.0));
+
+        assert!(merged.is_active());
+        assert!(!CurveTuning::default().is_active());
+    }
+
+    #[test]
+    fn test_curve_tuning_toml_roundtrip() {
+        let mut data = ThermalCurveData::default();
+        data.insert(
+            "tuned".to_string(),
+            linear_curve(CurveTuning {
+                hysteresis_c: Some(2.5),
+                ramp_up_pwm_per_sec: Some(10.0),
+                ramp_down_pwm_per_sec: Some(2.0),
+                smoothing_secs: Some(8.0),
+            }),
+        );
+        data.insert("plain".to_string(), linear_curve(CurveTuning::default()));
+
+        let toml_str = data.to_toml().unwrap();
+        assert!(toml_str.contains("hysteresis_c = 2.5"));
+        let parsed = ThermalCurveData::from_toml(&toml_str).unwrap();
+        assert_eq!(
+            parsed.get("tuned").unwrap().tuning,
+            data.get("tuned").unwrap().tuning
+        );
+        assert_eq!(parsed.get("plain").unwrap().tuning, CurveTuning::default());
+    }
+
+    #[test]
+    fn test_curve_tuning_from_toml_integers() {
+        let toml_str = r#"
+[curves.tuned]
+name = "tuned"
+points = [{ temp_c = 30.0, pwm = 20 }, { temp_c = 80.0, pwm = 100 }]
+hysteresis_c = 3
+ramp_up_pwm_per_sec = 10
+"#;
+        let data = ThermalCurveData::from_toml(toml_str).unwrap();
+        let tuning = data.get("tuned").unwrap().tuning;
+        assert_eq!(tuning.hysteresis_c, Some(3.0));
+        assert_eq!(tuning.ramp_up_pwm_per_sec, Some(10.0));
+        assert_eq!(tuning.smoothing_secs, None);
+    }
+
+    #[test]
+    fn test_curve_state_untuned_matches_interpolate() {
+        let curve = ThermalCurve::new(
+            "test",
+            vec![CurvePoint::new(30.0, 25), CurvePoint::new(70.0, 80)],
+        );
+        let mut state = CurveState::new();
+        for temp in [20.0, 45.0, 70.0, 50.0, 31.0] {
+            assert_eq!(state.update(&curve, temp, 2.0), curve.interpolate(temp));
+        }
+    }
+
+    #[test]
+    fn test_curve_state_hysteresis() {
+        let curve = linear_curve(CurveTuning {
+            hysteresis_c: Some(3.0),
+            ..Default::default()
+        });
+        let mut state = CurveState::new();
+
+        assert_eq!(state.update(&curve, 50.0, 1.0), 50);
+        // Rising is followed immediately
+        assert_eq!(state.update(&curve, 52.0, 1.0), 52);
+        // Small drops are held
+        assert_eq!(state.update(&curve, 50.0, 1.0), 52);
+        assert_eq!(state.update(&curve, 49.5, 1.0), 52);
+        // Dropping by the full threshold is followed
+        assert_eq!(state.update(&curve, 49.0, 1.0), 49);
+        assert_eq!(state.update(&curve, 47.0, 1.0), 49);
+    }
+
+    #[test]
+    fn test_curve_state_ramp_limits() {
+        let curve = linear_curve(CurveTuning {
+            ramp_up_pwm_per_sec: Some(5.0),
+            ramp_down_pwm_per_sec: Some(1.0),
+            ..Default::default()
+        });
+        let mut state = CurveState::new();
+
+        // First sample is applied directly
+        assert_eq!(state.update(&curve, 20.0, 0.0), 20);
+        // Up at most 5%/s
+        assert_eq!(state.update(&curve, 80.0, 2.0), 30);
+        assert_eq!(state.update(&curve, 80.0, 2.0), 40);
+        // Down at most 1%/s
+        assert_eq!(state.update(&curve, 0.0, 2.0), 38);
+        // Fractional steps accumulate (37.75, 37.5, 37.25)
+        assert_eq!(state.update(&curve, 0.0, 0.25), 38);
+        assert_eq!(state.update(&curve, 0.0, 0.25), 38);
+        assert_eq!(state.update(&curve, 0.0, 0.25), 37);
+        // Targets within the limit are reached
+        assert_eq!(state.update(&curve, 36.0, 10.0), 36);
+    }
+
+    #[test]
+    fn test_curve_state_smoothing() {
+        let curve = linear_curve(CurveTuning {
+            smoothing_secs: Some(10.0),
+            ..Default::default()
+        });
+        let mut state = CurveState::new();
+
+        assert_eq!(state.update(&curve, 40.0, 0.0), 40);
+        assert_eq!(state.smoothed_temperature(), Some(40.0));
+
+        // A spike only moves the average part of the way
+        let pwm = state.update(&curve, 80.0, 2.0);
+        let expected = 40.0 + (1.0 - (-0.2f32).exp()) * 40.0;
+        assert!((state.smoothed_temperature().unwrap() - expected).abs() < 0.01);
+        assert_eq!(pwm, expected.round() as u8);
+
+        // Converges given enough time
+        for _ in 0..50 {
+            state.update(&curve, 80.0, 2.0);
+        }
+        assert_eq!(state.update(&curve, 80.0, 2.0), 80);
+    }
 }
@@ -6,8 +6,10 @@
 //! Each port can belong to at most one zone (exclusive membership).
 //! Zones are global and can span multiple controllers.
 
+use crate::BoardInfo;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 
 /// A fan reference within a zone, identifying both controller and fan.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -83,6 +85,52 @@ impl Zone {
     }
 }
 
+/// What is wrong with a zone fan reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZoneFanProblemKind {
+    /// The zone references a controller that is not configured
+    UnknownController,
+    /// The fan ID does not exist on the controller's board
+    FanOutOfRange {
+        /// Number of fans on the controller's board
+        fan_count: usize,
+    },
+}
+
+/// A zone fan that does not match the configured controllers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneFanProblem {
+    /// Zone containing the fan
+    pub zone: String,
+    /// Controller ID referenced by the fan
+    pub controller: String,
+    /// Fan ID referenced by the fan
+    pub fan_id: u8,
+    /// What is wrong with it
+    pub kind: ZoneFanProblemKind,
+}
+
+impl fmt::Display for ZoneFanProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ZoneFanProblemKind::UnknownController => write!(
+                f,
+                "zone '{}': fan {} references unknown controller '{}'",
+                self.zone, self.fan_id, self.controller
+            ),
+            ZoneFanProblemKind::FanOutOfRange { fan_count } => write!(
+                f,
+                "zone '{}': fan {} is out of range for controller '{}' ({} fans, max ID: {})",
+                self.zone,
+                self.fan_id,
+                self.controller,
+                fan_count,
+                fan_count.saturating_sub(1)
+            ),
+        }
+    }
+}
+
 /// Zone data stored in zones.toml
 ///
 /// Maps zone names to their definitions.
@@ -146,6 +194,37 @@ impl ZoneData {
             .collect()
     }
 
+    /// Check every zone fan against its own controller's board.
+    ///
+    /// `boards` maps controller IDs to their board info. Returns all problems
+    /// found, ordered by zone name and then by position in the zone.
+    pub fn validate_fans(&self, boards: &HashMap<String, BoardInfo>) -> Vec<ZoneFanProblem> {
+        let mut names: Vec<&String> = self.zones.keys().collect();
+        names.sort();
+
+        let mut problems = Vec::new();
+        for name in names {
+            for fan in &self.zones[name].fans {
+                let kind = match boards.get(&fan.controller) {
+                    None => ZoneFanProblemKind::UnknownController,
+                    Some(board) if fan.fan_id as usize >= board.fan_count => {
+                        ZoneFanProblemKind::FanOutOfRange {
+                            fan_count: board.fan_count,
+                        }
+                    }
+                    Some(_) => continue,
+                };
+                problems.push(ZoneFanProblem {
+                    zone: name.clone(),
+                    controller: fan.controller.clone(),
+                    fan_id: fan.fan_id,
+                    kind,
+                });
+            }
+        }
+        problems
+    }
+
     /// Parse ZoneData from TOML string.
     pub fn from_toml(content: &str) -> Result<Self, toml::de::Error> {
         toml::from_str(content)
@@ -167,6 +246,75 @@ mod tests {
         assert!(data.zones.is_empty());
     }
 
+    #[test]
+    fn test_validate_fans_per_controller() {
+        use crate::BoardType;
+
+        let mut data = ZoneData::default();
+        data.insert(
+            "intake".to_string(),
+            Zone::new(
+                "intake",
+                vec![
+                    ZoneFan::new("main", 9),
+                    ZoneFan::new("gpu", 3),
+                    ZoneFan::new("gpu", 4),
+                ],
+            ),
+        );
+        data.insert(
+            "exhaust".to_string(),
+            Zone::new("exhaust", vec![ZoneFan::new("ghost", 0)]),
+        );
+
+        let boards = HashMap::from([
+            (
+                "main".to_string(),
+                BoardType::OpenFanStandard.to_board_info(),
+            ),
+            (
+                "gpu".to_string(),
+                BoardType::Custom { fan_count: 4 }.to_board_info(),
+            ),
+        ]);
+
+        let problems = data.validate_fans(&boards);
+        assert_eq!(
+            problems,
+            vec![
+                ZoneFanProblem {
+                    zone: "exhaust".to_string(),
+                    controller: "ghost".to_string(),
+                    fan_id: 0,
+                    kind: ZoneFanProblemKind::UnknownController,
+                },
+                ZoneFanProblem {
+                    zone: "intake".to_string(),
+                    controller: "gpu".to_string(),
+                    fan_id: 4,
+                    kind: ZoneFanProblemKind::FanOutOfRange { fan_count: 4 },
+                },
+            ]
+        );
+        assert_eq!(
+            problems[1].to_string(),
+            "zone 'intake': fan 4 is out of range for controller 'gpu' (4 fans, max ID: 3)"
+        );
+        assert!(
+            problems[0]
+                .to_string()
+                .contains("unknown controller 'ghost'")
+        );
+
+        // Only valid fans left
+        data.remove("exhaust");
+        data.insert(
+            "intake".to_string(),
+            Zone::new("intake", vec![ZoneFan::new("gpu", 3)]),
+        );
+        assert!(data.validate_fans(&boards).is_empty());
+    }
+
     #[test]
     fn test_zone_fan_creation() {
         let fan = ZoneFan::new("main", 0);
@@ -37,6 +37,26 @@ pub enum OpenFanError {
     #[error("Thermal curve not found: {0}")]
     CurveNotFound(String),
 
+    /// PID controller not found
+    #[error("PID controller not found: {0}")]
+    PidNotFound(String),
+
+    /// Curve binding not found
+    #[error("Curve binding not found: {0}")]
+    BindingNotFound(String),
+
+    /// Temperature sensor not found
+    #[error("Sensor not found: {0}")]
+    SensorNotFound(String),
+
+    /// Temperature sensor read failure
+    #[error("Sensor error: {0}")]
+    Sensor(String),
+
+    /// Zones reference fans that do not exist on their controllers
+    #[error("Invalid zone configuration:{}", format_zone_problems(.0))]
+    InvalidZones(Vec<crate::ZoneFanProblem>),
+
     /// CFM mapping not found
     #[error("CFM mapping not found for port {0}")]
     CfmMappingNotFound(u8),
@@ -89,6 +109,14 @@ pub enum OpenFanError {
     #[error("Duplicate controller ID: {0}")]
     DuplicateControllerId(String),
 
+    /// A calibration job is already running on the controller
+    #[error("Calibration already running on controller: {0}")]
+    CalibrationRunning(String),
+
+    /// A fan value is outside the fan's safety limits
+    #[error("Fan {fan_id} limit exceeded: {reason}")]
+    FanLimitExceeded { fan_id: u8, reason: String },
+
     /// Generic error
     #[error("{0}")]
     Other(String),
@@ -97,6 +125,10 @@ pub enum OpenFanError {
 /// Result type alias for OpenFAN operations
 pub type Result<T> = std::result::Result<T, OpenFanError>;
 
+fn format_zone_problems(problems: &[crate::ZoneFanProblem]) -> String {
+    problems.iter().map(|p| format!("\n  - {}", p)).collect()
+}
+
 impl From<serde_json::Error> for OpenFanError {
     fn from(err: serde_json::Error) -> Self {
         OpenFanError::Serialization(err.to_string())
@@ -107,6 +139,20 @@ impl From<serde_json::Error> for OpenFanError {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_invalid_zones_lists_every_problem() {
+        let problem = |zone: &str, controller: &str, fan_id| crate::ZoneFanProblem {
+            zone: zone.to_string(),
+            controller: controller.to_string(),
+            fan_id,
+            kind: crate::ZoneFanProblemKind::UnknownController,
+        };
+        let err = OpenFanError::InvalidZones(vec![problem("a", "x", 0), problem("b", "y", 1)]);
+        let message = err.to_string();
+        assert!(message.starts_with("Invalid zone configuration:\n  - zone 'a'"));
+        assert!(message.contains("\n  - zone 'b': fan 1 references unknown controller 'y'"));
+    }
+
     #[test]
     fn test_serde_json_error_conversion() {
         // Create a serde_json error by trying to parse invalid JSON
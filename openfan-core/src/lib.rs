@@ -7,9 +7,15 @@ pub mod types;
 // Re-export commonly used types
 pub use board::*;
 pub use config::{
-    AliasData, ControllerConfig, CurvePoint, DEFAULT_SAFE_BOOT_PROFILE, ProfileData, ProfileName,
-    ReconnectConfig, ShutdownConfig, StaticConfig, ThermalCurve, ThermalCurveData, Zone, ZoneData,
-    ZoneFan, default_config_path, default_data_dir, parse_points,
+    AggregateFunction, AggregateInput, AirflowDirection, AlarmsConfig, AliasData, ApiTokenConfig,
+    AuthConfig, CalibrationPoint, CfmModel, CfmPoint, CfmSource, ControllerConfig, CurveBinding,
+    CurveBindingData, CurvePoint, CurveState, CurveTuning, CustomSensorConfig,
+    DEFAULT_SAFE_BOOT_PROFILE, FanCalibration, FanCalibrationData, FanLimits, FanLimitsConfig,
+    FanLimitsData, FanStateData, HistoryConfig, LimitEnforcement, PidController, PidControllerData,
+    PidState, ProfileData, ProfileName, ReconnectConfig, ReloadConfig, SensorSource, SensorsConfig,
+    ShutdownConfig, StartupConfig, StaticConfig, TelemetryConfig, ThermalCurve, ThermalCurveData,
+    TokenScope, Zone, ZoneData, ZoneFan, ZoneFanProblem, ZoneFanProblemKind, default_config_path,
+    default_data_dir, parse_cfm_points, parse_points,
 };
 pub use error::*;
 pub use types::*;
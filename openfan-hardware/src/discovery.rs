@@ -0,0 +1,314 @@
+//! Serial port discovery of OpenFAN boards
+//!
+//! Candidate ports come from, in order:
+//! 1. the `OPENFAN_COMPORT` environment variable
+//! 2. USB serial ports whose VID/PID belongs to a known OpenFAN board
+//! 3. common device paths, only when no USB port matched
+//!
+//! A candidate is only known to be a board once [`probe`] got a firmware
+//! answer from it.
+
+use crate::fan_controller::FanController;
+use crate::serial_driver::SerialDriver;
+use openfan_core::{DefaultBoard, OpenFanError, Result};
+use std::path::Path;
+use tokio_serial::{SerialPortInfo, SerialPortType, UsbPortInfo};
+use tracing::{debug, warn};
+
+/// Environment variable naming the serial port of the board
+pub const COMPORT_ENV: &str = "OPENFAN_COMPORT";
+
+/// USB vendor and product IDs of known OpenFAN boards
+pub const OPENFAN_USB_IDS: &[(u16, u16)] = &[
+    // OpenFAN Standard (RP2040 USB CDC)
+    (0x2E8A, 0x000A),
+];
+
+/// Device paths tried when no USB port matched a known VID/PID
+#[cfg(target_os = "linux")]
+pub const COMMON_DEVICE_PATHS: &[&str] = &["/dev/ttyACM0", "/dev/ttyACM1"];
+
+/// Device paths tried when no USB port matched a known VID/PID
+#[cfg(not(target_os = "linux"))]
+pub const COMMON_DEVICE_PATHS: &[&str] = &[];
+
+/// Why a port is a discovery candidate
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandidateSource {
+    /// Named by `OPENFAN_COMPORT`
+    Env,
+    /// USB VID/PID of a known OpenFAN board
+    UsbId,
+    /// Common device path
+    CommonPath,
+}
+
+/// Serial port that may be an OpenFAN board
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortCandidate {
+    /// Device path (e.g. `/dev/ttyACM0`)
+    pub port: String,
+    /// Why the port is a candidate
+    pub source: CandidateSource,
+    /// USB vendor ID, for USB ports
+    pub vid: Option<u16>,
+    /// USB product ID, for USB ports
+    pub pid: Option<u16>,
+    /// USB serial number, if the device reports one
+    pub serial_number: Option<String>,
+    /// USB product string, if the device reports one
+    pub product: Option<String>,
+}
+
+/// Whether a USB VID/PID belongs to a known OpenFAN board.
+pub fn is_openfan_usb_id(vid: u16, pid: u16) -> bool {
+    OPENFAN_USB_IDS.contains(&(vid, pid))
+}
+
+/// List the serial ports that may be OpenFAN boards.
+///
+/// Ports are not opened; use [`probe`] to check them.
+pub fn find_candidates() -> Vec<PortCandidate> {
+    let env = std::env::var(COMPORT_ENV).ok();
+    collect_candidates(
+        env.as_deref(),
+        &available_ports(),
+        COMMON_DEVICE_PATHS,
+        |path| Path::new(path).exists(),
+    )
+}
+
+/// Find the serial port of the USB device with the given serial number.
+///
+/// Used to follow a board across replugs, when its device node may change.
+pub fn find_port_by_serial_number(serial_number: &str) -> Option<String> {
+    port_by_serial_number(&available_ports(), serial_number)
+}
+
+/// USB serial number of the device behind a port, if it reports one.
+///
+/// `port` must be the device node as enumerated (e.g. `/dev/ttyACM0`), not a
+/// symlink to it.
+pub fn serial_number_of(port: &str) -> Option<String> {
+    serial_number_at(&available_ports(), port)
+}
+
+/// Check that a port answers like an OpenFAN board.
+///
+/// Returns the firmware info line.
+///
+/// # Errors
+///
+/// Returns an error if the port cannot be opened, does not answer in time or
+/// does not answer with firmware info.
+pub async fn probe(port: &str, timeout_ms: u64) -> Result<String> {
+    debug!("Probing {}", port);
+    let driver = SerialDriver::<DefaultBoard>::new(port, timeout_ms, false)?;
+    let mut controller = FanController::new(driver);
+    let response = controller.get_fw_info().await?;
+    if !response.starts_with("<FW|") {
+        return Err(OpenFanError::Hardware(format!(
+            "Unexpected firmware response: {}",
+            response
+        )));
+    }
+    Ok(response)
+}
+
+fn available_ports() -> Vec<SerialPortInfo> {
+    // serialport panics when sysfs has no tty class (e.g. in some containers)
+    #[cfg(target_os = "linux")]
+    if !Path::new("/sys/class/tty").is_dir() {
+        return Vec::new();
+    }
+
+    tokio_serial::available_ports().unwrap_or_else(|e| {
+        warn!("Failed to enumerate serial ports: {}", e);
+        Vec::new()
+    })
+}
+
+fn usb_info(info: &SerialPortInfo) -> Option<&UsbPortInfo> {
+    match &info.port_type {
+        SerialPortType::UsbPort(usb) => Some(usb),
+        _ => None,
+    }
+}
+
+fn port_by_serial_number(ports: &[SerialPortInfo], serial_number: &str) -> Option<String> {
+    ports
+        .iter()
+        .find(|info| {
+            usb_info(info).and_then(|usb| usb.serial_number.as_deref()) == Some(serial_number)
+        })
+        .map(|info| info.port_name.clone())
+}
+
+fn serial_number_at(ports: &[SerialPortInfo], port: &str) -> Option<String> {
+    ports
+        .iter()
+        .find(|info| info.port_name == port)
+        .and_then(usb_info)
+        .and_then(|usb| usb.serial_number.clone())
+}
+
+fn collect_candidates(
+    env: Option<&str>,
+    ports: &[SerialPortInfo],
+    common_paths: &[&str],
+    exists: impl Fn(&str) -> bool,
+) -> Vec<PortCandidate> {
+    let mut candidates: Vec<PortCandidate> = Vec::new();
+    let mut add = |port: &str, source: CandidateSource| {
+        if candidates.iter().any(|c| c.port == port) {
+            return;
+        }
+        let usb = ports
+            .iter()
+            .find(|info| info.port_name == port)
+            .and_then(usb_info);
+        candidates.push(PortCandidate {
+            port: port.to_string(),
+            source,
+            vid: usb.map(|usb| usb.vid),
+            pid: usb.map(|usb| usb.pid),
+            serial_number: usb.and_then(|usb| usb.serial_number.clone()),
+            product: usb.and_then(|usb| usb.product.clone()),
+        });
+    };
+
+    if let Some(port) = env.map(str::trim).filter(|port| !port.is_empty()) {
+        add(port, CandidateSource::Env);
+    }
+
+    let mut usb_match = false;
+    for info in ports {
+        if let Some(usb) = usb_info(info)
+            && is_openfan_usb_id(usb.vid, usb.pid)
+        {
+            usb_match = true;
+            add(&info.port_name, CandidateSource::UsbId);
+        }
+    }
+
+    if !usb_match {
+        for path in common_paths.iter().filter(|path| exists(path)) {
+            add(path, CandidateSource::CommonPath);
+        }
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usb_port(name: &str, vid: u16, pid: u16, serial: &str) -> SerialPortInfo {
+        SerialPortInfo {
+            port_name: name.to_string(),
+            port_type: SerialPortType::UsbPort(UsbPortInfo {
+                vid,
+                pid,
+                serial_number: Some(serial.to_string()),
+                manufacturer: None,
+                product: Some("OpenFAN".to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_usb_id_matches() {
+        let ports = vec![
+            usb_port("/dev/ttyACM0", 0x2E8A, 0x000A, "E660A1"),
+            usb_port("/dev/ttyUSB0", 0x0403, 0x6001, "FTDI1"),
+            SerialPortInfo {
+                port_name: "/dev/ttyS0".to_string(),
+                port_type: SerialPortType::PciPort,
+            },
+        ];
+
+        let candidates = collect_candidates(None, &ports, &["/dev/ttyACM1"], |_| true);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].port, "/dev/ttyACM0");
+        assert_eq!(candidates[0].source, CandidateSource::UsbId);
+        assert_eq!(candidates[0].vid, Some(0x2E8A));
+        assert_eq!(candidates[0].serial_number.as_deref(), Some("E660A1"));
+    }
+
+    #[test]
+    fn test_env_comes_first_without_duplicates() {
+        let ports = vec![usb_port("/dev/ttyACM0", 0x2E8A, 0x000A, "E660A1")];
+
+        let candidates = collect_candidates(Some(" /dev/ttyACM0 "), &ports, &[], |_| true);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].source, CandidateSource::Env);
+        assert_eq!(candidates[0].pid, Some(0x000A));
+
+        let candidates = collect_candidates(Some("/dev/ttyUSB3"), &ports, &[], |_| true);
+        let sources: Vec<_> = candidates.iter().map(|c| c.source).collect();
+        assert_eq!(sources, [CandidateSource::Env, CandidateSource::UsbId]);
+        assert_eq!(candidates[0].vid, None);
+    }
+
+    #[test]
+    fn test_common_paths_only_without_usb_match() {
+        let common = ["/dev/ttyACM0", "/dev/ttyACM1"];
+        let exists = |path: &str| path == "/dev/ttyACM1";
+
+        let candidates = collect_candidates(Some(""), &[], &common, exists);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].port, "/dev/ttyACM1");
+        assert_eq!(candidates[0].source, CandidateSource::CommonPath);
+
+        let ports = vec![usb_port("/dev/ttyACM5", 0x2E8A, 0x000A, "E660A1")];
+        let candidates = collect_candidates(None, &ports, &common, exists);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].port, "/dev/ttyACM5");
+    }
+
+    #[test]
+    fn test_lookup_by_serial_number() {
+        let ports = vec![
+            usb_port("/dev/ttyACM0", 0x2E8A, 0x000A, "E660A1"),
+            usb_port("/dev/ttyACM1", 0x2E8A, 0x000A, "E660B2"),
+        ];
+
+        assert_eq!(
+            port_by_serial_number(&ports, "E660B2").as_deref(),
+            Some("/dev/ttyACM1")
+        );
+        assert_eq!(port_by_serial_number(&ports, "missing"), None);
+        assert_eq!(
+            serial_number_at(&ports, "/dev/ttyACM0").as_deref(),
+            Some("E660A1")
+        );
+        assert_eq!(serial_number_at(&ports, "/dev/ttyS0"), None);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_probe() {
+        use crate::{PtyEmulator, SimulatedDevice, SimulatorConfig};
+        use openfan_core::BoardConfig;
+
+        let dir = tempfile::tempdir().unwrap();
+        let emulator = PtyEmulator::start(
+            SimulatedDevice::new("probe", SimulatorConfig::new(10)),
+            dir.path().join("tty"),
+            DefaultBoard::BAUD_RATE,
+        )
+        .unwrap();
+        let port = emulator.path().to_str().unwrap();
+
+        assert!(probe(port, 500).await.unwrap().starts_with("<FW|"));
+
+        emulator.device().inject_timeouts(1);
+        assert!(matches!(
+            probe(port, 200).await,
+            Err(OpenFanError::Timeout(_))
+        ));
+
+        assert!(probe("/dev/nonexistent_device_12345", 200).await.is_err());
+    }
+}
@@ -0,0 +1,430 @@
+//! Firmware emulator on a pseudo-terminal
+//!
+//! [`PtyEmulator`] serves a [`SimulatedDevice`] over a pseudo-terminal, so
+//! anything that opens serial ports (`SerialDriver`, `openfand --device`) can
+//! talk to it as it would to a board plugged in over USB. The terminal is set
+//! to raw mode at the board's baud rate and speaks the firmware's `>XX` command
+//! / `<` response line protocol.
+//!
+//! Clients open a stable path: a symlink to the current PTY. Disconnecting
+//! the device hangs up the terminal, so clients get the same I/O errors as
+//! when a board is unplugged. Once the device is reconnected, a new terminal
+//! is opened behind the same symlink, much like a board re-enumerating.
+
+use crate::serial_driver::SerialTransport;
+use crate::simulator::SimulatedDevice;
+use openfan_core::{OpenFanError, Result};
+use std::ffi::CStr;
+use std::fs::OpenOptions;
+use std::io;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::unix::AsyncFd;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// How often the emulator checks whether the device was disconnected or reconnected
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// A [`SimulatedDevice`] exposed as a serial port
+///
+/// The emulator runs on a background task until it is dropped, which also
+/// removes the symlink.
+#[derive(Debug)]
+pub struct PtyEmulator {
+    device: SimulatedDevice,
+    link: PathBuf,
+    task: JoinHandle<()>,
+}
+
+impl PtyEmulator {
+    /// Start serving `device` on a new pseudo-terminal linked at `link`.
+    ///
+    /// The terminal is ready when this returns. Must be called from within a
+    /// Tokio runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the baud rate is not supported, if the terminal
+    /// cannot be created or if the symlink cannot be written.
+    pub fn start(
+        device: SimulatedDevice,
+        link: impl Into<PathBuf>,
+        baud_rate: u32,
+    ) -> Result<Self> {
+        let link = link.into();
+        let pty = Pty::open(baud_rate)?;
+        update_link(&link, &pty.slave_path)?;
+        info!(
+            "Emulating '{}' on {} (linked at {})",
+            device.name(),
+            pty.slave_path.display(),
+            link.display()
+        );
+
+        let task = tokio::spawn(run(device.clone(), link.clone(), baud_rate, pty));
+        Ok(Self { device, link, task })
+    }
+
+    /// Path clients should open, e.g. with `openfand --device`.
+    pub fn path(&self) -> &Path {
+        &self.link
+    }
+
+    /// The emulated device, for fault injection.
+    pub fn device(&self) -> &SimulatedDevice {
+        &self.device
+    }
+}
+
+impl Drop for PtyEmulator {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.link);
+    }
+}
+
+/// Serve the device, opening a new terminal after each disconnect.
+async fn run(device: SimulatedDevice, link: PathBuf, baud_rate: u32, mut pty: Pty) {
+    loop {
+        if let Err(e) = serve(&pty, &device).await {
+            warn!("Emulator for '{}' failed: {}", device.name(), e);
+        }
+        // Dropping the terminal hangs it up
+        drop(pty);
+        info!("Device '{}' disconnected", device.name());
+
+        while device.is_disconnected() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        pty = loop {
+            match Pty::open(baud_rate).and_then(|pty| {
+                update_link(&link, &pty.slave_path)?;
+                Ok(pty)
+            }) {
+                Ok(pty) => break pty,
+                Err(e) => {
+                    warn!("Failed to reopen terminal for '{}': {}", device.name(), e);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        };
+        info!(
+            "Device '{}' reconnected on {}",
+            device.name(),
+            pty.slave_path.display()
+        );
+    }
+}
+
+/// Answer commands on the terminal until the device is disconnected.
+async fn serve(pty: &Pty, device: &SimulatedDevice) -> Result<()> {
+    let mut transport = device.open()?;
+    let mut pending = Vec::new();
+    let mut chunk = [0u8; 256];
+    let mut poll = tokio::time::interval(POLL_INTERVAL);
+
+    loop {
+        tokio::select! {
+            read = pty.read(&mut chunk) => {
+                let n = read.map_err(|e| OpenFanError::Serial(format!("Read failed: {}", e)))?;
+                pending.extend_from_slice(&chunk[..n]);
+            }
+            _ = poll.tick() => {
+                if !transport.is_connected() {
+                    return Ok(());
+                }
+                continue;
+            }
+        }
+
+        while let Some(end) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=end).collect();
+            let command = String::from_utf8_lossy(&line).trim().to_string();
+            if command.is_empty() {
+                continue;
+            }
+
+            match transport.transaction(&command).await {
+                Ok(lines) => {
+                    for line in lines {
+                        pty.write_all(format!("{}\r\n", line).as_bytes())
+                            .await
+                            .map_err(|e| OpenFanError::Serial(format!("Write failed: {}", e)))?;
+                    }
+                }
+                // A board that times out just stays silent
+                Err(OpenFanError::Timeout(_)) => debug!("Dropping command {:?}", command),
+                Err(_) => return Ok(()),
+            }
+        }
+    }
+}
+
+/// A pseudo-terminal pair
+///
+/// The slave side is kept open so the master does not report a hang-up while
+/// no client is connected.
+struct Pty {
+    master: AsyncFd<OwnedFd>,
+    _slave: OwnedFd,
+    slave_path: PathBuf,
+}
+
+impl Pty {
+    fn open(baud_rate: u32) -> Result<Self> {
+        let speed = baud_constant(baud_rate)
+            .ok_or_else(|| OpenFanError::Serial(format!("Unsupported baud rate: {}", baud_rate)))?;
+
+        // SAFETY: posix_openpt has no preconditions; the returned descriptor is
+        // checked and then owned by `master`.
+        let fd = unsafe { libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY | libc::O_NONBLOCK) };
+        if fd < 0 {
+            return Err(os_error("posix_openpt"));
+        }
+        // SAFETY: `fd` is a freshly opened descriptor that nothing else owns.
+        let master = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        // SAFETY: `master` is a valid PTY master descriptor.
+        if unsafe { libc::grantpt(master.as_raw_fd()) } != 0 {
+            return Err(os_error("grantpt"));
+        }
+        // SAFETY: as above.
+        if unsafe { libc::unlockpt(master.as_raw_fd()) } != 0 {
+            return Err(os_error("unlockpt"));
+        }
+
+        let slave_path = slave_path(&master)?;
+        let slave: OwnedFd = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .custom_flags(libc::O_NOCTTY)
+            .open(&slave_path)
+            .map_err(|e| {
+                OpenFanError::Serial(format!("Failed to open {}: {}", slave_path.display(), e))
+            })?
+            .into();
+        configure(&slave, speed)?;
+
+        let master = AsyncFd::new(master)
+            .map_err(|e| OpenFanError::Serial(format!("Failed to register terminal: {}", e)))?;
+
+        Ok(Self {
+            master,
+            _slave: slave,
+            slave_path,
+        })
+    }
+
+    async fn read(&self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let mut guard = self.master.readable().await?;
+            // SAFETY: `buf` is valid for writes of `buf.len()` bytes.
+            match guard.try_io(|fd| {
+                cvt(unsafe { libc::read(fd.as_raw_fd(), buf.as_mut_ptr().cast(), buf.len()) })
+            }) {
+                Ok(result) => return result,
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    async fn write_all(&self, mut buf: &[u8]) -> io::Result<()> {
+        while !buf.is_empty() {
+            let mut guard = self.master.writable().await?;
+            // SAFETY: `buf` is valid for reads of `buf.len()` bytes.
+            match guard.try_io(|fd| {
+                cvt(unsafe { libc::write(fd.as_raw_fd(), buf.as_ptr().cast(), buf.len()) })
+            }) {
+                Ok(Ok(n)) => buf = &buf[n..],
+                Ok(Err(e)) => return Err(e),
+                Err(_would_block) => continue,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Convert the return value of `read`/`write` to an `io::Result`.
+fn cvt(ret: isize) -> io::Result<usize> {
+    if ret < 0 {
+        Err(io::Error::last_os_error())
+    } else {
+        Ok(ret as usize)
+    }
+}
+
+fn os_error(call: &str) -> OpenFanError {
+    OpenFanError::Serial(format!("{} failed: {}", call, io::Error::last_os_error()))
+}
+
+/// Path of the slave side of a PTY master.
+#[cfg(target_os = "linux")]
+fn slave_path(master: &OwnedFd) -> Result<PathBuf> {
+    let mut buf = [0 as libc::c_char; 128];
+    // SAFETY: `buf` is valid for writes of `buf.len()` bytes.
+    if unsafe { libc::ptsname_r(master.as_raw_fd(), buf.as_mut_ptr(), buf.len()) } != 0 {
+        return Err(os_error("ptsname_r"));
+    }
+    // SAFETY: ptsname_r wrote a NUL-terminated string into `buf`.
+    let name = unsafe { CStr::from_ptr(buf.as_ptr()) };
+    Ok(PathBuf::from(name.to_string_lossy().into_owned()))
+}
+
+/// Path of the slave side of a PTY master.
+#[cfg(not(target_os = "linux"))]
+fn slave_path(master: &OwnedFd) -> Result<PathBuf> {
+    // SAFETY: `master` is a valid PTY master descriptor. The returned string
+    // is copied before any other call to ptsname.
+    let name = unsafe { libc::ptsname(master.as_raw_fd()) };
+    if name.is_null() {
+        return Err(os_error("ptsname"));
+    }
+    // SAFETY: ptsname returned a non-null, NUL-terminated string.
+    let name = unsafe { CStr::from_ptr(name) };
+    Ok(PathBuf::from(name.to_string_lossy().into_owned()))
+}
+
+/// Put the terminal in raw mode at the given speed.
+fn configure(slave: &OwnedFd, speed: libc::speed_t) -> Result<()> {
+    let fd = slave.as_raw_fd();
+    let mut termios = std::mem::MaybeUninit::<libc::termios>::uninit();
+    // SAFETY: `termios` is valid for writes and initialized on success.
+    if unsafe { libc::tcgetattr(fd, termios.as_mut_ptr()) } != 0 {
+        return Err(os_error("tcgetattr"));
+    }
+    // SAFETY: tcgetattr succeeded.
+    let mut termios = unsafe { termios.assume_init() };
+
+    // SAFETY: `termios` is a valid, initialized structure.
+    unsafe {
+        libc::cfmakeraw(&mut termios);
+        if libc::cfsetispeed(&mut termios, speed) != 0
+            || libc::cfsetospeed(&mut termios, speed) != 0
+        {
+            return Err(os_error("cfsetspeed"));
+        }
+        if libc::tcsetattr(fd, libc::TCSANOW, &termios) != 0 {
+            return Err(os_error("tcsetattr"));
+        }
+    }
+    Ok(())
+}
+
+/// termios speed constant for a baud rate.
+fn baud_constant(baud_rate: u32) -> Option<libc::speed_t> {
+    match baud_rate {
+        9600 => Some(libc::B9600),
+        19200 => Some(libc::B19200),
+        38400 => Some(libc::B38400),
+        57600 => Some(libc::B57600),
+        115200 => Some(libc::B115200),
+        230400 => Some(libc::B230400),
+        _ => None,
+    }
+}
+
+/// Point `link` at `target`, replacing any previous link atomically.
+fn update_link(link: &Path, target: &Path) -> Result<()> {
+    let mut staging = link.as_os_str().to_owned();
+    staging.push(".new");
+    let staging = PathBuf::from(staging);
+
+    let _ = std::fs::remove_file(&staging);
+    std::os::unix::fs::symlink(target, &staging)
+        .and_then(|()| std::fs::rename(&staging, link))
+        .map_err(|e| {
+            OpenFanError::Serial(format!(
+                "Failed to link {} to {}: {}",
+                link.display(),
+                target.display(),
+                e
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimulatorConfig;
+    use crate::fan_controller::FanController;
+    use crate::serial_driver::SerialDriver;
+    use openfan_core::{BoardConfig, DefaultBoard};
+
+    fn emulator(dir: &tempfile::TempDir) -> PtyEmulator {
+        let device = SimulatedDevice::new("pty", SimulatorConfig::new(10).with_noise(0));
+        PtyEmulator::start(device, dir.path().join("tty"), DefaultBoard::BAUD_RATE).unwrap()
+    }
+
+    fn connect(emulator: &PtyEmulator) -> FanController<SerialDriver<DefaultBoard>> {
+        let driver =
+            SerialDriver::<DefaultBoard>::new(emulator.path().to_str().unwrap(), 500, false)
+                .unwrap();
+        FanController::with_transport(
+            Box::new(driver),
+            DefaultBoard::FAN_COUNT,
+            DefaultBoard::MAX_PWM,
+        )
+    }
+
+    #[test]
+    fn test_baud_constant() {
+        assert_eq!(baud_constant(115200), Some(libc::B115200));
+        assert_eq!(baud_constant(12345), None);
+    }
+
+    #[tokio::test]
+    async fn test_serial_driver_over_pty() {
+        let dir = tempfile::tempdir().unwrap();
+        let emulator = emulator(&dir);
+        let mut controller = connect(&emulator);
+
+        assert!(controller.get_fw_info().await.unwrap().contains("sim"));
+        assert_eq!(controller.get_all_fan_rpm().await.unwrap().len(), 10);
+
+        controller.set_fan_pwm(3, 100).await.unwrap();
+        emulator.device().advance(Duration::from_secs(30));
+        let rpm = controller.get_single_fan_rpm(3).await.unwrap();
+        assert_eq!(rpm, emulator.device().rpm(3).unwrap());
+        assert!(rpm > 0);
+    }
+
+    #[tokio::test]
+    async fn test_injected_timeout_over_pty() {
+        let dir = tempfile::tempdir().unwrap();
+        let emulator = emulator(&dir);
+        let mut controller = connect(&emulator);
+
+        emulator.device().inject_timeouts(1);
+        assert!(matches!(
+            controller.get_fw_info().await,
+            Err(OpenFanError::Timeout(_))
+        ));
+        assert!(controller.get_fw_info().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_hangs_up_pty() {
+        let dir = tempfile::tempdir().unwrap();
+        let emulator = emulator(&dir);
+        let mut controller = connect(&emulator);
+        assert!(controller.get_fw_info().await.is_ok());
+
+        emulator.device().disconnect();
+        tokio::time::sleep(POLL_INTERVAL * 4).await;
+        let err = controller.get_fw_info().await.unwrap_err();
+        assert!(
+            crate::is_disconnect_error(&err),
+            "unexpected error: {}",
+            err
+        );
+
+        emulator.device().reconnect();
+        tokio::time::sleep(POLL_INTERVAL * 4).await;
+        let mut controller = connect(&emulator);
+        assert!(controller.get_fw_info().await.is_ok());
+    }
+}
@@ -3,10 +3,12 @@
 //! Implements the fan control protocol over serial communication.
 
 use crate::serial_driver::{SerialDriver, SerialTransport};
-use openfan_core::{BoardConfig, FanRpmMap, OpenFanError, Result};
+use openfan_core::{BoardConfig, BoardInfo, FanRpmMap, OpenFanError, Result};
 use std::collections::HashMap;
 use std::fmt::Write;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use tracing::{debug, error, warn};
 
@@ -54,6 +56,49 @@ pub enum Command {
     GetFwInfo = 0x06,
 }
 
+/// Counters of the serial transactions sent to a board
+///
+/// Shared through an `Arc` so they can outlive a controller, e.g. across
+/// reconnections (see [`FanController::set_transaction_stats`]).
+#[derive(Debug, Default)]
+pub struct TransactionStats {
+    transactions: AtomicU64,
+    errors: AtomicU64,
+    latency_micros: AtomicU64,
+}
+
+impl TransactionStats {
+    /// Create zeroed counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a transaction that took `latency`, and whether it failed
+    pub fn record(&self, latency: Duration, failed: bool) {
+        self.transactions.fetch_add(1, Ordering::Relaxed);
+        self.latency_micros
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+        if failed {
+            self.errors.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Number of transactions sent, failed or not
+    pub fn transactions(&self) -> u64 {
+        self.transactions.load(Ordering::Relaxed)
+    }
+
+    /// Number of transactions that failed or got no valid response
+    pub fn errors(&self) -> u64 {
+        self.errors.load(Ordering::Relaxed)
+    }
+
+    /// Time spent in all transactions
+    pub fn total_latency(&self) -> Duration {
+        Duration::from_micros(self.latency_micros.load(Ordering::Relaxed))
+    }
+}
+
 /// Fan controller interface
 ///
 /// Generic over the transport type, allowing real hardware (`SerialDriver`)
@@ -62,8 +107,12 @@ pub struct FanController<T: SerialTransport + ?Sized = dyn SerialTransport> {
     driver: Arc<Mutex<Box<T>>>,
     fan_count: usize,
     max_pwm: u32,
+    min_target_rpm: u32,
+    max_target_rpm: u32,
     fan_rpm_cache: HashMap<u8, u32>,
     fan_pwm_cache: HashMap<u8, u32>,
+    fan_rpm_target_cache: HashMap<u8, u32>,
+    stats: Arc<TransactionStats>,
 }
 
 impl<B: BoardConfig + Send + Sync> FanController<SerialDriver<B>> {
@@ -73,8 +122,12 @@ impl<B: BoardConfig + Send + Sync> FanController<SerialDriver<B>> {
             driver: Arc::new(Mutex::new(Box::new(driver))),
             fan_count: B::FAN_COUNT,
             max_pwm: B::MAX_PWM,
+            min_target_rpm: B::MIN_TARGET_RPM,
+            max_target_rpm: B::MAX_TARGET_RPM,
             fan_rpm_cache: HashMap::new(),
             fan_pwm_cache: HashMap::new(),
+            fan_rpm_target_cache: HashMap::new(),
+            stats: Arc::new(TransactionStats::new()),
         }
     }
 }
@@ -82,17 +135,58 @@ impl<B: BoardConfig + Send + Sync> FanController<SerialDriver<B>> {
 impl<T: SerialTransport + ?Sized> FanController<T> {
     /// Create a new FanController with a boxed transport
     ///
-    /// This is primarily useful for testing with mock transports.
+    /// This is primarily useful for testing with mock transports. Target RPMs
+    /// are only checked against the 16-bit range of the protocol.
     pub fn with_transport(transport: Box<T>, fan_count: usize, max_pwm: u32) -> Self {
         Self {
             driver: Arc::new(Mutex::new(transport)),
             fan_count,
             max_pwm,
+            min_target_rpm: 0,
+            max_target_rpm: u16::MAX as u32,
+            fan_rpm_cache: HashMap::new(),
+            fan_pwm_cache: HashMap::new(),
+            fan_rpm_target_cache: HashMap::new(),
+            stats: Arc::new(TransactionStats::new()),
+        }
+    }
+
+    /// Create a new FanController for a board described at runtime
+    ///
+    /// Fan IDs, PWM and target RPMs are checked against `board`, so boards
+    /// without a `BoardConfig` (e.g. `BoardType::Custom`) get their own limits.
+    pub fn with_board_info(transport: Box<T>, board: &BoardInfo) -> Self {
+        Self {
+            driver: Arc::new(Mutex::new(transport)),
+            fan_count: board.fan_count,
+            max_pwm: board.max_pwm,
+            min_target_rpm: board.min_target_rpm,
+            max_target_rpm: board.max_target_rpm,
             fan_rpm_cache: HashMap::new(),
             fan_pwm_cache: HashMap::new(),
+            fan_rpm_target_cache: HashMap::new(),
+            stats: Arc::new(TransactionStats::new()),
         }
     }
 
+    /// Number of fan ports on the board
+    pub fn fan_count(&self) -> usize {
+        self.fan_count
+    }
+
+    /// Counters of the transactions sent by this controller
+    pub fn transaction_stats(&self) -> Arc<TransactionStats> {
+        self.stats.clone()
+    }
+
+    /// Record transactions into `stats` from now on
+    ///
+    /// Lets a new controller for the same board carry on the counters of the
+    /// one it replaces.
+    pub fn set_transaction_stats(&mut self, stats: Arc<TransactionStats>) {
+        self.stats = stats;
+    }
+
     /// Validate a fan ID against this board's fan count
     fn validate_fan_id(&self, fan_id: u8) -> Result<()> {
         if fan_id as usize >= self.fan_count {
@@ -120,8 +214,13 @@ impl<T: SerialTransport + ?Sized> FanController<T> {
 
         debug!("Sending command: {:?} with payload: {}", cmd, payload);
 
-        let response = driver.transaction(&payload).await?;
-        self.parse_response(response)
+        let start = Instant::now();
+        let result = driver
+            .transaction(&payload)
+            .await
+            .and_then(|response| self.parse_response(response));
+        self.stats.record(start.elapsed(), result.is_err());
+        result
     }
 
     /// Parse the response from hardware
@@ -198,6 +297,14 @@ impl<T: SerialTransport + ?Sized> FanController<T> {
         self.fan_pwm_cache.clone()
     }
 
+    /// Get cached target RPMs for all fans
+    ///
+    /// Returns the last targets set via set_fan_rpm(), for fans that have not
+    /// been set by PWM since.
+    pub fn get_all_fan_rpm_targets(&self) -> HashMap<u8, u32> {
+        self.fan_rpm_target_cache.clone()
+    }
+
     /// Get RPM for a single fan
     pub async fn get_single_fan_rpm(&mut self, fan_id: u8) -> Result<u32> {
         self.validate_fan_id(fan_id)?;
@@ -240,6 +347,7 @@ impl<T: SerialTransport + ?Sized> FanController<T> {
 
         // Cache the PWM value on successful write
         self.fan_pwm_cache.insert(fan_id, pwm_percent);
+        self.fan_rpm_target_cache.remove(&fan_id);
 
         Ok(result)
     }
@@ -263,6 +371,7 @@ impl<T: SerialTransport + ?Sized> FanController<T> {
         for fan_id in 0..self.fan_count as u8 {
             self.fan_pwm_cache.insert(fan_id, pwm_percent);
         }
+        self.fan_rpm_target_cache.clear();
 
         Ok(result)
     }
@@ -271,17 +380,23 @@ impl<T: SerialTransport + ?Sized> FanController<T> {
     pub async fn set_fan_rpm(&mut self, fan_id: u8, rpm: u32) -> Result<String> {
         self.validate_fan_id(fan_id)?;
 
-        if rpm > 65535 {
+        if rpm < self.min_target_rpm || rpm > self.max_target_rpm {
             return Err(OpenFanError::InvalidInput(format!(
-                "RPM must be 0-65535, got {}",
-                rpm
+                "RPM must be {}-{}, got {}",
+                self.min_target_rpm, self.max_target_rpm, rpm
             )));
         }
 
         let (rpm_high, rpm_low) = rpm_to_bytes(rpm);
         let data = [fan_id, rpm_high, rpm_low];
 
-        self.send_command(Command::SetFanRpm, Some(&data)).await
+        let result = self.send_command(Command::SetFanRpm, Some(&data)).await?;
+
+        // The fan now follows the RPM target instead of its last PWM
+        self.fan_rpm_target_cache.insert(fan_id, rpm);
+        self.fan_pwm_cache.remove(&fan_id);
+
+        Ok(result)
     }
 
     /// Get hardware information
@@ -482,6 +597,34 @@ mod tests {
         assert!(matches!(result.unwrap_err(), OpenFanError::InvalidInput(_)));
     }
 
+    #[tokio::test]
+    async fn test_with_board_info_limits() {
+        use openfan_core::BoardType;
+
+        let board = BoardType::Custom { fan_count: 4 }.to_board_info();
+        let mock = MockTransport::new();
+        mock.queue_response(vec!["<OK>".to_string()]);
+        mock.queue_response(vec!["<OK>".to_string()]);
+        let mut controller = FanController::with_board_info(Box::new(mock), &board);
+        assert_eq!(controller.fan_count(), 4);
+
+        assert!(matches!(
+            controller.set_fan_pwm(4, 50).await,
+            Err(OpenFanError::InvalidFanId {
+                fan_id: 4,
+                max_fans: 4
+            })
+        ));
+        assert!(matches!(
+            controller.set_fan_rpm(0, 400).await,
+            Err(OpenFanError::InvalidInput(_))
+        ));
+        assert!(controller.set_fan_rpm(3, 500).await.is_ok());
+
+        controller.set_all_fan_pwm(30).await.unwrap();
+        assert_eq!(controller.get_all_fan_pwm().len(), 4);
+    }
+
     #[tokio::test]
     async fn test_get_hw_info() {
         let mock = MockTransport::new();
@@ -568,6 +711,29 @@ mod tests {
         assert_eq!(pwm_map.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_rpm_targets_replace_cached_pwm() {
+        let mock = MockTransport::new();
+        for _ in 0..4 {
+            mock.queue_response(vec!["<OK>".to_string()]);
+        }
+
+        let mut controller = create_mock_controller(mock);
+        controller.set_fan_pwm(0, 25).await.unwrap();
+        controller.set_fan_pwm(1, 50).await.unwrap();
+        controller.set_fan_rpm(1, 1200).await.unwrap();
+
+        assert_eq!(controller.get_all_fan_pwm(), HashMap::from([(0, 25)]));
+        assert_eq!(
+            controller.get_all_fan_rpm_targets(),
+            HashMap::from([(1, 1200)])
+        );
+
+        controller.set_all_fan_pwm(30).await.unwrap();
+        assert_eq!(controller.get_all_fan_pwm().len(), 10);
+        assert!(controller.get_all_fan_rpm_targets().is_empty());
+    }
+
     #[tokio::test]
     async fn test_parse_response_no_valid_line() {
         let mock = MockTransport::new();
@@ -581,6 +747,28 @@ mod tests {
         assert!(matches!(result.unwrap_err(), OpenFanError::Hardware(_)));
     }
 
+    #[tokio::test]
+    async fn test_transaction_stats() {
+        let mock = MockTransport::new();
+        mock.queue_response(vec!["<OK>".to_string()]);
+        mock.queue_response(vec!["INVALID RESPONSE".to_string()]);
+
+        let mut controller = create_mock_controller(mock);
+        let stats = Arc::new(TransactionStats::new());
+        controller.set_transaction_stats(stats.clone());
+
+        controller.set_fan_pwm(0, 50).await.unwrap();
+        assert!(controller.get_fw_info().await.is_err());
+        // Nothing queued: the transport itself fails
+        assert!(controller.get_all_fan_rpm().await.is_err());
+        // Rejected before anything is sent
+        assert!(controller.set_fan_pwm(42, 50).await.is_err());
+
+        assert_eq!(stats.transactions(), 3);
+        assert_eq!(stats.errors(), 2);
+        assert!(Arc::ptr_eq(&stats, &controller.transaction_stats()));
+    }
+
     #[tokio::test]
     async fn test_parse_fan_rpm_invalid_format() {
         let mock = MockTransport::new();
@@ -7,18 +7,29 @@
 //! Public API:
 //! - `fan_controller::FanController` — high-level controller for fan operations
 //! - `serial_driver::SerialDriver` — low-level serial I/O driver
+//! - `simulator::SimulatedDevice` — simulated board for running without hardware
+//! - `emulator::PtyEmulator` — simulated board served on a pseudo-terminal (Unix)
+//! - `discovery` — serial port discovery of OpenFAN boards
 
 // Re-export modules so consumers can use `openfan_hardware::FanController` and
 // `openfan_hardware::SerialDriver`.
+pub mod discovery;
+#[cfg(unix)]
+pub mod emulator;
 pub mod fan_controller;
 pub mod serial_driver;
+pub mod simulator;
 
 // Re-export with default board type for convenience
-pub type DefaultFanController = fan_controller::FanController<openfan_core::DefaultBoard>;
 pub type DefaultSerialDriver = serial_driver::SerialDriver<openfan_core::DefaultBoard>;
+pub type DefaultFanController = fan_controller::FanController<DefaultSerialDriver>;
 
-pub use fan_controller::FanController;
+pub use discovery::{CandidateSource, PortCandidate};
+#[cfg(unix)]
+pub use emulator::PtyEmulator;
+pub use fan_controller::{FanController, TransactionStats};
 pub use serial_driver::{SerialDriver, SerialTransport, is_disconnect_error};
+pub use simulator::{SimulatedDevice, SimulatedTransport, SimulatorConfig};
 
 #[cfg(test)]
 mod tests {
@@ -3,7 +3,7 @@
 //! Provides async serial I/O with the fan controller hardware.
 
 use async_trait::async_trait;
-use openfan_core::{BoardConfig, OpenFanError, Result};
+use openfan_core::{BoardConfig, BoardInfo, OpenFanError, Result};
 use std::marker::PhantomData;
 use std::time::Duration;
 use tokio::time::timeout;
@@ -48,9 +48,26 @@ impl<B: BoardConfig> SerialDriver<B> {
     /// * `timeout_ms` - Timeout in milliseconds for read/write operations
     /// * `debug_uart` - Enable UART debug logging
     pub fn new(port_path: &str, timeout_ms: u64, debug_uart: bool) -> Result<Self> {
-        debug!("Opening serial port: {}", port_path);
+        Self::open(port_path, B::BAUD_RATE, timeout_ms, debug_uart)
+    }
+
+    /// Create a serial driver for a board described at runtime
+    ///
+    /// Opens the port at `board.baud_rate` rather than `B::BAUD_RATE`, for
+    /// boards such as `BoardType::Custom` that have no `BoardConfig`.
+    pub fn with_board_info(
+        port_path: &str,
+        board: &BoardInfo,
+        timeout_ms: u64,
+        debug_uart: bool,
+    ) -> Result<Self> {
+        Self::open(port_path, board.baud_rate, timeout_ms, debug_uart)
+    }
+
+    fn open(port_path: &str, baud_rate: u32, timeout_ms: u64, debug_uart: bool) -> Result<Self> {
+        debug!("Opening serial port: {} at {} baud", port_path, baud_rate);
 
-        let port = tokio_serial::new(port_path, B::BAUD_RATE)
+        let port = tokio_serial::new(port_path, baud_rate)
             .timeout(Duration::from_millis(timeout_ms))
             .data_bits(tokio_serial::DataBits::Eight)
             .parity(tokio_serial::Parity::None)
@@ -223,6 +240,8 @@ pub fn is_disconnect_error(err: &OpenFanError) -> bool {
                 || msg_lower.contains("device disconnected")
                 || msg_lower.contains("device not configured")
                 || msg_lower.contains("input/output error")
+                // serialport's wording for EIO, e.g. when flushing a hung-up port
+                || msg_lower.contains("i/o error")
         }
         // Timeouts are typically transient, not disconnection
         OpenFanError::Timeout(_) => false,
@@ -258,6 +277,12 @@ mod tests {
         assert!(is_disconnect_error(&err));
     }
 
+    #[test]
+    fn test_is_disconnect_error_serial_flush_io_error() {
+        let err = OpenFanError::Serial("Failed to clear buffer: I/O error".to_string());
+        assert!(is_disconnect_error(&err));
+    }
+
     #[test]
     fn test_is_disconnect_error_timeout_not_disconnect() {
         let err = OpenFanError::Timeout("Read timeout".to_string());
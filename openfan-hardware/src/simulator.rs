@@ -0,0 +1,559 @@
+//! Simulated fan controller for running without hardware
+//!
+//! [`SimulatedTransport`] speaks the same `>XX` command / `<` response protocol
+//! as the firmware, so a `FanController` built on it behaves like one talking
+//! to a real board. Behind it, each fan's RPM lags toward a target derived from
+//! its PWM duty (or its RPM setpoint), capped by the fan's maximum RPM, and
+//! readings carry some measurement noise.
+//!
+//! The device state lives in a shared [`SimulatedDevice`]. It outlives the
+//! transports opened on it, the way a board outlives a serial connection, and
+//! is used to inject faults: stalled fans, timeouts and disconnects.
+
+use crate::fan_controller::Command;
+use crate::serial_driver::SerialTransport;
+use async_trait::async_trait;
+use openfan_core::{OpenFanError, Result};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Configuration of a simulated controller
+#[derive(Debug, Clone)]
+pub struct SimulatorConfig {
+    /// Maximum RPM of each fan; the length is the number of fans
+    pub max_rpm: Vec<u32>,
+    /// Time for a fan to cover ~63% of the way to its target RPM
+    pub time_constant: Duration,
+    /// Amplitude of the noise added to RPM readings
+    pub noise_rpm: u32,
+    /// Seed of the noise generator
+    pub seed: u64,
+}
+
+impl SimulatorConfig {
+    /// Create a configuration for `fan_count` fans.
+    ///
+    /// Fans get a mix of maximum speeds between 1800 and 3000 RPM, a 2 second
+    /// time constant and ±15 RPM of noise.
+    pub fn new(fan_count: usize) -> Self {
+        Self {
+            max_rpm: (0..fan_count)
+                .map(|i| 1800 + (i as u32 % 4) * 400)
+                .collect(),
+            time_constant: Duration::from_secs(2),
+            noise_rpm: 15,
+            seed: 0x5EED,
+        }
+    }
+
+    /// Set the maximum RPM of a fan.
+    pub fn with_max_rpm(mut self, fan_id: u8, rpm: u32) -> Self {
+        if let Some(max) = self.max_rpm.get_mut(fan_id as usize) {
+            *max = rpm;
+        }
+        self
+    }
+
+    /// Set the time constant of the RPM response.
+    pub fn with_time_constant(mut self, time_constant: Duration) -> Self {
+        self.time_constant = time_constant;
+        self
+    }
+
+    /// Set the amplitude of the noise added to RPM readings.
+    pub fn with_noise(mut self, noise_rpm: u32) -> Self {
+        self.noise_rpm = noise_rpm;
+        self
+    }
+}
+
+/// What a fan is being driven by
+#[derive(Debug, Clone, Copy)]
+enum Drive {
+    /// PWM duty cycle as sent on the wire (0-255)
+    Pwm(u8),
+    /// Closed-loop RPM target
+    Rpm(u32),
+}
+
+#[derive(Debug)]
+struct SimulatedFan {
+    max_rpm: u32,
+    drive: Drive,
+    rpm: f64,
+    stalled: bool,
+}
+
+impl SimulatedFan {
+    fn target_rpm(&self) -> f64 {
+        if self.stalled {
+            return 0.0;
+        }
+        match self.drive {
+            Drive::Pwm(duty) => self.max_rpm as f64 * duty as f64 / 255.0,
+            Drive::Rpm(rpm) => rpm.min(self.max_rpm) as f64,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct DeviceState {
+    fans: Vec<SimulatedFan>,
+    time_constant: f64,
+    noise_rpm: u32,
+    rng: u64,
+    last_update: Instant,
+    pending_timeouts: u32,
+    disconnected: bool,
+    /// Incremented on every disconnect so transports opened before it stay dead
+    generation: u64,
+}
+
+impl DeviceState {
+    /// Move every fan toward its target over `elapsed_secs`.
+    fn advance(&mut self, elapsed_secs: f64) {
+        let alpha = if self.time_constant > 0.0 {
+            1.0 - (-elapsed_secs / self.time_constant).exp()
+        } else {
+            1.0
+        };
+        for fan in &mut self.fans {
+            fan.rpm += (fan.target_rpm() - fan.rpm) * alpha;
+        }
+    }
+
+    /// Catch up with the time elapsed since the last update.
+    fn sync(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_update).as_secs_f64();
+        self.last_update = now;
+        self.advance(elapsed);
+    }
+
+    /// xorshift64* - good enough for measurement noise
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng >> 12;
+        self.rng ^= self.rng << 25;
+        self.rng ^= self.rng >> 27;
+        self.rng.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// RPM as reported by the tachometer, noise included.
+    fn measured_rpm(&mut self, fan_id: usize) -> u32 {
+        let rpm = self.fans[fan_id].rpm;
+        if rpm < 1.0 {
+            return 0;
+        }
+        let noise = if self.noise_rpm > 0 {
+            let span = 2 * self.noise_rpm as u64 + 1;
+            (self.next_random() % span) as f64 - self.noise_rpm as f64
+        } else {
+            0.0
+        };
+        (rpm + noise).round().max(0.0) as u32
+    }
+
+    fn rpm_data(&mut self, fan_ids: impl Iterator<Item = usize>) -> String {
+        let mut data = String::from("<DATA|");
+        for fan_id in fan_ids {
+            let rpm = self.measured_rpm(fan_id);
+            data.push_str(&format!("{}:{:04X};", fan_id, rpm));
+        }
+        data.push('>');
+        data
+    }
+
+    /// Execute a firmware command and return its response line.
+    fn execute(&mut self, command: &str) -> String {
+        let Some(bytes) = command.strip_prefix('>').and_then(decode_hex) else {
+            return "<ERR|Invalid command>".to_string();
+        };
+        let Some((&cmd, data)) = bytes.split_first() else {
+            return "<ERR|Invalid command>".to_string();
+        };
+        let fan = |id: &u8| Some(*id as usize).filter(|&id| id < self.fans.len());
+
+        match (cmd, data) {
+            (c, []) if c == Command::GetAllFanRpm as u8 => self.rpm_data(0..self.fans.len()),
+            (c, [id]) if c == Command::GetSingleFanRpm as u8 => match fan(id) {
+                Some(id) => self.rpm_data(std::iter::once(id)),
+                None => "<ERR|Invalid fan ID>".to_string(),
+            },
+            (c, [id, duty]) if c == Command::SetFanPwm as u8 => match fan(id) {
+                Some(id) => {
+                    self.fans[id].drive = Drive::Pwm(*duty);
+                    "<OK>".to_string()
+                }
+                None => "<ERR|Invalid fan ID>".to_string(),
+            },
+            (c, [duty]) if c == Command::SetAllFanPwm as u8 => {
+                for fan in &mut self.fans {
+                    fan.drive = Drive::Pwm(*duty);
+                }
+                "<OK>".to_string()
+            }
+            (c, [id, high, low]) if c == Command::SetFanRpm as u8 => match fan(id) {
+                Some(id) => {
+                    self.fans[id].drive = Drive::Rpm(u32::from_be_bytes([0, 0, *high, *low]));
+                    "<OK>".to_string()
+                }
+                None => "<ERR|Invalid fan ID>".to_string(),
+            },
+            (c, []) if c == Command::GetHwInfo as u8 => {
+                format!("<HW|Model:Simulated;Fans:{};Rev:1.0>", self.fans.len())
+            }
+            (c, []) if c == Command::GetFwInfo as u8 => {
+                format!("<FW|Version:{}-sim>", env!("CARGO_PKG_VERSION"))
+            }
+            _ => format!("<ERR|Unknown command {:02X}>", cmd),
+        }
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.is_empty() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// A simulated controller board
+///
+/// Cloning gives another handle to the same board. Use [`Self::open`] to
+/// connect to it and the fault injection methods to misbehave.
+#[derive(Debug, Clone)]
+pub struct SimulatedDevice {
+    name: String,
+    state: Arc<Mutex<DeviceState>>,
+}
+
+impl SimulatedDevice {
+    /// Create a simulated board with all fans stopped.
+    pub fn new(name: impl Into<String>, config: SimulatorConfig) -> Self {
+        let fans = config
+            .max_rpm
+            .iter()
+            .map(|&max_rpm| SimulatedFan {
+                max_rpm,
+                drive: Drive::Pwm(0),
+                rpm: 0.0,
+                stalled: false,
+            })
+            .collect();
+        Self {
+            name: name.into(),
+            state: Arc::new(Mutex::new(DeviceState {
+                fans,
+                time_constant: config.time_constant.as_secs_f64(),
+                noise_rpm: config.noise_rpm,
+                // xorshift needs a non-zero state
+                rng: config.seed.max(1),
+                last_update: Instant::now(),
+                pending_timeouts: 0,
+                disconnected: false,
+                generation: 0,
+            })),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, DeviceState> {
+        // A panic while holding the lock cannot leave the model inconsistent
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Device name, used in the transport's port path (`sim://{name}`).
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of fans on the board.
+    pub fn fan_count(&self) -> usize {
+        self.state().fans.len()
+    }
+
+    /// Open a connection to the board.
+    ///
+    /// Fails while the board is disconnected, like opening a missing serial port.
+    pub fn open(&self) -> Result<SimulatedTransport> {
+        let state = self.state();
+        if state.disconnected {
+            return Err(OpenFanError::Serial(format!(
+                "Failed to open simulated device '{}': device disconnected",
+                self.name
+            )));
+        }
+        Ok(SimulatedTransport {
+            device: self.clone(),
+            port_path: format!("sim://{}", self.name),
+            generation: state.generation,
+        })
+    }
+
+    /// Actual RPM of a fan, without measurement noise.
+    pub fn rpm(&self, fan_id: u8) -> Option<u32> {
+        let mut state = self.state();
+        state.sync();
+        state
+            .fans
+            .get(fan_id as usize)
+            .map(|fan| fan.rpm.round() as u32)
+    }
+
+    /// Advance the simulation by `duration` on top of real time.
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.state();
+        state.sync();
+        state.advance(duration.as_secs_f64());
+    }
+
+    /// Stall a fan (its RPM falls to zero whatever it is driven with) or release it.
+    pub fn set_stalled(&self, fan_id: u8, stalled: bool) {
+        let mut state = self.state();
+        state.sync();
+        if let Some(fan) = state.fans.get_mut(fan_id as usize) {
+            fan.stalled = stalled;
+        }
+    }
+
+    /// Make the next `count` transactions time out.
+    pub fn inject_timeouts(&self, count: u32) {
+        self.state().pending_timeouts = count;
+    }
+
+    /// Unplug the board: open transports fail with a disconnect error and
+    /// [`Self::open`] fails until [`Self::reconnect`] is called.
+    pub fn disconnect(&self) {
+        let mut state = self.state();
+        if !state.disconnected {
+            state.disconnected = true;
+            state.generation += 1;
+        }
+    }
+
+    /// Plug the board back in. Fans keep their last drive, like a board that
+    /// stayed powered.
+    pub fn reconnect(&self) {
+        self.state().disconnected = false;
+    }
+
+    /// Whether the board is unplugged.
+    pub fn is_disconnected(&self) -> bool {
+        self.state().disconnected
+    }
+}
+
+/// Connection to a [`SimulatedDevice`]
+#[derive(Debug)]
+pub struct SimulatedTransport {
+    device: SimulatedDevice,
+    port_path: String,
+    generation: u64,
+}
+
+#[async_trait]
+impl SerialTransport for SimulatedTransport {
+    async fn transaction(&mut self, command: &str) -> Result<Vec<String>> {
+        let mut state = self.device.state();
+
+        if state.disconnected || state.generation != self.generation {
+            return Err(OpenFanError::DeviceDisconnected(format!(
+                "Simulated device '{}' disconnected",
+                self.device.name
+            )));
+        }
+        if state.pending_timeouts > 0 {
+            state.pending_timeouts -= 1;
+            return Err(OpenFanError::Timeout(
+                "Read operation timed out".to_string(),
+            ));
+        }
+
+        state.sync();
+        let response = state.execute(command);
+        debug!("SIM {}: {} -> {}", self.device.name, command, response);
+        Ok(vec![response])
+    }
+
+    fn clear_input_buffer(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        let state = self.device.state();
+        !state.disconnected && state.generation == self.generation
+    }
+
+    fn port_path(&self) -> Option<&str> {
+        Some(&self.port_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FanController;
+
+    fn quiet_device(fan_count: usize) -> SimulatedDevice {
+        SimulatedDevice::new("test", SimulatorConfig::new(fan_count).with_noise(0))
+    }
+
+    fn controller(device: &SimulatedDevice) -> FanController<SimulatedTransport> {
+        FanController::with_transport(Box::new(device.open().unwrap()), device.fan_count(), 100)
+    }
+
+    #[test]
+    fn test_decode_hex() {
+        assert_eq!(decode_hex("02037F"), Some(vec![0x02, 0x03, 0x7F]));
+        assert_eq!(decode_hex("0"), None);
+        assert_eq!(decode_hex("ZZ"), None);
+        assert_eq!(decode_hex(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_protocol_responses() {
+        let device = quiet_device(4);
+        let mut transport = device.open().unwrap();
+
+        let lines = transport.transaction(">00").await.unwrap();
+        assert_eq!(lines, vec!["<DATA|0:0000;1:0000;2:0000;3:0000;>"]);
+        assert_eq!(
+            transport.transaction(">0201FF").await.unwrap(),
+            vec!["<OK>"]
+        );
+        assert!(transport.transaction(">0209FF").await.unwrap()[0].starts_with("<ERR|Invalid fan"));
+        assert!(transport.transaction(">42").await.unwrap()[0].starts_with("<ERR|Unknown"));
+        assert!(transport.transaction("garbage").await.unwrap()[0].starts_with("<ERR"));
+        assert!(transport.transaction(">05").await.unwrap()[0].starts_with("<HW|"));
+        assert!(transport.transaction(">06").await.unwrap()[0].starts_with("<FW|"));
+        assert_eq!(transport.port_path(), Some("sim://test"));
+    }
+
+    #[tokio::test]
+    async fn test_rpm_lags_toward_pwm_target() {
+        let device = SimulatedDevice::new(
+            "test",
+            SimulatorConfig::new(2)
+                .with_max_rpm(0, 2000)
+                .with_noise(0)
+                .with_time_constant(Duration::from_secs(1)),
+        );
+        let mut controller = controller(&device);
+
+        controller.set_fan_pwm(0, 100).await.unwrap();
+        // Still spinning up right after the command
+        assert!(device.rpm(0).unwrap() < 100);
+
+        // One time constant: ~63% of the way
+        device.advance(Duration::from_secs(1));
+        let rpm = controller.get_single_fan_rpm(0).await.unwrap();
+        assert!((1250..=1280).contains(&rpm), "rpm = {}", rpm);
+
+        // Settled at the fan's maximum
+        device.advance(Duration::from_secs(10));
+        assert_eq!(controller.get_single_fan_rpm(0).await.unwrap(), 2000);
+        // The other fan was never driven
+        assert_eq!(controller.get_single_fan_rpm(1).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_rpm_target_capped_by_max_rpm() {
+        let device = SimulatedDevice::new(
+            "test",
+            SimulatorConfig::new(1).with_max_rpm(0, 1800).with_noise(0),
+        );
+        let mut controller = controller(&device);
+
+        controller.set_fan_rpm(0, 1200).await.unwrap();
+        device.advance(Duration::from_secs(60));
+        assert_eq!(device.rpm(0), Some(1200));
+
+        controller.set_fan_rpm(0, 5000).await.unwrap();
+        device.advance(Duration::from_secs(60));
+        assert_eq!(device.rpm(0), Some(1800));
+    }
+
+    #[tokio::test]
+    async fn test_noise_stays_within_amplitude() {
+        let device = SimulatedDevice::new(
+            "test",
+            SimulatorConfig::new(1)
+                .with_max_rpm(0, 3000)
+                .with_noise(20)
+                .with_time_constant(Duration::ZERO),
+        );
+        let mut controller = controller(&device);
+        controller.set_fan_pwm(0, 100).await.unwrap();
+
+        let readings: Vec<u32> = {
+            let mut readings = Vec::new();
+            for _ in 0..50 {
+                readings.push(controller.get_single_fan_rpm(0).await.unwrap());
+            }
+            readings
+        };
+        assert!(readings.iter().all(|rpm| (2980..=3020).contains(rpm)));
+        assert!(readings.iter().any(|&rpm| rpm != readings[0]));
+    }
+
+    #[tokio::test]
+    async fn test_stall_injection() {
+        let device = SimulatedDevice::new(
+            "test",
+            SimulatorConfig::new(2)
+                .with_noise(0)
+                .with_time_constant(Duration::ZERO),
+        );
+        let mut controller = controller(&device);
+        controller.set_all_fan_pwm(50).await.unwrap();
+
+        device.set_stalled(1, true);
+        let rpm = controller.get_all_fan_rpm().await.unwrap();
+        assert!(rpm[&0] > 0);
+        assert_eq!(rpm[&1], 0);
+
+        device.set_stalled(1, false);
+        assert!(controller.get_single_fan_rpm(1).await.unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_timeout_injection() {
+        let device = quiet_device(2);
+        let mut controller = controller(&device);
+
+        device.inject_timeouts(2);
+        for _ in 0..2 {
+            let err = controller.get_all_fan_rpm().await.unwrap_err();
+            assert!(matches!(err, OpenFanError::Timeout(_)));
+            assert!(!crate::is_disconnect_error(&err));
+        }
+        assert!(controller.get_all_fan_rpm().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_injection() {
+        let device = quiet_device(2);
+        let mut old = controller(&device);
+        old.set_fan_pwm(0, 60).await.unwrap();
+
+        device.disconnect();
+        let err = old.get_all_fan_rpm().await.unwrap_err();
+        assert!(crate::is_disconnect_error(&err));
+        assert!(device.open().is_err());
+
+        // The old connection stays dead after the board comes back
+        device.reconnect();
+        assert!(old.get_all_fan_rpm().await.is_err());
+
+        let transport = device.open().unwrap();
+        assert!(transport.is_connected());
+        let mut new = FanController::with_transport(Box::new(transport), 2, 100);
+        assert!(new.get_all_fan_rpm().await.is_ok());
+        // The board kept driving the fan while unplugged from the host
+        device.advance(Duration::from_secs(60));
+        assert!(device.rpm(0).unwrap() > 0);
+    }
+}
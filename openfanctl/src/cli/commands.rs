@@ -1,6 +1,8 @@
 //! CLI command and subcommand definitions
 
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
+use openfan_core::config::MAX_CFM_RPM;
+use openfan_core::{AirflowDirection, BoardType, CfmModel, CurveTuning, api, parse_cfm_points};
 
 /// OpenFAN Controller CLI
 #[derive(Parser, Debug)]
@@ -61,7 +63,11 @@ pub enum Commands {
     Info,
 
     /// Show fan status
-    Status,
+    Status {
+        /// Keep refreshing from the daemon's live event stream
+        #[arg(short, long)]
+        watch: bool,
+    },
 
     /// Check server connectivity and health
     Health,
@@ -111,12 +117,37 @@ pub enum Commands {
         command: CurveCommands,
     },
 
+    /// PID controller management commands
+    Pid {
+        #[command(subcommand)]
+        command: PidCommands,
+    },
+
     /// CFM mapping management commands
     Cfm {
         #[command(subcommand)]
         command: CfmCommands,
     },
 
+    /// Temperature sensor commands
+    Sensor {
+        #[command(subcommand)]
+        command: SensorCommands,
+    },
+
+    /// Show active fan stall and failure alarms
+    Alarms,
+
+    /// Show the recorded fan history of a controller
+    History(HistoryArgs),
+
+    /// Reload the server's configuration and data files
+    Reload {
+        /// Show the outcome of the last reload instead
+        #[arg(long)]
+        status: bool,
+    },
+
     /// Generate shell completion scripts
     Completion {
         /// Shell to generate completion for
@@ -138,6 +169,45 @@ pub enum ControllerCommands {
         /// Controller ID
         id: String,
     },
+
+    /// Scan serial ports for OpenFAN boards
+    Scan,
+
+    /// Register a controller with the running server
+    Add {
+        /// Controller ID
+        id: String,
+
+        /// Serial device path (e.g., /dev/ttyACM1)
+        #[arg(long)]
+        device: Option<String>,
+
+        /// USB serial number, used instead of --device to find the board
+        #[arg(long)]
+        serial_number: Option<String>,
+
+        /// Board type (standard, custom:N where N is fan count 1-16)
+        #[arg(long, default_value = "standard")]
+        board: BoardType,
+
+        /// Controller description
+        #[arg(short, long)]
+        description: Option<String>,
+
+        /// Also append the controller to [[controllers]] in the server's config.toml
+        #[arg(long)]
+        persist: bool,
+    },
+
+    /// Unregister a controller from the running server
+    Remove {
+        /// Controller ID
+        id: String,
+
+        /// Also remove the controller from [[controllers]] in the server's config.toml
+        #[arg(long)]
+        persist: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -167,6 +237,80 @@ pub enum FanCommands {
         /// Fan ID (0-9)
         fan_id: u8,
     },
+
+    /// Measure each fan's RPM across its PWM range
+    ///
+    /// Sweeps the PWM of the fans from 100% down to 0%, then restores their
+    /// previous speeds. The results are saved by the server.
+    Calibrate {
+        /// Comma-separated fan IDs to calibrate (default: all)
+        #[arg(long)]
+        fans: Option<String>,
+
+        /// PWM percentage between measurements (1-50)
+        #[arg(long, default_value_t = api::DEFAULT_CALIBRATION_STEP)]
+        step: u32,
+
+        /// Seconds to let the fans settle before each measurement
+        #[arg(long, default_value_t = api::DEFAULT_CALIBRATION_SETTLE_SECS)]
+        settle: u64,
+
+        /// Start the calibration without waiting for it to finish
+        #[arg(long)]
+        detach: bool,
+
+        /// Show the progress of the running or last calibration instead
+        #[arg(long, conflicts_with_all = ["cancel", "results", "fans", "detach"])]
+        status: bool,
+
+        /// Cancel the running calibration instead
+        #[arg(long, conflicts_with_all = ["results", "fans", "detach"])]
+        cancel: bool,
+
+        /// Show the saved calibration results instead
+        #[arg(long, conflicts_with_all = ["fans", "detach"])]
+        results: bool,
+    },
+
+    /// Safety limits kept by every way of setting a fan
+    Limits {
+        #[command(subcommand)]
+        command: FanLimitCommands,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum FanLimitCommands {
+    /// Show the limits of all fans
+    List,
+
+    /// Set the limits of a fan, replacing earlier ones
+    Set {
+        /// Fan ID (0-9)
+        fan_id: u8,
+
+        /// Lowest PWM percentage the fan runs at, apart from 0% unless --no-zero
+        #[arg(long)]
+        min_pwm: Option<u32>,
+
+        /// Highest PWM percentage the fan runs at
+        #[arg(long)]
+        max_pwm: Option<u32>,
+
+        /// Lowest target RPM the fan is set to
+        #[arg(long)]
+        min_rpm: Option<u32>,
+
+        /// Never stop the fan: 0% PWM is raised to --min-pwm or rejected
+        #[arg(long)]
+        no_zero: bool,
+    },
+
+    /// Remove the limits of a fan
+    Delete {
+        /// Fan ID (0-9)
+        fan_id: u8,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -316,6 +460,57 @@ pub enum ConfigCommands {
     Reset,
 }
 
+/// Time range and output options for the fan history
+#[derive(Args, Debug)]
+pub struct HistoryArgs {
+    /// Start of the range: Unix timestamp, or age such as 30m, 12h or 7d
+    #[arg(long, default_value = "1h")]
+    pub from: String,
+
+    /// End of the range: Unix timestamp, or age (default: now)
+    #[arg(long)]
+    pub to: Option<String>,
+
+    /// Seconds covered by each point (default: the recording resolution)
+    #[arg(long)]
+    pub step: Option<u64>,
+
+    /// Print CSV instead of the output format
+    #[arg(long)]
+    pub csv: bool,
+}
+
+/// Smoothing, hysteresis and ramp options for thermal curves
+#[derive(Args, Debug, Default)]
+pub struct CurveTuningArgs {
+    /// Hysteresis in °C: PWM only decreases once the temperature has dropped this much
+    #[arg(long)]
+    pub hysteresis: Option<f32>,
+
+    /// Maximum PWM increase in %/s (0 = unlimited)
+    #[arg(long)]
+    pub ramp_up: Option<f32>,
+
+    /// Maximum PWM decrease in %/s (0 = unlimited)
+    #[arg(long)]
+    pub ramp_down: Option<f32>,
+
+    /// Temperature smoothing window in seconds (exponential moving average, 0 = off)
+    #[arg(long)]
+    pub smoothing: Option<f32>,
+}
+
+impl From<CurveTuningArgs> for CurveTuning {
+    fn from(args: CurveTuningArgs) -> Self {
+        Self {
+            hysteresis_c: args.hysteresis,
+            ramp_up_pwm_per_sec: args.ramp_up,
+            ramp_down_pwm_per_sec: args.ramp_down,
+            smoothing_secs: args.smoothing,
+        }
+    }
+}
+
 #[derive(Subcommand, Debug)]
 pub enum CurveCommands {
     /// List all thermal curves
@@ -339,6 +534,9 @@ pub enum CurveCommands {
         /// Optional description
         #[arg(short, long)]
         description: Option<String>,
+
+        #[command(flatten)]
+        tuning: CurveTuningArgs,
     },
 
     /// Update an existing thermal curve
@@ -353,6 +551,10 @@ pub enum CurveCommands {
         /// Optional description
         #[arg(short, long)]
         description: Option<String>,
+
+        /// Tuning options left out keep their current value
+        #[command(flatten)]
+        tuning: CurveTuningArgs,
     },
 
     /// Delete a thermal curve
@@ -370,6 +572,144 @@ pub enum CurveCommands {
         #[arg(short, long)]
         temp: f32,
     },
+
+    /// List curve bindings and their control loop status
+    Bindings,
+
+    /// Bind a curve or PID controller to a temperature sensor to drive fans automatically
+    Bind {
+        /// Curve name
+        #[arg(required_unless_present = "pid", conflicts_with = "pid")]
+        curve: Option<String>,
+
+        /// PID controller to use instead of a curve
+        #[arg(long)]
+        pid: Option<String>,
+
+        /// Temperature sensor: an ID from `sensor list` (e.g. k10temp/temp1) or a millidegree file path
+        #[arg(short, long)]
+        sensor: String,
+
+        /// Zone whose fans are driven
+        #[arg(short, long, conflicts_with = "fans", required_unless_present = "fans")]
+        zone: Option<String>,
+
+        /// Comma-separated fan IDs to drive (e.g., "0,1,2")
+        #[arg(short, long)]
+        fans: Option<String>,
+
+        /// Binding name (defaults to the zone name, or the curve or PID name)
+        #[arg(short, long)]
+        name: Option<String>,
+
+        /// Poll interval in seconds
+        #[arg(short, long)]
+        interval: Option<u64>,
+    },
+
+    /// Remove a curve binding and stop its control loop
+    Unbind {
+        /// Binding name
+        name: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PidCommands {
+    /// List all PID controllers
+    List,
+
+    /// Get PID controller details
+    Get {
+        /// PID controller name
+        name: String,
+    },
+
+    /// Add a new PID controller
+    Add {
+        /// PID controller name
+        name: String,
+
+        /// Target temperature in °C
+        #[arg(short, long)]
+        setpoint: f32,
+
+        /// Proportional gain (PWM % per °C)
+        #[arg(long)]
+        kp: f32,
+
+        /// Integral gain (PWM % per °C·s)
+        #[arg(long, default_value_t = 0.0)]
+        ki: f32,
+
+        /// Derivative gain (PWM % per °C/s)
+        #[arg(long, default_value_t = 0.0)]
+        kd: f32,
+
+        /// Lowest PWM output (0-100)
+        #[arg(long)]
+        min_pwm: Option<u8>,
+
+        /// Highest PWM output (0-100)
+        #[arg(long)]
+        max_pwm: Option<u8>,
+
+        /// Optional description
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Update an existing PID controller (options left out keep their current value)
+    Update {
+        /// PID controller name
+        name: String,
+
+        /// Target temperature in °C
+        #[arg(short, long)]
+        setpoint: Option<f32>,
+
+        /// Proportional gain (PWM % per °C)
+        #[arg(long)]
+        kp: Option<f32>,
+
+        /// Integral gain (PWM % per °C·s)
+        #[arg(long)]
+        ki: Option<f32>,
+
+        /// Derivative gain (PWM % per °C/s)
+        #[arg(long)]
+        kd: Option<f32>,
+
+        /// Lowest PWM output (0-100)
+        #[arg(long)]
+        min_pwm: Option<u8>,
+
+        /// Highest PWM output (0-100)
+        #[arg(long)]
+        max_pwm: Option<u8>,
+
+        /// Optional description
+        #[arg(short, long)]
+        description: Option<String>,
+    },
+
+    /// Delete a PID controller
+    Delete {
+        /// PID controller name
+        name: String,
+    },
+
+    /// Show the live PID state (error, integral, P/I/D terms) of a binding
+    Status {
+        /// Binding name
+        binding: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SensorCommands {
+    /// List temperature sensors with their current readings
+    List,
 }
 
 #[derive(Subcommand, Debug)]
@@ -383,7 +723,7 @@ pub enum CfmCommands {
         port: u8,
     },
 
-    /// Set CFM@100% value for a port
+    /// Set CFM@100% value for a port, with an optional airflow model
     Set {
         /// Port ID (0-9)
         port: u8,
@@ -391,6 +731,9 @@ pub enum CfmCommands {
         /// CFM value at 100% PWM
         #[arg(long)]
         cfm_at_100: f32,
+
+        #[command(flatten)]
+        model: CfmModelArgs,
     },
 
     /// Delete CFM mapping for a port
@@ -398,4 +741,46 @@ pub enum CfmCommands {
         /// Port ID (0-9)
         port: u8,
     },
+
+    /// Show the estimated airflow of each fan
+    Airflow,
+
+    /// Show the intake, exhaust and balance airflow of each zone
+    Zones,
+}
+
+/// Airflow model of a CFM mapping; options left out are not set
+#[derive(Args, Debug, Clone, Default)]
+pub struct CfmModelArgs {
+    /// PWM→CFM points as "pwm:cfm,..." (e.g., "20:0,50:30,100:45")
+    #[arg(long)]
+    pub pwm_points: Option<String>,
+
+    /// RPM→CFM points as "rpm:cfm,..." (e.g., "400:10,1200:32,1800:45"), used when RPM is known
+    #[arg(long)]
+    pub rpm_points: Option<String>,
+
+    /// RPM at 100% PWM, to scale CFM@100% by the measured RPM
+    #[arg(long)]
+    pub max_rpm: Option<u32>,
+
+    /// Airflow direction (intake or exhaust), for zone airflow totals
+    #[arg(long)]
+    pub direction: Option<AirflowDirection>,
+}
+
+impl TryFrom<CfmModelArgs> for CfmModel {
+    type Error = String;
+
+    fn try_from(args: CfmModelArgs) -> Result<Self, Self::Error> {
+        let points = |input: Option<String>, max_at| {
+            input.map_or(Ok(Vec::new()), |input| parse_cfm_points(&input, max_at))
+        };
+        Ok(Self {
+            pwm_points: points(args.pwm_points, 100)?,
+            rpm_points: points(args.rpm_points, MAX_CFM_RPM)?,
+            max_rpm: args.max_rpm,
+            direction: args.direction,
+        })
+    }
 }
@@ -1,9 +1,13 @@
 //! Command execution handlers
 
 use anyhow::Result;
-use openfan_core::ZoneFan;
+use openfan_core::api::{
+    AddControllerRequest, AlarmKind, ConfigResource, DiscoverySource, SensorKind, TelemetryEvent,
+    ZoneFanOutcome,
+};
 use openfan_core::parse_points;
 use openfan_core::types::{ControlMode, FanProfile};
+use openfan_core::{CfmModel, CfmPoint, ControllerConfig, FanLimits, ZoneFan};
 
 use crate::client::OpenFanClient;
 use crate::config::CliConfig;
@@ -14,6 +18,12 @@ use super::commands::*;
 /// Error message when neither --pwm nor --rpm is specified.
 const ERR_PWM_OR_RPM_REQUIRED: &str = "Must specify either --pwm or --rpm";
 
+/// Shown in place of the API token
+const TOKEN_MASK: &str = "********";
+
+/// How often a calibration job's progress is polled
+const CALIBRATION_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
 /// Parse zone port specifications into ZoneFan entries.
 ///
 /// Supports two formats:
@@ -51,6 +61,25 @@ fn parse_zone_ports(ports: &str, default_controller: &str) -> Result<Vec<ZoneFan
     Ok(fans)
 }
 
+/// Parse a comma-separated list of fan IDs (e.g., "0,1,2").
+fn parse_fan_ids(fans: &str) -> Result<Vec<u8>> {
+    let fan_ids = fans
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            part.parse::<u8>()
+                .map_err(|_| anyhow::anyhow!("Invalid fan ID '{}'", part))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if fan_ids.is_empty() {
+        return Err(anyhow::anyhow!("No fan IDs provided"));
+    }
+
+    Ok(fan_ids)
+}
+
 /// Handle info command
 pub async fn handle_info(client: &OpenFanClient, format: &OutputFormat) -> Result<()> {
     let info = client.get_info().await?;
@@ -82,6 +111,57 @@ pub async fn handle_status(client: &OpenFanClient, format: &OutputFormat) -> Res
     Ok(())
 }
 
+/// Handle status command with --watch: follow the daemon's live event stream.
+///
+/// The table is redrawn on every fan reading and connection state change,
+/// and the CFM mappings are refetched when they change. JSON output prints
+/// each event on its own line. Returns when the daemon closes the stream.
+pub async fn handle_status_watch(client: &OpenFanClient, format: &OutputFormat) -> Result<()> {
+    let mut events = client.stream_events().await?;
+    let mut cfm_mappings = client.get_cfm_mappings().await.ok();
+    let mut connection_state = String::from("unknown");
+    let mut last_status = None;
+
+    while let Some(event) = events.next().await? {
+        if let OutputFormat::Json = format {
+            println!("{}", serde_json::to_string(&event)?);
+            continue;
+        }
+
+        match event {
+            TelemetryEvent::FanStatus { status, .. } => last_status = Some(status),
+            TelemetryEvent::ConnectionState { state, .. } => connection_state = state,
+            TelemetryEvent::ConfigChanged {
+                resource: ConfigResource::CfmMappings,
+                ..
+            } => cfm_mappings = client.get_cfm_mappings().await.ok(),
+            _ => continue,
+        }
+
+        // Clear the screen and move the cursor home before redrawing
+        print!("\x1B[2J\x1B[H");
+        println!(
+            "Controller {} ({}) - Ctrl-C to stop",
+            client.controller_id(),
+            connection_state
+        );
+        println!();
+        match &last_status {
+            Some(status) => println!(
+                "{}",
+                crate::format::format_fan_status_with_cfm(
+                    status,
+                    cfm_mappings.as_ref(),
+                    &format.into()
+                )?
+            ),
+            None => println!("Waiting for fan readings..."),
+        }
+    }
+
+    Ok(())
+}
+
 /// Handle health command
 pub async fn handle_health(client: &OpenFanClient, format: &OutputFormat) -> Result<()> {
     let health = client.health_check().await?;
@@ -175,6 +255,12 @@ pub async fn handle_controller(
                         "  Connected:  {}",
                         if info.connected { "yes" } else { "no" }
                     );
+                    if let Some(path) = &info.device_path {
+                        println!("  Device:     {}", path);
+                    }
+                    if let Some(serial_number) = &info.serial_number {
+                        println!("  Serial:     {}", serial_number);
+                    }
                     if let Some(desc) = &info.description {
                         println!("  Description: {}", desc);
                     }
@@ -185,6 +271,83 @@ pub async fn handle_controller(
             let message = client.reconnect_controller(&id).await?;
             println!("{}", format_success(&message));
         }
+        ControllerCommands::Add {
+            id,
+            device,
+            serial_number,
+            board,
+            description,
+            persist,
+        } => {
+            let mut controller = ControllerConfig::new(id, device.unwrap_or_default(), board);
+            controller.serial_number = serial_number;
+            controller.description = description;
+            let request = AddControllerRequest {
+                controller,
+                persist,
+            };
+            let info = client.add_controller(&request).await?;
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&info)?);
+                }
+                OutputFormat::Table => {
+                    println!(
+                        "{}",
+                        format_success(&format!(
+                            "Controller '{}' added ({}, {} fans)",
+                            info.id, info.board_name, info.fan_count
+                        ))
+                    );
+                }
+            }
+        }
+        ControllerCommands::Remove { id, persist } => {
+            let message = client.remove_controller(&id, persist).await?;
+            println!("{}", format_success(&message));
+        }
+        ControllerCommands::Scan => {
+            let response = client.discover_controllers().await?;
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&response)?);
+                }
+                OutputFormat::Table => {
+                    if response.ports.is_empty() {
+                        println!("No candidate serial ports found.");
+                    } else {
+                        println!(
+                            "{:<24} {:<12} {:<10} {:<16} Status",
+                            "Port", "Source", "USB ID", "Serial"
+                        );
+                        println!("{}", "-".repeat(80));
+                        for port in &response.ports {
+                            let source = match port.source {
+                                DiscoverySource::Env => "env",
+                                DiscoverySource::UsbId => "usb-id",
+                                DiscoverySource::CommonPath => "common-path",
+                            };
+                            let status = match (&port.controller, &port.firmware, &port.error) {
+                                (Some(id), _, _) => format!("in use by '{}'", id),
+                                (None, Some(firmware), _) => format!("OpenFAN ({})", firmware),
+                                (None, None, Some(error)) => format!("no board: {}", error),
+                                (None, None, None) => "-".to_string(),
+                            };
+                            println!(
+                                "{:<24} {:<12} {:<10} {:<16} {}",
+                                port.port,
+                                source,
+                                port.usb_id.as_deref().unwrap_or("-"),
+                                port.serial_number.as_deref().unwrap_or("-"),
+                                status
+                            );
+                        }
+                    }
+                }
+            }
+        }
     }
 
     Ok(())
@@ -245,8 +408,168 @@ pub async fn handle_fan(
                 }
             }
         }
+        FanCommands::Calibrate {
+            fans,
+            step,
+            settle,
+            detach,
+            status,
+            cancel,
+            results,
+        } => {
+            if status {
+                match client.get_calibration_status().await?.calibration {
+                    Some(status) => println!(
+                        "{}",
+                        crate::format::format_calibration_status(&status, &format.into())?
+                    ),
+                    None => match format {
+                        OutputFormat::Json => println!("null"),
+                        OutputFormat::Table => println!("No calibration since the server started."),
+                    },
+                }
+            } else if cancel {
+                let message = client.cancel_calibration().await?;
+                println!("{}", format_success(&message));
+            } else if results {
+                let results = client.get_calibration_results().await?;
+                println!(
+                    "{}",
+                    crate::format::format_calibration_results(&results, &format.into())?
+                );
+            } else {
+                let request = openfan_core::api::CalibrationRequest {
+                    fans: fans
+                        .as_deref()
+                        .map(parse_fan_ids)
+                        .transpose()?
+                        .unwrap_or_default(),
+                    step,
+                    settle_secs: settle,
+                };
+                let status = client.start_calibration(&request).await?;
+                if detach {
+                    println!(
+                        "{}",
+                        crate::format::format_calibration_status(&status, &format.into())?
+                    );
+                    return Ok(());
+                }
+                handle_calibration_wait(client, status, format).await?;
+            }
+        }
+        FanCommands::Limits { command } => handle_fan_limits(client, command, format).await?,
+    }
+
+    Ok(())
+}
+
+/// Handle fan limit commands
+async fn handle_fan_limits(
+    client: &OpenFanClient,
+    command: FanLimitCommands,
+    format: &OutputFormat,
+) -> Result<()> {
+    match command {
+        FanLimitCommands::List => {
+            let limits = client.get_fan_limits().await?;
+            println!(
+                "{}",
+                crate::format::format_fan_limits(&limits, &format.into())?
+            );
+        }
+        FanLimitCommands::Set {
+            fan_id,
+            min_pwm,
+            max_pwm,
+            min_rpm,
+            no_zero,
+        } => {
+            let limits = FanLimits {
+                min_pwm,
+                max_pwm,
+                min_rpm,
+                allow_zero: !no_zero,
+            };
+            client.set_fan_limits(fan_id, &limits).await?;
+            let message = if limits.is_unrestricted() {
+                format!("Removed the limits of fan {}", fan_id)
+            } else {
+                format!("Set the limits of fan {}: {}", fan_id, limits)
+            };
+            println!("{}", format_success(&message));
+        }
+        FanLimitCommands::Delete { fan_id } => {
+            client.delete_fan_limits(fan_id).await?;
+            println!(
+                "{}",
+                format_success(&format!("Removed the limits of fan {}", fan_id))
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Follow a calibration job until it ends, then show its results.
+///
+/// Stopping the command leaves the job running on the server.
+async fn handle_calibration_wait(
+    client: &OpenFanClient,
+    mut status: openfan_core::api::CalibrationStatus,
+    format: &OutputFormat,
+) -> Result<()> {
+    use openfan_core::api::CalibrationState;
+
+    if let OutputFormat::Table = format {
+        println!(
+            "Calibrating fans of '{}' - Ctrl-C stops waiting, `fan calibrate --cancel` stops the job",
+            status.controller
+        );
+    }
+
+    let mut reported = None;
+    while status.is_running() {
+        if let OutputFormat::Table = format
+            && reported != Some(status.completed_steps)
+        {
+            reported = Some(status.completed_steps);
+            match status.current_pwm {
+                Some(pwm) => println!("  {:>3}% - measuring at {}% PWM", status.percent(), pwm),
+                None => println!("  {:>3}%", status.percent()),
+            }
+        }
+
+        tokio::time::sleep(CALIBRATION_POLL_INTERVAL).await;
+        status = client
+            .get_calibration_status()
+            .await?
+            .calibration
+            .ok_or_else(|| anyhow::anyhow!("Calibration job disappeared from the server"))?;
     }
 
+    if status.state != CalibrationState::Completed {
+        if let OutputFormat::Json = format {
+            println!(
+                "{}",
+                crate::format::format_calibration_status(&status, &format.into())?
+            );
+        }
+        return Err(anyhow::anyhow!(
+            "Calibration {}{}",
+            status.state,
+            status
+                .error
+                .map(|error| format!(": {}", error))
+                .unwrap_or_default()
+        ));
+    }
+
+    let results = client.get_calibration_results().await?;
+    println!(
+        "{}",
+        crate::format::format_calibration_results(&results, &format.into())?
+    );
     Ok(())
 }
 
@@ -444,25 +767,64 @@ pub async fn handle_zone(
             client.delete_zone(&name).await?;
             println!("{}", format_success(&format!("Deleted zone: {}", name)));
         }
-        ZoneCommands::Apply { name, pwm, rpm } => match (pwm, rpm) {
-            (Some(pwm), None) => {
-                client.apply_zone(&name, "pwm", pwm).await?;
-                println!(
-                    "{}",
-                    format_success(&format!("Applied {}% PWM to zone '{}'", pwm, name))
-                );
-            }
-            (None, Some(rpm)) => {
-                client.apply_zone(&name, "rpm", rpm).await?;
-                println!(
-                    "{}",
-                    format_success(&format!("Applied {} RPM to zone '{}'", rpm, name))
-                );
+        ZoneCommands::Apply { name, pwm, rpm } => {
+            let (report, unit) = match (pwm, rpm) {
+                (Some(pwm), None) => (client.apply_zone(&name, "pwm", pwm).await?, "% PWM"),
+                (None, Some(rpm)) => (client.apply_zone(&name, "rpm", rpm).await?, " RPM"),
+                _ => {
+                    return Err(anyhow::anyhow!(ERR_PWM_OR_RPM_REQUIRED));
+                }
+            };
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                }
+                OutputFormat::Table => {
+                    if report.is_complete() {
+                        println!(
+                            "{}",
+                            format_success(&format!(
+                                "Applied {}{} to zone '{}'",
+                                report.value, unit, name
+                            ))
+                        );
+                    } else {
+                        println!(
+                            "Applied {}{} to {}/{} fans in zone '{}':",
+                            report.value,
+                            unit,
+                            report.applied_count(),
+                            report.results.len(),
+                            name
+                        );
+                        for result in &report.results {
+                            let outcome = match result.outcome {
+                                ZoneFanOutcome::Success => "ok",
+                                ZoneFanOutcome::Failure => "failed",
+                                ZoneFanOutcome::SkippedDisconnected => "skipped",
+                            };
+                            println!(
+                                "  {}:{:<4} {:<8} {}",
+                                result.controller,
+                                result.fan_id,
+                                outcome,
+                                result.error.as_deref().unwrap_or("")
+                            );
+                        }
+                    }
+                }
             }
-            _ => {
-                return Err(anyhow::anyhow!(ERR_PWM_OR_RPM_REQUIRED));
+
+            if !report.is_complete() {
+                return Err(anyhow::anyhow!(
+                    "Zone '{}' was only applied to {}/{} fans",
+                    name,
+                    report.applied_count(),
+                    report.results.len()
+                ));
             }
-        },
+        }
     }
 
     Ok(())
@@ -522,6 +884,19 @@ pub async fn handle_curve(
                     if let Some(desc) = &curve.description {
                         println!("Description: {}", desc);
                     }
+                    let tuning = &curve.tuning;
+                    if let Some(h) = tuning.hysteresis_c {
+                        println!("Hysteresis: {}°C", h);
+                    }
+                    if let Some(rate) = tuning.ramp_up_pwm_per_sec {
+                        println!("Ramp up: {}%/s", rate);
+                    }
+                    if let Some(rate) = tuning.ramp_down_pwm_per_sec {
+                        println!("Ramp down: {}%/s", rate);
+                    }
+                    if let Some(window) = tuning.smoothing_secs {
+                        println!("Smoothing: {}s", window);
+                    }
                 }
             }
         }
@@ -529,23 +904,27 @@ pub async fn handle_curve(
             name,
             points,
             description,
+            tuning,
         } => {
             let curve_points = parse_points(&points)
                 .map_err(|e| anyhow::anyhow!("Invalid points format: {}", e))?;
 
-            client.add_curve(&name, curve_points, description).await?;
+            client
+                .add_curve(&name, curve_points, description, tuning.into())
+                .await?;
             println!("{}", format_success(&format!("Added curve: {}", name)));
         }
         CurveCommands::Update {
             name,
             points,
             description,
+            tuning,
         } => {
             let curve_points = parse_points(&points)
                 .map_err(|e| anyhow::anyhow!("Invalid points format: {}", e))?;
 
             client
-                .update_curve(&name, curve_points, description)
+                .update_curve(&name, curve_points, description, tuning.into())
                 .await?;
             println!("{}", format_success(&format!("Updated curve: {}", name)));
         }
@@ -568,6 +947,277 @@ pub async fn handle_curve(
                 }
             }
         }
+        CurveCommands::Bindings => {
+            let response = client.get_bindings().await?;
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&response)?);
+                }
+                OutputFormat::Table => {
+                    if response.bindings.is_empty() {
+                        println!("No curve bindings configured.");
+                    } else {
+                        println!(
+                            "{:<16} {:<16} {:<16} {:<8} {:<8} {:<6} Sensor",
+                            "Name", "Curve/PID", "Target", "Temp", "PWM", "Loop"
+                        );
+                        println!("{}", "-".repeat(90));
+                        let mut entries: Vec<_> = response.bindings.values().collect();
+                        entries.sort_by(|a, b| a.binding.name.cmp(&b.binding.name));
+                        for status in entries {
+                            let binding = &status.binding;
+                            let target = match &binding.zone {
+                                Some(zone) => format!("zone:{}", zone),
+                                None => format!(
+                                    "fans:{}",
+                                    binding
+                                        .fans
+                                        .iter()
+                                        .map(|f| f.to_string())
+                                        .collect::<Vec<_>>()
+                                        .join(",")
+                                ),
+                            };
+                            let temp = status
+                                .temperature
+                                .map(|t| format!("{:.1}°C", t))
+                                .unwrap_or_else(|| "-".to_string());
+                            let pwm = status
+                                .pwm
+                                .map(|p| format!("{}%", p))
+                                .unwrap_or_else(|| "-".to_string());
+                            let state = match (&status.error, status.running) {
+                                (Some(_), _) => "error",
+                                (None, true) => "ok",
+                                (None, false) => "idle",
+                            };
+                            let strategy = match (&binding.curve, &binding.pid) {
+                                (Some(curve), _) => curve.clone(),
+                                (None, Some(pid)) => format!("pid:{}", pid),
+                                (None, None) => "-".to_string(),
+                            };
+                            println!(
+                                "{:<16} {:<16} {:<16} {:<8} {:<8} {:<6} {}",
+                                binding.name, strategy, target, temp, pwm, state, binding.sensor
+                            );
+                            if let Some(error) = &status.error {
+                                println!("  └─ {}", error);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        CurveCommands::Bind {
+            curve,
+            pid,
+            sensor,
+            zone,
+            fans,
+            name,
+            interval,
+        } => {
+            let fans = match fans {
+                Some(fans) => parse_fan_ids(&fans)?,
+                None => Vec::new(),
+            };
+            let strategy = match (&curve, &pid) {
+                (Some(curve), _) => format!("curve '{}'", curve),
+                (None, Some(pid)) => format!("PID '{}'", pid),
+                (None, None) => return Err(anyhow::anyhow!("Specify a curve or --pid")),
+            };
+            let name = name
+                .or_else(|| zone.clone())
+                .or_else(|| curve.clone())
+                .or_else(|| pid.clone())
+                .unwrap_or_default();
+
+            let request = openfan_core::api::AddBindingRequest {
+                name: name.clone(),
+                curve,
+                pid,
+                sensor,
+                zone,
+                fans,
+                interval_secs: interval,
+            };
+
+            client.add_binding(&request).await?;
+            println!(
+                "{}",
+                format_success(&format!("Bound {} as '{}'", strategy, name))
+            );
+        }
+        CurveCommands::Unbind { name } => {
+            client.delete_binding(&name).await?;
+            println!("{}", format_success(&format!("Removed binding: {}", name)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle PID controller commands
+pub async fn handle_pid(
+    client: &OpenFanClient,
+    command: PidCommands,
+    format: &OutputFormat,
+) -> Result<()> {
+    match command {
+        PidCommands::List => {
+            let response = client.get_pids().await?;
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&response)?);
+                }
+                OutputFormat::Table => {
+                    if response.controllers.is_empty() {
+                        println!("No PID controllers configured.");
+                    } else {
+                        println!(
+                            "{:<16} {:>9} {:>8} {:>8} {:>8} {:>9}",
+                            "Name", "Setpoint", "Kp", "Ki", "Kd", "Output"
+                        );
+                        println!("{}", "-".repeat(63));
+                        let mut names: Vec<_> = response.controllers.keys().collect();
+                        names.sort();
+                        for name in names {
+                            let pid = &response.controllers[name];
+                            println!(
+                                "{:<16} {:>8.1}° {:>8} {:>8} {:>8} {:>9}",
+                                name,
+                                pid.setpoint_c,
+                                pid.kp,
+                                pid.ki,
+                                pid.kd,
+                                format!("{}-{}%", pid.min_pwm, pid.max_pwm)
+                            );
+                        }
+                    }
+                }
+            }
+        }
+        PidCommands::Get { name } => {
+            let response = client.get_pid(&name).await?;
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&response)?);
+                }
+                OutputFormat::Table => {
+                    let pid = &response.pid;
+                    println!("PID controller: {}", pid.name);
+                    if let Some(desc) = &pid.description {
+                        println!("Description: {}", desc);
+                    }
+                    println!("{:<12} {:.1}°C", "Setpoint", pid.setpoint_c);
+                    println!("{:<12} {}", "Kp", pid.kp);
+                    println!("{:<12} {}", "Ki", pid.ki);
+                    println!("{:<12} {}", "Kd", pid.kd);
+                    println!("{:<12} {}-{}%", "Output", pid.min_pwm, pid.max_pwm);
+                }
+            }
+        }
+        PidCommands::Add {
+            name,
+            setpoint,
+            kp,
+            ki,
+            kd,
+            min_pwm,
+            max_pwm,
+            description,
+        } => {
+            let request = openfan_core::api::AddPidRequest {
+                name: name.clone(),
+                setpoint_c: setpoint,
+                kp,
+                ki,
+                kd,
+                min_pwm,
+                max_pwm,
+                description,
+            };
+            client.add_pid(&request).await?;
+            println!(
+                "{}",
+                format_success(&format!("Added PID controller: {}", name))
+            );
+        }
+        PidCommands::Update {
+            name,
+            setpoint,
+            kp,
+            ki,
+            kd,
+            min_pwm,
+            max_pwm,
+            description,
+        } => {
+            let request = openfan_core::api::UpdatePidRequest {
+                setpoint_c: setpoint,
+                kp,
+                ki,
+                kd,
+                min_pwm,
+                max_pwm,
+                description,
+            };
+            client.update_pid(&name, &request).await?;
+            println!(
+                "{}",
+                format_success(&format!("Updated PID controller: {}", name))
+            );
+        }
+        PidCommands::Delete { name } => {
+            client.delete_pid(&name).await?;
+            println!(
+                "{}",
+                format_success(&format!("Deleted PID controller: {}", name))
+            );
+        }
+        PidCommands::Status { binding } => {
+            let response = client.get_binding(&binding).await?;
+            let status = &response.binding;
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(status)?);
+                }
+                OutputFormat::Table => {
+                    let Some(pid) = &status.binding.pid else {
+                        return Err(anyhow::anyhow!(
+                            "Binding '{}' does not use a PID controller",
+                            binding
+                        ));
+                    };
+                    println!("Binding: {} (PID '{}')", binding, pid);
+                    if let Some(temp) = status.temperature {
+                        println!("{:<12} {:.1}°C", "Temperature", temp);
+                    }
+                    match &status.pid_state {
+                        Some(state) => {
+                            if let Some(error) = state.last_error {
+                                println!("{:<12} {:+.2}°C", "Error", error);
+                            }
+                            println!("{:<12} {:.2}", "Integral", state.integral);
+                            println!("{:<12} {:+.2}", "P term", state.p_term);
+                            println!("{:<12} {:+.2}", "I term", state.i_term);
+                            println!("{:<12} {:+.2}", "D term", state.d_term);
+                            if let Some(output) = state.output {
+                                println!("{:<12} {}%", "Output", output);
+                            }
+                        }
+                        None => println!("No control step has completed yet."),
+                    }
+                    if let Some(error) = &status.error {
+                        println!("Last error: {}", error);
+                    }
+                }
+            }
+        }
     }
 
     Ok(())
@@ -582,7 +1232,9 @@ pub async fn handle_config(
     match command {
         ConfigCommands::Show => match format {
             OutputFormat::Json => {
-                println!("{}", serde_json::to_string_pretty(current_config)?);
+                let mut config = current_config.clone();
+                config.token = config.token.map(|_| TOKEN_MASK.to_string());
+                println!("{}", serde_json::to_string_pretty(&config)?);
             }
             OutputFormat::Table => {
                 println!("CLI Configuration:");
@@ -592,11 +1244,20 @@ pub async fn handle_config(
                 println!("{:<20} {}", "Output Format", current_config.output_format);
                 println!("{:<20} {}", "Verbose", current_config.verbose);
                 println!("{:<20} {}s", "Timeout", current_config.timeout);
+                let token = current_config
+                    .token
+                    .as_ref()
+                    .map_or("(none)", |_| TOKEN_MASK);
+                println!("{:<20} {}", "API Token", token);
             }
         },
         ConfigCommands::Set { key, value } => {
             let mut config = current_config.clone();
-            let value_clone = value.clone();
+            let value_clone = if key == "token" {
+                TOKEN_MASK.to_string()
+            } else {
+                value.clone()
+            };
             match key.as_str() {
                 "server_url" => config.server_url = value,
                 "output_format" => {
@@ -616,6 +1277,8 @@ pub async fn handle_config(
                         .parse()
                         .map_err(|_| anyhow::anyhow!("Invalid timeout value. Must be a number"))?;
                 }
+                // An empty value removes the token
+                "token" => config.token = Some(value).filter(|token| !token.is_empty()),
                 _ => return Err(anyhow::anyhow!("Unknown config key: {}", key)),
             }
 
@@ -653,12 +1316,25 @@ pub async fn handle_cfm(
                     if cfm_response.mappings.is_empty() {
                         println!("No CFM mappings configured.");
                     } else {
-                        println!("{:<10} CFM@100%", "Port");
-                        println!("{}", "-".repeat(25));
+                        println!(
+                            "{:<10} {:<10} {:<10} Model",
+                            "Port", "CFM@100%", "Direction"
+                        );
+                        println!("{}", "-".repeat(50));
                         let mut entries: Vec<_> = cfm_response.mappings.iter().collect();
                         entries.sort_by_key(|(port, _)| *port);
                         for (port, cfm) in entries {
-                            println!("{:<10} {:.1}", port, cfm);
+                            let model = cfm_response.models.get(port);
+                            let direction = model
+                                .and_then(|model| model.direction)
+                                .map_or("-".to_string(), |direction| direction.to_string());
+                            println!(
+                                "{:<10} {:<10.1} {:<10} {}",
+                                port,
+                                cfm,
+                                direction,
+                                model.map_or("linear".to_string(), describe_cfm_model)
+                            );
                         }
                     }
                 }
@@ -673,11 +1349,33 @@ pub async fn handle_cfm(
                 }
                 OutputFormat::Table => {
                     println!("Port {} CFM@100%: {:.1}", port, cfm_response.cfm_at_100);
+                    let model = &cfm_response.model;
+                    if !model.pwm_points.is_empty() {
+                        println!("  PWM table: {}", format_cfm_points(&model.pwm_points, "%"));
+                    }
+                    if !model.rpm_points.is_empty() {
+                        println!(
+                            "  RPM table: {}",
+                            format_cfm_points(&model.rpm_points, " RPM")
+                        );
+                    }
+                    if let Some(max_rpm) = model.max_rpm {
+                        println!("  Max RPM: {}", max_rpm);
+                    }
+                    if let Some(direction) = model.direction {
+                        println!("  Direction: {}", direction);
+                    }
                 }
             }
         }
-        CfmCommands::Set { port, cfm_at_100 } => {
-            client.set_cfm(port, cfm_at_100).await?;
+        CfmCommands::Set {
+            port,
+            cfm_at_100,
+            model,
+        } => {
+            let model = CfmModel::try_from(model)
+                .map_err(|e| anyhow::anyhow!("Invalid airflow model: {}", e))?;
+            client.set_cfm(port, cfm_at_100, model).await?;
             println!(
                 "{}",
                 format_success(&format!(
@@ -693,34 +1391,251 @@ pub async fn handle_cfm(
                 format_success(&format!("Deleted CFM mapping for port {}", port))
             );
         }
+        CfmCommands::Airflow => {
+            let airflow = client.get_cfm_airflow().await?;
+            println!(
+                "{}",
+                crate::format::format_controller_airflow(&airflow, &format.into())?
+            );
+        }
+        CfmCommands::Zones => {
+            let zones = client.get_zones_airflow().await?;
+            println!(
+                "{}",
+                crate::format::format_zone_airflow(&zones, &format.into())?
+            );
+        }
     }
 
     Ok(())
 }
 
-/// Generate shell completion script
-pub fn generate_completion(shell: clap_complete::Shell) {
-    use clap::CommandFactory;
-    use clap_complete::generate;
-    use std::io;
+/// Describe how a port's airflow is estimated, e.g. "pwm table, max 1800 RPM"
+fn describe_cfm_model(model: &CfmModel) -> String {
+    let mut parts = Vec::new();
+    if !model.rpm_points.is_empty() {
+        parts.push(format!("rpm table ({} points)", model.rpm_points.len()));
+    }
+    if !model.pwm_points.is_empty() {
+        parts.push(format!("pwm table ({} points)", model.pwm_points.len()));
+    }
+    if let Some(max_rpm) = model.max_rpm {
+        parts.push(format!("max {} RPM", max_rpm));
+    }
+    if parts.is_empty() {
+        "linear".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
 
-    let mut cmd = Cli::command();
-    let bin_name = cmd.get_name().to_string();
-    generate(shell, &mut cmd, bin_name, &mut io::stdout());
+/// Format airflow table points, e.g. "20% 0.0, 100% 45.0"
+fn format_cfm_points(points: &[CfmPoint], unit: &str) -> String {
+    points
+        .iter()
+        .map(|point| format!("{}{} {:.1}", point.at, unit, point.cfm))
+        .collect::<Vec<_>>()
+        .join(", ")
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::client::OpenFanClient;
+/// Handle temperature sensor commands
+pub async fn handle_sensor(
+    client: &OpenFanClient,
+    command: SensorCommands,
+    format: &OutputFormat,
+) -> Result<()> {
+    match command {
+        SensorCommands::List => {
+            let sensor_response = client.get_sensors().await?;
+
+            match format {
+                OutputFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&sensor_response)?);
+                }
+                OutputFormat::Table => {
+                    if sensor_response.sensors.is_empty() {
+                        println!("No temperature sensors found.");
+                    } else {
+                        println!(
+                            "{:<24} {:<8} {:<16} {:>8}",
+                            "Sensor", "Kind", "Label", "Temp"
+                        );
+                        println!("{}", "-".repeat(59));
+                        for sensor in &sensor_response.sensors {
+                            let kind = match sensor.kind {
+                                SensorKind::Hwmon => "hwmon",
+                                SensorKind::File => "file",
+                                SensorKind::Command => "command",
+                                SensorKind::Virtual => "virtual",
+                            };
+                            let temp = match sensor.temperature {
+                                Some(t) => format!("{:.1}°C", t),
+                                None => "error".to_string(),
+                            };
+                            println!(
+                                "{:<24} {:<8} {:<16} {:>8}",
+                                sensor.id,
+                                kind,
+                                sensor.label.as_deref().unwrap_or("-"),
+                                temp
+                            );
+                            if let Some(dominant) = &sensor.dominant {
+                                println!("  {} (dominant: {})", sensor.source, dominant);
+                            }
+                            if let Some(error) = &sensor.error {
+                                println!("  {}", error);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Handle alarms command
+pub async fn handle_alarms(client: &OpenFanClient, format: &OutputFormat) -> Result<()> {
+    let alarm_response = client.get_alarms().await?;
+
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&alarm_response)?);
+        }
+        OutputFormat::Table => {
+            if alarm_response.alarms.is_empty() {
+                println!("No active alarms.");
+            } else {
+                println!(
+                    "{:<16} {:<4} {:<16} {:>6} Message",
+                    "Controller", "Fan", "Alarm", "RPM"
+                );
+                println!("{}", "-".repeat(70));
+                for alarm in &alarm_response.alarms {
+                    let kind = match alarm.kind {
+                        AlarmKind::Stall => "stall",
+                        AlarmKind::LowRpm => "low rpm",
+                        AlarmKind::UnexpectedSpin => "unexpected spin",
+                    };
+                    println!(
+                        "{:<16} {:<4} {:<16} {:>6} {}",
+                        alarm.controller, alarm.fan, kind, alarm.rpm, alarm.message
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a history time argument: a Unix timestamp, or an age before `now`
+/// such as "90s", "30m", "12h" or "7d".
+fn parse_time(value: &str, now: u64) -> Result<u64> {
+    let invalid = || {
+        anyhow::anyhow!(
+            "Invalid time '{}': expected a Unix timestamp or an age such as 30m, 12h or 7d",
+            value
+        )
+    };
+
+    if let Ok(timestamp) = value.parse::<u64>() {
+        return Ok(timestamp);
+    }
+    let (amount, unit) = value.split_at(value.len().saturating_sub(1));
+    let unit_secs = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        _ => return Err(invalid()),
+    };
+    let amount: u64 = amount.parse().map_err(|_| invalid())?;
+    Ok(now.saturating_sub(amount.saturating_mul(unit_secs)))
+}
+
+/// Handle history command
+pub async fn handle_history(
+    client: &OpenFanClient,
+    args: HistoryArgs,
+    format: &OutputFormat,
+) -> Result<()> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let from = parse_time(&args.from, now)?;
+    let to = match &args.to {
+        Some(to) => parse_time(to, now)?,
+        None => now,
+    };
+    if from > to {
+        anyhow::bail!("--from must not be after --to");
+    }
+
+    let history = client.get_history(from, to, args.step).await?;
+
+    if args.csv {
+        print!("{}", crate::format::format_history_csv(&history));
+    } else {
+        println!(
+            "{}",
+            crate::format::format_history(&history, &format.into())?
+        );
+    }
+
+    Ok(())
+}
+
+/// Handle reload command
+pub async fn handle_reload(
+    client: &OpenFanClient,
+    status: bool,
+    format: &OutputFormat,
+) -> Result<()> {
+    let report = if status {
+        match client.get_reload_status().await?.last_reload {
+            Some(report) => report,
+            None => {
+                match format {
+                    OutputFormat::Json => println!("null"),
+                    OutputFormat::Table => println!("No reload since the server started."),
+                }
+                return Ok(());
+            }
+        }
+    } else {
+        client.reload_config().await?
+    };
+
+    println!("{}", crate::format::format_reload(&report, &format.into())?);
+    Ok(())
+}
+
+/// Generate shell completion script
+pub fn generate_completion(shell: clap_complete::Shell) {
+    use clap::CommandFactory;
+    use clap_complete::generate;
+    use std::io;
+
+    let mut cmd = Cli::command();
+    let bin_name = cmd.get_name().to_string();
+    generate(shell, &mut cmd, bin_name, &mut io::stdout());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::OpenFanClient;
     use crate::test_utils::MockServer;
+    use openfan_core::BoardType;
     use std::time::Duration;
 
     /// Create a test client connected to a mock server
     async fn create_test_client() -> (MockServer, OpenFanClient) {
         let mock = MockServer::new();
         let (mock, url) = mock.start().await.unwrap();
-        let client = OpenFanClient::with_config(url, 10, 3, Duration::from_millis(500))
+        let client = OpenFanClient::with_config(url, 10, 3, Duration::from_millis(500), None)
             .await
             .unwrap();
         (mock, client)
@@ -758,6 +1673,31 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_handle_status_watch_json() {
+        let (_mock, client) = create_test_client().await;
+        let mut events = client.stream_events().await.unwrap();
+        assert!(matches!(
+            events.next().await.unwrap(),
+            Some(TelemetryEvent::ConnectionState { .. })
+        ));
+        assert!(matches!(
+            events.next().await.unwrap(),
+            Some(TelemetryEvent::FanStatus { .. })
+        ));
+        assert!(events.next().await.unwrap().is_none());
+
+        let result = handle_status_watch(&client, &OutputFormat::Json).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_status_watch_table() {
+        let (_mock, client) = create_test_client().await;
+        let result = handle_status_watch(&client, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+    }
+
     // ==================== handle_fan tests ====================
 
     #[tokio::test]
@@ -834,6 +1774,150 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    fn calibrate_command(
+        fans: Option<&str>,
+        step: u32,
+        status: bool,
+        cancel: bool,
+        results: bool,
+    ) -> FanCommands {
+        FanCommands::Calibrate {
+            fans: fans.map(str::to_string),
+            step,
+            settle: 0,
+            detach: false,
+            status,
+            cancel,
+            results,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_fan_calibrate() {
+        let (mock, client) = create_test_client().await;
+        let command = calibrate_command(Some("1,3"), 25, false, false, false);
+        let result = handle_fan(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+
+        let status = mock.state().calibration.lock().unwrap().clone().unwrap();
+        assert_eq!(status.fans, vec![1, 3]);
+        assert_eq!(status.step, 25);
+
+        for format in [OutputFormat::Table, OutputFormat::Json] {
+            let command = calibrate_command(None, 10, true, false, false);
+            assert!(handle_fan(&client, command, &format).await.is_ok());
+            let command = calibrate_command(None, 10, false, false, true);
+            assert!(handle_fan(&client, command, &format).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_fan_calibrate_invalid() {
+        let (_mock, client) = create_test_client().await;
+        let command = calibrate_command(Some("0,x"), 10, false, false, false);
+        assert!(
+            handle_fan(&client, command, &OutputFormat::Table)
+                .await
+                .is_err()
+        );
+        let command = calibrate_command(None, 0, false, false, false);
+        assert!(
+            handle_fan(&client, command, &OutputFormat::Table)
+                .await
+                .is_err()
+        );
+
+        // Nothing running to cancel
+        let command = calibrate_command(None, 10, false, true, false);
+        assert!(
+            handle_fan(&client, command, &OutputFormat::Table)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_fan_limits() {
+        let (mock, client) = create_test_client().await;
+        let command = FanCommands::Limits {
+            command: FanLimitCommands::Set {
+                fan_id: 1,
+                min_pwm: Some(30),
+                max_pwm: None,
+                min_rpm: Some(600),
+                no_zero: true,
+            },
+        };
+        assert!(
+            handle_fan(&client, command, &OutputFormat::Table)
+                .await
+                .is_ok()
+        );
+        let limits = mock.state().fan_limits.lock().unwrap()[&1];
+        assert_eq!(limits.min_pwm, Some(30));
+        assert!(!limits.allow_zero);
+
+        for format in [OutputFormat::Table, OutputFormat::Json] {
+            let command = FanCommands::Limits {
+                command: FanLimitCommands::List,
+            };
+            assert!(handle_fan(&client, command, &format).await.is_ok());
+            assert!(handle_status(&client, &format).await.is_ok());
+        }
+
+        let command = FanCommands::Limits {
+            command: FanLimitCommands::Delete { fan_id: 1 },
+        };
+        assert!(
+            handle_fan(&client, command, &OutputFormat::Table)
+                .await
+                .is_ok()
+        );
+        assert!(mock.state().fan_limits.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handle_fan_limits_invalid() {
+        let (_mock, client) = create_test_client().await;
+        // Never stopping a fan needs a minimum PWM
+        let command = FanCommands::Limits {
+            command: FanLimitCommands::Set {
+                fan_id: 0,
+                min_pwm: None,
+                max_pwm: None,
+                min_rpm: None,
+                no_zero: true,
+            },
+        };
+        assert!(
+            handle_fan(&client, command, &OutputFormat::Table)
+                .await
+                .is_err()
+        );
+        let command = FanCommands::Limits {
+            command: FanLimitCommands::Set {
+                fan_id: 0,
+                min_pwm: Some(60),
+                max_pwm: Some(40),
+                min_rpm: None,
+                no_zero: false,
+            },
+        };
+        assert!(
+            handle_fan(&client, command, &OutputFormat::Table)
+                .await
+                .is_err()
+        );
+        let command = FanCommands::Limits {
+            command: FanLimitCommands::Delete { fan_id: 0 },
+        };
+        assert!(
+            handle_fan(&client, command, &OutputFormat::Table)
+                .await
+                .is_err()
+        );
+    }
+
     // ==================== handle_profile tests ====================
 
     #[tokio::test]
@@ -1049,6 +2133,47 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_handle_zone_apply_json() {
+        let (mock, client) = create_test_client().await;
+        let command = ZoneCommands::Apply {
+            name: "cpu".to_string(),
+            pwm: Some(60),
+            rpm: None,
+        };
+        let result = handle_zone(&client, command, &OutputFormat::Json).await;
+        assert!(result.is_ok());
+        assert_eq!(mock.state().pwms.lock().unwrap().get("0"), Some(&60));
+    }
+
+    #[tokio::test]
+    async fn test_handle_zone_apply_partial() {
+        let (mock, client) = create_test_client().await;
+        mock.state().zones.lock().unwrap().insert(
+            "mixed".to_string(),
+            openfan_core::Zone::new(
+                "mixed",
+                vec![ZoneFan::new("default", 4), ZoneFan::new("gpu", 0)],
+            ),
+        );
+
+        let report = client.apply_zone("mixed", "pwm", 80).await.unwrap();
+        assert_eq!(report.applied_count(), 1);
+        assert_eq!(
+            report.results[1].outcome,
+            ZoneFanOutcome::SkippedDisconnected
+        );
+
+        let command = ZoneCommands::Apply {
+            name: "mixed".to_string(),
+            pwm: Some(80),
+            rpm: None,
+        };
+        let result = handle_zone(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("1/2 fans"));
+    }
+
     #[tokio::test]
     async fn test_handle_zone_apply_neither() {
         let (_mock, client) = create_test_client().await;
@@ -1112,6 +2237,7 @@ mod tests {
             name: "aggressive".to_string(),
             points: "20:30,40:50,60:80,80:100".to_string(),
             description: Some("Aggressive cooling curve".to_string()),
+            tuning: CurveTuningArgs::default(),
         };
         let result = handle_curve(&client, command, &OutputFormat::Table).await;
         assert!(result.is_ok());
@@ -1124,6 +2250,7 @@ mod tests {
             name: "bad_curve".to_string(),
             points: "invalid:points".to_string(),
             description: None,
+            tuning: CurveTuningArgs::default(),
         };
         let result = handle_curve(&client, command, &OutputFormat::Table).await;
         assert!(result.is_err());
@@ -1136,6 +2263,50 @@ mod tests {
             name: "default".to_string(),
             points: "25:20,50:50,75:80,90:100".to_string(),
             description: Some("Updated default curve".to_string()),
+            tuning: CurveTuningArgs::default(),
+        };
+        let result = handle_curve(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_curve_add_with_tuning() {
+        let (mock, client) = create_test_client().await;
+        let command = CurveCommands::Add {
+            name: "tuned".to_string(),
+            points: "30:20,80:100".to_string(),
+            description: None,
+            tuning: CurveTuningArgs {
+                hysteresis: Some(3.0),
+                ramp_up: Some(10.0),
+                ramp_down: Some(2.0),
+                smoothing: None,
+            },
+        };
+        let result = handle_curve(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+
+        let curves = mock.state().curves.lock().unwrap();
+        let tuning = curves.get("tuned").unwrap().tuning;
+        assert_eq!(tuning.hysteresis_c, Some(3.0));
+        assert_eq!(tuning.ramp_up_pwm_per_sec, Some(10.0));
+        assert_eq!(tuning.ramp_down_pwm_per_sec, Some(2.0));
+        assert_eq!(tuning.smoothing_secs, None);
+    }
+
+    #[tokio::test]
+    async fn test_handle_curve_get_with_tuning_table() {
+        let (mock, client) = create_test_client().await;
+        mock.state()
+            .curves
+            .lock()
+            .unwrap()
+            .get_mut("default")
+            .unwrap()
+            .tuning
+            .hysteresis_c = Some(2.0);
+        let command = CurveCommands::Get {
+            name: "default".to_string(),
         };
         let result = handle_curve(&client, command, &OutputFormat::Table).await;
         assert!(result.is_ok());
@@ -1173,6 +2344,404 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_handle_curve_bind_and_unbind() {
+        let (mock, client) = create_test_client().await;
+        let command = CurveCommands::Bind {
+            curve: Some("default".to_string()),
+            pid: None,
+            sensor: "/sys/class/thermal/thermal_zone0/temp".to_string(),
+            zone: None,
+            fans: Some("0,1".to_string()),
+            name: Some("cpu".to_string()),
+            interval: Some(5),
+        };
+        let result = handle_curve(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        {
+            let bindings = mock.state().bindings.lock().unwrap();
+            let status = bindings.get("cpu").unwrap();
+            assert_eq!(status.binding.fans, vec![0, 1]);
+            assert_eq!(status.binding.interval_secs, 5);
+        }
+
+        let result = handle_curve(&client, CurveCommands::Bindings, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        let result = handle_curve(&client, CurveCommands::Bindings, &OutputFormat::Json).await;
+        assert!(result.is_ok());
+
+        let command = CurveCommands::Unbind {
+            name: "cpu".to_string(),
+        };
+        let result = handle_curve(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        assert!(mock.state().bindings.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handle_curve_bind_zone_default_name() {
+        let (mock, client) = create_test_client().await;
+        let command = CurveCommands::Bind {
+            curve: Some("default".to_string()),
+            pid: None,
+            sensor: "/tmp/temp".to_string(),
+            zone: Some("cpu".to_string()),
+            fans: None,
+            name: None,
+            interval: None,
+        };
+        let result = handle_curve(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        assert!(mock.state().bindings.lock().unwrap().contains_key("cpu"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_curve_bind_invalid_fans() {
+        let (_mock, client) = create_test_client().await;
+        let command = CurveCommands::Bind {
+            curve: Some("default".to_string()),
+            pid: None,
+            sensor: "/tmp/temp".to_string(),
+            zone: None,
+            fans: Some("0,x".to_string()),
+            name: None,
+            interval: None,
+        };
+        let result = handle_curve(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("Invalid fan ID"));
+    }
+
+    #[tokio::test]
+    async fn test_handle_curve_unbind_not_found() {
+        let (_mock, client) = create_test_client().await;
+        let command = CurveCommands::Unbind {
+            name: "missing".to_string(),
+        };
+        let result = handle_curve(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_err());
+    }
+
+    // ==================== handle_pid tests ====================
+
+    fn pid_add_command(name: &str) -> PidCommands {
+        PidCommands::Add {
+            name: name.to_string(),
+            setpoint: 40.0,
+            kp: 5.0,
+            ki: 0.1,
+            kd: 0.0,
+            min_pwm: Some(20),
+            max_pwm: None,
+            description: Some("Water loop".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_pid_add_list_get() {
+        let (mock, client) = create_test_client().await;
+        let result = handle_pid(&client, pid_add_command("loop"), &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        {
+            let pids = mock.state().pids.lock().unwrap();
+            let pid = pids.get("loop").unwrap();
+            assert_eq!(pid.min_pwm, 20);
+            assert_eq!(pid.max_pwm, 100);
+        }
+
+        let result = handle_pid(&client, PidCommands::List, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        let result = handle_pid(&client, PidCommands::List, &OutputFormat::Json).await;
+        assert!(result.is_ok());
+
+        let command = PidCommands::Get {
+            name: "loop".to_string(),
+        };
+        let result = handle_pid(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_pid_update_and_delete() {
+        let (mock, client) = create_test_client().await;
+        handle_pid(&client, pid_add_command("loop"), &OutputFormat::Table)
+            .await
+            .unwrap();
+
+        let command = PidCommands::Update {
+            name: "loop".to_string(),
+            setpoint: Some(38.0),
+            kp: None,
+            ki: None,
+            kd: Some(1.0),
+            min_pwm: None,
+            max_pwm: None,
+            description: None,
+        };
+        let result = handle_pid(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        {
+            let pids = mock.state().pids.lock().unwrap();
+            let pid = pids.get("loop").unwrap();
+            assert_eq!(pid.setpoint_c, 38.0);
+            assert_eq!(pid.kp, 5.0);
+            assert_eq!(pid.kd, 1.0);
+        }
+
+        let command = PidCommands::Delete {
+            name: "loop".to_string(),
+        };
+        let result = handle_pid(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        assert!(mock.state().pids.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_handle_pid_get_not_found() {
+        let (_mock, client) = create_test_client().await;
+        let command = PidCommands::Get {
+            name: "missing".to_string(),
+        };
+        let result = handle_pid(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_pid_bind_and_status() {
+        let (mock, client) = create_test_client().await;
+        handle_pid(&client, pid_add_command("loop"), &OutputFormat::Table)
+            .await
+            .unwrap();
+
+        let command = CurveCommands::Bind {
+            curve: None,
+            pid: Some("loop".to_string()),
+            sensor: "/tmp/temp".to_string(),
+            zone: Some("cpu".to_string()),
+            fans: None,
+            name: Some("water".to_string()),
+            interval: None,
+        };
+        let result = handle_curve(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        {
+            let bindings = mock.state().bindings.lock().unwrap();
+            let status = bindings.get("water").unwrap();
+            assert_eq!(status.binding.pid.as_deref(), Some("loop"));
+            assert!(status.binding.curve.is_none());
+        }
+
+        let command = PidCommands::Status {
+            binding: "water".to_string(),
+        };
+        let result = handle_pid(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        let command = PidCommands::Status {
+            binding: "water".to_string(),
+        };
+        let result = handle_pid(&client, command, &OutputFormat::Json).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_pid_bind_unknown_pid() {
+        let (_mock, client) = create_test_client().await;
+        let command = CurveCommands::Bind {
+            curve: None,
+            pid: Some("missing".to_string()),
+            sensor: "/tmp/temp".to_string(),
+            zone: Some("cpu".to_string()),
+            fans: None,
+            name: None,
+            interval: None,
+        };
+        let result = handle_curve(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_err());
+    }
+
+    // ==================== handle_controller tests ====================
+
+    #[tokio::test]
+    async fn test_handle_controller_scan_table() {
+        let (_mock, client) = create_test_client().await;
+        let result =
+            handle_controller(&client, ControllerCommands::Scan, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_controller_scan_json() {
+        let (_mock, client) = create_test_client().await;
+        let response = client.discover_controllers().await.unwrap();
+        assert_eq!(response.ports.len(), 2);
+        assert_eq!(response.ports[0].controller.as_deref(), Some("default"));
+        assert!(response.ports[1].firmware.is_some());
+
+        let result =
+            handle_controller(&client, ControllerCommands::Scan, &OutputFormat::Json).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_controller_add_and_remove() {
+        let (_mock, client) = create_test_client().await;
+        let add = ControllerCommands::Add {
+            id: "gpu".to_string(),
+            device: Some("/dev/ttyACM1".to_string()),
+            serial_number: None,
+            board: BoardType::Custom { fan_count: 4 },
+            description: Some("GPU fans".to_string()),
+            persist: false,
+        };
+        let result = handle_controller(&client, add, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+
+        let remove = ControllerCommands::Remove {
+            id: "gpu".to_string(),
+            persist: true,
+        };
+        let result = handle_controller(&client, remove, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+
+        // The mock refuses to add or remove the default controller
+        let remove = ControllerCommands::Remove {
+            id: "default".to_string(),
+            persist: false,
+        };
+        let result = handle_controller(&client, remove, &OutputFormat::Table).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_controller_add_json() {
+        let (_mock, client) = create_test_client().await;
+        let mut controller = ControllerConfig::new("gpu", "", BoardType::Custom { fan_count: 4 });
+        controller.serial_number = Some("E6614103E7452D30".to_string());
+        let info = client
+            .add_controller(&AddControllerRequest {
+                controller,
+                persist: false,
+            })
+            .await
+            .unwrap();
+        assert_eq!(info.fan_count, 4);
+        assert_eq!(info.device_path, None);
+        assert_eq!(info.serial_number.as_deref(), Some("E6614103E7452D30"));
+
+        let add = ControllerCommands::Add {
+            id: "gpu".to_string(),
+            device: Some("/dev/ttyACM1".to_string()),
+            serial_number: None,
+            board: BoardType::OpenFanStandard,
+            description: None,
+            persist: true,
+        };
+        let result = handle_controller(&client, add, &OutputFormat::Json).await;
+        assert!(result.is_ok());
+    }
+
+    // ==================== handle_sensor tests ====================
+
+    #[tokio::test]
+    async fn test_handle_sensor_list_json() {
+        let (_mock, client) = create_test_client().await;
+        let result = handle_sensor(&client, SensorCommands::List, &OutputFormat::Json).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_sensor_list_table() {
+        let (_mock, client) = create_test_client().await;
+        let result = handle_sensor(&client, SensorCommands::List, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+    }
+
+    // ==================== handle_history tests ====================
+
+    #[test]
+    fn test_parse_time() {
+        let now = 1_700_000_000;
+        assert_eq!(parse_time("1690000000", now).unwrap(), 1_690_000_000);
+        assert_eq!(parse_time("90s", now).unwrap(), now - 90);
+        assert_eq!(parse_time("30m", now).unwrap(), now - 1800);
+        assert_eq!(parse_time("12h", now).unwrap(), now - 43_200);
+        assert_eq!(parse_time("7d", now).unwrap(), now - 604_800);
+        assert!(parse_time("7w", now).is_err());
+        assert!(parse_time("h", now).is_err());
+        assert!(parse_time("", now).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_history() {
+        let (_mock, client) = create_test_client().await;
+        let response = client.get_history(1000, 2000, Some(60)).await.unwrap();
+        assert_eq!(response.step_secs, 60);
+        assert_eq!(response.points.len(), 2);
+        assert_eq!(response.points[1].timestamp, 1020);
+
+        for (format, csv) in [
+            (OutputFormat::Table, false),
+            (OutputFormat::Json, false),
+            (OutputFormat::Table, true),
+        ] {
+            let args = HistoryArgs {
+                from: "2h".to_string(),
+                to: Some("1h".to_string()),
+                step: None,
+                csv,
+            };
+            assert!(handle_history(&client, args, &format).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handle_history_rejects_reversed_range() {
+        let (_mock, client) = create_test_client().await;
+        let args = HistoryArgs {
+            from: "1h".to_string(),
+            to: Some("2h".to_string()),
+            step: None,
+            csv: false,
+        };
+        let result = handle_history(&client, args, &OutputFormat::Table).await;
+        assert!(result.unwrap_err().to_string().contains("--from"));
+    }
+
+    // ==================== handle_alarms tests ====================
+
+    #[tokio::test]
+    async fn test_handle_alarms_json() {
+        let (_mock, client) = create_test_client().await;
+        let response = client.get_alarms().await.unwrap();
+        assert_eq!(response.alarms.len(), 1);
+        assert_eq!(response.alarms[0].kind, AlarmKind::Stall);
+
+        let result = handle_alarms(&client, &OutputFormat::Json).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_alarms_table() {
+        let (_mock, client) = create_test_client().await;
+        let result = handle_alarms(&client, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+    }
+
+    // ==================== handle_reload tests ====================
+
+    #[tokio::test]
+    async fn test_handle_reload() {
+        let (_mock, client) = create_test_client().await;
+        let report = client.reload_config().await.unwrap();
+        assert!(report.success);
+        assert_eq!(report.changes, vec!["[alarms]".to_string()]);
+
+        for format in [OutputFormat::Table, OutputFormat::Json] {
+            assert!(handle_reload(&client, false, &format).await.is_ok());
+            assert!(handle_reload(&client, true, &format).await.is_ok());
+        }
+    }
+
     // ==================== handle_cfm tests ====================
 
     #[tokio::test]
@@ -1213,9 +2782,62 @@ mod tests {
         let command = CfmCommands::Set {
             port: 2,
             cfm_at_100: 55.0,
+            model: CfmModelArgs::default(),
+        };
+        let result = handle_cfm(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_cfm_set_with_model() {
+        let (_mock, client) = create_test_client().await;
+        let command = CfmCommands::Set {
+            port: 2,
+            cfm_at_100: 55.0,
+            model: CfmModelArgs {
+                pwm_points: Some("20:0,60:35,100:55".to_string()),
+                rpm_points: None,
+                max_rpm: Some(1800),
+                direction: Some(openfan_core::AirflowDirection::Intake),
+            },
         };
         let result = handle_cfm(&client, command, &OutputFormat::Table).await;
         assert!(result.is_ok());
+
+        let response = client.get_cfm(2).await.unwrap();
+        assert_eq!(response.model.pwm_points.len(), 3);
+        assert_eq!(response.model.max_rpm, Some(1800));
+
+        let result = handle_cfm(&client, CfmCommands::Get { port: 2 }, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+        let result = handle_cfm(&client, CfmCommands::List, &OutputFormat::Table).await;
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_handle_cfm_set_invalid_points() {
+        let (_mock, client) = create_test_client().await;
+        let command = CfmCommands::Set {
+            port: 2,
+            cfm_at_100: 55.0,
+            model: CfmModelArgs {
+                pwm_points: Some("20:0".to_string()),
+                ..Default::default()
+            },
+        };
+        let result = handle_cfm(&client, command, &OutputFormat::Table).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_handle_cfm_airflow() {
+        let (_mock, client) = create_test_client().await;
+        for format in [OutputFormat::Table, OutputFormat::Json] {
+            let result = handle_cfm(&client, CfmCommands::Airflow, &format).await;
+            assert!(result.is_ok());
+            let result = handle_cfm(&client, CfmCommands::Zones, &format).await;
+            assert!(result.is_ok());
+        }
     }
 
     #[tokio::test]
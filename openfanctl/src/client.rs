@@ -1,8 +1,11 @@
 //! HTTP client for communicating with the OpenFAN server.
 
 use anyhow::{Context, Result};
-use openfan_core::{BoardInfo, CurvePoint, api, types::FanProfile};
-use reqwest::{Client, Response, StatusCode};
+use openfan_core::{
+    BoardInfo, CfmModel, CurvePoint, CurveTuning, FanLimits, api, types::FanProfile,
+};
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderValue};
+use reqwest::{Client, ClientBuilder, Response, StatusCode};
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::time::Duration;
@@ -12,6 +15,21 @@ fn normalize_url(url: &str) -> String {
     url.trim_end_matches('/').to_string()
 }
 
+/// Start an HTTP client that identifies openfanctl and sends the API token.
+fn client_builder(token: Option<&str>) -> Result<ClientBuilder> {
+    let mut headers = HeaderMap::new();
+    if let Some(token) = token {
+        let mut value = HeaderValue::from_str(&format!("Bearer {}", token))
+            .context("API token contains invalid characters")?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+
+    Ok(Client::builder()
+        .user_agent(concat!("openfanctl/", env!("CARGO_PKG_VERSION")))
+        .default_headers(headers))
+}
+
 /// HTTP client for communicating with the OpenFAN daemon's REST API.
 ///
 /// This client handles all HTTP communication with the server, including:
@@ -42,6 +60,7 @@ fn normalize_url(url: &str) -> String {
 ///     10,  // timeout in seconds
 ///     3,   // max retries
 ///     Duration::from_millis(500),  // initial retry delay
+///     None,  // API token
 /// ).await?;
 ///
 /// let info = client.get_info().await?;
@@ -58,6 +77,8 @@ pub struct OpenFanClient {
     board_info: BoardInfo,
     /// Controller ID used for all controller-scoped API routes.
     controller_id: String,
+    /// API token sent with every request, if the server requires one.
+    token: Option<String>,
 }
 
 impl OpenFanClient {
@@ -97,6 +118,7 @@ impl OpenFanClient {
     /// * `timeout_secs` - Request timeout in seconds
     /// * `max_retries` - Maximum number of retry attempts for failed requests
     /// * `retry_delay` - Initial delay between retries (uses exponential backoff)
+    /// * `token` - API token sent as `Authorization: Bearer`, if the server requires one
     ///
     /// # Errors
     ///
@@ -109,10 +131,10 @@ impl OpenFanClient {
         timeout_secs: u64,
         max_retries: u32,
         retry_delay: Duration,
+        token: Option<String>,
     ) -> Result<Self> {
-        let client = Client::builder()
+        let client = client_builder(token.as_deref())?
             .timeout(Duration::from_secs(timeout_secs))
-            .user_agent(concat!("openfanctl/", env!("CARGO_PKG_VERSION")))
             .build()
             .context("Failed to create HTTP client")?;
 
@@ -134,6 +156,7 @@ impl OpenFanClient {
                 baud_rate: 115200,
             },
             controller_id: Self::DEFAULT_CONTROLLER.to_string(),
+            token: token.clone(),
         };
 
         // Fetch board info from server
@@ -149,6 +172,7 @@ impl OpenFanClient {
             retry_delay,
             board_info: info.board_info,
             controller_id: Self::DEFAULT_CONTROLLER.to_string(),
+            token,
         })
     }
 
@@ -175,8 +199,17 @@ impl OpenFanClient {
             let error_msg = match status {
                 StatusCode::NOT_FOUND => format!("Endpoint {} not found", endpoint),
                 StatusCode::BAD_REQUEST => format!("Bad request to {}: {}", endpoint, text),
-                StatusCode::UNAUTHORIZED => format!("Unauthorized access to {}", endpoint),
-                StatusCode::FORBIDDEN => format!("Access forbidden to {}", endpoint),
+                StatusCode::UNAUTHORIZED => format!(
+                    "Unauthorized access to {}: set a valid API token with OPENFAN_TOKEN \
+                     or `token` in the CLI config",
+                    endpoint
+                ),
+                StatusCode::FORBIDDEN => {
+                    format!(
+                        "Access forbidden to {}: the API token is read-only",
+                        endpoint
+                    )
+                }
                 StatusCode::INTERNAL_SERVER_ERROR => {
                     format!("Server error at {}: {}", endpoint, text)
                 }
@@ -768,7 +801,17 @@ impl OpenFanClient {
     /// - The zone name is empty or whitespace
     /// - The mode is invalid
     /// - The value is out of range for the specified mode
-    pub async fn apply_zone(&self, name: &str, mode: &str, value: u16) -> Result<()> {
+    ///
+    /// # Returns
+    ///
+    /// Returns the per-fan report of the operation. Fans that could not be
+    /// set are reported there rather than as an error.
+    pub async fn apply_zone(
+        &self,
+        name: &str,
+        mode: &str,
+        value: u16,
+    ) -> Result<api::ZoneApplyResponse> {
         if name.trim().is_empty() {
             return Err(anyhow::anyhow!("Zone name cannot be empty"));
         }
@@ -808,7 +851,6 @@ impl OpenFanClient {
 
         self.execute_with_retry(endpoint, || self.client.get(&url).send())
             .await
-            .map(|_: ()| ())
     }
 
     // =========================================================================
@@ -863,6 +905,7 @@ impl OpenFanClient {
     /// * `name` - Name for the new curve
     /// * `points` - Temperature-to-PWM curve points
     /// * `description` - Optional description
+    /// * `tuning` - Optional smoothing, hysteresis and ramp limits
     ///
     /// # Errors
     ///
@@ -875,6 +918,7 @@ impl OpenFanClient {
         name: &str,
         points: Vec<CurvePoint>,
         description: Option<String>,
+        tuning: CurveTuning,
     ) -> Result<()> {
         if name.trim().is_empty() {
             return Err(anyhow::anyhow!("Curve name cannot be empty"));
@@ -884,12 +928,12 @@ impl OpenFanClient {
             "{}/api/v0/controller/{}/curves/add",
             self.base_url, self.controller_id
         );
-        let mut request_body = HashMap::new();
-        request_body.insert("name", serde_json::Value::String(name.to_string()));
-        request_body.insert("points", serde_json::to_value(&points)?);
-        if let Some(desc) = description {
-            request_body.insert("description", serde_json::Value::String(desc));
-        }
+        let request_body = api::AddCurveRequest {
+            name: name.to_string(),
+            points,
+            description,
+            tuning,
+        };
 
         let endpoint = &format!("controller/{}/curves/add", self.controller_id);
 
@@ -913,6 +957,7 @@ impl OpenFanClient {
     /// * `name` - Name of the curve to update
     /// * `points` - New temperature-to-PWM curve points
     /// * `description` - Optional new description
+    /// * `tuning` - Tuning settings to change; unset ones keep their current value
     ///
     /// # Errors
     ///
@@ -925,6 +970,7 @@ impl OpenFanClient {
         name: &str,
         points: Vec<CurvePoint>,
         description: Option<String>,
+        tuning: CurveTuning,
     ) -> Result<()> {
         if name.trim().is_empty() {
             return Err(anyhow::anyhow!("Curve name cannot be empty"));
@@ -935,11 +981,11 @@ impl OpenFanClient {
             "{}/api/v0/controller/{}/curve/{}/update",
             self.base_url, self.controller_id, encoded_name
         );
-        let mut request_body = HashMap::new();
-        request_body.insert("points", serde_json::to_value(&points)?);
-        if let Some(desc) = description {
-            request_body.insert("description", serde_json::Value::String(desc));
-        }
+        let request_body = api::UpdateCurveRequest {
+            points,
+            description,
+            tuning,
+        };
 
         let endpoint = &format!("controller/{}/curve/{}/update", self.controller_id, name);
 
@@ -1021,6 +1067,588 @@ impl OpenFanClient {
             .await
     }
 
+    // =========================================================================
+    // PID controller operations
+    // =========================================================================
+
+    /// Retrieve all configured PID controllers.
+    ///
+    /// # Returns
+    ///
+    /// Returns a map of PID controller names to their settings.
+    pub async fn get_pids(&self) -> Result<api::PidControllerResponse> {
+        let url = format!(
+            "{}/api/v0/controller/{}/pids/list",
+            self.base_url, self.controller_id
+        );
+        let endpoint = &format!("controller/{}/pids/list", self.controller_id);
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    /// Retrieve a specific PID controller by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the PID controller
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PID name is empty or whitespace.
+    pub async fn get_pid(&self, name: &str) -> Result<api::SinglePidResponse> {
+        if name.trim().is_empty() {
+            return Err(anyhow::anyhow!("PID name cannot be empty"));
+        }
+
+        let encoded_name = name.replace(' ', "%20").replace('&', "%26");
+        let url = format!(
+            "{}/api/v0/controller/{}/pid/{}/get",
+            self.base_url, self.controller_id, encoded_name
+        );
+        let endpoint = &format!("controller/{}/pid/{}/get", self.controller_id, name);
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    /// Create a new PID controller.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - PID controller definition
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The PID name is empty or whitespace
+    /// - A PID controller with the same name already exists
+    /// - The gains, setpoint or output range are invalid
+    pub async fn add_pid(&self, request: &api::AddPidRequest) -> Result<()> {
+        if request.name.trim().is_empty() {
+            return Err(anyhow::anyhow!("PID name cannot be empty"));
+        }
+
+        let url = format!(
+            "{}/api/v0/controller/{}/pids/add",
+            self.base_url, self.controller_id
+        );
+        let endpoint = &format!("controller/{}/pids/add", self.controller_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .with_context(|| format!("Failed to send add PID request to {}", endpoint))?;
+
+        Self::handle_response(response, endpoint)
+            .await
+            .map(|_: ()| ())
+    }
+
+    /// Update an existing PID controller.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the PID controller to update
+    /// * `request` - Settings to change; unset ones keep their current value
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The PID name is empty or whitespace
+    /// - The PID controller doesn't exist
+    /// - The resulting settings are invalid
+    pub async fn update_pid(&self, name: &str, request: &api::UpdatePidRequest) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(anyhow::anyhow!("PID name cannot be empty"));
+        }
+
+        let encoded_name = name.replace(' ', "%20").replace('&', "%26");
+        let url = format!(
+            "{}/api/v0/controller/{}/pid/{}/update",
+            self.base_url, self.controller_id, encoded_name
+        );
+        let endpoint = &format!("controller/{}/pid/{}/update", self.controller_id, name);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .with_context(|| format!("Failed to send update PID request to {}", endpoint))?;
+
+        Self::handle_response(response, endpoint)
+            .await
+            .map(|_: ()| ())
+    }
+
+    /// Delete a PID controller by name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the PID controller to delete
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the PID name is empty or whitespace.
+    pub async fn delete_pid(&self, name: &str) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(anyhow::anyhow!("PID name cannot be empty"));
+        }
+
+        let encoded_name = name.replace(' ', "%20").replace('&', "%26");
+        let url = format!(
+            "{}/api/v0/controller/{}/pid/{}",
+            self.base_url, self.controller_id, encoded_name
+        );
+        let endpoint = &format!("controller/{}/pid/{}", self.controller_id, name);
+
+        self.execute_with_retry(endpoint, || self.client.delete(&url).send())
+            .await
+            .map(|_: ()| ())
+    }
+
+    // =========================================================================
+    // Sensor operations
+    // =========================================================================
+
+    /// Retrieve all temperature sensors discovered by the server.
+    ///
+    /// # Returns
+    ///
+    /// Returns each sensor's ID, chip, label and current temperature.
+    pub async fn get_sensors(&self) -> Result<api::SensorListResponse> {
+        let url = format!("{}/api/v0/sensors", self.base_url);
+        let endpoint = "sensors";
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    // =========================================================================
+    // Alarm operations
+    // =========================================================================
+
+    /// Retrieve the active fan alarms of all controllers.
+    ///
+    /// # Returns
+    ///
+    /// Returns each alarm's controller, fan, kind and last RPM reading.
+    pub async fn get_alarms(&self) -> Result<api::AlarmListResponse> {
+        let url = format!("{}/api/v0/alarms", self.base_url);
+        let endpoint = "alarms";
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    // =========================================================================
+    // Configuration reload
+    // =========================================================================
+
+    /// Ask the server to reload its configuration and data files.
+    ///
+    /// # Returns
+    ///
+    /// Returns the changes applied and the warnings for settings that need a
+    /// restart.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new configuration is rejected, in which case
+    /// the server keeps the previous one.
+    pub async fn reload_config(&self) -> Result<api::ReloadReport> {
+        let url = format!("{}/api/v0/config/reload", self.base_url);
+        let endpoint = "config/reload";
+
+        let response = self
+            .client
+            .post(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to send reload request to {}", endpoint))?;
+
+        Self::handle_response(response, endpoint).await
+    }
+
+    /// Retrieve the outcome of the server's last reload.
+    ///
+    /// # Returns
+    ///
+    /// Returns the last reload report, if the server reloaded since it started.
+    pub async fn get_reload_status(&self) -> Result<api::ReloadStatusResponse> {
+        let url = format!("{}/api/v0/config/reload", self.base_url);
+        let endpoint = "config/reload";
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    // =========================================================================
+    // Live telemetry
+    // =========================================================================
+
+    /// Subscribe to the live telemetry stream of this client's controller.
+    ///
+    /// The stream carries the events of the controller and those of no
+    /// controller (sensor readings, zone changes). It is not subject to the
+    /// request timeout and is not retried.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server is unreachable or rejects the request.
+    pub async fn stream_events(&self) -> Result<EventStream> {
+        let url = format!(
+            "{}/api/v0/events?controller={}",
+            self.base_url, self.controller_id
+        );
+        let endpoint = "events";
+
+        // The request client's timeout would cut the stream, so only bound
+        // the connection
+        let client = client_builder(self.token.as_deref())?
+            .connect_timeout(Duration::from_secs(10))
+            .build()
+            .context("Failed to create event stream client")?;
+
+        let response = client
+            .get(&url)
+            .send()
+            .await
+            .with_context(|| format!("Failed to connect to {}", endpoint))?;
+
+        if !response.status().is_success() {
+            Self::handle_response::<serde_json::Value>(response, endpoint).await?;
+            anyhow::bail!("Unexpected response from {}", endpoint);
+        }
+
+        Ok(EventStream {
+            response,
+            buffer: String::new(),
+        })
+    }
+    // =========================================================================
+
+    /// Retrieve all curve bindings with their control loop status.
+    ///
+    /// # Returns
+    ///
+    /// Returns a map of binding names to their definitions and loop status.
+    pub async fn get_bindings(&self) -> Result<api::CurveBindingResponse> {
+        let url = format!(
+            "{}/api/v0/controller/{}/bindings/list",
+            self.base_url, self.controller_id
+        );
+        let endpoint = &format!("controller/{}/bindings/list", self.controller_id);
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    /// Bind a thermal curve or PID controller to a temperature sensor and a zone or fan list.
+    ///
+    /// The server starts a control loop for the binding immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Binding definition
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The binding name, curve, PID or sensor is empty or whitespace
+    /// - Neither or both of curve and PID are specified
+    /// - Neither or both of zone and fans are specified
+    /// - Any fan ID is invalid for this board type
+    pub async fn add_binding(&self, request: &api::AddBindingRequest) -> Result<()> {
+        if request.name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Binding name cannot be empty"));
+        }
+        match (&request.curve, &request.pid) {
+            (Some(curve), None) if curve.trim().is_empty() => {
+                return Err(anyhow::anyhow!("Curve name cannot be empty"));
+            }
+            (None, Some(pid)) if pid.trim().is_empty() => {
+                return Err(anyhow::anyhow!("PID name cannot be empty"));
+            }
+            (Some(_), None) | (None, Some(_)) => {}
+            _ => {
+                return Err(anyhow::anyhow!(
+                    "Specify either a curve or a PID controller"
+                ));
+            }
+        }
+        if request.sensor.trim().is_empty() {
+            return Err(anyhow::anyhow!("Sensor cannot be empty"));
+        }
+        if request.zone.is_some() != request.fans.is_empty() {
+            return Err(anyhow::anyhow!("Specify either a zone or a list of fans"));
+        }
+        for &fan_id in &request.fans {
+            self.board_info.validate_fan_id(fan_id)?;
+        }
+
+        let url = format!(
+            "{}/api/v0/controller/{}/bindings/add",
+            self.base_url, self.controller_id
+        );
+        let endpoint = &format!("controller/{}/bindings/add", self.controller_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .with_context(|| format!("Failed to send add binding request to {}", endpoint))?;
+
+        Self::handle_response(response, endpoint)
+            .await
+            .map(|_: ()| ())
+    }
+
+    /// Retrieve a curve binding with its control loop status.
+    ///
+    /// For PID bindings the status includes the controller's integral and
+    /// last error.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the binding
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the binding name is empty or whitespace.
+    pub async fn get_binding(&self, name: &str) -> Result<api::SingleBindingResponse> {
+        if name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Binding name cannot be empty"));
+        }
+
+        let encoded_name = name.replace(' ', "%20").replace('&', "%26");
+        let url = format!(
+            "{}/api/v0/controller/{}/binding/{}/get",
+            self.base_url, self.controller_id, encoded_name
+        );
+        let endpoint = &format!("controller/{}/binding/{}/get", self.controller_id, name);
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    /// Delete a curve binding by name, stopping its control loop.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - Name of the binding to delete
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the binding name is empty or whitespace.
+    pub async fn delete_binding(&self, name: &str) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(anyhow::anyhow!("Binding name cannot be empty"));
+        }
+
+        let encoded_name = name.replace(' ', "%20").replace('&', "%26");
+        let url = format!(
+            "{}/api/v0/controller/{}/binding/{}",
+            self.base_url, self.controller_id, encoded_name
+        );
+        let endpoint = &format!("controller/{}/binding/{}", self.controller_id, name);
+
+        self.execute_with_retry(endpoint, || self.client.delete(&url).send())
+            .await
+            .map(|_: ()| ())
+    }
+
+    // =========================================================================
+    // History operations
+    // =========================================================================
+
+    /// Retrieve the recorded fan history of this client's controller.
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - Start of the range, as a Unix timestamp
+    /// * `to` - End of the range, as a Unix timestamp
+    /// * `step` - Seconds covered by each point (default: the server's resolution)
+    ///
+    /// # Returns
+    ///
+    /// Returns the points with readings, oldest first, and the step used.
+    pub async fn get_history(
+        &self,
+        from: u64,
+        to: u64,
+        step: Option<u64>,
+    ) -> Result<api::HistoryResponse> {
+        let mut url = format!(
+            "{}/api/v0/controller/{}/history?from={}&to={}",
+            self.base_url, self.controller_id, from, to
+        );
+        if let Some(step) = step {
+            url.push_str(&format!("&step={}", step));
+        }
+        let endpoint = &format!("controller/{}/history", self.controller_id);
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    // =========================================================================
+    // Calibration operations
+    // =========================================================================
+
+    /// Start calibrating fans of this client's controller.
+    ///
+    /// The server sweeps each fan's PWM in the background and measures its
+    /// RPM; follow the job with [`Self::get_calibration_status`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - A fan ID is invalid for this board type
+    /// - The step or settle time is out of range
+    /// - A calibration is already running on the controller
+    pub async fn start_calibration(
+        &self,
+        request: &api::CalibrationRequest,
+    ) -> Result<api::CalibrationStatus> {
+        for &fan_id in &request.fans {
+            self.board_info.validate_fan_id(fan_id)?;
+        }
+        request.validate().map_err(|e| anyhow::anyhow!(e))?;
+
+        let url = format!(
+            "{}/api/v0/controller/{}/fan/calibration",
+            self.base_url, self.controller_id
+        );
+        let endpoint = &format!("controller/{}/fan/calibration", self.controller_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .with_context(|| format!("Failed to send calibration request to {}", endpoint))?;
+
+        Self::handle_response(response, endpoint).await
+    }
+
+    /// Retrieve the running or last calibration job of this client's controller.
+    pub async fn get_calibration_status(&self) -> Result<api::CalibrationStatusResponse> {
+        let url = format!(
+            "{}/api/v0/controller/{}/fan/calibration",
+            self.base_url, self.controller_id
+        );
+        let endpoint = &format!("controller/{}/fan/calibration", self.controller_id);
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    /// Cancel the running calibration job of this client's controller.
+    ///
+    /// The server restores the fans and discards the measurements.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no calibration is running.
+    pub async fn cancel_calibration(&self) -> Result<String> {
+        let url = format!(
+            "{}/api/v0/controller/{}/fan/calibration",
+            self.base_url, self.controller_id
+        );
+        let endpoint = &format!("controller/{}/fan/calibration", self.controller_id);
+
+        self.execute_with_retry(endpoint, || self.client.delete(&url).send())
+            .await
+    }
+
+    /// Retrieve the saved calibration results of this client's controller.
+    pub async fn get_calibration_results(&self) -> Result<api::CalibrationResultsResponse> {
+        let url = format!(
+            "{}/api/v0/controller/{}/fan/calibration/results",
+            self.base_url, self.controller_id
+        );
+        let endpoint = &format!("controller/{}/fan/calibration/results", self.controller_id);
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    // =========================================================================
+    // Fan limit operations
+    // =========================================================================
+
+    /// Retrieve the safety limits of this client's controller's fans.
+    pub async fn get_fan_limits(&self) -> Result<api::FanLimitsResponse> {
+        let url = format!(
+            "{}/api/v0/controller/{}/fan/limits",
+            self.base_url, self.controller_id
+        );
+        let endpoint = &format!("controller/{}/fan/limits", self.controller_id);
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    /// Set the safety limits of a fan, replacing earlier ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - Fan ID is invalid for this board type
+    /// - The limits are invalid
+    pub async fn set_fan_limits(&self, fan_id: u8, limits: &FanLimits) -> Result<()> {
+        self.board_info.validate_fan_id(fan_id)?;
+        limits
+            .validate(Some(&self.board_info))
+            .map_err(|e| anyhow::anyhow!(e))?;
+
+        let url = format!(
+            "{}/api/v0/controller/{}/fan/{}/limits",
+            self.base_url, self.controller_id, fan_id
+        );
+        let endpoint = &format!("controller/{}/fan/{}/limits", self.controller_id, fan_id);
+
+        let response = self
+            .client
+            .post(&url)
+            .json(limits)
+            .send()
+            .await
+            .with_context(|| format!("Failed to send fan limits request to {}", endpoint))?;
+
+        Self::handle_response(response, endpoint)
+            .await
+            .map(|_: ()| ())
+    }
+
+    /// Remove the safety limits of a fan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fan ID is invalid or the fan has no limits.
+    pub async fn delete_fan_limits(&self, fan_id: u8) -> Result<()> {
+        self.board_info.validate_fan_id(fan_id)?;
+
+        let url = format!(
+            "{}/api/v0/controller/{}/fan/{}/limits",
+            self.base_url, self.controller_id, fan_id
+        );
+        let endpoint = &format!("controller/{}/fan/{}/limits", self.controller_id, fan_id);
+
+        self.execute_with_retry(endpoint, || self.client.delete(&url).send())
+            .await
+            .map(|_: ()| ())
+    }
+
     // =========================================================================
     // CFM mapping operations
     // =========================================================================
@@ -1065,10 +1693,13 @@ impl OpenFanClient {
 
     /// Set the CFM@100% value for a specific port.
     ///
+    /// Replaces the port's mapping, including its airflow model.
+    ///
     /// # Arguments
     ///
     /// * `port` - Port identifier
     /// * `cfm_at_100` - CFM value when fan runs at 100% PWM
+    /// * `model` - Airflow tables, RPM at 100% PWM and direction (may be empty)
     ///
     /// # Errors
     ///
@@ -1076,7 +1707,8 @@ impl OpenFanClient {
     /// - The port ID is invalid for this board type
     /// - The CFM value is not positive
     /// - The CFM value exceeds the maximum allowed (500)
-    pub async fn set_cfm(&self, port: u8, cfm_at_100: f32) -> Result<()> {
+    /// - The airflow model is invalid
+    pub async fn set_cfm(&self, port: u8, cfm_at_100: f32, model: CfmModel) -> Result<()> {
         self.board_info.validate_fan_id(port)?;
 
         if cfm_at_100 <= 0.0 {
@@ -1085,12 +1717,13 @@ impl OpenFanClient {
         if cfm_at_100 > 500.0 {
             return Err(anyhow::anyhow!("CFM value must be <= 500"));
         }
+        model.validate().map_err(|e| anyhow::anyhow!(e))?;
 
         let url = format!(
             "{}/api/v0/controller/{}/cfm/{}",
             self.base_url, self.controller_id, port
         );
-        let request = api::SetCfmRequest { cfm_at_100 };
+        let request = api::SetCfmRequest { cfm_at_100, model };
         let endpoint = &format!("controller/{}/cfm/{}", self.controller_id, port);
 
         let response = self
@@ -1129,6 +1762,29 @@ impl OpenFanClient {
             .map(|_: ()| ())
     }
 
+    /// Retrieve the estimated airflow of each fan of the controller.
+    ///
+    /// Airflow is estimated by the server from the current RPM and PWM, using
+    /// each port's CFM mapping and airflow model.
+    pub async fn get_cfm_airflow(&self) -> Result<api::ControllerAirflowResponse> {
+        let url = format!(
+            "{}/api/v0/controller/{}/cfm/airflow",
+            self.base_url, self.controller_id
+        );
+        let endpoint = &format!("controller/{}/cfm/airflow", self.controller_id);
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
+    /// Retrieve the intake, exhaust and balance airflow of every zone.
+    pub async fn get_zones_airflow(&self) -> Result<api::ZoneAirflowResponse> {
+        let url = format!("{}/api/v0/cfm/zones", self.base_url);
+
+        self.execute_with_retry("cfm/zones", || self.client.get(&url).send())
+            .await
+    }
+
     /// Test basic connectivity to the server.
     ///
     /// Use a short timeout (3 seconds) to quickly determine if the server is reachable.
@@ -1230,6 +1886,18 @@ impl OpenFanClient {
             .await
     }
 
+    /// Scan the server's serial ports for OpenFAN boards.
+    ///
+    /// Ports not used by a registered controller are probed, which can take
+    /// up to the server's communication timeout per port.
+    pub async fn discover_controllers(&self) -> Result<api::DiscoverResponse> {
+        let url = format!("{}/api/v0/controllers/discover", self.base_url);
+        let endpoint = "controllers/discover";
+
+        self.execute_with_retry(endpoint, || self.client.get(&url).send())
+            .await
+    }
+
     /// Get information about a specific controller.
     ///
     /// # Arguments
@@ -1274,6 +1942,123 @@ impl OpenFanClient {
 
         Self::handle_response(response, endpoint).await
     }
+
+    /// Register a new controller with the running server.
+    ///
+    /// The server connects to the controller before registering it, and
+    /// appends it to `[[controllers]]` in its `config.toml` when
+    /// `request.persist` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ID is taken, the board cannot be reached, or
+    /// the server cannot persist the controller.
+    pub async fn add_controller(
+        &self,
+        request: &api::AddControllerRequest,
+    ) -> Result<api::ControllerInfo> {
+        if request.controller.id.trim().is_empty() {
+            return Err(anyhow::anyhow!("Controller ID cannot be empty"));
+        }
+
+        let url = format!("{}/api/v0/controllers", self.base_url);
+        let endpoint = "controllers";
+
+        let response = self
+            .client
+            .post(&url)
+            .json(request)
+            .send()
+            .await
+            .with_context(|| format!("Failed to send add controller request to {}", endpoint))?;
+
+        Self::handle_response(response, endpoint).await
+    }
+
+    /// Unregister a controller from the running server.
+    ///
+    /// # Arguments
+    ///
+    /// * `controller_id` - ID of the controller to remove
+    /// * `persist` - Also remove its `[[controllers]]` entry from `config.toml`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the controller does not exist, is the default
+    /// controller, or is still referenced by a zone.
+    pub async fn remove_controller(&self, controller_id: &str, persist: bool) -> Result<String> {
+        let url = format!(
+            "{}/api/v0/controller/{}?persist={}",
+            self.base_url, controller_id, persist
+        );
+        let endpoint = &format!("controller/{}", controller_id);
+
+        let response =
+            self.client.delete(&url).send().await.with_context(|| {
+                format!("Failed to send remove controller request to {}", endpoint)
+            })?;
+
+        Self::handle_response(response, endpoint).await
+    }
+}
+
+/// Live telemetry events read from the server's Server-Sent Events stream.
+///
+/// Returned by [`OpenFanClient::stream_events`].
+#[derive(Debug)]
+pub struct EventStream {
+    response: Response,
+    /// Received text not yet split into events
+    buffer: String,
+}
+
+impl EventStream {
+    /// Wait for the next event.
+    ///
+    /// # Returns
+    ///
+    /// Returns `None` once the server closes the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the connection fails while reading.
+    pub async fn next(&mut self) -> Result<Option<api::TelemetryEvent>> {
+        loop {
+            while let Some(end) = self.buffer.find("\n\n") {
+                let frame: String = self.buffer.drain(..end + 2).collect();
+                if let Some(event) = parse_event(&frame) {
+                    return Ok(Some(event));
+                }
+            }
+
+            match self
+                .response
+                .chunk()
+                .await
+                .context("Failed to read the event stream")?
+            {
+                Some(chunk) => self
+                    .buffer
+                    .push_str(&String::from_utf8_lossy(&chunk).replace('\r', "")),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Parse one Server-Sent Events frame into a telemetry event.
+///
+/// Keep-alive comments, and events this client does not know of, yield `None`.
+fn parse_event(frame: &str) -> Option<api::TelemetryEvent> {
+    let data: Vec<&str> = frame
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect();
+    if data.is_empty() {
+        return None;
+    }
+    serde_json::from_str(&data.join("\n")).ok()
 }
 
 #[cfg(test)]
@@ -1313,4 +2098,19 @@ mod tests {
         assert!(board_info.validate_fan_id(10).is_err());
         assert!(board_info.validate_fan_id(255).is_err());
     }
+    #[test]
+    fn test_parse_event() {
+        let frame = "event: connection_state\ndata: {\"type\":\"connection_state\",\"controller\":\"main\",\"state\":\"connected\"}\n\n";
+        match parse_event(frame) {
+            Some(api::TelemetryEvent::ConnectionState { controller, state }) => {
+                assert_eq!(controller, "main");
+                assert_eq!(state, "connected");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        // Keep-alive comments and unknown events are skipped
+        assert!(parse_event(":\n\n").is_none());
+        assert!(parse_event("event: later\ndata: {\"type\":\"later\"}\n\n").is_none());
+    }
 }
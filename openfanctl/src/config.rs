@@ -20,6 +20,10 @@ pub struct CliConfig {
 
     /// Request timeout in seconds
     pub timeout: u64,
+
+    /// API token sent to servers that require one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
 }
 
 impl Default for CliConfig {
@@ -29,6 +33,7 @@ impl Default for CliConfig {
             output_format: "table".to_string(),
             verbose: false,
             timeout: 10,
+            token: None,
         }
     }
 }
@@ -99,6 +104,7 @@ pub struct ConfigBuilder {
     output_format: Option<String>,
     verbose: Option<bool>,
     timeout: Option<u64>,
+    token: Option<String>,
 }
 
 impl ConfigBuilder {
@@ -144,6 +150,7 @@ impl ConfigBuilder {
                     output_format: builder.output_format.or(Some(config.output_format)),
                     verbose: builder.verbose.or(Some(config.verbose)),
                     timeout: builder.timeout.or(Some(config.timeout)),
+                    token: builder.token.or(config.token),
                 })
             }
             Err(_) => {
@@ -184,6 +191,13 @@ impl ConfigBuilder {
             self.timeout = Some(timeout);
         }
 
+        if self.token.is_none()
+            && let Ok(token) = std::env::var("OPENFAN_TOKEN")
+            && !token.is_empty()
+        {
+            self.token = Some(token);
+        }
+
         self
     }
 
@@ -205,6 +219,7 @@ impl ConfigBuilder {
             output_format,
             verbose: self.verbose.unwrap_or(defaults.verbose),
             timeout,
+            token: self.token,
         })
     }
 
@@ -422,6 +437,22 @@ mod tests {
         unset_env(&["OPENFAN_VERBOSE"]);
     }
 
+    #[test]
+    #[serial]
+    fn test_builder_token_from_env() {
+        unset_env(&["OPENFAN_TOKEN"]);
+        let config = ConfigBuilder::new().with_env_overrides().build().unwrap();
+        assert_eq!(config.token, None);
+        // Only saved when set
+        assert!(!toml::to_string(&config).unwrap().contains("token"));
+
+        set_env(&[("OPENFAN_TOKEN", "s3cret")]);
+        let config = ConfigBuilder::new().with_env_overrides().build().unwrap();
+        assert_eq!(config.token.as_deref(), Some("s3cret"));
+
+        unset_env(&["OPENFAN_TOKEN"]);
+    }
+
     #[test]
     #[serial]
     fn test_builder_invalid_env_values_ignored() {
@@ -5,8 +5,12 @@
 use anyhow::Result;
 use colored::Colorize;
 use openfan_core::api::{
-    AliasResponse, CfmListResponse, FanStatusResponse, InfoResponse, ProfileResponse,
+    AirflowPressure, AliasResponse, CalibrationResultsResponse, CalibrationState,
+    CalibrationStatus, CfmListResponse, ControllerAirflowResponse, FanAirflow, FanLimitsResponse,
+    FanStatusResponse, HistoryResponse, InfoResponse, ProfileResponse, ReloadReport, ReloadTrigger,
+    ZoneAirflowResponse,
 };
+use openfan_core::config::CfmMappingData;
 use std::collections::HashMap;
 
 use comfy_table::presets::UTF8_FULL;
@@ -79,15 +83,18 @@ pub fn format_fan_status_with_cfm(
                     "rpms": status.rpms,
                     "pwms": status.pwms,
                 });
-                // Calculate CFM values
+                if !status.limits.is_empty() {
+                    combined["limits"] = serde_json::to_value(&status.limits)?;
+                }
+                // Estimate CFM values
+                let cfm_data = CfmMappingData::from(cfm.clone());
                 let cfm_values: HashMap<String, f32> = status
                     .pwms
                     .iter()
                     .filter_map(|(port, pwm)| {
-                        cfm.mappings.get(port).map(|cfm_at_100| {
-                            let cfm = (*pwm as f32 / 100.0) * cfm_at_100;
-                            (port.to_string(), cfm)
-                        })
+                        cfm_data
+                            .estimate(*port, *pwm, status.rpms.get(port).copied())
+                            .map(|estimate| (port.to_string(), estimate.cfm))
                     })
                     .collect();
                 if !cfm_values.is_empty() {
@@ -101,7 +108,7 @@ pub fn format_fan_status_with_cfm(
         OutputFormat::Table => {
             // Check if we have CFM mappings to display
             let has_cfm = cfm_mappings
-                .map(|c| !c.mappings.is_empty())
+                .map(|c| !c.mappings.is_empty() || !c.models.is_empty())
                 .unwrap_or(false);
 
             if has_cfm {
@@ -110,9 +117,9 @@ pub fn format_fan_status_with_cfm(
                 table
                     .set_content_arrangement(ContentArrangement::Dynamic)
                     .load_style(UTF8_FULL)
-                    .set_header(vec!["Fan ID", "RPM", "PWM %", "CFM"]);
+                    .set_header(status_header(vec!["Fan ID", "RPM", "PWM %", "CFM"], status));
 
-                let cfm_data = cfm_mappings.unwrap();
+                let cfm_data = CfmMappingData::from(cfm_mappings.unwrap().clone());
 
                 // Collect all fan IDs from both rpms and pwms maps
                 let mut fan_ids: Vec<u8> = status
@@ -128,10 +135,11 @@ pub fn format_fan_status_with_cfm(
                     let rpm = status.rpms.get(&fan_id).unwrap_or(&0);
                     let pwm = status.pwms.get(&fan_id).unwrap_or(&0);
 
-                    // Calculate CFM if mapping exists
-                    let cfm_str = if let Some(cfm_at_100) = cfm_data.mappings.get(&fan_id) {
-                        let cfm = (*pwm as f32 / 100.0) * cfm_at_100;
-                        format!("{:.1}", cfm).yellow().to_string()
+                    // Estimate CFM if mapping exists
+                    let estimate =
+                        cfm_data.estimate(fan_id, *pwm, status.rpms.get(&fan_id).copied());
+                    let cfm_str = if let Some(estimate) = estimate {
+                        format!("{:.1}", estimate.cfm).yellow().to_string()
                     } else {
                         "-".dimmed().to_string()
                     };
@@ -148,7 +156,11 @@ pub fn format_fan_status_with_cfm(
                         "0%".dimmed().to_string()
                     };
 
-                    table.add_row(vec![format!("{}", fan_id), rpm_str, pwm_str, cfm_str]);
+                    table.add_row(status_row(
+                        vec![format!("{}", fan_id), rpm_str, pwm_str, cfm_str],
+                        status,
+                        fan_id,
+                    ));
                 }
 
                 Ok(format!("{}\n{}", "Fan Status:".bold(), table))
@@ -158,7 +170,7 @@ pub fn format_fan_status_with_cfm(
                 table
                     .set_content_arrangement(ContentArrangement::Dynamic)
                     .load_style(UTF8_FULL)
-                    .set_header(vec!["Fan ID", "RPM", "PWM %"]);
+                    .set_header(status_header(vec!["Fan ID", "RPM", "PWM %"], status));
 
                 // Collect all fan IDs from both rpms and pwms maps
                 let mut fan_ids: Vec<u8> = status
@@ -186,7 +198,11 @@ pub fn format_fan_status_with_cfm(
                         "0%".dimmed().to_string()
                     };
 
-                    table.add_row(vec![format!("{}", fan_id), rpm_str, pwm_str]);
+                    table.add_row(status_row(
+                        vec![format!("{}", fan_id), rpm_str, pwm_str],
+                        status,
+                        fan_id,
+                    ));
                 }
 
                 Ok(format!("{}\n{}", "Fan Status:".bold(), table))
@@ -195,6 +211,25 @@ pub fn format_fan_status_with_cfm(
     }
 }
 
+/// Fan status table header, with a limits column when some fan has limits
+fn status_header<'a>(mut header: Vec<&'a str>, status: &FanStatusResponse) -> Vec<&'a str> {
+    if !status.limits.is_empty() {
+        header.push("Limits");
+    }
+    header
+}
+
+/// Fan status table row, with the fan's limits when some fan has limits
+fn status_row(mut row: Vec<String>, status: &FanStatusResponse, fan_id: u8) -> Vec<String> {
+    if !status.limits.is_empty() {
+        row.push(match status.limits.get(&fan_id) {
+            Some(limits) => limits.to_string().yellow().to_string(),
+            None => "-".dimmed().to_string(),
+        });
+    }
+    row
+}
+
 /// Format profiles response
 pub fn format_profiles(profiles: &ProfileResponse, format: &OutputFormat) -> Result<String> {
     match format {
@@ -254,6 +289,445 @@ pub fn format_aliases(aliases: &AliasResponse, format: &OutputFormat) -> Result<
     }
 }
 
+/// Fan IDs with a reading in any history point, sorted
+fn history_fan_ids(history: &HistoryResponse) -> Vec<u8> {
+    let mut fan_ids: Vec<u8> = history
+        .points
+        .iter()
+        .flat_map(|point| point.rpms.keys().chain(point.pwms.keys()))
+        .copied()
+        .collect();
+    fan_ids.sort_unstable();
+    fan_ids.dedup();
+    fan_ids
+}
+
+/// Format a Unix timestamp as a UTC date and time
+fn format_utc(timestamp: u64) -> String {
+    // Civil date from days since the epoch (Howard Hinnant's algorithm)
+    let days = (timestamp / 86_400) as i64;
+    let secs = timestamp % 86_400;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Format fan history response
+pub fn format_history(history: &HistoryResponse, format: &OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(history)?),
+        OutputFormat::Table => {
+            let title = format!(
+                "Fan History of '{}' ({} to {} UTC, {}s per point):",
+                history.controller,
+                format_utc(history.from),
+                format_utc(history.to),
+                history.step_secs
+            );
+            if history.points.is_empty() {
+                return Ok(format!(
+                    "{}
+No readings recorded in this range.",
+                    title.bold()
+                ));
+            }
+
+            let fan_ids = history_fan_ids(history);
+            let mut header = vec!["Time (UTC)".to_string()];
+            header.extend(fan_ids.iter().map(|fan_id| format!("Fan {}", fan_id)));
+
+            let mut table = Table::new();
+            table
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .load_style(UTF8_FULL)
+                .set_header(header);
+
+            for point in &history.points {
+                let mut row = vec![format_utc(point.timestamp)];
+                row.extend(fan_ids.iter().map(|fan_id| {
+                    match (point.rpms.get(fan_id), point.pwms.get(fan_id)) {
+                        (Some(rpm), Some(pwm)) => format!("{} RPM {}%", rpm, pwm),
+                        (Some(rpm), None) => format!("{} RPM", rpm),
+                        (None, Some(pwm)) => format!("{}%", pwm),
+                        (None, None) => "-".to_string(),
+                    }
+                }));
+                table.add_row(row);
+            }
+
+            Ok(format!("{}\n{}", title.bold(), table))
+        }
+    }
+}
+
+/// Format fan history response as CSV
+///
+/// One row per point with its Unix timestamp, then the RPM and PWM of each
+/// fan; readings that are not known are left empty.
+pub fn format_history_csv(history: &HistoryResponse) -> String {
+    let fan_ids = history_fan_ids(history);
+
+    let mut output = String::from("timestamp");
+    for fan_id in &fan_ids {
+        output.push_str(&format!(",fan{0}_rpm,fan{0}_pwm", fan_id));
+    }
+    output.push('\n');
+
+    let cell = |value: Option<&u32>| value.map(u32::to_string).unwrap_or_default();
+    for point in &history.points {
+        output.push_str(&point.timestamp.to_string());
+        for fan_id in &fan_ids {
+            output.push(',');
+            output.push_str(&cell(point.rpms.get(fan_id)));
+            output.push(',');
+            output.push_str(&cell(point.pwms.get(fan_id)));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Format the estimated airflow of a controller's fans
+pub fn format_controller_airflow(
+    airflow: &ControllerAirflowResponse,
+    format: &OutputFormat,
+) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(airflow)?),
+        OutputFormat::Table => {
+            let table = airflow_table(&airflow.fans, false);
+            Ok(format!(
+                "{}\n{}\nTotal: {:.1} CFM",
+                format!("Airflow of '{}':", airflow.controller).bold(),
+                table,
+                airflow.total_cfm
+            ))
+        }
+    }
+}
+
+/// Format the intake, exhaust and balance airflow of zones
+pub fn format_zone_airflow(zones: &ZoneAirflowResponse, format: &OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(zones)?),
+        OutputFormat::Table => {
+            if zones.zones.is_empty() {
+                return Ok("No zones configured.".to_string());
+            }
+
+            let mut sections = Vec::new();
+            for zone in &zones.zones {
+                let pressure = match zone.pressure {
+                    AirflowPressure::Positive => "positive pressure".green(),
+                    AirflowPressure::Negative => "negative pressure".yellow(),
+                    AirflowPressure::Balanced => "balanced".cyan(),
+                };
+                let mut summary = format!(
+                    "Intake: {:.1} CFM, exhaust: {:.1} CFM, balance: {:+.1} CFM ({})",
+                    zone.intake_cfm, zone.exhaust_cfm, zone.balance_cfm, pressure
+                );
+                if zone.undirected_cfm > 0.0 {
+                    summary.push_str(&format!(
+                        "\nWithout direction: {:.1} CFM",
+                        zone.undirected_cfm
+                    ));
+                }
+                sections.push(format!(
+                    "{}\n{}\n{}",
+                    format!("Zone '{}':", zone.zone).bold(),
+                    airflow_table(&zone.fans, true),
+                    summary
+                ));
+            }
+            Ok(sections.join("\n\n"))
+        }
+    }
+}
+
+/// Table of fan airflow estimates, with the controller column for zones
+fn airflow_table(fans: &[FanAirflow], with_controller: bool) -> Table {
+    let mut header = vec!["Fan ID", "RPM", "PWM %", "CFM", "Source", "Direction"];
+    if with_controller {
+        header.insert(0, "Controller");
+    }
+
+    let mut table = Table::new();
+    table
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .load_style(UTF8_FULL)
+        .set_header(header);
+
+    let dash = || "-".dimmed().to_string();
+    for fan in fans {
+        let mut row = vec![
+            fan.fan_id.to_string(),
+            fan.rpm.map(|rpm| rpm.to_string()).unwrap_or_else(dash),
+            fan.pwm.map(|pwm| format!("{}%", pwm)).unwrap_or_else(dash),
+            match (fan.cfm, &fan.error) {
+                (Some(cfm), _) => format!("{:.1}", cfm).yellow().to_string(),
+                (None, Some(error)) => error.red().to_string(),
+                (None, None) => dash(),
+            },
+            fan.source
+                .map(|source| source.to_string())
+                .unwrap_or_else(dash),
+            fan.direction
+                .map(|direction| direction.to_string())
+                .unwrap_or_else(dash),
+        ];
+        if with_controller {
+            row.insert(0, fan.controller.clone());
+        }
+        table.add_row(row);
+    }
+    table
+}
+
+/// Format the progress of a calibration job
+pub fn format_calibration_status(
+    status: &CalibrationStatus,
+    format: &OutputFormat,
+) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(status)?),
+        OutputFormat::Table => {
+            let fans = status
+                .fans
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(", ");
+            let state = match status.state {
+                CalibrationState::Running => "running".cyan(),
+                CalibrationState::Completed => "completed".green(),
+                CalibrationState::Cancelled => "cancelled".yellow(),
+                CalibrationState::Failed => "failed".red(),
+            };
+            let mut lines = vec![
+                format!("Calibration of '{}': {}", status.controller, state)
+                    .bold()
+                    .to_string(),
+                format!(
+                    "  Fans: {} (step {}%, settle {}s)",
+                    fans, status.step, status.settle_secs
+                ),
+                format!(
+                    "  Progress: {}/{} measurements ({}%)",
+                    status.completed_steps,
+                    status.total_steps,
+                    status.percent()
+                ),
+            ];
+            if let Some(pwm) = status.current_pwm {
+                lines.push(format!("  Current PWM: {}%", pwm));
+            }
+            lines.push(format!("  Started: {} UTC", format_utc(status.started_at)));
+            if let Some(finished_at) = status.finished_at {
+                lines.push(format!("  Finished: {} UTC", format_utc(finished_at)));
+            }
+            if let Some(error) = &status.error {
+                lines.push(format!("  {} {}", "Error:".red(), error));
+            }
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
+/// Format the saved calibration results of a controller's fans
+pub fn format_calibration_results(
+    results: &CalibrationResultsResponse,
+    format: &OutputFormat,
+) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(results)?),
+        OutputFormat::Table => {
+            if results.fans.is_empty() {
+                return Ok(format!(
+                    "No fans of '{}' calibrated yet.",
+                    results.controller
+                ));
+            }
+
+            let mut summary = Table::new();
+            summary
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .load_style(UTF8_FULL)
+                .set_header(vec![
+                    "Fan ID",
+                    "Max RPM",
+                    "Min Start PWM",
+                    "Min Stable PWM",
+                    "Calibrated",
+                ]);
+
+            let mut fan_ids: Vec<_> = results.fans.keys().copied().collect();
+            fan_ids.sort_unstable();
+            let pwm_or_dash = |pwm: Option<u32>| match pwm {
+                Some(pwm) => format!("{}%", pwm),
+                None => "-".dimmed().to_string(),
+            };
+            for fan_id in &fan_ids {
+                let fan = &results.fans[fan_id];
+                let max_rpm = if fan.is_stopped() {
+                    "not spinning".red().to_string()
+                } else {
+                    fan.max_rpm().to_string()
+                };
+                summary.add_row(vec![
+                    fan_id.to_string(),
+                    max_rpm,
+                    pwm_or_dash(fan.min_start_pwm),
+                    pwm_or_dash(fan.min_stable_pwm),
+                    format!("{} UTC", format_utc(fan.calibrated_at)),
+                ]);
+            }
+
+            // One column per fan, one row per PWM step
+            let mut pwms: Vec<u32> = results
+                .fans
+                .values()
+                .flat_map(|fan| fan.points.iter().map(|point| point.pwm))
+                .collect();
+            pwms.sort_unstable();
+            pwms.dedup();
+
+            let mut curves = Table::new();
+            let mut header = vec!["PWM %".to_string()];
+            header.extend(fan_ids.iter().map(|fan_id| format!("Fan {} RPM", fan_id)));
+            curves
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .load_style(UTF8_FULL)
+                .set_header(header);
+            for pwm in pwms {
+                let mut row = vec![format!("{}%", pwm)];
+                row.extend(fan_ids.iter().map(|fan_id| {
+                    results.fans[fan_id]
+                        .points
+                        .iter()
+                        .find(|point| point.pwm == pwm)
+                        .map(|point| point.rpm.to_string())
+                        .unwrap_or_else(|| "-".dimmed().to_string())
+                }));
+                curves.add_row(row);
+            }
+
+            Ok(format!(
+                "{}\n{}\n{}",
+                format!("Calibration of '{}':", results.controller).bold(),
+                summary,
+                curves
+            ))
+        }
+    }
+}
+
+/// Format the safety limits of a controller's fans
+pub fn format_fan_limits(limits: &FanLimitsResponse, format: &OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(limits)?),
+        OutputFormat::Table => {
+            let title = format!(
+                "Fan limits of '{}' (enforcement: {}):",
+                limits.controller, limits.enforcement
+            );
+            if limits.limits.is_empty() {
+                return Ok(format!("{}\nNo fan has limits.", title.bold()));
+            }
+
+            let mut table = Table::new();
+            table
+                .set_content_arrangement(ContentArrangement::Dynamic)
+                .load_style(UTF8_FULL)
+                .set_header(vec!["Fan ID", "Min PWM", "Max PWM", "Min RPM", "Stop (0%)"]);
+
+            let mut fan_ids: Vec<_> = limits.limits.keys().copied().collect();
+            fan_ids.sort_unstable();
+            let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".dimmed().to_string());
+            for fan_id in fan_ids {
+                let fan = &limits.limits[&fan_id];
+                table.add_row(vec![
+                    fan_id.to_string(),
+                    or_dash(fan.min_pwm.map(|pwm| format!("{}%", pwm))),
+                    or_dash(fan.max_pwm.map(|pwm| format!("{}%", pwm))),
+                    or_dash(fan.min_rpm.map(|rpm| rpm.to_string())),
+                    if fan.allow_zero {
+                        "allowed".to_string()
+                    } else {
+                        "never".red().to_string()
+                    },
+                ]);
+            }
+
+            Ok(format!("{}\n{}", title.bold(), table))
+        }
+    }
+}
+
+/// Format a configuration reload report
+pub fn format_reload(report: &ReloadReport, format: &OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(report)?),
+        OutputFormat::Table => {
+            let trigger = match report.trigger {
+                ReloadTrigger::Signal => "SIGHUP",
+                ReloadTrigger::Watch => "file change",
+                ReloadTrigger::Api => "API request",
+            };
+            let mut lines = vec![
+                format!(
+                    "{} ({}, {} UTC)",
+                    if report.success {
+                        "Configuration reloaded"
+                    } else {
+                        "Reload rejected"
+                    },
+                    trigger,
+                    format_utc(report.timestamp)
+                )
+                .bold()
+                .to_string(),
+            ];
+
+            if let Some(error) = &report.error {
+                lines.push(format!("  {} {}", "Error:".red(), error));
+            } else if report.changes.is_empty() {
+                lines.push("  No changes.".to_string());
+            } else {
+                lines.extend(
+                    report
+                        .changes
+                        .iter()
+                        .map(|change| format!("  Changed: {}", change)),
+                );
+            }
+            lines.extend(
+                report
+                    .warnings
+                    .iter()
+                    .map(|warning| format!("  {} {}", "Warning:".yellow(), warning)),
+            );
+
+            Ok(lines.join("\n"))
+        }
+    }
+}
+
 /// Format success message
 pub fn format_success(message: &str) -> String {
     format!("{} {}", "✓".green().bold(), message)
@@ -308,7 +782,11 @@ mod tests {
         pwms.insert(0, 50);
         pwms.insert(1, 75);
 
-        let status = FanStatusResponse { rpms, pwms };
+        let status = FanStatusResponse {
+            rpms,
+            pwms,
+            ..Default::default()
+        };
         let result = format_fan_status(&status, &OutputFormat::Json).unwrap();
 
         assert!(result.contains("rpms"));
@@ -357,7 +835,11 @@ mod tests {
         pwms.insert(0, 50);
         pwms.insert(1, 75);
 
-        let status = FanStatusResponse { rpms, pwms };
+        let status = FanStatusResponse {
+            rpms,
+            pwms,
+            ..Default::default()
+        };
         let result = format_fan_status_with_cfm(&status, None, &OutputFormat::Table).unwrap();
 
         // Should show basic columns without CFM
@@ -377,13 +859,18 @@ mod tests {
         pwms.insert(0, 50);
         pwms.insert(1, 75);
 
-        let status = FanStatusResponse { rpms, pwms };
+        let status = FanStatusResponse {
+            rpms,
+            pwms,
+            ..Default::default()
+        };
 
         let mut cfm_mappings = HashMap::new();
         cfm_mappings.insert(0, 45.0); // 50% of 45 = 22.5 CFM
         cfm_mappings.insert(1, 60.0); // 75% of 60 = 45.0 CFM
         let cfm_response = CfmListResponse {
             mappings: cfm_mappings,
+            models: HashMap::new(),
         };
 
         let result =
@@ -407,13 +894,18 @@ mod tests {
         pwms.insert(1, 75);
         pwms.insert(2, 40);
 
-        let status = FanStatusResponse { rpms, pwms };
+        let status = FanStatusResponse {
+            rpms,
+            pwms,
+            ..Default::default()
+        };
 
         // Only map port 0, not port 1 or 2
         let mut cfm_mappings = HashMap::new();
         cfm_mappings.insert(0, 45.0);
         let cfm_response = CfmListResponse {
             mappings: cfm_mappings,
+            models: HashMap::new(),
         };
 
         let result =
@@ -436,13 +928,18 @@ mod tests {
         pwms.insert(0, 50);
         pwms.insert(1, 75);
 
-        let status = FanStatusResponse { rpms, pwms };
+        let status = FanStatusResponse {
+            rpms,
+            pwms,
+            ..Default::default()
+        };
 
         let mut cfm_mappings = HashMap::new();
         cfm_mappings.insert(0, 45.0);
         cfm_mappings.insert(1, 60.0);
         let cfm_response = CfmListResponse {
             mappings: cfm_mappings,
+            models: HashMap::new(),
         };
 
         let result =
@@ -467,7 +964,11 @@ mod tests {
         rpms.insert(0, 1200);
         pwms.insert(0, 50);
 
-        let status = FanStatusResponse { rpms, pwms };
+        let status = FanStatusResponse {
+            rpms,
+            pwms,
+            ..Default::default()
+        };
         let result = format_fan_status_with_cfm(&status, None, &OutputFormat::Json).unwrap();
 
         // Should not contain cfm in JSON output when no mappings
@@ -484,11 +985,16 @@ mod tests {
         rpms.insert(0, 1200);
         pwms.insert(0, 50);
 
-        let status = FanStatusResponse { rpms, pwms };
+        let status = FanStatusResponse {
+            rpms,
+            pwms,
+            ..Default::default()
+        };
 
         // Empty CFM mappings
         let cfm_response = CfmListResponse {
             mappings: HashMap::new(),
+            models: HashMap::new(),
         };
 
         let result =
@@ -505,12 +1011,17 @@ mod tests {
         rpms.insert(0, 1000);
         pwms.insert(0, 100); // 100% PWM
 
-        let status = FanStatusResponse { rpms, pwms };
+        let status = FanStatusResponse {
+            rpms,
+            pwms,
+            ..Default::default()
+        };
 
         let mut cfm_mappings = HashMap::new();
         cfm_mappings.insert(0, 45.0); // 100% of 45 = 45.0 CFM
         let cfm_response = CfmListResponse {
             mappings: cfm_mappings,
+            models: HashMap::new(),
         };
 
         let result =
@@ -527,12 +1038,17 @@ mod tests {
         rpms.insert(0, 0);
         pwms.insert(0, 0); // 0% PWM
 
-        let status = FanStatusResponse { rpms, pwms };
+        let status = FanStatusResponse {
+            rpms,
+            pwms,
+            ..Default::default()
+        };
 
         let mut cfm_mappings = HashMap::new();
         cfm_mappings.insert(0, 45.0); // 0% of 45 = 0.0 CFM
         let cfm_response = CfmListResponse {
             mappings: cfm_mappings,
+            models: HashMap::new(),
         };
 
         let result =
@@ -541,4 +1057,308 @@ mod tests {
         // At 0% PWM, CFM should be 0.0
         assert!(result.contains("0.0"));
     }
+
+    fn history() -> HistoryResponse {
+        HistoryResponse {
+            controller: "main".to_string(),
+            from: 1_700_000_000,
+            to: 1_700_000_060,
+            step_secs: 30,
+            points: vec![
+                openfan_core::api::HistoryPoint {
+                    timestamp: 1_700_000_010,
+                    rpms: HashMap::from([(0, 1200), (1, 900)]),
+                    pwms: HashMap::from([(0, 50)]),
+                },
+                openfan_core::api::HistoryPoint {
+                    timestamp: 1_700_000_040,
+                    rpms: HashMap::from([(0, 1300)]),
+                    pwms: HashMap::new(),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01 00:00:00");
+        assert_eq!(format_utc(951_825_600), "2000-02-29 12:00:00");
+        assert_eq!(format_utc(1_700_000_000), "2023-11-14 22:13:20");
+    }
+
+    #[test]
+    fn test_format_history_table() {
+        let result = format_history(&history(), &OutputFormat::Table).unwrap();
+        assert!(result.contains("2023-11-14 22:13:30"));
+        assert!(result.contains("1200 RPM 50%"));
+        assert!(result.contains("Fan 1"));
+
+        let empty = HistoryResponse {
+            points: Vec::new(),
+            ..history()
+        };
+        let result = format_history(&empty, &OutputFormat::Table).unwrap();
+        assert!(result.contains("No readings recorded"));
+    }
+
+    #[test]
+    fn test_format_history_csv() {
+        assert_eq!(
+            format_history_csv(&history()),
+            "timestamp,fan0_rpm,fan0_pwm,fan1_rpm,fan1_pwm\n\
+             1700000010,1200,50,900,\n\
+             1700000040,1300,,,\n"
+        );
+    }
+
+    #[test]
+    fn test_format_reload_table() {
+        let report = ReloadReport {
+            trigger: ReloadTrigger::Signal,
+            timestamp: 1_700_000_010,
+            success: true,
+            error: None,
+            changes: vec!["[alarms]".to_string()],
+            warnings: vec!["[server] changes apply after a restart".to_string()],
+        };
+        let result = format_reload(&report, &OutputFormat::Table).unwrap();
+        assert!(result.contains("SIGHUP, 2023-11-14 22:13:30 UTC"));
+        assert!(result.contains("Changed: [alarms]"));
+        assert!(result.contains("after a restart"));
+
+        let rejected = ReloadReport {
+            success: false,
+            error: Some("Profile 'Quiet' has 12 values".to_string()),
+            changes: Vec::new(),
+            ..report
+        };
+        let result = format_reload(&rejected, &OutputFormat::Table).unwrap();
+        assert!(result.contains("Reload rejected"));
+        assert!(result.contains("Profile 'Quiet' has 12 values"));
+    }
+
+    #[test]
+    fn test_format_fan_status_with_cfm_model() {
+        let status = FanStatusResponse {
+            rpms: HashMap::from([(0, 900)]),
+            pwms: HashMap::from([(0, 30)]),
+            ..Default::default()
+        };
+        let model = openfan_core::CfmModel {
+            rpm_points: vec![
+                openfan_core::CfmPoint::new(0, 0.0),
+                openfan_core::CfmPoint::new(1800, 72.0),
+            ],
+            ..Default::default()
+        };
+        let cfm_response = CfmListResponse {
+            mappings: HashMap::new(),
+            models: HashMap::from([(0, model)]),
+        };
+
+        // The RPM table gives 36 CFM at 900 RPM, where linear PWM would not apply
+        let result =
+            format_fan_status_with_cfm(&status, Some(&cfm_response), &OutputFormat::Table).unwrap();
+        assert!(result.contains("36.0"));
+
+        let result =
+            format_fan_status_with_cfm(&status, Some(&cfm_response), &OutputFormat::Json).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(parsed["cfm"]["0"], 36.0);
+    }
+
+    fn fan_airflow(fan_id: u8, cfm: Option<f32>) -> FanAirflow {
+        FanAirflow {
+            controller: "main".to_string(),
+            fan_id,
+            pwm: Some(50),
+            rpm: Some(1000),
+            cfm,
+            source: cfm.map(|_| openfan_core::CfmSource::PwmTable),
+            direction: Some(openfan_core::AirflowDirection::Intake),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_format_controller_airflow_table() {
+        let airflow = ControllerAirflowResponse {
+            controller: "main".to_string(),
+            fans: vec![fan_airflow(0, Some(22.5)), fan_airflow(1, None)],
+            total_cfm: 22.5,
+        };
+        let result = format_controller_airflow(&airflow, &OutputFormat::Table).unwrap();
+        assert!(result.contains("Airflow of 'main'"));
+        assert!(result.contains("22.5"));
+        assert!(result.contains("pwm table"));
+        assert!(result.contains("intake"));
+        assert!(result.contains("Total: 22.5 CFM"));
+    }
+
+    #[test]
+    fn test_format_zone_airflow_table() {
+        let mut exhaust = fan_airflow(1, Some(30.0));
+        exhaust.direction = Some(openfan_core::AirflowDirection::Exhaust);
+        let mut unreadable = fan_airflow(2, None);
+        unreadable.controller = "gpu".to_string();
+        unreadable.error = Some("Controller disconnected".to_string());
+        let zones = ZoneAirflowResponse {
+            zones: vec![openfan_core::api::ZoneAirflow::new(
+                "case",
+                vec![fan_airflow(0, Some(40.0)), exhaust, unreadable],
+            )],
+        };
+
+        let result = format_zone_airflow(&zones, &OutputFormat::Table).unwrap();
+        assert!(result.contains("Zone 'case'"));
+        assert!(result.contains("Intake: 40.0 CFM, exhaust: 30.0 CFM, balance: +10.0 CFM"));
+        assert!(result.contains("positive pressure"));
+        assert!(result.contains("Controller disconnected"));
+
+        let empty = ZoneAirflowResponse { zones: vec![] };
+        let result = format_zone_airflow(&empty, &OutputFormat::Table).unwrap();
+        assert_eq!(result, "No zones configured.");
+    }
+
+    #[test]
+    fn test_format_calibration_status_table() {
+        let status = CalibrationStatus {
+            controller: "default".to_string(),
+            state: CalibrationState::Running,
+            fans: vec![0, 2],
+            step: 10,
+            settle_secs: 3,
+            current_pwm: Some(70),
+            completed_steps: 4,
+            total_steps: 16,
+            started_at: 1_700_000_010,
+            finished_at: None,
+            error: None,
+        };
+        let result = format_calibration_status(&status, &OutputFormat::Table).unwrap();
+        assert!(result.contains("Fans: 0, 2 (step 10%, settle 3s)"));
+        assert!(result.contains("Progress: 4/16 measurements (25%)"));
+        assert!(result.contains("Current PWM: 70%"));
+
+        let failed = CalibrationStatus {
+            state: CalibrationState::Failed,
+            current_pwm: None,
+            finished_at: Some(1_700_000_020),
+            error: Some("Controller disconnected".to_string()),
+            ..status
+        };
+        let result = format_calibration_status(&failed, &OutputFormat::Table).unwrap();
+        assert!(result.contains("failed"));
+        assert!(result.contains("Finished: 2023-11-14 22:13:40 UTC"));
+        assert!(result.contains("Controller disconnected"));
+        assert!(!result.contains("Current PWM"));
+    }
+
+    #[test]
+    fn test_format_calibration_results_table() {
+        use openfan_core::{CalibrationPoint, FanCalibration};
+
+        let spinning = FanCalibration {
+            points: vec![
+                CalibrationPoint::new(0, 0),
+                CalibrationPoint::new(50, 900),
+                CalibrationPoint::new(100, 1800),
+            ],
+            min_start_pwm: Some(50),
+            min_stable_pwm: Some(50),
+            calibrated_at: 1_700_000_010,
+        };
+        let stopped = FanCalibration {
+            points: vec![CalibrationPoint::new(0, 0), CalibrationPoint::new(100, 0)],
+            min_start_pwm: None,
+            min_stable_pwm: None,
+            calibrated_at: 1_700_000_010,
+        };
+        let results = CalibrationResultsResponse {
+            controller: "default".to_string(),
+            fans: HashMap::from([(0, spinning), (3, stopped)]),
+        };
+
+        let result = format_calibration_results(&results, &OutputFormat::Table).unwrap();
+        assert!(result.contains("1800"));
+        assert!(result.contains("not spinning"));
+        assert!(result.contains("Fan 3 RPM"));
+
+        let empty = CalibrationResultsResponse {
+            controller: "default".to_string(),
+            fans: HashMap::new(),
+        };
+        let result = format_calibration_results(&empty, &OutputFormat::Table).unwrap();
+        assert_eq!(result, "No fans of 'default' calibrated yet.");
+    }
+
+    #[test]
+    fn test_format_fan_status_with_limits_table() {
+        use openfan_core::FanLimits;
+
+        let status = FanStatusResponse {
+            rpms: HashMap::from([(0, 1200), (1, 0)]),
+            pwms: HashMap::from([(0, 50), (1, 0)]),
+            limits: HashMap::from([(
+                0,
+                FanLimits {
+                    min_pwm: Some(40),
+                    max_pwm: Some(90),
+                    min_rpm: None,
+                    allow_zero: false,
+                },
+            )]),
+        };
+
+        let result = format_fan_status_with_cfm(&status, None, &OutputFormat::Table).unwrap();
+        assert!(result.contains("Limits"));
+        assert!(result.contains("PWM 40-90%"));
+
+        let result = format_fan_status_with_cfm(&status, None, &OutputFormat::Json).unwrap();
+        let json: serde_json::Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(json["limits"]["0"]["min_pwm"], 40);
+    }
+
+    #[test]
+    fn test_format_fan_limits_table() {
+        use openfan_core::{FanLimits, LimitEnforcement};
+
+        let limits = FanLimitsResponse {
+            controller: "default".to_string(),
+            enforcement: LimitEnforcement::Reject,
+            limits: HashMap::from([
+                (
+                    2,
+                    FanLimits {
+                        min_pwm: Some(30),
+                        min_rpm: Some(800),
+                        allow_zero: false,
+                        ..FanLimits::default()
+                    },
+                ),
+                (
+                    0,
+                    FanLimits {
+                        max_pwm: Some(80),
+                        ..FanLimits::default()
+                    },
+                ),
+            ]),
+        };
+
+        let result = format_fan_limits(&limits, &OutputFormat::Table).unwrap();
+        assert!(result.contains("enforcement: reject"));
+        assert!(result.contains("30%"));
+        assert!(result.contains("80%"));
+        assert!(result.contains("800"));
+        assert!(result.contains("never"));
+        assert!(result.find("80%").unwrap() < result.find("30%").unwrap());
+
+        let empty = FanLimitsResponse {
+            limits: HashMap::new(),
+            ..limits
+        };
+        let result = format_fan_limits(&empty, &OutputFormat::Table).unwrap();
+        assert!(result.contains("No fan has limits."));
+    }
 }
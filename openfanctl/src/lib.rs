@@ -18,6 +18,7 @@
 //!     10,  // timeout in seconds
 //!     3,   // max retries
 //!     Duration::from_millis(500),  // initial retry delay
+//!     None,  // API token
 //! ).await?;
 //!
 //! let info = client.get_info().await?;
@@ -5,9 +5,10 @@
 use anyhow::Result;
 use clap::Parser;
 use openfanctl::cli::{
-    Cli, Commands, OutputFormat, generate_completion, handle_alias, handle_cfm, handle_config,
-    handle_controller, handle_controllers_list, handle_curve, handle_fan, handle_health,
-    handle_info, handle_profile, handle_status, handle_zone,
+    Cli, Commands, OutputFormat, generate_completion, handle_alarms, handle_alias, handle_cfm,
+    handle_config, handle_controller, handle_controllers_list, handle_curve, handle_fan,
+    handle_health, handle_history, handle_info, handle_pid, handle_profile, handle_reload,
+    handle_sensor, handle_status, handle_status_watch, handle_zone,
 };
 use openfanctl::client::OpenFanClient;
 use openfanctl::config::CliConfig;
@@ -67,6 +68,18 @@ async fn main() -> Result<()> {
         eprintln!("Output format: {:?}", output_format);
     }
 
+    // Config commands only edit the local CLI config, where the API token the
+    // server may require is set, so they run without connecting
+    let command = match cli.command {
+        Commands::Config { command } => {
+            return report(
+                handle_config(command, &config, &output_format).await,
+                verbose,
+            );
+        }
+        command => command,
+    };
+
     // Create HTTP client with config-based timeout
     if verbose {
         eprintln!("Connecting to server and fetching board info...");
@@ -77,6 +90,7 @@ async fn main() -> Result<()> {
         config.timeout,
         3,
         std::time::Duration::from_millis(500),
+        config.token.clone(),
     )
     .await
     {
@@ -84,7 +98,7 @@ async fn main() -> Result<()> {
         Err(e) => {
             eprintln!("Error: Cannot connect to OpenFAN server at {}", server_url);
             eprintln!("Make sure the server is running and accessible.");
-            eprintln!("Connection error: {}", e);
+            eprintln!("Connection error: {:#}", e);
             std::process::exit(1);
         }
     };
@@ -105,27 +119,38 @@ async fn main() -> Result<()> {
     }
 
     // Execute commands
-    let result = match cli.command {
+    let result = match command {
         Commands::Info => handle_info(&client, &output_format).await,
-        Commands::Status => handle_status(&client, &output_format).await,
+        Commands::Status { watch: false } => handle_status(&client, &output_format).await,
+        Commands::Status { watch: true } => handle_status_watch(&client, &output_format).await,
         Commands::Health => handle_health(&client, &output_format).await,
         Commands::Controllers => handle_controllers_list(&client, &output_format).await,
         Commands::Controller { command } => {
             handle_controller(&client, command, &output_format).await
         }
-        Commands::Config { command } => handle_config(command, &config, &output_format).await,
+        Commands::Config { .. } => unreachable!("config commands run before connecting"),
         Commands::Fan { command } => handle_fan(&client, command, &output_format).await,
         Commands::Profile { command } => handle_profile(&client, command, &output_format).await,
         Commands::Alias { command } => handle_alias(&client, command, &output_format).await,
         Commands::Zone { command } => handle_zone(&client, command, &output_format).await,
         Commands::Curve { command } => handle_curve(&client, command, &output_format).await,
+        Commands::Pid { command } => handle_pid(&client, command, &output_format).await,
         Commands::Cfm { command } => handle_cfm(&client, command, &output_format).await,
+        Commands::Sensor { command } => handle_sensor(&client, command, &output_format).await,
+        Commands::Alarms => handle_alarms(&client, &output_format).await,
+        Commands::History(args) => handle_history(&client, args, &output_format).await,
+        Commands::Reload { status } => handle_reload(&client, status, &output_format).await,
         Commands::Completion { shell } => {
             generate_completion(shell);
             Ok(())
         }
     };
 
+    report(result, verbose)
+}
+
+/// Print the error of a failed command and exit with a failure status.
+fn report(result: Result<()>, verbose: bool) -> Result<()> {
     if let Err(e) = result {
         eprintln!("Error: {}", e);
         if verbose {
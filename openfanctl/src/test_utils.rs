@@ -7,12 +7,16 @@ use anyhow::Result;
 use axum::{
     Router,
     extract::{Path, Query},
-    http::StatusCode,
-    response::Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Json},
     routing::{get, post},
 };
+use openfan_core::config::CfmMappingData;
 use openfan_core::types::{ControlMode, FanProfile};
-use openfan_core::{BoardConfig, CurvePoint, DefaultBoard, ThermalCurve, Zone, api};
+use openfan_core::{
+    BoardConfig, CurveBinding, CurvePoint, DefaultBoard, FanLimits, LimitEnforcement,
+    PidController, PidState, ThermalCurve, Zone, api,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
@@ -36,8 +40,16 @@ pub struct MockServerState {
     pub zones: Arc<Mutex<HashMap<String, Zone>>>,
     /// Thermal curves
     pub curves: Arc<Mutex<HashMap<String, ThermalCurve>>>,
-    /// CFM mappings
-    pub cfm_mappings: Arc<Mutex<HashMap<u8, f32>>>,
+    /// PID controllers
+    pub pids: Arc<Mutex<HashMap<String, PidController>>>,
+    /// Curve bindings
+    pub bindings: Arc<Mutex<HashMap<String, api::BindingStatus>>>,
+    /// CFM mappings and airflow models
+    pub cfm_mappings: Arc<Mutex<CfmMappingData>>,
+    /// Last calibration job, which completes as soon as it starts
+    pub calibration: Arc<Mutex<Option<api::CalibrationStatus>>>,
+    /// Fan safety limits
+    pub fan_limits: Arc<Mutex<HashMap<u8, FanLimits>>>,
 }
 
 impl Default for MockServerState {
@@ -141,13 +153,14 @@ impl Default for MockServerState {
                     },
                 ],
                 description: Some("Default thermal curve".to_string()),
+                tuning: Default::default(),
             },
         );
 
         // Initialize CFM mappings
-        let mut cfm_mappings = HashMap::new();
-        cfm_mappings.insert(0, 50.0);
-        cfm_mappings.insert(1, 45.0);
+        let mut cfm_mappings = CfmMappingData::new();
+        cfm_mappings.set(0, 50.0);
+        cfm_mappings.set(1, 45.0);
 
         Self {
             rpms: Arc::new(Mutex::new(rpms)),
@@ -157,7 +170,11 @@ impl Default for MockServerState {
             info: Arc::new(Mutex::new(info)),
             zones: Arc::new(Mutex::new(zones)),
             curves: Arc::new(Mutex::new(curves)),
+            pids: Arc::new(Mutex::new(HashMap::new())),
+            bindings: Arc::new(Mutex::new(HashMap::new())),
             cfm_mappings: Arc::new(Mutex::new(cfm_mappings)),
+            calibration: Arc::new(Mutex::new(None)),
+            fan_limits: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
@@ -256,6 +273,20 @@ impl MockServer {
             .route("/", get(root_handler))
             // Info endpoint
             .route("/api/v0/info", get(info_handler))
+            .route("/api/v0/sensors", get(list_sensors_handler))
+            .route("/api/v0/alarms", get(list_alarms_handler))
+            .route(
+                "/api/v0/config/reload",
+                get(reload_status_handler).post(reload_handler),
+            )
+            .route("/api/v0/events", get(events_handler))
+            .route("/api/v0/controller/default/history", get(history_handler))
+            .route("/api/v0/controllers/discover", get(discover_handler))
+            .route("/api/v0/controllers", post(add_controller_handler))
+            .route(
+                "/api/v0/controller/{id}",
+                axum::routing::delete(remove_controller_handler),
+            )
             // Controller-scoped fan endpoints (used by client)
             .route(
                 "/api/v0/controller/default/fan/status",
@@ -273,6 +304,24 @@ impl MockServer {
                 "/api/v0/controller/default/fan/{id}/rpm/get",
                 get(get_fan_rpm_handler),
             )
+            .route(
+                "/api/v0/controller/default/fan/calibration",
+                get(calibration_status_handler)
+                    .post(start_calibration_handler)
+                    .delete(cancel_calibration_handler),
+            )
+            .route(
+                "/api/v0/controller/default/fan/calibration/results",
+                get(calibration_results_handler),
+            )
+            .route(
+                "/api/v0/controller/default/fan/limits",
+                get(fan_limits_handler),
+            )
+            .route(
+                "/api/v0/controller/default/fan/{id}/limits",
+                post(set_fan_limits_handler).delete(delete_fan_limits_handler),
+            )
             // Controller-scoped profile endpoints
             .route(
                 "/api/v0/controller/default/profiles/list",
@@ -339,8 +388,48 @@ impl MockServer {
                 "/api/v0/controller/default/curve/{name}/interpolate",
                 get(interpolate_curve_handler),
             )
+            // Controller-scoped PID controller endpoints
+            .route(
+                "/api/v0/controller/default/pids/list",
+                get(list_pids_handler),
+            )
+            .route("/api/v0/controller/default/pids/add", post(add_pid_handler))
+            .route(
+                "/api/v0/controller/default/pid/{name}/get",
+                get(get_pid_handler),
+            )
+            .route(
+                "/api/v0/controller/default/pid/{name}/update",
+                post(update_pid_handler),
+            )
+            .route(
+                "/api/v0/controller/default/pid/{name}",
+                axum::routing::delete(delete_pid_handler),
+            )
+            // Controller-scoped curve binding endpoints
+            .route(
+                "/api/v0/controller/default/bindings/list",
+                get(list_bindings_handler),
+            )
+            .route(
+                "/api/v0/controller/default/bindings/add",
+                post(add_binding_handler),
+            )
+            .route(
+                "/api/v0/controller/default/binding/{name}/get",
+                get(get_binding_handler),
+            )
+            .route(
+                "/api/v0/controller/default/binding/{name}",
+                axum::routing::delete(delete_binding_handler),
+            )
             // Controller-scoped CFM endpoints
             .route("/api/v0/controller/default/cfm/list", get(list_cfm_handler))
+            .route(
+                "/api/v0/controller/default/cfm/airflow",
+                get(cfm_airflow_handler),
+            )
+            .route("/api/v0/cfm/zones", get(zones_airflow_handler))
             .route(
                 "/api/v0/controller/default/cfm/{port}",
                 get(get_cfm_handler)
@@ -372,6 +461,10 @@ async fn info_handler(
 async fn fan_status_handler(
     axum::extract::State(state): axum::extract::State<MockServerState>,
 ) -> Json<api::ApiResponse<api::FanStatusResponse>> {
+    Json(api::ApiResponse::success(current_fan_status(&state)))
+}
+
+fn current_fan_status(state: &MockServerState) -> api::FanStatusResponse {
     let rpms_str = state.rpms.lock().unwrap().clone();
     let pwms_str = state.pwms.lock().unwrap().clone();
 
@@ -385,8 +478,11 @@ async fn fan_status_handler(
         .filter_map(|(k, v)| k.parse::<u8>().ok().map(|key| (key, *v)))
         .collect();
 
-    let response = api::FanStatusResponse { rpms, pwms };
-    Json(api::ApiResponse::success(response))
+    api::FanStatusResponse {
+        rpms,
+        pwms,
+        limits: state.fan_limits.lock().unwrap().clone(),
+    }
 }
 
 async fn set_fan_pwm_handler(
@@ -667,32 +763,52 @@ async fn apply_zone_handler(
     Path(name): Path<String>,
     Query(params): Query<ZoneApplyQuery>,
     axum::extract::State(state): axum::extract::State<MockServerState>,
-) -> Result<Json<api::ApiResponse<()>>, StatusCode> {
+) -> Result<Json<api::ApiResponse<api::ZoneApplyResponse>>, StatusCode> {
     let zone = {
         let zones = state.zones.lock().unwrap();
         zones.get(&name).cloned()
     };
 
-    if let Some(zone) = zone {
-        match params.mode.as_str() {
-            "pwm" => {
-                let mut pwms = state.pwms.lock().unwrap();
-                for fan in &zone.fans {
-                    pwms.insert(fan.fan_id.to_string(), params.value as u32);
-                }
+    let Some(zone) = zone else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    let (mode, values) = match params.mode.as_str() {
+        "pwm" => (ControlMode::Pwm, &state.pwms),
+        "rpm" => (ControlMode::Rpm, &state.rpms),
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    // Only the default controller exists; fans on other controllers are
+    // reported as disconnected
+    let mut values = values.lock().unwrap();
+    let results = zone
+        .fans
+        .iter()
+        .map(|fan| {
+            let connected = fan.controller == "default";
+            if connected {
+                values.insert(fan.fan_id.to_string(), params.value as u32);
             }
-            "rpm" => {
-                let mut rpms = state.rpms.lock().unwrap();
-                for fan in &zone.fans {
-                    rpms.insert(fan.fan_id.to_string(), params.value as u32);
-                }
+            api::ZoneFanResult {
+                controller: fan.controller.clone(),
+                fan_id: fan.fan_id,
+                outcome: if connected {
+                    api::ZoneFanOutcome::Success
+                } else {
+                    api::ZoneFanOutcome::SkippedDisconnected
+                },
+                error: (!connected).then(|| "Device disconnected".to_string()),
             }
-            _ => return Err(StatusCode::BAD_REQUEST),
-        }
-        Ok(Json(api::ApiResponse::success(())))
-    } else {
-        Err(StatusCode::NOT_FOUND)
-    }
+        })
+        .collect();
+
+    Ok(Json(api::ApiResponse::success(api::ZoneApplyResponse {
+        zone: name,
+        mode,
+        value: params.value,
+        results,
+    })))
 }
 
 // Curve handlers
@@ -728,6 +844,7 @@ async fn add_curve_handler(
         name: req.name.clone(),
         points: req.points,
         description: req.description,
+        tuning: req.tuning,
     };
     state.curves.lock().unwrap().insert(req.name, curve);
     Json(api::ApiResponse::success(()))
@@ -745,6 +862,7 @@ async fn update_curve_handler(
                 name,
                 points: req.points,
                 description: req.description,
+                tuning: entry.get().tuning.merged_with(&req.tuning),
             };
             entry.insert(curve);
             Ok(Json(api::ApiResponse::success(())))
@@ -789,13 +907,467 @@ async fn interpolate_curve_handler(
     }
 }
 
+// Sensor handlers
+
+async fn discover_handler() -> Json<api::ApiResponse<api::DiscoverResponse>> {
+    let response = api::DiscoverResponse {
+        ports: vec![
+            api::DiscoveredPort {
+                port: "/dev/ttyACM0".to_string(),
+                source: api::DiscoverySource::UsbId,
+                usb_id: Some("2E8A:000A".to_string()),
+                serial_number: Some("E6614103E7452D2F".to_string()),
+                product: Some("OpenFAN".to_string()),
+                controller: Some("default".to_string()),
+                firmware: None,
+                error: None,
+            },
+            api::DiscoveredPort {
+                port: "/dev/ttyACM1".to_string(),
+                source: api::DiscoverySource::UsbId,
+                usb_id: Some("2E8A:000A".to_string()),
+                serial_number: Some("E6614103E7452D30".to_string()),
+                product: Some("OpenFAN".to_string()),
+                controller: None,
+                firmware: Some("<FW|Version:1.0>".to_string()),
+                error: None,
+            },
+        ],
+    };
+    Json(api::ApiResponse::success(response))
+}
+
+async fn list_sensors_handler() -> Json<api::ApiResponse<api::SensorListResponse>> {
+    let response = api::SensorListResponse {
+        sensors: vec![
+            api::SensorInfo {
+                id: "k10temp/temp1".to_string(),
+                kind: api::SensorKind::Hwmon,
+                chip: Some("k10temp".to_string()),
+                label: Some("Tctl".to_string()),
+                source: "/sys/class/hwmon/hwmon0/temp1_input".to_string(),
+                temperature: Some(45.5),
+                error: None,
+                dominant: None,
+            },
+            api::SensorInfo {
+                id: "nvme0".to_string(),
+                kind: api::SensorKind::Command,
+                chip: None,
+                label: None,
+                source: "nvme-temp /dev/nvme0".to_string(),
+                temperature: None,
+                error: Some("Failed to read sensor".to_string()),
+                dominant: None,
+            },
+            api::SensorInfo {
+                id: "case".to_string(),
+                kind: api::SensorKind::Virtual,
+                chip: None,
+                label: None,
+                source: "max(k10temp/temp1, amdgpu/temp1 -10)".to_string(),
+                temperature: Some(45.5),
+                error: None,
+                dominant: Some("k10temp/temp1".to_string()),
+            },
+        ],
+    };
+    Json(api::ApiResponse::success(response))
+}
+
+async fn list_alarms_handler() -> Json<api::ApiResponse<api::AlarmListResponse>> {
+    let response = api::AlarmListResponse {
+        alarms: vec![api::AlarmInfo {
+            controller: "default".to_string(),
+            fan: 3,
+            kind: api::AlarmKind::Stall,
+            rpm: 0,
+            pwm: Some(60),
+            target_rpm: None,
+            since: 1_700_000_000,
+            message: "Fan 3 stalled at 0 RPM (60% PWM)".to_string(),
+        }],
+    };
+    Json(api::ApiResponse::success(response))
+}
+
+fn reload_report() -> api::ReloadReport {
+    api::ReloadReport {
+        trigger: api::ReloadTrigger::Api,
+        timestamp: 1_700_000_000,
+        success: true,
+        error: None,
+        changes: vec!["[alarms]".to_string()],
+        warnings: Vec::new(),
+    }
+}
+
+async fn add_controller_handler(
+    Json(request): Json<api::AddControllerRequest>,
+) -> Result<Json<api::ApiResponse<api::ControllerInfo>>, StatusCode> {
+    if request.controller.id == "default" {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let board = request.controller.board.to_board_info();
+    Ok(Json(api::ApiResponse::success(api::ControllerInfo {
+        id: request.controller.id,
+        board_name: board.name,
+        fan_count: board.fan_count,
+        description: request.controller.description,
+        mock_mode: true,
+        connected: true,
+        device_path: Some(request.controller.device).filter(|device| !device.is_empty()),
+        serial_number: request.controller.serial_number,
+    })))
+}
+
+async fn remove_controller_handler(
+    Path(id): Path<String>,
+) -> Result<Json<api::ApiResponse<String>>, StatusCode> {
+    if id == "default" {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(Json(api::ApiResponse::success(format!(
+        "Controller '{}' removed",
+        id
+    ))))
+}
+
+async fn reload_handler() -> Json<api::ApiResponse<api::ReloadReport>> {
+    Json(api::ApiResponse::success(reload_report()))
+}
+
+async fn reload_status_handler() -> Json<api::ApiResponse<api::ReloadStatusResponse>> {
+    Json(api::ApiResponse::success(api::ReloadStatusResponse {
+        last_reload: Some(reload_report()),
+    }))
+}
+
+/// Send a short, finite event stream: the controller state and one reading.
+async fn events_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> impl IntoResponse {
+    let events = [
+        api::TelemetryEvent::ConnectionState {
+            controller: "default".to_string(),
+            state: "connected".to_string(),
+        },
+        api::TelemetryEvent::FanStatus {
+            controller: "default".to_string(),
+            status: current_fan_status(&state),
+            timestamp: 1_700_000_000,
+        },
+    ];
+    let body: String = events
+        .iter()
+        .map(|event| {
+            format!(
+                "event: {}\ndata: {}\n\n",
+                event.name(),
+                serde_json::to_string(event).unwrap()
+            )
+        })
+        .collect();
+    ([(header::CONTENT_TYPE, "text/event-stream")], body)
+}
+
+/// Start a calibration job, which is reported as completed from then on.
+async fn start_calibration_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+    Json(request): Json<api::CalibrationRequest>,
+) -> Json<api::ApiResponse<api::CalibrationStatus>> {
+    let fans = if request.fans.is_empty() {
+        vec![0, 1]
+    } else {
+        request.fans.clone()
+    };
+    let total_steps = request.sweep().len() as u32;
+    let status = api::CalibrationStatus {
+        controller: "default".to_string(),
+        state: api::CalibrationState::Running,
+        fans,
+        step: request.step,
+        settle_secs: request.settle_secs,
+        current_pwm: Some(100),
+        completed_steps: 0,
+        total_steps,
+        started_at: 1_700_000_000,
+        finished_at: None,
+        error: None,
+    };
+    *state.calibration.lock().unwrap() = Some(api::CalibrationStatus {
+        state: api::CalibrationState::Completed,
+        current_pwm: None,
+        completed_steps: total_steps,
+        finished_at: Some(1_700_000_060),
+        ..status.clone()
+    });
+    Json(api::ApiResponse::success(status))
+}
+
+async fn calibration_status_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Json<api::ApiResponse<api::CalibrationStatusResponse>> {
+    Json(api::ApiResponse::success(api::CalibrationStatusResponse {
+        calibration: state.calibration.lock().unwrap().clone(),
+    }))
+}
+
+/// Jobs complete immediately, so there is never one to cancel.
+async fn cancel_calibration_handler() -> Result<Json<api::ApiResponse<String>>, StatusCode> {
+    Err(StatusCode::BAD_REQUEST)
+}
+
+/// A linear curve for each fan of the last calibration.
+async fn calibration_results_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Json<api::ApiResponse<api::CalibrationResultsResponse>> {
+    let fans = state
+        .calibration
+        .lock()
+        .unwrap()
+        .iter()
+        .flat_map(|status| status.fans.clone())
+        .map(|fan_id| {
+            let points = (0..=4)
+                .map(|i| openfan_core::CalibrationPoint::new(i * 25, i * 500))
+                .collect();
+            let calibration = openfan_core::FanCalibration {
+                points,
+                min_start_pwm: Some(25),
+                min_stable_pwm: Some(25),
+                calibrated_at: 1_700_000_060,
+            };
+            (fan_id, calibration)
+        })
+        .collect();
+    Json(api::ApiResponse::success(api::CalibrationResultsResponse {
+        controller: "default".to_string(),
+        fans,
+    }))
+}
+
+async fn fan_limits_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Json<api::ApiResponse<api::FanLimitsResponse>> {
+    Json(api::ApiResponse::success(api::FanLimitsResponse {
+        controller: "default".to_string(),
+        enforcement: LimitEnforcement::Clamp,
+        limits: state.fan_limits.lock().unwrap().clone(),
+    }))
+}
+
+async fn set_fan_limits_handler(
+    Path(id): Path<u8>,
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+    Json(limits): Json<FanLimits>,
+) -> Result<Json<api::ApiResponse<()>>, StatusCode> {
+    let board = openfan_core::BoardType::OpenFanStandard.to_board_info();
+    if id > 9 || limits.validate(Some(&board)).is_err() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let mut fan_limits = state.fan_limits.lock().unwrap();
+    if limits.is_unrestricted() {
+        fan_limits.remove(&id);
+    } else {
+        fan_limits.insert(id, limits);
+    }
+    Ok(Json(api::ApiResponse::success(())))
+}
+
+async fn delete_fan_limits_handler(
+    Path(id): Path<u8>,
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Result<Json<api::ApiResponse<()>>, StatusCode> {
+    match state.fan_limits.lock().unwrap().remove(&id) {
+        Some(_) => Ok(Json(api::ApiResponse::success(()))),
+        None => Err(StatusCode::BAD_REQUEST),
+    }
+}
+
+#[derive(Deserialize)]
+struct HistoryQuery {
+    from: u64,
+    to: u64,
+    step: Option<u64>,
+}
+
+/// Two points at the start of the requested range.
+async fn history_handler(
+    Query(query): Query<HistoryQuery>,
+) -> Json<api::ApiResponse<api::HistoryResponse>> {
+    let step_secs = query.step.unwrap_or(10).max(10);
+    let start = query.from - query.from % step_secs;
+    let points = (0..2)
+        .map(|i| api::HistoryPoint {
+            timestamp: start + i * step_secs,
+            rpms: HashMap::from([(0, 1200 + i as u32 * 100), (1, 800)]),
+            pwms: HashMap::from([(0, 50)]),
+        })
+        .collect();
+    Json(api::ApiResponse::success(api::HistoryResponse {
+        controller: "default".to_string(),
+        from: query.from,
+        to: query.to,
+        step_secs,
+        points,
+    }))
+}
+
+// PID controller handlers
+
+async fn list_pids_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Json<api::ApiResponse<api::PidControllerResponse>> {
+    let controllers = state.pids.lock().unwrap().clone();
+    Json(api::ApiResponse::success(api::PidControllerResponse {
+        controllers,
+    }))
+}
+
+async fn add_pid_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+    Json(req): Json<api::AddPidRequest>,
+) -> Result<Json<api::ApiResponse<()>>, StatusCode> {
+    let mut pid = PidController::new(&req.name, req.setpoint_c, req.kp, req.ki, req.kd)
+        .with_output_range(req.min_pwm.unwrap_or(0), req.max_pwm.unwrap_or(100));
+    pid.description = req.description;
+    if pid.validate().is_err() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    state.pids.lock().unwrap().insert(req.name, pid);
+    Ok(Json(api::ApiResponse::success(())))
+}
+
+async fn get_pid_handler(
+    Path(name): Path<String>,
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Result<Json<api::ApiResponse<api::SinglePidResponse>>, StatusCode> {
+    match state.pids.lock().unwrap().get(&name) {
+        Some(pid) => Ok(Json(api::ApiResponse::success(api::SinglePidResponse {
+            pid: pid.clone(),
+        }))),
+        None => Err(StatusCode::BAD_REQUEST),
+    }
+}
+
+async fn update_pid_handler(
+    Path(name): Path<String>,
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+    Json(req): Json<api::UpdatePidRequest>,
+) -> Result<Json<api::ApiResponse<()>>, StatusCode> {
+    let mut pids = state.pids.lock().unwrap();
+    let pid = pids.get_mut(&name).ok_or(StatusCode::BAD_REQUEST)?;
+    pid.setpoint_c = req.setpoint_c.unwrap_or(pid.setpoint_c);
+    pid.kp = req.kp.unwrap_or(pid.kp);
+    pid.ki = req.ki.unwrap_or(pid.ki);
+    pid.kd = req.kd.unwrap_or(pid.kd);
+    pid.min_pwm = req.min_pwm.unwrap_or(pid.min_pwm);
+    pid.max_pwm = req.max_pwm.unwrap_or(pid.max_pwm);
+    if req.description.is_some() {
+        pid.description = req.description;
+    }
+    Ok(Json(api::ApiResponse::success(())))
+}
+
+async fn delete_pid_handler(
+    Path(name): Path<String>,
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Result<Json<api::ApiResponse<()>>, StatusCode> {
+    if state.pids.lock().unwrap().remove(&name).is_some() {
+        Ok(Json(api::ApiResponse::success(())))
+    } else {
+        Err(StatusCode::BAD_REQUEST)
+    }
+}
+
+// Curve binding handlers
+
+async fn list_bindings_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Json<api::ApiResponse<api::CurveBindingResponse>> {
+    let bindings = state.bindings.lock().unwrap().clone();
+    let response = api::CurveBindingResponse { bindings };
+    Json(api::ApiResponse::success(response))
+}
+
+async fn add_binding_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+    Json(req): Json<api::AddBindingRequest>,
+) -> Result<Json<api::ApiResponse<()>>, StatusCode> {
+    let known = match (&req.curve, &req.pid) {
+        (Some(curve), None) => state.curves.lock().unwrap().contains_key(curve),
+        (None, Some(pid)) => state.pids.lock().unwrap().contains_key(pid),
+        _ => false,
+    };
+    if !known {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    // PID bindings report a state as if one control step had run
+    let pid_state = req.pid.as_ref().map(|_| PidState {
+        integral: 12.5,
+        last_error: Some(5.0),
+        p_term: 25.0,
+        i_term: 15.0,
+        d_term: 0.0,
+        output: Some(40),
+    });
+    let binding = CurveBinding {
+        name: req.name.clone(),
+        curve: req.curve,
+        pid: req.pid,
+        sensor: req.sensor,
+        zone: req.zone,
+        fans: req.fans,
+        interval_secs: req.interval_secs.unwrap_or(2),
+    };
+    let status = api::BindingStatus {
+        binding,
+        running: true,
+        temperature: Some(45.0),
+        pwm: Some(40),
+        error: None,
+        pid_state,
+    };
+    state.bindings.lock().unwrap().insert(req.name, status);
+    Ok(Json(api::ApiResponse::success(())))
+}
+
+async fn get_binding_handler(
+    Path(name): Path<String>,
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Result<Json<api::ApiResponse<api::SingleBindingResponse>>, StatusCode> {
+    match state.bindings.lock().unwrap().get(&name) {
+        Some(binding) => Ok(Json(api::ApiResponse::success(
+            api::SingleBindingResponse {
+                binding: binding.clone(),
+            },
+        ))),
+        None => Err(StatusCode::BAD_REQUEST),
+    }
+}
+
+async fn delete_binding_handler(
+    Path(name): Path<String>,
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Result<Json<api::ApiResponse<()>>, StatusCode> {
+    if state.bindings.lock().unwrap().remove(&name).is_some() {
+        Ok(Json(api::ApiResponse::success(())))
+    } else {
+        Err(StatusCode::BAD_REQUEST)
+    }
+}
+
 // CFM handlers
 
 async fn list_cfm_handler(
     axum::extract::State(state): axum::extract::State<MockServerState>,
 ) -> Json<api::ApiResponse<api::CfmListResponse>> {
-    let mappings = state.cfm_mappings.lock().unwrap().clone();
-    let response = api::CfmListResponse { mappings };
+    let data = state.cfm_mappings.lock().unwrap().clone();
+    let response = api::CfmListResponse {
+        mappings: data.mappings,
+        models: data.models,
+    };
     Json(api::ApiResponse::success(response))
 }
 
@@ -803,11 +1375,12 @@ async fn get_cfm_handler(
     Path(port): Path<u8>,
     axum::extract::State(state): axum::extract::State<MockServerState>,
 ) -> Result<Json<api::ApiResponse<api::CfmGetResponse>>, StatusCode> {
-    let mappings = state.cfm_mappings.lock().unwrap();
-    if let Some(&cfm) = mappings.get(&port) {
+    let data = state.cfm_mappings.lock().unwrap();
+    if let Some(cfm) = data.get(port) {
         let response = api::CfmGetResponse {
             port,
             cfm_at_100: cfm,
+            model: data.model(port).cloned().unwrap_or_default(),
         };
         Ok(Json(api::ApiResponse::success(response)))
     } else {
@@ -823,22 +1396,78 @@ async fn set_cfm_handler(
     if port > 9 {
         return Err(StatusCode::BAD_REQUEST);
     }
-    if req.cfm_at_100 <= 0.0 {
+    if req.cfm_at_100 <= 0.0 || req.model.validate().is_err() {
         return Err(StatusCode::BAD_REQUEST);
     }
-    state
-        .cfm_mappings
-        .lock()
-        .unwrap()
-        .insert(port, req.cfm_at_100);
+    let mut data = state.cfm_mappings.lock().unwrap();
+    data.set(port, req.cfm_at_100);
+    data.set_model(port, req.model);
     Ok(Json(api::ApiResponse::success(())))
 }
 
+/// Estimate the airflow of the mock fans of the default controller
+fn mock_fan_airflow(state: &MockServerState) -> Vec<api::FanAirflow> {
+    let rpms = state.rpms.lock().unwrap();
+    let pwms = state.pwms.lock().unwrap();
+    let data = state.cfm_mappings.lock().unwrap();
+    (0..10u8)
+        .map(|fan_id| {
+            let pwm = pwms.get(&fan_id.to_string()).copied();
+            let rpm = rpms.get(&fan_id.to_string()).copied();
+            let estimate = pwm.and_then(|pwm| data.estimate(fan_id, pwm, rpm));
+            api::FanAirflow {
+                controller: "default".to_string(),
+                fan_id,
+                pwm,
+                rpm,
+                cfm: estimate.map(|estimate| estimate.cfm),
+                source: estimate.map(|estimate| estimate.source),
+                direction: data.direction(fan_id),
+                error: None,
+            }
+        })
+        .collect()
+}
+
+async fn cfm_airflow_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Json<api::ApiResponse<api::ControllerAirflowResponse>> {
+    let fans = mock_fan_airflow(&state);
+    let total_cfm = fans.iter().filter_map(|fan| fan.cfm).sum();
+    Json(api::ApiResponse::success(api::ControllerAirflowResponse {
+        controller: "default".to_string(),
+        fans,
+        total_cfm,
+    }))
+}
+
+async fn zones_airflow_handler(
+    axum::extract::State(state): axum::extract::State<MockServerState>,
+) -> Json<api::ApiResponse<api::ZoneAirflowResponse>> {
+    let fans = mock_fan_airflow(&state);
+    let mut zones: Vec<_> = state.zones.lock().unwrap().values().cloned().collect();
+    zones.sort_by(|a, b| a.name.cmp(&b.name));
+    let zones = zones
+        .into_iter()
+        .map(|zone| {
+            let zone_fans = zone
+                .fans
+                .iter()
+                .filter_map(|fan| fans.get(fan.fan_id as usize).cloned())
+                .collect();
+            api::ZoneAirflow::new(zone.name, zone_fans)
+        })
+        .collect();
+    Json(api::ApiResponse::success(api::ZoneAirflowResponse {
+        zones,
+    }))
+}
+
 async fn delete_cfm_handler(
     Path(port): Path<u8>,
     axum::extract::State(state): axum::extract::State<MockServerState>,
 ) -> Result<Json<api::ApiResponse<()>>, StatusCode> {
-    if state.cfm_mappings.lock().unwrap().remove(&port).is_some() {
+    if state.cfm_mappings.lock().unwrap().remove(port) {
         Ok(Json(api::ApiResponse::success(())))
     } else {
         Err(StatusCode::NOT_FOUND)
@@ -76,11 +76,38 @@ fn get_cli_binary() -> PathBuf {
     binary
 }
 
+/// Get the path to the openfan-emulator binary, ensuring it exists
+fn get_emulator_binary() -> PathBuf {
+    let binary = std::env::var("OPENFAN_EMULATOR_BINARY")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| workspace_root().join("target/debug/openfan-emulator"));
+
+    if !binary.exists() {
+        panic!(
+            "\n\n\
+            ╔═══════════════════════════════════════════════════════════════════╗\n\
+            ║  E2E Test Error: Emulator binary not found                        ║\n\
+            ║                                                                   ║\n\
+            ║  Expected binary at: {:<43} ║\n\
+            ║                                                                   ║\n\
+            ║  Please run 'cargo build' before running e2e tests.               ║\n\
+            ║  Or set OPENFAN_EMULATOR_BINARY environment variable.             ║\n\
+            ╚═══════════════════════════════════════════════════════════════════╝\n\n",
+            binary.display()
+        );
+    }
+
+    binary
+}
+
 /// Test configuration
 const SERVER_STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
 const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
 const SERVER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Reconnection settings for servers on an emulated device
+const RECONNECT_ONCE: &str = "\n[reconnect]\nmax_attempts = 1\n";
+
 /// E2E Test harness that manages server lifecycle
 pub struct E2ETestHarness {
     server_process: Arc<Mutex<Option<Child>>>,
@@ -126,6 +153,28 @@ impl Default for E2ETestHarness {
 impl E2ETestHarness {
     /// Start the server in mock mode
     pub async fn start_server(&self) -> Result<()> {
+        self.spawn_server(&["--mock"], "", &[]).await
+    }
+
+    /// Start the server on a serial device, giving up reconnecting after one attempt
+    pub async fn start_server_on_device(&self, device: &str) -> Result<()> {
+        self.spawn_server(&["--device", device], RECONNECT_ONCE, &[])
+            .await
+    }
+
+    /// Start the server without any controller, letting it detect boards
+    pub async fn start_server_detecting(&self, comport: &str) -> Result<()> {
+        self.spawn_server(&[], RECONNECT_ONCE, &[("OPENFAN_COMPORT", comport)])
+            .await
+    }
+
+    /// Start the server with the given arguments, extra configuration and environment
+    async fn spawn_server(
+        &self,
+        server_args: &[&str],
+        extra_config: &str,
+        envs: &[(&str, &str)],
+    ) -> Result<()> {
         let mut process_guard = self.server_process.lock().await;
 
         println!("Starting server on port {}", self.server_port);
@@ -144,9 +193,10 @@ impl E2ETestHarness {
 bind_address = "127.0.0.1"
 port = {}
 communication_timeout = 1
-"#,
+{}"#,
             data_dir.display(),
-            self.server_port
+            self.server_port,
+            extra_config
         );
         let config_path = self
             .temp_dir
@@ -154,13 +204,13 @@ communication_timeout = 1
             .join(format!("test_config_{}.toml", self.server_port));
         std::fs::write(&config_path, config_content)?;
 
-        // Start the server process in mock mode
         // Use pre-built binary to avoid cargo lock contention in parallel tests
         let server_binary = get_server_binary();
 
         let child = Command::new(&server_binary)
+            .envs(envs.iter().copied())
+            .args(server_args)
             .args([
-                "--mock",
                 "--config",
                 &config_path.to_string_lossy(),
                 "--port",
@@ -230,14 +280,14 @@ communication_timeout = 1
         );
     }
 
-    /// Check if server is responding
+    /// Check if server is responding (`/` stays public when API tokens are required)
     async fn check_server_health(&self) -> Result<reqwest::Response> {
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(3))
             .build()?;
 
         client
-            .get(format!("{}/api/v0/info", self.server_url))
+            .get(format!("{}/", self.server_url))
             .send()
             .await
             .map_err(|e| anyhow::anyhow!("Health check failed: {}", e))
@@ -275,6 +325,15 @@ communication_timeout = 1
 
     /// Run a CLI command and return the output
     pub async fn run_cli_command(&self, args: &[&str]) -> Result<std::process::Output> {
+        self.run_cli_command_with_env(args, &[]).await
+    }
+
+    /// Run a CLI command with extra environment variables and return the output
+    pub async fn run_cli_command_with_env(
+        &self,
+        args: &[&str],
+        envs: &[(&str, &str)],
+    ) -> Result<std::process::Output> {
         // Use pre-built binary to avoid cargo lock contention in parallel tests
         let cli_binary = get_cli_binary();
 
@@ -283,6 +342,8 @@ communication_timeout = 1
 
         let output = timeout(COMMAND_TIMEOUT, async {
             Command::new(&cli_binary)
+                .env_remove("OPENFAN_TOKEN")
+                .envs(envs.iter().copied())
                 .args(&cmd_args)
                 .stdout(Stdio::piped())
                 .stderr(Stdio::piped())
@@ -618,6 +679,294 @@ async fn test_e2e_health_check() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_e2e_metrics_scrape() -> Result<()> {
+    let harness = E2ETestHarness::default();
+    harness.start_server().await?;
+
+    harness
+        .run_cli_success(&["fan", "set", "0", "--pwm", "60"])
+        .await?;
+    harness
+        .run_cli_success(&["alias", "set", "0", "CPU_Fan"])
+        .await?;
+    harness
+        .run_cli_success(&["cfm", "set", "0", "--cfm-at-100", "45.0"])
+        .await?;
+
+    let response = reqwest::get(format!("{}/metrics", harness.server_url)).await?;
+    assert!(response.status().is_success());
+    let body = response.text().await?;
+
+    let fan0 = r#"controller="default",fan="0",alias="CPU_Fan""#;
+    assert!(
+        body.contains(&format!("openfan_fan_pwm_percent{{{}}} 60\n", fan0)),
+        "Should export the commanded PWM: {}",
+        body
+    );
+    assert!(
+        body.contains(&format!("openfan_fan_cfm{{{}}} 27\n", fan0)),
+        "Should export the CFM at 60% of 45 CFM: {}",
+        body
+    );
+    assert!(body.contains(&format!("openfan_fan_rpm{{{}}}", fan0)));
+    assert!(body.contains(
+        "openfan_controller_connection_state{controller=\"default\",state=\"connected\"} 1"
+    ));
+    assert!(body.contains("openfan_serial_transactions_total{controller=\"default\"}"));
+
+    harness.stop_server().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_e2e_status_watch_streams_events() -> Result<()> {
+    let harness = E2ETestHarness::default();
+    harness.start_server().await?;
+
+    let mut child = TokioCommand::new(get_cli_binary())
+        .args([
+            "--server",
+            &harness.server_url,
+            "--no-config",
+            "--format",
+            "json",
+            "status",
+            "--watch",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let stdout = child.stdout.take().expect("stdout piped");
+    let mut lines = TokioBufReader::new(stdout).lines();
+
+    // The stream opens with the connection state, then carries the readings
+    // the daemon samples every second
+    let mut events = Vec::new();
+    let streamed = timeout(COMMAND_TIMEOUT, async {
+        while let Ok(Some(line)) = lines.next_line().await {
+            let event: Value = serde_json::from_str(&line)?;
+            let is_fan_status = event["type"] == "fan_status";
+            events.push(event);
+            if is_fan_status {
+                break;
+            }
+        }
+        anyhow::Ok(())
+    })
+    .await;
+
+    let _ = child.kill().await;
+    let _ = child.wait().await;
+    streamed.map_err(|_| anyhow::anyhow!("timed out waiting for a fan reading"))??;
+
+    assert_eq!(events[0]["type"], "connection_state");
+    assert_eq!(events[0]["controller"], "default");
+    assert_eq!(events[0]["state"], "connected");
+    let fan_status = events.last().unwrap();
+    assert_eq!(fan_status["type"], "fan_status");
+    assert!(fan_status["status"]["rpms"]["0"].is_number());
+
+    harness.stop_server().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_e2e_history_records_fan_readings() -> Result<()> {
+    let harness = E2ETestHarness::default();
+    harness.start_server().await?;
+
+    // The point being averaged shows up once the first sample is taken
+    let history = timeout(COMMAND_TIMEOUT, async {
+        loop {
+            let output = harness
+                .run_cli_success(&["--format", "json", "history", "--from", "10m"])
+                .await?;
+            let history: Value = serde_json::from_str(&output)?;
+            if !history["points"].as_array().unwrap().is_empty() {
+                return anyhow::Ok(history);
+            }
+            sleep(Duration::from_millis(200)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("timed out waiting for a history point"))??;
+
+    assert_eq!(history["controller"], "default");
+    assert_eq!(history["step_secs"], 10);
+    assert!(history["points"][0]["rpms"]["0"].is_number());
+
+    let csv = harness
+        .run_cli_success(&["history", "--from", "10m", "--step", "60", "--csv"])
+        .await?;
+    let mut lines = csv.lines();
+    assert!(
+        lines
+            .next()
+            .unwrap()
+            .starts_with("timestamp,fan0_rpm,fan0_pwm,")
+    );
+    let row = lines.next().expect("CSV should have a row per point");
+    assert_eq!(row.split(',').next().unwrap().parse::<u64>()? % 60, 0);
+
+    // The history is kept in the data directory, at a fixed size
+    let file = harness
+        .temp_dir
+        .path()
+        .join(format!("data_{}/history/default.bin", harness.server_port));
+    assert!(std::fs::metadata(&file)?.len() > 0);
+
+    harness.stop_server().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_e2e_api_tokens_restrict_access() -> Result<()> {
+    let harness = E2ETestHarness::default();
+    // SHA-256 of "reader" and "writer"
+    let auth = r#"
+[[auth.tokens]]
+name = "dashboard"
+sha256 = "3d0941964aa3ebdcb00ccef58b1bb399f9f898465e9886d5aec7f31090a0fb30"
+
+[[auth.tokens]]
+name = "admin"
+sha256 = "b93006774cbdd4b299389a03ac3d88c3a76b460d538795bc12718011a909fba5"
+scope = "control"
+"#;
+    harness.spawn_server(&["--mock"], auth, &[]).await?;
+
+    // Without a token the CLI cannot even fetch the board info
+    let output = harness.run_cli_command(&["status"]).await?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Unauthorized"));
+
+    // A read token can look but not touch
+    let reader = [("OPENFAN_TOKEN", "reader")];
+    let output = harness
+        .run_cli_command_with_env(&["status"], &reader)
+        .await?;
+    assert!(output.status.success());
+    let output = harness
+        .run_cli_command_with_env(&["fan", "set", "0", "--pwm", "40"], &reader)
+        .await?;
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("read-only"));
+
+    let writer = [("OPENFAN_TOKEN", "writer")];
+    let output = harness
+        .run_cli_command_with_env(&["fan", "set", "0", "--pwm", "40"], &writer)
+        .await?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    harness.stop_server().await?;
+    Ok(())
+}
+
+/// Add a profile to the default controller's profiles file, as an editor would
+fn append_quiet_profile(harness: &E2ETestHarness) -> Result<()> {
+    use std::io::Write;
+
+    let path = harness.temp_dir.path().join(format!(
+        "data_{}/controllers/default/profiles.toml",
+        harness.server_port
+    ));
+    let mut file = std::fs::OpenOptions::new().append(true).open(path)?;
+    writeln!(
+        file,
+        "\n[profiles.\"Quiet\"]\ntype = \"pwm\"\nvalues = [20, 20, 20, 20, 20, 20, 20, 20, 20, 20]"
+    )?;
+    Ok(())
+}
+
+/// Wait until the default controller lists the profile added by `append_quiet_profile`
+async fn wait_for_quiet_profile(harness: &E2ETestHarness) -> Result<()> {
+    timeout(COMMAND_TIMEOUT, async {
+        loop {
+            let output = harness
+                .run_cli_success(&["--format", "json", "profile", "list"])
+                .await?;
+            if output.contains("Quiet") {
+                return anyhow::Ok(());
+            }
+            sleep(Duration::from_millis(200)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("timed out waiting for the reloaded profile"))?
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_e2e_reload_on_sighup() -> Result<()> {
+    let harness = E2ETestHarness::default();
+    harness.start_server().await?;
+
+    let output = harness.run_cli_success(&["reload", "--status"]).await?;
+    assert!(output.contains("No reload since the server started"));
+
+    append_quiet_profile(&harness)?;
+    let pid = harness
+        .server_process
+        .lock()
+        .await
+        .as_ref()
+        .expect("server should be running")
+        .id();
+    let status = Command::new("kill")
+        .args(["-HUP", &pid.to_string()])
+        .status()?;
+    assert!(status.success());
+
+    wait_for_quiet_profile(&harness).await?;
+    let output = harness
+        .run_cli_success(&["--format", "json", "reload", "--status"])
+        .await?;
+    let report: Value = serde_json::from_str(&output)?;
+    assert_eq!(report["trigger"], "signal");
+    assert_eq!(report["success"], true);
+    assert_eq!(report["changes"][0], "controller 'default': profiles");
+
+    // Reloading again finds nothing to change
+    let output = harness.run_cli_success(&["reload"]).await?;
+    assert!(output.contains("No changes"), "{}", output);
+
+    harness.stop_server().await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_e2e_reload_watches_files() -> Result<()> {
+    let harness = E2ETestHarness::default();
+    harness
+        .spawn_server(
+            &["--mock"],
+            "\n[reload]\nwatch = true\ninterval_secs = 1\n",
+            &[],
+        )
+        .await?;
+
+    // Let the watcher take its first look before editing
+    sleep(Duration::from_millis(1500)).await;
+    append_quiet_profile(&harness)?;
+    wait_for_quiet_profile(&harness).await?;
+
+    let output = harness
+        .run_cli_success(&["--format", "json", "reload", "--status"])
+        .await?;
+    let report: Value = serde_json::from_str(&output)?;
+    assert_eq!(report["trigger"], "watch");
+
+    harness.stop_server().await?;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_e2e_server_without_mock_fails_gracefully() -> Result<()> {
     // This test verifies that the server exits cleanly when hardware is not available
@@ -685,6 +1034,162 @@ communication_timeout = 1
     Ok(())
 }
 
+/// Start a firmware emulator linked at `link`, waiting for the link to appear
+#[cfg(unix)]
+async fn spawn_emulator(link: &std::path::Path) -> Result<tokio::process::Child> {
+    let emulator = TokioCommand::new(get_emulator_binary())
+        .args(["--link", &link.to_string_lossy(), "--seed", "7"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+    timeout(SERVER_STARTUP_TIMEOUT, async {
+        while !link.exists() {
+            sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("Emulator did not create {}", link.display()))?;
+    Ok(emulator)
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_e2e_detects_board_from_comport() -> Result<()> {
+    let harness = E2ETestHarness::default();
+    let link = harness.temp_dir.path().join("tty");
+    let mut emulator = spawn_emulator(&link).await?;
+
+    harness
+        .start_server_detecting(&link.to_string_lossy())
+        .await?;
+
+    let info = harness
+        .run_cli_success(&["--format", "json", "controller", "info", "default"])
+        .await?;
+    assert!(
+        info.contains("Auto-detected on"),
+        "Detected board should be the default controller: {}",
+        info
+    );
+
+    // The port is reported as used by the controller, not probed again
+    let scan = harness
+        .run_cli_success(&["--format", "json", "controller", "scan"])
+        .await?;
+    let scan: Value = serde_json::from_str(&scan)?;
+    assert_eq!(scan["ports"][0]["source"], "env");
+    assert_eq!(scan["ports"][0]["controller"], "default");
+
+    harness.stop_server().await?;
+    emulator.kill().await?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_e2e_emulated_device_disconnect_and_reconnect() -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let harness = E2ETestHarness::default();
+    let link = harness.temp_dir.path().join("tty");
+
+    // Serve a simulated board on a pseudo-terminal
+    let mut emulator = spawn_emulator(&link).await?;
+    let mut emulator_stdin = emulator.stdin.take().unwrap();
+
+    // The server talks to it through its real serial driver
+    harness
+        .start_server_on_device(&link.to_string_lossy())
+        .await?;
+    let info = harness
+        .run_cli_success(&["--format", "json", "info"])
+        .await?;
+    assert!(
+        info.contains("-sim"),
+        "Should report emulated firmware: {}",
+        info
+    );
+    harness
+        .run_cli_success(&["fan", "set", "0", "--pwm", "80"])
+        .await?;
+
+    // Unplug the board: status fails once the sampler or a read notices, as
+    // readings younger than two telemetry intervals are served from the cache
+    emulator_stdin.write_all(b"disconnect\n").await?;
+    timeout(Duration::from_secs(5), async {
+        while harness.run_cli_command(&["status"]).await?.status.success() {
+            sleep(Duration::from_millis(250)).await;
+        }
+        Ok::<_, anyhow::Error>(())
+    })
+    .await
+    .expect("status should fail after the board is unplugged")?;
+
+    // Plug it back in: the next request reconnects
+    emulator_stdin.write_all(b"reconnect\n").await?;
+    sleep(Duration::from_millis(300)).await;
+    let status = harness.run_cli_success(&["status"]).await?;
+    assert!(
+        status.contains("RPM") || status.contains("PWM"),
+        "Status should show fan information after reconnect: {}",
+        status
+    );
+
+    harness.stop_server().await?;
+    emulator.kill().await?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_e2e_stalled_fan_raises_alarm() -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let harness = E2ETestHarness::default();
+    let link = harness.temp_dir.path().join("tty");
+    let mut emulator = spawn_emulator(&link).await?;
+    let mut emulator_stdin = emulator.stdin.take().unwrap();
+
+    let alarms_config = format!("{RECONNECT_ONCE}\n[alarms]\ninterval_secs = 1\ngrace_secs = 0\n");
+    harness
+        .spawn_server(&["--device", &link.to_string_lossy()], &alarms_config, &[])
+        .await?;
+    harness
+        .run_cli_success(&["fan", "set", "0", "--pwm", "60"])
+        .await?;
+
+    // Jam fan 0: its RPM drops to zero while it is still driven at 60%
+    emulator_stdin.write_all(b"stall 0\n").await?;
+
+    let alarms = timeout(Duration::from_secs(10), async {
+        loop {
+            let output = harness
+                .run_cli_success(&["--format", "json", "alarms"])
+                .await?;
+            let alarms: Value = serde_json::from_str(&output)?;
+            if alarms["alarms"]
+                .as_array()
+                .is_some_and(|alarms| !alarms.is_empty())
+            {
+                return Ok::<_, anyhow::Error>(alarms);
+            }
+            sleep(Duration::from_millis(250)).await;
+        }
+    })
+    .await
+    .map_err(|_| anyhow::anyhow!("No alarm raised for the stalled fan"))??;
+
+    assert_eq!(alarms["alarms"][0]["controller"], "default");
+    assert_eq!(alarms["alarms"][0]["fan"], 0);
+    assert_eq!(alarms["alarms"][0]["kind"], "stall");
+
+    harness.stop_server().await?;
+    emulator.kill().await?;
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_e2e_cli_server_connection_failure() -> Result<()> {
     // Test CLI behavior when server is not running
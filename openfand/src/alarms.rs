@@ -0,0 +1,903 @@
+//! Fan stall and failure alarms
+//!
+//! Runs one background task per connected controller. Each task takes the
+//! RPM of every fan each `[alarms] interval_secs` from the telemetry readings,
+//! which are only read from the hardware when stale, and compares it with the
+//! PWM or target RPM the fan was last set to. Fans never set by the daemon are not
+//! checked, as their speed is up to the firmware.
+//!
+//! A fault lasting `grace_secs` raises an alarm, which stays active until the
+//! fan behaves again. Raising an alarm applies the `failsafe_profile` to the
+//! controller, when one is configured, and holds the controller's curve
+//! bindings until none of its alarms is active anymore. Active alarms and the
+//! holds they placed carry over when the monitor restarts, as on a reload;
+//! those of a controller no longer monitored are dropped.
+//!
+//! Controllers are not checked while a calibration job runs on them, as the
+//! sweep stops and slows their fans on purpose.
+
+use crate::calibration::Calibrator;
+use crate::config::RuntimeConfig;
+use crate::control::{ControlEngine, Hold};
+use crate::controllers::{ConnectionManager, ControllerEntry, ControllerRegistry};
+use crate::limits::FanLimiter;
+use crate::shutdown::find_profile;
+use crate::telemetry::{FanSample, Telemetry};
+use openfan_core::api::{AlarmInfo, AlarmKind};
+use openfan_core::{AlarmsConfig, FanStateData};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+/// Key identifying a fan: (controller ID, fan ID)
+type FanKey = (String, u8);
+
+/// Fault seen on a fan, not yet lasting long enough to raise an alarm
+#[derive(Debug, Clone, Copy)]
+struct PendingFault {
+    kind: AlarmKind,
+    /// PWM and target RPM when the fault was first seen
+    command: (Option<u32>, Option<u32>),
+    since: Instant,
+}
+
+/// Faults a controller's monitor carries from one sample to the next
+#[derive(Debug, Default)]
+struct FanWatch {
+    pending: HashMap<u8, PendingFault>,
+}
+
+impl FanWatch {
+    /// Update the controller's alarms from a sample taken at `now`.
+    ///
+    /// Returns the alarms newly raised.
+    fn update(
+        &mut self,
+        config: &AlarmsConfig,
+        controller_id: &str,
+        sample: &FanSample,
+        now: Instant,
+        alarms: &mut HashMap<FanKey, AlarmInfo>,
+    ) -> Vec<AlarmInfo> {
+        let grace = Duration::from_secs(config.grace_secs);
+        let mut fans: Vec<_> = sample.rpm.iter().collect();
+        fans.sort();
+
+        let mut raised = Vec::new();
+        for (&fan, &rpm) in fans {
+            let key = (controller_id.to_string(), fan);
+            let pwm = sample.state.pwm.get(&fan).copied();
+            let target_rpm = sample.state.rpm.get(&fan).copied();
+
+            let Some(kind) = check_fan(config, rpm, pwm, target_rpm) else {
+                self.pending.remove(&fan);
+                if alarms.remove(&key).is_some() {
+                    info!(
+                        "Controller '{}': Alarm cleared on fan {} ({} RPM)",
+                        controller_id, fan, rpm
+                    );
+                }
+                continue;
+            };
+
+            let alarm = AlarmInfo {
+                controller: controller_id.to_string(),
+                fan,
+                kind,
+                rpm,
+                pwm,
+                target_rpm,
+                since: unix_time(),
+                message: describe(config, kind, fan, rpm, pwm, target_rpm),
+            };
+            match alarms.get_mut(&key) {
+                // Still the same fault: keep the alarm up to date
+                Some(active) if active.kind == kind => {
+                    active.rpm = rpm;
+                    active.pwm = pwm;
+                    active.target_rpm = target_rpm;
+                    active.message = alarm.message;
+                    continue;
+                }
+                Some(_) => {
+                    alarms.remove(&key);
+                }
+                None => {}
+            }
+
+            let command = (pwm, target_rpm);
+            let pending = self.pending.entry(fan).or_insert(PendingFault {
+                kind,
+                command,
+                since: now,
+            });
+            // A new fault, or a new command giving the fan time to settle
+            if pending.kind != kind || pending.command != command {
+                *pending = PendingFault {
+                    kind,
+                    command,
+                    since: now,
+                };
+            }
+            if now.duration_since(pending.since) >= grace {
+                self.pending.remove(&fan);
+                warn!("Controller '{}': {}", controller_id, alarm.message);
+                alarms.insert(key, alarm.clone());
+                raised.push(alarm);
+            }
+        }
+        raised
+    }
+}
+
+/// Fault of a fan given its RPM and what it was last set to
+fn check_fan(
+    config: &AlarmsConfig,
+    rpm: u32,
+    pwm: Option<u32>,
+    target_rpm: Option<u32>,
+) -> Option<AlarmKind> {
+    let expected_to_spin =
+        pwm.is_some_and(|pwm| pwm >= config.spin_pwm) || target_rpm.is_some_and(|rpm| rpm > 0);
+
+    if expected_to_spin {
+        if rpm <= config.stall_rpm {
+            Some(AlarmKind::Stall)
+        } else if rpm < config.low_rpm {
+            Some(AlarmKind::LowRpm)
+        } else {
+            None
+        }
+    } else if pwm == Some(0) && rpm > config.stall_rpm {
+        Some(AlarmKind::UnexpectedSpin)
+    } else {
+        None
+    }
+}
+
+fn describe(
+    config: &AlarmsConfig,
+    kind: AlarmKind,
+    fan: u8,
+    rpm: u32,
+    pwm: Option<u32>,
+    target_rpm: Option<u32>,
+) -> String {
+    let command = match (pwm, target_rpm) {
+        (_, Some(target)) => format!("target {} RPM", target),
+        (Some(pwm), None) => format!("{}% PWM", pwm),
+        (None, None) => "not set".to_string(),
+    };
+    match kind {
+        AlarmKind::Stall => format!("Fan {} stalled at {} RPM ({})", fan, rpm, command),
+        AlarmKind::LowRpm => format!(
+            "Fan {} at {} RPM, below {} RPM ({})",
+            fan, rpm, config.low_rpm, command
+        ),
+        AlarmKind::UnexpectedSpin => {
+            format!("Fan {} spinning at {} RPM ({})", fan, rpm, command)
+        }
+    }
+}
+
+/// Current Unix time, in seconds
+pub(crate) fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Manages the fan monitoring tasks and the active alarms of all controllers.
+pub(crate) struct AlarmMonitor {
+    registry: Arc<ControllerRegistry>,
+    config: Arc<RuntimeConfig>,
+    telemetry: Arc<Telemetry>,
+    control: Arc<ControlEngine>,
+    calibration: Arc<Calibrator>,
+    tasks: Mutex<HashMap<String, JoinHandle<()>>>,
+    alarms: Arc<RwLock<HashMap<FanKey, AlarmInfo>>>,
+}
+
+impl AlarmMonitor {
+    /// Create a new alarm monitor with no running tasks.
+    pub fn new(
+        registry: Arc<ControllerRegistry>,
+        config: Arc<RuntimeConfig>,
+        telemetry: Arc<Telemetry>,
+        control: Arc<ControlEngine>,
+        calibration: Arc<Calibrator>,
+    ) -> Self {
+        Self {
+            registry,
+            config,
+            telemetry,
+            control,
+            calibration,
+            tasks: Mutex::new(HashMap::new()),
+            alarms: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Start monitoring every registered controller with a hardware connection.
+    ///
+    /// Alarms still active from before a restart are kept, with the bindings
+    /// their failsafe holds, until the fans behave again. Those of controllers
+    /// no longer monitored are dropped and their bindings released, as nothing
+    /// would clear them.
+    pub async fn start_all(&self) {
+        let alarms_config = &self.config.static_config().alarms;
+        let entries: Vec<_> = self
+            .registry
+            .list()
+            .await
+            .into_iter()
+            .filter(|entry| alarms_config.enabled && entry.connection_manager().is_some())
+            .collect();
+        let monitored: HashSet<&str> = entries.iter().map(|entry| entry.id()).collect();
+        self.forget_unmonitored(&monitored).await;
+        if !alarms_config.enabled {
+            info!("Fan alarms disabled in config");
+            return;
+        }
+
+        let mut tasks = self.tasks.lock().await;
+        for entry in entries {
+            let Some(cm) = entry.connection_manager() else {
+                continue;
+            };
+            info!(
+                "Controller '{}': Monitoring fans every {}s",
+                entry.id(),
+                alarms_config.interval_secs
+            );
+            let handle = tokio::spawn(run_monitor(
+                self.config.clone(),
+                self.telemetry.clone(),
+                self.control.clone(),
+                self.calibration.clone(),
+                self.alarms.clone(),
+                entry.clone(),
+                cm.clone(),
+            ));
+            if let Some(previous) = tasks.insert(entry.id().to_string(), handle) {
+                previous.abort();
+            }
+        }
+    }
+
+    /// Stop all monitoring tasks, keeping the active alarms for [`Self::start_all`].
+    ///
+    /// Called before the shutdown profile is applied so a failsafe profile
+    /// doesn't override it.
+    pub async fn stop_all(&self) {
+        let mut tasks = self.tasks.lock().await;
+        for (_, handle) in tasks.drain() {
+            handle.abort();
+        }
+    }
+
+    /// Drop the alarms of the controllers not in `monitored`, releasing the
+    /// bindings held by their failsafe.
+    async fn forget_unmonitored(&self, monitored: &HashSet<&str>) {
+        let mut forgotten: Vec<String> = {
+            let mut alarms = self.alarms.write().await;
+            let forgotten = alarms
+                .keys()
+                .map(|(controller, _)| controller.clone())
+                .filter(|controller| !monitored.contains(controller.as_str()))
+                .collect::<HashSet<_>>();
+            alarms.retain(|(controller, _), _| !forgotten.contains(controller));
+            forgotten.into_iter().collect()
+        };
+        forgotten.sort();
+        for controller_id in forgotten {
+            info!(
+                "Controller '{}': Alarms dropped, the controller is no longer monitored",
+                controller_id
+            );
+            self.control.release(&controller_id, Hold::Failsafe).await;
+        }
+    }
+
+    /// Active alarms, by controller and fan ID.
+    pub async fn list(&self) -> Vec<AlarmInfo> {
+        let mut alarms: Vec<AlarmInfo> = self.alarms.read().await.values().cloned().collect();
+        alarms.sort_by(|a, b| (&a.controller, a.fan).cmp(&(&b.controller, b.fan)));
+        alarms
+    }
+}
+
+/// Monitoring loop for a single controller.
+async fn run_monitor(
+    config: Arc<RuntimeConfig>,
+    telemetry: Arc<Telemetry>,
+    control: Arc<ControlEngine>,
+    calibration: Arc<Calibrator>,
+    alarms: Arc<RwLock<HashMap<FanKey, AlarmInfo>>>,
+    entry: Arc<ControllerEntry>,
+    cm: Arc<ConnectionManager>,
+) {
+    let alarms_config = config.static_config().alarms.clone();
+    let mut ticker = tokio::time::interval(Duration::from_secs(alarms_config.interval_secs.max(1)));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut watch = FanWatch::default();
+
+    loop {
+        ticker.tick().await;
+
+        if calibration.is_running(entry.id()).await {
+            // Faults seen during the sweep don't carry over
+            watch = FanWatch::default();
+            continue;
+        }
+
+        let sample = telemetry.latest(entry.id(), &cm).await;
+        let sample = match sample {
+            Ok(sample) => sample,
+            Err(e) => {
+                // Connection problems are reported by the connection manager
+                debug!("Controller '{}': Fan monitoring skipped: {}", entry.id(), e);
+                continue;
+            }
+        };
+
+        let raised = watch.update(
+            &alarms_config,
+            entry.id(),
+            &sample,
+            Instant::now(),
+            &mut *alarms.write().await,
+        );
+
+        if !raised.is_empty()
+            && let Some(profile_name) = &alarms_config.failsafe_profile
+        {
+            apply_failsafe(&config, &control, &entry, &cm, profile_name).await;
+        } else if !alarms
+            .read()
+            .await
+            .keys()
+            .any(|(controller, _)| controller == entry.id())
+        {
+            // Also lifts a hold placed before the monitor restarted
+            control.release(entry.id(), Hold::Failsafe).await;
+        }
+    }
+}
+
+/// Apply the failsafe profile to a controller after an alarm.
+///
+/// The controller's bindings are held first so they don't override it.
+/// Nothing is done if the profile is not found.
+async fn apply_failsafe(
+    config: &RuntimeConfig,
+    control: &ControlEngine,
+    entry: &ControllerEntry,
+    cm: &ConnectionManager,
+    profile_name: &openfan_core::ProfileName,
+) {
+    let Some(profile) = find_profile(config, entry.id(), profile_name).await else {
+        warn!(
+            "Controller '{}': Failsafe profile '{}' not found",
+            entry.id(),
+            profile_name
+        );
+        return;
+    };
+    control.hold(entry.id(), Hold::Failsafe).await;
+
+    warn!(
+        "Controller '{}': Applying failsafe profile '{}'",
+        entry.id(),
+        profile_name
+    );
+    // Always clamped into the fan limits, as a rejected fan could be left stopped
+    let state = FanStateData::from_profile(&profile, entry.board_info().fan_count);
+    let result = match FanLimiter::load(config, entry).await {
+        Ok(limiter) => cm.restore_state(&limiter.clamp_state(&state)).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(failed) if failed.is_empty() => {}
+        Ok(failed) => warn!(
+            "Controller '{}': Failsafe profile not set on fan(s) {:?}",
+            entry.id(),
+            failed
+        ),
+        Err(e) => warn!(
+            "Controller '{}': Failed to apply failsafe profile: {}",
+            entry.id(),
+            e
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::connection;
+    use crate::controllers::{Device, SimulatedDevice};
+    use crate::sensors::SensorManager;
+    use openfan_core::CurveBinding;
+    use openfan_core::api::CalibrationRequest;
+    use openfan_core::config::StaticConfig;
+    use openfan_core::{BoardType, ProfileName, ReconnectConfig};
+    use openfan_hardware::SimulatorConfig;
+    use tempfile::TempDir;
+
+    fn sample(rpm: &[(u8, u32)], pwm: &[(u8, u32)], target_rpm: &[(u8, u32)]) -> FanSample {
+        FanSample {
+            rpm: rpm.iter().copied().collect(),
+            state: FanStateData::new(
+                pwm.iter().copied().collect(),
+                target_rpm.iter().copied().collect(),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_check_fan() {
+        let config = AlarmsConfig {
+            low_rpm: 500,
+            ..AlarmsConfig::default()
+        };
+
+        assert_eq!(
+            check_fan(&config, 0, Some(60), None),
+            Some(AlarmKind::Stall)
+        );
+        assert_eq!(
+            check_fan(&config, 300, Some(60), None),
+            Some(AlarmKind::LowRpm)
+        );
+        assert_eq!(check_fan(&config, 1200, Some(60), None), None);
+        assert_eq!(
+            check_fan(&config, 80, None, Some(1000)),
+            Some(AlarmKind::Stall)
+        );
+        assert_eq!(
+            check_fan(&config, 900, Some(0), None),
+            Some(AlarmKind::UnexpectedSpin)
+        );
+        // Below spin_pwm, a fan may or may not spin
+        assert_eq!(check_fan(&config, 0, Some(10), None), None);
+        assert_eq!(check_fan(&config, 900, Some(10), None), None);
+        // Never set by the daemon
+        assert_eq!(check_fan(&config, 0, None, None), None);
+    }
+
+    #[test]
+    fn test_alarm_raised_after_grace_period() {
+        let config = AlarmsConfig::default();
+        let mut watch = FanWatch::default();
+        let mut alarms = HashMap::new();
+        let start = Instant::now();
+        let stalled = sample(&[(0, 0), (1, 1500)], &[(0, 60), (1, 60)], &[]);
+
+        let raised = watch.update(&config, "main", &stalled, start, &mut alarms);
+        assert!(raised.is_empty());
+        let raised = watch.update(
+            &config,
+            "main",
+            &stalled,
+            start + Duration::from_secs(5),
+            &mut alarms,
+        );
+        assert!(raised.is_empty());
+
+        let raised = watch.update(
+            &config,
+            "main",
+            &stalled,
+            start + Duration::from_secs(10),
+            &mut alarms,
+        );
+        assert_eq!(raised.len(), 1);
+        assert_eq!(raised[0].fan, 0);
+        assert_eq!(raised[0].kind, AlarmKind::Stall);
+        assert_eq!(raised[0].message, "Fan 0 stalled at 0 RPM (60% PWM)");
+        assert_eq!(alarms.len(), 1);
+
+        // Raised once, then kept while the fault lasts
+        let raised = watch.update(
+            &config,
+            "main",
+            &stalled,
+            start + Duration::from_secs(15),
+            &mut alarms,
+        );
+        assert!(raised.is_empty());
+        assert_eq!(alarms.len(), 1);
+
+        // Cleared as soon as the fan spins again
+        let spinning = sample(&[(0, 1400), (1, 1500)], &[(0, 60), (1, 60)], &[]);
+        watch.update(
+            &config,
+            "main",
+            &spinning,
+            start + Duration::from_secs(20),
+            &mut alarms,
+        );
+        assert!(alarms.is_empty());
+    }
+
+    #[test]
+    fn test_new_command_restarts_grace_period() {
+        let config = AlarmsConfig::default();
+        let mut watch = FanWatch::default();
+        let mut alarms = HashMap::new();
+        let start = Instant::now();
+
+        watch.update(
+            &config,
+            "main",
+            &sample(&[(0, 0)], &[(0, 0)], &[]),
+            start,
+            &mut alarms,
+        );
+        // Just set from 0% to 80%: the fan is still spinning up
+        watch.update(
+            &config,
+            "main",
+            &sample(&[(0, 50)], &[(0, 80)], &[]),
+            start + Duration::from_secs(8),
+            &mut alarms,
+        );
+        let raised = watch.update(
+            &config,
+            "main",
+            &sample(&[(0, 60)], &[(0, 80)], &[]),
+            start + Duration::from_secs(12),
+            &mut alarms,
+        );
+        assert!(raised.is_empty());
+
+        let raised = watch.update(
+            &config,
+            "main",
+            &sample(&[(0, 60)], &[(0, 80)], &[]),
+            start + Duration::from_secs(18),
+            &mut alarms,
+        );
+        assert_eq!(raised.len(), 1);
+    }
+
+    async fn create_config(temp_dir: &TempDir, alarms: AlarmsConfig) -> Arc<RuntimeConfig> {
+        let config_path = temp_dir.path().join("config.toml");
+        let data_dir = temp_dir.path().join("data");
+        let static_config = StaticConfig {
+            data_dir: data_dir.clone(),
+            alarms,
+            ..StaticConfig::default()
+        };
+        tokio::fs::create_dir_all(&data_dir).await.unwrap();
+        tokio::fs::write(&config_path, static_config.to_toml().unwrap())
+            .await
+            .unwrap();
+        Arc::new(RuntimeConfig::load(&config_path).await.unwrap())
+    }
+
+    /// Alarm monitor of a "main" controller backed by a 2-fan simulated board
+    struct Setup {
+        monitor: AlarmMonitor,
+        registry: Arc<ControllerRegistry>,
+        control: Arc<ControlEngine>,
+        calibration: Arc<Calibrator>,
+        cm: Arc<ConnectionManager>,
+        simulated: SimulatedDevice,
+    }
+
+    async fn create_monitor(config: Arc<RuntimeConfig>) -> Setup {
+        let board = BoardType::Custom { fan_count: 2 }.to_board_info();
+        let simulated = SimulatedDevice::new(
+            "main",
+            SimulatorConfig::new(2)
+                .with_noise(0)
+                .with_time_constant(Duration::ZERO),
+        );
+        let device = Device::Simulated(simulated.clone());
+        let controller = connection::open_device(&device, &board, 1000, false)
+            .await
+            .unwrap();
+        let cm = Arc::new(ConnectionManager::new(
+            controller,
+            ReconnectConfig::default(),
+            device,
+            board.clone(),
+            1000,
+            false,
+        ));
+        let registry = Arc::new(ControllerRegistry::new());
+        registry
+            .register(
+                ControllerEntry::builder("main", board)
+                    .maybe_connection_manager(Some(cm.clone()))
+                    .build(),
+            )
+            .await
+            .unwrap();
+
+        let sensors = Arc::new(SensorManager::new(&config.static_config().sensors));
+        let control = Arc::new(ControlEngine::new(
+            registry.clone(),
+            config.clone(),
+            sensors.clone(),
+        ));
+        let calibration = Arc::new(Calibrator::new(
+            registry.clone(),
+            config.clone(),
+            control.clone(),
+        ));
+        let telemetry = Arc::new(Telemetry::new(registry.clone(), config.clone(), sensors));
+        Setup {
+            monitor: AlarmMonitor::new(
+                registry.clone(),
+                config,
+                telemetry,
+                control.clone(),
+                calibration.clone(),
+            ),
+            registry,
+            control,
+            calibration,
+            cm,
+            simulated,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_monitor_raises_alarm_and_applies_failsafe() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = create_config(
+            &temp_dir,
+            AlarmsConfig {
+                interval_secs: 1,
+                grace_secs: 0,
+                failsafe_profile: Some(ProfileName::new("100% PWM")),
+                ..AlarmsConfig::default()
+            },
+        )
+        .await;
+
+        let Setup {
+            monitor,
+            cm,
+            simulated,
+            ..
+        } = create_monitor(config).await;
+        cm.with_controller(async |ctrl| ctrl.set_all_fan_pwm(50).await)
+            .await
+            .unwrap();
+        simulated.set_stalled(1, true);
+
+        monitor.start_all().await;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while monitor.list().await.is_empty() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("stalled fan should raise an alarm");
+
+        let alarms = monitor.list().await;
+        assert_eq!(alarms.len(), 1);
+        assert_eq!(alarms[0].controller, "main");
+        assert_eq!(alarms[0].fan, 1);
+        assert_eq!(alarms[0].kind, AlarmKind::Stall);
+
+        // The failsafe profile took over every fan
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let pwm = cm
+            .with_controller(async |ctrl| Ok(ctrl.get_all_fan_pwm()))
+            .await
+            .unwrap();
+        assert_eq!(pwm, HashMap::from([(0, 100), (1, 100)]));
+
+        // Kept for the next start, as the fan may still be stalled
+        monitor.stop_all().await;
+        assert_eq!(monitor.list().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_monitor_skips_calibrating_controller() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = create_config(
+            &temp_dir,
+            AlarmsConfig {
+                interval_secs: 1,
+                grace_secs: 0,
+                failsafe_profile: Some(ProfileName::new("100% PWM")),
+                ..AlarmsConfig::default()
+            },
+        )
+        .await;
+        let Setup {
+            monitor,
+            calibration,
+            cm,
+            simulated,
+            ..
+        } = create_monitor(config).await;
+
+        cm.with_controller(async |ctrl| ctrl.set_all_fan_pwm(50).await)
+            .await
+            .unwrap();
+        simulated.set_stalled(1, true);
+        calibration
+            .start(
+                "main",
+                CalibrationRequest {
+                    fans: vec![0],
+                    step: 50,
+                    settle_secs: 5,
+                },
+            )
+            .await
+            .unwrap();
+
+        monitor.start_all().await;
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        assert!(monitor.list().await.is_empty());
+
+        // Checked again once the job is over
+        calibration.cancel("main").await;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while monitor.list().await.is_empty() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("stalled fan should raise an alarm after calibration");
+
+        monitor.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_failsafe_holds_bindings_until_cleared() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = create_config(
+            &temp_dir,
+            AlarmsConfig {
+                interval_secs: 1,
+                grace_secs: 0,
+                failsafe_profile: Some(ProfileName::new("100% PWM")),
+                ..AlarmsConfig::default()
+            },
+        )
+        .await;
+        let sensor = temp_dir.path().join("temp");
+        std::fs::write(&sensor, "50000").unwrap();
+        let binding = CurveBinding::for_fans("cpu", "Balanced", sensor.to_str().unwrap(), vec![0])
+            .with_interval(1);
+        let data = config.controller_data("main").await.unwrap();
+        data.curve_bindings_mut()
+            .await
+            .insert(binding.name.clone(), binding.clone());
+        let Setup {
+            monitor,
+            control,
+            cm,
+            simulated,
+            ..
+        } = create_monitor(config).await;
+        let fan_pwm = async |fan: u8| {
+            cm.with_controller(async |ctrl| Ok(ctrl.get_single_fan_pwm(fan)))
+                .await
+                .unwrap()
+        };
+
+        cm.with_controller(async |ctrl| ctrl.set_all_fan_pwm(50).await)
+            .await
+            .unwrap();
+        simulated.set_stalled(1, true);
+        monitor.start_all().await;
+        control.start_all().await;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while monitor.list().await.is_empty() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("stalled fan should raise an alarm");
+
+        // The binding doesn't take fan 0 back from the failsafe profile
+        tokio::time::sleep(Duration::from_millis(2500)).await;
+        assert_eq!(fan_pwm(0).await, Some(100));
+        assert!(!control.status("main", &binding).await.running);
+
+        // Once the alarm clears, the binding drives fan 0 again
+        simulated.set_stalled(1, false);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while fan_pwm(0).await != Some(50) {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("binding should be released once the alarm clears");
+        assert!(monitor.list().await.is_empty());
+
+        monitor.stop_all().await;
+        control.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_failsafe_survives_reload() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = create_config(
+            &temp_dir,
+            AlarmsConfig {
+                interval_secs: 1,
+                grace_secs: 0,
+                failsafe_profile: Some(ProfileName::new("100% PWM")),
+                ..AlarmsConfig::default()
+            },
+        )
+        .await;
+        let sensor = temp_dir.path().join("temp");
+        std::fs::write(&sensor, "50000").unwrap();
+        let binding = CurveBinding::for_fans("cpu", "Balanced", sensor.to_str().unwrap(), vec![0])
+            .with_interval(1);
+        let data = config.controller_data("main").await.unwrap();
+        data.curve_bindings_mut()
+            .await
+            .insert(binding.name.clone(), binding.clone());
+        let Setup {
+            monitor,
+            registry,
+            control,
+            cm,
+            simulated,
+            ..
+        } = create_monitor(config).await;
+
+        cm.with_controller(async |ctrl| ctrl.set_all_fan_pwm(50).await)
+            .await
+            .unwrap();
+        simulated.set_stalled(1, true);
+        monitor.start_all().await;
+        control.start_all().await;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while monitor.list().await.is_empty() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("stalled fan should raise an alarm");
+
+        // A reload restarts the services while the fan is still stalled
+        monitor.stop_all().await;
+        control.stop_all().await;
+        control.start_all().await;
+        monitor.start_all().await;
+        assert_eq!(monitor.list().await.len(), 1);
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert_eq!(monitor.list().await.len(), 1);
+        let status = control.status("main", &binding).await;
+        assert!(!status.running);
+        assert_eq!(status.error.as_deref(), Some("Held by a failsafe alarm"));
+        let pwm = cm
+            .with_controller(async |ctrl| Ok(ctrl.get_single_fan_pwm(0)))
+            .await
+            .unwrap();
+        assert_eq!(pwm, Some(100));
+
+        // Removing the controller drops its alarms and the hold
+        monitor.stop_all().await;
+        registry.unregister("main").await;
+        monitor.start_all().await;
+        assert!(monitor.list().await.is_empty());
+        assert!(
+            control.status("main", &binding).await.error.as_deref()
+                != Some("Held by a failsafe alarm")
+        );
+
+        monitor.stop_all().await;
+        control.stop_all().await;
+    }
+}
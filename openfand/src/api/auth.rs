@@ -0,0 +1,332 @@
+//! API token authentication
+//!
+//! Once `[[auth.tokens]]` are configured, every request but `GET /` must carry
+//! one of them as `Authorization: Bearer <token>`. Tokens are compared by
+//! their SHA-256, so the configuration never holds a usable secret.
+
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::MethodRouter,
+};
+use openfan_core::{AuthConfig, TokenScope};
+use sha2::{Digest, Sha256};
+use tracing::debug;
+
+/// SHA-256 of a token, as lowercase hexadecimal digits.
+fn token_hash(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Extract the token from an `Authorization: Bearer` header.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+/// Find the scope granted to a request, or why it is refused.
+fn authorize(auth: &AuthConfig, headers: &HeaderMap) -> Result<TokenScope, ApiError> {
+    if !auth.enabled() {
+        return Ok(TokenScope::Control);
+    }
+
+    let token = bearer_token(headers).ok_or_else(|| {
+        ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            "API token required: send it as 'Authorization: Bearer <token>'",
+        )
+    })?;
+
+    let hash = token_hash(token);
+    let entry = auth
+        .tokens
+        .iter()
+        .find(|entry| entry.sha256.eq_ignore_ascii_case(&hash))
+        .ok_or_else(|| ApiError::new(StatusCode::UNAUTHORIZED, "Invalid API token"))?;
+
+    debug!("Request authorized by token '{}'", entry.name);
+    Ok(entry.scope)
+}
+
+/// Reject requests without a valid token, and record the scope of the others.
+pub(crate) async fn authenticate(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    match authorize(&state.config.static_config().auth, request.headers()) {
+        Ok(scope) => {
+            request.extensions_mut().insert(scope);
+            next.run(request).await
+        }
+        Err(error) => ([(header::WWW_AUTHENTICATE, "Bearer")], error).into_response(),
+    }
+}
+
+/// Reject requests whose token only grants read access.
+async fn require_control(request: Request, next: Next) -> Result<Response, ApiError> {
+    match request.extensions().get::<TokenScope>() {
+        Some(TokenScope::Control) => Ok(next.run(request).await),
+        _ => Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "API token is read-only: this request needs a token with the control scope",
+        )),
+    }
+}
+
+/// Restrict a route to tokens with the control scope.
+pub(crate) fn control<S>(route: MethodRouter<S>) -> MethodRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    route.route_layer(middleware::from_fn(require_control))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use openfan_core::ApiTokenConfig;
+
+    fn auth() -> AuthConfig {
+        AuthConfig {
+            tokens: vec![
+                ApiTokenConfig {
+                    name: "grafana".to_string(),
+                    sha256: token_hash("reader"),
+                    scope: TokenScope::Read,
+                },
+                ApiTokenConfig {
+                    name: "admin".to_string(),
+                    sha256: token_hash("writer").to_uppercase(),
+                    scope: TokenScope::Control,
+                },
+            ],
+        }
+    }
+
+    fn headers(authorization: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(authorization).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_token_hash() {
+        assert_eq!(
+            token_hash("test"),
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+        );
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        assert_eq!(bearer_token(&headers("Bearer abc")), Some("abc"));
+        assert_eq!(bearer_token(&headers("bearer  abc ")), Some("abc"));
+        assert_eq!(bearer_token(&headers("Basic abc")), None);
+        assert_eq!(bearer_token(&headers("Bearer ")), None);
+        assert_eq!(bearer_token(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn test_authorize() {
+        // Without tokens, everything is allowed
+        let scope = authorize(&AuthConfig::default(), &HeaderMap::new()).unwrap();
+        assert_eq!(scope, TokenScope::Control);
+
+        let auth = auth();
+        let error = authorize(&auth, &HeaderMap::new()).unwrap_err();
+        assert_eq!(error.status_code, StatusCode::UNAUTHORIZED);
+        assert!(error.message.contains("token required"));
+
+        let error = authorize(&auth, &headers("Bearer nope")).unwrap_err();
+        assert_eq!(error.status_code, StatusCode::UNAUTHORIZED);
+        assert_eq!(error.message, "Invalid API token");
+
+        let scope = authorize(&auth, &headers("Bearer reader")).unwrap();
+        assert_eq!(scope, TokenScope::Read);
+        // Hashes are matched whatever their case
+        let scope = authorize(&auth, &headers("Bearer writer")).unwrap();
+        assert_eq!(scope, TokenScope::Control);
+    }
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use super::token_hash;
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+
+    use tower::ServiceExt;
+
+    use crate::api::create_router;
+    use crate::api::test_utils::create_test_state;
+
+    /// A read-only token "reader" and a control token "writer"
+    fn tokens_config() -> String {
+        format!(
+            "[[auth.tokens]]\nname = \"grafana\"\nsha256 = \"{}\"\n\
+             [[auth.tokens]]\nname = \"admin\"\nsha256 = \"{}\"\nscope = \"control\"\n",
+            token_hash("reader"),
+            token_hash("writer")
+        )
+    }
+
+    async fn send(
+        router: Router,
+        method: &str,
+        uri: &str,
+        token: Option<&str>,
+    ) -> axum::response::Response {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        router
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn error_message(response: axum::response::Response) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["status"], "error");
+        json["error"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_requests_without_valid_token_are_rejected() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let router = create_router(create_test_state(&config_dir, &tokens_config()).await);
+
+        let response = send(router.clone(), "GET", "/api/v0/info", None).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[header::WWW_AUTHENTICATE], "Bearer");
+        assert!(error_message(response).await.contains("token required"));
+
+        let response = send(router.clone(), "GET", "/api/v0/info", Some("nope")).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(error_message(response).await, "Invalid API token");
+
+        // The health check stays public
+        let response = send(router, "GET", "/", None).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_read_token_cannot_control_fans() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let router = create_router(create_test_state(&config_dir, &tokens_config()).await);
+
+        for uri in [
+            "/api/v0/controller/default/fan/status",
+            "/api/v0/controller/default/cfm/list",
+            "/metrics",
+        ] {
+            let response = send(router.clone(), "GET", uri, Some("reader")).await;
+            assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+        }
+
+        let response = send(
+            router.clone(),
+            "GET",
+            "/api/v0/controller/default/fan/0/pwm?value=0",
+            Some("reader"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        assert!(error_message(response).await.contains("read-only"));
+
+        // Routes sharing a path keep the scope of each method
+        let response = send(
+            router,
+            "DELETE",
+            "/api/v0/controller/default/cfm/0",
+            Some("reader"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_control_token_can_control_fans() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let router = create_router(create_test_state(&config_dir, &tokens_config()).await);
+
+        let response = send(
+            router,
+            "GET",
+            "/api/v0/controller/default/fan/0/pwm?value=50",
+            Some("writer"),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    /// CORS preflight for a request carrying a token, from `origin`
+    async fn preflight(router: Router, origin: &str) -> axum::response::Response {
+        let request = Request::builder()
+            .method("OPTIONS")
+            .uri("/api/v0/info")
+            .header(header::ORIGIN, origin)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+            .body(Body::empty())
+            .unwrap();
+        router.oneshot(request).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cors_only_allows_configured_origins() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let router = create_router(create_test_state(&config_dir, "").await);
+        let response = preflight(router, "https://dashboard.example.com").await;
+        assert!(
+            !response
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        );
+
+        let config_dir = tempfile::tempdir().unwrap();
+        let config = format!(
+            "[server]\nbind_address = \"127.0.0.1\"\nport = 3000\ncommunication_timeout = 1\n\
+             allowed_origins = [\"https://dashboard.example.com\"]\n{}",
+            tokens_config()
+        );
+        let router = create_router(create_test_state(&config_dir, &config).await);
+
+        let response = preflight(router.clone(), "https://dashboard.example.com").await;
+        let headers = response.headers();
+        assert_eq!(
+            headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+            "https://dashboard.example.com"
+        );
+        let allowed = headers[header::ACCESS_CONTROL_ALLOW_HEADERS]
+            .to_str()
+            .unwrap();
+        assert_eq!(allowed, "authorization,content-type");
+
+        let response = preflight(router, "https://evil.example.com").await;
+        assert!(
+            !response
+                .headers()
+                .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+        );
+    }
+}
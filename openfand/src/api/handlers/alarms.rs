@@ -0,0 +1,82 @@
+//! Alarm handlers for fan stall and failure monitoring
+
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api_ok;
+use axum::{Json, extract::State};
+use openfan_core::api;
+use tracing::debug;
+
+/// Lists the active fan alarms of all controllers.
+///
+/// Alarms are raised by the fan monitor when a fan's RPM does not match the
+/// PWM or target RPM it was set to, and cleared once it does again.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/alarms`
+pub(crate) async fn list_alarms(
+    State(state): State<AppState>,
+) -> Result<Json<api::ApiResponse<api::AlarmListResponse>>, ApiError> {
+    debug!("Request: GET /api/v0/alarms");
+
+    let alarms = state.alarms.list().await;
+
+    debug!("Listed {} active alarms", alarms.len());
+    api_ok!(api::AlarmListResponse { alarms })
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use openfan_core::BoardType;
+    use tower::ServiceExt;
+
+    use crate::api::{AppState, create_router};
+    use crate::config::RuntimeConfig;
+
+    #[tokio::test]
+    async fn test_list_alarms_empty() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let data_dir = config_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let config_path = config_dir.path().join("config.toml");
+        std::fs::write(
+            &config_path,
+            format!(
+                "data_dir = \"{}\"\n",
+                data_dir.to_string_lossy().replace('\\', "\\\\")
+            ),
+        )
+        .unwrap();
+
+        let config = RuntimeConfig::load(&config_path).await.unwrap();
+        let state = AppState::single_controller(
+            BoardType::OpenFanStandard.to_board_info(),
+            std::sync::Arc::new(config),
+            None,
+        )
+        .await;
+        let router = create_router(state);
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v0/alarms")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["status"], "success");
+        assert!(json["data"]["alarms"].as_array().unwrap().is_empty());
+    }
+}
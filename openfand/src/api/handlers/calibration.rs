@@ -0,0 +1,269 @@
+//! Fan calibration handlers
+
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api_ok;
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use openfan_core::api;
+use tracing::debug;
+
+/// Starts calibrating fans of a controller.
+///
+/// The job runs in the background; its progress is reported by
+/// `GET /api/v0/controller/{id}/fan/calibration`. Fails with 409 if a job is
+/// already running on the controller.
+///
+/// # Endpoint
+///
+/// `POST /api/v0/controller/{id}/fan/calibration`
+///
+/// # Request Body
+///
+/// ```json
+/// {"fans": [0, 1], "step": 10, "settle_secs": 3}
+/// ```
+pub(crate) async fn start_calibration(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+    Json(request): Json<api::CalibrationRequest>,
+) -> Result<Json<api::ApiResponse<api::CalibrationStatus>>, ApiError> {
+    debug!(
+        "Request: POST /api/v0/controller/{}/fan/calibration",
+        controller_id
+    );
+
+    let status = state.calibration.start(&controller_id, request).await?;
+    api_ok!(status)
+}
+
+/// Reports the running or last calibration job of a controller.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/controller/{id}/fan/calibration`
+pub(crate) async fn get_calibration_status(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+) -> Result<Json<api::ApiResponse<api::CalibrationStatusResponse>>, ApiError> {
+    debug!(
+        "Request: GET /api/v0/controller/{}/fan/calibration",
+        controller_id
+    );
+
+    state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    api_ok!(api::CalibrationStatusResponse {
+        calibration: state.calibration.status(&controller_id).await,
+    })
+}
+
+/// Cancels the running calibration job of a controller.
+///
+/// The fans are restored and nothing is saved. Fails if no job is running.
+///
+/// # Endpoint
+///
+/// `DELETE /api/v0/controller/{id}/fan/calibration`
+pub(crate) async fn cancel_calibration(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+) -> Result<Json<api::ApiResponse<String>>, ApiError> {
+    debug!(
+        "Request: DELETE /api/v0/controller/{}/fan/calibration",
+        controller_id
+    );
+
+    state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    if !state.calibration.cancel(&controller_id).await {
+        return Err(ApiError::bad_request(format!(
+            "No calibration running on controller: {}",
+            controller_id
+        )));
+    }
+    api_ok!(format!(
+        "Cancelling calibration on controller '{}'",
+        controller_id
+    ))
+}
+
+/// Retrieves the saved calibration results of a controller's fans.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/controller/{id}/fan/calibration/results`
+pub(crate) async fn get_calibration_results(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+) -> Result<Json<api::ApiResponse<api::CalibrationResultsResponse>>, ApiError> {
+    debug!(
+        "Request: GET /api/v0/controller/{}/fan/calibration/results",
+        controller_id
+    );
+
+    state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    let data = state.config.controller_data(&controller_id).await?;
+    let calibration = data.load_fan_calibration().await?;
+    api_ok!(api::CalibrationResultsResponse {
+        controller: controller_id,
+        fans: calibration.fans,
+    })
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+
+    use openfan_core::BoardType;
+
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    use crate::api::{AppState, create_router, test_utils};
+
+    async fn create_test_state(temp_dir: &tempfile::TempDir, mock: bool) -> AppState {
+        if mock {
+            let board = BoardType::Custom { fan_count: 2 }.to_board_info();
+            let config = test_utils::load_config(temp_dir, "").await;
+            return AppState::single_controller(board, config, None).await;
+        }
+        test_utils::create_test_state(temp_dir, "").await
+    }
+
+    async fn send(
+        router: Router,
+        method: &str,
+        uri: &str,
+        body: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        };
+        let response = router.oneshot(request.unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_calibration_job_lifecycle() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&temp_dir, false).await;
+        let uri = "/api/v0/controller/default/fan/calibration";
+
+        let (status, json) = send(create_router(state.clone()), "GET", uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["data"]["calibration"].is_null());
+
+        let (status, json) = send(
+            create_router(state.clone()),
+            "POST",
+            uri,
+            Some(r#"{"fans": [1], "step": 50, "settle_secs": 0}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        assert_eq!(json["data"]["state"], "running");
+        assert_eq!(json["data"]["total_steps"], 5);
+
+        let mut json = serde_json::Value::Null;
+        for _ in 0..250 {
+            (_, json) = send(create_router(state.clone()), "GET", uri, None).await;
+            if json["data"]["calibration"]["state"] != "running" {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(
+            json["data"]["calibration"]["state"], "completed",
+            "{}",
+            json
+        );
+
+        let (status, json) = send(
+            create_router(state.clone()),
+            "GET",
+            "/api/v0/controller/default/fan/calibration/results",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let fan = &json["data"]["fans"]["1"];
+        assert_eq!(fan["points"].as_array().unwrap().len(), 3);
+        assert_eq!(fan["min_stable_pwm"], 50);
+        assert!(json["data"]["fans"]["0"].is_null());
+
+        // Nothing left to cancel
+        let (status, _) = send(create_router(state.clone()), "DELETE", uri, None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_calibration_rejected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&temp_dir, false).await;
+        let uri = "/api/v0/controller/default/fan/calibration";
+
+        let (status, _) = send(
+            create_router(state.clone()),
+            "POST",
+            uri,
+            Some(r#"{"step": 0}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = send(
+            create_router(state.clone()),
+            "POST",
+            uri,
+            Some(r#"{"fans": [7]}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = send(
+            create_router(state.clone()),
+            "POST",
+            uri,
+            Some(r#"{"settle_secs": 30}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(create_router(state.clone()), "POST", uri, Some("{}")).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = send(create_router(state.clone()), "DELETE", uri, None).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Controllers without hardware cannot be calibrated
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&temp_dir, true).await;
+        let (status, _) = send(create_router(state), "POST", uri, Some("{}")).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -9,7 +9,8 @@ use axum::{
 };
 use openfan_core::config::CfmMappingData;
 use openfan_core::{OpenFanError, api};
-use tracing::{debug, info};
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
 
 /// List all CFM mappings for a specific controller.
 ///
@@ -33,9 +34,10 @@ pub(crate) async fn list_controller_cfm(
     let controller_data = state.config.controller_data(&controller_id).await?;
 
     let cfm_data = controller_data.cfm_mappings().await;
-    let mappings = cfm_data.mappings.clone();
-
-    let response = api::CfmListResponse { mappings };
+    let response = api::CfmListResponse {
+        mappings: cfm_data.mappings.clone(),
+        models: cfm_data.models.clone(),
+    };
 
     info!(
         "Listed {} CFM mappings for controller '{}'",
@@ -84,6 +86,7 @@ pub(crate) async fn get_controller_cfm(
             let response = api::CfmGetResponse {
                 port: port_id,
                 cfm_at_100: cfm,
+                model: cfm_data.model(port_id).cloned().unwrap_or_default(),
             };
             api_ok!(response)
         }
@@ -121,10 +124,13 @@ pub(crate) async fn set_controller_cfm(
     // Validate port ID against board configuration
     entry.board_info().validate_fan_id(port_id)?;
 
-    // Validate CFM value
+    // Validate CFM value and airflow model
     if let Err(e) = CfmMappingData::validate_cfm(request.cfm_at_100) {
         return api_fail!(e);
     }
+    if let Err(e) = request.model.validate() {
+        return api_fail!(e);
+    }
 
     // Get controller data
     let controller_data = state.config.controller_data(&controller_id).await?;
@@ -133,6 +139,7 @@ pub(crate) async fn set_controller_cfm(
     {
         let mut cfm_data = controller_data.cfm_mappings_mut().await;
         cfm_data.set(port_id, request.cfm_at_100);
+        cfm_data.set_model(port_id, request.model);
     }
 
     // Save configuration
@@ -207,6 +214,147 @@ pub(crate) async fn delete_controller_cfm(
     api_ok!(())
 }
 
+/// Estimate the airflow of all fans of a controller.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/controller/{id}/cfm/airflow`
+pub(crate) async fn get_controller_airflow(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+) -> Result<Json<api::ApiResponse<api::ControllerAirflowResponse>>, ApiError> {
+    debug!(
+        "Request: GET /api/v0/controller/{}/cfm/airflow",
+        controller_id
+    );
+
+    let entry = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    let status = state.telemetry.fan_status(&entry).await?;
+    let controller_data = state.config.controller_data(&controller_id).await?;
+    let readings = Readings {
+        status: Ok(status),
+        cfm: controller_data.cfm_mappings().await.clone(),
+    };
+
+    let fans: Vec<_> = (0..entry.board_info().fan_count as u8)
+        .map(|fan_id| readings.fan(&controller_id, fan_id))
+        .collect();
+    let total_cfm = fans.iter().filter_map(|fan| fan.cfm).sum();
+
+    api_ok!(api::ControllerAirflowResponse {
+        controller: controller_id,
+        fans,
+        total_cfm,
+    })
+}
+
+/// Estimate the intake and exhaust airflow of every zone.
+///
+/// Fans of controllers that cannot be read are reported with the error and
+/// left out of the totals.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/cfm/zones`
+pub(crate) async fn get_zones_airflow(
+    State(state): State<AppState>,
+) -> Result<Json<api::ApiResponse<api::ZoneAirflowResponse>>, ApiError> {
+    debug!("Request: GET /api/v0/cfm/zones");
+
+    let mut zones: Vec<_> = state.config.zones().await.zones.values().cloned().collect();
+    zones.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Read each controller once, however many zones use it
+    let mut readings: HashMap<String, Readings> = HashMap::new();
+    for fan in zones.iter().flat_map(|zone| &zone.fans) {
+        if !readings.contains_key(&fan.controller) {
+            let read = Readings::read(&state, &fan.controller).await;
+            if let Err(e) = &read.status {
+                warn!(
+                    "Failed to read fans of controller '{}' for zone airflow: {}",
+                    fan.controller, e
+                );
+            }
+            readings.insert(fan.controller.clone(), read);
+        }
+    }
+
+    let zones = zones
+        .into_iter()
+        .map(|zone| {
+            let fans = zone
+                .fans
+                .iter()
+                .map(|fan| readings[&fan.controller].fan(&fan.controller, fan.fan_id))
+                .collect();
+            api::ZoneAirflow::new(zone.name, fans)
+        })
+        .collect();
+
+    api_ok!(api::ZoneAirflowResponse { zones })
+}
+
+/// Fan readings and CFM mappings of a controller
+struct Readings {
+    /// RPM and PWM of the fans, or why they could not be read
+    status: Result<api::FanStatusResponse, String>,
+    /// CFM mappings and airflow models of the fans
+    cfm: CfmMappingData,
+}
+
+impl Readings {
+    /// Read the fans and CFM mappings of a controller.
+    async fn read(state: &AppState, controller_id: &str) -> Self {
+        let status = match state.registry.get_or_err(controller_id).await {
+            Ok(entry) => state
+                .telemetry
+                .fan_status(&entry)
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        let cfm = match state.config.controller_data(controller_id).await {
+            Ok(data) => data.cfm_mappings().await.clone(),
+            Err(_) => CfmMappingData::new(),
+        };
+        Self { status, cfm }
+    }
+
+    /// Estimate the airflow of one fan.
+    fn fan(&self, controller_id: &str, fan_id: u8) -> api::FanAirflow {
+        let mut airflow = api::FanAirflow {
+            controller: controller_id.to_string(),
+            fan_id,
+            pwm: None,
+            rpm: None,
+            cfm: None,
+            source: None,
+            direction: self.cfm.direction(fan_id),
+            error: None,
+        };
+        match &self.status {
+            Ok(status) => {
+                airflow.pwm = status.pwms.get(&fan_id).copied();
+                airflow.rpm = status.rpms.get(&fan_id).copied();
+                if let Some(pwm) = airflow.pwm
+                    && let Some(estimate) = self.cfm.estimate(fan_id, pwm, airflow.rpm)
+                {
+                    // Round off the f32 noise, e.g. 27.000001907 for 27
+                    airflow.cfm = Some((estimate.cfm * 100.0).round() / 100.0);
+                    airflow.source = Some(estimate.source);
+                }
+            }
+            Err(e) => airflow.error = Some(e.clone()),
+        }
+        airflow
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use openfan_core::config::CfmMappingData;
@@ -318,6 +466,7 @@ mod tests {
         let json = r#"{"cfm_at_100": 45.0}"#;
         let request: api::SetCfmRequest = serde_json::from_str(json).unwrap();
         assert_eq!(request.cfm_at_100, 45.0);
+        assert!(request.model.is_empty());
     }
 
     #[test]
@@ -339,6 +488,7 @@ mod tests {
         let get_response = api::CfmGetResponse {
             port: 5,
             cfm_at_100: 45.0,
+            model: Default::default(),
         };
         assert_eq!(get_response.port, 5);
         assert_eq!(get_response.cfm_at_100, 45.0);
@@ -347,7 +497,10 @@ mod tests {
         let mut mappings = HashMap::new();
         mappings.insert(0, 30.0);
         mappings.insert(5, 45.0);
-        let list_response = api::CfmListResponse { mappings };
+        let list_response = api::CfmListResponse {
+            mappings,
+            models: HashMap::new(),
+        };
         assert_eq!(list_response.mappings.len(), 2);
         assert_eq!(list_response.mappings.get(&0), Some(&30.0));
         assert_eq!(list_response.mappings.get(&5), Some(&45.0));
@@ -364,16 +517,19 @@ mod integration_tests {
     };
     use http_body_util::BodyExt;
     use openfan_core::BoardType;
+    use std::time::Duration;
     use tempfile::TempDir;
     use tower::ServiceExt;
 
+    use crate::api::test_utils::{connect_simulated, load_config};
     use crate::api::{AppState, create_router};
-    use crate::config::RuntimeConfig;
+    use crate::controllers::SimulatedDevice;
 
     /// Test harness that keeps the config directory alive for the duration of the test.
     /// The TempDir must outlive the router since CFM handlers persist config to disk.
     struct TestApp {
         router: Router,
+        simulated: SimulatedDevice,
         _config_dir: TempDir,
     }
 
@@ -381,33 +537,22 @@ mod integration_tests {
         async fn new() -> Self {
             let board_info = BoardType::OpenFanStandard.to_board_info();
             let config_dir = tempfile::tempdir().unwrap();
-
-            // Create data directory inside temp dir
-            let data_dir = config_dir.path().join("data");
-            std::fs::create_dir_all(&data_dir).unwrap();
-
-            // Note: data_dir MUST be at top level in TOML, before any [section]
-            let data_dir_str = data_dir.to_string_lossy().replace('\\', "\\\\");
-            let config_content = format!(
-                r#"data_dir = "{}"
-
+            let config = load_config(
+                &config_dir,
+                r#"
 [server]
 bind_address = "127.0.0.1"
 port = 3000
 communication_timeout = 1
 "#,
-                data_dir_str
-            );
-
-            let config_path = config_dir.path().join("config.toml");
-            std::fs::write(&config_path, config_content).unwrap();
-
-            let config = RuntimeConfig::load(&config_path).await.unwrap();
-            let state =
-                AppState::single_controller(board_info, std::sync::Arc::new(config), None).await;
+            )
+            .await;
+            let (cm, simulated) = connect_simulated("default", &board_info).await;
+            let state = AppState::single_controller(board_info, config, Some(cm)).await;
 
             TestApp {
                 router: create_router(state),
+                simulated,
                 _config_dir: config_dir,
             }
         }
@@ -600,4 +745,163 @@ communication_timeout = 1
 
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    async fn send_json(
+        app: &TestApp,
+        method: Method,
+        uri: &str,
+        body: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+            None => request.body(Body::empty()).unwrap(),
+        };
+        let response = app.router().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = body_string(response.into_body()).await;
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    /// Drive the fans of the default controller and let them reach their speed
+    async fn drive_fans(app: &TestApp, settings: &[&str]) {
+        for setting in settings {
+            let uri = format!("/api/v0/controller/default/fan/{}", setting);
+            let (status, json) = send_json(app, Method::GET, &uri, None).await;
+            assert_eq!(status, StatusCode::OK, "{}", json);
+        }
+        app.simulated.advance(Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn test_set_cfm_with_model() {
+        let app = TestApp::new().await;
+
+        let body = r#"{"cfm_at_100": 60.0, "direction": "exhaust", "max_rpm": 2000,
+            "pwm_points": [{"at": 20, "cfm": 0.0}, {"at": 100, "cfm": 60.0}]}"#;
+        let (status, _) = send_json(
+            &app,
+            Method::POST,
+            "/api/v0/controller/default/cfm/1",
+            Some(body),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, json) =
+            send_json(&app, Method::GET, "/api/v0/controller/default/cfm/1", None).await;
+        assert_eq!(json["data"]["cfm_at_100"], 60.0);
+        assert_eq!(json["data"]["direction"], "exhaust");
+        assert_eq!(json["data"]["pwm_points"][0]["at"], 20);
+
+        let (_, json) = send_json(
+            &app,
+            Method::GET,
+            "/api/v0/controller/default/cfm/list",
+            None,
+        )
+        .await;
+        assert_eq!(json["data"]["models"]["1"]["max_rpm"], 2000);
+
+        // Setting only CFM@100% drops the model
+        let (status, _) = send_json(
+            &app,
+            Method::POST,
+            "/api/v0/controller/default/cfm/1",
+            Some(r#"{"cfm_at_100": 50.0}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, json) =
+            send_json(&app, Method::GET, "/api/v0/controller/default/cfm/1", None).await;
+        assert!(json["data"].get("direction").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_set_cfm_invalid_model() {
+        let app = TestApp::new().await;
+
+        let body = r#"{"cfm_at_100": 60.0, "pwm_points": [{"at": 100, "cfm": 60.0}, {"at": 20, "cfm": 0.0}]}"#;
+        let (status, json) = send_json(
+            &app,
+            Method::POST,
+            "/api/v0/controller/default/cfm/0",
+            Some(body),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(json["error"].as_str().unwrap().contains("ascending"));
+    }
+
+    #[tokio::test]
+    async fn test_controller_airflow() {
+        let app = TestApp::new().await;
+
+        drive_fans(&app, &["0/pwm?value=50", "1/pwm?value=80"]).await;
+        // 64 CFM at 3200 RPM
+        let fan1_cfm = f64::from(app.simulated.rpm(1).unwrap()) / 50.0;
+        for (port, body) in [
+            (0, r#"{"cfm_at_100": 40.0}"#),
+            (
+                1,
+                r#"{"cfm_at_100": 60.0, "rpm_points": [{"at": 0, "cfm": 0.0}, {"at": 3200, "cfm": 64.0}]}"#,
+            ),
+        ] {
+            let uri = format!("/api/v0/controller/default/cfm/{}", port);
+            let (status, _) = send_json(&app, Method::POST, &uri, Some(body)).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        let (status, json) = send_json(
+            &app,
+            Method::GET,
+            "/api/v0/controller/default/cfm/airflow",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        let data = &json["data"];
+        assert_eq!(data["fans"].as_array().unwrap().len(), 10);
+        assert_eq!(data["fans"][0]["cfm"], 20.0);
+        assert_eq!(data["fans"][0]["source"], "linear");
+        assert!((data["fans"][1]["cfm"].as_f64().unwrap() - fan1_cfm).abs() < 0.01);
+        assert_eq!(data["fans"][1]["source"], "rpm_table");
+        assert!(data["fans"][2].get("cfm").is_none());
+        assert!((data["total_cfm"].as_f64().unwrap() - (20.0 + fan1_cfm)).abs() < 0.01);
+    }
+
+    #[tokio::test]
+    async fn test_zones_airflow() {
+        let app = TestApp::new().await;
+
+        for (port, body) in [
+            (0, r#"{"cfm_at_100": 80.0, "direction": "intake"}"#),
+            (1, r#"{"cfm_at_100": 60.0, "direction": "exhaust"}"#),
+        ] {
+            let uri = format!("/api/v0/controller/default/cfm/{}", port);
+            let (status, _) = send_json(&app, Method::POST, &uri, Some(body)).await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let zone = r#"{"name": "case", "fans": [
+            {"controller": "default", "fan_id": 0},
+            {"controller": "default", "fan_id": 1},
+            {"controller": "default", "fan_id": 2}]}"#;
+        let (status, _) = send_json(&app, Method::POST, "/api/v0/zones/add", Some(zone)).await;
+        assert_eq!(status, StatusCode::OK);
+        drive_fans(&app, &["0/pwm?value=50", "1/pwm?value=55"]).await;
+
+        let (status, json) = send_json(&app, Method::GET, "/api/v0/cfm/zones", None).await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        let zone = &json["data"]["zones"][0];
+        assert_eq!(zone["zone"], "case");
+        // 50% of 80 in, 55% of 60 out
+        assert_eq!(zone["intake_cfm"], 40.0);
+        assert_eq!(zone["exhaust_cfm"], 33.0);
+        assert_eq!(zone["balance_cfm"], 7.0);
+        assert_eq!(zone["pressure"], "positive");
+        assert_eq!(zone["fans"].as_array().unwrap().len(), 3);
+    }
 }
@@ -1,15 +1,22 @@
 //! Controller management API handlers
 //!
-//! Endpoints for listing and managing multiple fan controllers.
+//! Endpoints for listing, discovering and managing multiple fan controllers.
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
 };
-use openfan_core::api::{ApiResponse, ControllerInfo, ControllersListResponse};
-use tracing::{info, warn};
+use openfan_core::api::{
+    AddControllerRequest, ApiResponse, ControllerInfo, ControllersListResponse, DiscoverResponse,
+    DiscoveredPort, DiscoverySource,
+};
+use openfan_hardware::{CandidateSource, discovery};
+use serde::Deserialize;
+use std::path::PathBuf;
+use tracing::{debug, info, warn};
 
 use crate::api::{AppState, error::ApiError};
+use crate::controllers::ControllerEntry;
 
 /// GET /api/v0/controllers
 ///
@@ -21,14 +28,7 @@ pub async fn list_controllers(
 
     let controller_list: Vec<ControllerInfo> = controllers
         .iter()
-        .map(|entry| ControllerInfo {
-            id: entry.id().to_string(),
-            board_name: entry.board_info().name.clone(),
-            fan_count: entry.board_info().fan_count,
-            description: entry.description().map(String::from),
-            mock_mode: entry.is_mock(),
-            connected: entry.is_connected(),
-        })
+        .map(|entry| controller_info(entry))
         .collect();
 
     let response = ControllersListResponse {
@@ -39,6 +39,123 @@ pub async fn list_controllers(
     Ok(Json(ApiResponse::success(response)))
 }
 
+/// Query parameters for removing a controller.
+#[derive(Deserialize)]
+pub(crate) struct RemoveControllerQuery {
+    /// Also remove the controller from `[[controllers]]`
+    #[serde(default)]
+    pub persist: bool,
+}
+
+/// POST /api/v0/controllers
+///
+/// Connect and register a controller without restarting. The body holds the
+/// fields of a `[[controllers]]` entry, and `persist` to add it there too.
+pub async fn add_controller(
+    State(state): State<AppState>,
+    Json(request): Json<AddControllerRequest>,
+) -> Result<Json<ApiResponse<ControllerInfo>>, ApiError> {
+    debug!(
+        "Request: POST /api/v0/controllers (id: {})",
+        request.controller.id
+    );
+
+    let entry = state
+        .reload
+        .add_controller(request.controller, request.persist)
+        .await?;
+
+    Ok(Json(ApiResponse::success(controller_info(&entry))))
+}
+
+/// DELETE /api/v0/controller/{id}
+///
+/// Disconnect and unregister a controller. With `?persist=true`, it is also
+/// removed from `[[controllers]]`.
+pub async fn remove_controller(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+    Query(query): Query<RemoveControllerQuery>,
+) -> Result<Json<ApiResponse<String>>, ApiError> {
+    debug!("Request: DELETE /api/v0/controller/{}", controller_id);
+
+    state
+        .reload
+        .remove_controller(&controller_id, query.persist)
+        .await?;
+
+    Ok(Json(ApiResponse::success(format!(
+        "Controller '{}' removed",
+        controller_id
+    ))))
+}
+
+/// GET /api/v0/controllers/discover
+///
+/// List serial ports that may be OpenFAN boards. Ports not used by a
+/// registered controller are probed for firmware info.
+pub async fn discover_controllers(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<DiscoverResponse>>, ApiError> {
+    debug!("Request: GET /api/v0/controllers/discover");
+
+    let timeout_ms = state.config.static_config().server.communication_timeout * 1000;
+    let in_use: Vec<(PathBuf, String)> = state
+        .registry
+        .list()
+        .await
+        .iter()
+        .filter_map(|entry| {
+            let path = entry.connection_manager()?.identity().path?;
+            Some((canonical_path(&path), entry.id().to_string()))
+        })
+        .collect();
+
+    let mut ports = Vec::new();
+    for candidate in discovery::find_candidates() {
+        let controller = in_use
+            .iter()
+            .find(|(path, _)| *path == canonical_path(&candidate.port))
+            .map(|(_, id)| id.clone());
+
+        // Opening a port in use would disturb its controller
+        let (firmware, error) = if controller.is_some() {
+            (None, None)
+        } else {
+            match discovery::probe(&candidate.port, timeout_ms).await {
+                Ok(firmware) => (Some(firmware), None),
+                Err(e) => (None, Some(e.to_string())),
+            }
+        };
+
+        ports.push(DiscoveredPort {
+            port: candidate.port,
+            source: match candidate.source {
+                CandidateSource::Env => DiscoverySource::Env,
+                CandidateSource::UsbId => DiscoverySource::UsbId,
+                CandidateSource::CommonPath => DiscoverySource::CommonPath,
+            },
+            usb_id: candidate
+                .vid
+                .zip(candidate.pid)
+                .map(|(vid, pid)| format!("{:04X}:{:04X}", vid, pid)),
+            serial_number: candidate.serial_number,
+            product: candidate.product,
+            controller,
+            firmware,
+            error,
+        });
+    }
+
+    info!("Discovered {} candidate port(s)", ports.len());
+    Ok(Json(ApiResponse::success(DiscoverResponse { ports })))
+}
+
+/// Resolve symlinks (e.g. `/dev/serial/by-id/...`) so paths can be compared
+fn canonical_path(path: &str) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path))
+}
+
 /// GET /api/v0/controller/{id}/info
 ///
 /// Get detailed info about a specific controller.
@@ -52,16 +169,25 @@ pub async fn get_controller_info(
         .await
         .map_err(ApiError::from)?;
 
-    let info = ControllerInfo {
+    Ok(Json(ApiResponse::success(controller_info(&entry))))
+}
+
+fn controller_info(entry: &ControllerEntry) -> ControllerInfo {
+    let identity = entry
+        .connection_manager()
+        .map(|cm| cm.identity())
+        .unwrap_or_default();
+
+    ControllerInfo {
         id: entry.id().to_string(),
         board_name: entry.board_info().name.clone(),
         fan_count: entry.board_info().fan_count,
         description: entry.description().map(String::from),
         mock_mode: entry.is_mock(),
         connected: entry.is_connected(),
-    };
-
-    Ok(Json(ApiResponse::success(info)))
+        device_path: identity.path,
+        serial_number: identity.serial_number,
+    }
 }
 
 /// POST /api/v0/controller/{id}/reconnect
@@ -102,7 +228,8 @@ mod tests {
     use super::*;
     use crate::api::create_router;
     use crate::config::RuntimeConfig;
-    use crate::controllers::{ControllerEntry, ControllerRegistry};
+    use crate::controllers::ControllerRegistry;
+    use crate::reload::ControllerSetup;
     use axum::{
         body::Body,
         http::{Request, StatusCode},
@@ -139,7 +266,13 @@ mod tests {
             .await
             .unwrap();
 
-        let state = AppState::new(Arc::new(registry), Arc::new(config), main_board, None);
+        let state = AppState::new(
+            Arc::new(registry),
+            Arc::new(config),
+            main_board,
+            None,
+            ControllerSetup::default(),
+        );
         create_router(state)
     }
 
@@ -197,6 +330,50 @@ mod tests {
         assert_eq!(json["data"]["fan_count"], 10);
         assert_eq!(json["data"]["description"], "Main chassis controller");
         assert_eq!(json["data"]["mock_mode"], true);
+        assert!(json["data"].get("device_path").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_controller_info_device_path() {
+        use crate::api::test_utils::{connect_simulated, load_config};
+
+        let board_info = BoardType::OpenFanStandard.to_board_info();
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = load_config(&temp_dir, "").await;
+        let (cm, _) = connect_simulated("bench", &board_info).await;
+        let registry = ControllerRegistry::new();
+        registry
+            .register(
+                ControllerEntry::builder("bench", board_info.clone())
+                    .maybe_connection_manager(Some(cm))
+                    .build(),
+            )
+            .await
+            .unwrap();
+        let state = AppState::new(
+            Arc::new(registry),
+            config,
+            board_info,
+            None,
+            ControllerSetup::default(),
+        );
+
+        let response = create_router(state)
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v0/controller/bench/info")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_string(response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["data"]["device_path"], "sim://bench");
+        assert!(json["data"].get("serial_number").is_none());
     }
 
     #[tokio::test]
@@ -224,6 +401,29 @@ mod tests {
         assert_eq!(json["data"]["fan_count"], 4);
     }
 
+    #[tokio::test]
+    async fn test_discover_controllers() {
+        let app = create_test_app().await;
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v0/controllers/discover")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Candidates depend on the host's serial ports
+        let body = body_string(response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["status"], "success");
+        assert!(json["data"]["ports"].is_array());
+    }
+
     #[tokio::test]
     async fn test_get_controller_info_not_found() {
         let app = create_test_app().await;
@@ -262,4 +462,197 @@ mod tests {
         let body = body_string(response.into_body()).await;
         assert!(body.contains("mock mode"));
     }
+
+    /// Create a state whose controllers come from `[[controllers]]`, so runtime
+    /// changes can be persisted.
+    async fn create_managed_state(config_dir: &tempfile::TempDir) -> AppState {
+        let config_path = config_dir.path().join("config.toml");
+        let data_dir = config_dir.path().join("data");
+        tokio::fs::write(
+            &config_path,
+            format!(
+                "# Fan controllers\ndata_dir = \"{}\"\n\n\
+                 # The chassis board\n[[controllers]]\nid = \"main\"\n\
+                 device = \"/dev/ttyACM0\"\nboard = \"standard\"\n",
+                data_dir.to_string_lossy().replace('\\', "\\\\")
+            ),
+        )
+        .await
+        .unwrap();
+        let config = RuntimeConfig::load(&config_path).await.unwrap();
+
+        let main_board = BoardType::OpenFanStandard.to_board_info();
+        let registry = ControllerRegistry::new();
+        registry
+            .register(ControllerEntry::builder("main", main_board.clone()).build())
+            .await
+            .unwrap();
+        let setup = ControllerSetup {
+            from_config: true,
+            default_id: "main".to_string(),
+            mock: true,
+            verbose: false,
+        };
+        AppState::new(
+            Arc::new(registry),
+            Arc::new(config),
+            main_board,
+            None,
+            setup,
+        )
+    }
+
+    async fn send(
+        state: &AppState,
+        method: &str,
+        uri: &str,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+            None => request.body(Body::empty()).unwrap(),
+        };
+        let response = create_router(state.clone()).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = body_string(response.into_body()).await;
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_controller() {
+        let config_dir = tempfile::TempDir::new().unwrap();
+        let state = create_managed_state(&config_dir).await;
+        let gpu = serde_json::json!({
+            "id": "gpu",
+            "device": "/dev/ttyACM1",
+            "board": "custom:4",
+            "description": "GPU fans",
+        });
+
+        let (status, json) = send(&state, "POST", "/api/v0/controllers", Some(gpu.clone())).await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        assert_eq!(json["data"]["id"], "gpu");
+        assert_eq!(json["data"]["fan_count"], 4);
+        assert_eq!(json["data"]["connected"], true);
+
+        // The new controller is usable right away
+        let (status, json) = send(&state, "GET", "/api/v0/controller/gpu/fan/status", None).await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+
+        // Without persist, config.toml is left alone
+        let content = std::fs::read_to_string(config_dir.path().join("config.toml")).unwrap();
+        assert!(!content.contains("gpu"));
+
+        let (status, _) = send(&state, "POST", "/api/v0/controllers", Some(gpu)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, json) = send(&state, "DELETE", "/api/v0/controller/gpu", None).await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        assert!(state.registry.get("gpu").await.is_none());
+
+        let (status, _) = send(&state, "DELETE", "/api/v0/controller/gpu", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_add_controller_persist() {
+        let config_dir = tempfile::TempDir::new().unwrap();
+        let state = create_managed_state(&config_dir).await;
+        let config_path = config_dir.path().join("config.toml");
+
+        let body = serde_json::json!({
+            "id": "gpu",
+            "serial_number": "E6614103E7452D30",
+            "board": "custom:4",
+            "persist": true,
+        });
+        let (status, json) = send(&state, "POST", "/api/v0/controllers", Some(body)).await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+
+        let content = std::fs::read_to_string(&config_path).unwrap();
+        assert!(content.contains("# The chassis board"), "{}", content);
+        let controllers = &state.config.static_config().controllers;
+        assert_eq!(controllers.len(), 2);
+        assert_eq!(controllers[1].id, "gpu");
+        assert_eq!(
+            controllers[1].serial_number.as_deref(),
+            Some("E6614103E7452D30")
+        );
+
+        let (status, json) = send(
+            &state,
+            "DELETE",
+            "/api/v0/controller/gpu?persist=true",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        let content = std::fs::read_to_string(&config_path).unwrap();
+        assert!(!content.contains("gpu"), "{}", content);
+        assert!(content.contains("# The chassis board"));
+        assert_eq!(state.config.static_config().controllers.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_remove_controller_refusals() {
+        let config_dir = tempfile::TempDir::new().unwrap();
+        let state = create_managed_state(&config_dir).await;
+
+        let (status, json) = send(&state, "DELETE", "/api/v0/controller/main", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            json["error"]
+                .as_str()
+                .unwrap()
+                .contains("default controller")
+        );
+
+        let body =
+            serde_json::json!({ "id": "gpu", "device": "/dev/ttyACM1", "board": "custom:4" });
+        let (status, _) = send(&state, "POST", "/api/v0/controllers", Some(body)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // A zone still drives one of its fans
+        let zone = serde_json::json!({
+            "name": "gpu-zone",
+            "fans": [{ "controller": "gpu", "fan_id": 0 }],
+        });
+        let (status, json) = send(&state, "POST", "/api/v0/zones/add", Some(zone)).await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+
+        let (status, _) = send(&state, "DELETE", "/api/v0/controller/gpu", None).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(state.registry.get("gpu").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_add_controller_persist_needs_controllers_section() {
+        let app = create_test_app().await;
+
+        let body = serde_json::json!({
+            "id": "extra",
+            "device": "/dev/ttyACM2",
+            "board": "standard",
+            "persist": true,
+        });
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri("/api/v0/controllers")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = body_string(response.into_body()).await;
+        assert!(body.contains("Cannot persist"));
+    }
 }
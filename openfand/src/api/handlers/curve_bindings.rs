@@ -0,0 +1,622 @@
+//! Curve binding handlers for managing closed-loop thermal control
+
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::handlers::thermal_curves::is_valid_curve_name;
+use crate::{api_fail, api_ok};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use openfan_core::{CurveBinding, OpenFanError, api, config::DEFAULT_BINDING_INTERVAL_SECS};
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+/// Lists all curve bindings for a specific controller with their loop status.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/controller/{id}/bindings/list`
+pub(crate) async fn list_controller_bindings(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+) -> Result<Json<api::ApiResponse<api::CurveBindingResponse>>, ApiError> {
+    debug!(
+        "Request: GET /api/v0/controller/{}/bindings/list",
+        controller_id
+    );
+
+    // Validate controller exists in registry
+    let _ = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    // Get controller data
+    let controller_data = state.config.controller_data(&controller_id).await?;
+
+    let bindings: Vec<CurveBinding> = controller_data
+        .curve_bindings()
+        .await
+        .bindings
+        .values()
+        .cloned()
+        .collect();
+
+    let mut statuses = HashMap::new();
+    for binding in bindings {
+        let status = state.control.status(&controller_id, &binding).await;
+        statuses.insert(binding.name, status);
+    }
+
+    let response = api::CurveBindingResponse { bindings: statuses };
+
+    info!(
+        "Listed {} curve bindings for controller '{}'",
+        response.bindings.len(),
+        controller_id
+    );
+    api_ok!(response)
+}
+
+/// Adds a new curve binding for a specific controller and starts its control loop.
+///
+/// # Endpoint
+///
+/// `POST /api/v0/controller/{id}/bindings/add`
+pub(crate) async fn add_controller_binding(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+    Json(request): Json<api::AddBindingRequest>,
+) -> Result<Json<api::ApiResponse<()>>, ApiError> {
+    debug!(
+        "Request: POST /api/v0/controller/{}/bindings/add",
+        controller_id
+    );
+
+    // Validate controller exists in registry
+    let entry = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    let binding_name = request.name.trim();
+
+    if !is_valid_curve_name(binding_name) {
+        return api_fail!(
+            "Binding name must be non-empty and contain only alphanumeric characters, hyphens, and underscores!"
+        );
+    }
+
+    let binding = CurveBinding {
+        name: binding_name.to_string(),
+        curve: request.curve.map(|c| c.trim().to_string()),
+        pid: request.pid.map(|p| p.trim().to_string()),
+        sensor: request.sensor.trim().to_string(),
+        zone: request.zone.map(|z| z.trim().to_string()),
+        fans: request.fans,
+        interval_secs: request
+            .interval_secs
+            .unwrap_or(DEFAULT_BINDING_INTERVAL_SECS),
+    };
+
+    if let Err(e) = binding.validate() {
+        return api_fail!(e);
+    }
+
+    // Validate fan IDs against this controller's board
+    for &fan_id in &binding.fans {
+        entry.board_info().validate_fan_id(fan_id)?;
+    }
+
+    // Validate zone exists
+    if let Some(zone) = &binding.zone
+        && !state.config.zones().await.contains(zone)
+    {
+        return Err(OpenFanError::ZoneNotFound(zone.clone()).into());
+    }
+
+    // Get controller data
+    let controller_data = state.config.controller_data(&controller_id).await?;
+
+    // Validate curve or PID controller exists on this controller
+    if let Some(curve) = &binding.curve
+        && !controller_data.thermal_curves().await.contains(curve)
+    {
+        return Err(OpenFanError::CurveNotFound(curve.clone()).into());
+    }
+    if let Some(pid) = &binding.pid
+        && !controller_data.pid_controllers().await.contains(pid)
+    {
+        return Err(OpenFanError::PidNotFound(pid.clone()).into());
+    }
+
+    // Add binding
+    {
+        let mut bindings = controller_data.curve_bindings_mut().await;
+
+        if bindings.contains(binding_name) {
+            return api_fail!(format!("Curve binding '{}' already exists!", binding_name));
+        }
+
+        bindings.insert(binding_name.to_string(), binding.clone());
+    }
+
+    // Save to disk
+    if let Err(e) = controller_data.save_curve_bindings().await {
+        return Err(ApiError::internal_error(format!(
+            "Failed to save curve bindings: {}",
+            e
+        )));
+    }
+
+    state.control.start(&controller_id, binding).await;
+
+    info!(
+        "Added curve binding '{}' for controller '{}'",
+        binding_name, controller_id
+    );
+    api_ok!(())
+}
+
+/// Gets a single curve binding by name for a specific controller.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/controller/{id}/binding/{name}/get`
+pub(crate) async fn get_controller_binding(
+    State(state): State<AppState>,
+    Path((controller_id, name)): Path<(String, String)>,
+) -> Result<Json<api::ApiResponse<api::SingleBindingResponse>>, ApiError> {
+    debug!(
+        "Request: GET /api/v0/controller/{}/binding/{}/get",
+        controller_id, name
+    );
+
+    // Validate controller exists in registry
+    let _ = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    // Get controller data
+    let controller_data = state.config.controller_data(&controller_id).await?;
+
+    let binding = controller_data.curve_bindings().await.get(&name).cloned();
+
+    match binding {
+        Some(binding) => {
+            let response = api::SingleBindingResponse {
+                binding: state.control.status(&controller_id, &binding).await,
+            };
+            api_ok!(response)
+        }
+        None => Err(OpenFanError::BindingNotFound(name).into()),
+    }
+}
+
+/// Deletes a curve binding for a specific controller and stops its control loop.
+///
+/// # Endpoint
+///
+/// `DELETE /api/v0/controller/{id}/binding/{name}`
+pub(crate) async fn delete_controller_binding(
+    State(state): State<AppState>,
+    Path((controller_id, name)): Path<(String, String)>,
+) -> Result<Json<api::ApiResponse<()>>, ApiError> {
+    debug!(
+        "Request: DELETE /api/v0/controller/{}/binding/{}",
+        controller_id, name
+    );
+
+    // Validate controller exists in registry
+    let _ = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    // Get controller data
+    let controller_data = state.config.controller_data(&controller_id).await?;
+
+    // Remove binding
+    {
+        let mut bindings = controller_data.curve_bindings_mut().await;
+
+        if bindings.remove(&name).is_none() {
+            return Err(OpenFanError::BindingNotFound(name).into());
+        }
+    }
+
+    // Save to disk
+    if let Err(e) = controller_data.save_curve_bindings().await {
+        return Err(ApiError::internal_error(format!(
+            "Failed to save curve bindings: {}",
+            e
+        )));
+    }
+
+    state.control.stop(&controller_id, &name).await;
+
+    info!(
+        "Deleted curve binding '{}' from controller '{}'",
+        name, controller_id
+    );
+    api_ok!(())
+}
+
+/// Integration tests that exercise actual HTTP handlers
+#[cfg(test)]
+mod integration_tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Method, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use openfan_core::BoardType;
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    use crate::api::{AppState, create_router};
+    use crate::config::RuntimeConfig;
+
+    struct TestApp {
+        router: Router,
+        state: AppState,
+        config_dir: TempDir,
+    }
+
+    impl TestApp {
+        async fn new() -> Self {
+            let board_info = BoardType::OpenFanStandard.to_board_info();
+            let config_dir = tempfile::tempdir().unwrap();
+
+            let data_dir = config_dir.path().join("data");
+            std::fs::create_dir_all(&data_dir).unwrap();
+
+            let data_dir_str = data_dir.to_string_lossy().replace('\\', "\\\\");
+            let config_content = format!(
+                r#"data_dir = "{}"
+
+[server]
+bind_address = "127.0.0.1"
+port = 3000
+communication_timeout = 1
+"#,
+                data_dir_str
+            );
+
+            let config_path = config_dir.path().join("config.toml");
+            std::fs::write(&config_path, config_content).unwrap();
+
+            let config = RuntimeConfig::load(&config_path).await.unwrap();
+            let state =
+                AppState::single_controller(board_info, std::sync::Arc::new(config), None).await;
+
+            TestApp {
+                router: create_router(state.clone()),
+                state,
+                config_dir,
+            }
+        }
+
+        fn router(&self) -> Router {
+            self.router.clone()
+        }
+
+        /// Write a sensor file with the given temperature in millidegrees.
+        fn sensor(&self, millidegrees: i64) -> String {
+            let path = self.config_dir.path().join("sensor_temp");
+            std::fs::write(&path, millidegrees.to_string()).unwrap();
+            path.to_string_lossy().to_string()
+        }
+
+        async fn add_binding(&self, body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+            let response = self
+                .router()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri("/api/v0/controller/default/bindings/add")
+                        .header("content-type", "application/json")
+                        .body(Body::from(body.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            let status = response.status();
+            let body = body_string(response.into_body()).await;
+            (status, serde_json::from_str(&body).unwrap())
+        }
+
+        async fn get(&self, uri: &str) -> (StatusCode, serde_json::Value) {
+            let response = self
+                .router()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            let status = response.status();
+            let body = body_string(response.into_body()).await;
+            (status, serde_json::from_str(&body).unwrap())
+        }
+
+        async fn delete(&self, uri: &str) -> StatusCode {
+            self.router()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::DELETE)
+                        .uri(uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+                .status()
+        }
+    }
+
+    async fn body_string(body: Body) -> String {
+        let bytes = body.collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_list_bindings_empty() {
+        let app = TestApp::new().await;
+
+        let (status, json) = app.get("/api/v0/controller/default/bindings/list").await;
+
+        assert_eq!(status, StatusCode::OK);
+        let bindings = json["data"]["bindings"].as_object().unwrap();
+        assert!(bindings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_add_binding_with_fans() {
+        let app = TestApp::new().await;
+        let sensor = app.sensor(50000);
+
+        let (status, _) = app
+            .add_binding(serde_json::json!({
+                "name": "cpu",
+                "curve": "Balanced",
+                "sensor": sensor,
+                "fans": [0, 1],
+                "interval_secs": 5
+            }))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, json) = app.get("/api/v0/controller/default/binding/cpu/get").await;
+        assert_eq!(status, StatusCode::OK);
+        let binding = &json["data"]["binding"];
+        assert_eq!(binding["curve"], "Balanced");
+        assert_eq!(binding["interval_secs"], 5);
+        assert_eq!(binding["running"], true);
+
+        // Persisted to the controller data dir
+        let path = app
+            .state
+            .config
+            .data_dir()
+            .join("controllers/default/curve_bindings.toml");
+        let content = std::fs::read_to_string(path).unwrap();
+        assert!(content.contains("Balanced"));
+
+        app.state.control.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_add_binding_with_zone() {
+        let app = TestApp::new().await;
+        let sensor = app.sensor(40000);
+
+        {
+            let mut zones = app.state.config.zones_mut().await;
+            zones.insert(
+                "intake".to_string(),
+                openfan_core::Zone::new("intake", vec![openfan_core::ZoneFan::new("default", 0)]),
+            );
+        }
+
+        let (status, _) = app
+            .add_binding(serde_json::json!({
+                "name": "intake",
+                "curve": "Silent",
+                "sensor": sensor,
+                "zone": "intake"
+            }))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        app.state.control.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_add_binding_validation_errors() {
+        let app = TestApp::new().await;
+        let sensor = app.sensor(40000);
+
+        let cases = [
+            // Invalid name
+            serde_json::json!({"name": "has space", "curve": "Balanced", "sensor": sensor, "fans": [0]}),
+            // No target
+            serde_json::json!({"name": "a", "curve": "Balanced", "sensor": sensor}),
+            // Both targets
+            serde_json::json!({"name": "a", "curve": "Balanced", "sensor": sensor, "zone": "z", "fans": [0]}),
+            // Unknown curve
+            serde_json::json!({"name": "a", "curve": "Missing", "sensor": sensor, "fans": [0]}),
+            // Unknown PID controller
+            serde_json::json!({"name": "a", "pid": "Missing", "sensor": sensor, "fans": [0]}),
+            // Both curve and PID
+            serde_json::json!({"name": "a", "curve": "Balanced", "pid": "loop", "sensor": sensor, "fans": [0]}),
+            // Neither curve nor PID
+            serde_json::json!({"name": "a", "sensor": sensor, "fans": [0]}),
+            // Unknown zone
+            serde_json::json!({"name": "a", "curve": "Balanced", "sensor": sensor, "zone": "missing"}),
+            // Fan out of range
+            serde_json::json!({"name": "a", "curve": "Balanced", "sensor": sensor, "fans": [10]}),
+            // Interval out of range
+            serde_json::json!({"name": "a", "curve": "Balanced", "sensor": sensor, "fans": [0], "interval_secs": 0}),
+        ];
+
+        for case in cases {
+            let (status, json) = app.add_binding(case.clone()).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "case: {}", case);
+            assert_eq!(json["status"], "error");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_add_binding_with_pid() {
+        let app = TestApp::new().await;
+        let sensor = app.sensor(45000);
+
+        {
+            let controller_data = app.state.config.controller_data("default").await.unwrap();
+            controller_data.pid_controllers_mut().await.insert(
+                "loop".to_string(),
+                openfan_core::PidController::new("loop", 40.0, 10.0, 0.0, 0.0),
+            );
+        }
+
+        let (status, _) = app
+            .add_binding(serde_json::json!({
+                "name": "water",
+                "pid": "loop",
+                "sensor": sensor,
+                "fans": [0]
+            }))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Wait for the first control step to report the PID state
+        let mut binding = serde_json::Value::Null;
+        for _ in 0..100 {
+            let (_, json) = app
+                .get("/api/v0/controller/default/binding/water/get")
+                .await;
+            binding = json["data"]["binding"].clone();
+            if binding.get("pid_state").is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        assert_eq!(binding["pid"], "loop");
+        assert!(binding.get("curve").is_none());
+        assert_eq!(binding["pid_state"]["last_error"], 5.0);
+        assert_eq!(binding["pwm"], 50);
+
+        // A PID controller used by a binding cannot be deleted
+        let status = app.delete("/api/v0/controller/default/pid/loop").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        app.state.control.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_add_binding_duplicate() {
+        let app = TestApp::new().await;
+        let sensor = app.sensor(40000);
+        let body = serde_json::json!({
+            "name": "cpu",
+            "curve": "Balanced",
+            "sensor": sensor,
+            "fans": [0]
+        });
+
+        let (status, _) = app.add_binding(body.clone()).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, json) = app.add_binding(body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(json["error"].as_str().unwrap().contains("already exists"));
+
+        app.state.control.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_get_binding_not_found() {
+        let app = TestApp::new().await;
+
+        let (status, json) = app
+            .get("/api/v0/controller/default/binding/missing/get")
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            json["error"]
+                .as_str()
+                .unwrap()
+                .contains("Curve binding not found")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_binding_not_found() {
+        let app = TestApp::new().await;
+
+        let status = app
+            .delete("/api/v0/controller/default/binding/missing")
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_add_then_delete_binding() {
+        let app = TestApp::new().await;
+        let sensor = app.sensor(40000);
+
+        let (status, _) = app
+            .add_binding(serde_json::json!({
+                "name": "cpu",
+                "curve": "Balanced",
+                "sensor": sensor,
+                "fans": [0]
+            }))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let status = app.delete("/api/v0/controller/default/binding/cpu").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, json) = app.get("/api/v0/controller/default/bindings/list").await;
+        assert!(json["data"]["bindings"].as_object().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_bound_curve_is_rejected() {
+        let app = TestApp::new().await;
+        let sensor = app.sensor(40000);
+
+        let (status, _) = app
+            .add_binding(serde_json::json!({
+                "name": "cpu",
+                "curve": "Balanced",
+                "sensor": sensor,
+                "fans": [0]
+            }))
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let status = app
+            .delete("/api/v0/controller/default/curve/Balanced")
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        app.state.control.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_bindings_unknown_controller() {
+        let app = TestApp::new().await;
+
+        let (status, _) = app.get("/api/v0/controller/nope/bindings/list").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,140 @@
+//! Live telemetry stream handler
+
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+};
+use openfan_core::api::TelemetryEvent;
+use serde::Deserialize;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tracing::debug;
+
+/// Query parameters for the event stream.
+#[derive(Deserialize)]
+pub(crate) struct EventsQuery {
+    /// Only send the events of this controller, and those of no controller
+    pub controller: Option<String>,
+}
+
+/// Streams live telemetry as Server-Sent Events.
+///
+/// Starts with the connection state and last fan readings of each
+/// controller, then sends fan readings as they are sampled, sensor readings,
+/// connection state changes and configuration changes. Each event is named
+/// after its `type` and carries the JSON event as data. A subscriber too slow
+/// to keep up skips the events it missed.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/events?controller=main`
+pub(crate) async fn stream_events(
+    State(state): State<AppState>,
+    Query(query): Query<EventsQuery>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    debug!(
+        "Request: GET /api/v0/events (controller: {:?})",
+        query.controller
+    );
+
+    if let Some(controller_id) = &query.controller {
+        state
+            .registry
+            .get_or_err(controller_id)
+            .await
+            .map_err(ApiError::from)?;
+    }
+
+    // Subscribe before taking the snapshot so no change falls in between
+    let events = BroadcastStream::new(state.telemetry.subscribe()).filter_map(Result::ok);
+    let snapshot = state.telemetry.snapshot().await;
+
+    let controller = query.controller;
+    let stream = tokio_stream::iter(snapshot)
+        .chain(events)
+        .filter(move |event| match (&controller, event.controller()) {
+            (Some(wanted), Some(controller)) => wanted == controller,
+            _ => true,
+        })
+        .map(|event: TelemetryEvent| Event::default().event(event.name()).json_data(&event));
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    use crate::api::create_router;
+    use crate::api::test_utils::create_test_state;
+
+    async fn get(router: Router, uri: &str) -> axum::response::Response {
+        router
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_stream_events() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&config_dir, "").await;
+        state.telemetry.start_all().await;
+        let router = create_router(state.clone());
+
+        let response = get(router, "/api/v0/events?controller=default").await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+
+        let mut body = response.into_body();
+        let mut received = String::new();
+        let mut saved = false;
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !received.contains("event: config_changed") {
+                let frame = body.frame().await.unwrap().unwrap();
+                if let Ok(data) = frame.into_data() {
+                    received.push_str(std::str::from_utf8(&data).unwrap());
+                }
+                // Change the configuration once the stream is live
+                if !saved && received.contains("event: fan_status") {
+                    let data = state.config.controller_data("default").await.unwrap();
+                    data.save_aliases().await.unwrap();
+                    saved = true;
+                }
+            }
+        })
+        .await
+        .expect("stream should carry fan readings and configuration changes");
+
+        assert!(received.starts_with("event: connection_state\n"));
+        assert!(received.contains(
+            r#"data: {"type":"connection_state","controller":"default","state":"connected"}"#
+        ));
+        assert!(received.contains(r#""resource":"aliases""#));
+
+        state.telemetry.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_unknown_controller() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&config_dir, "").await;
+        let router = create_router(state);
+
+        let response = get(router, "/api/v0/events?controller=nope").await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
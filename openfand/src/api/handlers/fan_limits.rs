@@ -0,0 +1,351 @@
+//! Fan safety limit handlers
+
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api_ok;
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use openfan_core::{FanLimits, api};
+use tracing::{debug, info};
+
+/// Retrieves the safety limits of a controller's fans.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/controller/{id}/fan/limits`
+pub(crate) async fn get_fan_limits(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+) -> Result<Json<api::ApiResponse<api::FanLimitsResponse>>, ApiError> {
+    debug!(
+        "Request: GET /api/v0/controller/{}/fan/limits",
+        controller_id
+    );
+
+    state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    let controller_data = state.config.controller_data(&controller_id).await?;
+    let limits = controller_data.fan_limits().await.fans.clone();
+
+    api_ok!(api::FanLimitsResponse {
+        controller: controller_id,
+        enforcement: state.config.static_config().fan_limits.enforcement,
+        limits,
+    })
+}
+
+/// Sets the safety limits of a fan, replacing earlier ones.
+///
+/// Limits without any restriction remove the fan's entry. The limits apply
+/// from the next value set on the fan.
+///
+/// # Endpoint
+///
+/// `POST /api/v0/controller/{id}/fan/{fan}/limits`
+///
+/// # Request Body
+///
+/// ```json
+/// {"min_pwm": 30, "max_pwm": 100, "min_rpm": 800, "allow_zero": false}
+/// ```
+pub(crate) async fn set_fan_limits(
+    State(state): State<AppState>,
+    Path((controller_id, fan_id)): Path<(String, String)>,
+    Json(limits): Json<FanLimits>,
+) -> Result<Json<api::ApiResponse<()>>, ApiError> {
+    debug!(
+        "Request: POST /api/v0/controller/{}/fan/{}/limits",
+        controller_id, fan_id
+    );
+
+    // Parse and validate fan ID
+    let fan_index = fan_id
+        .parse::<u8>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid fan ID: {}", fan_id)))?;
+
+    let entry = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+    entry.board_info().validate_fan_id(fan_index)?;
+
+    limits
+        .validate(Some(entry.board_info()))
+        .map_err(ApiError::bad_request)?;
+
+    let controller_data = state.config.controller_data(&controller_id).await?;
+    controller_data
+        .fan_limits_mut()
+        .await
+        .set(fan_index, limits);
+    if let Err(e) = controller_data.save_fan_limits().await {
+        return Err(ApiError::internal_error(format!(
+            "Failed to save fan limits: {}",
+            e
+        )));
+    }
+
+    info!(
+        "Set limits of fan {} on controller '{}': {}",
+        fan_index, controller_id, limits
+    );
+    api_ok!(())
+}
+
+/// Removes the safety limits of a fan.
+///
+/// # Endpoint
+///
+/// `DELETE /api/v0/controller/{id}/fan/{fan}/limits`
+pub(crate) async fn delete_fan_limits(
+    State(state): State<AppState>,
+    Path((controller_id, fan_id)): Path<(String, String)>,
+) -> Result<Json<api::ApiResponse<()>>, ApiError> {
+    debug!(
+        "Request: DELETE /api/v0/controller/{}/fan/{}/limits",
+        controller_id, fan_id
+    );
+
+    // Parse and validate fan ID
+    let fan_index = fan_id
+        .parse::<u8>()
+        .map_err(|_| ApiError::bad_request(format!("Invalid fan ID: {}", fan_id)))?;
+
+    let entry = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+    entry.board_info().validate_fan_id(fan_index)?;
+
+    let controller_data = state.config.controller_data(&controller_id).await?;
+    if !controller_data.fan_limits_mut().await.remove(fan_index) {
+        return Err(ApiError::bad_request(format!(
+            "Fan {} has no limits",
+            fan_index
+        )));
+    }
+    if let Err(e) = controller_data.save_fan_limits().await {
+        return Err(ApiError::internal_error(format!(
+            "Failed to save fan limits: {}",
+            e
+        )));
+    }
+
+    info!(
+        "Removed limits of fan {} on controller '{}'",
+        fan_index, controller_id
+    );
+    api_ok!(())
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+
+    use openfan_core::{BoardType, LimitEnforcement};
+
+    use tower::ServiceExt;
+
+    use crate::api::test_utils::{connect_simulated, load_config};
+    use crate::api::{AppState, create_router};
+
+    async fn create_test_state(
+        temp_dir: &tempfile::TempDir,
+        enforcement: LimitEnforcement,
+    ) -> AppState {
+        let extra_config = format!("[fan_limits]\nenforcement = \"{}\"\n", enforcement);
+        let config = load_config(temp_dir, &extra_config).await;
+        let board = BoardType::Custom { fan_count: 3 }.to_board_info();
+        let (cm, _) = connect_simulated("default", &board).await;
+        AppState::single_controller(board, config, Some(cm)).await
+    }
+
+    async fn send(
+        router: Router,
+        method: &str,
+        uri: &str,
+        body: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::builder().method(method).uri(uri);
+        let request = match body {
+            Some(body) => request
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string())),
+            None => request.body(Body::empty()),
+        };
+        let response = router.oneshot(request.unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_fan_limits_clamp_set_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&temp_dir, LimitEnforcement::Clamp).await;
+
+        let (status, json) = send(
+            create_router(state.clone()),
+            "POST",
+            "/api/v0/controller/default/fan/1/limits",
+            Some(r#"{"min_pwm": 30, "max_pwm": 80, "allow_zero": false}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+
+        let (_, json) = send(
+            create_router(state.clone()),
+            "GET",
+            "/api/v0/controller/default/fan/limits",
+            None,
+        )
+        .await;
+        assert_eq!(json["data"]["enforcement"], "clamp");
+        assert_eq!(json["data"]["limits"]["1"]["min_pwm"], 30);
+
+        for uri in [
+            "/api/v0/controller/default/fan/all/set?value=0",
+            "/api/v0/controller/default/fan/1/pwm?value=0",
+        ] {
+            let (status, _) = send(create_router(state.clone()), "GET", uri, None).await;
+            assert_eq!(status, StatusCode::OK);
+            let (_, json) = send(
+                create_router(state.clone()),
+                "GET",
+                "/api/v0/controller/default/fan/status",
+                None,
+            )
+            .await;
+            assert_eq!(json["data"]["pwms"]["0"], 0);
+            assert_eq!(json["data"]["pwms"]["1"], 30);
+            assert_eq!(json["data"]["limits"]["1"]["allow_zero"], false);
+            assert!(json["data"]["limits"]["0"].is_null());
+        }
+
+        let (_, _) = send(
+            create_router(state.clone()),
+            "GET",
+            "/api/v0/controller/default/fan/1/pwm?value=100",
+            None,
+        )
+        .await;
+        let (_, json) = send(
+            create_router(state.clone()),
+            "GET",
+            "/api/v0/controller/default/fan/status",
+            None,
+        )
+        .await;
+        assert_eq!(json["data"]["pwms"]["1"], 80);
+
+        let (status, _) = send(
+            create_router(state.clone()),
+            "DELETE",
+            "/api/v0/controller/default/fan/1/limits",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(
+            create_router(state.clone()),
+            "DELETE",
+            "/api/v0/controller/default/fan/1/limits",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_fan_limits_reject() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&temp_dir, LimitEnforcement::Reject).await;
+
+        // Invalid limits and fans are refused
+        for (uri, body) in [
+            (
+                "/api/v0/controller/default/fan/0/limits",
+                r#"{"allow_zero": false}"#,
+            ),
+            (
+                "/api/v0/controller/default/fan/0/limits",
+                r#"{"min_pwm": 60, "max_pwm": 40}"#,
+            ),
+            (
+                "/api/v0/controller/default/fan/0/limits",
+                r#"{"min_rpm": 100000}"#,
+            ),
+            (
+                "/api/v0/controller/default/fan/3/limits",
+                r#"{"min_pwm": 20}"#,
+            ),
+        ] {
+            let (status, _) = send(create_router(state.clone()), "POST", uri, Some(body)).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+        }
+
+        let (status, _) = send(
+            create_router(state.clone()),
+            "GET",
+            "/api/v0/controller/default/fan/all/set?value=50",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(
+            create_router(state.clone()),
+            "POST",
+            "/api/v0/controller/default/fan/2/limits",
+            Some(r#"{"min_pwm": 40, "min_rpm": 1000, "allow_zero": false}"#),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        for uri in [
+            "/api/v0/controller/default/fan/all/set?value=0",
+            "/api/v0/controller/default/fan/2/pwm?value=20",
+            "/api/v0/controller/default/fan/2/rpm?value=800",
+        ] {
+            let (status, json) = send(create_router(state.clone()), "GET", uri, None).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", uri);
+            assert!(
+                json["error"].as_str().unwrap().contains("Fan 2"),
+                "{}",
+                json
+            );
+        }
+
+        // Nothing was set by the rejected requests
+        let (_, json) = send(
+            create_router(state.clone()),
+            "GET",
+            "/api/v0/controller/default/fan/status",
+            None,
+        )
+        .await;
+        assert_eq!(json["data"]["pwms"]["0"], 50);
+        assert_eq!(json["data"]["pwms"]["2"], 50);
+
+        let (status, _) = send(
+            create_router(state.clone()),
+            "GET",
+            "/api/v0/controller/default/fan/2/pwm?value=50",
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
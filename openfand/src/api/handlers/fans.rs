@@ -2,14 +2,14 @@
 
 use crate::api::AppState;
 use crate::api::error::ApiError;
+use crate::limits::FanLimiter;
 use crate::{api_fail, api_ok};
 use axum::{
     Json,
     extract::{Path, Query, State},
 };
-use openfan_core::api;
+use openfan_core::{OpenFanError, api};
 use serde::Deserialize;
-use std::collections::HashMap;
 
 use tracing::debug;
 
@@ -22,6 +22,10 @@ pub(crate) struct FanControlQuery {
 
 /// Retrieves the current status of all fans for a specific controller.
 ///
+/// The readings come from the last telemetry sample, or from the hardware
+/// when it is stale. Fans with safety limits report them along with their
+/// readings.
+///
 /// # Endpoint
 ///
 /// `GET /api/v0/controller/{id}/fan/status`
@@ -41,49 +45,25 @@ pub(crate) async fn get_controller_fan_status(
         .await
         .map_err(ApiError::from)?;
 
-    let board_info = entry.board_info();
-
-    // Check if hardware is available
-    let Some(cm) = entry.connection_manager() else {
-        debug!(
-            "Controller '{}' is in mock mode - returning simulated fan status",
-            controller_id
-        );
-        // Return mock fan data for testing/development
-        let mut mock_rpms = HashMap::new();
-        let mut mock_pwms = HashMap::new();
-        for i in 0..board_info.fan_count as u8 {
-            mock_rpms.insert(i, 1500 + (i as u32 * 100));
-            mock_pwms.insert(i, 50 + (i as u32 * 5));
-        }
-        let mock_status = api::FanStatusResponse {
-            rpms: mock_rpms,
-            pwms: mock_pwms,
-        };
-        return api_ok!(mock_status);
-    };
-
-    // Get RPM and PWM data from hardware via connection manager
-    let status = cm
-        .with_controller(async |controller| {
-            let rpm_map = controller.get_all_fan_rpm().await?;
-            let pwm_map = controller.get_all_fan_pwm();
-            debug!(
-                "Fan status retrieved - RPM: {:?}, PWM: {:?}",
-                rpm_map, pwm_map
-            );
-            Ok(api::FanStatusResponse {
-                rpms: rpm_map,
-                pwms: pwm_map,
-            })
-        })
-        .await?;
-
+    let mut status = state.telemetry.fan_status(&entry).await?;
+    let fan_count = entry.board_info().fan_count;
+    let data = state.config.controller_data(&controller_id).await?;
+    status.limits = data
+        .fan_limits()
+        .await
+        .fans
+        .iter()
+        .filter(|(fan_id, _)| usize::from(**fan_id) < fan_count)
+        .map(|(&fan_id, &limits)| (fan_id, limits))
+        .collect();
     api_ok!(status)
 }
 
 /// Sets the PWM value for all fans on a specific controller.
 ///
+/// Fans with safety limits get the value moved into their limits, or the
+/// request fails without setting any fan when limits are enforced by rejecting.
+///
 /// # Endpoint
 ///
 /// `GET /api/v0/controller/{id}/fan/all/set?value=50`
@@ -116,19 +96,26 @@ pub(crate) async fn set_controller_all_fans(
         controller_id, pwm_value
     );
 
-    // Check if hardware is available
-    let Some(cm) = entry.connection_manager() else {
-        debug!(
-            "Controller '{}' is in mock mode - simulating fan PWM set",
-            controller_id
-        );
-        return api_ok!(());
-    };
+    let limiter = FanLimiter::load(&state.config, &entry).await?;
+    let fan_values = (0..entry.board_info().fan_count as u8)
+        .map(|fan_id| Ok((fan_id, limiter.pwm(fan_id, pwm_value)?)))
+        .collect::<openfan_core::Result<Vec<_>>>()?;
+
+    let cm = entry
+        .connection_manager()
+        .ok_or(OpenFanError::DeviceNotFound)?;
 
     // Send command to hardware via connection manager
     cm.with_controller(async |controller| {
-        let response = controller.set_all_fan_pwm(pwm_value).await?;
-        debug!("Set all fans response: {}", response);
+        if fan_values.iter().all(|&(_, value)| value == pwm_value) {
+            let response = controller.set_all_fan_pwm(pwm_value).await?;
+            debug!("Set all fans response: {}", response);
+            return Ok(());
+        }
+        for &(fan_id, value) in &fan_values {
+            let response = controller.set_fan_pwm(fan_id, value).await?;
+            debug!("Set fan {} PWM response: {}", fan_id, response);
+        }
         Ok(())
     })
     .await?;
@@ -170,22 +157,20 @@ pub(crate) async fn set_controller_fan_pwm(
         return api_fail!("Missing 'value' parameter");
     };
 
-    // Validate and clamp PWM value
+    // Validate and clamp PWM value, then apply the fan's limits
     let pwm_value = value.clamp(0.0, 100.0) as u32;
+    let pwm_value = FanLimiter::load(&state.config, &entry)
+        .await?
+        .pwm(fan_index, pwm_value)?;
 
     debug!(
         "Setting fan {} on controller '{}' to {}% PWM",
         fan_index, controller_id, pwm_value
     );
 
-    // Check if hardware is available
-    let Some(cm) = entry.connection_manager() else {
-        debug!(
-            "Controller '{}' is in mock mode - simulating fan {} PWM set",
-            controller_id, fan_index
-        );
-        return api_ok!(());
-    };
+    let cm = entry
+        .connection_manager()
+        .ok_or(OpenFanError::DeviceNotFound)?;
 
     // Send command to hardware via connection manager
     cm.with_controller(async |controller| {
@@ -227,15 +212,9 @@ pub(crate) async fn get_controller_fan_rpm(
     // Validate fan ID against board configuration
     entry.board_info().validate_fan_id(fan_index)?;
 
-    // Check if hardware is available
-    let Some(cm) = entry.connection_manager() else {
-        debug!(
-            "Controller '{}' is in mock mode - returning mock RPM for fan {}",
-            controller_id, fan_index
-        );
-        let mock_rpm = 1500 + (fan_index as u32 * 100);
-        return api_ok!(mock_rpm);
-    };
+    let cm = entry
+        .connection_manager()
+        .ok_or(OpenFanError::DeviceNotFound)?;
 
     // Get single fan RPM from hardware via connection manager
     let rpm = cm
@@ -283,8 +262,10 @@ pub(crate) async fn set_controller_fan_rpm(
         return api_fail!("Missing 'value' parameter");
     };
 
-    // Convert to u32 and validate against board's target RPM range
-    let rpm_value = value as u32;
+    // Convert to u32, apply the fan's limits and validate against board's target RPM range
+    let rpm_value = FanLimiter::load(&state.config, &entry)
+        .await?
+        .rpm(fan_index, value as u32)?;
     entry.board_info().validate_target_rpm(rpm_value)?;
 
     debug!(
@@ -292,14 +273,9 @@ pub(crate) async fn set_controller_fan_rpm(
         fan_index, controller_id, rpm_value
     );
 
-    // Check if hardware is available
-    let Some(cm) = entry.connection_manager() else {
-        debug!(
-            "Controller '{}' is in mock mode - simulating fan {} RPM set",
-            controller_id, fan_index
-        );
-        return api_ok!(());
-    };
+    let cm = entry
+        .connection_manager()
+        .ok_or(OpenFanError::DeviceNotFound)?;
 
     // Send command to hardware via connection manager
     cm.with_controller(async |controller| {
@@ -413,28 +389,40 @@ mod integration_tests {
         http::{Request, StatusCode},
     };
     use http_body_util::BodyExt;
-    use openfan_core::{BoardType, config::StaticConfig};
+    use openfan_core::BoardType;
+
+    use std::time::Duration;
     use tower::ServiceExt;
 
+    use crate::api::test_utils::{connect_simulated, load_config};
     use crate::api::{AppState, create_router};
-    use crate::config::RuntimeConfig;
+    use crate::controllers::SimulatedDevice;
 
-    /// Create a test app with mock mode (no hardware)
+    /// Create a test app whose default controller is a simulated board
     async fn create_test_app() -> Router {
+        create_simulated_app().await.0
+    }
+
+    /// Create a test app whose default controller has no hardware connection
+    async fn create_unconnected_app() -> Router {
         let board_info = BoardType::OpenFanStandard.to_board_info();
         let temp_dir = tempfile::tempdir().unwrap();
-        // Create a config with data_dir inside the temp directory
-        let config_path = temp_dir.path().join("config.toml");
-        let static_config = StaticConfig::with_data_dir(temp_dir.path().join("data"));
-        tokio::fs::write(&config_path, static_config.to_toml().unwrap())
-            .await
-            .unwrap();
-        let config = RuntimeConfig::load(&config_path).await.unwrap();
-        let state =
-            AppState::single_controller(board_info, std::sync::Arc::new(config), None).await;
+        let config = load_config(&temp_dir, "").await;
+        let state = AppState::single_controller(board_info, config, None).await;
         create_router(state)
     }
 
+    /// Create a test app whose default controller is a simulated board
+    async fn create_simulated_app() -> (Router, SimulatedDevice) {
+        let board_info = BoardType::OpenFanStandard.to_board_info();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = load_config(&temp_dir, "").await;
+        let (cm, simulated) = connect_simulated("default", &board_info).await;
+
+        let state = AppState::single_controller(board_info, config, Some(cm)).await;
+        (create_router(state), simulated)
+    }
+
     /// Helper to extract response body as string
     async fn body_string(body: Body) -> String {
         let bytes = body.collect().await.unwrap().to_bytes();
@@ -442,7 +430,7 @@ mod integration_tests {
     }
 
     #[tokio::test]
-    async fn test_get_fan_status_mock_mode() {
+    async fn test_get_fan_status() {
         let app = create_test_app().await;
 
         let response = app
@@ -460,13 +448,13 @@ mod integration_tests {
         let body = body_string(response.into_body()).await;
         let json: serde_json::Value = serde_json::from_str(&body).unwrap();
 
-        // Verify it's a success response with mock data
+        // Verify it's a success response with fan data
         assert!(json.get("data").is_some());
         let data = json.get("data").unwrap();
         assert!(data.get("rpms").is_some());
         assert!(data.get("pwms").is_some());
 
-        // Mock mode returns 10 fans for Standard board
+        // The simulated Standard board reports 10 fans
         let rpms = data.get("rpms").unwrap().as_object().unwrap();
         assert_eq!(rpms.len(), 10);
     }
@@ -561,8 +549,21 @@ mod integration_tests {
     }
 
     #[tokio::test]
-    async fn test_get_fan_rpm_mock_mode() {
-        let app = create_test_app().await;
+    async fn test_get_fan_rpm_simulated() {
+        let (app, simulated) = create_simulated_app().await;
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v0/controller/default/fan/5/pwm?value=100")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        simulated.advance(Duration::from_secs(60));
 
         let response = app
             .oneshot(
@@ -579,9 +580,33 @@ mod integration_tests {
         let body = body_string(response.into_body()).await;
         let json: serde_json::Value = serde_json::from_str(&body).unwrap();
 
-        // Mock RPM for fan 5 should be 1500 + (5 * 100) = 2000
+        // Read from the simulated board, at full speed
         let data = json.get("data").unwrap();
-        assert_eq!(data.as_u64().unwrap(), 2000);
+        let rpm = simulated.rpm(5).unwrap();
+        assert!(rpm > 0);
+        assert_eq!(data.as_u64().unwrap(), u64::from(rpm));
+    }
+
+    #[tokio::test]
+    async fn test_fans_without_connection_unavailable() {
+        for uri in [
+            "/api/v0/controller/default/fan/5/rpm/get",
+            "/api/v0/controller/default/fan/5/rpm?value=1500",
+            "/api/v0/controller/default/fan/5/pwm?value=50",
+            "/api/v0/controller/default/fan/all/set?value=50",
+        ] {
+            let response = create_unconnected_app()
+                .await
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(
+                response.status(),
+                StatusCode::SERVICE_UNAVAILABLE,
+                "{}",
+                uri
+            );
+        }
     }
 
     #[tokio::test]
@@ -874,4 +899,73 @@ mod integration_tests {
             .unwrap();
         assert_eq!(response.status(), StatusCode::OK);
     }
+
+    #[tokio::test]
+    async fn test_simulated_board_fan_status() {
+        let (app, simulated) = create_simulated_app().await;
+
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v0/controller/default/fan/2/pwm?value=100")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Let the fan spin up to its maximum speed (2600 RPM for fan 2)
+        simulated.advance(Duration::from_secs(60));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v0/controller/default/fan/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_string(response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let data = json.get("data").unwrap();
+        assert_eq!(data["rpms"]["2"], 2600);
+        assert_eq!(data["rpms"]["0"], 0);
+        assert_eq!(data["pwms"]["2"], 100);
+    }
+
+    #[tokio::test]
+    async fn test_simulated_board_disconnect() {
+        let (app, simulated) = create_simulated_app().await;
+
+        simulated.disconnect();
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v0/controller/default/fan/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        // Plugged back in: the next request reconnects
+        simulated.reconnect();
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v0/controller/default/fan/status")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
 }
@@ -0,0 +1,204 @@
+//! Fan history handlers
+
+use crate::alarms::unix_time;
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api_ok;
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use openfan_core::api;
+use serde::Deserialize;
+use tracing::debug;
+
+/// Range returned when no start is given, in seconds
+const DEFAULT_RANGE_SECS: u64 = 3600;
+
+/// Query parameters for fan history.
+#[derive(Deserialize)]
+pub(crate) struct HistoryQuery {
+    /// Start of the range, as a Unix timestamp (default: one hour before `to`)
+    pub from: Option<u64>,
+    /// End of the range, as a Unix timestamp (default: now)
+    pub to: Option<u64>,
+    /// Time covered by each point, in seconds (default: the resolution)
+    pub step: Option<u64>,
+}
+
+/// Retrieves the recorded fan history of a controller.
+///
+/// Points are averaged over `step` seconds, which is rounded up to a multiple
+/// of `[history] resolution_secs` and coarsened for long ranges; the response
+/// gives the step used. Steps without readings are left out, and steps longer
+/// than `retention_hours` are rejected.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/controller/{id}/history?from=1700000000&to=1700003600&step=60`
+pub(crate) async fn get_controller_history(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<api::ApiResponse<api::HistoryResponse>>, ApiError> {
+    debug!("Request: GET /api/v0/controller/{}/history", controller_id);
+
+    let _ = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    if !state.config.static_config().history.enabled {
+        return Err(ApiError::service_unavailable(
+            "Fan history is disabled in config",
+        ));
+    }
+
+    let to = query.to.unwrap_or_else(unix_time);
+    let from = query.from.unwrap_or(to.saturating_sub(DEFAULT_RANGE_SECS));
+    if from > to {
+        return Err(ApiError::bad_request(format!(
+            "Start of range ({}) is after its end ({})",
+            from, to
+        )));
+    }
+    if query.step == Some(0) {
+        return Err(ApiError::bad_request("Step must be at least 1 second"));
+    }
+    let retention_secs = state.history.retention_secs();
+    if query.step.is_some_and(|step| step > retention_secs) {
+        return Err(ApiError::bad_request(format!(
+            "Step must be at most {} seconds, the retention window",
+            retention_secs
+        )));
+    }
+
+    let (step_secs, points) = state
+        .history
+        .query(&controller_id, from, to, query.step)
+        .await
+        .map_err(|e| ApiError::internal_error(format!("Failed to read fan history: {}", e)))?;
+
+    debug!(
+        "Read {} history points for controller '{}'",
+        points.len(),
+        controller_id
+    );
+    api_ok!(api::HistoryResponse {
+        controller: controller_id,
+        from,
+        to,
+        step_secs,
+        points,
+    })
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    use crate::api::create_router;
+    use crate::api::test_utils::create_test_state;
+
+    async fn get(router: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = router
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_get_history_records_samples() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&config_dir, "[history]\nresolution_secs = 5\n").await;
+        state.telemetry.start_all().await;
+        state.history.start_all().await;
+
+        // The point being averaged is returned as soon as a sample arrives
+        let json = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let router = create_router(state.clone());
+                let (status, json) = get(router, "/api/v0/controller/default/history").await;
+                assert_eq!(status, StatusCode::OK);
+                if !json["data"]["points"].as_array().unwrap().is_empty() {
+                    return json;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("a sample should be recorded");
+
+        let data = &json["data"];
+        assert_eq!(data["controller"], "default");
+        assert_eq!(data["step_secs"], 5);
+        assert_eq!(
+            data["to"].as_u64().unwrap() - data["from"].as_u64().unwrap(),
+            3600
+        );
+        let point = &data["points"][0];
+        assert_eq!(point["timestamp"].as_u64().unwrap() % 5, 0);
+        assert!(point["rpms"]["0"].is_number());
+
+        state.history.stop_all().await;
+        state.telemetry.stop_all().await;
+        assert!(config_dir.path().join("data/history/default.bin").exists());
+    }
+
+    #[tokio::test]
+    async fn test_get_history_rejects_bad_queries() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&config_dir, "").await;
+
+        let (status, _) = get(
+            create_router(state.clone()),
+            "/api/v0/controller/nope/history",
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, json) = get(
+            create_router(state.clone()),
+            "/api/v0/controller/default/history?from=200&to=100",
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(json["error"].as_str().unwrap().contains("after its end"));
+
+        let (status, _) = get(
+            create_router(state.clone()),
+            "/api/v0/controller/default/history?step=0",
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, json) = get(
+            create_router(state),
+            &format!("/api/v0/controller/default/history?step={}", u64::MAX),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(json["error"].as_str().unwrap().contains("retention window"));
+    }
+
+    #[tokio::test]
+    async fn test_get_history_disabled() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&config_dir, "[history]\nenabled = false\n").await;
+
+        let (status, _) = get(create_router(state), "/api/v0/controller/default/history").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
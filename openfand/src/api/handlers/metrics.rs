@@ -0,0 +1,363 @@
+//! Prometheus metrics handler
+//!
+//! Exposes fan readings and controller health in the Prometheus text
+//! exposition format, which OpenMetrics scrapers also accept.
+
+use crate::api::AppState;
+use crate::controllers::{ConnectionState, ControllerEntry};
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use openfan_core::config::{AliasData, CfmMappingData};
+use std::fmt::Write;
+use tracing::{debug, warn};
+
+/// Content type of the Prometheus text exposition format
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Connection states reported by `openfan_controller_connection_state`
+const CONNECTION_STATES: [&str; 3] = ["connected", "disconnected", "reconnecting"];
+
+/// A metric and its samples, rendered together as the format requires
+struct Family {
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+    samples: Vec<(String, String, f64)>,
+}
+
+impl Family {
+    fn new(name: &'static str, kind: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Add a sample with already rendered labels, under `suffix` of the name
+    fn add(&mut self, suffix: &str, labels: &str, value: f64) {
+        self.samples
+            .push((suffix.to_string(), labels.to_string(), value));
+    }
+
+    fn render(&self, out: &mut String) {
+        if self.samples.is_empty() {
+            return;
+        }
+        // write! to String is infallible
+        let _ = writeln!(out, "# HELP {} {}", self.name, self.help);
+        let _ = writeln!(out, "# TYPE {} {}", self.name, self.kind);
+        for (suffix, labels, value) in &self.samples {
+            let _ = writeln!(out, "{}{}{{{}}} {}", self.name, suffix, labels, value);
+        }
+    }
+}
+
+/// All metrics exposed by the daemon
+struct Metrics {
+    connection_state: Family,
+    reconnects: Family,
+    transactions: Family,
+    transaction_errors: Family,
+    transaction_duration: Family,
+    fan_rpm: Family,
+    fan_pwm: Family,
+    fan_target_rpm: Family,
+    fan_cfm: Family,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Self {
+            connection_state: Family::new(
+                "openfan_controller_connection_state",
+                "gauge",
+                "Connection state of the controller (1 for the current state).",
+            ),
+            reconnects: Family::new(
+                "openfan_controller_reconnects_total",
+                "counter",
+                "Successful reconnections since the daemon started.",
+            ),
+            transactions: Family::new(
+                "openfan_serial_transactions_total",
+                "counter",
+                "Serial transactions sent to the controller.",
+            ),
+            transaction_errors: Family::new(
+                "openfan_serial_transaction_errors_total",
+                "counter",
+                "Serial transactions that failed or got no valid response.",
+            ),
+            transaction_duration: Family::new(
+                "openfan_serial_transaction_duration_seconds",
+                "summary",
+                "Time spent in serial transactions.",
+            ),
+            fan_rpm: Family::new("openfan_fan_rpm", "gauge", "Measured fan speed in RPM."),
+            fan_pwm: Family::new(
+                "openfan_fan_pwm_percent",
+                "gauge",
+                "PWM percentage the fan was last set to.",
+            ),
+            fan_target_rpm: Family::new(
+                "openfan_fan_target_rpm",
+                "gauge",
+                "Target RPM the fan was last set to.",
+            ),
+            fan_cfm: Family::new(
+                "openfan_fan_cfm",
+                "gauge",
+                "Estimated airflow in CFM, from the fan's CFM mapping, RPM and PWM.",
+            ),
+        }
+    }
+
+    /// Add the metrics of one controller
+    async fn collect(&mut self, state: &AppState, entry: &ControllerEntry) {
+        let id = entry.id();
+        let Some(cm) = entry.connection_manager() else {
+            // Placeholder controllers have no hardware to report on
+            return;
+        };
+        let controller = format!("controller=\"{}\"", escape(id));
+
+        let connection_state = cm.connection_state().await;
+        for name in CONNECTION_STATES {
+            let value = if connection_state.as_str() == name {
+                1.0
+            } else {
+                0.0
+            };
+            self.connection_state
+                .add("", &format!("{},state=\"{}\"", controller, name), value);
+        }
+        self.reconnects
+            .add("", &controller, cm.reconnect_count() as f64);
+
+        // Sample the fans, unless that would start a reconnection
+        let sample = if connection_state == ConnectionState::Connected {
+            cm.with_controller(async |ctrl| {
+                Ok((
+                    ctrl.get_all_fan_rpm().await?,
+                    ctrl.get_all_fan_pwm(),
+                    ctrl.get_all_fan_rpm_targets(),
+                ))
+            })
+            .await
+            .inspect_err(|e| warn!("Controller '{}': Failed to read fans: {}", id, e))
+            .ok()
+        } else {
+            None
+        };
+
+        // Read after sampling, so this scrape's transaction is counted
+        let stats = cm.transaction_stats();
+        self.transactions
+            .add("", &controller, stats.transactions() as f64);
+        self.transaction_errors
+            .add("", &controller, stats.errors() as f64);
+        self.transaction_duration
+            .add("_sum", &controller, stats.total_latency().as_secs_f64());
+        self.transaction_duration
+            .add("_count", &controller, stats.transactions() as f64);
+
+        let Some((rpms, pwms, targets)) = sample else {
+            return;
+        };
+        let (aliases, cfm_mappings) = match state.config.controller_data(id).await {
+            Ok(data) => (
+                data.aliases().await.clone(),
+                data.cfm_mappings().await.clone(),
+            ),
+            Err(e) => {
+                warn!("Controller '{}': Failed to load data: {}", id, e);
+                (AliasData::empty(), CfmMappingData::new())
+            }
+        };
+
+        for fan in 0..entry.board_info().fan_count as u8 {
+            let labels = format!(
+                "{},fan=\"{}\",alias=\"{}\"",
+                controller,
+                fan,
+                escape(&aliases.get(fan))
+            );
+            if let Some(&rpm) = rpms.get(&fan) {
+                self.fan_rpm.add("", &labels, rpm as f64);
+            }
+            if let Some(&pwm) = pwms.get(&fan) {
+                self.fan_pwm.add("", &labels, pwm as f64);
+                if let Some(estimate) = cfm_mappings.estimate(fan, pwm, rpms.get(&fan).copied()) {
+                    // Round off the f32 noise, e.g. 27.000001907 for 27
+                    let cfm = (estimate.cfm as f64 * 100.0).round() / 100.0;
+                    self.fan_cfm.add("", &labels, cfm);
+                }
+            }
+            if let Some(&target) = targets.get(&fan) {
+                self.fan_target_rpm.add("", &labels, target as f64);
+            }
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        for family in [
+            &self.connection_state,
+            &self.reconnects,
+            &self.transactions,
+            &self.transaction_errors,
+            &self.transaction_duration,
+            &self.fan_rpm,
+            &self.fan_pwm,
+            &self.fan_target_rpm,
+            &self.fan_cfm,
+        ] {
+            family.render(&mut out);
+        }
+        out
+    }
+}
+
+/// Escape a label value: backslashes, double quotes and newlines
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Exports fan and controller metrics for Prometheus.
+///
+/// Fans are read on every scrape, so readings are never stale. A controller
+/// that is not connected only reports its connection state and counters.
+///
+/// # Endpoint
+///
+/// `GET /metrics`
+pub(crate) async fn get_metrics(State(state): State<AppState>) -> Response {
+    debug!("Request: GET /metrics");
+
+    let mut entries = state.registry.list().await;
+    entries.sort_by(|a, b| a.id().cmp(b.id()));
+
+    let mut metrics = Metrics::new();
+    for entry in &entries {
+        metrics.collect(&state, entry).await;
+    }
+
+    ([(header::CONTENT_TYPE, CONTENT_TYPE)], metrics.render()).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("CPU Fan"), "CPU Fan");
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+
+    #[test]
+    fn test_family_render() {
+        let mut family = Family::new("openfan_test", "gauge", "A test metric.");
+        let mut out = String::new();
+        family.render(&mut out);
+        assert!(out.is_empty());
+
+        family.add("", "controller=\"main\"", 1.5);
+        family.add("_count", "controller=\"main\"", 3.0);
+        family.render(&mut out);
+        assert_eq!(
+            out,
+            "# HELP openfan_test A test metric.\n\
+             # TYPE openfan_test gauge\n\
+             openfan_test{controller=\"main\"} 1.5\n\
+             openfan_test_count{controller=\"main\"} 3\n"
+        );
+    }
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode, header},
+    };
+    use http_body_util::BodyExt;
+    use openfan_core::BoardType;
+
+    use tower::ServiceExt;
+
+    use crate::api::test_utils::{connect_simulated, load_config};
+    use crate::api::{AppState, create_router};
+
+    #[tokio::test]
+    async fn test_metrics_for_simulated_controller() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let config = load_config(&config_dir, "").await;
+        let board = BoardType::Custom { fan_count: 2 }.to_board_info();
+        let (cm, _) = connect_simulated("default", &board).await;
+        cm.with_controller(async |ctrl| ctrl.set_fan_pwm(0, 50).await)
+            .await
+            .unwrap();
+        {
+            let data = config.controller_data("default").await.unwrap();
+            data.aliases_mut()
+                .await
+                .set(0, "CPU \"Intake\"".to_string());
+            data.cfm_mappings_mut().await.set(0, 40.0);
+        }
+
+        let state = AppState::single_controller(board, config, Some(cm)).await;
+        let router = create_router(state);
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/metrics")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(
+            response.headers()[header::CONTENT_TYPE]
+                .to_str()
+                .unwrap()
+                .starts_with("text/plain; version=0.0.4")
+        );
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+
+        let fan0 = r#"controller="default",fan="0",alias="CPU \"Intake\"""#;
+        assert!(body.contains("# TYPE openfan_fan_rpm gauge"));
+        assert!(body.contains(&format!("openfan_fan_pwm_percent{{{}}} 50\n", fan0)));
+        assert!(body.contains(&format!("openfan_fan_cfm{{{}}} 20\n", fan0)));
+        assert!(body.contains(r#"openfan_fan_rpm{controller="default",fan="1",alias="Fan #2"}"#));
+        // Fan 1 was never set
+        assert!(!body.contains(r#"openfan_fan_pwm_percent{controller="default",fan="1""#));
+        assert!(body.contains(
+            "openfan_controller_connection_state{controller=\"default\",state=\"connected\"} 1\n"
+        ));
+        assert!(body.contains(
+            "openfan_controller_connection_state{controller=\"default\",state=\"reconnecting\"} 0\n"
+        ));
+        assert!(body.contains("openfan_controller_reconnects_total{controller=\"default\"} 0\n"));
+        assert!(
+            body.contains("openfan_serial_transaction_errors_total{controller=\"default\"} 0\n")
+        );
+        assert!(body.contains("# TYPE openfan_serial_transaction_duration_seconds summary"));
+        assert!(
+            body.contains(
+                "openfan_serial_transaction_duration_seconds_count{controller=\"default\"}"
+            )
+        );
+    }
+}
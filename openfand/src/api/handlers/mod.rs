@@ -5,17 +5,28 @@
 //! # Handler Modules
 //!
 //! - [`info`] - System information and root endpoint
+//! - [`sensors`] - Host temperature sensor discovery
+//! - [`alarms`] - Active fan stall and failure alarms
+//! - [`events`] - Live telemetry stream (Server-Sent Events)
+//! - [`history`] - Recorded fan history
+//! - [`metrics`] - Prometheus metrics export
+//! - [`reload`] - Configuration reload
 //! - [`fans`] - Fan status and control (PWM/RPM)
+//! - [`calibration`] - Fan calibration jobs and their results
+//! - [`fan_limits`] - Per-fan safety limits
 //! - [`profiles`] - Fan profile management (CRUD operations)
 //! - [`aliases`] - Fan alias management
 //! - [`zones`] - Zone management for grouped fan control
 //! - [`thermal_curves`] - Thermal curve management for temperature-based PWM control
+//! - [`pid_controllers`] - PID controller management for holding a target temperature
+//! - [`curve_bindings`] - Curve bindings driven by the closed-loop control engine
 //! - [`cfm`] - CFM mapping management for display-only airflow information
 //! - [`controllers`] - Controller management for multi-controller setups
 //!
 //! # API Structure
 //!
-//! All handlers follow a consistent pattern:
+//! All handlers except [`events`] and [`metrics`], which stream events and
+//! return the Prometheus text format, follow a consistent pattern:
 //! - Accept `State<AppState>` for accessing shared application state
 //! - Return `Result<Json<ApiResponse<T>>, ApiError>` for uniform responses
 //! - Use `api_ok!()` and `api_fail!()` macros for response construction
@@ -28,8 +39,10 @@
 //!
 //! # Mock Mode
 //!
-//! When hardware is not available (`state.connection_manager` is `None`),
-//! handlers return simulated data for testing and development purposes.
+//! `openfand --mock` backs each controller with a simulated board, so handlers
+//! go through the regular `ConnectionManager` path. Requests that need the
+//! hardware of a controller registered without a connection manager fail with
+//! [`OpenFanError::DeviceNotFound`](openfan_core::OpenFanError::DeviceNotFound).
 //!
 //! # Connection Management
 //!
@@ -38,12 +51,22 @@
 //! to execute operations, which automatically detects disconnections and
 //! triggers reconnection attempts.
 
+pub(crate) mod alarms;
 pub(crate) mod aliases;
+pub(crate) mod calibration;
 pub(crate) mod cfm;
 pub(crate) mod controllers;
+pub(crate) mod curve_bindings;
+pub(crate) mod events;
+pub(crate) mod fan_limits;
 pub(crate) mod fans;
+pub(crate) mod history;
 pub(crate) mod info;
+pub(crate) mod metrics;
+pub(crate) mod pid_controllers;
 pub(crate) mod profiles;
+pub(crate) mod reload;
+pub(crate) mod sensors;
 pub(crate) mod thermal_curves;
 pub(crate) mod zones;
 
@@ -0,0 +1,515 @@
+//! PID controller handlers for CRUD operations
+
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api::handlers::thermal_curves::is_valid_curve_name;
+use crate::{api_fail, api_ok};
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use openfan_core::{OpenFanError, PidController, api};
+use tracing::{debug, info};
+
+/// Lists all PID controllers for a specific controller.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/controller/{id}/pids/list`
+pub(crate) async fn list_controller_pids(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+) -> Result<Json<api::ApiResponse<api::PidControllerResponse>>, ApiError> {
+    debug!(
+        "Request: GET /api/v0/controller/{}/pids/list",
+        controller_id
+    );
+
+    // Validate controller exists in registry
+    let _ = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    // Get controller data
+    let controller_data = state.config.controller_data(&controller_id).await?;
+
+    let response = api::PidControllerResponse {
+        controllers: controller_data.pid_controllers().await.controllers.clone(),
+    };
+
+    info!(
+        "Listed {} PID controllers for controller '{}'",
+        response.controllers.len(),
+        controller_id
+    );
+    api_ok!(response)
+}
+
+/// Adds a new PID controller for a specific controller.
+///
+/// # Endpoint
+///
+/// `POST /api/v0/controller/{id}/pids/add`
+pub(crate) async fn add_controller_pid(
+    State(state): State<AppState>,
+    Path(controller_id): Path<String>,
+    Json(request): Json<api::AddPidRequest>,
+) -> Result<Json<api::ApiResponse<()>>, ApiError> {
+    debug!(
+        "Request: POST /api/v0/controller/{}/pids/add",
+        controller_id
+    );
+
+    // Validate controller exists in registry
+    let _ = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    let pid_name = request.name.trim();
+
+    if !is_valid_curve_name(pid_name) {
+        return api_fail!(
+            "PID name must be non-empty and contain only alphanumeric characters, hyphens, and underscores!"
+        );
+    }
+
+    let mut pid = PidController::new(
+        pid_name,
+        request.setpoint_c,
+        request.kp,
+        request.ki,
+        request.kd,
+    )
+    .with_output_range(request.min_pwm.unwrap_or(0), request.max_pwm.unwrap_or(100));
+    pid.description = request.description;
+
+    if let Err(e) = pid.validate() {
+        return api_fail!(e);
+    }
+
+    // Get controller data
+    let controller_data = state.config.controller_data(&controller_id).await?;
+
+    // Add PID controller
+    {
+        let mut controllers = controller_data.pid_controllers_mut().await;
+
+        if controllers.contains(pid_name) {
+            return api_fail!(format!("PID controller '{}' already exists!", pid_name));
+        }
+
+        controllers.insert(pid_name.to_string(), pid);
+    }
+
+    // Save to disk
+    if let Err(e) = controller_data.save_pid_controllers().await {
+        return Err(ApiError::internal_error(format!(
+            "Failed to save PID controllers: {}",
+            e
+        )));
+    }
+
+    info!(
+        "Added PID controller '{}' for controller '{}'",
+        pid_name, controller_id
+    );
+    api_ok!(())
+}
+
+/// Gets a single PID controller by name for a specific controller.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/controller/{id}/pid/{name}/get`
+pub(crate) async fn get_controller_pid(
+    State(state): State<AppState>,
+    Path((controller_id, name)): Path<(String, String)>,
+) -> Result<Json<api::ApiResponse<api::SinglePidResponse>>, ApiError> {
+    debug!(
+        "Request: GET /api/v0/controller/{}/pid/{}/get",
+        controller_id, name
+    );
+
+    // Validate controller exists in registry
+    let _ = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    // Get controller data
+    let controller_data = state.config.controller_data(&controller_id).await?;
+
+    match controller_data.pid_controllers().await.get(&name) {
+        Some(pid) => api_ok!(api::SinglePidResponse { pid: pid.clone() }),
+        None => Err(OpenFanError::PidNotFound(name).into()),
+    }
+}
+
+/// Updates an existing PID controller for a specific controller.
+///
+/// Settings not provided keep their current value. Running control loops
+/// pick up the change on their next poll.
+///
+/// # Endpoint
+///
+/// `POST /api/v0/controller/{id}/pid/{name}/update`
+pub(crate) async fn update_controller_pid(
+    State(state): State<AppState>,
+    Path((controller_id, name)): Path<(String, String)>,
+    Json(request): Json<api::UpdatePidRequest>,
+) -> Result<Json<api::ApiResponse<()>>, ApiError> {
+    debug!(
+        "Request: POST /api/v0/controller/{}/pid/{}/update",
+        controller_id, name
+    );
+
+    // Validate controller exists in registry
+    let _ = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    // Get controller data
+    let controller_data = state.config.controller_data(&controller_id).await?;
+
+    // Update PID controller
+    {
+        let mut controllers = controller_data.pid_controllers_mut().await;
+
+        let Some(existing) = controllers.get(&name) else {
+            return Err(OpenFanError::PidNotFound(name).into());
+        };
+
+        let mut pid = existing.clone();
+        pid.setpoint_c = request.setpoint_c.unwrap_or(pid.setpoint_c);
+        pid.kp = request.kp.unwrap_or(pid.kp);
+        pid.ki = request.ki.unwrap_or(pid.ki);
+        pid.kd = request.kd.unwrap_or(pid.kd);
+        pid.min_pwm = request.min_pwm.unwrap_or(pid.min_pwm);
+        pid.max_pwm = request.max_pwm.unwrap_or(pid.max_pwm);
+        if request.description.is_some() {
+            pid.description = request.description;
+        }
+
+        if let Err(e) = pid.validate() {
+            return api_fail!(e);
+        }
+
+        controllers.insert(name.clone(), pid);
+    }
+
+    // Save to disk
+    if let Err(e) = controller_data.save_pid_controllers().await {
+        return Err(ApiError::internal_error(format!(
+            "Failed to save PID controllers: {}",
+            e
+        )));
+    }
+
+    info!(
+        "Updated PID controller '{}' for controller '{}'",
+        name, controller_id
+    );
+    api_ok!(())
+}
+
+/// Deletes a PID controller for a specific controller.
+///
+/// # Endpoint
+///
+/// `DELETE /api/v0/controller/{id}/pid/{name}`
+pub(crate) async fn delete_controller_pid(
+    State(state): State<AppState>,
+    Path((controller_id, name)): Path<(String, String)>,
+) -> Result<Json<api::ApiResponse<()>>, ApiError> {
+    debug!(
+        "Request: DELETE /api/v0/controller/{}/pid/{}",
+        controller_id, name
+    );
+
+    // Validate controller exists in registry
+    let _ = state
+        .registry
+        .get_or_err(&controller_id)
+        .await
+        .map_err(ApiError::from)?;
+
+    // Get controller data
+    let controller_data = state.config.controller_data(&controller_id).await?;
+
+    // Refuse to delete a PID controller that a binding still drives fans with
+    {
+        let bindings = controller_data.curve_bindings().await;
+        if let Some(binding) = bindings.bindings_for_pid(&name).next() {
+            return api_fail!(format!(
+                "PID controller '{}' is used by binding '{}'",
+                name, binding.name
+            ));
+        }
+    }
+
+    // Remove PID controller
+    {
+        let mut controllers = controller_data.pid_controllers_mut().await;
+
+        if controllers.remove(&name).is_none() {
+            return Err(OpenFanError::PidNotFound(name).into());
+        }
+    }
+
+    // Save to disk
+    if let Err(e) = controller_data.save_pid_controllers().await {
+        return Err(ApiError::internal_error(format!(
+            "Failed to save PID controllers: {}",
+            e
+        )));
+    }
+
+    info!(
+        "Deleted PID controller '{}' from controller '{}'",
+        name, controller_id
+    );
+    api_ok!(())
+}
+
+/// Integration tests that exercise actual HTTP handlers
+#[cfg(test)]
+mod integration_tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Method, Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use openfan_core::BoardType;
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    use crate::api::{AppState, create_router};
+    use crate::config::RuntimeConfig;
+
+    struct TestApp {
+        router: Router,
+        _config_dir: TempDir,
+    }
+
+    impl TestApp {
+        async fn new() -> Self {
+            let board_info = BoardType::OpenFanStandard.to_board_info();
+            let config_dir = tempfile::tempdir().unwrap();
+
+            let data_dir = config_dir.path().join("data");
+            std::fs::create_dir_all(&data_dir).unwrap();
+
+            let data_dir_str = data_dir.to_string_lossy().replace('\\', "\\\\");
+            let config_content = format!(
+                r#"data_dir = "{}"
+
+[server]
+bind_address = "127.0.0.1"
+port = 3000
+communication_timeout = 1
+"#,
+                data_dir_str
+            );
+
+            let config_path = config_dir.path().join("config.toml");
+            std::fs::write(&config_path, config_content).unwrap();
+
+            let config = RuntimeConfig::load(&config_path).await.unwrap();
+            let state =
+                AppState::single_controller(board_info, std::sync::Arc::new(config), None).await;
+
+            TestApp {
+                router: create_router(state),
+                _config_dir: config_dir,
+            }
+        }
+
+        async fn post(
+            &self,
+            uri: &str,
+            body: serde_json::Value,
+        ) -> (StatusCode, serde_json::Value) {
+            let response = self
+                .router
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri(uri)
+                        .header("content-type", "application/json")
+                        .body(Body::from(body.to_string()))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            let status = response.status();
+            let body = body_string(response.into_body()).await;
+            (status, serde_json::from_str(&body).unwrap())
+        }
+
+        async fn get(&self, uri: &str) -> (StatusCode, serde_json::Value) {
+            let response = self
+                .router
+                .clone()
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+
+            let status = response.status();
+            let body = body_string(response.into_body()).await;
+            (status, serde_json::from_str(&body).unwrap())
+        }
+
+        async fn delete(&self, uri: &str) -> StatusCode {
+            self.router
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::DELETE)
+                        .uri(uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap()
+                .status()
+        }
+
+        async fn add_loop_pid(&self) {
+            let (status, _) = self
+                .post(
+                    "/api/v0/controller/default/pids/add",
+                    serde_json::json!({
+                        "name": "loop",
+                        "setpoint_c": 40.0,
+                        "kp": 5.0,
+                        "ki": 0.1,
+                        "kd": 0.0,
+                        "min_pwm": 20,
+                        "description": "Water loop"
+                    }),
+                )
+                .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+    }
+
+    async fn body_string(body: Body) -> String {
+        let bytes = body.collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_and_get_pid() {
+        let app = TestApp::new().await;
+        app.add_loop_pid().await;
+
+        let (status, json) = app.get("/api/v0/controller/default/pid/loop/get").await;
+        assert_eq!(status, StatusCode::OK);
+        let pid = &json["data"]["pid"];
+        assert_eq!(pid["setpoint_c"], 40.0);
+        assert_eq!(pid["min_pwm"], 20);
+        assert_eq!(pid["max_pwm"], 100);
+        assert_eq!(pid["description"], "Water loop");
+
+        let (status, json) = app.get("/api/v0/controller/default/pids/list").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["data"]["controllers"]["loop"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_add_pid_validation_errors() {
+        let app = TestApp::new().await;
+        app.add_loop_pid().await;
+
+        let cases = [
+            // Invalid name
+            serde_json::json!({"name": "has space", "setpoint_c": 40.0, "kp": 1.0, "ki": 0.0, "kd": 0.0}),
+            // Negative gain
+            serde_json::json!({"name": "a", "setpoint_c": 40.0, "kp": -1.0, "ki": 0.0, "kd": 0.0}),
+            // Setpoint out of range
+            serde_json::json!({"name": "a", "setpoint_c": 400.0, "kp": 1.0, "ki": 0.0, "kd": 0.0}),
+            // Inverted output range
+            serde_json::json!({"name": "a", "setpoint_c": 40.0, "kp": 1.0, "ki": 0.0, "kd": 0.0, "min_pwm": 80, "max_pwm": 20}),
+            // Duplicate
+            serde_json::json!({"name": "loop", "setpoint_c": 40.0, "kp": 1.0, "ki": 0.0, "kd": 0.0}),
+        ];
+
+        for case in cases {
+            let (status, json) = app
+                .post("/api/v0/controller/default/pids/add", case.clone())
+                .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "case: {}", case);
+            assert_eq!(json["status"], "error");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_pid_merges_settings() {
+        let app = TestApp::new().await;
+        app.add_loop_pid().await;
+
+        let (status, _) = app
+            .post(
+                "/api/v0/controller/default/pid/loop/update",
+                serde_json::json!({"setpoint_c": 45.0, "kd": 2.0}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (_, json) = app.get("/api/v0/controller/default/pid/loop/get").await;
+        let pid = &json["data"]["pid"];
+        assert_eq!(pid["setpoint_c"], 45.0);
+        assert_eq!(pid["kd"], 2.0);
+        assert_eq!(pid["kp"], 5.0);
+        assert_eq!(pid["min_pwm"], 20);
+        assert_eq!(pid["description"], "Water loop");
+
+        // Invalid result is rejected
+        let (status, _) = app
+            .post(
+                "/api/v0/controller/default/pid/loop/update",
+                serde_json::json!({"max_pwm": 10}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, json) = app
+            .post(
+                "/api/v0/controller/default/pid/missing/update",
+                serde_json::json!({"kp": 1.0}),
+            )
+            .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            json["error"]
+                .as_str()
+                .unwrap()
+                .contains("PID controller not found")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_delete_pid() {
+        let app = TestApp::new().await;
+        app.add_loop_pid().await;
+
+        let status = app.delete("/api/v0/controller/default/pid/loop").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = app.get("/api/v0/controller/default/pid/loop/get").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let status = app.delete("/api/v0/controller/default/pid/loop").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
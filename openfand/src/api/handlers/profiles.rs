@@ -2,12 +2,13 @@
 
 use crate::api::AppState;
 use crate::api::error::ApiError;
+use crate::limits::FanLimiter;
 use crate::{api_fail, api_ok};
 use axum::{
     Json,
     extract::{Path, Query, State},
 };
-use openfan_core::{ControlMode, FanProfile, api};
+use openfan_core::{ControlMode, FanProfile, OpenFanError, api};
 use serde::Deserialize;
 
 use tracing::{debug, info, warn};
@@ -254,20 +255,19 @@ pub(crate) async fn set_controller_profile(
         }
     };
 
-    // Check if hardware is available
-    let Some(cm) = entry.connection_manager() else {
-        debug!(
-            "Controller '{}' is in mock mode - simulating profile application",
-            controller_id
-        );
-        info!(
-            "Applied profile '{}' to controller '{}' (mock mode)",
-            profile_name, controller_id
-        );
-        return api_ok!(());
-    };
+    // Move the values into the fans' limits; when rejecting, no fan is set
+    let limiter = FanLimiter::load(&state.config, &entry).await?;
+    let profile_values = profile
+        .values
+        .iter()
+        .enumerate()
+        .map(|(fan_id, &value)| limiter.value(fan_id as u8, profile.control_mode, value))
+        .collect::<openfan_core::Result<Vec<_>>>()?;
+
+    let cm = entry
+        .connection_manager()
+        .ok_or(OpenFanError::DeviceNotFound)?;
 
-    let profile_values = profile.values.clone();
     let control_mode = profile.control_mode;
     let pname = profile_name.clone();
     let cid = controller_id.clone();
@@ -421,8 +421,8 @@ mod integration_tests {
     use tempfile::TempDir;
     use tower::ServiceExt;
 
+    use crate::api::test_utils::{connect_simulated, load_config};
     use crate::api::{AppState, create_router};
-    use crate::config::RuntimeConfig;
 
     struct TestApp {
         router: Router,
@@ -433,28 +433,18 @@ mod integration_tests {
         async fn new() -> Self {
             let board_info = BoardType::OpenFanStandard.to_board_info();
             let config_dir = tempfile::tempdir().unwrap();
-
-            let data_dir = config_dir.path().join("data");
-            std::fs::create_dir_all(&data_dir).unwrap();
-
-            let data_dir_str = data_dir.to_string_lossy().replace('\\', "\\\\");
-            let config_content = format!(
-                r#"data_dir = "{}"
-
+            let config = load_config(
+                &config_dir,
+                r#"
 [server]
 bind_address = "127.0.0.1"
 port = 3000
 communication_timeout = 1
 "#,
-                data_dir_str
-            );
-
-            let config_path = config_dir.path().join("config.toml");
-            std::fs::write(&config_path, config_content).unwrap();
-
-            let config = RuntimeConfig::load(&config_path).await.unwrap();
-            let state =
-                AppState::single_controller(board_info, std::sync::Arc::new(config), None).await;
+            )
+            .await;
+            let (cm, _) = connect_simulated("default", &board_info).await;
+            let state = AppState::single_controller(board_info, config, Some(cm)).await;
 
             TestApp {
                 router: create_router(state),
@@ -735,7 +725,7 @@ communication_timeout = 1
     }
 
     #[tokio::test]
-    async fn test_add_then_set_profile_mock_mode() {
+    async fn test_add_then_set_profile() {
         let app = TestApp::new().await;
 
         // Add profile
@@ -755,7 +745,7 @@ communication_timeout = 1
             .unwrap();
         assert_eq!(add_response.status(), StatusCode::OK);
 
-        // Set/apply profile (mock mode - no hardware)
+        // Set/apply profile on the simulated board
         let set_response = app
             .router()
             .oneshot(
@@ -0,0 +1,342 @@
+//! Configuration reload handlers
+
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api_ok;
+use axum::{Json, extract::State};
+use openfan_core::api;
+use tracing::debug;
+
+/// Reloads `config.toml` and the data files, as SIGHUP does.
+///
+/// A rejected reload keeps the previous configuration and fails with the
+/// reason; warnings list the changes that need a restart.
+///
+/// # Endpoint
+///
+/// `POST /api/v0/config/reload`
+pub(crate) async fn reload_config(
+    State(state): State<AppState>,
+) -> Result<Json<api::ApiResponse<api::ReloadReport>>, ApiError> {
+    debug!("Request: POST /api/v0/config/reload");
+
+    let report = state.reload.reload(api::ReloadTrigger::Api).await;
+    match &report.error {
+        Some(error) => Err(ApiError::bad_request(format!(
+            "Reload rejected, keeping the previous configuration: {}",
+            error
+        ))),
+        None => api_ok!(report),
+    }
+}
+
+/// Reports the outcome of the last reload, whatever triggered it.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/config/reload`
+pub(crate) async fn get_reload_status(
+    State(state): State<AppState>,
+) -> Result<Json<api::ApiResponse<api::ReloadStatusResponse>>, ApiError> {
+    debug!("Request: GET /api/v0/config/reload");
+
+    api_ok!(api::ReloadStatusResponse {
+        last_reload: state.reload.last().await,
+    })
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use openfan_core::BoardType;
+
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use tower::ServiceExt;
+
+    use crate::api::{AppState, create_router, test_utils};
+    use crate::config::RuntimeConfig;
+    use crate::controllers::{ControllerEntry, ControllerRegistry};
+    use crate::reload::ControllerSetup;
+
+    fn write_config(config_dir: &Path, extra_config: &str) {
+        let data_dir = config_dir.join("data");
+        std::fs::write(
+            config_dir.join("config.toml"),
+            format!(
+                "data_dir = \"{}\"\n{}",
+                data_dir.to_string_lossy().replace('\\', "\\\\"),
+                extra_config
+            ),
+        )
+        .unwrap();
+    }
+
+    async fn create_test_state(config_dir: &tempfile::TempDir) -> AppState {
+        // Profiles and aliases sized for the 2-fan board, as the reload validates them
+        let data_dir = config_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        std::fs::write(
+            data_dir.join("profiles.toml"),
+            "[profiles.\"Half\"]\ntype = \"pwm\"\nvalues = [50, 50]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            data_dir.join("aliases.toml"),
+            "[aliases]\n0 = \"Fan #1\"\n1 = \"Fan #2\"\n",
+        )
+        .unwrap();
+        test_utils::create_test_state(config_dir, "").await
+    }
+
+    async fn send(router: Router, method: &str, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri(uri)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_reload_applies_edited_files() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&config_dir).await;
+        let data = state.config.controller_data("default").await.unwrap();
+
+        let (status, json) =
+            send(create_router(state.clone()), "GET", "/api/v0/config/reload").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json["data"]["last_reload"].is_null());
+
+        // Edit config.toml and a controller's profiles by hand
+        write_config(
+            config_dir.path(),
+            "[alarms]\nenabled = false\n[server]\nbind_address = \"127.0.0.1\"\nport = 4000\ncommunication_timeout = 1\n",
+        );
+        std::fs::write(
+            config_dir
+                .path()
+                .join("data/controllers/default/profiles.toml"),
+            "[profiles.\"Quiet\"]\ntype = \"pwm\"\nvalues = [20, 20]\n",
+        )
+        .unwrap();
+
+        let (status, json) = send(
+            create_router(state.clone()),
+            "POST",
+            "/api/v0/config/reload",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        let report = &json["data"];
+        assert_eq!(report["trigger"], "api");
+        assert_eq!(report["success"], true);
+        let changes = report["changes"].as_array().unwrap();
+        assert!(changes.contains(&"[alarms]".into()), "{:?}", changes);
+        assert!(changes.contains(&"controller 'default': profiles".into()));
+        assert!(report["warnings"][0].as_str().unwrap().contains("[server]"));
+
+        assert!(!state.config.static_config().alarms.enabled);
+        // Settings read at startup keep their value
+        assert_eq!(state.config.static_config().server.port, 3000);
+        // Holders of the controller's data see the new profiles
+        assert!(data.profiles().await.profiles.contains_key("Quiet"));
+
+        // Nothing changed since
+        let (_, json) = send(
+            create_router(state.clone()),
+            "POST",
+            "/api/v0/config/reload",
+        )
+        .await;
+        assert!(json["data"]["changes"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_reload_keeps_previous_config() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&config_dir).await;
+
+        // A zone fan beyond the 2-fan board
+        std::fs::write(
+            config_dir.path().join("data/zones.toml"),
+            "[zones.\"intake\"]\nname = \"intake\"\n\
+             fans = [{ controller = \"default\", fan_id = 5 }]\n",
+        )
+        .unwrap();
+        write_config(config_dir.path(), "[alarms]\nenabled = false\n");
+
+        let (status, json) = send(
+            create_router(state.clone()),
+            "POST",
+            "/api/v0/config/reload",
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            json["error"]
+                .as_str()
+                .unwrap()
+                .contains("keeping the previous configuration")
+        );
+        assert!(state.config.static_config().alarms.enabled);
+        assert!(state.config.zones().await.zones.is_empty());
+
+        let (_, json) = send(create_router(state), "GET", "/api/v0/config/reload").await;
+        let report = &json["data"]["last_reload"];
+        assert_eq!(report["success"], false);
+        assert!(
+            report["error"]
+                .as_str()
+                .unwrap()
+                .contains("fan 5 is out of range"),
+            "{}",
+            report
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reload_rejects_dangling_binding() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let state = create_test_state(&config_dir).await;
+        let data = state.config.controller_data("default").await.unwrap();
+        let bindings_path = config_dir
+            .path()
+            .join("data/controllers/default/curve_bindings.toml");
+
+        for (bindings, error) in [
+            (
+                "[bindings.cpu]\nname = \"cpu\"\ncurve = \"Missing\"\n\
+                 sensor = \"/tmp/temp\"\nfans = [0]\n",
+                "thermal curve 'Missing' not found",
+            ),
+            (
+                "[bindings.cpu]\nname = \"cpu\"\ncurve = \"Balanced\"\n\
+                 sensor = \"no-such-sensor\"\nfans = [0]\n",
+                "sensor 'no-such-sensor' not found",
+            ),
+            (
+                "[bindings.cpu]\nname = \"cpu\"\ncurve = \"Balanced\"\n\
+                 sensor = \"/tmp/temp\"\nzone = \"missing\"\n",
+                "zone 'missing' not found",
+            ),
+            (
+                "[bindings.cpu]\nname = \"cpu\"\ncurve = \"Balanced\"\n\
+                 sensor = \"/tmp/temp\"\nfans = [5]\n",
+                "Fan ID out of range: 5",
+            ),
+            (
+                "[bindings.cpu]\nname = \"cpu\"\ncurve = \"Balanced\"\n\
+                 sensor = \"/tmp/temp\"\nfans = [0]\ninterval_secs = 0\n",
+                "binding 'cpu'",
+            ),
+        ] {
+            std::fs::write(&bindings_path, bindings).unwrap();
+            write_config(config_dir.path(), "[alarms]\nenabled = false\n");
+
+            let (status, json) = send(
+                create_router(state.clone()),
+                "POST",
+                "/api/v0/config/reload",
+            )
+            .await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", bindings);
+            let message = json["error"].as_str().unwrap();
+            assert!(message.contains(error), "{}", message);
+
+            // The previous configuration is kept
+            assert!(state.config.static_config().alarms.enabled);
+            assert!(data.curve_bindings().await.bindings.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reload_follows_controllers_section() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let main =
+            "[[controllers]]\nid = \"main\"\ndevice = \"/dev/ttyACM0\"\nboard = \"standard\"\n";
+        write_config(config_dir.path(), main);
+        let config = Arc::new(
+            RuntimeConfig::load(&config_dir.path().join("config.toml"))
+                .await
+                .unwrap(),
+        );
+
+        let board = BoardType::OpenFanStandard.to_board_info();
+        let registry = ControllerRegistry::new();
+        registry
+            .register(ControllerEntry::builder("main", board.clone()).build())
+            .await
+            .unwrap();
+        let setup = ControllerSetup {
+            from_config: true,
+            default_id: "main".to_string(),
+            mock: true,
+            verbose: false,
+        };
+        let state = AppState::new(Arc::new(registry), config, board, None, setup);
+
+        // Add a controller, and try to move the default one
+        write_config(
+            config_dir.path(),
+            "[[controllers]]\nid = \"main\"\ndevice = \"/dev/ttyACM1\"\nboard = \"standard\"\n\
+             [[controllers]]\nid = \"gpu\"\ndevice = \"/dev/ttyACM2\"\nboard = \"custom:4\"\n",
+        );
+
+        let (status, json) = send(
+            create_router(state.clone()),
+            "POST",
+            "/api/v0/config/reload",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", json);
+        let report = &json["data"];
+        assert!(
+            report["changes"]
+                .as_array()
+                .unwrap()
+                .contains(&"controller 'gpu': added".into()),
+            "{}",
+            report
+        );
+        assert!(
+            report["warnings"][0]
+                .as_str()
+                .unwrap()
+                .contains("'main' is the default controller")
+        );
+
+        let gpu = state.registry.get("gpu").await.unwrap();
+        assert_eq!(gpu.board_info().fan_count, 4);
+        assert!(gpu.connection_manager().is_some());
+        assert_eq!(
+            state.config.static_config().controllers[0].device,
+            "/dev/ttyACM0"
+        );
+
+        // Dropping it from the file removes it again
+        write_config(config_dir.path(), main);
+        let (status, _) = send(
+            create_router(state.clone()),
+            "POST",
+            "/api/v0/config/reload",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(state.registry.get("gpu").await.is_none());
+    }
+}
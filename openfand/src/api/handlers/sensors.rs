@@ -0,0 +1,101 @@
+//! Sensor handlers for host temperature sensor discovery
+
+use crate::api::AppState;
+use crate::api::error::ApiError;
+use crate::api_ok;
+use axum::{Json, extract::State};
+use openfan_core::api;
+use tracing::{debug, info};
+
+/// Lists all discovered temperature sensors with their current readings.
+///
+/// Sensor IDs returned here can be used as the `sensor` of a curve binding.
+///
+/// # Endpoint
+///
+/// `GET /api/v0/sensors`
+pub(crate) async fn list_sensors(
+    State(state): State<AppState>,
+) -> Result<Json<api::ApiResponse<api::SensorListResponse>>, ApiError> {
+    debug!("Request: GET /api/v0/sensors");
+
+    let sensors = state.sensors.list().await;
+
+    info!("Listed {} temperature sensors", sensors.len());
+    api_ok!(api::SensorListResponse { sensors })
+}
+
+#[cfg(test)]
+mod integration_tests {
+    use axum::{
+        Router,
+        body::Body,
+        http::{Request, StatusCode},
+    };
+    use http_body_util::BodyExt;
+    use openfan_core::BoardType;
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    use crate::api::{AppState, create_router};
+    use crate::config::RuntimeConfig;
+
+    async fn create_test_router(config_dir: &TempDir) -> Router {
+        let board_info = BoardType::OpenFanStandard.to_board_info();
+
+        let data_dir = config_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+
+        let hwmon_root = config_dir.path().join("hwmon");
+        let chip = hwmon_root.join("hwmon0");
+        std::fs::create_dir_all(&chip).unwrap();
+        std::fs::write(chip.join("name"), "nvme\n").unwrap();
+        std::fs::write(chip.join("temp1_input"), "38850\n").unwrap();
+        std::fs::write(chip.join("temp1_label"), "Composite\n").unwrap();
+
+        let config_content = format!(
+            r#"data_dir = "{}"
+
+[sensors]
+hwmon_root = "{}"
+"#,
+            data_dir.to_string_lossy().replace('\\', "\\\\"),
+            hwmon_root.to_string_lossy().replace('\\', "\\\\")
+        );
+
+        let config_path = config_dir.path().join("config.toml");
+        std::fs::write(&config_path, config_content).unwrap();
+
+        let config = RuntimeConfig::load(&config_path).await.unwrap();
+        let state =
+            AppState::single_controller(board_info, std::sync::Arc::new(config), None).await;
+        create_router(state)
+    }
+
+    #[tokio::test]
+    async fn test_list_sensors() {
+        let config_dir = tempfile::tempdir().unwrap();
+        let router = create_test_router(&config_dir).await;
+
+        let response = router
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v0/sensors")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+        let sensors = json["data"]["sensors"].as_array().unwrap();
+        assert_eq!(sensors.len(), 1);
+        assert_eq!(sensors[0]["id"], "nvme/temp1");
+        assert_eq!(sensors[0]["chip"], "nvme");
+        assert_eq!(sensors[0]["label"], "Composite");
+        assert_eq!(sensors[0]["temperature"], 38.85);
+    }
+}
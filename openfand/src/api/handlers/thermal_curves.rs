@@ -21,7 +21,7 @@ pub(crate) struct InterpolateQuery {
 /// Validates a curve name.
 ///
 /// Valid names contain only alphanumeric characters, hyphens, and underscores.
-fn is_valid_curve_name(name: &str) -> bool {
+pub(crate) fn is_valid_curve_name(name: &str) -> bool {
     !name.is_empty()
         && name
             .chars()
@@ -135,10 +135,13 @@ pub(crate) async fn add_controller_curve(
         );
     }
 
-    // Validate points
+    // Validate points and tuning
     if let Err(e) = validate_points(&request.points) {
         return api_fail!(e);
     }
+    if let Err(e) = request.tuning.validate() {
+        return api_fail!(e);
+    }
 
     // Get controller data
     let controller_data = state.config.controller_data(&controller_id).await?;
@@ -155,7 +158,8 @@ pub(crate) async fn add_controller_curve(
         let curve = match &request.description {
             Some(desc) => ThermalCurve::with_description(curve_name, request.points.clone(), desc),
             None => ThermalCurve::new(curve_name, request.points.clone()),
-        };
+        }
+        .with_tuning(request.tuning);
 
         curves.insert(curve_name.to_string(), curve);
     }
@@ -251,6 +255,15 @@ pub(crate) async fn update_controller_curve(
             return Err(OpenFanError::CurveNotFound(name).into());
         }
 
+        // Tuning settings not provided keep their current value
+        let tuning = curves
+            .get(&name)
+            .map(|c| c.tuning.merged_with(&request.tuning))
+            .unwrap_or(request.tuning);
+        if let Err(e) = tuning.validate() {
+            return api_fail!(e);
+        }
+
         let curve = match &request.description {
             Some(desc) => ThermalCurve::with_description(&name, request.points.clone(), desc),
             None => {
@@ -260,7 +273,8 @@ pub(crate) async fn update_controller_curve(
                 curve.description = existing_desc;
                 curve
             }
-        };
+        }
+        .with_tuning(tuning);
 
         curves.insert(name.clone(), curve);
     }
@@ -304,6 +318,17 @@ pub(crate) async fn delete_controller_curve(
     // Get controller data
     let controller_data = state.config.controller_data(&controller_id).await?;
 
+    // Refuse to delete a curve that a binding still drives fans with
+    {
+        let bindings = controller_data.curve_bindings().await;
+        if let Some(binding) = bindings.bindings_for_curve(&name).next() {
+            return api_fail!(format!(
+                "Thermal curve '{}' is used by binding '{}'",
+                name, binding.name
+            ));
+        }
+    }
+
     // Remove curve
     {
         let mut curves = controller_data.thermal_curves_mut().await;
@@ -818,4 +843,103 @@ communication_timeout = 1
         assert!(data.get("temperature").is_some());
         assert!(data.get("pwm").is_some());
     }
+
+    async fn post_json(app: &TestApp, uri: &str, body: &str) -> StatusCode {
+        app.router()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+    }
+
+    async fn get_curve_json(app: &TestApp, name: &str) -> serde_json::Value {
+        let response = app
+            .router()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/api/v0/controller/default/curve/{}/get", name))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = body_string(response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        json["data"]["curve"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_add_curve_with_tuning() {
+        let app = TestApp::new().await;
+
+        let status = post_json(
+            &app,
+            "/api/v0/controller/default/curves/add",
+            r#"{"name": "tuned", "points": [{"temp_c": 30.0, "pwm": 25}, {"temp_c": 80.0, "pwm": 100}], "hysteresis_c": 2.0, "ramp_up_pwm_per_sec": 10.0, "smoothing_secs": 5.0}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let curve = get_curve_json(&app, "tuned").await;
+        assert_eq!(curve["hysteresis_c"], 2.0);
+        assert_eq!(curve["ramp_up_pwm_per_sec"], 10.0);
+        assert_eq!(curve["smoothing_secs"], 5.0);
+        assert!(curve.get("ramp_down_pwm_per_sec").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_add_curve_invalid_tuning() {
+        let app = TestApp::new().await;
+
+        let status = post_json(
+            &app,
+            "/api/v0/controller/default/curves/add",
+            r#"{"name": "bad-tuning", "points": [{"temp_c": 30.0, "pwm": 25}, {"temp_c": 80.0, "pwm": 100}], "hysteresis_c": -1.0}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_update_curve_merges_tuning() {
+        let app = TestApp::new().await;
+
+        let status = post_json(
+            &app,
+            "/api/v0/controller/default/curves/add",
+            r#"{"name": "merge", "points": [{"temp_c": 30.0, "pwm": 25}, {"temp_c": 80.0, "pwm": 100}], "hysteresis_c": 2.0, "smoothing_secs": 5.0}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let status = post_json(
+            &app,
+            "/api/v0/controller/default/curve/merge/update",
+            r#"{"points": [{"temp_c": 20.0, "pwm": 20}, {"temp_c": 90.0, "pwm": 100}], "hysteresis_c": 4.0, "ramp_down_pwm_per_sec": 1.5}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let curve = get_curve_json(&app, "merge").await;
+        assert_eq!(curve["hysteresis_c"], 4.0);
+        assert_eq!(curve["ramp_down_pwm_per_sec"], 1.5);
+        // Not provided in the update, so preserved
+        assert_eq!(curve["smoothing_secs"], 5.0);
+
+        let status = post_json(
+            &app,
+            "/api/v0/controller/default/curve/merge/update",
+            r#"{"points": [{"temp_c": 20.0, "pwm": 20}, {"temp_c": 90.0, "pwm": 100}], "smoothing_secs": 99999.0}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
 }
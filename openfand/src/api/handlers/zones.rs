@@ -2,13 +2,21 @@
 
 use crate::api::AppState;
 use crate::api::error::ApiError;
+use crate::config::{RuntimeConfig, check_zones};
+use crate::controllers::ControllerRegistry;
+use crate::limits::FanLimiter;
 use crate::{api_fail, api_ok};
 use axum::{
     Json,
     extract::{Path, Query, State},
 };
-use openfan_core::{ControlMode, OpenFanError, Zone, api};
+use openfan_core::api::ZoneFanOutcome;
+use openfan_core::{ControlMode, OpenFanError, Zone, ZoneData, ZoneFan, api};
+use openfan_hardware::is_disconnect_error;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::task::JoinSet;
 use tracing::{debug, info, warn};
 
 /// Query parameters for zone apply operation.
@@ -20,6 +28,15 @@ pub(crate) struct ApplyZoneQuery {
     pub value: u16,
 }
 
+/// Check zone fans against the boards of the registered controllers, as at startup.
+async fn check_fans(state: &AppState, name: &str, fans: &[ZoneFan]) -> Result<(), ApiError> {
+    let zone = ZoneData {
+        zones: HashMap::from([(name.to_string(), Zone::new(name, fans.to_vec()))]),
+    };
+    check_zones(&zone, &state.registry.boards().await)?;
+    Ok(())
+}
+
 /// Validates a zone name.
 ///
 /// Valid names contain only alphanumeric characters, hyphens, and underscores.
@@ -109,16 +126,8 @@ pub(crate) async fn add_zone(
         );
     }
 
-    // Validate fan IDs against board configuration
-    // TODO: In multi-controller mode, validate against the controller's board info
-    for fan in &request.fans {
-        if let Err(e) = state.board_info.validate_fan_id(fan.fan_id) {
-            return api_fail!(format!(
-                "Invalid fan ID {} for controller '{}': {}",
-                fan.fan_id, fan.controller, e
-            ));
-        }
-    }
+    // Validate fans against the boards of their controllers
+    check_fans(&state, zone_name, &request.fans).await?;
 
     // Check for duplicate fans in request
     let mut seen = std::collections::HashSet::new();
@@ -202,16 +211,8 @@ pub(crate) async fn update_zone(
 ) -> Result<Json<api::ApiResponse<()>>, ApiError> {
     debug!("Request: POST /api/v0/zone/{}/update", name);
 
-    // Validate fan IDs against board configuration
-    // TODO: In multi-controller mode, validate against the controller's board info
-    for fan in &request.fans {
-        if let Err(e) = state.board_info.validate_fan_id(fan.fan_id) {
-            return api_fail!(format!(
-                "Invalid fan ID {} for controller '{}': {}",
-                fan.fan_id, fan.controller, e
-            ));
-        }
-    }
+    // Validate fans against the boards of their controllers
+    check_fans(&state, &name, &request.fans).await?;
 
     // Check for duplicate fans in request
     let mut seen = std::collections::HashSet::new();
@@ -302,6 +303,10 @@ pub(crate) async fn delete_zone(
 
 /// Applies a PWM or RPM value to all fans in a zone.
 ///
+/// Fans are grouped by controller and each controller is driven concurrently
+/// through its own connection manager. The response reports the outcome of
+/// every fan, so a partially applied zone is visible to the caller.
+///
 /// # Endpoint
 ///
 /// `GET /api/v0/zone/{name}/apply?mode=pwm&value=75`
@@ -314,7 +319,7 @@ pub(crate) async fn apply_zone(
     State(state): State<AppState>,
     Path(name): Path<String>,
     Query(params): Query<ApplyZoneQuery>,
-) -> Result<Json<api::ApiResponse<()>>, ApiError> {
+) -> Result<Json<api::ApiResponse<api::ZoneApplyResponse>>, ApiError> {
     debug!(
         "Request: GET /api/v0/zone/{}/apply?mode={}&value={}",
         name, params.mode, params.value
@@ -367,51 +372,181 @@ pub(crate) async fn apply_zone(
         return api_fail!(format!("Zone '{}' has no fans assigned!", name));
     }
 
-    // Check if hardware is available
-    let Some(cm) = &state.connection_manager else {
-        debug!("Hardware not available - simulating zone application for testing");
-        info!(
-            "Applied {} {} to zone '{}' (mock mode)",
-            params.value,
-            params.mode.to_uppercase(),
-            name
-        );
-        return api_ok!(());
-    };
+    // Group fans by controller, keeping zone order within each group
+    let mut groups: Vec<(String, Vec<u8>)> = Vec::new();
+    for fan in &zone.fans {
+        match groups.iter_mut().find(|(id, _)| *id == fan.controller) {
+            Some((_, fan_ids)) => fan_ids.push(fan.fan_id),
+            None => groups.push((fan.controller.clone(), vec![fan.fan_id])),
+        }
+    }
+
+    let mut tasks = JoinSet::new();
+    for (controller_id, fan_ids) in groups {
+        tasks.spawn(apply_to_controller(
+            state.registry.clone(),
+            state.config.clone(),
+            controller_id,
+            fan_ids,
+            mode,
+            params.value as u32,
+        ));
+    }
 
-    let value = params.value as u32;
-    // For now, in single-controller mode, we just extract the fan_ids
-    // TODO: In multi-controller mode, group fans by controller and apply to each
-    let fan_ids: Vec<u8> = zone.fans.iter().map(|f| f.fan_id).collect();
-    let zone_name = name.clone();
-
-    // Apply value to each fan in the zone via connection manager
-    cm.with_controller(async |controller| {
-        for &fan_id in &fan_ids {
-            let result = match mode {
-                ControlMode::Pwm => controller.set_fan_pwm(fan_id, value).await,
-                ControlMode::Rpm => controller.set_fan_rpm(fan_id, value).await,
-            };
-
-            if let Err(e) = result {
-                warn!(
-                    "Failed to set fan {} in zone '{}': {}",
-                    fan_id, zone_name, e
-                );
+    let mut results = Vec::with_capacity(zone.fans.len());
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(controller_results) => results.extend(controller_results),
+            Err(e) => {
+                return Err(ApiError::internal_error(format!(
+                    "Zone apply task failed: {}",
+                    e
+                )));
             }
         }
-        Ok(())
-    })
-    .await?;
+    }
+
+    // Report fans in zone order
+    results.sort_by_key(|r| {
+        zone.fans
+            .iter()
+            .position(|f| f.controller == r.controller && f.fan_id == r.fan_id)
+    });
+
+    for result in &results {
+        if let Some(error) = &result.error {
+            warn!(
+                "Failed to set fan {} of controller '{}' in zone '{}': {}",
+                result.fan_id, result.controller, name, error
+            );
+        }
+    }
+
+    let response = api::ZoneApplyResponse {
+        zone: name,
+        mode,
+        value: params.value,
+        results,
+    };
 
     info!(
-        "Applied {} {} to {} fans in zone '{}'",
+        "Applied {} {} to {}/{} fans in zone '{}'",
         params.value,
         params.mode.to_uppercase(),
-        zone.fans.len(),
-        name
+        response.applied_count(),
+        response.results.len(),
+        response.zone
     );
-    api_ok!(())
+    api_ok!(response)
+}
+
+/// Applies a value to the fans of one controller and reports each fan.
+///
+/// Each fan gets the value moved into its safety limits; fans whose limits
+/// reject it are reported as failed. A disconnect ends the batch: the
+/// remaining fans are reported as skipped and the connection manager takes
+/// care of reconnecting.
+async fn apply_to_controller(
+    registry: Arc<ControllerRegistry>,
+    config: Arc<RuntimeConfig>,
+    controller_id: String,
+    fan_ids: Vec<u8>,
+    mode: ControlMode,
+    value: u32,
+) -> Vec<api::ZoneFanResult> {
+    let fan_result = |fan_id, outcome, error| api::ZoneFanResult {
+        controller: controller_id.clone(),
+        fan_id,
+        outcome,
+        error,
+    };
+
+    let Some(entry) = registry.get(&controller_id).await else {
+        let error = OpenFanError::ControllerNotFound(controller_id.clone()).to_string();
+        return fan_ids
+            .into_iter()
+            .map(|fan_id| fan_result(fan_id, ZoneFanOutcome::Failure, Some(error.clone())))
+            .collect();
+    };
+
+    let limiter = match FanLimiter::load(&config, &entry).await {
+        Ok(limiter) => limiter,
+        Err(e) => {
+            let error = e.to_string();
+            return fan_ids
+                .into_iter()
+                .map(|fan_id| fan_result(fan_id, ZoneFanOutcome::Failure, Some(error.clone())))
+                .collect();
+        }
+    };
+
+    // Fans outside this controller's board or whose limits reject the value
+    // are reported, not sent
+    let mut results = Vec::with_capacity(fan_ids.len());
+    let mut valid = Vec::with_capacity(fan_ids.len());
+    for fan_id in fan_ids {
+        let value = entry
+            .board_info()
+            .validate_fan_id(fan_id)
+            .and_then(|()| limiter.value(fan_id, mode, value));
+        match value {
+            Ok(value) => valid.push((fan_id, value)),
+            Err(e) => results.push(fan_result(
+                fan_id,
+                ZoneFanOutcome::Failure,
+                Some(e.to_string()),
+            )),
+        }
+    }
+
+    let Some(cm) = entry.connection_manager() else {
+        let error = OpenFanError::DeviceNotFound.to_string();
+        results.extend(
+            valid.into_iter().map(|(fan_id, _)| {
+                fan_result(fan_id, ZoneFanOutcome::Failure, Some(error.clone()))
+            }),
+        );
+        return results;
+    };
+
+    let mut attempted = Vec::with_capacity(valid.len());
+    let outcome = cm
+        .with_controller(async |controller| {
+            for &(fan_id, value) in &valid {
+                let result = match mode {
+                    ControlMode::Pwm => controller.set_fan_pwm(fan_id, value).await,
+                    ControlMode::Rpm => controller.set_fan_rpm(fan_id, value).await,
+                };
+                match result {
+                    Ok(_) => attempted.push(fan_result(fan_id, ZoneFanOutcome::Success, None)),
+                    Err(e) if is_disconnect_error(&e) => return Err(e),
+                    Err(e) => attempted.push(fan_result(
+                        fan_id,
+                        ZoneFanOutcome::Failure,
+                        Some(e.to_string()),
+                    )),
+                }
+            }
+            Ok(())
+        })
+        .await;
+
+    let remaining = &valid[attempted.len()..];
+    results.extend(attempted);
+    if let Err(e) = outcome {
+        let outcome = match e {
+            OpenFanError::DeviceDisconnected(_) | OpenFanError::Reconnecting => {
+                ZoneFanOutcome::SkippedDisconnected
+            }
+            _ => ZoneFanOutcome::Failure,
+        };
+        results.extend(
+            remaining
+                .iter()
+                .map(|&(fan_id, _)| fan_result(fan_id, outcome, Some(e.to_string()))),
+        );
+    }
+    results
 }
 
 #[cfg(test)]
@@ -461,8 +596,8 @@ mod integration_tests {
     use tempfile::TempDir;
     use tower::ServiceExt;
 
+    use crate::api::test_utils::{connect_simulated, load_config};
     use crate::api::{AppState, create_router};
-    use crate::config::RuntimeConfig;
 
     struct TestApp {
         router: Router,
@@ -473,28 +608,18 @@ mod integration_tests {
         async fn new() -> Self {
             let board_info = BoardType::OpenFanStandard.to_board_info();
             let config_dir = tempfile::tempdir().unwrap();
-
-            let data_dir = config_dir.path().join("data");
-            std::fs::create_dir_all(&data_dir).unwrap();
-
-            let data_dir_str = data_dir.to_string_lossy().replace('\\', "\\\\");
-            let config_content = format!(
-                r#"data_dir = "{}"
-
+            let config = load_config(
+                &config_dir,
+                r#"
 [server]
 bind_address = "127.0.0.1"
 port = 3000
 communication_timeout = 1
 "#,
-                data_dir_str
-            );
-
-            let config_path = config_dir.path().join("config.toml");
-            std::fs::write(&config_path, config_content).unwrap();
-
-            let config = RuntimeConfig::load(&config_path).await.unwrap();
-            let state =
-                AppState::single_controller(board_info, std::sync::Arc::new(config), None).await;
+            )
+            .await;
+            let (cm, _) = connect_simulated("default", &board_info).await;
+            let state = AppState::single_controller(board_info, config, Some(cm)).await;
 
             TestApp {
                 router: create_router(state),
@@ -838,7 +963,7 @@ communication_timeout = 1
     }
 
     #[tokio::test]
-    async fn test_add_then_apply_zone_mock_mode() {
+    async fn test_add_then_apply_zone() {
         let app = TestApp::new().await;
 
         // Add zone
@@ -858,7 +983,7 @@ communication_timeout = 1
             .unwrap();
         assert_eq!(add_response.status(), StatusCode::OK);
 
-        // Apply zone (mock mode)
+        // Apply zone
         let apply_response = app
             .router()
             .oneshot(
@@ -870,6 +995,12 @@ communication_timeout = 1
             .await
             .unwrap();
         assert_eq!(apply_response.status(), StatusCode::OK);
+
+        let body = body_string(apply_response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let results = json["data"]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result["outcome"] == "success"));
     }
 }
 
@@ -882,71 +1013,70 @@ mod multi_controller_tests {
         http::{Method, Request, StatusCode},
     };
     use http_body_util::BodyExt;
-    use openfan_core::BoardType;
+    use openfan_core::{BoardType, Zone, ZoneFan};
     use std::sync::Arc;
     use tempfile::TempDir;
     use tower::ServiceExt;
 
+    use crate::api::test_utils::{connect_simulated, load_config};
     use crate::api::{AppState, create_router};
     use crate::config::RuntimeConfig;
     use crate::controllers::{ControllerEntry, ControllerRegistry};
+    use crate::reload::ControllerSetup;
 
     struct MultiControllerTestApp {
         router: Router,
+        config: Arc<RuntimeConfig>,
         _config_dir: TempDir,
     }
 
     impl MultiControllerTestApp {
-        /// Create a test app with multiple mock controllers
+        /// Create a test app with multiple simulated controllers
         async fn new() -> Self {
             let config_dir = tempfile::tempdir().unwrap();
-
-            let data_dir = config_dir.path().join("data");
-            std::fs::create_dir_all(&data_dir).unwrap();
-
-            let data_dir_str = data_dir.to_string_lossy().replace('\\', "\\\\");
-            let config_content = format!(
-                r#"data_dir = "{}"
-
+            let config = load_config(
+                &config_dir,
+                r#"
 [server]
 bind_address = "127.0.0.1"
 port = 3000
 communication_timeout = 1
 "#,
-                data_dir_str
-            );
-
-            let config_path = config_dir.path().join("config.toml");
-            std::fs::write(&config_path, config_content).unwrap();
-
-            let config = RuntimeConfig::load(&config_path).await.unwrap();
+            )
+            .await;
 
             // Create registry with multiple controllers
             let registry = ControllerRegistry::new();
 
             // Main controller: standard board (10 fans)
             let main_board = BoardType::OpenFanStandard.to_board_info();
+            let (main_cm, _) = connect_simulated("main", &main_board).await;
             let main_entry = ControllerEntry::builder("main", main_board.clone())
                 .maybe_description(Some("Main chassis".to_string()))
+                .maybe_connection_manager(Some(main_cm))
                 .build();
             registry.register(main_entry).await.unwrap();
 
             // GPU controller: custom board (4 fans)
             let gpu_board = BoardType::Custom { fan_count: 4 }.to_board_info();
+            let (gpu_cm, _) = connect_simulated("gpu", &gpu_board).await;
             let gpu_entry = ControllerEntry::builder("gpu", gpu_board)
                 .maybe_description(Some("GPU cooling".to_string()))
+                .maybe_connection_manager(Some(gpu_cm))
                 .build();
             registry.register(gpu_entry).await.unwrap();
 
             let state = AppState::new(
                 Arc::new(registry),
-                Arc::new(config),
+                config.clone(),
                 main_board, // default controller is "main"
-                None,       // mock mode
+                None,
+                ControllerSetup::default(),
             );
 
             MultiControllerTestApp {
                 router: create_router(state),
+                config,
                 _config_dir: config_dir,
             }
         }
@@ -1187,7 +1317,7 @@ communication_timeout = 1
             .unwrap();
         assert_eq!(add_response.status(), StatusCode::OK);
 
-        // Apply PWM to the cross-controller zone (mock mode)
+        // Apply PWM to the cross-controller zone
         let apply_response = app
             .router()
             .oneshot(
@@ -1200,6 +1330,69 @@ communication_timeout = 1
             .unwrap();
 
         assert_eq!(apply_response.status(), StatusCode::OK);
+
+        // Each fan is reported against its own controller, in zone order
+        let body = body_string(apply_response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let data = json.get("data").unwrap();
+        assert_eq!(data["mode"], "pwm");
+        assert_eq!(data["value"], 60);
+        let results = data["results"].as_array().unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0]["controller"], "main");
+        assert_eq!(results[0]["fan_id"], 3);
+        assert_eq!(results[0]["outcome"], "success");
+        assert_eq!(results[1]["controller"], "gpu");
+        assert_eq!(results[1]["fan_id"], 3);
+        assert_eq!(results[1]["outcome"], "success");
+    }
+
+    #[tokio::test]
+    async fn test_zone_apply_reports_failed_fans() {
+        let app = MultiControllerTestApp::new().await;
+
+        // Fan 5 does not exist on the 4-fan GPU board and "ghost" is not
+        // registered, which the API refuses: set the zone behind its back
+        app.config.zones_mut().await.insert(
+            "partial".to_string(),
+            Zone::new(
+                "partial",
+                vec![
+                    ZoneFan::new("gpu", 5),
+                    ZoneFan::new("main", 4),
+                    ZoneFan::new("ghost", 0),
+                ],
+            ),
+        );
+
+        let apply_response = app
+            .router()
+            .oneshot(
+                Request::builder()
+                    .uri("/api/v0/zone/partial/apply?mode=rpm&value=1200")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(apply_response.status(), StatusCode::OK);
+
+        let body = body_string(apply_response.into_body()).await;
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        let results = json["data"]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+
+        assert_eq!(results[0]["controller"], "gpu");
+        assert_eq!(results[0]["outcome"], "failure");
+        assert!(results[0]["error"].as_str().unwrap().contains('5'));
+
+        assert_eq!(results[1]["controller"], "main");
+        assert_eq!(results[1]["outcome"], "success");
+        assert!(results[1].get("error").is_none());
+
+        assert_eq!(results[2]["controller"], "ghost");
+        assert_eq!(results[2]["outcome"], "failure");
+        assert!(results[2]["error"].as_str().unwrap().contains("ghost"));
     }
 
     #[tokio::test]
@@ -1262,7 +1455,7 @@ communication_timeout = 1
     async fn test_controller_scoped_fan_status() {
         let app = MultiControllerTestApp::new().await;
 
-        // Get fan status for main controller (mock mode returns simulated data)
+        // Get fan status for main controller
         let response = app
             .router()
             .oneshot(
@@ -1280,13 +1473,13 @@ communication_timeout = 1
         let json: serde_json::Value = serde_json::from_str(&body).unwrap();
         let data = json.get("data").unwrap();
 
-        // Mock mode returns rpms and pwms for all fans on the controller
+        // The board reports the RPM of every fan, PWMs only once they are set
         let rpms = data.get("rpms").unwrap().as_object().unwrap();
         let pwms = data.get("pwms").unwrap().as_object().unwrap();
 
         // Main controller has 10 fans (standard board)
         assert_eq!(rpms.len(), 10);
-        assert_eq!(pwms.len(), 10);
+        assert!(pwms.is_empty());
     }
 
     #[tokio::test]
@@ -1311,13 +1504,13 @@ communication_timeout = 1
         let json: serde_json::Value = serde_json::from_str(&body).unwrap();
         let data = json.get("data").unwrap();
 
-        // Mock mode returns rpms and pwms for all fans on the controller
+        // The board reports the RPM of every fan, PWMs only once they are set
         let rpms = data.get("rpms").unwrap().as_object().unwrap();
         let pwms = data.get("pwms").unwrap().as_object().unwrap();
 
         // GPU controller has 4 fans (custom:4 board)
         assert_eq!(rpms.len(), 4);
-        assert_eq!(pwms.len(), 4);
+        assert!(pwms.is_empty());
     }
 
     #[tokio::test]
@@ -1391,4 +1584,73 @@ communication_timeout = 1
             "Updated to cross-controller"
         );
     }
+
+    #[tokio::test]
+    async fn test_zone_fans_checked_against_their_controller() {
+        let app = MultiControllerTestApp::new().await;
+
+        let send = async |uri: &str, body: &'static str| {
+            let response = app
+                .router()
+                .oneshot(
+                    Request::builder()
+                        .method(Method::POST)
+                        .uri(uri)
+                        .header("content-type", "application/json")
+                        .body(Body::from(body))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            (response.status(), body_string(response.into_body()).await)
+        };
+
+        // Fan 5 exists on the 10-fan main board but not on the 4-fan GPU board
+        let (status, body) = send(
+            "/api/v0/zones/add",
+            r#"{"name": "gpu-zone", "fans": [{"controller": "gpu", "fan_id": 5}]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            body.contains("out of range for controller 'gpu'"),
+            "{}",
+            body
+        );
+
+        let (status, body) = send(
+            "/api/v0/zones/add",
+            r#"{"name": "lost-zone", "fans": [{"controller": "nowhere", "fan_id": 0}]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("unknown controller 'nowhere'"), "{}", body);
+
+        let (status, _) = send(
+            "/api/v0/zones/add",
+            r#"{"name": "main-zone", "fans": [{"controller": "main", "fan_id": 5}]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = send(
+            "/api/v0/zone/main-zone/update",
+            r#"{"fans": [{"controller": "gpu", "fan_id": 5}]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            body.contains("out of range for controller 'gpu'"),
+            "{}",
+            body
+        );
+
+        let (status, body) = send(
+            "/api/v0/zone/main-zone/update",
+            r#"{"fans": [{"controller": "nowhere", "fan_id": 0}]}"#,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("unknown controller 'nowhere'"), "{}", body);
+    }
 }
@@ -2,21 +2,35 @@
 //!
 //! Contains the REST API implementation with Axum router and handlers.
 
+pub(crate) mod auth;
 pub(crate) mod handlers;
+#[cfg(test)]
+pub(crate) mod test_utils;
 
+use crate::alarms::AlarmMonitor;
+use crate::calibration::Calibrator;
 use crate::config::RuntimeConfig;
+use crate::control::ControlEngine;
 use crate::controllers::{ConnectionManager, ControllerRegistry};
+use crate::history::History;
+use crate::reload::{ControllerSetup, Reloader, Services};
+use crate::sensors::SensorManager;
+use crate::telemetry::Telemetry;
 use axum::{
     Router,
     extract::DefaultBodyLimit,
-    http::{HeaderValue, Method},
+    http::{HeaderValue, Method, header},
+    middleware,
     routing::{get, post},
 };
 use openfan_core::BoardInfo;
 use std::sync::Arc;
 use std::time::Instant;
 use tower::ServiceBuilder;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use tower_http::{
+    cors::{AllowOrigin, CorsLayer},
+    trace::TraceLayer,
+};
 use tracing::info;
 
 /// Application state shared across all handlers
@@ -28,6 +42,20 @@ pub(crate) struct AppState {
     pub config: Arc<RuntimeConfig>,
     /// Server start time for uptime calculation
     pub start_time: Instant,
+    /// Host temperature sensors
+    pub sensors: Arc<SensorManager>,
+    /// Closed-loop thermal control engine driving curve bindings
+    pub control: Arc<ControlEngine>,
+    /// Fan stall and failure monitoring
+    pub alarms: Arc<AlarmMonitor>,
+    /// Background fan sampling and live event stream
+    pub telemetry: Arc<Telemetry>,
+    /// Recorded fan history
+    pub history: Arc<History>,
+    /// Configuration reload
+    pub reload: Arc<Reloader>,
+    /// Fan calibration jobs
+    pub calibration: Arc<Calibrator>,
 
     /// Board info for the default controller (used by system info and zone handlers)
     pub board_info: Arc<BoardInfo>,
@@ -48,16 +76,66 @@ impl AppState {
     ///   shutdown handler, which needs access to shutdown settings
     /// * `default_board_info` - Board info for the default/first controller
     /// * `default_connection_manager` - Connection manager for the default/first controller
+    /// * `setup` - How the controllers were set up, for reloads to follow `[[controllers]]`
     pub fn new(
         registry: Arc<ControllerRegistry>,
         config: Arc<RuntimeConfig>,
         default_board_info: BoardInfo,
         default_connection_manager: Option<Arc<ConnectionManager>>,
+        setup: ControllerSetup,
     ) -> Self {
+        let sensors = Arc::new(SensorManager::new(&config.static_config().sensors));
+        let control = Arc::new(ControlEngine::new(
+            registry.clone(),
+            config.clone(),
+            sensors.clone(),
+        ));
+        let calibration = Arc::new(Calibrator::new(
+            registry.clone(),
+            config.clone(),
+            control.clone(),
+        ));
+        let telemetry = Arc::new(Telemetry::new(
+            registry.clone(),
+            config.clone(),
+            sensors.clone(),
+        ));
+        let alarms = Arc::new(AlarmMonitor::new(
+            registry.clone(),
+            config.clone(),
+            telemetry.clone(),
+            control.clone(),
+            calibration.clone(),
+        ));
+        let history = Arc::new(History::new(
+            registry.clone(),
+            config.clone(),
+            telemetry.clone(),
+        ));
+        let reload = Arc::new(Reloader::new(
+            registry.clone(),
+            config.clone(),
+            sensors.clone(),
+            Services {
+                control: control.clone(),
+                alarms: alarms.clone(),
+                telemetry: telemetry.clone(),
+                history: history.clone(),
+            },
+            setup,
+        ));
+
         Self {
             registry,
             config,
             start_time: Instant::now(),
+            sensors,
+            control,
+            alarms,
+            telemetry,
+            history,
+            reload,
+            calibration,
             board_info: Arc::new(default_board_info),
             connection_manager: default_connection_manager,
         }
@@ -84,10 +162,59 @@ impl AppState {
             .await
             .expect("Failed to register default controller");
 
+        let registry = Arc::new(registry);
+        let sensors = Arc::new(SensorManager::new(&config.static_config().sensors));
+        let control = Arc::new(ControlEngine::new(
+            registry.clone(),
+            config.clone(),
+            sensors.clone(),
+        ));
+        let calibration = Arc::new(Calibrator::new(
+            registry.clone(),
+            config.clone(),
+            control.clone(),
+        ));
+        let telemetry = Arc::new(Telemetry::new(
+            registry.clone(),
+            config.clone(),
+            sensors.clone(),
+        ));
+        let alarms = Arc::new(AlarmMonitor::new(
+            registry.clone(),
+            config.clone(),
+            telemetry.clone(),
+            control.clone(),
+            calibration.clone(),
+        ));
+        let history = Arc::new(History::new(
+            registry.clone(),
+            config.clone(),
+            telemetry.clone(),
+        ));
+        let reload = Arc::new(Reloader::new(
+            registry.clone(),
+            config.clone(),
+            sensors.clone(),
+            Services {
+                control: control.clone(),
+                alarms: alarms.clone(),
+                telemetry: telemetry.clone(),
+                history: history.clone(),
+            },
+            ControllerSetup::default(),
+        ));
+
         Self {
-            registry: Arc::new(registry),
+            registry,
             config,
             start_time: Instant::now(),
+            sensors,
+            control,
+            alarms,
+            telemetry,
+            history,
+            reload,
+            calibration,
             board_info: Arc::new(board_info),
             connection_manager,
         }
@@ -95,13 +222,26 @@ impl AppState {
 }
 
 /// Create the main API router with all endpoints
+///
+/// All endpoints but `/` require an API token once `[[auth.tokens]]` are
+/// configured; those wrapped in [`auth::control`] require the control scope.
+/// Browsers may only call the API from the `[server] allowed_origins`.
 pub(crate) fn create_router(state: AppState) -> Router {
     info!("Setting up API router...");
 
+    // Validated when the config is loaded
+    let origins: Vec<HeaderValue> = state
+        .config
+        .static_config()
+        .server
+        .allowed_origins
+        .iter()
+        .filter_map(|origin| origin.parse().ok())
+        .collect();
     let cors = CorsLayer::new()
-        .allow_origin("*".parse::<HeaderValue>().unwrap())
+        .allow_origin(AllowOrigin::list(origins))
         .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-        .allow_headers(tower_http::cors::Any);
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE]);
 
     let middleware_stack = ServiceBuilder::new()
         .layer(TraceLayer::new_for_http())
@@ -113,22 +253,41 @@ pub(crate) fn create_router(state: AppState) -> Router {
         // System-wide endpoints
         // =========================================================================
         .route("/api/v0/info", get(handlers::info::get_info))
-        .route("/", get(handlers::info::root))
+        .route("/api/v0/sensors", get(handlers::sensors::list_sensors))
+        .route("/api/v0/alarms", get(handlers::alarms::list_alarms))
+        .route("/api/v0/events", get(handlers::events::stream_events))
+        .route("/metrics", get(handlers::metrics::get_metrics))
+        .route(
+            "/api/v0/config/reload",
+            get(handlers::reload::get_reload_status)
+                .merge(auth::control(post(handlers::reload::reload_config))),
+        )
         //
         // =========================================================================
         // Controller management endpoints
         // =========================================================================
         .route(
             "/api/v0/controllers",
-            get(handlers::controllers::list_controllers),
+            get(handlers::controllers::list_controllers)
+                .merge(auth::control(post(handlers::controllers::add_controller))),
+        )
+        .route(
+            "/api/v0/controllers/discover",
+            get(handlers::controllers::discover_controllers),
         )
         .route(
             "/api/v0/controller/{id}/info",
             get(handlers::controllers::get_controller_info),
         )
+        .route(
+            "/api/v0/controller/{id}",
+            auth::control(axum::routing::delete(
+                handlers::controllers::remove_controller,
+            )),
+        )
         .route(
             "/api/v0/controller/{id}/reconnect",
-            post(handlers::controllers::reconnect_controller),
+            auth::control(post(handlers::controllers::reconnect_controller)),
         )
         //
         // =========================================================================
@@ -138,22 +297,58 @@ pub(crate) fn create_router(state: AppState) -> Router {
             "/api/v0/controller/{id}/fan/status",
             get(handlers::fans::get_controller_fan_status),
         )
+        .route(
+            "/api/v0/controller/{id}/history",
+            get(handlers::history::get_controller_history),
+        )
         .route(
             "/api/v0/controller/{id}/fan/all/set",
-            get(handlers::fans::set_controller_all_fans),
+            auth::control(get(handlers::fans::set_controller_all_fans)),
         )
         .route(
             "/api/v0/controller/{id}/fan/{fan}/pwm",
-            get(handlers::fans::set_controller_fan_pwm),
+            auth::control(get(handlers::fans::set_controller_fan_pwm)),
         )
         .route(
             "/api/v0/controller/{id}/fan/{fan}/rpm",
-            get(handlers::fans::set_controller_fan_rpm),
+            auth::control(get(handlers::fans::set_controller_fan_rpm)),
         )
         .route(
             "/api/v0/controller/{id}/fan/{fan}/rpm/get",
             get(handlers::fans::get_controller_fan_rpm),
         )
+        .route(
+            "/api/v0/controller/{id}/fan/calibration",
+            get(handlers::calibration::get_calibration_status),
+        )
+        .route(
+            "/api/v0/controller/{id}/fan/calibration",
+            auth::control(post(handlers::calibration::start_calibration)),
+        )
+        .route(
+            "/api/v0/controller/{id}/fan/calibration",
+            auth::control(axum::routing::delete(
+                handlers::calibration::cancel_calibration,
+            )),
+        )
+        .route(
+            "/api/v0/controller/{id}/fan/calibration/results",
+            get(handlers::calibration::get_calibration_results),
+        )
+        .route(
+            "/api/v0/controller/{id}/fan/limits",
+            get(handlers::fan_limits::get_fan_limits),
+        )
+        .route(
+            "/api/v0/controller/{id}/fan/{fan}/limits",
+            auth::control(post(handlers::fan_limits::set_fan_limits)),
+        )
+        .route(
+            "/api/v0/controller/{id}/fan/{fan}/limits",
+            auth::control(axum::routing::delete(
+                handlers::fan_limits::delete_fan_limits,
+            )),
+        )
         //
         // =========================================================================
         // Controller-scoped profile endpoints
@@ -164,15 +359,15 @@ pub(crate) fn create_router(state: AppState) -> Router {
         )
         .route(
             "/api/v0/controller/{id}/profiles/add",
-            post(handlers::profiles::add_controller_profile),
+            auth::control(post(handlers::profiles::add_controller_profile)),
         )
         .route(
             "/api/v0/controller/{id}/profiles/remove",
-            get(handlers::profiles::remove_controller_profile),
+            auth::control(get(handlers::profiles::remove_controller_profile)),
         )
         .route(
             "/api/v0/controller/{id}/profiles/set",
-            get(handlers::profiles::set_controller_profile),
+            auth::control(get(handlers::profiles::set_controller_profile)),
         )
         //
         // =========================================================================
@@ -188,11 +383,13 @@ pub(crate) fn create_router(state: AppState) -> Router {
         )
         .route(
             "/api/v0/controller/{id}/alias/{fan}/set",
-            get(handlers::aliases::set_controller_alias),
+            auth::control(get(handlers::aliases::set_controller_alias)),
         )
         .route(
             "/api/v0/controller/{id}/alias/{fan}",
-            axum::routing::delete(handlers::aliases::delete_controller_alias),
+            auth::control(axum::routing::delete(
+                handlers::aliases::delete_controller_alias,
+            )),
         )
         //
         // =========================================================================
@@ -204,7 +401,7 @@ pub(crate) fn create_router(state: AppState) -> Router {
         )
         .route(
             "/api/v0/controller/{id}/curves/add",
-            post(handlers::thermal_curves::add_controller_curve),
+            auth::control(post(handlers::thermal_curves::add_controller_curve)),
         )
         .route(
             "/api/v0/controller/{id}/curve/{name}/get",
@@ -212,11 +409,13 @@ pub(crate) fn create_router(state: AppState) -> Router {
         )
         .route(
             "/api/v0/controller/{id}/curve/{name}/update",
-            post(handlers::thermal_curves::update_controller_curve),
+            auth::control(post(handlers::thermal_curves::update_controller_curve)),
         )
         .route(
             "/api/v0/controller/{id}/curve/{name}",
-            axum::routing::delete(handlers::thermal_curves::delete_controller_curve),
+            auth::control(axum::routing::delete(
+                handlers::thermal_curves::delete_controller_curve,
+            )),
         )
         .route(
             "/api/v0/controller/{id}/curve/{name}/interpolate",
@@ -224,43 +423,108 @@ pub(crate) fn create_router(state: AppState) -> Router {
         )
         //
         // =========================================================================
+        // Controller-scoped PID controller endpoints
+        // =========================================================================
+        .route(
+            "/api/v0/controller/{id}/pids/list",
+            get(handlers::pid_controllers::list_controller_pids),
+        )
+        .route(
+            "/api/v0/controller/{id}/pids/add",
+            auth::control(post(handlers::pid_controllers::add_controller_pid)),
+        )
+        .route(
+            "/api/v0/controller/{id}/pid/{name}/get",
+            get(handlers::pid_controllers::get_controller_pid),
+        )
+        .route(
+            "/api/v0/controller/{id}/pid/{name}/update",
+            auth::control(post(handlers::pid_controllers::update_controller_pid)),
+        )
+        .route(
+            "/api/v0/controller/{id}/pid/{name}",
+            auth::control(axum::routing::delete(
+                handlers::pid_controllers::delete_controller_pid,
+            )),
+        )
+        //
+        // =========================================================================
+        // Controller-scoped curve binding endpoints
+        // =========================================================================
+        .route(
+            "/api/v0/controller/{id}/bindings/list",
+            get(handlers::curve_bindings::list_controller_bindings),
+        )
+        .route(
+            "/api/v0/controller/{id}/bindings/add",
+            auth::control(post(handlers::curve_bindings::add_controller_binding)),
+        )
+        .route(
+            "/api/v0/controller/{id}/binding/{name}/get",
+            get(handlers::curve_bindings::get_controller_binding),
+        )
+        .route(
+            "/api/v0/controller/{id}/binding/{name}",
+            auth::control(axum::routing::delete(
+                handlers::curve_bindings::delete_controller_binding,
+            )),
+        )
+        //
+        // =========================================================================
         // Controller-scoped CFM endpoints
         // =========================================================================
         .route(
             "/api/v0/controller/{id}/cfm/list",
             get(handlers::cfm::list_controller_cfm),
         )
+        .route(
+            "/api/v0/controller/{id}/cfm/airflow",
+            get(handlers::cfm::get_controller_airflow),
+        )
+        .route("/api/v0/cfm/zones", get(handlers::cfm::get_zones_airflow))
         .route(
             "/api/v0/controller/{id}/cfm/{port}",
             get(handlers::cfm::get_controller_cfm),
         )
         .route(
             "/api/v0/controller/{id}/cfm/{port}",
-            post(handlers::cfm::set_controller_cfm),
+            auth::control(post(handlers::cfm::set_controller_cfm)),
         )
         .route(
             "/api/v0/controller/{id}/cfm/{port}",
-            axum::routing::delete(handlers::cfm::delete_controller_cfm),
+            auth::control(axum::routing::delete(handlers::cfm::delete_controller_cfm)),
         )
         //
         // =========================================================================
         // Global zone endpoints (zones span across controllers)
         // =========================================================================
         .route("/api/v0/zones/list", get(handlers::zones::list_zones))
-        .route("/api/v0/zones/add", post(handlers::zones::add_zone))
+        .route(
+            "/api/v0/zones/add",
+            auth::control(post(handlers::zones::add_zone)),
+        )
         .route("/api/v0/zone/{name}/get", get(handlers::zones::get_zone))
         .route(
             "/api/v0/zone/{name}/update",
-            post(handlers::zones::update_zone),
+            auth::control(post(handlers::zones::update_zone)),
         )
         .route(
             "/api/v0/zone/{name}/delete",
-            get(handlers::zones::delete_zone),
+            auth::control(get(handlers::zones::delete_zone)),
         )
         .route(
             "/api/v0/zone/{name}/apply",
-            get(handlers::zones::apply_zone),
+            auth::control(get(handlers::zones::apply_zone)),
         )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::authenticate,
+        ))
+        //
+        // =========================================================================
+        // Public endpoints (health checks)
+        // =========================================================================
+        .route("/", get(handlers::info::root))
         .layer(middleware_stack)
         .with_state(state)
 }
@@ -330,12 +594,22 @@ pub(crate) mod error {
                 openfan_core::OpenFanError::ProfileNotFound(name) => {
                     Self::bad_request(format!("Profile not found: {}", name))
                 }
+                openfan_core::OpenFanError::InvalidZones(_) => Self::bad_request(err.to_string()),
                 openfan_core::OpenFanError::ZoneNotFound(name) => {
                     Self::bad_request(format!("Zone not found: {}", name))
                 }
                 openfan_core::OpenFanError::CurveNotFound(name) => {
                     Self::bad_request(format!("Thermal curve not found: {}", name))
                 }
+                openfan_core::OpenFanError::PidNotFound(name) => {
+                    Self::bad_request(format!("PID controller not found: {}", name))
+                }
+                openfan_core::OpenFanError::BindingNotFound(name) => {
+                    Self::bad_request(format!("Curve binding not found: {}", name))
+                }
+                openfan_core::OpenFanError::SensorNotFound(id) => {
+                    Self::bad_request(format!("Sensor not found: {}", id))
+                }
                 openfan_core::OpenFanError::CfmMappingNotFound(port) => {
                     Self::bad_request(format!("CFM mapping not found for port {}", port))
                 }
@@ -355,6 +629,7 @@ pub(crate) mod error {
                     ))
                 }
                 openfan_core::OpenFanError::Hardware(msg) => Self::service_unavailable(msg),
+                openfan_core::OpenFanError::Sensor(msg) => Self::service_unavailable(msg),
                 openfan_core::OpenFanError::Serial(msg) => Self::service_unavailable(msg),
                 openfan_core::OpenFanError::Timeout(msg) => Self::service_unavailable(msg),
                 openfan_core::OpenFanError::ControllerNotFound(id) => Self::new(
@@ -367,6 +642,13 @@ pub(crate) mod error {
                 openfan_core::OpenFanError::DuplicateControllerId(id) => {
                     Self::bad_request(format!("Duplicate controller ID: {}", id))
                 }
+                openfan_core::OpenFanError::CalibrationRunning(id) => Self::new(
+                    StatusCode::CONFLICT,
+                    format!("Calibration already running on controller: {}", id),
+                ),
+                openfan_core::OpenFanError::FanLimitExceeded { .. } => {
+                    Self::bad_request(err.to_string())
+                }
                 _ => Self::internal_error(err.to_string()),
             }
         }
@@ -417,6 +699,50 @@ mod tests {
         assert!(api_error.message.contains("test-curve"));
     }
 
+    #[test]
+    fn test_pid_not_found_error_conversion() {
+        let error = OpenFanError::PidNotFound("test-pid".to_string());
+        let api_error: ApiError = error.into();
+
+        assert_eq!(api_error.status_code, StatusCode::BAD_REQUEST);
+        assert!(api_error.message.contains("PID controller not found"));
+        assert!(api_error.message.contains("test-pid"));
+    }
+
+    #[test]
+    fn test_invalid_zones_error_conversion() {
+        let error = OpenFanError::InvalidZones(vec![openfan_core::ZoneFanProblem {
+            zone: "intake".to_string(),
+            controller: "gpu".to_string(),
+            fan_id: 4,
+            kind: openfan_core::ZoneFanProblemKind::FanOutOfRange { fan_count: 4 },
+        }]);
+        let api_error: ApiError = error.into();
+
+        assert_eq!(api_error.status_code, StatusCode::BAD_REQUEST);
+        assert!(api_error.message.contains("zone 'intake': fan 4"));
+    }
+
+    #[test]
+    fn test_binding_not_found_error_conversion() {
+        let error = OpenFanError::BindingNotFound("test-binding".to_string());
+        let api_error: ApiError = error.into();
+
+        assert_eq!(api_error.status_code, StatusCode::BAD_REQUEST);
+        assert!(api_error.message.contains("Curve binding not found"));
+        assert!(api_error.message.contains("test-binding"));
+    }
+
+    #[test]
+    fn test_sensor_not_found_error_conversion() {
+        let error = OpenFanError::SensorNotFound("k10temp/temp9".to_string());
+        let api_error: ApiError = error.into();
+
+        assert_eq!(api_error.status_code, StatusCode::BAD_REQUEST);
+        assert!(api_error.message.contains("Sensor not found"));
+        assert!(api_error.message.contains("k10temp/temp9"));
+    }
+
     #[test]
     fn test_cfm_mapping_not_found_error_conversion() {
         let error = OpenFanError::CfmMappingNotFound(5);
@@ -478,6 +804,15 @@ mod tests {
         assert_eq!(api_error.message, "connection failed");
     }
 
+    #[test]
+    fn test_sensor_error_conversion() {
+        let error = OpenFanError::Sensor("command exited with status 1".to_string());
+        let api_error: ApiError = error.into();
+
+        assert_eq!(api_error.status_code, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(api_error.message, "command exited with status 1");
+    }
+
     #[test]
     fn test_serial_error_conversion() {
         let error = OpenFanError::Serial("port busy".to_string());
@@ -574,4 +909,27 @@ mod tests {
         assert!(api_error.message.contains("Duplicate controller ID"));
         assert!(api_error.message.contains("gpu"));
     }
+
+    #[test]
+    fn test_calibration_running_error_conversion() {
+        let error = OpenFanError::CalibrationRunning("main".to_string());
+        let api_error: ApiError = error.into();
+
+        assert_eq!(api_error.status_code, StatusCode::CONFLICT);
+        assert!(api_error.message.contains("Calibration already running"));
+        assert!(api_error.message.contains("main"));
+    }
+
+    #[test]
+    fn test_fan_limit_exceeded_error_conversion() {
+        let error = OpenFanError::FanLimitExceeded {
+            fan_id: 2,
+            reason: "0% PWM is not allowed".to_string(),
+        };
+        let api_error: ApiError = error.into();
+
+        assert_eq!(api_error.status_code, StatusCode::BAD_REQUEST);
+        assert!(api_error.message.contains("Fan 2"));
+        assert!(api_error.message.contains("0% PWM is not allowed"));
+    }
 }
@@ -0,0 +1,63 @@
+//! Fixtures shared by the API tests
+
+use crate::api::AppState;
+use crate::config::RuntimeConfig;
+use crate::controllers::{ConnectionManager, Device, SimulatedDevice, connection};
+use openfan_core::{BoardInfo, BoardType, ReconnectConfig};
+use openfan_hardware::SimulatorConfig;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// Write `config.toml` to `config_dir`, with a data directory next to it and
+/// `extra_config` after it, and load it.
+pub(crate) async fn load_config(config_dir: &TempDir, extra_config: &str) -> Arc<RuntimeConfig> {
+    let data_dir = config_dir.path().join("data");
+    std::fs::create_dir_all(&data_dir).unwrap();
+    let config_path = config_dir.path().join("config.toml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "data_dir = \"{}\"\n{}",
+            data_dir.to_string_lossy().replace('\\', "\\\\"),
+            extra_config
+        ),
+    )
+    .unwrap();
+    Arc::new(RuntimeConfig::load(&config_path).await.unwrap())
+}
+
+/// Open a simulated board whose fans reach their speed at once, without noise.
+pub(crate) async fn connect_simulated(
+    id: &str,
+    board: &BoardInfo,
+) -> (Arc<ConnectionManager>, SimulatedDevice) {
+    let simulated = SimulatedDevice::new(
+        id,
+        SimulatorConfig::new(board.fan_count)
+            .with_noise(0)
+            .with_time_constant(Duration::ZERO),
+    );
+    let device = Device::Simulated(simulated.clone());
+    let controller = connection::open_device(&device, board, 1000, false)
+        .await
+        .unwrap();
+    let cm = Arc::new(ConnectionManager::new(
+        controller,
+        ReconnectConfig::default(),
+        device,
+        board.clone(),
+        1000,
+        false,
+    ));
+    (cm, simulated)
+}
+
+/// App state whose default controller is a simulated 2-fan board, configured
+/// by `extra_config`.
+pub(crate) async fn create_test_state(config_dir: &TempDir, extra_config: &str) -> AppState {
+    let config = load_config(config_dir, extra_config).await;
+    let board = BoardType::Custom { fan_count: 2 }.to_board_info();
+    let (cm, _) = connect_simulated("default", &board).await;
+    AppState::single_controller(board, config, Some(cm)).await
+}
@@ -0,0 +1,125 @@
+//! OpenFAN Firmware Emulator
+//!
+//! Serves a simulated board on a pseudo-terminal, speaking the firmware's
+//! serial protocol, so the daemon can be run end to end without hardware:
+//!
+//! ```text
+//! openfan-emulator --link /tmp/openfan-tty &
+//! openfand --device /tmp/openfan-tty
+//! ```
+//!
+//! Unlike `openfand --mock`, the daemon goes through its real serial driver,
+//! reconnect and heartbeat logic.
+//!
+//! # Fault Injection
+//!
+//! Commands read from stdin, one per line:
+//!
+//! - `disconnect` - hang up the terminal, like unplugging the board
+//! - `reconnect` - open a new terminal behind the same link
+//! - `stall <fan>` / `unstall <fan>` - stall a fan or release it
+//! - `timeout <count>` - leave the next `count` commands unanswered
+
+use anyhow::Result;
+use clap::Parser;
+use openfan_core::BoardType;
+use std::path::PathBuf;
+
+/// OpenFAN firmware emulator
+#[derive(Parser, Debug)]
+#[command(name = "openfan-emulator")]
+#[command(version, about = "OpenFAN firmware emulator on a pseudo-terminal", long_about = None)]
+struct Args {
+    /// Path of the symlink to the emulated serial port
+    #[arg(short, long, default_value = "/tmp/openfan-emulator")]
+    link: PathBuf,
+
+    /// Board type (standard, custom:N where N is fan count 1-16)
+    #[arg(long, default_value = "standard")]
+    board: BoardType,
+
+    /// Seed of the RPM noise generator
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Enable verbose logging
+    #[arg(short, long)]
+    verbose: bool,
+}
+
+#[cfg(unix)]
+#[tokio::main]
+async fn main() -> Result<()> {
+    use openfan_hardware::{PtyEmulator, SimulatedDevice, SimulatorConfig};
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tracing::{info, warn};
+
+    let args = Args::parse();
+    init_tracing(args.verbose);
+
+    let board_info = args.board.to_board_info();
+    let mut config = SimulatorConfig::new(board_info.fan_count);
+    if let Some(seed) = args.seed {
+        config.seed = seed;
+    }
+    let device = SimulatedDevice::new("emulator", config);
+    let emulator = PtyEmulator::start(device, &args.link, board_info.baud_rate)?;
+    info!(
+        "Emulating {} ({} fans) at {}",
+        board_info.name,
+        board_info.fan_count,
+        emulator.path().display()
+    );
+
+    let commands = async {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let device = emulator.device();
+            let mut words = line.split_whitespace();
+            let command = words.next();
+            let arg = words.next().and_then(|arg| arg.parse::<u32>().ok());
+            match (command, arg) {
+                (None, _) => {}
+                (Some("disconnect"), _) => device.disconnect(),
+                (Some("reconnect"), _) => device.reconnect(),
+                (Some("stall"), Some(fan)) => device.set_stalled(fan as u8, true),
+                (Some("unstall"), Some(fan)) => device.set_stalled(fan as u8, false),
+                (Some("timeout"), Some(count)) => device.inject_timeouts(count),
+                _ => warn!("Unknown command: {}", line.trim()),
+            }
+        }
+        // Without stdin, keep running until interrupted
+        std::future::pending::<()>().await
+    };
+
+    tokio::select! {
+        _ = commands => {}
+        _ = tokio::signal::ctrl_c() => info!("Received Ctrl+C, shutting down..."),
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn main() -> Result<()> {
+    let _ = Args::parse();
+    anyhow::bail!(
+        "The firmware emulator requires pseudo-terminals, which are only available on Unix"
+    )
+}
+
+/// Initialize tracing subscriber for logging
+#[cfg(unix)]
+fn init_tracing(verbose: bool) {
+    use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitExt};
+
+    let filter = if verbose {
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("debug"))
+    } else {
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
+    };
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .init();
+}
@@ -0,0 +1,717 @@
+//! Fan calibration
+//!
+//! Runs at most one calibration job per controller. A job sweeps the PWM of
+//! the selected fans from 100% down to 0%, giving the fans time to settle at
+//! each step before reading their RPM. It then raises the PWM of the stopped
+//! fans again, step by step, to find the lowest PWM that starts each fan from
+//! standstill. Fans count as spinning above `[alarms] stall_rpm`. Each fan
+//! is only set to the PWMs its safety limits allow, so a fan that may not be
+//! stopped is never swept below its minimum PWM.
+//!
+//! The controller's curve bindings are held for the duration of the job, so
+//! their control loops don't fight the sweep, even when a reload or a new
+//! binding would start them, and started again at the end.
+//! Fan alarms skip the controller while the job runs.
+//! Whether the job completes, fails or is cancelled, the fans get back the PWM
+//! or target RPM they had before it; fans never set by the daemon are left at
+//! 100% PWM. Results of a completed job are saved to the controller's
+//! `fan_calibration.toml`, next to those of fans calibrated earlier.
+
+use crate::alarms::unix_time;
+use crate::config::RuntimeConfig;
+use crate::control::{ControlEngine, Hold};
+use crate::controllers::{ConnectionManager, ControllerEntry, ControllerRegistry};
+use crate::limits::FanLimiter;
+use openfan_core::api::{CalibrationRequest, CalibrationState, CalibrationStatus};
+use openfan_core::{CalibrationPoint, FanCalibration, FanStateData, OpenFanError, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock, watch};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// A calibration job, running or finished
+struct Job {
+    status: Arc<RwLock<CalibrationStatus>>,
+    cancel: watch::Sender<bool>,
+    task: JoinHandle<()>,
+}
+
+/// Why a sweep ended early
+enum Interrupted {
+    Cancelled,
+    Failed(OpenFanError),
+}
+
+impl From<OpenFanError> for Interrupted {
+    fn from(e: OpenFanError) -> Self {
+        Interrupted::Failed(e)
+    }
+}
+
+/// Manages the calibration jobs of all controllers.
+pub(crate) struct Calibrator {
+    registry: Arc<ControllerRegistry>,
+    config: Arc<RuntimeConfig>,
+    control: Arc<ControlEngine>,
+    jobs: Mutex<HashMap<String, Job>>,
+}
+
+impl Calibrator {
+    /// Create a new calibrator with no jobs.
+    pub fn new(
+        registry: Arc<ControllerRegistry>,
+        config: Arc<RuntimeConfig>,
+        control: Arc<ControlEngine>,
+    ) -> Self {
+        Self {
+            registry,
+            config,
+            control,
+            jobs: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start calibrating fans of a controller.
+    ///
+    /// Fails if the request is invalid, the controller has no hardware
+    /// connection, or a job is already running on it.
+    pub async fn start(
+        &self,
+        controller_id: &str,
+        request: CalibrationRequest,
+    ) -> Result<CalibrationStatus> {
+        request.validate().map_err(OpenFanError::InvalidInput)?;
+        let entry = self.registry.get_or_err(controller_id).await?;
+        let cm = entry
+            .connection_manager()
+            .cloned()
+            .ok_or(OpenFanError::DeviceNotFound)?;
+
+        let mut fans = if request.fans.is_empty() {
+            (0..entry.board_info().fan_count as u8).collect()
+        } else {
+            request.fans.clone()
+        };
+        fans.sort_unstable();
+        fans.dedup();
+        for &fan_id in &fans {
+            entry.board_info().validate_fan_id(fan_id)?;
+        }
+
+        let mut jobs = self.jobs.lock().await;
+        if let Some(job) = jobs.get(controller_id)
+            && job.status.read().await.is_running()
+        {
+            return Err(OpenFanError::CalibrationRunning(controller_id.to_string()));
+        }
+
+        let sweep = request.sweep();
+        let start_steps = sweep.iter().filter(|&&pwm| pwm > 0).count();
+        let status = CalibrationStatus {
+            controller: controller_id.to_string(),
+            state: CalibrationState::Running,
+            fans: fans.clone(),
+            step: request.step,
+            settle_secs: request.settle_secs,
+            current_pwm: None,
+            completed_steps: 0,
+            total_steps: (sweep.len() + start_steps) as u32,
+            started_at: unix_time(),
+            finished_at: None,
+            error: None,
+        };
+
+        info!(
+            "Controller '{}': Calibrating fan(s) {:?} ({}% steps, {}s settle)",
+            controller_id, fans, request.step, request.settle_secs
+        );
+
+        let shared = Arc::new(RwLock::new(status.clone()));
+        let (cancel, cancelled) = watch::channel(false);
+        let task = tokio::spawn(run_job(
+            self.config.clone(),
+            self.control.clone(),
+            entry,
+            cm,
+            request,
+            shared.clone(),
+            cancelled,
+        ));
+        jobs.insert(
+            controller_id.to_string(),
+            Job {
+                status: shared,
+                cancel,
+                task,
+            },
+        );
+        Ok(status)
+    }
+
+    /// Running or last calibration job of a controller.
+    pub async fn status(&self, controller_id: &str) -> Option<CalibrationStatus> {
+        let jobs = self.jobs.lock().await;
+        let job = jobs.get(controller_id)?;
+        Some(job.status.read().await.clone())
+    }
+
+    /// Whether a job is running on a controller.
+    pub async fn is_running(&self, controller_id: &str) -> bool {
+        let jobs = self.jobs.lock().await;
+        match jobs.get(controller_id) {
+            Some(job) => job.status.read().await.is_running(),
+            None => false,
+        }
+    }
+
+    /// Ask the running job of a controller to stop.
+    ///
+    /// The fans are restored before the job ends. Returns `false` if no job
+    /// is running.
+    pub async fn cancel(&self, controller_id: &str) -> bool {
+        let jobs = self.jobs.lock().await;
+        match jobs.get(controller_id) {
+            Some(job) if job.status.read().await.is_running() => {
+                info!("Controller '{}': Cancelling calibration", controller_id);
+                job.cancel.send_replace(true);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Abort all jobs without restoring the fans.
+    ///
+    /// Called before the shutdown profile is applied, which sets the fans anyway.
+    pub async fn stop_all(&self) {
+        let mut jobs = self.jobs.lock().await;
+        for (_, job) in jobs.drain() {
+            job.task.abort();
+        }
+    }
+}
+
+/// Calibration job for a single controller.
+async fn run_job(
+    config: Arc<RuntimeConfig>,
+    control: Arc<ControlEngine>,
+    entry: Arc<ControllerEntry>,
+    cm: Arc<ConnectionManager>,
+    request: CalibrationRequest,
+    status: Arc<RwLock<CalibrationStatus>>,
+    mut cancelled: watch::Receiver<bool>,
+) {
+    let controller_id = entry.id().to_string();
+    let fans = status.read().await.fans.clone();
+
+    let previous = cm
+        .with_controller(async |controller| {
+            Ok(FanStateData::new(
+                controller.get_all_fan_pwm(),
+                controller.get_all_fan_rpm_targets(),
+            ))
+        })
+        .await;
+    control.hold(&controller_id, Hold::Calibration).await;
+
+    let outcome = match previous {
+        Ok(previous) => {
+            let outcome = match FanLimiter::load(&config, &entry).await {
+                Ok(limiter) => {
+                    sweep(
+                        &config,
+                        &cm,
+                        &limiter,
+                        &fans,
+                        &request,
+                        &status,
+                        &mut cancelled,
+                    )
+                    .await
+                }
+                Err(e) => Err(Interrupted::Failed(e)),
+            };
+            restore_fans(&controller_id, &cm, &fans, &previous).await;
+            outcome
+        }
+        Err(e) => Err(Interrupted::Failed(e)),
+    };
+    control.release(&controller_id, Hold::Calibration).await;
+
+    let (state, error) = match outcome {
+        Ok(results) => match save_results(&config, &controller_id, results).await {
+            Ok(()) => (CalibrationState::Completed, None),
+            Err(e) => (CalibrationState::Failed, Some(e.to_string())),
+        },
+        Err(Interrupted::Cancelled) => (CalibrationState::Cancelled, None),
+        Err(Interrupted::Failed(e)) => (CalibrationState::Failed, Some(e.to_string())),
+    };
+    match &error {
+        Some(error) => warn!(
+            "Controller '{}': Calibration failed: {}",
+            controller_id, error
+        ),
+        None => info!("Controller '{}': Calibration {}", controller_id, state),
+    }
+
+    let mut status = status.write().await;
+    status.state = state;
+    status.error = error;
+    status.current_pwm = None;
+    status.finished_at = Some(unix_time());
+    if state == CalibrationState::Completed {
+        status.completed_steps = status.total_steps;
+    }
+}
+
+/// Sweep the fans and measure them.
+///
+/// Fans are skipped at the steps outside their safety limits, and only
+/// measured from standstill if their limits let them stop.
+async fn sweep(
+    config: &RuntimeConfig,
+    cm: &ConnectionManager,
+    limiter: &FanLimiter,
+    fans: &[u8],
+    request: &CalibrationRequest,
+    status: &RwLock<CalibrationStatus>,
+    cancelled: &mut watch::Receiver<bool>,
+) -> std::result::Result<HashMap<u8, FanCalibration>, Interrupted> {
+    let threshold = config.static_config().alarms.stall_rpm;
+    let settle = Duration::from_secs(request.settle_secs);
+    let sweep = request.sweep();
+
+    // From 100% down: RPM at each step, and how low each fan keeps spinning
+    let mut points: HashMap<u8, Vec<CalibrationPoint>> = HashMap::new();
+    for &pwm in &sweep {
+        let allowed: Vec<u8> = allowed_fans(limiter, fans, pwm);
+        if allowed.is_empty() {
+            continue;
+        }
+        let rpms = measure(cm, &allowed, pwm, settle, status, cancelled).await?;
+        for (fan_id, rpm) in rpms {
+            points
+                .entry(fan_id)
+                .or_default()
+                .push(CalibrationPoint::new(pwm, rpm));
+        }
+    }
+
+    // From standstill up: the lowest PWM that starts each fan
+    let mut min_start: HashMap<u8, u32> = HashMap::new();
+    let mut stopped: Vec<u8> = Vec::new();
+    for &fan_id in fans {
+        match points.get(&fan_id).and_then(|points| points.last()) {
+            Some(point) if point.pwm == 0 && point.rpm > threshold => {
+                min_start.insert(fan_id, 0);
+            }
+            Some(point) if point.rpm <= threshold => stopped.push(fan_id),
+            _ => {}
+        }
+    }
+    for &pwm in sweep.iter().rev().filter(|&&pwm| pwm > 0) {
+        if stopped.is_empty() {
+            break;
+        }
+        let allowed = allowed_fans(limiter, &stopped, pwm);
+        if allowed.is_empty() {
+            continue;
+        }
+        let rpms = measure(cm, &allowed, pwm, settle, status, cancelled).await?;
+        for (fan_id, rpm) in rpms {
+            if rpm > threshold {
+                min_start.insert(fan_id, pwm);
+                stopped.retain(|&id| id != fan_id);
+            }
+        }
+    }
+
+    let calibrated_at = unix_time();
+    Ok(points
+        .into_iter()
+        .map(|(fan_id, mut points)| {
+            let min_stable_pwm = min_stable_pwm(&points, threshold);
+            points.reverse();
+            let calibration = FanCalibration {
+                points,
+                min_start_pwm: min_start.get(&fan_id).copied(),
+                min_stable_pwm,
+                calibrated_at,
+            };
+            (fan_id, calibration)
+        })
+        .collect())
+}
+
+/// Fans whose safety limits allow `pwm`.
+fn allowed_fans(limiter: &FanLimiter, fans: &[u8], pwm: u32) -> Vec<u8> {
+    fans.iter()
+        .copied()
+        .filter(|&fan_id| limiter.allows_pwm(fan_id, pwm))
+        .collect()
+}
+
+/// Set the fans to `pwm`, wait for them to settle and read their RPM.
+async fn measure(
+    cm: &ConnectionManager,
+    fans: &[u8],
+    pwm: u32,
+    settle: Duration,
+    status: &RwLock<CalibrationStatus>,
+    cancelled: &mut watch::Receiver<bool>,
+) -> std::result::Result<Vec<(u8, u32)>, Interrupted> {
+    if *cancelled.borrow() {
+        return Err(Interrupted::Cancelled);
+    }
+    status.write().await.current_pwm = Some(pwm);
+
+    cm.with_controller(async |controller| {
+        for &fan_id in fans {
+            controller.set_fan_pwm(fan_id, pwm).await?;
+        }
+        Ok(())
+    })
+    .await?;
+
+    tokio::select! {
+        _ = tokio::time::sleep(settle) => {}
+        _ = cancelled.wait_for(|&cancelled| cancelled) => return Err(Interrupted::Cancelled),
+    }
+
+    let rpms = cm
+        .with_controller(async |controller| {
+            let mut rpms = Vec::with_capacity(fans.len());
+            for &fan_id in fans {
+                rpms.push((fan_id, controller.get_single_fan_rpm(fan_id).await?));
+            }
+            Ok(rpms)
+        })
+        .await?;
+    status.write().await.completed_steps += 1;
+    Ok(rpms)
+}
+
+/// Lowest PWM of a downward sweep before the fan first stopped.
+///
+/// `points` are by descending PWM. Returns None if the fan never spun.
+fn min_stable_pwm(points: &[CalibrationPoint], threshold: u32) -> Option<u32> {
+    points
+        .iter()
+        .take_while(|point| point.rpm > threshold)
+        .last()
+        .map(|point| point.pwm)
+}
+
+/// Give the calibrated fans their PWM or target RPM from before the job.
+async fn restore_fans(
+    controller_id: &str,
+    cm: &ConnectionManager,
+    fans: &[u8],
+    previous: &FanStateData,
+) {
+    let mut state = FanStateData::default();
+    for &fan_id in fans {
+        if let Some(&rpm) = previous.rpm.get(&fan_id) {
+            state.rpm.insert(fan_id, rpm);
+        } else {
+            let pwm = previous.pwm.get(&fan_id).copied().unwrap_or(100);
+            state.pwm.insert(fan_id, pwm);
+        }
+    }
+
+    match cm.restore_state(&state).await {
+        Ok(failed) if failed.is_empty() => {}
+        Ok(failed) => warn!(
+            "Controller '{}': Fan(s) {:?} not restored after calibration",
+            controller_id, failed
+        ),
+        Err(e) => warn!(
+            "Controller '{}': Failed to restore fans after calibration: {}",
+            controller_id, e
+        ),
+    }
+}
+
+/// Save calibration results, keeping those of the other fans.
+async fn save_results(
+    config: &RuntimeConfig,
+    controller_id: &str,
+    results: HashMap<u8, FanCalibration>,
+) -> Result<()> {
+    let data = config.controller_data(controller_id).await?;
+    let mut calibration = data.load_fan_calibration().await?;
+    for (fan_id, result) in results {
+        calibration.insert(fan_id, result);
+    }
+    data.save_fan_calibration(&calibration).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{Device, SimulatedDevice, connection};
+    use crate::sensors::SensorManager;
+    use openfan_core::config::StaticConfig;
+    use openfan_core::{BoardType, ReconnectConfig};
+    use openfan_hardware::SimulatorConfig;
+    use tempfile::TempDir;
+
+    struct Setup {
+        calibrator: Calibrator,
+        control: Arc<ControlEngine>,
+        config: Arc<RuntimeConfig>,
+        cm: Arc<ConnectionManager>,
+        device: SimulatedDevice,
+    }
+
+    async fn setup(temp_dir: &TempDir) -> Setup {
+        let data_dir = temp_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let config = StaticConfig::with_data_dir(data_dir);
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(&config_path, config.to_toml().unwrap()).unwrap();
+        let config = Arc::new(RuntimeConfig::load(&config_path).await.unwrap());
+
+        let board = BoardType::Custom { fan_count: 2 }.to_board_info();
+        let device = SimulatedDevice::new(
+            "default",
+            SimulatorConfig::new(2)
+                .with_max_rpm(0, 2000)
+                .with_max_rpm(1, 1000)
+                .with_noise(0)
+                .with_time_constant(Duration::ZERO),
+        );
+        let controller =
+            connection::open_device(&Device::Simulated(device.clone()), &board, 1000, false)
+                .await
+                .unwrap();
+        let cm = Arc::new(ConnectionManager::new(
+            controller,
+            ReconnectConfig::default(),
+            Device::Simulated(device.clone()),
+            board.clone(),
+            1000,
+            false,
+        ));
+
+        let registry = ControllerRegistry::new();
+        registry
+            .register(
+                ControllerEntry::builder("default", board)
+                    .maybe_connection_manager(Some(cm.clone()))
+                    .build(),
+            )
+            .await
+            .unwrap();
+        let registry = Arc::new(registry);
+        let sensors = Arc::new(SensorManager::new(&config.static_config().sensors));
+        let control = Arc::new(ControlEngine::new(
+            registry.clone(),
+            config.clone(),
+            sensors,
+        ));
+
+        Setup {
+            calibrator: Calibrator::new(registry, config.clone(), control.clone()),
+            control,
+            config,
+            cm,
+            device,
+        }
+    }
+
+    async fn wait_until_done(calibrator: &Calibrator) -> CalibrationStatus {
+        for _ in 0..250 {
+            let status = calibrator.status("default").await.unwrap();
+            if !status.is_running() {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        calibrator.status("default").await.unwrap()
+    }
+
+    #[test]
+    fn test_min_stable_pwm() {
+        let points = [
+            CalibrationPoint::new(100, 1800),
+            CalibrationPoint::new(50, 900),
+            CalibrationPoint::new(20, 300),
+            CalibrationPoint::new(10, 0),
+            CalibrationPoint::new(0, 0),
+        ];
+        assert_eq!(min_stable_pwm(&points, 100), Some(20));
+        assert_eq!(min_stable_pwm(&points, 500), Some(50));
+        assert_eq!(min_stable_pwm(&points, 2000), None);
+    }
+
+    #[tokio::test]
+    async fn test_calibration_measures_and_restores_fans() {
+        let temp_dir = TempDir::new().unwrap();
+        let setup = setup(&temp_dir).await;
+        setup
+            .cm
+            .with_controller(async |controller| controller.set_fan_pwm(0, 40).await)
+            .await
+            .unwrap();
+
+        let request = CalibrationRequest {
+            step: 25,
+            settle_secs: 0,
+            ..Default::default()
+        };
+        let status = setup.calibrator.start("default", request).await.unwrap();
+        assert_eq!(status.fans, vec![0, 1]);
+        assert_eq!(status.total_steps, 9);
+
+        let status = wait_until_done(&setup.calibrator).await;
+        assert_eq!(status.state, CalibrationState::Completed, "{:?}", status);
+        assert_eq!(status.completed_steps, status.total_steps);
+
+        let data = setup.config.controller_data("default").await.unwrap();
+        let calibration = data.load_fan_calibration().await.unwrap();
+        let fan = calibration.get(0).unwrap();
+        assert_eq!(
+            fan.points.iter().map(|point| point.pwm).collect::<Vec<_>>(),
+            vec![0, 25, 50, 75, 100]
+        );
+        assert_eq!(fan.max_rpm(), 2000);
+        assert_eq!(fan.min_stable_pwm, Some(25));
+        assert_eq!(fan.min_start_pwm, Some(25));
+        assert_eq!(calibration.get(1).unwrap().max_rpm(), 1000);
+
+        // Fan 0 is back at its PWM, fan 1 was never set and gets 100%
+        let pwms = setup
+            .cm
+            .with_controller(async |controller| Ok(controller.get_all_fan_pwm()))
+            .await
+            .unwrap();
+        assert_eq!(pwms, HashMap::from([(0, 40), (1, 100)]));
+        assert_eq!(setup.device.rpm(0), Some(800));
+    }
+
+    #[tokio::test]
+    async fn test_calibration_stays_within_fan_limits() {
+        let temp_dir = TempDir::new().unwrap();
+        let setup = setup(&temp_dir).await;
+        let data = setup.config.controller_data("default").await.unwrap();
+        data.fan_limits_mut().await.set(
+            1,
+            openfan_core::FanLimits {
+                min_pwm: Some(50),
+                allow_zero: false,
+                ..Default::default()
+            },
+        );
+
+        let request = CalibrationRequest {
+            step: 25,
+            settle_secs: 0,
+            ..Default::default()
+        };
+        setup.calibrator.start("default", request).await.unwrap();
+        let status = wait_until_done(&setup.calibrator).await;
+        assert_eq!(status.state, CalibrationState::Completed, "{:?}", status);
+
+        let calibration = data.load_fan_calibration().await.unwrap();
+        assert_eq!(calibration.get(0).unwrap().points.len(), 5);
+        let fan = calibration.get(1).unwrap();
+        assert_eq!(
+            fan.points.iter().map(|point| point.pwm).collect::<Vec<_>>(),
+            vec![50, 75, 100]
+        );
+        assert_eq!(fan.min_stable_pwm, Some(50));
+        assert_eq!(fan.min_start_pwm, None);
+    }
+
+    #[tokio::test]
+    async fn test_calibration_rejects_concurrent_and_cancels() {
+        let temp_dir = TempDir::new().unwrap();
+        let setup = setup(&temp_dir).await;
+
+        let invalid = CalibrationRequest {
+            fans: vec![5],
+            ..Default::default()
+        };
+        assert!(matches!(
+            setup.calibrator.start("default", invalid).await,
+            Err(OpenFanError::InvalidFanId { .. })
+        ));
+        assert!(!setup.calibrator.cancel("default").await);
+
+        let request = CalibrationRequest {
+            fans: vec![1],
+            settle_secs: 30,
+            ..Default::default()
+        };
+        setup
+            .calibrator
+            .start("default", request.clone())
+            .await
+            .unwrap();
+        assert!(matches!(
+            setup.calibrator.start("default", request).await,
+            Err(OpenFanError::CalibrationRunning(_))
+        ));
+
+        assert!(setup.calibrator.cancel("default").await);
+        let status = wait_until_done(&setup.calibrator).await;
+        assert_eq!(status.state, CalibrationState::Cancelled);
+        assert!(status.finished_at.is_some());
+
+        // Nothing saved, and the fan is back at 100%
+        let data = setup.config.controller_data("default").await.unwrap();
+        assert!(data.load_fan_calibration().await.unwrap().is_empty());
+        assert_eq!(setup.device.rpm(1), Some(1000));
+    }
+
+    #[tokio::test]
+    async fn test_calibration_holds_bindings_across_reload() {
+        let temp_dir = TempDir::new().unwrap();
+        let setup = setup(&temp_dir).await;
+
+        let sensor = temp_dir.path().join("temp");
+        std::fs::write(&sensor, "40000").unwrap();
+        let binding = openfan_core::CurveBinding::for_fans(
+            "case",
+            "Balanced",
+            sensor.to_str().unwrap(),
+            vec![0],
+        );
+        let data = setup.config.controller_data("default").await.unwrap();
+        data.curve_bindings_mut()
+            .await
+            .insert(binding.name.clone(), binding.clone());
+        setup.control.start_all().await;
+
+        let request = CalibrationRequest {
+            fans: vec![0],
+            settle_secs: 30,
+            ..Default::default()
+        };
+        setup.calibrator.start("default", request).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // A reload restarting the control loops leaves the sweep alone
+        setup.control.stop_all().await;
+        setup.control.start_all().await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let status = setup.control.status("default", &binding).await;
+        assert!(!status.running);
+        assert_eq!(status.error.as_deref(), Some("Held by a fan calibration"));
+        let pwm = setup
+            .cm
+            .with_controller(async |controller| Ok(controller.get_all_fan_pwm()))
+            .await
+            .unwrap();
+        assert_eq!(pwm.get(&0), Some(&100));
+
+        // The binding runs again once the job is over
+        assert!(setup.calibrator.cancel("default").await);
+        wait_until_done(&setup.calibrator).await;
+        assert!(setup.control.status("default", &binding).await.running);
+        setup.control.stop_all().await;
+    }
+}
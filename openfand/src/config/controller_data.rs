@@ -1,21 +1,29 @@
 //! Per-controller mutable data management
 //!
 //! Each controller has its own set of aliases, profiles, thermal curves,
-//! and CFM mappings stored in a separate directory under the data directory.
+//! PID controllers, curve bindings, CFM mappings and fan limits stored in a
+//! separate directory under the data directory, along with the last applied
+//! fan state when it is persisted and the results of fan calibration.
 
 use openfan_core::{
     OpenFanError, Result,
-    config::{AliasData, CfmMappingData, ProfileData, ThermalCurveData},
+    api::{ConfigResource, TelemetryEvent},
+    config::{
+        AliasData, CfmMappingData, CurveBindingData, FanCalibrationData, FanLimitsData,
+        FanStateData, PidControllerData, ProfileData, ThermalCurveData,
+    },
 };
 use std::path::{Path, PathBuf};
 use tokio::fs;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
 use tracing::{debug, info};
 
+use super::replace_changed;
+
 /// Per-controller mutable data storage
 ///
-/// Manages aliases, profiles, thermal curves, and CFM mappings for a single
-/// controller. Each controller has its own data directory.
+/// Manages aliases, profiles, thermal curves, PID controllers, curve bindings, CFM
+/// mappings and fan limits for a single controller. Each controller has its own data directory.
 pub struct ControllerData {
     /// Controller ID (used for logging)
     id: String,
@@ -32,8 +40,20 @@ pub struct ControllerData {
     /// Thermal curve data with independent locking
     thermal_curves: RwLock<ThermalCurveData>,
 
+    /// PID controller data with independent locking
+    pid_controllers: RwLock<PidControllerData>,
+
+    /// Curve binding data with independent locking
+    curve_bindings: RwLock<CurveBindingData>,
+
     /// CFM mapping data with independent locking
     cfm_mappings: RwLock<CfmMappingData>,
+
+    /// Fan limit data with independent locking
+    fan_limits: RwLock<FanLimitsData>,
+
+    /// Where saved changes are announced, if anywhere
+    changes: Option<broadcast::Sender<TelemetryEvent>>,
 }
 
 impl ControllerData {
@@ -53,7 +73,17 @@ impl ControllerData {
     /// println!("Loaded {} profiles", profiles.profiles.len());
     /// ```
     pub async fn load(id: impl Into<String>, base_data_dir: &Path) -> Result<Self> {
-        let id = id.into();
+        Self::open(id.into(), base_data_dir, true).await
+    }
+
+    /// Read controller data from a directory, without creating anything
+    ///
+    /// Missing files read as their defaults, as [`Self::load`] would create them.
+    pub async fn read(id: impl Into<String>, base_data_dir: &Path) -> Result<Self> {
+        Self::open(id.into(), base_data_dir, false).await
+    }
+
+    async fn open(id: String, base_data_dir: &Path, create: bool) -> Result<Self> {
         let data_path = base_data_dir.join("controllers").join(&id);
 
         info!(
@@ -62,22 +92,30 @@ impl ControllerData {
             data_path.display()
         );
 
-        // Ensure controller data directory exists
-        Self::ensure_data_dir(&data_path).await?;
+        if create {
+            // Ensure controller data directory exists
+            Self::ensure_data_dir(&data_path).await?;
+        }
 
         // Load or create mutable data files
-        let aliases = Self::load_aliases(&data_path).await?;
-        let profiles = Self::load_profiles(&data_path).await?;
-        let thermal_curves = Self::load_thermal_curves(&data_path).await?;
-        let cfm_mappings = Self::load_cfm_mappings(&data_path).await?;
+        let aliases = Self::load_aliases(&data_path, create).await?;
+        let profiles = Self::load_profiles(&data_path, create).await?;
+        let thermal_curves = Self::load_thermal_curves(&data_path, create).await?;
+        let pid_controllers = Self::load_pid_controllers(&data_path, create).await?;
+        let curve_bindings = Self::load_curve_bindings(&data_path, create).await?;
+        let cfm_mappings = Self::load_cfm_mappings(&data_path, create).await?;
+        let fan_limits = Self::load_fan_limits(&data_path, create).await?;
 
         info!(
-            "Controller '{}' data loaded: {} profiles, {} aliases, {} curves, {} CFM mappings",
+            "Controller '{}' data loaded: {} profiles, {} aliases, {} curves, {} PID controllers, {} bindings, {} CFM mappings, {} fan limits",
             id,
             profiles.profiles.len(),
             aliases.aliases.len(),
             thermal_curves.curves.len(),
-            cfm_mappings.len()
+            pid_controllers.controllers.len(),
+            curve_bindings.bindings.len(),
+            cfm_mappings.len(),
+            fan_limits.fans.len()
         );
 
         Ok(Self {
@@ -86,10 +124,64 @@ impl ControllerData {
             aliases: RwLock::new(aliases),
             profiles: RwLock::new(profiles),
             thermal_curves: RwLock::new(thermal_curves),
+            pid_controllers: RwLock::new(pid_controllers),
+            curve_bindings: RwLock::new(curve_bindings),
             cfm_mappings: RwLock::new(cfm_mappings),
+            fan_limits: RwLock::new(fan_limits),
+            changes: None,
         })
     }
 
+    /// Announce every saved change on `changes`
+    pub fn with_changes(mut self, changes: broadcast::Sender<TelemetryEvent>) -> Self {
+        self.changes = Some(changes);
+        self
+    }
+
+    /// Replace the data with the same controller's data loaded again from disk
+    ///
+    /// Only the resources that differ are replaced, each announced like a
+    /// save. Returns the replaced resources.
+    pub async fn replace(&self, reloaded: ControllerData) -> Vec<ConfigResource> {
+        let mut replaced = Vec::new();
+        if replace_changed(&self.aliases, reloaded.aliases.into_inner()).await {
+            replaced.push(ConfigResource::Aliases);
+        }
+        if replace_changed(&self.profiles, reloaded.profiles.into_inner()).await {
+            replaced.push(ConfigResource::Profiles);
+        }
+        if replace_changed(&self.thermal_curves, reloaded.thermal_curves.into_inner()).await {
+            replaced.push(ConfigResource::ThermalCurves);
+        }
+        if replace_changed(&self.pid_controllers, reloaded.pid_controllers.into_inner()).await {
+            replaced.push(ConfigResource::PidControllers);
+        }
+        if replace_changed(&self.curve_bindings, reloaded.curve_bindings.into_inner()).await {
+            replaced.push(ConfigResource::CurveBindings);
+        }
+        if replace_changed(&self.cfm_mappings, reloaded.cfm_mappings.into_inner()).await {
+            replaced.push(ConfigResource::CfmMappings);
+        }
+        if replace_changed(&self.fan_limits, reloaded.fan_limits.into_inner()).await {
+            replaced.push(ConfigResource::FanLimits);
+        }
+
+        for resource in &replaced {
+            self.notify(*resource);
+        }
+        replaced
+    }
+
+    /// Announce a saved change, if anyone listens
+    fn notify(&self, resource: ConfigResource) {
+        if let Some(changes) = &self.changes {
+            let _ = changes.send(TelemetryEvent::ConfigChanged {
+                controller: Some(self.id.clone()),
+                resource,
+            });
+        }
+    }
+
     /// Ensure data directory exists and is writable
     async fn ensure_data_dir(data_path: &Path) -> Result<()> {
         if !data_path.exists() {
@@ -139,13 +231,15 @@ impl ControllerData {
     // Alias access and modification
     // =========================================================================
 
-    async fn load_aliases(data_path: &Path) -> Result<AliasData> {
+    async fn load_aliases(data_path: &Path, create: bool) -> Result<AliasData> {
         let path = data_path.join("aliases.toml");
 
         if !path.exists() {
-            debug!("Aliases file not found. Creating with defaults.");
             let data = AliasData::default();
-            Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            if create {
+                debug!("Aliases file not found. Creating with defaults.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
             return Ok(data);
         }
 
@@ -183,6 +277,7 @@ impl ControllerData {
             self.id,
             path.display()
         );
+        self.notify(ConfigResource::Aliases);
         Ok(())
     }
 
@@ -190,13 +285,15 @@ impl ControllerData {
     // Profile access and modification
     // =========================================================================
 
-    async fn load_profiles(data_path: &Path) -> Result<ProfileData> {
+    async fn load_profiles(data_path: &Path, create: bool) -> Result<ProfileData> {
         let path = data_path.join("profiles.toml");
 
         if !path.exists() {
-            debug!("Profiles file not found. Creating with defaults.");
             let data = ProfileData::with_defaults();
-            Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            if create {
+                debug!("Profiles file not found. Creating with defaults.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
             return Ok(data);
         }
 
@@ -234,6 +331,7 @@ impl ControllerData {
             self.id,
             path.display()
         );
+        self.notify(ConfigResource::Profiles);
         Ok(())
     }
 
@@ -241,13 +339,15 @@ impl ControllerData {
     // Thermal curve access and modification
     // =========================================================================
 
-    async fn load_thermal_curves(data_path: &Path) -> Result<ThermalCurveData> {
+    async fn load_thermal_curves(data_path: &Path, create: bool) -> Result<ThermalCurveData> {
         let path = data_path.join("thermal_curves.toml");
 
         if !path.exists() {
-            debug!("Thermal curves file not found. Creating with defaults.");
             let data = ThermalCurveData::with_defaults();
-            Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            if create {
+                debug!("Thermal curves file not found. Creating with defaults.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
             return Ok(data);
         }
 
@@ -255,9 +355,12 @@ impl ControllerData {
             OpenFanError::Config(format!("Failed to read thermal curves file: {}", e))
         })?;
 
-        ThermalCurveData::from_toml(&content).map_err(|e| {
+        let data = ThermalCurveData::from_toml(&content).map_err(|e| {
             OpenFanError::Config(format!("Failed to parse thermal curves file: {}", e))
-        })
+        })?;
+        data.validate()
+            .map_err(|e| OpenFanError::Config(format!("Invalid thermal curves file: {}", e)))?;
+        Ok(data)
     }
 
     /// Get read lock on thermal curve data
@@ -286,6 +389,125 @@ impl ControllerData {
             self.id,
             path.display()
         );
+        self.notify(ConfigResource::ThermalCurves);
+        Ok(())
+    }
+
+    // =========================================================================
+    // PID controller access and modification
+    // =========================================================================
+
+    async fn load_pid_controllers(data_path: &Path, create: bool) -> Result<PidControllerData> {
+        let path = data_path.join("pid_controllers.toml");
+
+        if !path.exists() {
+            let data = PidControllerData::default();
+            if create {
+                debug!("PID controllers file not found. Creating empty.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
+            return Ok(data);
+        }
+
+        let content = fs::read_to_string(&path).await.map_err(|e| {
+            OpenFanError::Config(format!("Failed to read PID controllers file: {}", e))
+        })?;
+
+        let data = PidControllerData::from_toml(&content).map_err(|e| {
+            OpenFanError::Config(format!("Failed to parse PID controllers file: {}", e))
+        })?;
+        data.validate()
+            .map_err(|e| OpenFanError::Config(format!("Invalid PID controllers file: {}", e)))?;
+        Ok(data)
+    }
+
+    /// Get read lock on PID controller data
+    pub async fn pid_controllers(&self) -> tokio::sync::RwLockReadGuard<'_, PidControllerData> {
+        self.pid_controllers.read().await
+    }
+
+    /// Get write lock on PID controller data
+    pub async fn pid_controllers_mut(
+        &self,
+    ) -> tokio::sync::RwLockWriteGuard<'_, PidControllerData> {
+        self.pid_controllers.write().await
+    }
+
+    /// Save PID controller data to disk
+    pub async fn save_pid_controllers(&self) -> Result<()> {
+        let controllers = self.pid_controllers.read().await;
+        let path = self.data_path.join("pid_controllers.toml");
+
+        let content = controllers.to_toml().map_err(|e| {
+            OpenFanError::Config(format!("Failed to serialize PID controllers: {}", e))
+        })?;
+
+        Self::write_toml(&path, &content).await?;
+
+        debug!(
+            "Saved PID controllers for controller '{}' to {}",
+            self.id,
+            path.display()
+        );
+        self.notify(ConfigResource::PidControllers);
+        Ok(())
+    }
+
+    // =========================================================================
+    // Curve binding access and modification
+    // =========================================================================
+
+    async fn load_curve_bindings(data_path: &Path, create: bool) -> Result<CurveBindingData> {
+        let path = data_path.join("curve_bindings.toml");
+
+        if !path.exists() {
+            let data = CurveBindingData::default();
+            if create {
+                debug!("Curve bindings file not found. Creating empty.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
+            return Ok(data);
+        }
+
+        let content = fs::read_to_string(&path).await.map_err(|e| {
+            OpenFanError::Config(format!("Failed to read curve bindings file: {}", e))
+        })?;
+
+        let data = CurveBindingData::from_toml(&content).map_err(|e| {
+            OpenFanError::Config(format!("Failed to parse curve bindings file: {}", e))
+        })?;
+        data.validate()
+            .map_err(|e| OpenFanError::Config(format!("Invalid curve bindings file: {}", e)))?;
+        Ok(data)
+    }
+
+    /// Get read lock on curve binding data
+    pub async fn curve_bindings(&self) -> tokio::sync::RwLockReadGuard<'_, CurveBindingData> {
+        self.curve_bindings.read().await
+    }
+
+    /// Get write lock on curve binding data
+    pub async fn curve_bindings_mut(&self) -> tokio::sync::RwLockWriteGuard<'_, CurveBindingData> {
+        self.curve_bindings.write().await
+    }
+
+    /// Save curve binding data to disk
+    pub async fn save_curve_bindings(&self) -> Result<()> {
+        let bindings = self.curve_bindings.read().await;
+        let path = self.data_path.join("curve_bindings.toml");
+
+        let content = bindings.to_toml().map_err(|e| {
+            OpenFanError::Config(format!("Failed to serialize curve bindings: {}", e))
+        })?;
+
+        Self::write_toml(&path, &content).await?;
+
+        debug!(
+            "Saved curve bindings for controller '{}' to {}",
+            self.id,
+            path.display()
+        );
+        self.notify(ConfigResource::CurveBindings);
         Ok(())
     }
 
@@ -293,13 +515,15 @@ impl ControllerData {
     // CFM mapping access and modification
     // =========================================================================
 
-    async fn load_cfm_mappings(data_path: &Path) -> Result<CfmMappingData> {
+    async fn load_cfm_mappings(data_path: &Path, create: bool) -> Result<CfmMappingData> {
         let path = data_path.join("cfm_mappings.toml");
 
         if !path.exists() {
-            debug!("CFM mappings file not found. Creating empty.");
             let data = CfmMappingData::default();
-            Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            if create {
+                debug!("CFM mappings file not found. Creating empty.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
             return Ok(data);
         }
 
@@ -337,6 +561,146 @@ impl ControllerData {
             self.id,
             path.display()
         );
+        self.notify(ConfigResource::CfmMappings);
+        Ok(())
+    }
+
+    // =========================================================================
+    // Fan limit access and modification
+    // =========================================================================
+
+    async fn load_fan_limits(data_path: &Path, create: bool) -> Result<FanLimitsData> {
+        let path = data_path.join("fan_limits.toml");
+
+        if !path.exists() {
+            let data = FanLimitsData::default();
+            if create {
+                debug!("Fan limits file not found. Creating empty.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
+            return Ok(data);
+        }
+
+        let content = fs::read_to_string(&path)
+            .await
+            .map_err(|e| OpenFanError::Config(format!("Failed to read fan limits file: {}", e)))?;
+
+        let data = FanLimitsData::from_toml(&content)
+            .map_err(|e| OpenFanError::Config(format!("Failed to parse fan limits file: {}", e)))?;
+        data.validate(None)
+            .map_err(|e| OpenFanError::Config(format!("Invalid fan limits file: {}", e)))?;
+        Ok(data)
+    }
+
+    /// Get read lock on fan limit data
+    pub async fn fan_limits(&self) -> tokio::sync::RwLockReadGuard<'_, FanLimitsData> {
+        self.fan_limits.read().await
+    }
+
+    /// Get write lock on fan limit data
+    pub async fn fan_limits_mut(&self) -> tokio::sync::RwLockWriteGuard<'_, FanLimitsData> {
+        self.fan_limits.write().await
+    }
+
+    /// Save fan limit data to disk
+    pub async fn save_fan_limits(&self) -> Result<()> {
+        let limits = self.fan_limits.read().await;
+        let path = self.data_path.join("fan_limits.toml");
+
+        let content = limits
+            .to_toml()
+            .map_err(|e| OpenFanError::Config(format!("Failed to serialize fan limits: {}", e)))?;
+
+        Self::write_toml(&path, &content).await?;
+
+        debug!(
+            "Saved fan limits for controller '{}' to {}",
+            self.id,
+            path.display()
+        );
+        self.notify(ConfigResource::FanLimits);
+        Ok(())
+    }
+
+    // =========================================================================
+    // Last applied fan state
+    // =========================================================================
+
+    /// Load the fan state saved by [`Self::save_fan_state`]
+    ///
+    /// Returns None if no state was saved yet. Unlike the other data files,
+    /// no default file is created.
+    pub async fn load_fan_state(&self) -> Result<Option<FanStateData>> {
+        let path = self.data_path.join("fan_state.toml");
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs::read_to_string(&path)
+            .await
+            .map_err(|e| OpenFanError::Config(format!("Failed to read fan state file: {}", e)))?;
+
+        FanStateData::from_toml(&content)
+            .map(Some)
+            .map_err(|e| OpenFanError::Config(format!("Failed to parse fan state file: {}", e)))
+    }
+
+    /// Save the last applied fan state to disk
+    pub async fn save_fan_state(&self, state: &FanStateData) -> Result<()> {
+        let path = self.data_path.join("fan_state.toml");
+
+        let content = state
+            .to_toml()
+            .map_err(|e| OpenFanError::Config(format!("Failed to serialize fan state: {}", e)))?;
+
+        Self::write_toml(&path, &content).await?;
+
+        debug!(
+            "Saved fan state for controller '{}' to {}",
+            self.id,
+            path.display()
+        );
+        Ok(())
+    }
+
+    // =========================================================================
+    // Fan calibration results
+    // =========================================================================
+
+    /// Load the calibration results saved by [`Self::save_fan_calibration`]
+    ///
+    /// Returns empty data if no fan was calibrated yet. Like the fan state,
+    /// no default file is created.
+    pub async fn load_fan_calibration(&self) -> Result<FanCalibrationData> {
+        let path = self.data_path.join("fan_calibration.toml");
+        if !path.exists() {
+            return Ok(FanCalibrationData::default());
+        }
+
+        let content = fs::read_to_string(&path).await.map_err(|e| {
+            OpenFanError::Config(format!("Failed to read fan calibration file: {}", e))
+        })?;
+
+        FanCalibrationData::from_toml(&content).map_err(|e| {
+            OpenFanError::Config(format!("Failed to parse fan calibration file: {}", e))
+        })
+    }
+
+    /// Save fan calibration results to disk
+    pub async fn save_fan_calibration(&self, data: &FanCalibrationData) -> Result<()> {
+        let path = self.data_path.join("fan_calibration.toml");
+
+        let content = data.to_toml().map_err(|e| {
+            OpenFanError::Config(format!("Failed to serialize fan calibration: {}", e))
+        })?;
+
+        Self::write_toml(&path, &content).await?;
+
+        debug!(
+            "Saved fan calibration for controller '{}' to {}",
+            self.id,
+            path.display()
+        );
         Ok(())
     }
 }
@@ -364,7 +728,10 @@ mod tests {
         assert!(controller_dir.join("aliases.toml").exists());
         assert!(controller_dir.join("profiles.toml").exists());
         assert!(controller_dir.join("thermal_curves.toml").exists());
+        assert!(controller_dir.join("pid_controllers.toml").exists());
+        assert!(controller_dir.join("curve_bindings.toml").exists());
         assert!(controller_dir.join("cfm_mappings.toml").exists());
+        assert!(controller_dir.join("fan_limits.toml").exists());
     }
 
     #[tokio::test]
@@ -387,6 +754,37 @@ mod tests {
         assert_eq!(aliases.get(0), "CPU Fan");
     }
 
+    #[tokio::test]
+    async fn test_controller_data_fan_limit_operations() {
+        let temp_dir = TempDir::new().unwrap();
+        let data = ControllerData::load("main", temp_dir.path()).await.unwrap();
+        assert!(data.fan_limits().await.is_empty());
+
+        let limits = openfan_core::FanLimits {
+            min_pwm: Some(30),
+            allow_zero: false,
+            ..Default::default()
+        };
+        data.fan_limits_mut().await.set(1, limits);
+        data.save_fan_limits().await.unwrap();
+
+        let data2 = ControllerData::load("main", temp_dir.path()).await.unwrap();
+        assert_eq!(data2.fan_limits().await.get(1), limits);
+
+        // Invalid limits written by hand are refused
+        let path = temp_dir
+            .path()
+            .join("controllers")
+            .join("main")
+            .join("fan_limits.toml");
+        std::fs::write(&path, "[fans.1]\nallow_zero = false\n").unwrap();
+        let error = ControllerData::load("main", temp_dir.path())
+            .await
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("fan 1"));
+    }
+
     #[tokio::test]
     async fn test_controller_data_profile_operations() {
         let temp_dir = TempDir::new().unwrap();
@@ -430,4 +828,174 @@ mod tests {
         assert!(temp_dir.path().join("controllers").join("main").exists());
         assert!(temp_dir.path().join("controllers").join("gpu").exists());
     }
+
+    #[tokio::test]
+    async fn test_controller_data_fan_state() {
+        use std::collections::HashMap;
+
+        let temp_dir = TempDir::new().unwrap();
+        let data = ControllerData::load("main", temp_dir.path()).await.unwrap();
+
+        // Not created with defaults, unlike the other files
+        assert!(data.load_fan_state().await.unwrap().is_none());
+
+        let state = FanStateData::new(HashMap::from([(0, 30)]), HashMap::from([(1, 900)]));
+        data.save_fan_state(&state).await.unwrap();
+        assert_eq!(data.load_fan_state().await.unwrap(), Some(state));
+    }
+
+    #[tokio::test]
+    async fn test_controller_data_fan_calibration() {
+        use openfan_core::{CalibrationPoint, FanCalibration};
+
+        let temp_dir = TempDir::new().unwrap();
+        let data = ControllerData::load("main", temp_dir.path()).await.unwrap();
+
+        assert!(data.load_fan_calibration().await.unwrap().is_empty());
+        assert!(
+            !temp_dir
+                .path()
+                .join("controllers/main/fan_calibration.toml")
+                .exists()
+        );
+
+        let mut calibration = FanCalibrationData::default();
+        calibration.insert(
+            1,
+            FanCalibration {
+                points: vec![
+                    CalibrationPoint::new(0, 0),
+                    CalibrationPoint::new(100, 2000),
+                ],
+                min_start_pwm: Some(20),
+                min_stable_pwm: Some(10),
+                calibrated_at: 1_700_000_000,
+            },
+        );
+        data.save_fan_calibration(&calibration).await.unwrap();
+        assert_eq!(data.load_fan_calibration().await.unwrap(), calibration);
+    }
+
+    #[tokio::test]
+    async fn test_controller_data_curve_binding_operations() {
+        use openfan_core::CurveBinding;
+
+        let temp_dir = TempDir::new().unwrap();
+        let data = ControllerData::load("main", temp_dir.path()).await.unwrap();
+
+        assert!(data.curve_bindings().await.bindings.is_empty());
+
+        {
+            let mut bindings = data.curve_bindings_mut().await;
+            bindings.insert(
+                "cpu".to_string(),
+                CurveBinding::for_fans("cpu", "Balanced", "/tmp/temp", vec![0, 1]),
+            );
+        }
+        data.save_curve_bindings().await.unwrap();
+
+        // Reload and verify
+        let data2 = ControllerData::load("main", temp_dir.path()).await.unwrap();
+        let bindings = data2.curve_bindings().await;
+        let binding = bindings.get("cpu").unwrap();
+        assert_eq!(binding.curve.as_deref(), Some("Balanced"));
+        assert_eq!(binding.fans, vec![0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_controller_data_rejects_invalid_entries() {
+        let temp_dir = TempDir::new().unwrap();
+        let _ = ControllerData::load("main", temp_dir.path()).await.unwrap();
+        let controller_dir = temp_dir.path().join("controllers").join("main");
+
+        std::fs::write(
+            controller_dir.join("curve_bindings.toml"),
+            "[bindings.cpu]\nname = \"cpu\"\ncurve = \"Balanced\"\nsensor = \"/tmp/temp\"\nfans = [0]\ninterval_secs = 0\n",
+        )
+        .unwrap();
+        let err = ControllerData::load("main", temp_dir.path())
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("binding 'cpu'"), "{}", err);
+
+        std::fs::write(controller_dir.join("curve_bindings.toml"), "").unwrap();
+        std::fs::write(
+            controller_dir.join("pid_controllers.toml"),
+            "[controllers.loop]\nname = \"loop\"\nsetpoint_c = 40.0\nkp = -1.0\nki = 0.0\nkd = 0.0\n",
+        )
+        .unwrap();
+        let err = ControllerData::load("main", temp_dir.path())
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("PID 'loop'"), "{}", err);
+
+        std::fs::write(controller_dir.join("pid_controllers.toml"), "").unwrap();
+        std::fs::write(
+            controller_dir.join("thermal_curves.toml"),
+            "[curves.flat]\nname = \"flat\"\npoints = [{ temp_c = 40.0, pwm = 50 }]\n",
+        )
+        .unwrap();
+        let err = ControllerData::load("main", temp_dir.path())
+            .await
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("curve 'flat'"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_controller_data_pid_operations() {
+        use openfan_core::PidController;
+
+        let temp_dir = TempDir::new().unwrap();
+        let data = ControllerData::load("main", temp_dir.path()).await.unwrap();
+
+        assert!(data.pid_controllers().await.controllers.is_empty());
+
+        data.pid_controllers_mut().await.insert(
+            "loop".to_string(),
+            PidController::new("loop", 40.0, 5.0, 0.1, 0.0).with_output_range(20, 100),
+        );
+        data.save_pid_controllers().await.unwrap();
+
+        // Reload and verify
+        let data2 = ControllerData::load("main", temp_dir.path()).await.unwrap();
+        let controllers = data2.pid_controllers().await;
+        let pid = controllers.get("loop").unwrap();
+        assert_eq!(pid.setpoint_c, 40.0);
+        assert_eq!(pid.min_pwm, 20);
+    }
+
+    #[tokio::test]
+    async fn test_controller_data_replace_only_changed() {
+        let temp_dir = TempDir::new().unwrap();
+        let (tx, mut rx) = broadcast::channel(8);
+        let data = ControllerData::load("main", temp_dir.path())
+            .await
+            .unwrap()
+            .with_changes(tx);
+
+        // Edit the file behind the daemon's back, then read it again
+        let other = ControllerData::load("main", temp_dir.path()).await.unwrap();
+        other.aliases_mut().await.set(0, "CPU Fan".to_string());
+        other.save_aliases().await.unwrap();
+        let reloaded = ControllerData::load("main", temp_dir.path()).await.unwrap();
+
+        let replaced = data.replace(reloaded).await;
+        assert_eq!(replaced, vec![ConfigResource::Aliases]);
+        assert_eq!(data.aliases().await.get(0), "CPU Fan");
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            TelemetryEvent::ConfigChanged {
+                resource: ConfigResource::Aliases,
+                ..
+            }
+        ));
+        assert!(rx.try_recv().is_err());
+
+        // Unchanged data replaces nothing
+        let reloaded = ControllerData::load("main", temp_dir.path()).await.unwrap();
+        assert!(data.replace(reloaded).await.is_empty());
+    }
 }
@@ -5,19 +5,43 @@
 //! # Architecture
 //!
 //! Configuration is split into:
-//! - Static config (`config.toml`) - Server and hardware settings, loaded once at startup
+//! - Static config (`config.toml`) - Server and hardware settings, loaded at startup and on reload
 //! - Global zones (`data_dir/zones.toml`) - Cross-controller zone definitions
 //! - Per-controller data (`data_dir/controllers/{id}/`):
 //!   - `aliases.toml` - Fan aliases, mutable via API
 //!   - `profiles.toml` - Fan profiles, mutable via API
 //!   - `thermal_curves.toml` - Thermal response curves
+//!   - `pid_controllers.toml` - PID controllers for holding a target temperature
+//!   - `curve_bindings.toml` - Curve-to-sensor bindings driven by the control loop
 //!   - `cfm_mappings.toml` - CFM calibration data
+//!   - `fan_state.toml` - Last applied fan state, written by the daemon for `[startup] restore_state`
 //!
 //! This follows the bind9-style separation where static configuration is kept
-//! separate from runtime data that can be modified via API.
+//! separate from runtime data that can be modified via API. Both can be read
+//! again from disk while the daemon runs, see [`RuntimeConfig::read_back`].
 
 mod controller_data;
 mod runtime_config;
 
 pub(crate) use controller_data::ControllerData;
-pub(crate) use runtime_config::RuntimeConfig;
+pub(crate) use runtime_config::{ReloadedConfig, RuntimeConfig, check_zones};
+
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+/// Whether two values differ once serialized, whatever their map ordering.
+pub(crate) fn differs<T: Serialize>(a: &T, b: &T) -> bool {
+    serde_json::to_value(a).ok() != serde_json::to_value(b).ok()
+}
+
+/// Replace the value behind a lock if the new one differs.
+///
+/// Returns whether it was replaced.
+async fn replace_changed<T: Serialize>(lock: &RwLock<T>, value: T) -> bool {
+    let mut current = lock.write().await;
+    if !differs(&*current, &value) {
+        return false;
+    }
+    *current = value;
+    true
+}
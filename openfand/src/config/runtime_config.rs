@@ -1,31 +1,45 @@
 //! Runtime configuration management
 //!
 //! Combines static configuration with mutable data files, providing
-//! thread-safe access and independent save operations.
+//! thread-safe access and independent save operations. Both can be read again
+//! from disk and replaced while the daemon runs.
 
 use openfan_core::{
     BoardInfo, OpenFanError, Result,
-    config::{AliasData, CfmMappingData, ProfileData, StaticConfig, ThermalCurveData, ZoneData},
+    api::{ConfigResource, TelemetryEvent},
+    config::{
+        AliasData, CfmMappingData, ControllerConfig, ProfileData, StaticConfig, ThermalCurveData,
+        ZoneData,
+    },
 };
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::fs;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, broadcast};
 use tracing::{debug, info, warn};
 
-use super::ControllerData;
+use super::{ControllerData, differs, replace_changed};
+
+/// Number of unread change events kept for a slow subscriber
+const CHANGES_CAPACITY: usize = 32;
 
 /// Runtime configuration combining static config and mutable data.
 ///
-/// Static config is read once at startup and remains immutable.
+/// Static config is read at startup and replaced as a whole on reload.
 /// Mutable data (aliases, profiles, zones, thermal curves, cfm mappings) can be modified via API and saved independently.
 ///
 /// For multi-controller setups, per-controller data is stored separately in `ControllerData`
 /// instances accessed via `controller_data()`.
 pub(crate) struct RuntimeConfig {
-    /// Static configuration (immutable after load)
-    static_config: StaticConfig,
+    /// Path to `config.toml`, read again on reload
+    config_path: PathBuf,
+
+    /// Static configuration, replaced as a whole on reload
+    static_config: std::sync::RwLock<Arc<StaticConfig>>,
+
+    /// Data directory, fixed at startup
+    data_dir: PathBuf,
 
     /// Per-controller mutable data (aliases, profiles, curves, CFM)
     /// Key is controller ID, value is the controller's data
@@ -43,6 +57,9 @@ pub(crate) struct RuntimeConfig {
 
     /// CFM mapping data with independent locking
     cfm_mappings: RwLock<CfmMappingData>,
+
+    /// Announces saved changes to zones and per-controller data
+    changes: broadcast::Sender<TelemetryEvent>,
 }
 
 impl RuntimeConfig {
@@ -70,10 +87,10 @@ impl RuntimeConfig {
         Self::ensure_data_dir(&static_config.data_dir).await?;
 
         // Load or create mutable data files
-        let aliases = Self::load_aliases(&static_config.data_dir).await?;
-        let profiles = Self::load_profiles(&static_config.data_dir).await?;
-        let zones = Self::load_zones(&static_config.data_dir).await?;
-        let cfm_mappings = Self::load_cfm_mappings(&static_config.data_dir).await?;
+        let aliases = Self::load_aliases(&static_config.data_dir, true).await?;
+        let profiles = Self::load_profiles(&static_config.data_dir, true).await?;
+        let zones = Self::load_zones(&static_config.data_dir, true).await?;
+        let cfm_mappings = Self::load_cfm_mappings(&static_config.data_dir, true).await?;
 
         // Ensure thermal_curves.toml exists (for per-controller data compatibility)
         Self::ensure_thermal_curves_file(&static_config.data_dir).await?;
@@ -87,12 +104,15 @@ impl RuntimeConfig {
         );
 
         Ok(Self {
-            static_config,
+            config_path: config_path.to_path_buf(),
+            data_dir: static_config.data_dir.clone(),
+            static_config: std::sync::RwLock::new(Arc::new(static_config)),
             controller_data: RwLock::new(HashMap::new()),
             aliases: RwLock::new(aliases),
             profiles: RwLock::new(profiles),
             zones: RwLock::new(zones),
             cfm_mappings: RwLock::new(cfm_mappings),
+            changes: broadcast::channel(CHANGES_CAPACITY).0,
         })
     }
 
@@ -127,12 +147,39 @@ impl RuntimeConfig {
             return Ok(config);
         }
 
+        Self::read_static_config(path).await
+    }
+
+    /// Read and validate static config from an existing TOML file.
+    async fn read_static_config(path: &Path) -> Result<StaticConfig> {
         let content = fs::read_to_string(path)
             .await
             .map_err(|e| OpenFanError::Config(format!("Failed to read config file: {}", e)))?;
 
-        StaticConfig::from_toml(&content)
-            .map_err(|e| OpenFanError::Config(format!("Failed to parse config file: {}", e)))
+        let config = StaticConfig::from_toml(&content)
+            .map_err(|e| OpenFanError::Config(format!("Failed to parse config file: {}", e)))?;
+
+        config
+            .server
+            .validate()
+            .map_err(|e| OpenFanError::Config(format!("Invalid [server] config: {}", e)))?;
+        config
+            .sensors
+            .validate()
+            .map_err(|e| OpenFanError::Config(format!("Invalid [sensors] config: {}", e)))?;
+        config
+            .validate_controllers()
+            .map_err(|e| OpenFanError::Config(format!("Invalid [[controllers]] config: {}", e)))?;
+        config
+            .auth
+            .validate()
+            .map_err(|e| OpenFanError::Config(format!("Invalid [auth] config: {}", e)))?;
+        config
+            .history
+            .validate()
+            .map_err(|e| OpenFanError::Config(format!("Invalid [history] config: {}", e)))?;
+
+        Ok(config)
     }
 
     /// Ensure data directory exists and is writable.
@@ -163,14 +210,17 @@ impl RuntimeConfig {
         Ok(())
     }
 
-    /// Load aliases from TOML file, creating with defaults if missing.
-    async fn load_aliases(data_dir: &Path) -> Result<AliasData> {
+    /// Load aliases from TOML file, or the defaults if missing, written to it
+    /// with `create`.
+    async fn load_aliases(data_dir: &Path, create: bool) -> Result<AliasData> {
         let path = data_dir.join("aliases.toml");
 
         if !path.exists() {
-            debug!("Aliases file not found. Creating with defaults.");
             let data = AliasData::default();
-            Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            if create {
+                debug!("Aliases file not found. Creating with defaults.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
             return Ok(data);
         }
 
@@ -182,14 +232,17 @@ impl RuntimeConfig {
             .map_err(|e| OpenFanError::Config(format!("Failed to parse aliases file: {}", e)))
     }
 
-    /// Load profiles from TOML file, creating with defaults if missing.
-    async fn load_profiles(data_dir: &Path) -> Result<ProfileData> {
+    /// Load profiles from TOML file, or the defaults if missing, written to it
+    /// with `create`.
+    async fn load_profiles(data_dir: &Path, create: bool) -> Result<ProfileData> {
         let path = data_dir.join("profiles.toml");
 
         if !path.exists() {
-            debug!("Profiles file not found. Creating with defaults.");
             let data = ProfileData::with_defaults();
-            Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            if create {
+                debug!("Profiles file not found. Creating with defaults.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
             return Ok(data);
         }
 
@@ -201,14 +254,17 @@ impl RuntimeConfig {
             .map_err(|e| OpenFanError::Config(format!("Failed to parse profiles file: {}", e)))
     }
 
-    /// Load zones from TOML file, creating with defaults if missing.
-    async fn load_zones(data_dir: &Path) -> Result<ZoneData> {
+    /// Load zones from TOML file, or the defaults if missing, written to it
+    /// with `create`.
+    async fn load_zones(data_dir: &Path, create: bool) -> Result<ZoneData> {
         let path = data_dir.join("zones.toml");
 
         if !path.exists() {
-            debug!("Zones file not found. Creating with defaults.");
             let data = ZoneData::default();
-            Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            if create {
+                debug!("Zones file not found. Creating with defaults.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
             return Ok(data);
         }
 
@@ -236,14 +292,17 @@ impl RuntimeConfig {
         Ok(())
     }
 
-    /// Load CFM mappings from TOML file, creating empty if missing.
-    async fn load_cfm_mappings(data_dir: &Path) -> Result<CfmMappingData> {
+    /// Load CFM mappings from TOML file, or none if missing, creating it empty
+    /// with `create`.
+    async fn load_cfm_mappings(data_dir: &Path, create: bool) -> Result<CfmMappingData> {
         let path = data_dir.join("cfm_mappings.toml");
 
         if !path.exists() {
-            debug!("CFM mappings file not found. Creating empty.");
             let data = CfmMappingData::default();
-            Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            if create {
+                debug!("CFM mappings file not found. Creating empty.");
+                Self::write_toml(&path, &data.to_toml().unwrap()).await?;
+            }
             return Ok(data);
         }
 
@@ -274,14 +333,29 @@ impl RuntimeConfig {
     // Static config access (read-only)
     // =========================================================================
 
-    /// Get reference to static configuration.
-    pub fn static_config(&self) -> &StaticConfig {
-        &self.static_config
+    /// Get the static configuration in use.
+    ///
+    /// A reload replaces it, so keep the returned config only as long as
+    /// consistent settings are needed.
+    pub fn static_config(&self) -> Arc<StaticConfig> {
+        self.static_config.read().unwrap().clone()
+    }
+
+    /// Get the path of `config.toml`.
+    pub fn config_path(&self) -> &Path {
+        &self.config_path
     }
 
     /// Get data directory path.
     pub fn data_dir(&self) -> &Path {
-        &self.static_config.data_dir
+        &self.data_dir
+    }
+
+    /// Subscribe to saved changes of zones and per-controller data.
+    ///
+    /// Each save sends a [`TelemetryEvent::ConfigChanged`].
+    pub fn subscribe_changes(&self) -> broadcast::Receiver<TelemetryEvent> {
+        self.changes.subscribe()
     }
 
     // =========================================================================
@@ -318,7 +392,9 @@ impl RuntimeConfig {
         }
 
         // Need to create new controller data
-        let cd = ControllerData::load(controller_id, self.data_dir()).await?;
+        let cd = ControllerData::load(controller_id, self.data_dir())
+            .await?
+            .with_changes(self.changes.clone());
         let cd = Arc::new(cd);
 
         // Store in cache
@@ -334,6 +410,182 @@ impl RuntimeConfig {
         Ok(cd)
     }
 
+    /// Forget the data of a removed controller.
+    ///
+    /// It is loaded from disk again if the controller comes back.
+    pub async fn remove_controller_data(&self, controller_id: &str) {
+        self.controller_data.write().await.remove(controller_id);
+    }
+
+    // =========================================================================
+    // Controller entries in config.toml
+    // =========================================================================
+
+    /// Add a controller entry to `[[controllers]]`.
+    ///
+    /// The entry is appended to `config.toml` as a new table, leaving the rest
+    /// of the file and its comments as written, and to the static config in use.
+    pub async fn add_controller_entry(&self, controller: &ControllerConfig) -> Result<()> {
+        let table = toml::to_string(controller).map_err(|e| {
+            OpenFanError::Config(format!("Failed to serialize controller entry: {}", e))
+        })?;
+        self.edit_controllers(
+            |content| {
+                let content = content.trim_end();
+                if content.is_empty() {
+                    format!("[[controllers]]\n{}", table)
+                } else {
+                    format!("{}\n\n[[controllers]]\n{}", content, table)
+                }
+            },
+            |controllers| controllers.push(controller.clone()),
+        )
+        .await
+    }
+
+    /// Remove a controller entry from `[[controllers]]`.
+    ///
+    /// The entry's table is cut out of `config.toml`, with the comments right
+    /// above it, and the entry is dropped from the static config in use.
+    pub async fn remove_controller_entry(&self, controller_id: &str) -> Result<()> {
+        self.edit_controllers(
+            |content| remove_controller_table(content, controller_id),
+            |controllers| controllers.retain(|ctrl| ctrl.id != controller_id),
+        )
+        .await
+    }
+
+    /// Edit `[[controllers]]` in `config.toml` and in the static config in use.
+    ///
+    /// The edited file must parse to the same controllers as `edit` gives, so
+    /// an edit that went wrong is never written.
+    async fn edit_controllers(
+        &self,
+        edit_text: impl FnOnce(&str) -> String,
+        edit: impl Fn(&mut Vec<ControllerConfig>),
+    ) -> Result<()> {
+        let content = fs::read_to_string(&self.config_path)
+            .await
+            .map_err(|e| OpenFanError::Config(format!("Failed to read config file: {}", e)))?;
+        let mut expected = StaticConfig::from_toml(&content)
+            .map_err(|e| OpenFanError::Config(format!("Failed to parse config file: {}", e)))?
+            .controllers;
+        edit(&mut expected);
+
+        let edited = edit_text(&content);
+        let parsed = StaticConfig::from_toml(&edited).map_err(|e| {
+            OpenFanError::Config(format!("Failed to update [[controllers]]: {}", e))
+        })?;
+        if differs(&parsed.controllers, &expected) {
+            return Err(OpenFanError::Config(format!(
+                "Failed to update [[controllers]] in {}: edit it by hand instead",
+                self.config_path.display()
+            )));
+        }
+        parsed
+            .validate_controllers()
+            .map_err(|e| OpenFanError::Config(format!("Invalid [[controllers]] config: {}", e)))?;
+        Self::write_toml(&self.config_path, &edited).await?;
+
+        let mut static_config = (*self.static_config()).clone();
+        edit(&mut static_config.controllers);
+        *self.static_config.write().unwrap() = Arc::new(static_config);
+        let _ = self.changes.send(TelemetryEvent::ConfigChanged {
+            controller: None,
+            resource: ConfigResource::Config,
+        });
+
+        info!("Updated [[controllers]] in {}", self.config_path.display());
+        Ok(())
+    }
+
+    // =========================================================================
+    // Reload
+    // =========================================================================
+
+    /// Read `config.toml` and the data files again, without using them yet.
+    ///
+    /// Data files are read from the data directory in use, and per-controller
+    /// data only for the controllers whose data is loaded. Missing files read
+    /// as their defaults and are not created: nothing changes until the result
+    /// is given to [`Self::apply`].
+    pub async fn read_back(&self) -> Result<ReloadedConfig> {
+        info!(
+            "Reading configuration again from: {}",
+            self.config_path.display()
+        );
+
+        let static_config = Self::read_static_config(&self.config_path).await?;
+        let aliases = Self::load_aliases(&self.data_dir, false).await?;
+        let profiles = Self::load_profiles(&self.data_dir, false).await?;
+        let zones = Self::load_zones(&self.data_dir, false).await?;
+        let cfm_mappings = Self::load_cfm_mappings(&self.data_dir, false).await?;
+
+        let ids: Vec<String> = self.controller_data.read().await.keys().cloned().collect();
+        let mut controller_data = HashMap::new();
+        for id in ids {
+            let data = ControllerData::read(&id, &self.data_dir).await?;
+            controller_data.insert(id, data);
+        }
+
+        Ok(ReloadedConfig {
+            static_config,
+            aliases,
+            profiles,
+            zones,
+            cfm_mappings,
+            controller_data,
+        })
+    }
+
+    /// Put a configuration read by [`Self::read_back`] in use.
+    ///
+    /// Data is replaced in place, so holders of a controller's data see the
+    /// new content. Returns what changed, by controller ID for per-controller
+    /// data; each change is announced like a save.
+    pub async fn apply(&self, reloaded: ReloadedConfig) -> Vec<(Option<String>, ConfigResource)> {
+        let mut changed = Vec::new();
+
+        let static_config = Arc::new(reloaded.static_config);
+        let previous = std::mem::replace(
+            &mut *self.static_config.write().unwrap(),
+            static_config.clone(),
+        );
+        if differs(&*previous, &*static_config) {
+            changed.push((None, ConfigResource::Config));
+        }
+
+        if replace_changed(&self.aliases, reloaded.aliases).await {
+            changed.push((None, ConfigResource::Aliases));
+        }
+        if replace_changed(&self.profiles, reloaded.profiles).await {
+            changed.push((None, ConfigResource::Profiles));
+        }
+        if replace_changed(&self.zones, reloaded.zones).await {
+            changed.push((None, ConfigResource::Zones));
+        }
+        if replace_changed(&self.cfm_mappings, reloaded.cfm_mappings).await {
+            changed.push((None, ConfigResource::CfmMappings));
+        }
+        for (controller, resource) in &changed {
+            let _ = self.changes.send(TelemetryEvent::ConfigChanged {
+                controller: controller.clone(),
+                resource: *resource,
+            });
+        }
+
+        for (id, data) in reloaded.controller_data {
+            let Some(current) = self.controller_data.read().await.get(&id).cloned() else {
+                continue;
+            };
+            for resource in current.replace(data).await {
+                changed.push((Some(id.clone()), resource));
+            }
+        }
+
+        changed
+    }
+
     // =========================================================================
     // Profile access (used by shutdown handler)
     // =========================================================================
@@ -360,7 +612,7 @@ impl RuntimeConfig {
     /// Save zone data to disk.
     pub async fn save_zones(&self) -> Result<()> {
         let zones = self.zones.read().await;
-        let path = self.static_config.data_dir.join("zones.toml");
+        let path = self.data_dir.join("zones.toml");
 
         let content = zones
             .to_toml()
@@ -369,6 +621,10 @@ impl RuntimeConfig {
         Self::write_toml(&path, &content).await?;
 
         debug!("Saved zones to {}", path.display());
+        let _ = self.changes.send(TelemetryEvent::ConfigChanged {
+            controller: None,
+            resource: ConfigResource::Zones,
+        });
         Ok(())
     }
 
@@ -379,7 +635,7 @@ impl RuntimeConfig {
     /// Save alias data to disk (used internally by fill_defaults_for_board).
     async fn save_aliases(&self) -> Result<()> {
         let aliases = self.aliases.read().await;
-        let path = self.static_config.data_dir.join("aliases.toml");
+        let path = self.data_dir.join("aliases.toml");
 
         let content = aliases
             .to_toml()
@@ -397,78 +653,34 @@ impl RuntimeConfig {
 
     /// Validate configuration against detected board.
     ///
-    /// Checks that profiles, aliases, zones, and CFM mappings are compatible with the board's fan count.
+    /// Checks that profiles, aliases, and CFM mappings are compatible with the board's fan count.
+    /// Zones span controllers and are checked separately by [`Self::validate_zones`].
     pub async fn validate_for_board(&self, board: &BoardInfo) -> Result<()> {
-        let profiles = self.profiles.read().await;
-        let aliases = self.aliases.read().await;
-        let zones = self.zones.read().await;
-        let cfm_mappings = self.cfm_mappings.read().await;
-
-        // Validate profiles
-        for (name, profile) in &profiles.profiles {
-            if profile.values.len() > board.fan_count {
-                return Err(OpenFanError::Config(format!(
-                    "Profile '{}' has {} values but board '{}' only supports {} fans",
-                    name,
-                    profile.values.len(),
-                    board.name,
-                    board.fan_count
-                )));
-            }
-            if profile.values.len() < board.fan_count {
-                warn!(
-                    "Profile '{}' has {} values but board has {} fans (will use defaults for extra fans)",
-                    name,
-                    profile.values.len(),
-                    board.fan_count
-                );
-            }
-        }
-
-        // Validate aliases
-        if let Some(&max_id) = aliases.aliases.keys().max()
-            && max_id >= board.fan_count as u8
-        {
-            return Err(OpenFanError::Config(format!(
-                "Alias exists for fan {} but board '{}' only has {} fans (max ID: {})",
-                max_id,
-                board.name,
-                board.fan_count,
-                board.fan_count - 1
-            )));
-        }
+        check_board(
+            &*self.profiles.read().await,
+            &*self.aliases.read().await,
+            &*self.cfm_mappings.read().await,
+            board,
+        )
+    }
 
-        // Validate zones
-        // TODO: In multi-controller mode, validate against each controller's board info
-        for (name, zone) in &zones.zones {
-            for fan in &zone.fans {
-                if fan.fan_id >= board.fan_count as u8 {
-                    return Err(OpenFanError::Config(format!(
-                        "Zone '{}' references fan {} (controller: '{}') but board '{}' only has {} fans (max ID: {})",
-                        name,
-                        fan.fan_id,
-                        fan.controller,
-                        board.name,
-                        board.fan_count,
-                        board.fan_count - 1
-                    )));
-                }
-            }
-        }
+    /// Validate global zones against the registered controllers.
+    ///
+    /// Each zone fan is checked against its own controller's board, given as a
+    /// map of controller ID to board info. All problems are reported in a
+    /// single [`OpenFanError::InvalidZones`] error.
+    pub async fn validate_zones(&self, boards: &HashMap<String, BoardInfo>) -> Result<()> {
+        check_zones(&*self.zones.read().await, boards)
+    }
 
-        // Validate CFM mappings
-        if let Some(&max_port) = cfm_mappings.mappings.keys().max()
-            && max_port >= board.fan_count as u8
-        {
-            return Err(OpenFanError::Config(format!(
-                "CFM mapping exists for port {} but board '{}' only has {} fans (max ID: {})",
-                max_port,
-                board.name,
-                board.fan_count,
-                board.fan_count - 1
-            )));
+    /// Validate the fan limits of each controller against its board, given
+    /// as a map of controller ID to board info.
+    pub async fn validate_fan_limits(&self, boards: &HashMap<String, BoardInfo>) -> Result<()> {
+        let mut ids: Vec<_> = boards.keys().collect();
+        ids.sort();
+        for id in ids {
+            check_fan_limits(id, &*self.controller_data(id).await?, &boards[id]).await?;
         }
-
         Ok(())
     }
 
@@ -498,6 +710,238 @@ impl RuntimeConfig {
     }
 }
 
+/// Cut the `[[controllers]]` table with the given ID out of a TOML document.
+///
+/// Comment lines right above a table header are taken as part of that table.
+fn remove_controller_table(content: &str, controller_id: &str) -> String {
+    // Split the document before each table header, with its comments
+    let mut tables: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in content.lines() {
+        if is_table_header(line) {
+            let previous = tables.last_mut().unwrap();
+            let comments = previous
+                .iter()
+                .rposition(|line| !line.trim_start().starts_with('#'))
+                .map_or(0, |last| last + 1);
+            let table = previous.split_off(comments);
+            tables.push(table);
+        }
+        tables.last_mut().unwrap().push(line);
+    }
+
+    let is_removed = |table: &Vec<&str>| {
+        let mut lines = table.iter().skip_while(|line| !is_table_header(line));
+        if lines.next().map(|header| header.trim()) != Some("[[controllers]]") {
+            return false;
+        }
+        let body = lines.copied().collect::<Vec<_>>().join("\n");
+        toml::from_str::<ControllerConfig>(&body).is_ok_and(|ctrl| ctrl.id == controller_id)
+    };
+
+    let mut lines: Vec<&str> = tables
+        .iter()
+        .filter(|table| !is_removed(table))
+        .flatten()
+        .copied()
+        .collect();
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    let mut edited = lines.join("\n");
+    edited.push('\n');
+    edited
+}
+
+/// Whether a line starts a table, as `[name]` or `[[name]]`
+fn is_table_header(line: &str) -> bool {
+    let line = line.split('#').next().unwrap_or_default().trim();
+    line.starts_with('[') && line.ends_with(']') && !line.contains([',', '='])
+}
+
+/// Check global profiles, aliases and CFM mappings against a board's fan count.
+fn check_board(
+    profiles: &ProfileData,
+    aliases: &AliasData,
+    cfm_mappings: &CfmMappingData,
+    board: &BoardInfo,
+) -> Result<()> {
+    // Validate profiles
+    for (name, profile) in &profiles.profiles {
+        if profile.values.len() > board.fan_count {
+            return Err(OpenFanError::Config(format!(
+                "Profile '{}' has {} values but board '{}' only supports {} fans",
+                name,
+                profile.values.len(),
+                board.name,
+                board.fan_count
+            )));
+        }
+        if profile.values.len() < board.fan_count {
+            warn!(
+                "Profile '{}' has {} values but board has {} fans (will use defaults for extra fans)",
+                name,
+                profile.values.len(),
+                board.fan_count
+            );
+        }
+    }
+
+    // Validate aliases
+    if let Some(&max_id) = aliases.aliases.keys().max()
+        && max_id >= board.fan_count as u8
+    {
+        return Err(OpenFanError::Config(format!(
+            "Alias exists for fan {} but board '{}' only has {} fans (max ID: {})",
+            max_id,
+            board.name,
+            board.fan_count,
+            board.fan_count - 1
+        )));
+    }
+
+    // Validate CFM mappings
+    if let Some(max_port) = cfm_mappings.max_port()
+        && max_port >= board.fan_count as u8
+    {
+        return Err(OpenFanError::Config(format!(
+            "CFM mapping exists for port {} but board '{}' only has {} fans (max ID: {})",
+            max_port,
+            board.name,
+            board.fan_count,
+            board.fan_count - 1
+        )));
+    }
+
+    Ok(())
+}
+
+/// Check zone fans against the boards of their controllers.
+pub(crate) fn check_zones(zones: &ZoneData, boards: &HashMap<String, BoardInfo>) -> Result<()> {
+    let problems = zones.validate_fans(boards);
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(OpenFanError::InvalidZones(problems))
+    }
+}
+
+/// Check a controller's curve bindings against what they reference.
+///
+/// The curve or PID controller must exist on the same controller, the zone
+/// among the global zones, the fans on the controller's board when it is
+/// known, and the sensor among `sensors` unless it is a file path.
+async fn check_bindings(
+    controller_id: &str,
+    data: &ControllerData,
+    zones: &ZoneData,
+    board: Option<&BoardInfo>,
+    sensors: &HashSet<String>,
+) -> Result<()> {
+    let bindings = data.curve_bindings().await;
+    let curves = data.thermal_curves().await;
+    let pids = data.pid_controllers().await;
+
+    let mut names: Vec<_> = bindings.names().collect();
+    names.sort();
+    for name in names {
+        let binding = &bindings.bindings[name];
+        let problem = if let Some(curve) = binding.curve.as_ref().filter(|c| !curves.contains(c)) {
+            Some(format!("thermal curve '{}' not found", curve))
+        } else if let Some(pid) = binding.pid.as_ref().filter(|p| !pids.contains(p)) {
+            Some(format!("PID controller '{}' not found", pid))
+        } else if let Some(zone) = binding.zone.as_ref().filter(|z| !zones.contains(z)) {
+            Some(format!("zone '{}' not found", zone))
+        } else if !Path::new(&binding.sensor).is_absolute() && !sensors.contains(&binding.sensor) {
+            Some(format!("sensor '{}' not found", binding.sensor))
+        } else {
+            board.and_then(|board| {
+                binding
+                    .fans
+                    .iter()
+                    .find_map(|&fan_id| board.validate_fan_id(fan_id).err())
+                    .map(|e| e.to_string())
+            })
+        };
+        if let Some(problem) = problem {
+            return Err(OpenFanError::Config(format!(
+                "Controller '{}': binding '{}': {}",
+                controller_id, name, problem
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check a controller's fan limits against its board.
+async fn check_fan_limits(
+    controller_id: &str,
+    data: &ControllerData,
+    board: &BoardInfo,
+) -> Result<()> {
+    data.fan_limits().await.validate(Some(board)).map_err(|e| {
+        OpenFanError::Config(format!(
+            "Controller '{}': invalid fan limits: {}",
+            controller_id, e
+        ))
+    })
+}
+
+/// Configuration read back from disk by [`RuntimeConfig::read_back`], not in use yet
+pub(crate) struct ReloadedConfig {
+    /// New static configuration
+    pub static_config: StaticConfig,
+    aliases: AliasData,
+    profiles: ProfileData,
+    zones: ZoneData,
+    cfm_mappings: CfmMappingData,
+    /// Data of the controllers whose data is loaded, by controller ID
+    controller_data: HashMap<String, ControllerData>,
+}
+
+impl ReloadedConfig {
+    /// Validate the configuration like at startup.
+    ///
+    /// Global profiles, aliases and CFM mappings are checked against the
+    /// default controller's board, and zones against the boards of the
+    /// controllers that will be registered, by controller ID. Thermal curves,
+    /// PID controllers and bindings were each validated as they were read;
+    /// bindings are also checked against the curves, PID controllers, zones,
+    /// fans and sensors (IDs given in `sensors`) they reference, and fan
+    /// limits against the controller's board.
+    pub async fn validate(
+        &self,
+        default_board: &BoardInfo,
+        boards: &HashMap<String, BoardInfo>,
+        sensors: &HashSet<String>,
+    ) -> Result<()> {
+        check_board(
+            &self.profiles,
+            &self.aliases,
+            &self.cfm_mappings,
+            default_board,
+        )?;
+        check_zones(&self.zones, boards)?;
+
+        let mut ids: Vec<_> = self.controller_data.keys().collect();
+        ids.sort();
+        for id in ids {
+            check_bindings(
+                id,
+                &self.controller_data[id],
+                &self.zones,
+                boards.get(id),
+                sensors,
+            )
+            .await?;
+            if let Some(board) = boards.get(id) {
+                check_fan_limits(id, &self.controller_data[id], board).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -538,6 +982,33 @@ mod tests {
         assert!(profiles.contains("1000 RPM"));
     }
 
+    #[tokio::test]
+    async fn test_runtime_config_rejects_invalid_sensors() {
+        use openfan_core::{CustomSensorConfig, SensorSource};
+
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+
+        let sensor = CustomSensorConfig {
+            id: "ambient".to_string(),
+            label: None,
+            source: SensorSource::File {
+                path: PathBuf::from("/tmp/ambient"),
+                scale: 1.0,
+            },
+        };
+        let mut config = StaticConfig::with_data_dir(temp_dir.path().join("data"));
+        config.sensors.custom = vec![sensor.clone(), sensor];
+        fs::write(&config_path, config.to_toml().unwrap())
+            .await
+            .unwrap();
+
+        let Err(err) = RuntimeConfig::load(&config_path).await else {
+            panic!("Expected duplicate sensor IDs to be rejected");
+        };
+        assert!(err.to_string().contains("Duplicate custom sensor ID"));
+    }
+
     #[tokio::test]
     async fn test_runtime_config_zone_operations() {
         use openfan_core::ZoneFan;
@@ -575,24 +1046,142 @@ mod tests {
         assert_eq!(intake.description, Some("Front intake fans".to_string()));
     }
 
-    #[tokio::test]
-    async fn test_validate_for_board_valid_zone() {
-        use openfan_core::ZoneFan;
+    fn test_boards() -> HashMap<String, openfan_core::BoardInfo> {
         use openfan_core::board::BoardType;
 
+        HashMap::from([
+            (
+                "default".to_string(),
+                BoardType::OpenFanStandard.to_board_info(),
+            ),
+            (
+                "gpu".to_string(),
+                BoardType::Custom { fan_count: 4 }.to_board_info(),
+            ),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_runtime_config_announces_saved_changes() {
         let temp_dir = TempDir::new().unwrap();
         let config_path = create_test_config(temp_dir.path()).await;
         let config = RuntimeConfig::load(&config_path).await.unwrap();
+        let mut changes = config.subscribe_changes();
 
-        // Standard board has 10 fans
-        let board = BoardType::OpenFanStandard.to_board_info();
+        config.save_zones().await.unwrap();
+        let data = config.controller_data("main").await.unwrap();
+        data.save_cfm_mappings().await.unwrap();
+        // Not configuration: not announced
+        data.save_fan_state(&Default::default()).await.unwrap();
+
+        let event = changes.try_recv().unwrap();
+        assert!(matches!(
+            event,
+            TelemetryEvent::ConfigChanged {
+                controller: None,
+                resource: ConfigResource::Zones
+            }
+        ));
+        let event = changes.try_recv().unwrap();
+        assert_eq!(event.controller(), Some("main"));
+        assert!(matches!(
+            event,
+            TelemetryEvent::ConfigChanged {
+                resource: ConfigResource::CfmMappings,
+                ..
+            }
+        ));
+        assert!(changes.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_runtime_config_read_back_and_apply() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = create_test_config(temp_dir.path()).await;
+        let config = RuntimeConfig::load(&config_path).await.unwrap();
+        let data = config.controller_data("main").await.unwrap();
 
-        // Add valid zone (all fans within 10-fan limit)
+        // Edit config.toml and a controller's aliases by hand
+        let mut static_config = StaticConfig::with_data_dir(temp_dir.path().join("data"));
+        static_config.alarms.enabled = false;
+        fs::write(&config_path, static_config.to_toml().unwrap())
+            .await
+            .unwrap();
+        let other = ControllerData::load("main", config.data_dir())
+            .await
+            .unwrap();
+        other.aliases_mut().await.set(2, "Rear".to_string());
+        other.save_aliases().await.unwrap();
+
+        let reloaded = config.read_back().await.unwrap();
+        let mut changes = config.subscribe_changes();
+        let changed = config.apply(reloaded).await;
+        assert_eq!(
+            changed,
+            vec![
+                (None, ConfigResource::Config),
+                (Some("main".to_string()), ConfigResource::Aliases),
+            ]
+        );
+        assert!(!config.static_config().alarms.enabled);
+        assert_eq!(data.aliases().await.get(2), "Rear");
+        assert!(matches!(
+            changes.try_recv().unwrap(),
+            TelemetryEvent::ConfigChanged {
+                controller: None,
+                resource: ConfigResource::Config
+            }
+        ));
+
+        // Applying the same files again changes nothing
+        let reloaded = config.read_back().await.unwrap();
+        assert!(config.apply(reloaded).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_runtime_config_read_back_creates_no_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = create_test_config(temp_dir.path()).await;
+        let config = RuntimeConfig::load(&config_path).await.unwrap();
+        config.controller_data("main").await.unwrap();
+
+        // Missing files read as their defaults, and are left missing
+        let data_dir = config.data_dir().to_path_buf();
+        let controller_dir = data_dir.join("controllers").join("main");
+        let files = [
+            data_dir.join("aliases.toml"),
+            data_dir.join("profiles.toml"),
+            data_dir.join("zones.toml"),
+            data_dir.join("cfm_mappings.toml"),
+            controller_dir.join("aliases.toml"),
+            controller_dir.join("profiles.toml"),
+            controller_dir.join("fan_limits.toml"),
+        ];
+        for file in &files {
+            fs::remove_file(file).await.unwrap();
+        }
+
+        let reloaded = config.read_back().await.unwrap();
+        for file in &files {
+            assert!(!file.exists(), "{}", file.display());
+        }
+        assert!(config.apply(reloaded).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_validate_zones_valid() {
+        use openfan_core::ZoneFan;
+
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = create_test_config(temp_dir.path()).await;
+        let config = RuntimeConfig::load(&config_path).await.unwrap();
+
+        // Fans within each controller's own limit
         {
             let fans = vec![
                 ZoneFan::new("default", 0),
-                ZoneFan::new("default", 1),
-                ZoneFan::new("default", 2),
+                ZoneFan::new("default", 9),
+                ZoneFan::new("gpu", 3),
             ];
             let mut zones = config.zones_mut().await;
             zones.insert(
@@ -602,39 +1191,159 @@ mod tests {
         }
 
         // Validation should pass
-        assert!(config.validate_for_board(&board).await.is_ok());
+        assert!(config.validate_zones(&test_boards()).await.is_ok());
     }
 
     #[tokio::test]
-    async fn test_validate_for_board_invalid_zone() {
+    async fn test_validate_zones_reports_all_problems() {
         use openfan_core::ZoneFan;
-        use openfan_core::board::BoardType;
 
         let temp_dir = TempDir::new().unwrap();
         let config_path = create_test_config(temp_dir.path()).await;
         let config = RuntimeConfig::load(&config_path).await.unwrap();
 
-        // Standard board has 10 fans (IDs 0-9)
-        let board = BoardType::OpenFanStandard.to_board_info();
-
-        // Add zone referencing fan 15 (invalid)
-        let fans = vec![ZoneFan::new("default", 0), ZoneFan::new("default", 15)];
         {
             let mut zones = config.zones_mut().await;
+            // Fan 15 is beyond the standard board, fan 4 beyond the 4-fan GPU board
             zones.insert(
                 "invalid".to_string(),
-                openfan_core::Zone::new("invalid", fans),
+                openfan_core::Zone::new(
+                    "invalid",
+                    vec![
+                        ZoneFan::new("default", 0),
+                        ZoneFan::new("default", 15),
+                        ZoneFan::new("gpu", 4),
+                    ],
+                ),
+            );
+            zones.insert(
+                "orphan".to_string(),
+                openfan_core::Zone::new("orphan", vec![ZoneFan::new("ghost", 0)]),
             );
         }
 
-        // Validation should fail
-        let result = config.validate_for_board(&board).await;
-        assert!(result.is_err());
-        assert!(
-            result
-                .unwrap_err()
-                .to_string()
-                .contains("Zone 'invalid' references fan 15")
-        );
+        let err = config.validate_zones(&test_boards()).await.unwrap_err();
+        let OpenFanError::InvalidZones(problems) = &err else {
+            panic!("Expected InvalidZones error, got {:?}", err);
+        };
+        assert_eq!(problems.len(), 3);
+
+        let message = err.to_string();
+        assert!(message.contains(
+            "zone 'invalid': fan 15 is out of range for controller 'default' (10 fans, max ID: 9)"
+        ));
+        assert!(message.contains("zone 'invalid': fan 4 is out of range for controller 'gpu'"));
+        assert!(message.contains("zone 'orphan': fan 0 references unknown controller 'ghost'"));
+    }
+
+    #[tokio::test]
+    async fn test_validate_for_board_ignores_zones() {
+        use openfan_core::ZoneFan;
+        use openfan_core::board::BoardType;
+
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = create_test_config(temp_dir.path()).await;
+        let config = RuntimeConfig::load(&config_path).await.unwrap();
+
+        // A 16-fan controller next to a 10-fan default board
+        {
+            let mut zones = config.zones_mut().await;
+            zones.insert(
+                "big".to_string(),
+                openfan_core::Zone::new("big", vec![ZoneFan::new("big", 15)]),
+            );
+        }
+
+        let board = BoardType::OpenFanStandard.to_board_info();
+        assert!(config.validate_for_board(&board).await.is_ok());
+    }
+
+    #[test]
+    fn test_remove_controller_table_keeps_comments() {
+        let content = "\
+# Server settings
+data_dir = \"/tmp/openfan\"
+
+# Chassis board
+[[controllers]]
+id = \"main\" # by path
+device = \"/dev/ttyACM0\"
+board = \"standard\"
+
+# GPU board, by serial
+[[controllers]]
+id = \"gpu\"
+serial_number = \"E6614103E7452D30\"
+board = \"custom:4\"
+
+# Alerts
+[alarms]
+enabled = true
+";
+        let edited = remove_controller_table(content, "gpu");
+        assert!(!edited.contains("gpu"));
+        assert!(!edited.contains("GPU board"));
+        assert!(edited.contains("# Chassis board\n[[controllers]]\nid = \"main\" # by path"));
+        assert!(edited.contains("# Alerts\n[alarms]"));
+
+        let config = StaticConfig::from_toml(&edited).unwrap();
+        assert_eq!(config.controllers.len(), 1);
+        assert!(config.alarms.enabled);
+
+        // An unknown ID leaves the document as it was
+        assert_eq!(remove_controller_table(content, "ghost"), content);
+    }
+
+    #[tokio::test]
+    async fn test_controller_entry_round_trip() {
+        use openfan_core::board::BoardType;
+
+        let temp_dir = TempDir::new().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+        fs::write(
+            &config_path,
+            format!(
+                "# Keep me\ndata_dir = \"{}\"\n\n[[controllers]]\nid = \"main\"\n\
+                 device = \"/dev/ttyACM0\"\nboard = \"standard\"\n",
+                temp_dir
+                    .path()
+                    .join("data")
+                    .to_string_lossy()
+                    .replace('\\', "\\\\")
+            ),
+        )
+        .await
+        .unwrap();
+        let config = RuntimeConfig::load(&config_path).await.unwrap();
+        let mut changes = config.subscribe_changes();
+
+        let mut gpu =
+            ControllerConfig::new("gpu", "/dev/ttyACM1", BoardType::Custom { fan_count: 4 });
+        gpu.description = Some("GPU fans".to_string());
+        config.add_controller_entry(&gpu).await.unwrap();
+        assert!(matches!(
+            changes.recv().await.unwrap(),
+            TelemetryEvent::ConfigChanged {
+                controller: None,
+                resource: ConfigResource::Config,
+            }
+        ));
+
+        let reloaded = RuntimeConfig::load(&config_path).await.unwrap();
+        let controllers = &reloaded.static_config().controllers;
+        assert_eq!(controllers.len(), 2);
+        assert_eq!(controllers[1].device, "/dev/ttyACM1");
+        assert_eq!(controllers[1].board, gpu.board);
+        assert_eq!(controllers[1].description.as_deref(), Some("GPU fans"));
+        assert_eq!(config.static_config().controllers[1].id, "gpu");
+
+        // Duplicates fail validation and leave the file alone
+        assert!(config.add_controller_entry(&gpu).await.is_err());
+
+        config.remove_controller_entry("gpu").await.unwrap();
+        let content = fs::read_to_string(&config_path).await.unwrap();
+        assert!(content.starts_with("# Keep me\n"));
+        assert!(!content.contains("gpu"));
+        assert_eq!(config.static_config().controllers.len(), 1);
     }
 }
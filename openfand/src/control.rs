@@ -0,0 +1,783 @@
+//! Closed-loop thermal control
+//!
+//! Runs one background task per curve binding. Each task periodically reads
+//! the binding's temperature sensor through the [`SensorManager`], evaluates
+//! the bound thermal curve or PID controller and applies the resulting PWM to
+//! the binding's fans through the controller's `ConnectionManager`.
+//!
+//! Curves, PID controllers and zones are looked up on every step, so edits
+//! made through the API take effect on the next poll without restarting the
+//! loop. Each loop keeps a [`CurveState`] so the curve's smoothing, hysteresis
+//! and ramp limits carry over between polls, and a [`PidState`] holding the
+//! PID integral and last error, which is reported in the binding status. The
+//! PWM is only written to a fan when it differs from the value last commanded
+//! to that fan, so a fan changed by a manual set, profile or zone is brought
+//! back on the next poll.
+//!
+//! While a failsafe alarm is active on a controller, or a fan calibration runs
+//! on it, its loops are held: stopped, and not started again, even by a
+//! reload, until every [`Hold`] is released.
+
+use crate::config::RuntimeConfig;
+use crate::controllers::ControllerRegistry;
+use crate::limits::FanLimiter;
+use crate::sensors::SensorManager;
+use openfan_core::{CurveBinding, CurveState, OpenFanError, PidState, Result, api::BindingStatus};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info, warn};
+
+/// Key identifying a binding: (controller ID, binding name)
+type BindingKey = (String, String);
+
+/// Last observed outcome of a binding's control loop
+#[derive(Debug, Clone, Default)]
+struct LoopState {
+    temperature: Option<f32>,
+    pwm: Option<u8>,
+    error: Option<String>,
+    pid: Option<PidState>,
+}
+
+/// State a control loop carries from one step to the next
+#[derive(Debug, Default)]
+struct LoopMemory {
+    /// Smoothing, hysteresis and ramp state of the bound curve
+    curve: CurveState,
+    /// Integral and last error of the bound PID controller
+    pid: PidState,
+    /// Time of the last update, for time-based smoothing, ramps and PID terms
+    last_update: Option<Instant>,
+}
+
+/// Why a controller's control loops are held
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Hold {
+    /// A fan alarm applied the failsafe profile
+    Failsafe,
+    /// A fan calibration is sweeping the fans
+    Calibration,
+}
+
+impl std::fmt::Display for Hold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Failsafe => write!(f, "a failsafe alarm"),
+            Self::Calibration => write!(f, "a fan calibration"),
+        }
+    }
+}
+
+/// Manages the background control loops for all curve bindings.
+pub(crate) struct ControlEngine {
+    registry: Arc<ControllerRegistry>,
+    config: Arc<RuntimeConfig>,
+    sensors: Arc<SensorManager>,
+    tasks: Mutex<HashMap<BindingKey, JoinHandle<()>>>,
+    states: Arc<RwLock<HashMap<BindingKey, LoopState>>>,
+    /// Controllers whose loops are held, with what holds them
+    held: Mutex<HashMap<String, HashSet<Hold>>>,
+}
+
+impl ControlEngine {
+    /// Create a new control engine with no running loops.
+    pub fn new(
+        registry: Arc<ControllerRegistry>,
+        config: Arc<RuntimeConfig>,
+        sensors: Arc<SensorManager>,
+    ) -> Self {
+        Self {
+            registry,
+            config,
+            sensors,
+            tasks: Mutex::new(HashMap::new()),
+            states: Arc::new(RwLock::new(HashMap::new())),
+            held: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Start control loops for every persisted binding of every registered controller.
+    pub async fn start_all(&self) {
+        for entry in self.registry.list().await {
+            self.start_controller(entry.id()).await;
+        }
+    }
+
+    /// Start control loops for every persisted binding of a controller.
+    async fn start_controller(&self, controller_id: &str) {
+        let controller_data = match self.config.controller_data(controller_id).await {
+            Ok(data) => data,
+            Err(e) => {
+                warn!(
+                    "Controller '{}': Failed to load curve bindings: {}",
+                    controller_id, e
+                );
+                return;
+            }
+        };
+
+        let bindings: Vec<CurveBinding> = controller_data
+            .curve_bindings()
+            .await
+            .bindings
+            .values()
+            .cloned()
+            .collect();
+
+        for binding in bindings {
+            self.start(controller_id, binding).await;
+        }
+    }
+
+    /// Start (or restart) the control loop for a binding.
+    ///
+    /// Does nothing while the controller's loops are held.
+    pub async fn start(&self, controller_id: &str, binding: CurveBinding) {
+        let key = (controller_id.to_string(), binding.name.clone());
+
+        let held = self.held.lock().await;
+        if let Some(hold) = held
+            .get(controller_id)
+            .and_then(|holds| holds.iter().next())
+        {
+            info!(
+                "Controller '{}': Control loop '{}' held by {}",
+                controller_id, binding.name, hold
+            );
+            return;
+        }
+        let mut tasks = self.tasks.lock().await;
+        if let Some(handle) = tasks.remove(&key) {
+            handle.abort();
+        }
+        self.states.write().await.remove(&key);
+
+        info!(
+            "Controller '{}': Starting control loop '{}' ({}, sensor '{}', every {}s)",
+            controller_id,
+            binding.name,
+            binding.strategy(),
+            binding.sensor,
+            binding.interval_secs
+        );
+
+        let handle = tokio::spawn(run_loop(
+            self.registry.clone(),
+            self.config.clone(),
+            self.sensors.clone(),
+            self.states.clone(),
+            controller_id.to_string(),
+            binding,
+        ));
+        tasks.insert(key, handle);
+    }
+
+    /// Stop the control loop for a binding.
+    ///
+    /// Returns `true` if a loop was running.
+    pub async fn stop(&self, controller_id: &str, name: &str) -> bool {
+        let key = (controller_id.to_string(), name.to_string());
+
+        self.states.write().await.remove(&key);
+        match self.tasks.lock().await.remove(&key) {
+            Some(handle) => {
+                handle.abort();
+                info!(
+                    "Controller '{}': Stopped control loop '{}'",
+                    controller_id, name
+                );
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Stop a controller's control loops and hold them until [`Self::release`]
+    /// is called with the same `hold`.
+    ///
+    /// Used while a failsafe alarm is active or a calibration runs, so the
+    /// loops don't override the failsafe profile or the sweep.
+    pub async fn hold(&self, controller_id: &str, hold: Hold) {
+        let mut held = self.held.lock().await;
+        let holds = held.entry(controller_id.to_string()).or_default();
+        if !holds.insert(hold) || holds.len() > 1 {
+            return;
+        }
+        let mut tasks = self.tasks.lock().await;
+        let mut states = self.states.write().await;
+        tasks.retain(|(id, _), handle| {
+            if id != controller_id {
+                return true;
+            }
+            handle.abort();
+            false
+        });
+        states.retain(|(id, _), _| id != controller_id);
+        warn!(
+            "Controller '{}': Control loops held by {}",
+            controller_id, hold
+        );
+    }
+
+    /// Lift a hold of a controller, starting its control loops again once
+    /// nothing holds them.
+    pub async fn release(&self, controller_id: &str, hold: Hold) {
+        {
+            let mut held = self.held.lock().await;
+            let Some(holds) = held.get_mut(controller_id) else {
+                return;
+            };
+            if !holds.remove(&hold) || !holds.is_empty() {
+                return;
+            }
+            held.remove(controller_id);
+        }
+        info!("Controller '{}': Control loops released", controller_id);
+        self.start_controller(controller_id).await;
+    }
+
+    /// Stop all control loops.
+    ///
+    /// Called before the shutdown profile is applied so the loops don't
+    /// override it.
+    pub async fn stop_all(&self) {
+        let mut tasks = self.tasks.lock().await;
+        for (_, handle) in tasks.drain() {
+            handle.abort();
+        }
+        self.states.write().await.clear();
+    }
+
+    /// Build the API status for a binding.
+    pub async fn status(&self, controller_id: &str, binding: &CurveBinding) -> BindingStatus {
+        let key = (controller_id.to_string(), binding.name.clone());
+
+        let running = self
+            .tasks
+            .lock()
+            .await
+            .get(&key)
+            .is_some_and(|handle| !handle.is_finished());
+        let state = self
+            .states
+            .read()
+            .await
+            .get(&key)
+            .cloned()
+            .unwrap_or_default();
+
+        let held = self.held.lock().await;
+        let error = match held
+            .get(controller_id)
+            .and_then(|holds| holds.iter().next())
+        {
+            Some(hold) => Some(format!("Held by {}", hold)),
+            None => state.error,
+        };
+
+        BindingStatus {
+            binding: binding.clone(),
+            running,
+            temperature: state.temperature,
+            pwm: state.pwm,
+            error,
+            pid_state: state.pid,
+        }
+    }
+}
+
+/// Control loop for a single binding.
+async fn run_loop(
+    registry: Arc<ControllerRegistry>,
+    config: Arc<RuntimeConfig>,
+    sensors: Arc<SensorManager>,
+    states: Arc<RwLock<HashMap<BindingKey, LoopState>>>,
+    controller_id: String,
+    binding: CurveBinding,
+) {
+    let key = (controller_id.clone(), binding.name.clone());
+
+    // A zero interval would panic the ticker; bindings are validated on load
+    // and on add, so this only guards against a bad caller.
+    if let Err(message) = binding.validate() {
+        warn!(
+            "Controller '{}': Not starting control loop '{}': {}",
+            controller_id, binding.name, message
+        );
+        states.write().await.entry(key).or_default().error = Some(message);
+        return;
+    }
+
+    let mut ticker = tokio::time::interval(Duration::from_secs(binding.interval_secs));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut memory = LoopMemory::default();
+
+    loop {
+        ticker.tick().await;
+
+        let result = control_step(
+            &registry,
+            &config,
+            &sensors,
+            &controller_id,
+            &binding,
+            &mut memory,
+        )
+        .await;
+
+        let mut states = states.write().await;
+        let state = states.entry(key.clone()).or_default();
+        match result {
+            Ok((temperature, pwm)) => {
+                state.temperature = Some(temperature);
+                state.pwm = Some(pwm);
+                state.error = None;
+                if binding.pid.is_some() {
+                    state.pid = Some(memory.pid.clone());
+                }
+            }
+            Err(e) => {
+                let message = e.to_string();
+                // Only log when the error changes to avoid flooding the log every poll
+                if state.error.as_deref() != Some(message.as_str()) {
+                    warn!(
+                        "Controller '{}': Control loop '{}' failed: {}",
+                        controller_id, binding.name, message
+                    );
+                }
+                state.error = Some(message);
+            }
+        }
+    }
+}
+
+/// Run a single control step: read, evaluate the curve or PID controller, apply.
+///
+/// Returns the temperature read and the PWM computed from the curve or PID.
+/// Each fan gets the PWM moved into its safety limits; when limits are
+/// enforced by rejecting, a PWM outside them fails the step.
+async fn control_step(
+    registry: &ControllerRegistry,
+    config: &RuntimeConfig,
+    sensors: &SensorManager,
+    controller_id: &str,
+    binding: &CurveBinding,
+    memory: &mut LoopMemory,
+) -> Result<(f32, u8)> {
+    let temperature = sensors.read(&binding.sensor).await?;
+
+    let controller_data = config.controller_data(controller_id).await?;
+    let now = Instant::now();
+    let elapsed_secs = memory
+        .last_update
+        .map_or(0.0, |t| now.duration_since(t).as_secs_f32());
+    let pwm = match (&binding.curve, &binding.pid) {
+        (Some(curve), _) => {
+            let curves = controller_data.thermal_curves().await;
+            let curve = curves
+                .get(curve)
+                .ok_or_else(|| OpenFanError::CurveNotFound(curve.clone()))?;
+            memory.curve.update(curve, temperature, elapsed_secs)
+        }
+        (None, Some(pid)) => {
+            let controllers = controller_data.pid_controllers().await;
+            let pid = controllers
+                .get(pid)
+                .ok_or_else(|| OpenFanError::PidNotFound(pid.clone()))?;
+            memory.pid.update(pid, temperature, elapsed_secs)
+        }
+        (None, None) => {
+            return Err(OpenFanError::InvalidInput(format!(
+                "Binding '{}' has no curve or PID",
+                binding.name
+            )));
+        }
+    };
+    memory.last_update = Some(now);
+
+    let fans = {
+        let zones = config.zones().await;
+        binding
+            .resolve_fans(controller_id, &zones)
+            .ok_or_else(|| OpenFanError::ZoneNotFound(binding.zone.clone().unwrap_or_default()))?
+    };
+
+    let entry = registry.get_or_err(controller_id).await?;
+    let limiter = FanLimiter::load(config, &entry).await?;
+    let mut fan_values = Vec::with_capacity(fans.len());
+    for &fan_id in &fans {
+        entry.board_info().validate_fan_id(fan_id)?;
+        fan_values.push((fan_id, limiter.pwm(fan_id, pwm as u32)?));
+    }
+
+    match entry.connection_manager() {
+        Some(cm) => {
+            // Skip fans already at the value to avoid redundant serial traffic
+            cm.with_controller(async |controller| {
+                for &(fan_id, value) in &fan_values {
+                    if controller.get_single_fan_pwm(fan_id) != Some(value) {
+                        controller.set_fan_pwm(fan_id, value).await?;
+                    }
+                }
+                Ok(())
+            })
+            .await?;
+        }
+        None => {
+            debug!(
+                "Controller '{}' is in mock mode - simulating control loop '{}'",
+                controller_id, binding.name
+            );
+        }
+    }
+
+    debug!(
+        "Controller '{}': Control loop '{}' at {:.1}C -> {}% PWM on fans {:?}",
+        controller_id, binding.name, temperature, pwm, fans
+    );
+    Ok((temperature, pwm))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::ControllerEntry;
+    use openfan_core::{BoardType, config::StaticConfig};
+    use tempfile::TempDir;
+
+    async fn create_engine(temp_dir: &TempDir) -> ControlEngine {
+        create_engine_with(temp_dir, None).await
+    }
+
+    async fn create_engine_with(
+        temp_dir: &TempDir,
+        cm: Option<Arc<crate::controllers::ConnectionManager>>,
+    ) -> ControlEngine {
+        let data_dir = temp_dir.path().join("data");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        let config = StaticConfig::with_data_dir(data_dir);
+        let config_path = temp_dir.path().join("config.toml");
+        std::fs::write(&config_path, config.to_toml().unwrap()).unwrap();
+        let config = RuntimeConfig::load(&config_path).await.unwrap();
+
+        let registry = ControllerRegistry::new();
+        registry
+            .register(
+                ControllerEntry::builder("default", BoardType::OpenFanStandard.to_board_info())
+                    .maybe_connection_manager(cm)
+                    .build(),
+            )
+            .await
+            .unwrap();
+
+        let sensors = SensorManager::new(&config.static_config().sensors);
+        ControlEngine::new(Arc::new(registry), Arc::new(config), Arc::new(sensors))
+    }
+
+    async fn wait_for_status(
+        engine: &ControlEngine,
+        binding: &CurveBinding,
+        done: impl Fn(&BindingStatus) -> bool,
+    ) -> BindingStatus {
+        for _ in 0..150 {
+            let status = engine.status("default", binding).await;
+            if done(&status) {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        engine.status("default", binding).await
+    }
+
+    #[tokio::test]
+    async fn test_control_loop_applies_curve() {
+        let temp_dir = TempDir::new().unwrap();
+        let engine = create_engine(&temp_dir).await;
+
+        let sensor = temp_dir.path().join("temp");
+        std::fs::write(&sensor, "50000").unwrap();
+
+        let binding =
+            CurveBinding::for_fans("cpu", "Balanced", sensor.to_str().unwrap(), vec![0, 1]);
+        engine.start("default", binding.clone()).await;
+
+        let status = wait_for_status(&engine, &binding, |s| s.pwm.is_some()).await;
+        assert!(status.running);
+        assert_eq!(status.temperature, Some(50.0));
+        // Balanced curve: 50C -> 50%
+        assert_eq!(status.pwm, Some(50));
+        assert!(status.error.is_none());
+
+        assert!(engine.stop("default", "cpu").await);
+        assert!(!engine.stop("default", "cpu").await);
+        assert!(!engine.status("default", &binding).await.running);
+    }
+
+    #[tokio::test]
+    async fn test_held_controller_loops_wait_for_release() {
+        let temp_dir = TempDir::new().unwrap();
+        let engine = create_engine(&temp_dir).await;
+
+        let sensor = temp_dir.path().join("temp");
+        std::fs::write(&sensor, "50000").unwrap();
+        let binding =
+            CurveBinding::for_fans("cpu", "Balanced", sensor.to_str().unwrap(), vec![0, 1]);
+        let data = engine.config.controller_data("default").await.unwrap();
+        data.curve_bindings_mut()
+            .await
+            .insert(binding.name.clone(), binding.clone());
+        engine.start("default", binding.clone()).await;
+        wait_for_status(&engine, &binding, |s| s.pwm.is_some()).await;
+
+        engine.hold("default", Hold::Failsafe).await;
+        let status = engine.status("default", &binding).await;
+        assert!(!status.running);
+        assert_eq!(status.error.as_deref(), Some("Held by a failsafe alarm"));
+
+        // Not started again until released, even by a reload
+        engine.start("default", binding.clone()).await;
+        engine.stop_all().await;
+        engine.start_all().await;
+        assert!(!engine.status("default", &binding).await.running);
+
+        // Every hold must be released
+        engine.hold("default", Hold::Calibration).await;
+        engine.release("default", Hold::Failsafe).await;
+        let status = engine.status("default", &binding).await;
+        assert!(!status.running);
+        assert_eq!(status.error.as_deref(), Some("Held by a fan calibration"));
+
+        engine.release("default", Hold::Calibration).await;
+        let status = wait_for_status(&engine, &binding, |s| s.pwm.is_some()).await;
+        assert!(status.running);
+        assert!(status.error.is_none());
+
+        engine.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_control_loop_restores_fans_changed_elsewhere() {
+        use crate::controllers::{ConnectionManager, Device, SimulatedDevice, connection};
+        use openfan_core::ReconnectConfig;
+        use openfan_hardware::SimulatorConfig;
+
+        let temp_dir = TempDir::new().unwrap();
+        let board = BoardType::OpenFanStandard.to_board_info();
+        let device = Device::Simulated(SimulatedDevice::new(
+            "default",
+            SimulatorConfig::new(board.fan_count).with_noise(0),
+        ));
+        let controller = connection::open_device(&device, &board, 1000, false)
+            .await
+            .unwrap();
+        let cm = Arc::new(ConnectionManager::new(
+            controller,
+            ReconnectConfig::default(),
+            device,
+            board,
+            1000,
+            false,
+        ));
+        let engine = create_engine_with(&temp_dir, Some(cm.clone())).await;
+
+        let sensor = temp_dir.path().join("temp");
+        std::fs::write(&sensor, "50000").unwrap();
+        let binding = CurveBinding::for_fans("cpu", "Balanced", sensor.to_str().unwrap(), vec![0])
+            .with_interval(1);
+        engine.start("default", binding.clone()).await;
+        wait_for_status(&engine, &binding, |s| s.pwm.is_some()).await;
+
+        let commanded = async || {
+            cm.with_controller(async |ctrl| Ok(ctrl.get_single_fan_pwm(0)))
+                .await
+                .unwrap()
+        };
+        assert_eq!(commanded().await, Some(50));
+
+        // A manual set overrides the fan while the curve output stays the same
+        cm.with_controller(async |ctrl| ctrl.set_fan_pwm(0, 100).await)
+            .await
+            .unwrap();
+        assert_eq!(commanded().await, Some(100));
+
+        let mut restored = false;
+        for _ in 0..150 {
+            if commanded().await == Some(50) {
+                restored = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(restored, "control loop should bring the fan back to 50%");
+        engine.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_control_loop_refuses_zero_interval() {
+        let temp_dir = TempDir::new().unwrap();
+        let engine = create_engine(&temp_dir).await;
+
+        let binding =
+            CurveBinding::for_fans("cpu", "Balanced", "/tmp/temp", vec![0]).with_interval(0);
+        engine.start("default", binding.clone()).await;
+
+        let status = wait_for_status(&engine, &binding, |s| s.error.is_some()).await;
+        assert!(!status.running);
+        assert!(status.error.unwrap().contains("interval"));
+    }
+
+    #[tokio::test]
+    async fn test_control_loop_reports_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let engine = create_engine(&temp_dir).await;
+
+        let sensor = temp_dir.path().join("temp");
+        std::fs::write(&sensor, "50000").unwrap();
+
+        // Unknown curve
+        let binding =
+            CurveBinding::for_fans("bad-curve", "Missing", sensor.to_str().unwrap(), vec![0]);
+        engine.start("default", binding.clone()).await;
+        let status = wait_for_status(&engine, &binding, |s| s.error.is_some()).await;
+        assert!(status.running);
+        assert!(status.error.unwrap().contains("Missing"));
+
+        // Unknown PID controller
+        let binding = CurveBinding::for_fans("bad-pid", "", sensor.to_str().unwrap(), vec![0])
+            .using_pid("missing-pid");
+        engine.start("default", binding.clone()).await;
+        let status = wait_for_status(&engine, &binding, |s| s.error.is_some()).await;
+        assert!(status.error.unwrap().contains("PID controller not found"));
+
+        // Unknown zone
+        let binding =
+            CurveBinding::for_zone("bad-zone", "Balanced", sensor.to_str().unwrap(), "nowhere");
+        engine.start("default", binding.clone()).await;
+        let status = wait_for_status(&engine, &binding, |s| s.error.is_some()).await;
+        assert!(status.error.unwrap().contains("nowhere"));
+
+        // Fan out of range for the board
+        let binding =
+            CurveBinding::for_fans("bad-fan", "Balanced", sensor.to_str().unwrap(), vec![12]);
+        engine.start("default", binding.clone()).await;
+        let status = wait_for_status(&engine, &binding, |s| s.error.is_some()).await;
+        assert!(status.error.unwrap().contains("out of range"));
+
+        engine.stop_all().await;
+        assert!(!engine.status("default", &binding).await.running);
+    }
+
+    #[tokio::test]
+    async fn test_control_loop_applies_curve_tuning() {
+        use openfan_core::{CurvePoint, CurveTuning, ThermalCurve};
+
+        let temp_dir = TempDir::new().unwrap();
+        let engine = create_engine(&temp_dir).await;
+
+        let curve = ThermalCurve::new(
+            "linear",
+            vec![CurvePoint::new(0.0, 0), CurvePoint::new(100.0, 100)],
+        )
+        .with_tuning(CurveTuning {
+            hysteresis_c: Some(5.0),
+            ..Default::default()
+        });
+        {
+            let controller_data = engine.config.controller_data("default").await.unwrap();
+            controller_data
+                .thermal_curves_mut()
+                .await
+                .insert("linear".to_string(), curve);
+        }
+
+        let sensor = temp_dir.path().join("temp");
+        std::fs::write(&sensor, "50000").unwrap();
+
+        let binding = CurveBinding::for_fans("cpu", "linear", sensor.to_str().unwrap(), vec![0])
+            .with_interval(1);
+        engine.start("default", binding.clone()).await;
+        let status = wait_for_status(&engine, &binding, |s| s.pwm.is_some()).await;
+        assert_eq!(status.pwm, Some(50));
+
+        // A drop smaller than the hysteresis keeps the PWM
+        std::fs::write(&sensor, "47000").unwrap();
+        let status = wait_for_status(&engine, &binding, |s| s.temperature == Some(47.0)).await;
+        assert_eq!(status.temperature, Some(47.0));
+        assert_eq!(status.pwm, Some(50));
+
+        engine.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_control_loop_applies_pid() {
+        use openfan_core::PidController;
+
+        let temp_dir = TempDir::new().unwrap();
+        let engine = create_engine(&temp_dir).await;
+
+        {
+            let controller_data = engine.config.controller_data("default").await.unwrap();
+            controller_data.pid_controllers_mut().await.insert(
+                "loop".to_string(),
+                PidController::new("loop", 40.0, 10.0, 0.5, 0.0).with_output_range(20, 100),
+            );
+        }
+
+        let sensor = temp_dir.path().join("temp");
+        std::fs::write(&sensor, "45000").unwrap();
+
+        let binding = CurveBinding::for_fans("water", "", sensor.to_str().unwrap(), vec![0])
+            .using_pid("loop")
+            .with_interval(1);
+        engine.start("default", binding.clone()).await;
+
+        // First step: proportional term only, 5C over the setpoint
+        let status = wait_for_status(&engine, &binding, |s| s.pwm.is_some()).await;
+        assert_eq!(status.pwm, Some(50));
+        let pid_state = status.pid_state.unwrap();
+        assert_eq!(pid_state.last_error, Some(5.0));
+        assert_eq!(pid_state.p_term, 50.0);
+        assert_eq!(pid_state.integral, 0.0);
+
+        // The integral builds up on the following steps
+        let status = wait_for_status(&engine, &binding, |s| {
+            s.pid_state.as_ref().is_some_and(|p| p.integral > 0.0)
+        })
+        .await;
+        assert!(status.pwm.unwrap() > 50);
+
+        engine.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_start_all_loads_persisted_bindings() {
+        let temp_dir = TempDir::new().unwrap();
+        let engine = create_engine(&temp_dir).await;
+
+        let sensor = temp_dir.path().join("temp");
+        std::fs::write(&sensor, "30000").unwrap();
+
+        let binding = CurveBinding::for_fans("case", "Silent", sensor.to_str().unwrap(), vec![2]);
+        {
+            let controller_data = engine.config.controller_data("default").await.unwrap();
+            controller_data
+                .curve_bindings_mut()
+                .await
+                .insert("case".to_string(), binding.clone());
+        }
+
+        engine.start_all().await;
+
+        let status = wait_for_status(&engine, &binding, |s| s.pwm.is_some()).await;
+        assert!(status.running);
+        // Silent curve: below 40C -> 20%
+        assert_eq!(status.pwm, Some(20));
+
+        engine.stop_all().await;
+    }
+}
@@ -3,19 +3,19 @@
 //! This module provides a wrapper around `FanController` that handles
 //! device disconnections and automatic reconnection with exponential backoff.
 
-use std::collections::HashMap;
-use std::sync::Arc;
 use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 
-use openfan_core::{OpenFanError, ReconnectConfig, Result};
-use openfan_hardware::is_disconnect_error;
+use openfan_core::{BoardInfo, FanStateData, OpenFanError, ReconnectConfig, Result};
+use openfan_hardware::{TransactionStats, is_disconnect_error};
 use tokio::sync::{Mutex, RwLock};
 use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
-use super::DefaultFanController;
 use super::connection;
+use super::{DefaultFanController, Device, DeviceIdentity};
+use crate::config::ControllerData;
 
 /// Connection state machine states
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -47,20 +47,32 @@ pub struct ConnectionManager {
     state: RwLock<ConnectionState>,
     /// Reconnection configuration
     config: ReconnectConfig,
-    /// Serial device path for reconnection
-    device_path: String,
+    /// Device to reopen on reconnection
+    device: Device,
+    /// Board behind the device, for its baud rate and limits
+    board_info: BoardInfo,
     /// Serial communication timeout in milliseconds
     timeout_ms: u64,
     /// Enable UART debug logging
     debug_uart: bool,
-    /// Cached PWM values to restore after reconnection
-    pwm_cache: Mutex<HashMap<u8, u32>>,
+    /// Cached PWM and target RPM values to restore after reconnection
+    state_cache: Mutex<FanStateData>,
+    /// Where the fan state is saved on every change, once enabled
+    state_store: OnceLock<Arc<ControllerData>>,
+    /// Fan state last written to the store
+    saved_state: Mutex<Option<FanStateData>>,
     /// Number of successful reconnections since startup
     reconnect_count: AtomicU32,
+    /// Serial transaction counters, carried over to reconnected controllers
+    stats: Arc<TransactionStats>,
     /// Timestamp of last disconnection
     last_disconnect: Mutex<Option<Instant>>,
     /// Lock to prevent concurrent reconnection attempts
     reconnect_lock: Mutex<()>,
+    /// Where the device was found on the last successful connection
+    identity: std::sync::Mutex<DeviceIdentity>,
+    /// Heartbeat task, while running
+    heartbeat: std::sync::Mutex<Option<tokio::task::AbortHandle>>,
 }
 
 impl ConnectionManager {
@@ -68,32 +80,43 @@ impl ConnectionManager {
     pub fn new(
         controller: DefaultFanController,
         config: ReconnectConfig,
-        device_path: String,
+        device: Device,
+        board_info: BoardInfo,
         timeout_ms: u64,
         debug_uart: bool,
     ) -> Self {
+        let identity = connection::identify(&device);
+        let stats = controller.transaction_stats();
         Self {
             controller: RwLock::new(Some(controller)),
             state: RwLock::new(ConnectionState::Connected),
             config,
-            device_path,
+            device,
+            board_info,
             timeout_ms,
             debug_uart,
-            pwm_cache: Mutex::new(HashMap::new()),
+            state_cache: Mutex::new(FanStateData::default()),
+            state_store: OnceLock::new(),
+            saved_state: Mutex::new(None),
             reconnect_count: AtomicU32::new(0),
+            stats,
             last_disconnect: Mutex::new(None),
             reconnect_lock: Mutex::new(()),
+            identity: std::sync::Mutex::new(identity),
+            heartbeat: std::sync::Mutex::new(None),
         }
     }
 
     /// Execute an operation on the fan controller with automatic disconnect detection
     ///
     /// If the operation fails due to a disconnection, the manager will:
-    /// 1. Cache the current PWM state
+    /// 1. Cache the current PWM and target RPM state
     /// 2. Update the connection state to Disconnected
     /// 3. Return a `DeviceDisconnected` error
     ///
     /// Subsequent calls will return `Reconnecting` or attempt lazy reconnection.
+    /// Otherwise, the fan state is saved if it changed and persistence is
+    /// enabled (see [`Self::persist_state`]).
     ///
     /// # Usage
     ///
@@ -126,19 +149,56 @@ impl ConnectionManager {
 
         // Execute the operation
         let mut controller_guard = self.controller.write().await;
-        if let Some(ref mut controller) = *controller_guard {
-            match f(controller).await {
-                Ok(result) => Ok(result),
-                Err(e) if is_disconnect_error(&e) => {
-                    // Device disconnected during operation
-                    drop(controller_guard);
-                    self.handle_disconnect().await;
-                    Err(OpenFanError::DeviceDisconnected(e.to_string()))
-                }
-                Err(e) => Err(e),
-            }
-        } else {
-            Err(OpenFanError::DeviceNotFound)
+        let Some(ref mut controller) = *controller_guard else {
+            return Err(OpenFanError::DeviceNotFound);
+        };
+        let result = f(controller).await;
+        if let Err(e) = &result
+            && is_disconnect_error(e)
+        {
+            // Device disconnected during operation
+            drop(controller_guard);
+            self.handle_disconnect().await;
+            return Err(OpenFanError::DeviceDisconnected(e.to_string()));
+        }
+
+        if self.state_store.get().is_some() {
+            let state = fan_state(controller);
+            drop(controller_guard);
+            self.save_state(state).await;
+        }
+        result
+    }
+
+    /// Apply a fan state, such as one saved by a previous run
+    ///
+    /// Each fan is set to its PWM or target RPM. Returns the fans that could
+    /// not be set; a disconnection stops the restore with an error.
+    pub async fn restore_state(&self, state: &FanStateData) -> Result<Vec<u8>> {
+        self.with_controller(async |controller| apply_fan_state(controller, state).await)
+            .await
+    }
+
+    /// Save the fan state to the controller's data directory on every change
+    ///
+    /// Enabled by `[startup] restore_state`, so the state can be restored by
+    /// the next run. Only the first call has an effect.
+    pub fn persist_state(&self, data: Arc<ControllerData>) {
+        let _ = self.state_store.set(data);
+    }
+
+    /// Write the fan state to the store if it changed since the last write
+    async fn save_state(&self, state: FanStateData) {
+        let Some(data) = self.state_store.get() else {
+            return;
+        };
+        let mut saved_state = self.saved_state.lock().await;
+        if saved_state.as_ref() == Some(&state) {
+            return;
+        }
+        match data.save_fan_state(&state).await {
+            Ok(()) => *saved_state = Some(state),
+            Err(e) => warn!("Failed to save fan state: {}", e),
         }
     }
 
@@ -150,11 +210,15 @@ impl ConnectionManager {
         if *state == ConnectionState::Connected {
             warn!("Device disconnected, caching state for recovery");
 
-            // Cache PWM values before marking as disconnected
+            // Cache fan state before marking as disconnected
             if let Some(ref controller) = *self.controller.read().await {
-                let mut pwm_cache = self.pwm_cache.lock().await;
-                *pwm_cache = controller.get_all_fan_pwm();
-                debug!("Cached {} PWM values", pwm_cache.len());
+                let mut state_cache = self.state_cache.lock().await;
+                *state_cache = fan_state(controller);
+                debug!(
+                    "Cached {} PWM and {} RPM values",
+                    state_cache.pwm.len(),
+                    state_cache.rpm.len()
+                );
             }
 
             // Update state
@@ -179,6 +243,7 @@ impl ConnectionManager {
             ));
         }
 
+        let device = self.reconnect_device();
         let mut attempt = 0u32;
         let mut delay = Duration::from_secs(self.config.initial_delay_secs);
         let max_delay = Duration::from_secs(self.config.max_delay_secs);
@@ -203,11 +268,17 @@ impl ConnectionManager {
                 delay
             );
 
-            // Try to connect using stored device path
-            match connection::connect_to_device(&self.device_path, self.timeout_ms, self.debug_uart)
-                .await
+            match connection::open_device(
+                &device,
+                &self.board_info,
+                self.timeout_ms,
+                self.debug_uart,
+            )
+            .await
             {
                 Ok(mut new_controller) => {
+                    new_controller.set_transaction_stats(self.stats.clone());
+
                     // Verify connection works
                     if connection::test_connection(&mut new_controller)
                         .await
@@ -215,17 +286,14 @@ impl ConnectionManager {
                     {
                         info!("Reconnection successful after {} attempts", attempt);
 
-                        // Restore PWM cache
-                        let pwm_cache = self.pwm_cache.lock().await;
-                        for (&fan_id, &pwm) in pwm_cache.iter() {
-                            if let Err(e) = new_controller.set_fan_pwm(fan_id, pwm).await {
-                                warn!("Failed to restore PWM for fan {}: {}", fan_id, e);
-                            } else {
-                                debug!("Restored PWM for fan {} to {}", fan_id, pwm);
-                            }
+                        // Restore cached fan state
+                        let state_cache = self.state_cache.lock().await;
+                        if let Err(e) = apply_fan_state(&mut new_controller, &state_cache).await {
+                            warn!("Failed to restore fan state: {}", e);
                         }
 
                         // Update controller and state
+                        *self.identity.lock().unwrap() = connection::identify(&device);
                         *self.controller.write().await = Some(new_controller);
                         *self.state.write().await = ConnectionState::Connected;
                         self.reconnect_count.fetch_add(1, Ordering::Relaxed);
@@ -263,11 +331,33 @@ impl ConnectionManager {
         *self.state.read().await
     }
 
+    /// Get where the device was found on the last successful connection
+    pub fn identity(&self) -> DeviceIdentity {
+        self.identity.lock().unwrap().clone()
+    }
+
+    /// Device to reopen after a disconnect
+    ///
+    /// A serial port whose board reported a USB serial number is looked up by
+    /// that number, so a replugged board is found on its new device node
+    /// rather than whatever took over the old one.
+    fn reconnect_device(&self) -> Device {
+        match (&self.device, self.identity().serial_number) {
+            (Device::Serial(_), Some(serial_number)) => Device::UsbSerial(serial_number),
+            (device, _) => device.clone(),
+        }
+    }
+
     /// Get the number of successful reconnections since startup
     pub fn reconnect_count(&self) -> u32 {
         self.reconnect_count.load(Ordering::Relaxed)
     }
 
+    /// Get the serial transaction counters since startup
+    pub fn transaction_stats(&self) -> &TransactionStats {
+        &self.stats
+    }
+
     /// Get the time since last disconnection (if any)
     pub async fn time_since_disconnect(&self) -> Option<Duration> {
         self.last_disconnect
@@ -285,10 +375,12 @@ impl ConnectionManager {
     ///
     /// The heartbeat periodically checks the connection by querying firmware info.
     /// If the check fails with a disconnect error, it triggers the reconnection flow.
+    /// The task holds the manager until [`Self::stop_heartbeat`] is called.
     pub fn start_heartbeat(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
         let interval = Duration::from_secs(self.config.heartbeat_interval_secs);
+        let manager = self.clone();
 
-        tokio::spawn(async move {
+        let handle = tokio::spawn(async move {
             info!(
                 "Starting connection heartbeat with {}s interval",
                 interval.as_secs()
@@ -319,7 +411,34 @@ impl ConnectionManager {
                     }
                 }
             }
-        })
+        });
+        if let Some(previous) = manager
+            .heartbeat
+            .lock()
+            .unwrap()
+            .replace(handle.abort_handle())
+        {
+            previous.abort();
+        }
+        handle
+    }
+
+    /// Stop the heartbeat task, if running
+    pub fn stop_heartbeat(&self) {
+        if let Some(handle) = self.heartbeat.lock().unwrap().take() {
+            handle.abort();
+        }
+    }
+
+    /// Close the device, so that another manager can open it
+    ///
+    /// The heartbeat is stopped and the manager is left disconnected without
+    /// caching the fan state; [`Self::force_reconnect`] opens the device again.
+    pub async fn close(&self) {
+        self.stop_heartbeat();
+        let mut state = self.state.write().await;
+        *self.controller.write().await = None;
+        *state = ConnectionState::Disconnected;
     }
 
     /// Force a manual reconnection attempt
@@ -350,9 +469,56 @@ impl ConnectionManager {
     }
 }
 
+/// PWM and target RPM last set on each fan
+fn fan_state(controller: &DefaultFanController) -> FanStateData {
+    FanStateData::new(
+        controller.get_all_fan_pwm(),
+        controller.get_all_fan_rpm_targets(),
+    )
+}
+
+/// Set each fan to its PWM or target RPM from `state`
+///
+/// Returns the fans that could not be set. A disconnection is returned as an
+/// error, as there is no point trying the other fans.
+async fn apply_fan_state(
+    controller: &mut DefaultFanController,
+    state: &FanStateData,
+) -> Result<Vec<u8>> {
+    let mut failed = Vec::new();
+    let mut pwm: Vec<_> = state.pwm.iter().collect();
+    pwm.sort();
+    for (&fan_id, &value) in pwm {
+        match controller.set_fan_pwm(fan_id, value).await {
+            Ok(_) => debug!("Restored PWM for fan {} to {}", fan_id, value),
+            Err(e) if is_disconnect_error(&e) => return Err(e),
+            Err(e) => {
+                warn!("Failed to restore PWM for fan {}: {}", fan_id, e);
+                failed.push(fan_id);
+            }
+        }
+    }
+    let mut rpm: Vec<_> = state.rpm.iter().collect();
+    rpm.sort();
+    for (&fan_id, &value) in rpm {
+        match controller.set_fan_rpm(fan_id, value).await {
+            Ok(_) => debug!("Restored RPM for fan {} to {}", fan_id, value),
+            Err(e) if is_disconnect_error(&e) => return Err(e),
+            Err(e) => {
+                warn!("Failed to restore RPM for fan {}: {}", fan_id, e);
+                failed.push(fan_id);
+            }
+        }
+    }
+    Ok(failed)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::controllers::SimulatedDevice;
+    use openfan_core::BoardType;
+    use openfan_hardware::SimulatorConfig;
 
     #[test]
     fn test_connection_state_as_str() {
@@ -365,4 +531,150 @@ mod tests {
     }
 
     // Note: ReconnectConfig defaults are tested in openfan-core/src/config/static_config.rs
+
+    async fn simulated_manager() -> (ConnectionManager, SimulatedDevice) {
+        let simulated = SimulatedDevice::new(
+            "sim",
+            SimulatorConfig::new(4)
+                .with_noise(0)
+                .with_time_constant(Duration::ZERO),
+        );
+        let device = Device::Simulated(simulated.clone());
+        let board = BoardType::Custom { fan_count: 4 }.to_board_info();
+        let controller = connection::open_device(&device, &board, 1000, false)
+            .await
+            .unwrap();
+        let config = ReconnectConfig {
+            max_attempts: 1,
+            enable_heartbeat: false,
+            ..Default::default()
+        };
+        let manager = ConnectionManager::new(controller, config, device, board, 1000, false);
+        (manager, simulated)
+    }
+
+    #[tokio::test]
+    async fn test_simulated_timeout_keeps_connection() {
+        let (manager, simulated) = simulated_manager().await;
+
+        simulated.inject_timeouts(1);
+        let err = manager
+            .with_controller(async |ctrl| ctrl.get_all_fan_rpm().await)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OpenFanError::Timeout(_)));
+        assert_eq!(manager.connection_state().await, ConnectionState::Connected);
+
+        assert!(
+            manager
+                .with_controller(async |ctrl| ctrl.get_all_fan_rpm().await)
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_simulated_disconnect_and_reconnect() {
+        let (manager, simulated) = simulated_manager().await;
+        manager
+            .with_controller(async |ctrl| ctrl.set_fan_pwm(1, 40).await)
+            .await
+            .unwrap();
+
+        simulated.disconnect();
+        let err = manager
+            .with_controller(async |ctrl| ctrl.get_all_fan_rpm().await)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OpenFanError::DeviceDisconnected(_)));
+        assert_eq!(
+            manager.connection_state().await,
+            ConnectionState::Disconnected
+        );
+
+        // Still unplugged: the lazy reconnection gives up after one attempt
+        let err = manager
+            .with_controller(async |ctrl| ctrl.get_all_fan_rpm().await)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OpenFanError::ReconnectionFailed { .. }));
+
+        simulated.reconnect();
+        let rpms = manager
+            .with_controller(async |ctrl| ctrl.get_all_fan_rpm().await)
+            .await
+            .unwrap();
+        assert!(rpms[&1] > 0);
+        assert_eq!(manager.connection_state().await, ConnectionState::Connected);
+        assert_eq!(manager.reconnect_count(), 1);
+
+        // Counters survive the new connection, including the failed request
+        let stats = manager.transaction_stats();
+        assert!(stats.transactions() > 3);
+        assert!(stats.errors() >= 1);
+
+        // The PWM set before the disconnect was restored on the new connection
+        let pwm = manager
+            .with_controller(async |ctrl| Ok(ctrl.get_single_fan_pwm(1)))
+            .await
+            .unwrap();
+        assert_eq!(pwm, Some(40));
+    }
+
+    #[tokio::test]
+    async fn test_pty_heartbeat_detects_hang_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let board = BoardType::OpenFanStandard.to_board_info();
+        let emulator = openfan_hardware::PtyEmulator::start(
+            SimulatedDevice::new("pty", SimulatorConfig::new(10).with_noise(0)),
+            dir.path().join("tty"),
+            board.baud_rate,
+        )
+        .unwrap();
+        let device = Device::Serial(emulator.path().to_string_lossy().into_owned());
+        let controller = connection::open_device(&device, &board, 500, false)
+            .await
+            .unwrap();
+        let config = ReconnectConfig {
+            max_attempts: 1,
+            heartbeat_interval_secs: 1,
+            ..Default::default()
+        };
+        let manager = Arc::new(ConnectionManager::new(
+            controller, config, device, board, 500, false,
+        ));
+        manager
+            .with_controller(async |ctrl| ctrl.set_fan_pwm(2, 60).await)
+            .await
+            .unwrap();
+        let heartbeat = manager.clone().start_heartbeat();
+
+        // The heartbeat notices the hang-up without any request coming in
+        emulator.device().disconnect();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while manager.connection_state().await != ConnectionState::Disconnected {
+                sleep(Duration::from_millis(100)).await;
+            }
+        })
+        .await
+        .expect("heartbeat should detect the disconnect");
+
+        // The next request reopens the port, now backed by a new terminal
+        emulator.device().reconnect();
+        sleep(Duration::from_millis(200)).await;
+        let pwm = manager
+            .with_controller(async |ctrl| {
+                ctrl.get_fw_info().await?;
+                Ok(ctrl.get_single_fan_pwm(2))
+            })
+            .await
+            .unwrap();
+        assert_eq!(pwm, Some(60));
+        assert_eq!(manager.reconnect_count(), 1);
+        let identity = manager.identity();
+        assert!(identity.path.is_some_and(|path| path.starts_with("/dev/")));
+        assert_eq!(identity.serial_number, None);
+
+        heartbeat.abort();
+    }
 }
@@ -176,6 +176,19 @@ impl ControllerRegistry {
         Ok(())
     }
 
+    /// Register a controller, replacing the entry with the same ID if any
+    ///
+    /// Returns the replaced entry.
+    pub async fn replace(&self, entry: ControllerEntry) -> Option<Arc<ControllerEntry>> {
+        let mut controllers = self.controllers.write().await;
+        controllers.insert(entry.id.clone(), Arc::new(entry))
+    }
+
+    /// Remove a controller, returning its entry if it was registered
+    pub async fn unregister(&self, id: &str) -> Option<Arc<ControllerEntry>> {
+        self.controllers.write().await.remove(id)
+    }
+
     /// Get a controller by ID
     pub async fn get(&self, id: &str) -> Option<Arc<ControllerEntry>> {
         let controllers = self.controllers.read().await;
@@ -194,6 +207,15 @@ impl ControllerRegistry {
         let controllers = self.controllers.read().await;
         controllers.values().cloned().collect()
     }
+
+    /// Board info of every controller, by controller ID
+    pub async fn boards(&self) -> HashMap<String, BoardInfo> {
+        let controllers = self.controllers.read().await;
+        controllers
+            .iter()
+            .map(|(id, entry)| (id.clone(), entry.board_info().clone()))
+            .collect()
+    }
 }
 
 impl Default for ControllerRegistry {
@@ -242,6 +264,57 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_unregister_controller() {
+        let registry = ControllerRegistry::new();
+        let entry = ControllerEntry::builder("main", mock_board_info()).build();
+        registry.register(entry).await.unwrap();
+
+        let removed = registry.unregister("main").await.unwrap();
+        assert_eq!(removed.id(), "main");
+        assert!(registry.get("main").await.is_none());
+        assert!(registry.unregister("main").await.is_none());
+
+        // The ID can be registered again
+        let entry = ControllerEntry::builder("main", mock_board_info()).build();
+        registry.register(entry).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_boards() {
+        let registry = ControllerRegistry::new();
+        let gpu_board = BoardType::Custom { fan_count: 4 }.to_board_info();
+        for entry in [
+            ControllerEntry::builder("main", mock_board_info()).build(),
+            ControllerEntry::builder("gpu", gpu_board).build(),
+        ] {
+            registry.register(entry).await.unwrap();
+        }
+
+        let boards = registry.boards().await;
+        assert_eq!(boards.len(), 2);
+        assert_eq!(boards["main"].fan_count, 10);
+        assert_eq!(boards["gpu"].fan_count, 4);
+    }
+
+    #[tokio::test]
+    async fn test_replace_controller() {
+        let registry = ControllerRegistry::new();
+        let entry = ControllerEntry::builder("main", mock_board_info()).build();
+        assert!(registry.replace(entry).await.is_none());
+
+        let entry = ControllerEntry::builder("main", mock_board_info())
+            .maybe_description(Some("Main chassis".to_string()))
+            .build();
+        let replaced = registry.replace(entry).await.unwrap();
+        assert_eq!(replaced.description(), None);
+        assert_eq!(
+            registry.get("main").await.unwrap().description(),
+            Some("Main chassis")
+        );
+        assert_eq!(registry.list().await.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_get_controller() {
         let registry = ControllerRegistry::new();
@@ -3,7 +3,7 @@
 //! This module manages fan controller connections, including:
 //! - Connection lifecycle (connect, disconnect, reconnect state machine)
 //! - Multi-controller registry (register, lookup, list controllers)
-//! - Resilience features (exponential backoff, PWM state caching, heartbeat monitoring)
+//! - Resilience features (exponential backoff, fan state caching, heartbeat monitoring)
 //!
 //! Low-level hardware protocol is handled by the `openfan_hardware` crate.
 
@@ -12,34 +12,276 @@ mod controller_registry;
 
 pub use connection_manager::{ConnectionManager, ConnectionState};
 pub use controller_registry::{ControllerEntry, ControllerRegistry};
-pub use openfan_hardware::{FanController, SerialDriver};
+pub use openfan_hardware::{FanController, SerialDriver, SimulatedDevice};
 
-/// Type alias for the standard fan controller with default board
-pub type DefaultFanController = FanController<SerialDriver<openfan_core::DefaultBoard>>;
+use async_trait::async_trait;
+use openfan_hardware::{SerialTransport, SimulatedTransport};
+
+/// Type alias for the fan controller used by the daemon
+pub type DefaultFanController = FanController<Transport>;
+
+/// Transport to a real or simulated board
+pub enum Transport {
+    /// Serial connection to a real board
+    Serial(SerialDriver<openfan_core::DefaultBoard>),
+    /// Connection to a simulated board
+    Simulated(SimulatedTransport),
+}
+
+#[async_trait]
+impl SerialTransport for Transport {
+    async fn transaction(&mut self, command: &str) -> openfan_core::Result<Vec<String>> {
+        match self {
+            Transport::Serial(driver) => driver.transaction(command).await,
+            Transport::Simulated(transport) => transport.transaction(command).await,
+        }
+    }
+
+    fn clear_input_buffer(&mut self) -> openfan_core::Result<()> {
+        match self {
+            Transport::Serial(driver) => driver.clear_input_buffer(),
+            Transport::Simulated(transport) => transport.clear_input_buffer(),
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        match self {
+            Transport::Serial(driver) => driver.is_connected(),
+            Transport::Simulated(transport) => transport.is_connected(),
+        }
+    }
+
+    fn port_path(&self) -> Option<&str> {
+        match self {
+            Transport::Serial(driver) => driver.port_path(),
+            Transport::Simulated(transport) => transport.port_path(),
+        }
+    }
+}
+
+/// Device a controller talks to
+#[derive(Debug, Clone)]
+pub enum Device {
+    /// Serial port of a real board (e.g. `/dev/ttyACM0`)
+    Serial(String),
+    /// Real board identified by its USB serial number, wherever it is plugged
+    UsbSerial(String),
+    /// In-process simulated board, used by mock mode
+    Simulated(SimulatedDevice),
+}
+
+impl std::fmt::Display for Device {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Device::Serial(path) => write!(f, "{}", path),
+            Device::UsbSerial(serial_number) => write!(f, "USB serial {}", serial_number),
+            Device::Simulated(device) => write!(f, "sim://{}", device.name()),
+        }
+    }
+}
+
+/// Where a device currently is, and what identifies it across replugs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceIdentity {
+    /// Resolved device node (e.g. `/dev/ttyACM1`), if the device was found
+    pub path: Option<String>,
+    /// USB serial number, if the device reports one
+    pub serial_number: Option<String>,
+}
 
 /// Controller initialization and connection utilities
 pub(crate) mod connection {
     use super::*;
-    use openfan_core::{DefaultBoard, OpenFanError, Result};
+    use openfan_core::{
+        BoardInfo, ControllerConfig, DefaultBoard, OpenFanError, ReconnectConfig, Result,
+    };
+    use openfan_hardware::{SimulatorConfig, discovery};
+    use std::path::Path;
+    use std::sync::Arc;
     use tracing::{info, warn};
 
     /// Connect to a specific serial device
     ///
     /// Use this when the device path is known (e.g., from --device flag or config).
+    /// The port is opened at the board's baud rate, and fan IDs, PWM and RPM
+    /// are checked against the board's limits.
     pub async fn connect_to_device(
         device_path: &str,
+        board_info: &BoardInfo,
         timeout_ms: u64,
         debug_uart: bool,
     ) -> Result<DefaultFanController> {
         info!("Connecting to device: {}", device_path);
 
-        let driver = SerialDriver::<DefaultBoard>::new(device_path, timeout_ms, debug_uart)
-            .map_err(|e| {
-                OpenFanError::Serial(format!("Failed to connect to {}: {}", device_path, e))
-            })?;
+        let driver = SerialDriver::<DefaultBoard>::with_board_info(
+            device_path,
+            board_info,
+            timeout_ms,
+            debug_uart,
+        )
+        .map_err(|e| {
+            OpenFanError::Serial(format!("Failed to connect to {}: {}", device_path, e))
+        })?;
 
         info!("Successfully connected to {}", device_path);
-        Ok(FanController::new(driver))
+        Ok(FanController::with_board_info(
+            Box::new(Transport::Serial(driver)),
+            board_info,
+        ))
+    }
+
+    /// Connect to a device, real or simulated
+    pub async fn open_device(
+        device: &Device,
+        board_info: &BoardInfo,
+        timeout_ms: u64,
+        debug_uart: bool,
+    ) -> Result<DefaultFanController> {
+        match device {
+            Device::Serial(path) => {
+                connect_to_device(path, board_info, timeout_ms, debug_uart).await
+            }
+            Device::UsbSerial(serial_number) => {
+                let path =
+                    discovery::find_port_by_serial_number(serial_number).ok_or_else(|| {
+                        OpenFanError::Serial(format!(
+                            "No serial port with USB serial number {}",
+                            serial_number
+                        ))
+                    })?;
+                connect_to_device(&path, board_info, timeout_ms, debug_uart).await
+            }
+            Device::Simulated(simulated) => {
+                let transport = simulated.open()?;
+                Ok(FanController::with_board_info(
+                    Box::new(Transport::Simulated(transport)),
+                    board_info,
+                ))
+            }
+        }
+    }
+
+    /// Resolve a device to its current device node and USB serial number
+    ///
+    /// Symlinks such as `/dev/serial/by-id/...` are followed to the device
+    /// node they point at.
+    pub fn identify(device: &Device) -> DeviceIdentity {
+        match device {
+            Device::Serial(path) => {
+                let path = canonical_port(path);
+                DeviceIdentity {
+                    serial_number: discovery::serial_number_of(&path),
+                    path: Some(path),
+                }
+            }
+            Device::UsbSerial(serial_number) => DeviceIdentity {
+                path: discovery::find_port_by_serial_number(serial_number)
+                    .map(|path| canonical_port(&path)),
+                serial_number: Some(serial_number.clone()),
+            },
+            Device::Simulated(_) => DeviceIdentity {
+                path: Some(device.to_string()),
+                serial_number: None,
+            },
+        }
+    }
+
+    fn canonical_port(path: &str) -> String {
+        Path::new(path)
+            .canonicalize()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_else(|_| path.to_string())
+    }
+
+    /// Find the serial ports with an OpenFAN board behind them
+    ///
+    /// Candidates come from `OPENFAN_COMPORT`, known USB VID/PIDs and common
+    /// device paths, and are kept if they answer a firmware info probe.
+    pub async fn discover_devices(timeout_ms: u64) -> Vec<String> {
+        let candidates = discovery::find_candidates();
+        info!("Auto-detection: {} candidate port(s)", candidates.len());
+
+        let mut ports = Vec::new();
+        for candidate in candidates {
+            match discovery::probe(&candidate.port, timeout_ms).await {
+                Ok(firmware) => {
+                    info!("Found OpenFAN board on {}: {}", candidate.port, firmware);
+                    ports.push(candidate.port);
+                }
+                Err(e) => warn!("Skipping {}: {}", candidate.port, e),
+            }
+        }
+        ports
+    }
+
+    /// Device for a controller: its serial port, or a simulated board in mock mode
+    pub fn device_for(id: &str, device_path: &str, board_info: &BoardInfo, mock: bool) -> Device {
+        if mock {
+            Device::Simulated(SimulatedDevice::new(
+                id,
+                SimulatorConfig::new(board_info.fan_count),
+            ))
+        } else {
+            Device::Serial(device_path.to_string())
+        }
+    }
+
+    /// Device for a `[[controllers]]` entry, found by USB serial number if it has one
+    pub fn device_for_config(config: &ControllerConfig, mock: bool) -> Device {
+        match &config.serial_number {
+            Some(serial_number) if !mock => Device::UsbSerial(serial_number.clone()),
+            _ => device_for(
+                &config.id,
+                &config.device,
+                &config.board.to_board_info(),
+                mock,
+            ),
+        }
+    }
+
+    /// Open a controller's device and wrap it in a [`ConnectionManager`]
+    ///
+    /// A failed hardware test is only logged. The heartbeat is started when
+    /// enabled in `reconnect_config`.
+    pub async fn connect(
+        id: &str,
+        device: Device,
+        board_info: &BoardInfo,
+        timeout_ms: u64,
+        verbose: bool,
+        reconnect_config: &ReconnectConfig,
+    ) -> Result<Arc<ConnectionManager>> {
+        info!("Connecting to controller '{}' at {}...", id, device);
+
+        let mut controller = open_device(&device, board_info, timeout_ms, verbose).await?;
+        info!("Controller '{}' connected successfully", id);
+
+        // Test the connection
+        if let Err(e) = test_connection(&mut controller).await {
+            warn!(
+                "Controller '{}' hardware test failed, continuing: {}",
+                id, e
+            );
+        }
+
+        let manager = Arc::new(ConnectionManager::new(
+            controller,
+            reconnect_config.clone(),
+            device,
+            board_info.clone(),
+            timeout_ms,
+            verbose,
+        ));
+
+        if reconnect_config.enable_heartbeat {
+            info!(
+                "Controller '{}': heartbeat enabled (interval: {}s)",
+                id, reconnect_config.heartbeat_interval_secs
+            );
+            manager.clone().start_heartbeat();
+        }
+
+        Ok(manager)
     }
 
     /// Test hardware connection by getting firmware info
@@ -61,11 +303,17 @@ pub(crate) mod connection {
     #[cfg(test)]
     mod tests {
         use super::*;
+        use openfan_core::{BoardConfig, BoardType};
+
+        fn standard() -> BoardInfo {
+            BoardType::OpenFanStandard.to_board_info()
+        }
 
         #[tokio::test]
         async fn test_connect_to_device_invalid_path() {
             // Test that connecting to a non-existent device returns an error
-            let result = connect_to_device("/dev/nonexistent_device_12345", 1000, false).await;
+            let result =
+                connect_to_device("/dev/nonexistent_device_12345", &standard(), 1000, false).await;
 
             match result {
                 Err(OpenFanError::Serial(msg)) => {
@@ -79,5 +327,89 @@ pub(crate) mod connection {
                 Ok(_) => panic!("Expected error for non-existent device"),
             }
         }
+
+        #[tokio::test]
+        async fn test_open_simulated_device() {
+            let simulated = SimulatedDevice::new("sim", openfan_hardware::SimulatorConfig::new(4));
+            let device = Device::Simulated(simulated.clone());
+            assert_eq!(device.to_string(), "sim://sim");
+
+            let board = BoardType::Custom { fan_count: 4 }.to_board_info();
+            let mut controller = open_device(&device, &board, 1000, false).await.unwrap();
+            assert!(test_connection(&mut controller).await.is_ok());
+            assert_eq!(controller.fan_count(), 4);
+            assert_eq!(controller.get_all_fan_rpm().await.unwrap().len(), 4);
+
+            simulated.disconnect();
+            assert!(open_device(&device, &board, 1000, false).await.is_err());
+
+            let identity = identify(&device);
+            assert_eq!(identity.path.as_deref(), Some("sim://sim"));
+            assert_eq!(identity.serial_number, None);
+        }
+
+        #[tokio::test]
+        async fn test_open_unknown_usb_serial_number() {
+            let device = Device::UsbSerial("NO-SUCH-BOARD".to_string());
+            assert_eq!(device.to_string(), "USB serial NO-SUCH-BOARD");
+
+            match open_device(&device, &standard(), 1000, false).await {
+                Err(OpenFanError::Serial(msg)) => assert!(msg.contains("NO-SUCH-BOARD"), "{}", msg),
+                Err(other) => panic!("Expected Serial error, got {:?}", other),
+                Ok(_) => panic!("Expected error for unknown serial number"),
+            }
+
+            let identity = identify(&device);
+            assert_eq!(identity.path, None);
+            assert_eq!(identity.serial_number.as_deref(), Some("NO-SUCH-BOARD"));
+        }
+
+        #[cfg(unix)]
+        #[tokio::test]
+        async fn test_identify_follows_symlink() {
+            let dir = tempfile::tempdir().unwrap();
+            let emulator = openfan_hardware::PtyEmulator::start(
+                SimulatedDevice::new("pty", openfan_hardware::SimulatorConfig::new(10)),
+                dir.path().join("tty"),
+                DefaultBoard::BAUD_RATE,
+            )
+            .unwrap();
+            let link = emulator.path().to_string_lossy().into_owned();
+
+            let identity = identify(&Device::Serial(link.clone()));
+            let path = identity.path.unwrap();
+            assert_ne!(path, link);
+            assert!(path.starts_with("/dev/"), "{}", path);
+            assert_eq!(identity.serial_number, None);
+        }
+
+        #[cfg(unix)]
+        #[tokio::test]
+        async fn test_connect_custom_board() {
+            let board = BoardType::Custom { fan_count: 4 }.to_board_info();
+            let dir = tempfile::tempdir().unwrap();
+            let emulator = openfan_hardware::PtyEmulator::start(
+                SimulatedDevice::new("pty", openfan_hardware::SimulatorConfig::new(4)),
+                dir.path().join("tty"),
+                board.baud_rate,
+            )
+            .unwrap();
+            let path = emulator.path().to_str().unwrap();
+
+            let mut controller = connect_to_device(path, &board, 500, false).await.unwrap();
+            assert!(matches!(
+                controller.set_fan_pwm(4, 50).await,
+                Err(OpenFanError::InvalidFanId {
+                    fan_id: 4,
+                    max_fans: 4
+                })
+            ));
+
+            // PWM cached for reconnection only covers the board's own fans
+            controller.set_all_fan_pwm(40).await.unwrap();
+            let mut fans: Vec<u8> = controller.get_all_fan_pwm().into_keys().collect();
+            fans.sort();
+            assert_eq!(fans, [0, 1, 2, 3]);
+        }
     }
 }
@@ -0,0 +1,771 @@
+//! Fan history
+//!
+//! Records the fan readings sampled by [`Telemetry`] so they can be looked at
+//! after the fact. Samples are averaged into points of `[history]
+//! resolution_secs` and written to one ring file per controller,
+//! `{data_dir}/history/{id}.bin`. A file holds `retention_hours` of points and
+//! never grows: once full, the oldest point is overwritten by the newest.
+
+use crate::config::RuntimeConfig;
+use crate::controllers::ControllerRegistry;
+use crate::telemetry::Telemetry;
+use openfan_core::api::{HistoryPoint, TelemetryEvent};
+use std::collections::{BTreeMap, HashMap};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::{Mutex, broadcast};
+use tokio::task::JoinHandle;
+use tracing::{debug, info, warn};
+
+/// Identifies history files and the version of their layout
+const MAGIC: &[u8; 8] = b"OFHIST01";
+/// Magic, fan count, capacity, resolution, head and length, padded
+const HEADER_LEN: u64 = 32;
+/// Stored for readings that are not known, such as the PWM of a fan never set
+const UNKNOWN: u32 = u32::MAX;
+/// Most points returned by a query; longer ranges get a coarser step
+const MAX_POINTS: u64 = 10_000;
+/// Most records read from a history file at once
+const READ_CHUNK: u32 = 4096;
+
+/// Shape of a history file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Layout {
+    fan_count: usize,
+    /// Number of points the file holds
+    capacity: u32,
+    resolution_secs: u32,
+}
+
+impl Layout {
+    /// Timestamp, then RPM and PWM of each fan
+    fn record_len(&self) -> u64 {
+        8 + 8 * self.fan_count as u64
+    }
+
+    fn file_len(&self) -> u64 {
+        HEADER_LEN + u64::from(self.capacity) * self.record_len()
+    }
+
+    /// Fan IDs of each record, in order
+    fn fans(&self) -> impl Iterator<Item = u8> {
+        (0..=u8::MAX).take(self.fan_count)
+    }
+}
+
+/// Fixed-size file of points, written in a circle
+struct RingFile {
+    path: PathBuf,
+    file: File,
+    layout: Layout,
+    /// Slot the next point is written to
+    head: u32,
+    /// Number of points written, up to the capacity
+    len: u32,
+}
+
+impl RingFile {
+    /// Open the history file at `path`.
+    ///
+    /// A missing file, or one written with another layout, is replaced by an
+    /// empty one.
+    async fn open(path: &Path, layout: Layout) -> std::io::Result<Self> {
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .await?;
+
+        let mut header = [0u8; HEADER_LEN as usize];
+        let found = match file.read_exact(&mut header).await {
+            Ok(_) => decode_header(&header),
+            Err(_) => None,
+        };
+        let file_len = file.metadata().await?.len();
+
+        let mut ring = Self {
+            path: path.to_path_buf(),
+            file,
+            layout,
+            head: 0,
+            len: 0,
+        };
+        match found {
+            Some((found, head, len))
+                if found == layout
+                    && file_len == layout.file_len()
+                    && head < layout.capacity
+                    && len <= layout.capacity =>
+            {
+                ring.head = head;
+                ring.len = len;
+            }
+            found => {
+                if found.is_some() {
+                    info!(
+                        "History settings or board changed, starting a new {}",
+                        path.display()
+                    );
+                }
+                ring.file.set_len(0).await?;
+                ring.file.set_len(layout.file_len()).await?;
+                ring.write_header().await?;
+            }
+        }
+        Ok(ring)
+    }
+
+    async fn write_header(&mut self) -> std::io::Result<()> {
+        let mut header = [0u8; HEADER_LEN as usize];
+        header[..8].copy_from_slice(MAGIC);
+        let fields = [
+            self.layout.fan_count as u32,
+            self.layout.capacity,
+            self.layout.resolution_secs,
+            self.head,
+            self.len,
+        ];
+        for (i, field) in fields.iter().enumerate() {
+            header[8 + 4 * i..12 + 4 * i].copy_from_slice(&field.to_le_bytes());
+        }
+        self.file.seek(SeekFrom::Start(0)).await?;
+        self.file.write_all(&header).await?;
+        self.file.flush().await
+    }
+
+    /// Write a point over the oldest one once the file is full.
+    async fn append(&mut self, point: &HistoryPoint) -> std::io::Result<()> {
+        let offset = HEADER_LEN + u64::from(self.head) * self.layout.record_len();
+        self.file.seek(SeekFrom::Start(offset)).await?;
+        self.file
+            .write_all(&encode_record(point, &self.layout))
+            .await?;
+
+        self.head = (self.head + 1) % self.layout.capacity;
+        self.len = (self.len + 1).min(self.layout.capacity);
+        self.write_header().await
+    }
+
+    /// Points written so far, to be read without holding the file.
+    fn view(&self) -> RingView {
+        RingView {
+            path: self.path.clone(),
+            layout: self.layout,
+            head: self.head,
+            len: self.len,
+        }
+    }
+}
+
+/// Points of a history file as they were when the view was taken
+struct RingView {
+    path: PathBuf,
+    layout: Layout,
+    head: u32,
+    len: u32,
+}
+
+impl RingView {
+    /// Slot of the `index`th point, oldest first
+    fn slot(&self, index: u32) -> u32 {
+        let capacity = self.layout.capacity;
+        (self.head + capacity - self.len + index) % capacity
+    }
+
+    fn offset(&self, index: u32) -> u64 {
+        HEADER_LEN + u64::from(self.slot(index)) * self.layout.record_len()
+    }
+
+    /// Index of the first point written at or after `timestamp`
+    async fn find(&self, file: &mut File, timestamp: u64) -> std::io::Result<u32> {
+        let (mut low, mut high) = (0, self.len);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            file.seek(SeekFrom::Start(self.offset(mid))).await?;
+            if file.read_u64_le().await? < timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        Ok(low)
+    }
+
+    /// Visit the points between `from` and `to`, oldest first.
+    ///
+    /// Points are written in time order, so only the records in range are
+    /// read, [`READ_CHUNK`] at a time.
+    async fn read_range(
+        &self,
+        from: u64,
+        to: u64,
+        mut visit: impl FnMut(HistoryPoint),
+    ) -> std::io::Result<()> {
+        if self.len == 0 {
+            return Ok(());
+        }
+        let mut file = File::open(&self.path).await?;
+        let start = self.find(&mut file, from).await?;
+        let end = self.find(&mut file, to.saturating_add(1)).await?;
+
+        let record_len = self.layout.record_len() as usize;
+        let mut index = start;
+        while index < end {
+            // Records are contiguous up to the end of the file
+            let count = (end - index)
+                .min(self.layout.capacity - self.slot(index))
+                .min(READ_CHUNK);
+            let mut bytes = vec![0u8; count as usize * record_len];
+            file.seek(SeekFrom::Start(self.offset(index))).await?;
+            file.read_exact(&mut bytes).await?;
+
+            for record in bytes.chunks_exact(record_len) {
+                let point = decode_record(record, &self.layout);
+                // The oldest points may be overwritten while reading
+                if (from..=to).contains(&point.timestamp) {
+                    visit(point);
+                }
+            }
+            index += count;
+        }
+        Ok(())
+    }
+}
+
+fn decode_header(header: &[u8]) -> Option<(Layout, u32, u32)> {
+    if &header[..8] != MAGIC {
+        return None;
+    }
+    let field = |i: usize| u32::from_le_bytes(header[8 + 4 * i..12 + 4 * i].try_into().unwrap());
+    let layout = Layout {
+        fan_count: field(0) as usize,
+        capacity: field(1),
+        resolution_secs: field(2),
+    };
+    Some((layout, field(3), field(4)))
+}
+
+fn encode_record(point: &HistoryPoint, layout: &Layout) -> Vec<u8> {
+    let mut record = Vec::with_capacity(layout.record_len() as usize);
+    record.extend_from_slice(&point.timestamp.to_le_bytes());
+    for fan in layout.fans() {
+        let rpm = point.rpms.get(&fan).copied().unwrap_or(UNKNOWN);
+        let pwm = point.pwms.get(&fan).copied().unwrap_or(UNKNOWN);
+        record.extend_from_slice(&rpm.to_le_bytes());
+        record.extend_from_slice(&pwm.to_le_bytes());
+    }
+    record
+}
+
+fn decode_record(record: &[u8], layout: &Layout) -> HistoryPoint {
+    let value = |offset: usize| {
+        let value = u32::from_le_bytes(record[offset..offset + 4].try_into().unwrap());
+        (value != UNKNOWN).then_some(value)
+    };
+    let mut point = HistoryPoint {
+        timestamp: u64::from_le_bytes(record[..8].try_into().unwrap()),
+        rpms: HashMap::new(),
+        pwms: HashMap::new(),
+    };
+    for (i, fan) in layout.fans().enumerate() {
+        if let Some(rpm) = value(8 + 8 * i) {
+            point.rpms.insert(fan, rpm);
+        }
+        if let Some(pwm) = value(12 + 8 * i) {
+            point.pwms.insert(fan, pwm);
+        }
+    }
+    point
+}
+
+/// Readings being averaged into the point starting at `start`
+struct Bucket {
+    start: u64,
+    /// Sum and count of the readings of each fan
+    rpms: BTreeMap<u8, (u64, u64)>,
+    pwms: BTreeMap<u8, (u64, u64)>,
+}
+
+impl Bucket {
+    fn new(start: u64) -> Self {
+        Self {
+            start,
+            rpms: BTreeMap::new(),
+            pwms: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, rpms: &HashMap<u8, u32>, pwms: &HashMap<u8, u32>) {
+        for (sums, readings) in [(&mut self.rpms, rpms), (&mut self.pwms, pwms)] {
+            for (fan, value) in readings {
+                let (sum, count) = sums.entry(*fan).or_default();
+                *sum += u64::from(*value);
+                *count += 1;
+            }
+        }
+    }
+
+    fn point(&self) -> HistoryPoint {
+        let average = |sums: &BTreeMap<u8, (u64, u64)>| {
+            sums.iter()
+                .map(|(fan, (sum, count))| (*fan, ((sum + count / 2) / count) as u32))
+                .collect()
+        };
+        HistoryPoint {
+            timestamp: self.start,
+            rpms: average(&self.rpms),
+            pwms: average(&self.pwms),
+        }
+    }
+}
+
+/// History of one controller
+struct ControllerHistory {
+    ring: RingFile,
+    /// Point being averaged, not written yet
+    bucket: Option<Bucket>,
+}
+
+impl ControllerHistory {
+    /// Add a sample, writing the previous point when the sample starts a new one.
+    async fn record(
+        &mut self,
+        rpms: &HashMap<u8, u32>,
+        pwms: &HashMap<u8, u32>,
+        timestamp: u64,
+    ) -> std::io::Result<()> {
+        let resolution = u64::from(self.ring.layout.resolution_secs);
+        let start = timestamp - timestamp % resolution;
+
+        let done = self.bucket.take_if(|bucket| bucket.start != start);
+        self.bucket
+            .get_or_insert_with(|| Bucket::new(start))
+            .add(rpms, pwms);
+        match done {
+            Some(done) => self.ring.append(&done.point()).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Written points and the one being averaged
+    fn snapshot(&self) -> (RingView, Option<HistoryPoint>) {
+        (self.ring.view(), self.bucket.as_ref().map(Bucket::point))
+    }
+}
+
+/// Step of a query: at least the resolution and a multiple of it, and coarse
+/// enough to return at most [`MAX_POINTS`] points.
+fn query_step(from: u64, to: u64, step: Option<u64>, resolution: u64) -> u64 {
+    let step = step
+        .unwrap_or(resolution)
+        .max((to - from).div_ceil(MAX_POINTS))
+        .max(resolution);
+    step.div_ceil(resolution).saturating_mul(resolution)
+}
+
+/// Averages points over steps of `step` seconds.
+struct Aggregate {
+    step: u64,
+    buckets: BTreeMap<u64, Bucket>,
+}
+
+impl Aggregate {
+    fn new(step: u64) -> Self {
+        Self {
+            step,
+            buckets: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, point: &HistoryPoint) {
+        let start = point.timestamp - point.timestamp % self.step;
+        self.buckets
+            .entry(start)
+            .or_insert_with(|| Bucket::new(start))
+            .add(&point.rpms, &point.pwms);
+    }
+
+    fn points(&self) -> Vec<HistoryPoint> {
+        self.buckets.values().map(Bucket::point).collect()
+    }
+}
+
+/// Records the fan history of all controllers and answers history queries.
+pub(crate) struct History {
+    registry: Arc<ControllerRegistry>,
+    config: Arc<RuntimeConfig>,
+    telemetry: Arc<Telemetry>,
+    /// History of each controller being recorded, by controller ID
+    stores: Arc<Mutex<HashMap<String, ControllerHistory>>>,
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl History {
+    /// Create a history that records nothing until started.
+    pub fn new(
+        registry: Arc<ControllerRegistry>,
+        config: Arc<RuntimeConfig>,
+        telemetry: Arc<Telemetry>,
+    ) -> Self {
+        Self {
+            registry,
+            config,
+            telemetry,
+            stores: Arc::new(Mutex::new(HashMap::new())),
+            task: Mutex::new(None),
+        }
+    }
+
+    /// Time covered by one recorded point, in seconds
+    pub fn resolution_secs(&self) -> u64 {
+        self.config
+            .static_config()
+            .history
+            .resolution_secs
+            .clamp(1, u64::from(u32::MAX))
+    }
+
+    /// Time kept before points are overwritten, in seconds
+    pub fn retention_secs(&self) -> u64 {
+        self.config.static_config().history.retention_secs()
+    }
+
+    /// Open the history file of every controller with a hardware connection
+    /// and start recording its telemetry samples.
+    pub async fn start_all(&self) {
+        let static_config = self.config.static_config();
+        if !static_config.history.enabled {
+            info!("Fan history disabled in config");
+            return;
+        }
+        if !static_config.telemetry.enabled {
+            warn!("Fan history needs [telemetry] sampling, which is disabled: nothing is recorded");
+        }
+
+        let dir = self.config.data_dir().join("history");
+        if let Err(e) = tokio::fs::create_dir_all(&dir).await {
+            warn!(
+                "Fan history disabled: cannot create {}: {}",
+                dir.display(),
+                e
+            );
+            return;
+        }
+
+        let resolution = self.resolution_secs();
+        // Bounded by HistoryConfig::validate
+        let capacity = static_config
+            .history
+            .capacity()
+            .clamp(1, u64::from(u32::MAX)) as u32;
+
+        let mut stores = self.stores.lock().await;
+        for entry in self.registry.list().await {
+            if entry.connection_manager().is_none() {
+                continue;
+            }
+            let layout = Layout {
+                fan_count: entry.board_info().fan_count,
+                capacity,
+                resolution_secs: resolution as u32,
+            };
+            let path = dir.join(format!("{}.bin", entry.id()));
+            match RingFile::open(&path, layout).await {
+                Ok(ring) => {
+                    info!(
+                        "Controller '{}': Recording fan history to {} ({} bytes)",
+                        entry.id(),
+                        path.display(),
+                        layout.file_len()
+                    );
+                    stores.insert(
+                        entry.id().to_string(),
+                        ControllerHistory { ring, bucket: None },
+                    );
+                }
+                Err(e) => warn!(
+                    "Controller '{}': Fan history disabled: cannot open {}: {}",
+                    entry.id(),
+                    path.display(),
+                    e
+                ),
+            }
+        }
+        drop(stores);
+
+        let handle = tokio::spawn(run_recorder(
+            self.telemetry.subscribe(),
+            self.stores.clone(),
+        ));
+        if let Some(previous) = self.task.lock().await.replace(handle) {
+            previous.abort();
+        }
+    }
+
+    /// Stop recording, writing the points still being averaged and closing the files.
+    pub async fn stop_all(&self) {
+        if let Some(handle) = self.task.lock().await.take() {
+            handle.abort();
+        }
+        for (id, mut store) in self.stores.lock().await.drain() {
+            if let Some(bucket) = store.bucket.take()
+                && let Err(e) = store.ring.append(&bucket.point()).await
+            {
+                warn!("Controller '{}': Failed to write fan history: {}", id, e);
+            }
+        }
+    }
+
+    /// Points of a controller between `from` and `to`, averaged over steps.
+    ///
+    /// Returns the step used, see [`query_step`], with the points. A
+    /// controller whose history is not recorded has no points.
+    pub async fn query(
+        &self,
+        controller_id: &str,
+        from: u64,
+        to: u64,
+        step: Option<u64>,
+    ) -> std::io::Result<(u64, Vec<HistoryPoint>)> {
+        let step = query_step(from, to, step, self.resolution_secs());
+        let mut aggregate = Aggregate::new(step);
+        // Read without holding up the recorder
+        let snapshot = self
+            .stores
+            .lock()
+            .await
+            .get(controller_id)
+            .map(ControllerHistory::snapshot);
+        if let Some((view, pending)) = snapshot {
+            view.read_range(from, to, |point| aggregate.add(&point))
+                .await?;
+            if let Some(point) = pending.filter(|point| (from..=to).contains(&point.timestamp)) {
+                aggregate.add(&point);
+            }
+        }
+        Ok((step, aggregate.points()))
+    }
+}
+
+/// Recording loop, fed with the telemetry fan samples of all controllers.
+async fn run_recorder(
+    mut events: broadcast::Receiver<TelemetryEvent>,
+    stores: Arc<Mutex<HashMap<String, ControllerHistory>>>,
+) {
+    loop {
+        match events.recv().await {
+            Ok(TelemetryEvent::FanStatus {
+                controller,
+                status,
+                timestamp,
+            }) => {
+                if let Some(store) = stores.lock().await.get_mut(&controller)
+                    && let Err(e) = store.record(&status.rpms, &status.pwms, timestamp).await
+                {
+                    warn!(
+                        "Controller '{}': Failed to write fan history: {}",
+                        controller, e
+                    );
+                }
+            }
+            Ok(_) => {}
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                debug!("Fan history missed {} telemetry events", skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const LAYOUT: Layout = Layout {
+        fan_count: 2,
+        capacity: 3,
+        resolution_secs: 10,
+    };
+
+    fn point(timestamp: u64, rpm: u32, pwm: Option<u32>) -> HistoryPoint {
+        HistoryPoint {
+            timestamp,
+            rpms: HashMap::from([(0, rpm), (1, rpm * 2)]),
+            pwms: pwm.map(|pwm| HashMap::from([(0, pwm)])).unwrap_or_default(),
+        }
+    }
+
+    async fn read_range(ring: &RingFile, from: u64, to: u64) -> Vec<HistoryPoint> {
+        let mut points = Vec::new();
+        ring.view()
+            .read_range(from, to, |point| points.push(point))
+            .await
+            .unwrap();
+        points
+    }
+
+    async fn read_points(ring: &RingFile) -> Vec<HistoryPoint> {
+        read_range(ring, 0, u64::MAX).await
+    }
+
+    #[tokio::test]
+    async fn test_ring_file_keeps_newest_points() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("main.bin");
+
+        let mut ring = RingFile::open(&path, LAYOUT).await.unwrap();
+        assert!(read_points(&ring).await.is_empty());
+        for i in 0..5 {
+            ring.append(&point(i * 10, 1000 + i as u32, Some(50)))
+                .await
+                .unwrap();
+        }
+
+        let points = read_points(&ring).await;
+        assert_eq!(
+            points,
+            vec![
+                point(20, 1002, Some(50)),
+                point(30, 1003, Some(50)),
+                point(40, 1004, Some(50))
+            ]
+        );
+        // The size is bounded by the layout
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), LAYOUT.file_len());
+
+        // Points survive a restart
+        drop(ring);
+        let ring = RingFile::open(&path, LAYOUT).await.unwrap();
+        assert_eq!(read_points(&ring).await, points);
+    }
+
+    #[tokio::test]
+    async fn test_ring_file_reads_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let layout = Layout {
+            capacity: 5,
+            ..LAYOUT
+        };
+        let mut ring = RingFile::open(&temp_dir.path().join("main.bin"), layout)
+            .await
+            .unwrap();
+        assert!(read_range(&ring, 0, 100).await.is_empty());
+
+        // Points 30 to 70, wrapping around the end of the file
+        for i in 0..8 {
+            ring.append(&point(i * 10, 1000, None)).await.unwrap();
+        }
+        let timestamps = |points: Vec<HistoryPoint>| {
+            points
+                .iter()
+                .map(|point| point.timestamp)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            timestamps(read_range(&ring, 0, u64::MAX).await),
+            [30, 40, 50, 60, 70]
+        );
+        assert_eq!(timestamps(read_range(&ring, 35, 60).await), [40, 50, 60]);
+        assert_eq!(timestamps(read_range(&ring, 40, 40).await), [40]);
+        assert_eq!(timestamps(read_range(&ring, 65, 1000).await), [70]);
+        assert!(read_range(&ring, 0, 29).await.is_empty());
+        assert!(read_range(&ring, 71, 1000).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ring_file_restarts_on_layout_change() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("main.bin");
+
+        let mut ring = RingFile::open(&path, LAYOUT).await.unwrap();
+        ring.append(&point(0, 1000, None)).await.unwrap();
+        drop(ring);
+
+        let layout = Layout {
+            resolution_secs: 60,
+            ..LAYOUT
+        };
+        let ring = RingFile::open(&path, layout).await.unwrap();
+        assert!(read_points(&ring).await.is_empty());
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), layout.file_len());
+
+        // Not a history file at all
+        std::fs::write(&path, b"garbage").unwrap();
+        let ring = RingFile::open(&path, LAYOUT).await.unwrap();
+        assert!(read_points(&ring).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_samples_are_averaged_into_points() {
+        let temp_dir = TempDir::new().unwrap();
+        let ring = RingFile::open(&temp_dir.path().join("main.bin"), LAYOUT)
+            .await
+            .unwrap();
+        let mut history = ControllerHistory { ring, bucket: None };
+
+        let sample = |rpm: u32| (HashMap::from([(0, rpm)]), HashMap::from([(0, 40)]));
+        for (timestamp, rpm) in [(101, 1000), (105, 2000), (112, 3000)] {
+            let (rpms, pwms) = sample(rpm);
+            history.record(&rpms, &pwms, timestamp).await.unwrap();
+        }
+
+        // The first point is written, the second is still being averaged
+        let points = read_points(&history.ring).await;
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].timestamp, 100);
+        assert_eq!(points[0].rpms[&0], 1500);
+        assert_eq!(points[0].pwms[&0], 40);
+        let (_, pending) = history.snapshot();
+        let pending = pending.unwrap();
+        assert_eq!(pending.timestamp, 110);
+        assert_eq!(pending.rpms[&0], 3000);
+    }
+
+    #[test]
+    fn test_query_step() {
+        // Defaults to the resolution, rounded up to a multiple of it
+        assert_eq!(query_step(0, 3600, None, 10), 10);
+        assert_eq!(query_step(0, 3600, Some(5), 10), 10);
+        assert_eq!(query_step(0, 3600, Some(25), 10), 30);
+        // Coarse enough for at most MAX_POINTS points
+        assert_eq!(query_step(0, 7 * 24 * 3600, None, 10), 70);
+        // Huge steps and ranges do not overflow
+        assert_eq!(query_step(0, 3600, Some(u64::MAX), 10), u64::MAX);
+        assert_eq!(
+            query_step(0, u64::MAX, None, 1),
+            u64::MAX.div_ceil(MAX_POINTS)
+        );
+    }
+
+    #[test]
+    fn test_aggregate() {
+        let points = [
+            point(100, 1000, Some(40)),
+            point(110, 2000, None),
+            point(120, 3000, Some(60)),
+            point(200, 4000, Some(80)),
+        ];
+
+        let aggregate = |step| {
+            let mut aggregate = Aggregate::new(step);
+            points.iter().for_each(|point| aggregate.add(point));
+            aggregate.points()
+        };
+
+        let aggregated = aggregate(200);
+        assert_eq!(aggregated.len(), 2);
+        assert_eq!(aggregated[0].timestamp, 0);
+        assert_eq!(aggregated[0].rpms, HashMap::from([(0, 2000), (1, 4000)]));
+        assert_eq!(aggregated[0].pwms, HashMap::from([(0, 50)]));
+        assert_eq!(aggregated[1].timestamp, 200);
+
+        assert_eq!(aggregate(10).len(), 4);
+        assert!(Aggregate::new(10).points().is_empty());
+    }
+}
@@ -0,0 +1,293 @@
+//! Per-fan safety limit enforcement
+//!
+//! Every path that sets fans — manual PWM and RPM requests, profiles, zones,
+//! curve bindings, startup, failsafe and shutdown — passes its values through
+//! the [`FanLimiter`] of the controller. With `[fan_limits] enforcement =
+//! "clamp"` values outside a fan's limits are moved into them; with `"reject"`
+//! they fail with [`OpenFanError::FanLimitExceeded`] and are not applied.
+//!
+//! Shutdown and the failsafe profile always clamp: leaving a fan as it is
+//! could leave it stopped when it matters most.
+
+use crate::config::RuntimeConfig;
+use crate::controllers::{ConnectionManager, ControllerEntry};
+use openfan_core::{
+    ControlMode, FanLimits, FanLimitsData, FanStateData, LimitEnforcement, OpenFanError, Result,
+};
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+
+/// Limits of a controller's fans, as read when the limiter was loaded
+pub(crate) struct FanLimiter {
+    /// Controller ID (used for logging)
+    controller: String,
+    /// Limits of the fans that have any
+    limits: FanLimitsData,
+    /// How values outside the limits are handled
+    enforcement: LimitEnforcement,
+    /// Lowest RPM target of the board, for fans that may not be stopped
+    min_target_rpm: u32,
+}
+
+impl FanLimiter {
+    /// Load the current limits of a controller's fans.
+    pub(crate) async fn load(config: &RuntimeConfig, entry: &ControllerEntry) -> Result<Self> {
+        let data = config.controller_data(entry.id()).await?;
+        let limits = data.fan_limits().await.clone();
+        Ok(Self::new(
+            entry.id(),
+            limits,
+            config.static_config().fan_limits.enforcement,
+            entry.board_info().min_target_rpm,
+        ))
+    }
+
+    /// Create a limiter from known limits.
+    pub(crate) fn new(
+        controller: impl Into<String>,
+        limits: FanLimitsData,
+        enforcement: LimitEnforcement,
+        min_target_rpm: u32,
+    ) -> Self {
+        Self {
+            controller: controller.into(),
+            limits,
+            enforcement,
+            min_target_rpm,
+        }
+    }
+
+    /// Limits of a fan, unrestricted if it has none.
+    pub(crate) fn get(&self, fan_id: u8) -> FanLimits {
+        self.limits.get(fan_id)
+    }
+
+    /// PWM percentage to send to a fan in place of `pwm`.
+    pub(crate) fn pwm(&self, fan_id: u8, pwm: u32) -> Result<u32> {
+        let limits = self.get(fan_id);
+        self.enforce(
+            fan_id,
+            pwm,
+            limits.check_pwm(pwm),
+            || limits.clamp_pwm(pwm),
+            "% PWM",
+        )
+    }
+
+    /// Target RPM to send to a fan in place of `rpm`.
+    pub(crate) fn rpm(&self, fan_id: u8, rpm: u32) -> Result<u32> {
+        let limits = self.get(fan_id);
+        self.enforce(
+            fan_id,
+            rpm,
+            limits.check_rpm(rpm),
+            || limits.clamp_rpm(rpm, self.min_target_rpm),
+            " RPM",
+        )
+    }
+
+    /// Value to send to a fan in place of `value`, a PWM or target RPM.
+    pub(crate) fn value(&self, fan_id: u8, mode: ControlMode, value: u32) -> Result<u32> {
+        match mode {
+            ControlMode::Pwm => self.pwm(fan_id, value),
+            ControlMode::Rpm => self.rpm(fan_id, value),
+        }
+    }
+
+    /// Value to send to a fan in place of `value`, moved into its limits
+    /// whatever the enforcement mode.
+    ///
+    /// For the safety paths, where a rejected value would leave the fan as it
+    /// is; every clamped value is logged.
+    pub(crate) fn clamp_value(&self, fan_id: u8, mode: ControlMode, value: u32) -> u32 {
+        let limits = self.get(fan_id);
+        let (violation, clamped, unit) = match mode {
+            ControlMode::Pwm => (limits.check_pwm(value), limits.clamp_pwm(value), "% PWM"),
+            ControlMode::Rpm => (
+                limits.check_rpm(value),
+                limits.clamp_rpm(value, self.min_target_rpm),
+                " RPM",
+            ),
+        };
+        let Some(reason) = violation else {
+            return value;
+        };
+        info!(
+            "Controller '{}': fan {} limited from {}{} to {}{} ({})",
+            self.controller, fan_id, value, unit, clamped, unit, reason
+        );
+        clamped
+    }
+
+    /// Fan state moved into the limits whatever the enforcement mode.
+    pub(crate) fn clamp_state(&self, state: &FanStateData) -> FanStateData {
+        let clamp = |values: &HashMap<u8, u32>, mode: ControlMode| {
+            values
+                .iter()
+                .map(|(&fan_id, &value)| (fan_id, self.clamp_value(fan_id, mode, value)))
+                .collect()
+        };
+        FanStateData::new(
+            clamp(&state.pwm, ControlMode::Pwm),
+            clamp(&state.rpm, ControlMode::Rpm),
+        )
+    }
+
+    /// Check if a PWM percentage is within a fan's limits as it is.
+    pub(crate) fn allows_pwm(&self, fan_id: u8, pwm: u32) -> bool {
+        self.get(fan_id).check_pwm(pwm).is_none()
+    }
+
+    /// Fan state moved into the limits.
+    ///
+    /// Returns the state to apply and, when rejecting, the fans left out.
+    pub(crate) fn limit_state(&self, state: &FanStateData) -> (FanStateData, Vec<u8>) {
+        let mut rejected = Vec::new();
+        let mut limit = |values: &HashMap<u8, u32>, mode: ControlMode| {
+            let mut limited = HashMap::new();
+            for (&fan_id, &value) in values {
+                match self.value(fan_id, mode, value) {
+                    Ok(value) => {
+                        limited.insert(fan_id, value);
+                    }
+                    Err(_) => rejected.push(fan_id),
+                }
+            }
+            limited
+        };
+        let pwm = limit(&state.pwm, ControlMode::Pwm);
+        let rpm = limit(&state.rpm, ControlMode::Rpm);
+        rejected.sort_unstable();
+        (FanStateData::new(pwm, rpm), rejected)
+    }
+
+    /// Apply a fan state within the limits.
+    ///
+    /// Returns the fans that were not set, rejected ones included; a
+    /// disconnection stops the restore with an error.
+    pub(crate) async fn restore_state(
+        &self,
+        cm: &ConnectionManager,
+        state: &FanStateData,
+    ) -> Result<Vec<u8>> {
+        let (state, mut failed) = self.limit_state(state);
+        failed.extend(cm.restore_state(&state).await?);
+        failed.sort_unstable();
+        failed.dedup();
+        Ok(failed)
+    }
+
+    fn enforce(
+        &self,
+        fan_id: u8,
+        value: u32,
+        violation: Option<String>,
+        clamp: impl FnOnce() -> u32,
+        unit: &str,
+    ) -> Result<u32> {
+        let Some(reason) = violation else {
+            return Ok(value);
+        };
+        match self.enforcement {
+            LimitEnforcement::Clamp => {
+                let clamped = clamp();
+                debug!(
+                    "Controller '{}': fan {} limited from {}{} to {}{}",
+                    self.controller, fan_id, value, unit, clamped, unit
+                );
+                Ok(clamped)
+            }
+            LimitEnforcement::Reject => {
+                warn!(
+                    "Controller '{}': fan {} rejected: {}",
+                    self.controller, fan_id, reason
+                );
+                Err(OpenFanError::FanLimitExceeded { fan_id, reason })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(enforcement: LimitEnforcement) -> FanLimiter {
+        let mut limits = FanLimitsData::default();
+        limits.set(
+            0,
+            FanLimits {
+                min_pwm: Some(30),
+                max_pwm: Some(80),
+                min_rpm: Some(800),
+                allow_zero: false,
+            },
+        );
+        limits.set(
+            1,
+            FanLimits {
+                min_pwm: Some(20),
+                min_rpm: Some(1000),
+                ..FanLimits::default()
+            },
+        );
+        FanLimiter::new("main", limits, enforcement, 500)
+    }
+
+    #[test]
+    fn test_limiter_clamps() {
+        let limiter = limiter(LimitEnforcement::Clamp);
+        assert_eq!(limiter.pwm(0, 0).unwrap(), 30);
+        assert_eq!(limiter.pwm(0, 100).unwrap(), 80);
+        assert_eq!(limiter.pwm(1, 0).unwrap(), 0);
+        assert_eq!(limiter.pwm(1, 10).unwrap(), 20);
+        assert_eq!(limiter.pwm(2, 0).unwrap(), 0);
+        assert_eq!(limiter.value(0, ControlMode::Rpm, 500).unwrap(), 800);
+        assert_eq!(limiter.rpm(0, 0).unwrap(), 800);
+        // A fan that may be stopped keeps a 0 RPM target despite its minimum
+        assert_eq!(limiter.rpm(1, 0).unwrap(), 0);
+        assert_eq!(limiter.rpm(1, 600).unwrap(), 1000);
+        assert_eq!(limiter.clamp_value(1, ControlMode::Rpm, 0), 0);
+        assert!(!limiter.allows_pwm(0, 0));
+        assert!(limiter.allows_pwm(1, 0));
+    }
+
+    #[test]
+    fn test_limiter_rejects() {
+        let limiter = limiter(LimitEnforcement::Reject);
+        let error = limiter.pwm(0, 0).unwrap_err();
+        assert!(matches!(
+            error,
+            OpenFanError::FanLimitExceeded { fan_id: 0, .. }
+        ));
+        assert!(error.to_string().contains("0% PWM is not allowed"));
+        assert!(limiter.rpm(0, 500).is_err());
+        assert!(limiter.rpm(0, 0).is_err());
+        assert_eq!(limiter.rpm(1, 0).unwrap(), 0);
+        assert!(limiter.rpm(1, 600).is_err());
+        assert_eq!(limiter.pwm(0, 50).unwrap(), 50);
+        assert_eq!(limiter.pwm(2, 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_limiter_state() {
+        let state = FanStateData::new(
+            HashMap::from([(0, 0), (1, 50), (2, 0)]),
+            HashMap::from([(3, 300)]),
+        );
+
+        let (limited, rejected) = limiter(LimitEnforcement::Clamp).limit_state(&state);
+        assert!(rejected.is_empty());
+        assert_eq!(limited.pwm, HashMap::from([(0, 30), (1, 50), (2, 0)]));
+        assert_eq!(limited.rpm, HashMap::from([(3, 300)]));
+
+        let (limited, rejected) = limiter(LimitEnforcement::Reject).limit_state(&state);
+        assert_eq!(rejected, vec![0]);
+        assert_eq!(limited.pwm, HashMap::from([(1, 50), (2, 0)]));
+
+        // Safety paths clamp even when rejecting
+        let clamped = limiter(LimitEnforcement::Reject).clamp_state(&state);
+        assert_eq!(clamped.pwm, HashMap::from([(0, 30), (1, 50), (2, 0)]));
+        assert_eq!(clamped.rpm, HashMap::from([(3, 300)]));
+    }
+}
@@ -10,21 +10,50 @@
 //!    a single controller. This creates an implicit "default" controller.
 //!
 //! 2. **Config file** (multi-controller mode): Define `[[controllers]]` entries in
-//!    the config.toml file for multiple controllers.
+//!    the config.toml file for multiple controllers. Each entry names its board
+//!    by `device` path or by USB `serial_number`.
 //!
 //! If `--device` is specified, it takes precedence over config file controllers.
+//!
+//! # Auto-Detection
+//!
+//! Without `--device`, `[[controllers]]` or `--mock`, the daemon looks for
+//! boards itself: the port named by `OPENFAN_COMPORT`, USB ports with a known
+//! OpenFAN VID/PID, then common device paths. Every candidate answering a
+//! firmware info probe is registered, the first one as "default".
+//!
+//! # Mock Mode
+//!
+//! With `--mock`, every controller is backed by a simulated board instead of a
+//! serial port. Simulated boards speak the firmware protocol and go through
+//! the same `ConnectionManager` path as real hardware.
+//!
+//! # Configuration Reload
+//!
+//! SIGHUP reloads `config.toml` and the data files without a restart; `[[controllers]]`
+//! changes are only followed when the controllers came from the config file.
 
+mod alarms;
 mod api;
+mod calibration;
 mod config;
+mod control;
 mod controllers;
+mod history;
+mod limits;
+mod reload;
+mod sensors;
 mod shutdown;
+mod startup;
+mod telemetry;
 
 use anyhow::Result;
 use api::AppState;
 use clap::Parser;
 use config::RuntimeConfig;
-use controllers::{ConnectionManager, ControllerEntry, ControllerRegistry, connection};
+use controllers::{ConnectionManager, ControllerEntry, ControllerRegistry, Device, connection};
 use openfan_core::{BoardInfo, BoardType, default_config_path};
+use reload::ControllerSetup;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::signal;
@@ -105,10 +134,34 @@ async fn main() -> Result<()> {
     let timeout_ms = server_config.communication_timeout * 1000;
     let reconnect_config = runtime_config.static_config().reconnect.clone();
 
+    // Warn when anyone on the network can set the fans
+    let auth = &runtime_config.static_config().auth;
+    if auth.enabled() {
+        info!(
+            "API token authentication enabled ({} tokens)",
+            auth.tokens.len()
+        );
+    } else if !bind_host
+        .parse::<std::net::IpAddr>()
+        .is_ok_and(|ip| ip.is_loopback())
+        && bind_host != "localhost"
+    {
+        warn!(
+            "Listening on {} without API tokens: anyone who can reach it can control the fans. \
+             Configure [[auth.tokens]] to require one.",
+            bind_host
+        );
+    }
+
     // Step 2: Initialize controller registry
     let registry = ControllerRegistry::new();
     let mut default_board_info: Option<BoardInfo> = None;
     let mut default_connection_manager: Option<Arc<ConnectionManager>> = None;
+    let mut setup = ControllerSetup {
+        mock: args.mock,
+        verbose: args.verbose,
+        ..ControllerSetup::default()
+    };
 
     // Determine controller configuration mode
     if let Some(ref device) = args.device {
@@ -122,7 +175,8 @@ async fn main() -> Result<()> {
 
         let connection_manager = connect_controller(
             "default",
-            device,
+            connection::device_for("default", device, &board_info, args.mock),
+            &board_info,
             timeout_ms,
             args.verbose,
             &reconnect_config,
@@ -143,14 +197,19 @@ async fn main() -> Result<()> {
             "Multi-controller mode: {} controller(s) configured",
             controllers.len()
         );
+        // Reloads follow the changes to [[controllers]]
+        setup.from_config = true;
+        setup.default_id = controllers[0].id.clone();
 
         for (idx, ctrl_config) in controllers.iter().enumerate() {
             let board_info = ctrl_config.board.to_board_info();
 
+            let device = connection::device_for_config(ctrl_config, args.mock);
+
             info!(
                 "  Controller '{}': device={}, board={} ({} fans){}",
                 ctrl_config.id,
-                ctrl_config.device,
+                device,
                 board_info.name,
                 board_info.fan_count,
                 ctrl_config
@@ -160,18 +219,15 @@ async fn main() -> Result<()> {
                     .unwrap_or_default()
             );
 
-            let connection_manager = if args.mock {
-                None
-            } else {
-                connect_controller(
-                    &ctrl_config.id,
-                    &ctrl_config.device,
-                    timeout_ms,
-                    args.verbose,
-                    &reconnect_config,
-                )
-                .await
-            };
+            let connection_manager = connect_controller(
+                &ctrl_config.id,
+                device,
+                &board_info,
+                timeout_ms,
+                args.verbose,
+                &reconnect_config,
+            )
+            .await;
 
             // First controller becomes the default for legacy compatibility
             if idx == 0 {
@@ -186,7 +242,7 @@ async fn main() -> Result<()> {
             registry.register(entry).await?;
         }
     } else if args.mock {
-        // Mock mode without config: create single mock "default" controller
+        // Mock mode without config: create a single simulated "default" controller
         let board_info = args.board.to_board_info();
 
         info!(
@@ -194,18 +250,73 @@ async fn main() -> Result<()> {
             board_info.name, board_info.fan_count
         );
 
+        let connection_manager = connect_controller(
+            "default",
+            connection::device_for("default", "", &board_info, true),
+            &board_info,
+            timeout_ms,
+            args.verbose,
+            &reconnect_config,
+        )
+        .await;
+
         default_board_info = Some(board_info.clone());
+        default_connection_manager = connection_manager.clone();
 
-        let entry = ControllerEntry::builder("default", board_info).build();
+        let entry = ControllerEntry::builder("default", board_info)
+            .maybe_connection_manager(connection_manager)
+            .build();
         registry.register(entry).await?;
     } else {
-        error!(
-            "No controllers configured. Use one of:\n  \
-             --device /dev/ttyACM0 --board standard    (single controller)\n  \
-             --mock --board standard                   (mock mode)\n  \
-             Configure [[controllers]] in config.toml  (multi-controller)"
+        // Auto-detection: register every board found on a serial port
+        let ports = connection::discover_devices(timeout_ms).await;
+        if ports.is_empty() {
+            error!(
+                "No controllers configured and no OpenFAN board detected. Use one of:\n  \
+                 --device /dev/ttyACM0 --board standard    (single controller)\n  \
+                 --mock --board standard                   (mock mode)\n  \
+                 Configure [[controllers]] in config.toml  (multi-controller)"
+            );
+            std::process::exit(1);
+        }
+
+        let board_info = args.board.to_board_info();
+        info!(
+            "Auto-detected {} board(s), board={} ({} fans)",
+            ports.len(),
+            board_info.name,
+            board_info.fan_count
         );
-        std::process::exit(1);
+
+        for (idx, port) in ports.into_iter().enumerate() {
+            // First board becomes the default controller
+            let id = if idx == 0 {
+                "default".to_string()
+            } else {
+                format!("openfan{}", idx)
+            };
+
+            let connection_manager = connect_controller(
+                &id,
+                Device::Serial(port.clone()),
+                &board_info,
+                timeout_ms,
+                args.verbose,
+                &reconnect_config,
+            )
+            .await;
+
+            if idx == 0 {
+                default_board_info = Some(board_info.clone());
+                default_connection_manager = connection_manager.clone();
+            }
+
+            let entry = ControllerEntry::builder(&id, board_info.clone())
+                .maybe_connection_manager(connection_manager)
+                .maybe_description(Some(format!("Auto-detected on {}", port)))
+                .build();
+            registry.register(entry).await?;
+        }
     }
 
     // Ensure we have at least one controller
@@ -217,12 +328,20 @@ async fn main() -> Result<()> {
         registry.list().await.len()
     );
 
-    // Step 3: Validate global zones against all controllers
-    // TODO: In the future, validate each zone fan against its controller's board
+    // Step 3: Validate configuration, checking each zone fan against its own controller
     if let Err(e) = runtime_config.validate_for_board(&default_board_info).await {
         error!("Configuration validation failed: {}", e);
         std::process::exit(1);
     }
+    let boards = registry.boards().await;
+    if let Err(e) = runtime_config.validate_zones(&boards).await {
+        error!("Configuration validation failed: {}", e);
+        std::process::exit(1);
+    }
+    if let Err(e) = runtime_config.validate_fan_limits(&boards).await {
+        error!("Configuration validation failed: {}", e);
+        std::process::exit(1);
+    }
     info!("Configuration validated successfully");
 
     // Auto-fill missing defaults for the default controller
@@ -234,9 +353,12 @@ async fn main() -> Result<()> {
     let registry = Arc::new(registry);
     let runtime_config = Arc::new(runtime_config);
 
+    // Set the fans before control loops and API clients take over
+    startup::apply_startup_state(&runtime_config, &registry).await;
+
     // Clone for shutdown handler
     let runtime_config_for_shutdown = runtime_config.clone();
-    let cm_for_shutdown = default_connection_manager.clone();
+    let registry_for_shutdown = registry.clone();
     let is_mock = args.mock;
 
     // Step 4: Create application state
@@ -245,8 +367,30 @@ async fn main() -> Result<()> {
         runtime_config,
         default_board_info,
         default_connection_manager,
+        setup,
     );
 
+    // Step 5: Start closed-loop thermal control for persisted curve bindings
+    app_state.control.start_all().await;
+    let control_for_shutdown = app_state.control.clone();
+
+    // Step 6: Watch the fans for stalls and failures
+    app_state.alarms.start_all().await;
+    let alarms_for_shutdown = app_state.alarms.clone();
+
+    // Step 7: Sample the fans for the event stream
+    app_state.telemetry.start_all().await;
+    let telemetry_for_shutdown = app_state.telemetry.clone();
+
+    // Step 8: Record the sampled fan readings
+    app_state.history.start_all().await;
+    let history_for_shutdown = app_state.history.clone();
+
+    // Step 9: Reload the configuration on SIGHUP or file changes
+    app_state.reload.start_all().await;
+    let reload_for_shutdown = app_state.reload.clone();
+    let calibration_for_shutdown = app_state.calibration.clone();
+
     // Set up API router
     let app = api::create_router(app_state);
 
@@ -261,9 +405,16 @@ async fn main() -> Result<()> {
     axum::serve(listener, app)
         .with_graceful_shutdown(async move {
             shutdown_signal().await;
+            // Stop reloads, calibration, control loops and alarms first so they don't override the shutdown profile
+            reload_for_shutdown.stop_all().await;
+            calibration_for_shutdown.stop_all().await;
+            control_for_shutdown.stop_all().await;
+            alarms_for_shutdown.stop_all().await;
+            telemetry_for_shutdown.stop_all().await;
+            history_for_shutdown.stop_all().await;
             shutdown::apply_safe_boot_profile(
                 &runtime_config_for_shutdown,
-                cm_for_shutdown.as_ref(),
+                &registry_for_shutdown,
                 is_mock,
             )
             .await;
@@ -274,48 +425,26 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// Connect to a single controller and wrap in ConnectionManager
+/// Connect to a single controller, exiting if it cannot be opened
 async fn connect_controller(
     id: &str,
-    device_path: &str,
+    device: Device,
+    board_info: &BoardInfo,
     timeout_ms: u64,
     verbose: bool,
     reconnect_config: &openfan_core::ReconnectConfig,
 ) -> Option<Arc<ConnectionManager>> {
-    info!("Connecting to controller '{}' at {}...", id, device_path);
-
-    match connection::connect_to_device(device_path, timeout_ms, verbose).await {
-        Ok(mut controller) => {
-            info!("Controller '{}' connected successfully", id);
-
-            // Test the connection
-            if let Err(e) = connection::test_connection(&mut controller).await {
-                warn!(
-                    "Controller '{}' hardware test failed, continuing: {}",
-                    id, e
-                );
-            }
-
-            // Wrap in ConnectionManager
-            let manager = Arc::new(ConnectionManager::new(
-                controller,
-                reconnect_config.clone(),
-                device_path.to_string(),
-                timeout_ms,
-                verbose,
-            ));
-
-            // Start heartbeat if enabled
-            if reconnect_config.enable_heartbeat {
-                info!(
-                    "Controller '{}': heartbeat enabled (interval: {}s)",
-                    id, reconnect_config.heartbeat_interval_secs
-                );
-                manager.clone().start_heartbeat();
-            }
-
-            Some(manager)
-        }
+    match connection::connect(
+        id,
+        device,
+        board_info,
+        timeout_ms,
+        verbose,
+        reconnect_config,
+    )
+    .await
+    {
+        Ok(manager) => Some(manager),
         Err(e) => {
             error!(
                 "Controller '{}' connection failed: {}. Use --mock for testing without hardware.",
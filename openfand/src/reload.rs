@@ -0,0 +1,870 @@
+//! Configuration reload
+//!
+//! Reads `config.toml` and the data files again on SIGHUP, on
+//! `POST /api/v0/config/reload`, or when `[reload] watch` sees them change.
+//!
+//! The new configuration is validated like at startup before anything is
+//! replaced, and controllers added or changed in `[[controllers]]` are
+//! connected before the old ones are dropped. A rejected reload keeps the
+//! previous configuration; like every reload, it is reported by
+//! `GET /api/v0/config/reload`. Background tasks affected by the changes are
+//! restarted.
+//!
+//! `[server]`, `data_dir` and `[reconnect]` are only read at startup, and the
+//! default controller keeps its device and board until restart. Changes to
+//! them are left out of the reload and reported as warnings.
+//!
+//! Controllers can also be added and removed on request, through
+//! `POST /api/v0/controllers` and `DELETE /api/v0/controller/{id}`. These go
+//! through the same locking and task restarts, and may update
+//! `[[controllers]]` to match.
+
+use crate::alarms::{AlarmMonitor, unix_time};
+use crate::config::{ReloadedConfig, RuntimeConfig, differs};
+use crate::control::ControlEngine;
+use crate::controllers::{ConnectionManager, ControllerEntry, ControllerRegistry, connection};
+use crate::history::History;
+use crate::sensors::{self, SensorManager};
+use crate::startup::{self, StartupOutcome};
+use crate::telemetry::Telemetry;
+use openfan_core::api::{ConfigResource, ReloadReport, ReloadTrigger};
+use openfan_core::{ControllerConfig, OpenFanError, ReconnectConfig, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::fs;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// How the controllers were set up at startup
+#[derive(Debug, Clone)]
+pub(crate) struct ControllerSetup {
+    /// Controllers come from `[[controllers]]` and follow its changes
+    pub from_config: bool,
+    /// ID of the default controller, kept until restart
+    pub default_id: String,
+    /// Back controllers with simulated boards
+    pub mock: bool,
+    /// Enable UART debug logging
+    pub verbose: bool,
+}
+
+impl Default for ControllerSetup {
+    fn default() -> Self {
+        Self {
+            from_config: false,
+            default_id: "default".to_string(),
+            mock: false,
+            verbose: false,
+        }
+    }
+}
+
+/// Changes to the registered controllers
+#[derive(Default)]
+struct ControllerPlan {
+    /// Controllers to connect and register
+    added: Vec<ControllerConfig>,
+    /// Controllers to reconnect, as their device or board changed
+    reconnected: Vec<ControllerConfig>,
+    /// Controllers whose description changed
+    described: Vec<ControllerConfig>,
+    /// IDs of the controllers to unregister
+    removed: Vec<String>,
+}
+
+impl ControllerPlan {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.reconnected.is_empty()
+            && self.described.is_empty()
+            && self.removed.is_empty()
+    }
+
+    /// Describe the changes, as in [`ReloadReport::changes`]
+    fn describe(&self) -> Vec<String> {
+        let mut changes = Vec::new();
+        for (configs, what) in [
+            (&self.added, "added"),
+            (&self.reconnected, "reconnected"),
+            (&self.described, "description"),
+        ] {
+            changes.extend(
+                configs
+                    .iter()
+                    .map(|ctrl| format!("controller '{}': {}", ctrl.id, what)),
+            );
+        }
+        changes.extend(
+            self.removed
+                .iter()
+                .map(|id| format!("controller '{}': removed", id)),
+        );
+        changes
+    }
+}
+
+/// Background tasks to restart after a reload
+#[derive(Default)]
+struct Restart {
+    control: bool,
+    alarms: bool,
+    telemetry: bool,
+    history: bool,
+}
+
+impl Restart {
+    /// Restart every task, as the controllers changed
+    fn all() -> Self {
+        Self {
+            control: true,
+            alarms: true,
+            telemetry: true,
+            history: true,
+        }
+    }
+}
+
+/// Modification time and length of each watched file
+type Fingerprint = HashMap<PathBuf, (Option<SystemTime>, u64)>;
+
+/// Background tasks that follow the configuration
+pub(crate) struct Services {
+    pub control: Arc<ControlEngine>,
+    pub alarms: Arc<AlarmMonitor>,
+    pub telemetry: Arc<Telemetry>,
+    pub history: Arc<History>,
+}
+
+impl Services {
+    async fn stop_all(&self) {
+        self.control.stop_all().await;
+        self.alarms.stop_all().await;
+        self.telemetry.stop_all().await;
+        self.history.stop_all().await;
+    }
+
+    async fn start_all(&self) {
+        self.control.start_all().await;
+        self.alarms.start_all().await;
+        self.telemetry.start_all().await;
+        self.history.start_all().await;
+    }
+
+    async fn restart(&self, restart: &Restart) {
+        if restart.control {
+            self.control.stop_all().await;
+            self.control.start_all().await;
+        }
+        if restart.alarms {
+            self.alarms.stop_all().await;
+            self.alarms.start_all().await;
+        }
+        if restart.telemetry {
+            self.telemetry.stop_all().await;
+            self.telemetry.start_all().await;
+        }
+        if restart.history {
+            self.history.stop_all().await;
+            self.history.start_all().await;
+        }
+    }
+}
+
+/// Reloads the configuration and the controllers it defines, and adds or
+/// removes controllers on request.
+pub(crate) struct Reloader {
+    registry: Arc<ControllerRegistry>,
+    config: Arc<RuntimeConfig>,
+    sensors: Arc<SensorManager>,
+    services: Services,
+    setup: ControllerSetup,
+    /// Held while reloading; set once stopped, after which reloads are refused
+    stopped: Mutex<bool>,
+    /// Outcome of the last reload
+    last: RwLock<Option<ReloadReport>>,
+    /// Signal and file watcher tasks
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Reloader {
+    pub fn new(
+        registry: Arc<ControllerRegistry>,
+        config: Arc<RuntimeConfig>,
+        sensors: Arc<SensorManager>,
+        services: Services,
+        setup: ControllerSetup,
+    ) -> Self {
+        Self {
+            registry,
+            config,
+            sensors,
+            services,
+            setup,
+            stopped: Mutex::new(false),
+            last: RwLock::new(None),
+            tasks: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Reload on SIGHUP, and on file changes when `[reload] watch` is set.
+    pub async fn start_all(self: &Arc<Self>) {
+        let mut tasks = self.tasks.lock().await;
+        // Installed before returning, so SIGHUP no longer terminates the daemon
+        #[cfg(unix)]
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+            Ok(hangup) => tasks.push(tokio::spawn(run_signal(self.clone(), hangup))),
+            Err(e) => warn!("Cannot reload on SIGHUP: {}", e),
+        }
+        tasks.push(tokio::spawn(run_watcher(self.clone())));
+    }
+
+    /// Stop reloading, waiting for a reload in progress to finish.
+    ///
+    /// Called before the shutdown profile is applied so a reload doesn't
+    /// restart the control loops.
+    pub async fn stop_all(&self) {
+        *self.stopped.lock().await = true;
+        for handle in self.tasks.lock().await.drain(..) {
+            handle.abort();
+        }
+    }
+
+    /// Outcome of the last reload, if any.
+    pub async fn last(&self) -> Option<ReloadReport> {
+        self.last.read().await.clone()
+    }
+
+    /// Reload the configuration, keeping the previous one if the new one is rejected.
+    pub async fn reload(&self, trigger: ReloadTrigger) -> ReloadReport {
+        let result = match self.lock_running().await {
+            Ok(_running) => self.try_reload().await,
+            Err(e) => Err(e),
+        };
+
+        let mut report = ReloadReport {
+            trigger,
+            timestamp: unix_time(),
+            success: false,
+            error: None,
+            changes: Vec::new(),
+            warnings: Vec::new(),
+        };
+        match result {
+            Ok((changes, warnings)) => {
+                if changes.is_empty() {
+                    info!("Configuration reloaded: no changes");
+                } else {
+                    info!("Configuration reloaded: {}", changes.join(", "));
+                }
+                for warning in &warnings {
+                    warn!("{}", warning);
+                }
+                report.success = true;
+                report.changes = changes;
+                report.warnings = warnings;
+            }
+            Err(e) => {
+                warn!(
+                    "Configuration reload rejected, keeping the previous configuration: {}",
+                    e
+                );
+                report.error = Some(e.to_string());
+            }
+        }
+
+        *self.last.write().await = Some(report.clone());
+        report
+    }
+
+    /// Connect and register a controller while the server runs.
+    ///
+    /// The controller gets its startup fan state like one added by a reload.
+    /// With `persist`, it is also added to `[[controllers]]`, which needs the
+    /// controllers to come from the config file.
+    pub async fn add_controller(
+        &self,
+        ctrl: ControllerConfig,
+        persist: bool,
+    ) -> Result<Arc<ControllerEntry>> {
+        let _running = self.lock_running().await?;
+        ctrl.validate().map_err(OpenFanError::InvalidInput)?;
+        if self.registry.get(&ctrl.id).await.is_some() {
+            return Err(OpenFanError::DuplicateControllerId(ctrl.id));
+        }
+        if persist {
+            self.check_persist()?;
+        }
+
+        let static_config = self.config.static_config();
+        let board_info = ctrl.board.to_board_info();
+        let cm = connection::connect(
+            &ctrl.id,
+            connection::device_for_config(&ctrl, self.setup.mock),
+            &board_info,
+            static_config.server.communication_timeout * 1000,
+            self.setup.verbose,
+            &static_config.reconnect,
+        )
+        .await
+        .map_err(|e| {
+            OpenFanError::Hardware(format!("Controller '{}' connection failed: {}", ctrl.id, e))
+        })?;
+        if persist && let Err(e) = self.config.add_controller_entry(&ctrl).await {
+            cm.close().await;
+            return Err(e);
+        }
+
+        let entry = ControllerEntry::builder(&ctrl.id, board_info)
+            .maybe_connection_manager(Some(cm))
+            .maybe_description(ctrl.description.clone())
+            .build();
+        self.registry.replace(entry).await;
+        info!("Controller '{}' added", ctrl.id);
+
+        let id = ctrl.id.clone();
+        let plan = ControllerPlan {
+            added: vec![ctrl],
+            ..Default::default()
+        };
+        self.start_new_controllers(&plan).await;
+        self.services.restart(&Restart::all()).await;
+
+        self.registry.get_or_err(&id).await
+    }
+
+    /// Disconnect and unregister a controller while the server runs.
+    ///
+    /// The default controller stays until restart, and a controller used by a
+    /// zone stays until the zone no longer uses it. With `persist`, it is also
+    /// removed from `[[controllers]]`.
+    pub async fn remove_controller(&self, id: &str, persist: bool) -> Result<()> {
+        let _running = self.lock_running().await?;
+        if id == self.setup.default_id {
+            return Err(OpenFanError::InvalidInput(format!(
+                "Controller '{}' is the default controller and is only removed on restart",
+                id
+            )));
+        }
+        self.registry.get_or_err(id).await?;
+        if persist {
+            self.check_persist()?;
+        }
+
+        let mut boards = self.registry.boards().await;
+        boards.remove(id);
+        self.config.validate_zones(&boards).await?;
+
+        let listed = self
+            .config
+            .static_config()
+            .controllers
+            .iter()
+            .any(|ctrl| ctrl.id == id);
+        if persist && listed {
+            self.config.remove_controller_entry(id).await?;
+        }
+
+        // Nothing may use the controller while it is closed
+        self.services.stop_all().await;
+        self.unregister(id).await;
+        self.services.start_all().await;
+        Ok(())
+    }
+
+    /// Hold off reloads, unless the server is shutting down.
+    async fn lock_running(&self) -> Result<tokio::sync::MutexGuard<'_, bool>> {
+        let stopped = self.stopped.lock().await;
+        if *stopped {
+            return Err(OpenFanError::Config(
+                "The server is shutting down".to_string(),
+            ));
+        }
+        Ok(stopped)
+    }
+
+    /// Check that `[[controllers]]` describes the controllers, so it can be edited.
+    fn check_persist(&self) -> Result<()> {
+        if self.setup.from_config {
+            Ok(())
+        } else {
+            Err(OpenFanError::InvalidInput(
+                "Cannot persist: the controllers were set up by --device, --mock or \
+                 auto-detection rather than [[controllers]]"
+                    .to_string(),
+            ))
+        }
+    }
+
+    /// Read, validate and apply the configuration.
+    ///
+    /// Returns the changes and the warnings of the report.
+    async fn try_reload(&self) -> Result<(Vec<String>, Vec<String>)> {
+        let current = self.config.static_config();
+        let mut reloaded = self.config.read_back().await?;
+        let mut warnings = Vec::new();
+
+        // Settings only read at startup keep the value in use
+        let new = &mut reloaded.static_config;
+        if differs(&current.server, &new.server) {
+            warnings.push("[server] changes need a restart".to_string());
+        }
+        new.server = current.server.clone();
+        if current.data_dir != new.data_dir {
+            warnings.push("data_dir changes need a restart".to_string());
+        }
+        new.data_dir = current.data_dir.clone();
+        if differs(&current.reconnect, &new.reconnect) {
+            warnings.push("[reconnect] changes need a restart".to_string());
+        }
+        new.reconnect = current.reconnect.clone();
+
+        let plan = self.plan_controllers(&current.controllers, &mut new.controllers, &mut warnings);
+        self.validate(&reloaded, &plan).await?;
+
+        let mut changes = Vec::new();
+        for (section, changed) in [
+            (
+                "[auth]",
+                differs(&current.auth, &reloaded.static_config.auth),
+            ),
+            (
+                "[startup]",
+                differs(&current.startup, &reloaded.static_config.startup),
+            ),
+            (
+                "[shutdown]",
+                differs(&current.shutdown, &reloaded.static_config.shutdown),
+            ),
+            (
+                "[sensors]",
+                differs(&current.sensors, &reloaded.static_config.sensors),
+            ),
+            (
+                "[alarms]",
+                differs(&current.alarms, &reloaded.static_config.alarms),
+            ),
+            (
+                "[telemetry]",
+                differs(&current.telemetry, &reloaded.static_config.telemetry),
+            ),
+            (
+                "[history]",
+                differs(&current.history, &reloaded.static_config.history),
+            ),
+            (
+                "[reload]",
+                differs(&current.reload, &reloaded.static_config.reload),
+            ),
+            (
+                "[fan_limits]",
+                differs(&current.fan_limits, &reloaded.static_config.fan_limits),
+            ),
+        ] {
+            if changed {
+                changes.push(section.to_string());
+            }
+        }
+        let sensors_changed = differs(&current.sensors, &reloaded.static_config.sensors);
+        let mut restart = Restart {
+            control: sensors_changed,
+            alarms: differs(&current.alarms, &reloaded.static_config.alarms),
+            telemetry: sensors_changed
+                || differs(&current.telemetry, &reloaded.static_config.telemetry),
+            history: differs(&current.history, &reloaded.static_config.history)
+                || differs(&current.telemetry, &reloaded.static_config.telemetry),
+        };
+
+        if !plan.is_empty() {
+            // Nothing may use the controllers while they are swapped
+            self.services.stop_all().await;
+            if let Err(e) = self.apply_controllers(&plan, &current).await {
+                self.services.start_all().await;
+                return Err(e);
+            }
+            changes.extend(plan.describe());
+            restart = Restart::all();
+        }
+
+        if sensors_changed {
+            self.sensors
+                .reconfigure(&reloaded.static_config.sensors)
+                .await;
+        }
+        for (controller, resource) in self.config.apply(reloaded).await {
+            if matches!(
+                resource,
+                ConfigResource::ThermalCurves
+                    | ConfigResource::PidControllers
+                    | ConfigResource::CurveBindings
+            ) {
+                restart.control = true;
+            }
+            match (controller, resource) {
+                (_, ConfigResource::Config) => {}
+                (Some(id), resource) => {
+                    changes.push(format!("controller '{}': {}", id, resource_name(resource)))
+                }
+                (None, resource) => changes.push(resource_name(resource).to_string()),
+            }
+        }
+
+        if !plan.is_empty() {
+            self.start_new_controllers(&plan).await;
+            self.services.start_all().await;
+        } else {
+            self.services.restart(&restart).await;
+        }
+
+        Ok((changes, warnings))
+    }
+
+    /// Work out the controller changes, keeping what only changes on restart.
+    fn plan_controllers(
+        &self,
+        current: &[ControllerConfig],
+        new: &mut Vec<ControllerConfig>,
+        warnings: &mut Vec<String>,
+    ) -> ControllerPlan {
+        let mut plan = ControllerPlan::default();
+
+        if !self.setup.from_config {
+            if differs(&current, &new.as_slice()) {
+                warnings.push(
+                    "[[controllers]] changes need a restart: the controllers were set up \
+                     by --device, --mock or auto-detection"
+                        .to_string(),
+                );
+            }
+            *new = current.to_vec();
+            return plan;
+        }
+
+        // The default controller stays as it is until restart
+        let default_id = &self.setup.default_id;
+        if let Some(old) = current.iter().find(|ctrl| &ctrl.id == default_id) {
+            match new.iter_mut().find(|ctrl| &ctrl.id == default_id) {
+                Some(ctrl) if connection_differs(old, ctrl) => {
+                    warnings.push(format!(
+                        "Controller '{}' is the default controller: its device and board \
+                         only change on restart",
+                        default_id
+                    ));
+                    ctrl.device = old.device.clone();
+                    ctrl.serial_number = old.serial_number.clone();
+                    ctrl.board = old.board;
+                }
+                Some(_) => {}
+                None => {
+                    warnings.push(format!(
+                        "Controller '{}' is the default controller: it is only removed on restart",
+                        default_id
+                    ));
+                    new.insert(0, old.clone());
+                }
+            }
+        }
+
+        for ctrl in new.iter() {
+            match current.iter().find(|old| old.id == ctrl.id) {
+                None => plan.added.push(ctrl.clone()),
+                Some(old) if connection_differs(old, ctrl) => plan.reconnected.push(ctrl.clone()),
+                Some(old) if old.description != ctrl.description => {
+                    plan.described.push(ctrl.clone())
+                }
+                Some(_) => {}
+            }
+        }
+        plan.removed = current
+            .iter()
+            .filter(|old| !new.iter().any(|ctrl| ctrl.id == old.id))
+            .map(|old| old.id.clone())
+            .collect();
+
+        plan
+    }
+
+    /// Validate the configuration against the boards of the planned controllers.
+    async fn validate(&self, reloaded: &ReloadedConfig, plan: &ControllerPlan) -> Result<()> {
+        let mut boards = self.registry.boards().await;
+        for id in &plan.removed {
+            boards.remove(id);
+        }
+        for ctrl in plan.added.iter().chain(&plan.reconnected) {
+            boards.insert(ctrl.id.clone(), ctrl.board.to_board_info());
+        }
+
+        let default_board = boards
+            .get(&self.setup.default_id)
+            .cloned()
+            .ok_or_else(|| OpenFanError::ControllerNotFound(self.setup.default_id.clone()))?;
+        let sensors = sensors::sensor_ids(&reloaded.static_config.sensors).await;
+        reloaded.validate(&default_board, &boards, &sensors).await
+    }
+
+    /// Connect, register and unregister controllers as planned.
+    ///
+    /// Reconnected controllers close their device first, in case the new one
+    /// is the same port. If any controller fails to connect, they reopen it
+    /// and the registry is left untouched.
+    async fn apply_controllers(
+        &self,
+        plan: &ControllerPlan,
+        static_config: &openfan_core::StaticConfig,
+    ) -> Result<()> {
+        let timeout_ms = static_config.server.communication_timeout * 1000;
+        let reconnect = &static_config.reconnect;
+
+        let mut closed = Vec::new();
+        for ctrl in &plan.reconnected {
+            if let Some(entry) = self.registry.get(&ctrl.id).await
+                && let Some(cm) = entry.connection_manager()
+            {
+                cm.close().await;
+                closed.push(cm.clone());
+            }
+        }
+
+        let mut connected = Vec::new();
+        for ctrl in plan.added.iter().chain(&plan.reconnected) {
+            let board_info = ctrl.board.to_board_info();
+            let device = connection::device_for_config(ctrl, self.setup.mock);
+            match connection::connect(
+                &ctrl.id,
+                device,
+                &board_info,
+                timeout_ms,
+                self.setup.verbose,
+                reconnect,
+            )
+            .await
+            {
+                Ok(cm) => connected.push((ctrl, board_info, cm)),
+                Err(e) => {
+                    for (_, _, cm) in &connected {
+                        cm.close().await;
+                    }
+                    for cm in closed {
+                        reopen(&cm, reconnect).await;
+                    }
+                    return Err(OpenFanError::Config(format!(
+                        "Controller '{}' connection failed: {}",
+                        ctrl.id, e
+                    )));
+                }
+            }
+        }
+
+        for id in &plan.removed {
+            self.unregister(id).await;
+        }
+        for (ctrl, board_info, cm) in connected {
+            let entry = ControllerEntry::builder(&ctrl.id, board_info)
+                .maybe_connection_manager(Some(cm))
+                .maybe_description(ctrl.description.clone())
+                .build();
+            // A controller added at runtime may come back from the file
+            if let Some(previous) = self.registry.replace(entry).await
+                && let Some(cm) = previous.connection_manager()
+            {
+                cm.close().await;
+            }
+        }
+        for ctrl in &plan.described {
+            if let Some(entry) = self.registry.get(&ctrl.id).await {
+                let entry = ControllerEntry::builder(&ctrl.id, entry.board_info().clone())
+                    .maybe_connection_manager(entry.connection_manager().cloned())
+                    .maybe_description(ctrl.description.clone())
+                    .build();
+                self.registry.replace(entry).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Disconnect and unregister a controller, dropping its cached data.
+    ///
+    /// Its data files are kept, so it gets them back if added again.
+    async fn unregister(&self, id: &str) {
+        if let Some(entry) = self.registry.unregister(id).await
+            && let Some(cm) = entry.connection_manager()
+        {
+            cm.close().await;
+        }
+        self.config.remove_controller_data(id).await;
+        info!("Controller '{}' removed", id);
+    }
+
+    /// Set the initial fan state of the controllers connected by the reload.
+    async fn start_new_controllers(&self, plan: &ControllerPlan) {
+        if !self.config.static_config().startup.enabled {
+            return;
+        }
+        for ctrl in plan.added.iter().chain(&plan.reconnected) {
+            let Some(entry) = self.registry.get(&ctrl.id).await else {
+                continue;
+            };
+            match startup::start_controller(&self.config, &entry).await {
+                StartupOutcome::Restored => {
+                    info!("Restored the last fan state of controller '{}'", ctrl.id)
+                }
+                StartupOutcome::Applied(profile) => info!(
+                    "Startup profile '{}' applied to controller '{}'",
+                    profile, ctrl.id
+                ),
+                StartupOutcome::Skipped => {}
+                StartupOutcome::Failed(reason) => warn!(
+                    "Failed to set the startup fan state of controller '{}': {}",
+                    ctrl.id, reason
+                ),
+            }
+        }
+    }
+
+    /// Files whose changes trigger a reload, when watched.
+    async fn fingerprint(&self) -> Fingerprint {
+        let data_dir = self.config.data_dir();
+        let mut paths = vec![self.config.config_path().to_path_buf()];
+        paths.extend(data_files(data_dir).await);
+        if let Ok(mut dirs) = fs::read_dir(data_dir.join("controllers")).await {
+            while let Ok(Some(dir)) = dirs.next_entry().await {
+                paths.extend(data_files(&dir.path()).await);
+            }
+        }
+
+        let mut fingerprint = Fingerprint::new();
+        for path in paths {
+            if let Ok(metadata) = fs::metadata(&path).await {
+                fingerprint.insert(path, (metadata.modified().ok(), metadata.len()));
+            }
+        }
+        fingerprint
+    }
+}
+
+/// Whether a controller needs reconnecting to apply a new entry
+fn connection_differs(old: &ControllerConfig, new: &ControllerConfig) -> bool {
+    differs(
+        &(&old.device, &old.serial_number, &old.board),
+        &(&new.device, &new.serial_number, &new.board),
+    )
+}
+
+/// Reopen the device of a controller closed for a reload that failed
+async fn reopen(cm: &Arc<ConnectionManager>, reconnect: &ReconnectConfig) {
+    if let Err(e) = cm.force_reconnect().await {
+        warn!(
+            "Failed to reopen a controller after a rejected reload: {}",
+            e
+        );
+    }
+    if reconnect.enable_heartbeat {
+        cm.clone().start_heartbeat();
+    }
+}
+
+/// Name of a data resource in reload reports, as in its file name
+fn resource_name(resource: ConfigResource) -> &'static str {
+    match resource {
+        ConfigResource::Aliases => "aliases",
+        ConfigResource::Profiles => "profiles",
+        ConfigResource::Zones => "zones",
+        ConfigResource::ThermalCurves => "thermal_curves",
+        ConfigResource::PidControllers => "pid_controllers",
+        ConfigResource::CurveBindings => "curve_bindings",
+        ConfigResource::CfmMappings => "cfm_mappings",
+        ConfigResource::FanLimits => "fan_limits",
+        ConfigResource::Config => "config",
+    }
+}
+
+/// Data files written by the daemon itself rather than edited, never watched
+const DAEMON_FILES: [&str; 2] = ["fan_state.toml", "fan_calibration.toml"];
+
+/// Data files of a directory, leaving out the ones written by the daemon
+async fn data_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(mut entries) = fs::read_dir(dir).await else {
+        return files;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "toml")
+            && path
+                .file_name()
+                .is_some_and(|name| !DAEMON_FILES.iter().any(|file| name == *file))
+        {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// Reload on every SIGHUP
+#[cfg(unix)]
+async fn run_signal(reloader: Arc<Reloader>, mut hangup: tokio::signal::unix::Signal) {
+    while hangup.recv().await.is_some() {
+        info!("Received SIGHUP, reloading configuration...");
+        reloader.reload(ReloadTrigger::Signal).await;
+    }
+}
+
+/// Reload when a watched file changes, while `[reload] watch` is set
+async fn run_watcher(reloader: Arc<Reloader>) {
+    let mut seen: Option<Fingerprint> = None;
+    loop {
+        let reload_config = reloader.config.static_config().reload.clone();
+        if !reload_config.watch {
+            seen = None;
+        } else {
+            let fingerprint = reloader.fingerprint().await;
+            match &seen {
+                Some(seen) if *seen != fingerprint => {
+                    info!("Configuration files changed, reloading configuration...");
+                    reloader.reload(ReloadTrigger::Watch).await;
+                }
+                Some(_) => {}
+                None => info!(
+                    "Watching configuration files for changes every {}s",
+                    reload_config.interval_secs.max(1)
+                ),
+            }
+            // Files created by the reload itself are not changes
+            seen = Some(reloader.fingerprint().await);
+        }
+        tokio::time::sleep(Duration::from_secs(reload_config.interval_secs.max(1))).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn test_data_files_leave_out_daemon_files() {
+        let temp_dir = TempDir::new().unwrap();
+        for file in [
+            "profiles.toml",
+            "fan_limits.toml",
+            "fan_state.toml",
+            "fan_calibration.toml",
+            "notes.txt",
+        ] {
+            fs::write(temp_dir.path().join(file), "").await.unwrap();
+        }
+
+        let mut files = data_files(temp_dir.path()).await;
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                temp_dir.path().join("fan_limits.toml"),
+                temp_dir.path().join("profiles.toml"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Custom file and command temperature sources
+//!
+//! These back the `[[sensors.custom]]` entries of `config.toml`. Both kinds
+//! parse the first number found in their output; the caller applies the
+//! configured scale.
+
+use openfan_core::{OpenFanError, Result};
+use std::path::Path;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+
+/// Read the first number from a file.
+pub(crate) async fn read_file(id: &str, path: &Path) -> Result<f64> {
+    let content = tokio::fs::read_to_string(path).await.map_err(|e| {
+        OpenFanError::Sensor(format!(
+            "Sensor '{}': failed to read '{}': {}",
+            id,
+            path.display(),
+            e
+        ))
+    })?;
+
+    parse_number(&content).ok_or_else(|| {
+        OpenFanError::Parse(format!(
+            "Sensor '{}': no number found in '{}'",
+            id,
+            path.display()
+        ))
+    })
+}
+
+/// Run a program and read the first number from its stdout.
+///
+/// The program is killed if it does not exit within `timeout`.
+pub(crate) async fn run_command(
+    id: &str,
+    command: &str,
+    args: &[String],
+    timeout: Duration,
+) -> Result<f64> {
+    let child = Command::new(command)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| {
+            OpenFanError::Sensor(format!(
+                "Sensor '{}': failed to run '{}': {}",
+                id, command, e
+            ))
+        })?;
+
+    // Dropping the future on timeout drops the child, which kills it
+    let output = tokio::time::timeout(timeout, child.wait_with_output())
+        .await
+        .map_err(|_| {
+            OpenFanError::Timeout(format!(
+                "Sensor '{}': '{}' did not finish within {}s",
+                id,
+                command,
+                timeout.as_secs()
+            ))
+        })?
+        .map_err(|e| {
+            OpenFanError::Sensor(format!(
+                "Sensor '{}': failed to run '{}': {}",
+                id, command, e
+            ))
+        })?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.lines().next().unwrap_or("").trim();
+        return Err(OpenFanError::Sensor(format!(
+            "Sensor '{}': '{}' exited with {}{}{}",
+            id,
+            command,
+            output.status,
+            if detail.is_empty() { "" } else { ": " },
+            detail
+        )));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_number(&stdout).ok_or_else(|| {
+        OpenFanError::Parse(format!(
+            "Sensor '{}': no number found in output of '{}'",
+            id, command
+        ))
+    })
+}
+
+/// Find the first whitespace-separated token that parses as a finite number.
+fn parse_number(output: &str) -> Option<f64> {
+    output
+        .split_whitespace()
+        .filter_map(|token| token.parse::<f64>().ok())
+        .find(|value| value.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_number() {
+        assert_eq!(parse_number("42\n"), Some(42.0));
+        assert_eq!(parse_number("  -3.5  "), Some(-3.5));
+        assert_eq!(parse_number("Temperature: 38 Celsius"), Some(38.0));
+        assert_eq!(parse_number("no digits here"), None);
+        assert_eq!(parse_number(""), None);
+        assert_eq!(parse_number("inf 12"), Some(12.0));
+    }
+
+    #[tokio::test]
+    async fn test_read_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("temp");
+        std::fs::write(&path, "41250\n").unwrap();
+        assert_eq!(read_file("a", &path).await.unwrap(), 41250.0);
+
+        std::fs::write(&path, "n/a\n").unwrap();
+        let err = read_file("a", &path).await.unwrap_err();
+        assert!(matches!(err, OpenFanError::Parse(_)));
+
+        let err = read_file("a", &temp_dir.path().join("missing"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OpenFanError::Sensor(_)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_command() {
+        let args = vec!["-c".to_string(), "echo 'temp: 55.5'".to_string()];
+        let value = run_command("a", "sh", &args, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(value, 55.5);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_command_errors() {
+        let timeout = Duration::from_secs(5);
+
+        let err = run_command("a", "/nonexistent/sensor-tool", &[], timeout)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OpenFanError::Sensor(_)));
+
+        let args = vec!["-c".to_string(), "echo broken >&2; exit 3".to_string()];
+        let err = run_command("a", "sh", &args, timeout).await.unwrap_err();
+        assert!(matches!(err, OpenFanError::Sensor(_)));
+        assert!(err.to_string().contains("broken"));
+
+        let args = vec!["-c".to_string(), "echo hot".to_string()];
+        let err = run_command("a", "sh", &args, timeout).await.unwrap_err();
+        assert!(matches!(err, OpenFanError::Parse(_)));
+
+        let args = vec!["-c".to_string(), "sleep 5".to_string()];
+        let err = run_command("a", "sh", &args, Duration::from_millis(100))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OpenFanError::Timeout(_)));
+    }
+}
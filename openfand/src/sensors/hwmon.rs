@@ -0,0 +1,206 @@
+//! Linux hwmon temperature sensor discovery
+//!
+//! Scans a sysfs hwmon root (normally `/sys/class/hwmon`) for chips and their
+//! `temp*_input` files. Each chip directory looks like:
+//!
+//! ```text
+//! hwmon2/
+//!   name            -> "k10temp"
+//!   temp1_input     -> "45250"  (millidegrees Celsius)
+//!   temp1_label     -> "Tctl"   (optional)
+//! ```
+//!
+//! `hwmonN` numbering is assigned by the kernel in probe order and is not
+//! stable across reboots, so sensor IDs are built from the chip name instead:
+//! `{chip}/temp{N}`, e.g. `k10temp/temp1`. When several chips share a name
+//! (e.g. multiple NVMe drives), the second one becomes `nvme-1`, the third
+//! `nvme-2`, and so on, in `hwmonN` order.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// A temperature input exposed by a hwmon chip
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct HwmonSensor {
+    /// Stable sensor ID (`{chip}/temp{N}`)
+    pub id: String,
+    /// Chip name from the `name` file
+    pub chip: String,
+    /// Label from the `temp{N}_label` file, if present
+    pub label: Option<String>,
+    /// Path of the `temp{N}_input` file
+    pub path: PathBuf,
+}
+
+/// Enumerate all temperature inputs under a hwmon root.
+///
+/// Returns sensors in `hwmonN` order, then by input index. A missing or
+/// unreadable root yields an empty list (e.g. on non-Linux hosts).
+pub(crate) async fn discover(root: &Path) -> Vec<HwmonSensor> {
+    let mut chip_dirs = match list_dir(root).await {
+        Ok(entries) => entries,
+        Err(e) => {
+            debug!("Cannot read hwmon root '{}': {}", root.display(), e);
+            return Vec::new();
+        }
+    };
+    chip_dirs.sort_by_key(|(name, _)| (index_suffix(name, "hwmon"), name.clone()));
+
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut sensors = Vec::new();
+
+    for (dir_name, chip_dir) in chip_dirs {
+        let chip = read_trimmed(&chip_dir.join("name"))
+            .await
+            .unwrap_or_else(|| dir_name.clone());
+
+        let count = seen.entry(chip.clone()).or_insert(0);
+        let chip_key = if *count == 0 {
+            chip.clone()
+        } else {
+            format!("{}-{}", chip, count)
+        };
+        *count += 1;
+
+        let Ok(files) = list_dir(&chip_dir).await else {
+            continue;
+        };
+        let mut inputs: Vec<u32> = files
+            .iter()
+            .filter_map(|(name, _)| temp_input_index(name))
+            .collect();
+        inputs.sort_unstable();
+
+        for index in inputs {
+            let label = read_trimmed(&chip_dir.join(format!("temp{}_label", index))).await;
+            sensors.push(HwmonSensor {
+                id: format!("{}/temp{}", chip_key, index),
+                chip: chip.clone(),
+                label,
+                path: chip_dir.join(format!("temp{}_input", index)),
+            });
+        }
+    }
+
+    sensors
+}
+
+/// List the entries of a directory as (file name, path) pairs.
+async fn list_dir(dir: &Path) -> std::io::Result<Vec<(String, PathBuf)>> {
+    let mut entries = Vec::new();
+    let mut read_dir = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = read_dir.next_entry().await? {
+        if let Some(name) = entry.file_name().to_str() {
+            entries.push((name.to_string(), entry.path()));
+        }
+    }
+    Ok(entries)
+}
+
+/// Read a small sysfs attribute, trimmed. Returns `None` if missing or empty.
+async fn read_trimmed(path: &Path) -> Option<String> {
+    let content = tokio::fs::read_to_string(path).await.ok()?;
+    let content = content.trim();
+    (!content.is_empty()).then(|| content.to_string())
+}
+
+/// Parse the numeric suffix of names like `hwmon12`; non-matching names sort last.
+fn index_suffix(name: &str, prefix: &str) -> u32 {
+    name.strip_prefix(prefix)
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(u32::MAX)
+}
+
+/// Parse `N` from a `temp{N}_input` file name.
+fn temp_input_index(name: &str) -> Option<u32> {
+    name.strip_prefix("temp")?
+        .strip_suffix("_input")?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_chip(root: &Path, dir: &str, name: Option<&str>, temps: &[(u32, &str, Option<&str>)]) {
+        let chip_dir = root.join(dir);
+        std::fs::create_dir_all(&chip_dir).unwrap();
+        if let Some(name) = name {
+            std::fs::write(chip_dir.join("name"), format!("{}\n", name)).unwrap();
+        }
+        for (index, value, label) in temps {
+            std::fs::write(chip_dir.join(format!("temp{}_input", index)), value).unwrap();
+            if let Some(label) = label {
+                std::fs::write(chip_dir.join(format!("temp{}_label", index)), label).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_temp_input_index() {
+        assert_eq!(temp_input_index("temp1_input"), Some(1));
+        assert_eq!(temp_input_index("temp12_input"), Some(12));
+        assert_eq!(temp_input_index("temp1_label"), None);
+        assert_eq!(temp_input_index("fan1_input"), None);
+        assert_eq!(temp_input_index("tempX_input"), None);
+    }
+
+    #[tokio::test]
+    async fn test_discover_chips_and_labels() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_chip(
+            root,
+            "hwmon0",
+            Some("k10temp"),
+            &[(1, "45250", Some("Tctl")), (3, "40000", Some("Tccd1"))],
+        );
+        write_chip(root, "hwmon1", Some("acpitz"), &[(1, "27800", None)]);
+        // Chip without temperature inputs
+        write_chip(root, "hwmon2", Some("cros_ec"), &[]);
+
+        let sensors = discover(root).await;
+        let ids: Vec<&str> = sensors.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["k10temp/temp1", "k10temp/temp3", "acpitz/temp1"]);
+
+        assert_eq!(sensors[0].chip, "k10temp");
+        assert_eq!(sensors[0].label.as_deref(), Some("Tctl"));
+        assert_eq!(sensors[0].path, root.join("hwmon0").join("temp1_input"));
+        assert!(sensors[2].label.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_discover_disambiguates_duplicate_chips() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        // hwmon10 must sort after hwmon2
+        write_chip(
+            root,
+            "hwmon10",
+            Some("nvme"),
+            &[(1, "38000", Some("Composite"))],
+        );
+        write_chip(
+            root,
+            "hwmon2",
+            Some("nvme"),
+            &[(1, "35000", Some("Composite"))],
+        );
+        write_chip(root, "hwmon3", None, &[(1, "30000", None)]);
+
+        let sensors = discover(root).await;
+        let ids: Vec<&str> = sensors.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["nvme/temp1", "hwmon3/temp1", "nvme-1/temp1"]);
+        assert_eq!(sensors[2].path, root.join("hwmon10").join("temp1_input"));
+    }
+
+    #[tokio::test]
+    async fn test_discover_missing_root() {
+        let temp_dir = TempDir::new().unwrap();
+        let sensors = discover(&temp_dir.path().join("missing")).await;
+        assert!(sensors.is_empty());
+    }
+}
@@ -0,0 +1,510 @@
+//! Temperature sensors
+//!
+//! Provides discovery and reading of host temperature sensors for the API and
+//! the closed-loop control engine.
+//!
+//! A sensor is referenced either by:
+//! - the ID of a custom file, command or virtual sensor from `config.toml`
+//!   (see [`custom`]),
+//! - a hwmon sensor ID such as `k10temp/temp1` (see [`hwmon`]), or
+//! - an absolute path to a sysfs-style file containing millidegrees Celsius,
+//!   such as `/sys/class/thermal/thermal_zone0/temp`.
+//!
+//! Virtual sensors combine the readings of other sensors (max, min, average or
+//! weighted average, with a per-input offset) and report which input currently
+//! dominates. Their inputs are read on demand, so they are as fresh as the
+//! inputs themselves.
+//!
+//! Read failures are returned as [`OpenFanError`]s and reported in the sensor
+//! listing; they never abort the daemon.
+
+mod custom;
+mod hwmon;
+
+use openfan_core::{
+    AggregateFunction, AggregateInput, CustomSensorConfig, OpenFanError, Result, SensorSource,
+    SensorsConfig,
+    api::{SensorInfo, SensorKind},
+};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// IDs of the custom sensors of `config` and of the discovered hwmon sensors.
+pub(crate) async fn sensor_ids(config: &SensorsConfig) -> HashSet<String> {
+    let mut ids: HashSet<String> = config.custom.iter().map(|s| s.id.clone()).collect();
+    ids.extend(
+        hwmon::discover(&config.hwmon_root)
+            .await
+            .into_iter()
+            .map(|s| s.id),
+    );
+    ids
+}
+
+/// Discovers and reads temperature sensors.
+pub(crate) struct SensorManager {
+    /// `[sensors]` configuration, replaced on reload
+    config: std::sync::RwLock<Arc<SensorsConfig>>,
+    /// Last successful command sensor readings, reused within `cache_ttl_secs`
+    cache: Mutex<HashMap<String, (Instant, f32)>>,
+}
+
+impl SensorManager {
+    /// Create a sensor manager from the `[sensors]` configuration.
+    pub fn new(config: &SensorsConfig) -> Self {
+        Self {
+            config: std::sync::RwLock::new(Arc::new(config.clone())),
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Use a new `[sensors]` configuration, dropping cached readings.
+    pub async fn reconfigure(&self, config: &SensorsConfig) {
+        *self.config.write().unwrap() = Arc::new(config.clone());
+        self.cache.lock().await.clear();
+    }
+
+    fn config(&self) -> Arc<SensorsConfig> {
+        self.config.read().unwrap().clone()
+    }
+
+    /// List all sensors with their current readings.
+    ///
+    /// Custom sensors come first, in configuration order, followed by the
+    /// discovered hwmon sensors. A sensor that fails to read is still listed,
+    /// with its error set.
+    pub async fn list(&self) -> Vec<SensorInfo> {
+        let config = self.config();
+        let mut sensors = Vec::new();
+
+        for sensor in &config.custom {
+            let mut dominant = None;
+            let (kind, source, reading) = match &sensor.source {
+                SensorSource::File { path, .. } => (
+                    SensorKind::File,
+                    path.display().to_string(),
+                    self.read_custom(sensor).await,
+                ),
+                SensorSource::Command { command, args, .. } => {
+                    let mut line = vec![command.as_str()];
+                    line.extend(args.iter().map(String::as_str));
+                    (
+                        SensorKind::Command,
+                        line.join(" "),
+                        self.read_custom(sensor).await,
+                    )
+                }
+                SensorSource::Virtual { function, inputs } => {
+                    let reading = self.read_virtual(&sensor.id, *function, inputs).await;
+                    (
+                        SensorKind::Virtual,
+                        formula(*function, inputs),
+                        reading.map(|(temp, input)| {
+                            dominant = Some(input);
+                            temp
+                        }),
+                    )
+                }
+            };
+            let (temperature, error) = split_reading(reading);
+            sensors.push(SensorInfo {
+                id: sensor.id.clone(),
+                kind,
+                chip: None,
+                label: sensor.label.clone(),
+                source,
+                temperature,
+                error,
+                dominant,
+            });
+        }
+
+        for sensor in hwmon::discover(&config.hwmon_root).await {
+            let (temperature, error) = split_reading(read_millidegrees(&sensor.path).await);
+            sensors.push(SensorInfo {
+                id: sensor.id,
+                kind: SensorKind::Hwmon,
+                chip: Some(sensor.chip),
+                label: sensor.label,
+                source: sensor.path.display().to_string(),
+                temperature,
+                error,
+                dominant: None,
+            });
+        }
+
+        sensors
+    }
+
+    /// Read a temperature in degrees Celsius from a sensor ID or file path.
+    pub async fn read(&self, sensor: &str) -> Result<f32> {
+        let config = self.config();
+        if let Some(custom) = config.custom.iter().find(|s| s.id == sensor)
+            && let SensorSource::Virtual { function, inputs } = &custom.source
+        {
+            let (temp, _) = self.read_virtual(&custom.id, *function, inputs).await?;
+            return Ok(temp);
+        }
+        self.read_input(sensor).await
+    }
+
+    /// Read a physical sensor (anything but a virtual sensor).
+    async fn read_input(&self, sensor: &str) -> Result<f32> {
+        let path = Path::new(sensor);
+        if path.is_absolute() {
+            return read_millidegrees(path).await;
+        }
+
+        let config = self.config();
+        if let Some(custom) = config.custom.iter().find(|s| s.id == sensor) {
+            return self.read_custom(custom).await;
+        }
+
+        let found = hwmon::discover(&config.hwmon_root)
+            .await
+            .into_iter()
+            .find(|s| s.id == sensor)
+            .ok_or_else(|| OpenFanError::SensorNotFound(sensor.to_string()))?;
+        read_millidegrees(&found.path).await
+    }
+
+    /// Read the inputs of a virtual sensor and combine them.
+    ///
+    /// Returns the temperature and the ID of the dominant input. Fails if any
+    /// input fails, since combining the remaining inputs could under-report.
+    async fn read_virtual(
+        &self,
+        id: &str,
+        function: AggregateFunction,
+        inputs: &[AggregateInput],
+    ) -> Result<(f32, String)> {
+        let mut values = Vec::with_capacity(inputs.len());
+        for input in inputs {
+            let temp = self.read_input(&input.sensor).await.map_err(|e| {
+                OpenFanError::Sensor(format!(
+                    "Sensor '{}': input '{}' failed: {}",
+                    id, input.sensor, e
+                ))
+            })?;
+            values.push((temp as f64 + input.offset, input.weight));
+        }
+
+        let (temp, index) = function.combine(&values).ok_or_else(|| {
+            OpenFanError::Sensor(format!("Sensor '{}': no inputs to combine", id))
+        })?;
+        Ok((temp as f32, inputs[index].sensor.clone()))
+    }
+
+    /// Read a custom file or command sensor, honoring the command cache TTL.
+    async fn read_custom(&self, sensor: &CustomSensorConfig) -> Result<f32> {
+        match &sensor.source {
+            // Rejected by `SensorsConfig::validate`
+            SensorSource::Virtual { .. } => Err(OpenFanError::Sensor(format!(
+                "Sensor '{}': virtual sensors cannot be used as inputs",
+                sensor.id
+            ))),
+            SensorSource::File { path, scale } => {
+                let value = custom::read_file(&sensor.id, path).await?;
+                Ok((value * scale) as f32)
+            }
+            SensorSource::Command {
+                command,
+                args,
+                timeout_secs,
+                cache_ttl_secs,
+                scale,
+            } => {
+                let ttl = Duration::from_secs(*cache_ttl_secs);
+                if let Some((at, temp)) = self.cache.lock().await.get(&sensor.id)
+                    && at.elapsed() < ttl
+                {
+                    return Ok(*temp);
+                }
+
+                let value = custom::run_command(
+                    &sensor.id,
+                    command,
+                    args,
+                    Duration::from_secs(*timeout_secs),
+                )
+                .await?;
+                let temp = (value * scale) as f32;
+
+                if !ttl.is_zero() {
+                    self.cache
+                        .lock()
+                        .await
+                        .insert(sensor.id.clone(), (Instant::now(), temp));
+                }
+                Ok(temp)
+            }
+        }
+    }
+}
+
+/// Describe a virtual sensor, e.g. `max(k10temp/temp1, amdgpu/temp1 -10)`.
+fn formula(function: AggregateFunction, inputs: &[AggregateInput]) -> String {
+    let terms: Vec<String> = inputs
+        .iter()
+        .map(|input| {
+            let mut term = input.sensor.clone();
+            if input.offset != 0.0 {
+                term.push_str(&format!(" {:+}", input.offset));
+            }
+            if function == AggregateFunction::Weighted && input.weight != 1.0 {
+                term.push_str(&format!(" x{}", input.weight));
+            }
+            term
+        })
+        .collect();
+    format!("{}({})", function.name(), terms.join(", "))
+}
+
+/// Split a reading into the (temperature, error) pair reported by the API.
+fn split_reading(reading: Result<f32>) -> (Option<f32>, Option<String>) {
+    match reading {
+        Ok(temp) => (Some(temp), None),
+        Err(e) => (None, Some(e.to_string())),
+    }
+}
+
+/// Read a millidegree value from a file and convert it to degrees Celsius.
+async fn read_millidegrees(path: &Path) -> Result<f32> {
+    let content = tokio::fs::read_to_string(path).await.map_err(|e| {
+        OpenFanError::Sensor(format!("Failed to read sensor '{}': {}", path.display(), e))
+    })?;
+
+    let value: i64 = content.trim().parse().map_err(|_| {
+        OpenFanError::Parse(format!(
+            "Invalid temperature value '{}' in '{}'",
+            content.trim(),
+            path.display()
+        ))
+    })?;
+
+    Ok(value as f32 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_manager(temp_dir: &TempDir) -> SensorManager {
+        SensorManager::new(&create_config(temp_dir))
+    }
+
+    fn create_config(temp_dir: &TempDir) -> SensorsConfig {
+        let root = temp_dir.path().join("hwmon");
+        let chip = root.join("hwmon0");
+        std::fs::create_dir_all(&chip).unwrap();
+        std::fs::write(chip.join("name"), "k10temp\n").unwrap();
+        std::fs::write(chip.join("temp1_input"), "45500\n").unwrap();
+        std::fs::write(chip.join("temp1_label"), "Tctl\n").unwrap();
+        std::fs::write(chip.join("temp2_input"), "garbage\n").unwrap();
+
+        let ambient = temp_dir.path().join("ambient");
+        std::fs::write(&ambient, "23500\n").unwrap();
+
+        SensorsConfig {
+            hwmon_root: root,
+            custom: vec![
+                CustomSensorConfig {
+                    id: "ambient".to_string(),
+                    label: Some("Room".to_string()),
+                    source: SensorSource::File {
+                        path: ambient,
+                        scale: 0.001,
+                    },
+                },
+                CustomSensorConfig {
+                    id: "broken".to_string(),
+                    label: None,
+                    source: SensorSource::File {
+                        path: temp_dir.path().join("missing"),
+                        scale: 1.0,
+                    },
+                },
+            ],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_reads_sensors() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+
+        let sensors = manager.list().await;
+        let ids: Vec<&str> = sensors.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            vec!["ambient", "broken", "k10temp/temp1", "k10temp/temp2"]
+        );
+
+        assert_eq!(sensors[0].kind, SensorKind::File);
+        assert_eq!(sensors[0].label.as_deref(), Some("Room"));
+        assert_eq!(sensors[0].temperature, Some(23.5));
+        assert!(sensors[0].chip.is_none());
+
+        assert!(sensors[1].temperature.is_none());
+        assert!(sensors[1].error.as_ref().unwrap().contains("broken"));
+
+        assert_eq!(sensors[2].kind, SensorKind::Hwmon);
+        assert_eq!(sensors[2].chip.as_deref(), Some("k10temp"));
+        assert_eq!(sensors[2].label.as_deref(), Some("Tctl"));
+        assert_eq!(sensors[2].temperature, Some(45.5));
+        assert!(sensors[2].error.is_none());
+
+        assert!(sensors[3].temperature.is_none());
+        assert!(sensors[3].error.as_ref().unwrap().contains("garbage"));
+    }
+
+    #[tokio::test]
+    async fn test_read_by_id() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+
+        assert_eq!(manager.read("k10temp/temp1").await.unwrap(), 45.5);
+
+        let err = manager.read("k10temp/temp9").await.unwrap_err();
+        assert!(matches!(err, OpenFanError::SensorNotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_read_custom_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+
+        assert_eq!(manager.read("ambient").await.unwrap(), 23.5);
+        let err = manager.read("broken").await.unwrap_err();
+        assert!(matches!(err, OpenFanError::Sensor(_)));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_read_custom_command_cache() {
+        let temp_dir = TempDir::new().unwrap();
+        let counter = temp_dir.path().join("counter");
+        std::fs::write(&counter, "").unwrap();
+
+        // Each run appends a line to the counter file and prints 40
+        let script = format!("echo x >> '{}'; echo 40", counter.display());
+        let command = |id: &str, cache_ttl_secs: u64| CustomSensorConfig {
+            id: id.to_string(),
+            label: None,
+            source: SensorSource::Command {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script.clone()],
+                timeout_secs: 5,
+                cache_ttl_secs,
+                scale: 1.5,
+            },
+        };
+        let manager = SensorManager::new(&SensorsConfig {
+            hwmon_root: temp_dir.path().join("hwmon"),
+            custom: vec![command("cached", 60), command("uncached", 0)],
+        });
+        let runs = || std::fs::read_to_string(&counter).unwrap().lines().count();
+
+        assert_eq!(manager.read("cached").await.unwrap(), 60.0);
+        assert_eq!(manager.read("cached").await.unwrap(), 60.0);
+        assert_eq!(runs(), 1);
+
+        manager.read("uncached").await.unwrap();
+        manager.read("uncached").await.unwrap();
+        assert_eq!(runs(), 3);
+
+        let sensors = manager.list().await;
+        assert_eq!(sensors[0].kind, SensorKind::Command);
+        assert!(sensors[0].source.starts_with("sh -c"));
+    }
+
+    #[tokio::test]
+    async fn test_read_virtual() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = create_config(&temp_dir);
+        let virtual_sensor = |id: &str, function, inputs| CustomSensorConfig {
+            id: id.to_string(),
+            label: None,
+            source: SensorSource::Virtual { function, inputs },
+        };
+        config.custom.extend([
+            // 45.5 vs 23.5 + 30
+            virtual_sensor(
+                "case",
+                AggregateFunction::Max,
+                vec![
+                    AggregateInput::new("k10temp/temp1"),
+                    AggregateInput::new("ambient").with_offset(30.0),
+                ],
+            ),
+            virtual_sensor(
+                "blend",
+                AggregateFunction::Weighted,
+                vec![
+                    AggregateInput::new("k10temp/temp1").with_weight(3.0),
+                    AggregateInput::new("ambient"),
+                ],
+            ),
+            virtual_sensor(
+                "partial",
+                AggregateFunction::Max,
+                vec![
+                    AggregateInput::new("k10temp/temp1"),
+                    AggregateInput::new("broken"),
+                ],
+            ),
+        ]);
+        let manager = SensorManager::new(&config);
+
+        assert_eq!(manager.read("case").await.unwrap(), 53.5);
+        assert_eq!(manager.read("blend").await.unwrap(), 40.0);
+        let err = manager.read("partial").await.unwrap_err();
+        assert!(matches!(err, OpenFanError::Sensor(_)));
+        assert!(err.to_string().contains("input 'broken'"));
+
+        let sensors = manager.list().await;
+        let case = sensors.iter().find(|s| s.id == "case").unwrap();
+        assert_eq!(case.kind, SensorKind::Virtual);
+        assert_eq!(case.source, "max(k10temp/temp1, ambient +30)");
+        assert_eq!(case.temperature, Some(53.5));
+        assert_eq!(case.dominant.as_deref(), Some("ambient"));
+
+        let blend = sensors.iter().find(|s| s.id == "blend").unwrap();
+        assert_eq!(blend.source, "weighted(k10temp/temp1 x3, ambient)");
+        assert_eq!(blend.dominant.as_deref(), Some("k10temp/temp1"));
+
+        let partial = sensors.iter().find(|s| s.id == "partial").unwrap();
+        assert!(partial.temperature.is_none());
+        assert!(partial.dominant.is_none());
+        assert!(partial.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_read_by_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+
+        let path = temp_dir.path().join("temp");
+        std::fs::write(&path, "-5000\n").unwrap();
+        assert_eq!(manager.read(path.to_str().unwrap()).await.unwrap(), -5.0);
+    }
+
+    #[tokio::test]
+    async fn test_read_path_errors() {
+        let temp_dir = TempDir::new().unwrap();
+        let manager = create_manager(&temp_dir);
+
+        let missing = temp_dir.path().join("missing");
+        let err = manager.read(missing.to_str().unwrap()).await.unwrap_err();
+        assert!(matches!(err, OpenFanError::Sensor(_)));
+
+        let invalid = temp_dir.path().join("invalid");
+        std::fs::write(&invalid, "hot").unwrap();
+        let err = manager.read(invalid.to_str().unwrap()).await.unwrap_err();
+        assert!(matches!(err, OpenFanError::Parse(_)));
+    }
+}
@@ -4,88 +4,252 @@
 //! fans continue running at a safe speed when the daemon terminates.
 
 use crate::config::RuntimeConfig;
-use crate::controllers::ConnectionManager;
-use openfan_core::ControlMode;
+use crate::controllers::{ControllerEntry, ControllerRegistry};
+use crate::limits::FanLimiter;
+use openfan_core::{ControlMode, FanProfile, OpenFanError, ProfileName};
+use openfan_hardware::is_disconnect_error;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio::time::{Instant, timeout_at};
 use tracing::{info, warn};
 
+/// Outcome of applying the safe boot profile to one controller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShutdownOutcome {
+    /// The profile was set on every fan of the controller
+    Applied,
+    /// The controller has no hardware connection
+    Skipped,
+    /// The profile could not be applied, with the reason
+    Failed(String),
+}
+
+/// Safe boot profile report for one controller
+#[derive(Debug, Clone)]
+pub struct ControllerShutdown {
+    /// Controller ID
+    pub controller: String,
+    /// Profile applied to the controller
+    pub profile: ProfileName,
+    /// What happened
+    pub outcome: ShutdownOutcome,
+}
+
 /// Apply safe boot profile before shutdown
 ///
-/// Applies a configured fan profile (default: "100% PWM") before the daemon
-/// terminates, ensuring fans run at a safe speed during system shutdown/reboot.
+/// Applies a configured fan profile (default: "100% PWM") to every registered
+/// controller before the daemon terminates, ensuring fans run at a safe speed
+/// during system shutdown/reboot.
 ///
 /// This prevents a thermal safety issue where fans would stop when the daemon
 /// terminates but before the system completes shutdown. The profile is applied
 /// only if enabled in config and hardware is available.
 ///
+/// Each controller gets its `shutdown_profile` from `[[controllers]]`, or the
+/// `[shutdown]` profile, looked up in its own profiles first and then in the
+/// global ones. Controllers are driven concurrently; those not done within
+/// `[shutdown] timeout_secs` are reported as failed.
+///
 /// # Arguments
 ///
 /// * `runtime_config` - Runtime configuration containing shutdown settings and profiles
-/// * `connection_manager` - Hardware connection manager (None in mock mode)
+/// * `registry` - Registered controllers
 /// * `is_mock` - Whether running in mock mode (skips profile application)
+///
+/// Returns one report per controller, by controller ID, or none when the
+/// profile is disabled or in mock mode.
 pub async fn apply_safe_boot_profile(
     runtime_config: &Arc<RuntimeConfig>,
-    connection_manager: Option<&Arc<ConnectionManager>>,
+    registry: &ControllerRegistry,
     is_mock: bool,
-) {
+) -> Vec<ControllerShutdown> {
     let shutdown_config = &runtime_config.static_config().shutdown;
 
     if !shutdown_config.enabled {
         info!("Safe boot profile disabled in config");
-        return;
+        return Vec::new();
     }
 
     if is_mock {
         info!("Mock mode - skipping safe boot profile");
-        return;
+        return Vec::new();
     }
 
-    let Some(cm) = connection_manager else {
-        warn!("No hardware connection - cannot apply safe boot profile");
-        return;
-    };
+    let mut entries = registry.list().await;
+    entries.sort_by(|a, b| a.id().cmp(b.id()));
+    let mut reports: Vec<ControllerShutdown> = entries
+        .iter()
+        .map(|entry| ControllerShutdown {
+            controller: entry.id().to_string(),
+            profile: shutdown_profile(runtime_config, entry.id()),
+            outcome: ShutdownOutcome::Failed(format!(
+                "Timed out after {}s",
+                shutdown_config.timeout_secs
+            )),
+        })
+        .collect();
+
+    let mut tasks = JoinSet::new();
+    for (idx, entry) in entries.into_iter().enumerate() {
+        let config = runtime_config.clone();
+        let profile = reports[idx].profile.clone();
+        tasks.spawn(async move { (idx, apply_to_controller(&config, &entry, &profile).await) });
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(shutdown_config.timeout_secs);
+    loop {
+        match timeout_at(deadline, tasks.join_next()).await {
+            Ok(Some(Ok((idx, outcome)))) => reports[idx].outcome = outcome,
+            Ok(Some(Err(e))) => warn!("Safe boot profile task failed: {}", e),
+            Ok(None) => break,
+            Err(_) => {
+                tasks.abort_all();
+                break;
+            }
+        }
+    }
 
-    let profile_name = &shutdown_config.profile;
-    let profile = {
-        let profiles = runtime_config.profiles().await;
-        profiles.get(profile_name.as_str()).cloned()
+    let mut applied = 0;
+    for report in &reports {
+        match &report.outcome {
+            ShutdownOutcome::Applied => {
+                applied += 1;
+                info!(
+                    "Safe boot profile '{}' applied to controller '{}'",
+                    report.profile, report.controller
+                );
+            }
+            ShutdownOutcome::Skipped => warn!(
+                "No hardware connection - cannot apply safe boot profile to controller '{}'",
+                report.controller
+            ),
+            ShutdownOutcome::Failed(reason) => warn!(
+                "Failed to apply safe boot profile '{}' to controller '{}': {}",
+                report.profile, report.controller, reason
+            ),
+        }
+    }
+    info!(
+        "Safe boot profile applied to {}/{} controller(s)",
+        applied,
+        reports.len()
+    );
+
+    reports
+}
+
+/// Shutdown profile of a controller: its override, or the global one
+fn shutdown_profile(runtime_config: &RuntimeConfig, controller_id: &str) -> ProfileName {
+    let static_config = runtime_config.static_config();
+    static_config
+        .controllers
+        .iter()
+        .find(|ctrl| ctrl.id == controller_id)
+        .and_then(|ctrl| ctrl.shutdown_profile.clone())
+        .unwrap_or_else(|| static_config.shutdown.profile.clone())
+}
+
+/// Find a profile in the controller's own profiles, then in the global ones
+pub(crate) async fn find_profile(
+    runtime_config: &RuntimeConfig,
+    controller_id: &str,
+    name: &ProfileName,
+) -> Option<FanProfile> {
+    match runtime_config.controller_data(controller_id).await {
+        Ok(data) => {
+            if let Some(profile) = data.profiles().await.get(name.as_str()) {
+                return Some(profile.clone());
+            }
+        }
+        Err(e) => warn!(
+            "Failed to load profiles of controller '{}': {}",
+            controller_id, e
+        ),
+    }
+    runtime_config.profiles().await.get(name.as_str()).cloned()
+}
+
+async fn apply_to_controller(
+    runtime_config: &RuntimeConfig,
+    entry: &ControllerEntry,
+    profile_name: &ProfileName,
+) -> ShutdownOutcome {
+    let Some(cm) = entry.connection_manager() else {
+        return ShutdownOutcome::Skipped;
     };
 
-    let Some(profile) = profile else {
-        warn!("Safe boot profile '{}' not found", profile_name);
-        return;
+    let Some(profile) = find_profile(runtime_config, entry.id(), profile_name).await else {
+        return ShutdownOutcome::Failed(format!("Profile '{}' not found", profile_name));
     };
 
-    info!("Applying safe boot profile '{}'...", profile_name);
+    info!(
+        "Applying safe boot profile '{}' to controller '{}'...",
+        profile_name,
+        entry.id()
+    );
 
-    let values = profile.values.clone();
+    // Always clamped into the fan limits, as a rejected fan could be left stopped
+    let limiter = match FanLimiter::load(runtime_config, entry).await {
+        Ok(limiter) => limiter,
+        Err(e) => return ShutdownOutcome::Failed(e.to_string()),
+    };
     let mode = profile.control_mode;
+    let values: Vec<_> = profile
+        .values
+        .iter()
+        .enumerate()
+        .map(|(fan_id, &value)| limiter.clamp_value(fan_id as u8, mode, value))
+        .collect();
     let result = cm
         .with_controller(async |controller| {
-            for (fan_id, &value) in values.iter().enumerate() {
+            let mut failed = Vec::new();
+            let fan_count = controller.fan_count();
+            for (fan_id, &value) in values.iter().enumerate().take(fan_count) {
                 let fan_id = fan_id as u8;
                 let res = match mode {
                     ControlMode::Pwm => controller.set_fan_pwm(fan_id, value).await,
                     ControlMode::Rpm => controller.set_fan_rpm(fan_id, value).await,
                 };
-                if let Err(e) = res {
-                    warn!("Failed to set fan {} during shutdown: {}", fan_id, e);
+                match res {
+                    Ok(_) => {}
+                    // No point trying the other fans of a board that is gone
+                    Err(e) if is_disconnect_error(&e) => return Err(e),
+                    Err(e) => {
+                        warn!("Failed to set fan {} during shutdown: {}", fan_id, e);
+                        failed.push(fan_id);
+                    }
                 }
             }
-            Ok(())
+            Ok(failed)
         })
         .await;
 
     match result {
-        Ok(_) => info!("Safe boot profile applied successfully"),
-        Err(e) => warn!("Failed to apply safe boot profile: {}", e),
+        Ok(failed) if failed.is_empty() => ShutdownOutcome::Applied,
+        Ok(failed) => ShutdownOutcome::Failed(format!(
+            "Failed to set fan(s) {}",
+            failed
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+        Err(e) => ShutdownOutcome::Failed(match e {
+            OpenFanError::DeviceDisconnected(_) => "Device disconnected".to_string(),
+            e => e.to_string(),
+        }),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use openfan_core::config::{ProfileName, ShutdownConfig, StaticConfig};
+    use crate::controllers::connection;
+    use crate::controllers::{ConnectionManager, ConnectionState, Device, SimulatedDevice};
+    use openfan_core::config::{ControllerConfig, ShutdownConfig, StaticConfig};
+    use openfan_core::{BoardType, FanLimitsConfig, LimitEnforcement, ReconnectConfig};
+    use openfan_hardware::SimulatorConfig;
     use std::path::Path;
     use tempfile::TempDir;
     use tokio::fs;
@@ -96,18 +260,37 @@ mod tests {
         shutdown_enabled: bool,
         profile_name: &str,
     ) -> Arc<RuntimeConfig> {
-        let config_path = temp_dir.join("config.toml");
-        let data_dir = temp_dir.join("data");
-
-        // Create a custom static config with specific shutdown settings
-        let static_config = StaticConfig {
-            data_dir: data_dir.clone(),
-            shutdown: ShutdownConfig {
+        create_config_with_controllers(
+            temp_dir,
+            ShutdownConfig {
                 enabled: shutdown_enabled,
                 profile: ProfileName::new(profile_name),
+                ..ShutdownConfig::default()
             },
+            Vec::new(),
+        )
+        .await
+    }
+
+    async fn create_config_with_controllers(
+        temp_dir: &Path,
+        shutdown: ShutdownConfig,
+        controllers: Vec<ControllerConfig>,
+    ) -> Arc<RuntimeConfig> {
+        // Create a custom static config with specific shutdown settings
+        let static_config = StaticConfig {
+            shutdown,
+            controllers,
             ..StaticConfig::default()
         };
+        write_config(temp_dir, static_config).await
+    }
+
+    /// Write a static config using a data directory inside `temp_dir`
+    async fn write_config(temp_dir: &Path, mut static_config: StaticConfig) -> Arc<RuntimeConfig> {
+        let config_path = temp_dir.join("config.toml");
+        let data_dir = temp_dir.join("data");
+        static_config.data_dir = data_dir.clone();
 
         fs::create_dir_all(&data_dir).await.unwrap();
         fs::write(&config_path, static_config.to_toml().unwrap())
@@ -117,56 +300,227 @@ mod tests {
         Arc::new(RuntimeConfig::load(&config_path).await.unwrap())
     }
 
+    /// Register a controller backed by a simulated board
+    async fn register_simulated(
+        registry: &ControllerRegistry,
+        id: &str,
+        fan_count: usize,
+        reconnect: ReconnectConfig,
+    ) -> (Arc<ConnectionManager>, SimulatedDevice) {
+        let board = BoardType::Custom { fan_count }.to_board_info();
+        let simulated = SimulatedDevice::new(id, SimulatorConfig::new(fan_count));
+        let device = Device::Simulated(simulated.clone());
+        let controller = connection::open_device(&device, &board, 1000, false)
+            .await
+            .unwrap();
+        let cm = Arc::new(ConnectionManager::new(
+            controller,
+            reconnect,
+            device,
+            board.clone(),
+            1000,
+            false,
+        ));
+        registry
+            .register(
+                ControllerEntry::builder(id, board)
+                    .maybe_connection_manager(Some(cm.clone()))
+                    .build(),
+            )
+            .await
+            .unwrap();
+        (cm, simulated)
+    }
+
+    async fn cached_pwm(cm: &ConnectionManager, fan_id: u8) -> Option<u32> {
+        cm.with_controller(async |ctrl| Ok(ctrl.get_single_fan_pwm(fan_id)))
+            .await
+            .unwrap()
+    }
+
     #[tokio::test]
     async fn test_shutdown_disabled_returns_early() {
         let temp_dir = TempDir::new().unwrap();
         let config = create_test_config(temp_dir.path(), false, "100% PWM").await;
+        let registry = ControllerRegistry::new();
+        register_simulated(&registry, "main", 4, ReconnectConfig::default()).await;
 
         // Should return early without attempting to apply profile
-        // (no connection manager needed since we return before checking it)
-        apply_safe_boot_profile(&config, None, false).await;
-
-        // If we reach here without panic, the early return worked
+        assert!(
+            apply_safe_boot_profile(&config, &registry, false)
+                .await
+                .is_empty()
+        );
     }
 
     #[tokio::test]
     async fn test_mock_mode_returns_early() {
         let temp_dir = TempDir::new().unwrap();
         let config = create_test_config(temp_dir.path(), true, "100% PWM").await;
+        let registry = ControllerRegistry::new();
+        register_simulated(&registry, "main", 4, ReconnectConfig::default()).await;
 
         // Should return early in mock mode
-        apply_safe_boot_profile(&config, None, true).await;
-
-        // If we reach here without panic, the early return worked
+        assert!(
+            apply_safe_boot_profile(&config, &registry, true)
+                .await
+                .is_empty()
+        );
     }
 
     #[tokio::test]
-    async fn test_no_connection_manager_returns_early() {
+    async fn test_no_connection_manager_is_skipped() {
         let temp_dir = TempDir::new().unwrap();
         let config = create_test_config(temp_dir.path(), true, "100% PWM").await;
+        let registry = ControllerRegistry::new();
+        registry
+            .register(
+                ControllerEntry::builder("main", BoardType::OpenFanStandard.to_board_info())
+                    .build(),
+            )
+            .await
+            .unwrap();
 
-        // Should return early when no connection manager
-        apply_safe_boot_profile(&config, None, false).await;
-
-        // If we reach here without panic, the early return worked
+        let reports = apply_safe_boot_profile(&config, &registry, false).await;
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].outcome, ShutdownOutcome::Skipped);
     }
 
     #[tokio::test]
-    async fn test_missing_profile_returns_early() {
+    async fn test_missing_profile_fails() {
         let temp_dir = TempDir::new().unwrap();
         // Use a non-existent profile name
         let config = create_test_config(temp_dir.path(), true, "NonExistent Profile").await;
+        let registry = ControllerRegistry::new();
+        register_simulated(&registry, "main", 4, ReconnectConfig::default()).await;
 
-        // The function needs a ConnectionManager to get past the None check,
-        // but we can't easily create one without hardware.
-        // This test verifies the config is set up correctly for the missing profile case.
-        let shutdown_config = &config.static_config().shutdown;
-        assert!(shutdown_config.enabled);
-        assert_eq!(shutdown_config.profile.as_str(), "NonExistent Profile");
+        let reports = apply_safe_boot_profile(&config, &registry, false).await;
+        assert_eq!(reports.len(), 1);
+        assert!(
+            matches!(&reports[0].outcome, ShutdownOutcome::Failed(reason) if reason.contains("not found")),
+            "{:?}",
+            reports[0].outcome
+        );
+    }
 
-        // Verify the profile doesn't exist
-        let profiles = config.profiles().await;
-        assert!(profiles.get("NonExistent Profile").is_none());
+    #[tokio::test]
+    async fn test_applied_to_every_controller() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut gpu = ControllerConfig::new("gpu", "/dev/ttyACM1", BoardType::OpenFanStandard);
+        gpu.shutdown_profile = Some(ProfileName::new("50% PWM"));
+        let config =
+            create_config_with_controllers(temp_dir.path(), ShutdownConfig::default(), vec![gpu])
+                .await;
+        let registry = ControllerRegistry::new();
+        let (main, _) = register_simulated(&registry, "main", 10, ReconnectConfig::default()).await;
+        let (gpu, _) = register_simulated(&registry, "gpu", 4, ReconnectConfig::default()).await;
+
+        let reports = apply_safe_boot_profile(&config, &registry, false).await;
+        let outcomes: Vec<_> = reports
+            .iter()
+            .map(|r| (r.controller.as_str(), r.profile.as_str(), &r.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                ("gpu", "50% PWM", &ShutdownOutcome::Applied),
+                ("main", "100% PWM", &ShutdownOutcome::Applied),
+            ]
+        );
+
+        assert_eq!(cached_pwm(&main, 9).await, Some(100));
+        assert_eq!(cached_pwm(&gpu, 3).await, Some(50));
+        assert_eq!(cached_pwm(&gpu, 4).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_fan_limits_applied() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = create_test_config(temp_dir.path(), true, "100% PWM").await;
+        let registry = ControllerRegistry::new();
+        let (main, _) = register_simulated(&registry, "main", 4, ReconnectConfig::default()).await;
+        let data = config.controller_data("main").await.unwrap();
+        data.fan_limits_mut().await.set(
+            1,
+            openfan_core::FanLimits {
+                max_pwm: Some(70),
+                ..Default::default()
+            },
+        );
+
+        let reports = apply_safe_boot_profile(&config, &registry, false).await;
+        assert_eq!(reports[0].outcome, ShutdownOutcome::Applied);
+        assert_eq!(cached_pwm(&main, 0).await, Some(100));
+        assert_eq!(cached_pwm(&main, 1).await, Some(70));
+    }
+
+    #[tokio::test]
+    async fn test_fan_limits_clamped_in_reject_mode() {
+        let temp_dir = TempDir::new().unwrap();
+        let static_config = StaticConfig {
+            shutdown: ShutdownConfig {
+                profile: ProfileName::new("50% PWM"),
+                ..ShutdownConfig::default()
+            },
+            fan_limits: FanLimitsConfig {
+                enforcement: LimitEnforcement::Reject,
+            },
+            ..StaticConfig::default()
+        };
+        let config = write_config(temp_dir.path(), static_config).await;
+        let registry = ControllerRegistry::new();
+        let (main, _) = register_simulated(&registry, "main", 4, ReconnectConfig::default()).await;
+        let data = config.controller_data("main").await.unwrap();
+        data.fan_limits_mut().await.set(
+            1,
+            openfan_core::FanLimits {
+                min_pwm: Some(60),
+                ..Default::default()
+            },
+        );
+
+        // Rejected values are still clamped on the way out
+        let reports = apply_safe_boot_profile(&config, &registry, false).await;
+        assert_eq!(reports[0].outcome, ShutdownOutcome::Applied);
+        assert_eq!(cached_pwm(&main, 0).await, Some(50));
+        assert_eq!(cached_pwm(&main, 1).await, Some(60));
+    }
+
+    #[tokio::test]
+    async fn test_unresponsive_controller_times_out() {
+        let temp_dir = TempDir::new().unwrap();
+        let shutdown = ShutdownConfig {
+            timeout_secs: 1,
+            ..ShutdownConfig::default()
+        };
+        let config = create_config_with_controllers(temp_dir.path(), shutdown, Vec::new()).await;
+        let registry = ControllerRegistry::new();
+        let (main, _) = register_simulated(&registry, "main", 4, ReconnectConfig::default()).await;
+        // Retries forever once the board is gone
+        let unlimited = ReconnectConfig {
+            max_attempts: 0,
+            enable_heartbeat: false,
+            ..Default::default()
+        };
+        let (gone, simulated) = register_simulated(&registry, "gone", 4, unlimited).await;
+        simulated.disconnect();
+        let _ = gone
+            .with_controller(async |ctrl| ctrl.get_fw_info().await)
+            .await;
+        assert_eq!(gone.connection_state().await, ConnectionState::Disconnected);
+
+        let started = std::time::Instant::now();
+        let reports = apply_safe_boot_profile(&config, &registry, false).await;
+        assert!(started.elapsed() < Duration::from_secs(3));
+
+        assert_eq!(reports[0].controller, "gone");
+        assert!(
+            matches!(&reports[0].outcome, ShutdownOutcome::Failed(reason) if reason.contains("Timed out")),
+            "{:?}",
+            reports[0].outcome
+        );
+        assert_eq!(reports[1].outcome, ShutdownOutcome::Applied);
+        assert_eq!(cached_pwm(&main, 0).await, Some(100));
     }
 
     #[tokio::test]
@@ -0,0 +1,399 @@
+//! Startup handling for the initial fan state
+//!
+//! Sets the fans when the daemon starts, instead of leaving them at whatever
+//! the firmware applied, by restoring the last saved fan state or applying a
+//! startup profile.
+
+use crate::config::RuntimeConfig;
+use crate::controllers::{ControllerEntry, ControllerRegistry};
+use crate::limits::FanLimiter;
+use crate::shutdown::find_profile;
+use openfan_core::{FanStateData, OpenFanError, ProfileName};
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+
+/// Outcome of setting the initial fan state of one controller
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupOutcome {
+    /// The fan state saved by the previous run was restored
+    Restored,
+    /// The named profile was set on every fan of the controller
+    Applied(ProfileName),
+    /// The controller has no hardware connection
+    Skipped,
+    /// The fans could not be set, with the reason
+    Failed(String),
+}
+
+/// Initial fan state report for one controller
+#[derive(Debug, Clone)]
+pub struct ControllerStartup {
+    /// Controller ID
+    pub controller: String,
+    /// What happened
+    pub outcome: StartupOutcome,
+}
+
+/// Set the initial fan state of every controller
+///
+/// Each controller gets its `startup_profile` from `[[controllers]]`, or the
+/// `[startup]` profile, looked up in its own profiles first and then in the
+/// global ones.
+///
+/// With `restore_state`, the fan state is saved to the controller's data
+/// directory on every change from now on, and the state saved by the previous
+/// run is restored instead of the profile when there is one.
+///
+/// Returns one report per controller, by controller ID, or none when startup
+/// handling is disabled.
+pub async fn apply_startup_state(
+    runtime_config: &Arc<RuntimeConfig>,
+    registry: &ControllerRegistry,
+) -> Vec<ControllerStartup> {
+    let startup_config = &runtime_config.static_config().startup;
+
+    if !startup_config.enabled {
+        info!("Startup fan state disabled in config");
+        return Vec::new();
+    }
+
+    let mut entries = registry.list().await;
+    entries.sort_by(|a, b| a.id().cmp(b.id()));
+    let mut reports: Vec<ControllerStartup> = entries
+        .iter()
+        .map(|entry| ControllerStartup {
+            controller: entry.id().to_string(),
+            outcome: StartupOutcome::Skipped,
+        })
+        .collect();
+
+    let mut tasks = JoinSet::new();
+    for (idx, entry) in entries.into_iter().enumerate() {
+        let config = runtime_config.clone();
+        tasks.spawn(async move { (idx, start_controller(&config, &entry).await) });
+    }
+    while let Some(result) = tasks.join_next().await {
+        match result {
+            Ok((idx, outcome)) => reports[idx].outcome = outcome,
+            Err(e) => warn!("Startup fan state task failed: {}", e),
+        }
+    }
+
+    let mut applied = 0;
+    for report in &reports {
+        match &report.outcome {
+            StartupOutcome::Restored => {
+                applied += 1;
+                info!(
+                    "Restored the last fan state of controller '{}'",
+                    report.controller
+                );
+            }
+            StartupOutcome::Applied(profile) => {
+                applied += 1;
+                info!(
+                    "Startup profile '{}' applied to controller '{}'",
+                    profile, report.controller
+                );
+            }
+            StartupOutcome::Skipped => warn!(
+                "No hardware connection - cannot set the startup fan state of controller '{}'",
+                report.controller
+            ),
+            StartupOutcome::Failed(reason) => warn!(
+                "Failed to set the startup fan state of controller '{}': {}",
+                report.controller, reason
+            ),
+        }
+    }
+    info!(
+        "Startup fan state set on {}/{} controller(s)",
+        applied,
+        reports.len()
+    );
+
+    reports
+}
+
+/// Startup profile of a controller: its override, or the global one
+fn startup_profile(runtime_config: &RuntimeConfig, controller_id: &str) -> ProfileName {
+    let static_config = runtime_config.static_config();
+    static_config
+        .controllers
+        .iter()
+        .find(|ctrl| ctrl.id == controller_id)
+        .and_then(|ctrl| ctrl.startup_profile.clone())
+        .unwrap_or_else(|| static_config.startup.profile.clone())
+}
+
+/// Set the initial fan state of one controller, such as one added by a reload
+pub(crate) async fn start_controller(
+    runtime_config: &RuntimeConfig,
+    entry: &ControllerEntry,
+) -> StartupOutcome {
+    let Some(cm) = entry.connection_manager() else {
+        return StartupOutcome::Skipped;
+    };
+    let limiter = match FanLimiter::load(runtime_config, entry).await {
+        Ok(limiter) => limiter,
+        Err(e) => return StartupOutcome::Failed(e.to_string()),
+    };
+
+    if runtime_config.static_config().startup.restore_state {
+        let data = match runtime_config.controller_data(entry.id()).await {
+            Ok(data) => data,
+            Err(e) => return StartupOutcome::Failed(e.to_string()),
+        };
+        cm.persist_state(data.clone());
+
+        match data.load_fan_state().await {
+            Ok(Some(state)) if !state.is_empty() => {
+                info!(
+                    "Restoring the last fan state of controller '{}'...",
+                    entry.id()
+                );
+                return match limiter.restore_state(cm, &state).await {
+                    Ok(failed) if failed.is_empty() => StartupOutcome::Restored,
+                    result => failure(result),
+                };
+            }
+            Ok(_) => {}
+            Err(e) => warn!(
+                "Ignoring the saved fan state of controller '{}': {}",
+                entry.id(),
+                e
+            ),
+        }
+    }
+
+    let profile_name = startup_profile(runtime_config, entry.id());
+    let Some(profile) = find_profile(runtime_config, entry.id(), &profile_name).await else {
+        return StartupOutcome::Failed(format!("Profile '{}' not found", profile_name));
+    };
+
+    info!(
+        "Applying startup profile '{}' to controller '{}'...",
+        profile_name,
+        entry.id()
+    );
+    let state = FanStateData::from_profile(&profile, entry.board_info().fan_count);
+    match limiter.restore_state(cm, &state).await {
+        Ok(failed) if failed.is_empty() => StartupOutcome::Applied(profile_name),
+        result => failure(result),
+    }
+}
+
+fn failure(result: openfan_core::Result<Vec<u8>>) -> StartupOutcome {
+    StartupOutcome::Failed(match result {
+        Ok(failed) => format!(
+            "Failed to set fan(s) {}",
+            failed
+                .iter()
+                .map(u8::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Err(OpenFanError::DeviceDisconnected(_)) => "Device disconnected".to_string(),
+        Err(e) => e.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::connection;
+    use crate::controllers::{ConnectionManager, Device, SimulatedDevice};
+    use openfan_core::config::{ControllerConfig, StartupConfig, StaticConfig};
+    use openfan_core::{BoardType, ReconnectConfig};
+    use openfan_hardware::SimulatorConfig;
+    use std::collections::HashMap;
+    use std::path::Path;
+    use tempfile::TempDir;
+    use tokio::fs;
+
+    async fn create_config(
+        temp_dir: &Path,
+        startup: StartupConfig,
+        controllers: Vec<ControllerConfig>,
+    ) -> Arc<RuntimeConfig> {
+        let config_path = temp_dir.join("config.toml");
+        let data_dir = temp_dir.join("data");
+
+        let static_config = StaticConfig {
+            data_dir: data_dir.clone(),
+            startup,
+            controllers,
+            ..StaticConfig::default()
+        };
+
+        fs::create_dir_all(&data_dir).await.unwrap();
+        fs::write(&config_path, static_config.to_toml().unwrap())
+            .await
+            .unwrap();
+
+        Arc::new(RuntimeConfig::load(&config_path).await.unwrap())
+    }
+
+    fn enabled(restore_state: bool) -> StartupConfig {
+        StartupConfig {
+            enabled: true,
+            restore_state,
+            ..StartupConfig::default()
+        }
+    }
+
+    /// Register a controller backed by a simulated board
+    async fn register_simulated(
+        registry: &ControllerRegistry,
+        id: &str,
+        fan_count: usize,
+    ) -> Arc<ConnectionManager> {
+        let board = BoardType::Custom { fan_count }.to_board_info();
+        let device = Device::Simulated(SimulatedDevice::new(id, SimulatorConfig::new(fan_count)));
+        let controller = connection::open_device(&device, &board, 1000, false)
+            .await
+            .unwrap();
+        let cm = Arc::new(ConnectionManager::new(
+            controller,
+            ReconnectConfig::default(),
+            device,
+            board.clone(),
+            1000,
+            false,
+        ));
+        registry
+            .register(
+                ControllerEntry::builder(id, board)
+                    .maybe_connection_manager(Some(cm.clone()))
+                    .build(),
+            )
+            .await
+            .unwrap();
+        cm
+    }
+
+    async fn fan_state(cm: &ConnectionManager) -> FanStateData {
+        cm.with_controller(async |ctrl| {
+            Ok(FanStateData::new(
+                ctrl.get_all_fan_pwm(),
+                ctrl.get_all_fan_rpm_targets(),
+            ))
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_startup_disabled_by_default() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = create_config(temp_dir.path(), StartupConfig::default(), Vec::new()).await;
+        let registry = ControllerRegistry::new();
+        let cm = register_simulated(&registry, "main", 4).await;
+
+        assert!(apply_startup_state(&config, &registry).await.is_empty());
+        assert!(fan_state(&cm).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_startup_profile_per_controller() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut gpu = ControllerConfig::new("gpu", "/dev/ttyACM1", BoardType::OpenFanStandard);
+        gpu.startup_profile = Some(ProfileName::new("1000 RPM"));
+        let config = create_config(temp_dir.path(), enabled(false), vec![gpu]).await;
+        let registry = ControllerRegistry::new();
+        let main = register_simulated(&registry, "main", 10).await;
+        let gpu = register_simulated(&registry, "gpu", 4).await;
+        registry
+            .register(
+                ControllerEntry::builder("offline", BoardType::OpenFanStandard.to_board_info())
+                    .build(),
+            )
+            .await
+            .unwrap();
+
+        let reports = apply_startup_state(&config, &registry).await;
+        let outcomes: Vec<_> = reports
+            .iter()
+            .map(|r| (r.controller.as_str(), &r.outcome))
+            .collect();
+        assert_eq!(
+            outcomes,
+            [
+                (
+                    "gpu",
+                    &StartupOutcome::Applied(ProfileName::new("1000 RPM"))
+                ),
+                (
+                    "main",
+                    &StartupOutcome::Applied(ProfileName::new("100% PWM"))
+                ),
+                ("offline", &StartupOutcome::Skipped),
+            ]
+        );
+
+        let main_state = fan_state(&main).await;
+        assert_eq!(main_state.pwm.len(), 10);
+        assert_eq!(main_state.pwm.get(&9), Some(&100));
+        let gpu_state = fan_state(&gpu).await;
+        assert_eq!(gpu_state.rpm.len(), 4);
+        assert_eq!(gpu_state.rpm.get(&3), Some(&1000));
+
+        // Without restore_state, nothing is saved
+        let data = config.controller_data("main").await.unwrap();
+        assert!(data.load_fan_state().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_missing_profile_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let startup = StartupConfig {
+            profile: ProfileName::new("NonExistent Profile"),
+            ..enabled(false)
+        };
+        let config = create_config(temp_dir.path(), startup, Vec::new()).await;
+        let registry = ControllerRegistry::new();
+        register_simulated(&registry, "main", 4).await;
+
+        let reports = apply_startup_state(&config, &registry).await;
+        assert!(
+            matches!(&reports[0].outcome, StartupOutcome::Failed(reason) if reason.contains("not found")),
+            "{:?}",
+            reports[0].outcome
+        );
+    }
+
+    #[tokio::test]
+    async fn test_restore_state_across_restarts() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = create_config(temp_dir.path(), enabled(true), Vec::new()).await;
+
+        // First run: nothing saved yet, so the profile is applied
+        let registry = ControllerRegistry::new();
+        let cm = register_simulated(&registry, "main", 4).await;
+        let reports = apply_startup_state(&config, &registry).await;
+        assert_eq!(
+            reports[0].outcome,
+            StartupOutcome::Applied(ProfileName::new("100% PWM"))
+        );
+
+        // Every change is saved
+        cm.with_controller(async |ctrl| {
+            ctrl.set_fan_pwm(0, 30).await?;
+            ctrl.set_fan_rpm(2, 900).await
+        })
+        .await
+        .unwrap();
+        let data = config.controller_data("main").await.unwrap();
+        let saved = data.load_fan_state().await.unwrap().unwrap();
+        assert_eq!(saved.pwm, HashMap::from([(0, 30), (1, 100), (3, 100)]));
+        assert_eq!(saved.rpm, HashMap::from([(2, 900)]));
+
+        // Second run: the saved state wins over the profile
+        let registry = ControllerRegistry::new();
+        let cm = register_simulated(&registry, "main", 4).await;
+        let reports = apply_startup_state(&config, &registry).await;
+        assert_eq!(reports[0].outcome, StartupOutcome::Restored);
+        assert_eq!(fan_state(&cm).await, saved);
+    }
+}
@@ -0,0 +1,527 @@
+//! Live telemetry
+//!
+//! Runs one background sampler per connected controller. While anyone is
+//! subscribed — event stream clients or the history recorder — each sampler
+//! reads the RPM of every fan each `[telemetry] interval_secs` and broadcasts
+//! the readings, however many subscribers there are. Connection state changes
+//! are broadcast whether or not the fans are read.
+//!
+//! The last readings of each controller are kept and serve fan status
+//! requests, new subscribers and the alarm monitor. Readings older than two
+//! intervals are stale: the fans are then read on demand, and the result is
+//! kept for the next request, so the serial link sees at most one read per
+//! interval however many readers there are.
+//!
+//! A host task reads the temperature sensors at the same pace while anyone is
+//! subscribed, and forwards configuration changes announced by
+//! [`RuntimeConfig::subscribe_changes`].
+
+use crate::alarms::unix_time;
+use crate::config::RuntimeConfig;
+use crate::controllers::{
+    ConnectionManager, ConnectionState, ControllerEntry, ControllerRegistry, DefaultFanController,
+};
+use crate::sensors::SensorManager;
+use openfan_core::api::{FanStatusResponse, TelemetryEvent};
+use openfan_core::{FanStateData, OpenFanError, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock, broadcast};
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, info};
+
+/// Number of unsent events kept for a slow subscriber before it skips ahead
+const EVENTS_CAPACITY: usize = 64;
+
+/// Fan readings of a controller at one point in time
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FanSample {
+    /// Measured RPM of each fan
+    pub rpm: HashMap<u8, u32>,
+    /// Last commanded PWM and target RPM of each fan
+    pub state: FanStateData,
+}
+
+impl FanSample {
+    /// The sample as a fan status response.
+    pub fn status(&self) -> FanStatusResponse {
+        FanStatusResponse {
+            rpms: self.rpm.clone(),
+            pwms: self.state.pwm.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Last fan readings of a controller
+#[derive(Debug, Clone)]
+struct Sample {
+    readings: FanSample,
+    /// Unix time of the readings, for events
+    timestamp: u64,
+    /// When the readings were taken, for staleness
+    taken: Instant,
+}
+
+impl Sample {
+    fn new(readings: FanSample) -> Self {
+        Self {
+            readings,
+            timestamp: unix_time(),
+            taken: Instant::now(),
+        }
+    }
+
+    fn event(&self, controller: &str) -> TelemetryEvent {
+        TelemetryEvent::FanStatus {
+            controller: controller.to_string(),
+            status: self.readings.status(),
+            timestamp: self.timestamp,
+        }
+    }
+}
+
+/// Manages the telemetry tasks and the cached fan readings of all controllers.
+pub(crate) struct Telemetry {
+    registry: Arc<ControllerRegistry>,
+    config: Arc<RuntimeConfig>,
+    sensors: Arc<SensorManager>,
+    events: broadcast::Sender<TelemetryEvent>,
+    samples: Arc<RwLock<HashMap<String, Sample>>>,
+    /// Sampler tasks by controller ID
+    tasks: Mutex<HashMap<String, JoinHandle<()>>>,
+    /// Sensor and configuration change task
+    host_task: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Telemetry {
+    /// Create telemetry with no running tasks.
+    pub fn new(
+        registry: Arc<ControllerRegistry>,
+        config: Arc<RuntimeConfig>,
+        sensors: Arc<SensorManager>,
+    ) -> Self {
+        Self {
+            registry,
+            config,
+            sensors,
+            events: broadcast::channel(EVENTS_CAPACITY).0,
+            samples: Arc::new(RwLock::new(HashMap::new())),
+            tasks: Mutex::new(HashMap::new()),
+            host_task: Mutex::new(None),
+        }
+    }
+
+    /// Time between samples
+    fn interval(&self) -> Duration {
+        Duration::from_secs(self.config.static_config().telemetry.interval_secs.max(1))
+    }
+
+    /// Fan status of a controller, from its last readings unless stale.
+    ///
+    /// Fails with [`OpenFanError::DeviceNotFound`] for a controller without a
+    /// hardware connection.
+    pub async fn fan_status(&self, entry: &ControllerEntry) -> Result<FanStatusResponse> {
+        let cm = entry
+            .connection_manager()
+            .ok_or(OpenFanError::DeviceNotFound)?;
+        Ok(self.latest(entry.id(), cm).await?.status())
+    }
+
+    /// Last readings of a controller, reading the fans if they are stale.
+    ///
+    /// The commanded state is always current, as it takes no serial traffic.
+    /// A controller that is not connected is read, to reconnect or fail.
+    pub async fn latest(&self, controller_id: &str, cm: &ConnectionManager) -> Result<FanSample> {
+        let connected = cm.connection_state().await == ConnectionState::Connected;
+        let rpm = self
+            .samples
+            .read()
+            .await
+            .get(controller_id)
+            .filter(|sample| sample.taken.elapsed() < self.interval() * 2)
+            .map(|sample| sample.readings.rpm.clone());
+        if let Some(rpm) = rpm
+            && connected
+        {
+            let state = cm
+                .with_controller(async |ctrl| Ok(commanded_state(ctrl)))
+                .await?;
+            return Ok(FanSample { rpm, state });
+        }
+
+        let readings = read_fans(cm).await?;
+        self.samples
+            .write()
+            .await
+            .insert(controller_id.to_string(), Sample::new(readings.clone()));
+        Ok(readings)
+    }
+
+    /// Start sampling every registered controller with a hardware connection.
+    ///
+    /// Configuration changes are forwarded even when sampling is disabled.
+    pub async fn start_all(&self) {
+        let telemetry_config = &self.config.static_config().telemetry;
+
+        let host_task = tokio::spawn(run_host(
+            self.config.clone(),
+            self.sensors.clone(),
+            self.events.clone(),
+            telemetry_config.enabled.then(|| self.interval()),
+        ));
+        if let Some(previous) = self.host_task.lock().await.replace(host_task) {
+            previous.abort();
+        }
+
+        if !telemetry_config.enabled {
+            info!("Fan telemetry disabled in config");
+            return;
+        }
+
+        let mut tasks = self.tasks.lock().await;
+        for entry in self.registry.list().await {
+            let Some(cm) = entry.connection_manager() else {
+                continue;
+            };
+            info!(
+                "Controller '{}': Sampling fans every {}s",
+                entry.id(),
+                self.interval().as_secs()
+            );
+            let handle = tokio::spawn(run_sampler(
+                entry.clone(),
+                cm.clone(),
+                self.interval(),
+                self.events.clone(),
+                self.samples.clone(),
+            ));
+            if let Some(previous) = tasks.insert(entry.id().to_string(), handle) {
+                previous.abort();
+            }
+        }
+    }
+
+    /// Stop all telemetry tasks.
+    pub async fn stop_all(&self) {
+        let mut tasks = self.tasks.lock().await;
+        for (_, handle) in tasks.drain() {
+            handle.abort();
+        }
+        if let Some(handle) = self.host_task.lock().await.take() {
+            handle.abort();
+        }
+    }
+
+    /// Subscribe to telemetry events.
+    pub fn subscribe(&self) -> broadcast::Receiver<TelemetryEvent> {
+        self.events.subscribe()
+    }
+
+    /// Current state, as events for a new subscriber
+    ///
+    /// The connection state and last fan readings of each controller, by
+    /// controller ID.
+    pub async fn snapshot(&self) -> Vec<TelemetryEvent> {
+        let mut entries = self.registry.list().await;
+        entries.sort_by(|a, b| a.id().cmp(b.id()));
+
+        let samples = self.samples.read().await;
+        let mut events = Vec::new();
+        for entry in entries {
+            let Some(cm) = entry.connection_manager() else {
+                continue;
+            };
+            events.push(TelemetryEvent::ConnectionState {
+                controller: entry.id().to_string(),
+                state: cm.connection_state().await.as_str().to_string(),
+            });
+            if let Some(sample) = samples.get(entry.id()) {
+                events.push(sample.event(entry.id()));
+            }
+        }
+        events
+    }
+}
+
+/// Read the RPM and commanded state of every fan.
+async fn read_fans(cm: &ConnectionManager) -> Result<FanSample> {
+    cm.with_controller(async |ctrl| {
+        Ok(FanSample {
+            rpm: ctrl.get_all_fan_rpm().await?,
+            state: commanded_state(ctrl),
+        })
+    })
+    .await
+}
+
+/// PWM and target RPM each fan was last set to
+fn commanded_state(ctrl: &DefaultFanController) -> FanStateData {
+    FanStateData::new(ctrl.get_all_fan_pwm(), ctrl.get_all_fan_rpm_targets())
+}
+
+/// Sampling loop for a single controller.
+///
+/// The fans are only read while there are subscribers.
+async fn run_sampler(
+    entry: Arc<ControllerEntry>,
+    cm: Arc<ConnectionManager>,
+    interval: Duration,
+    events: broadcast::Sender<TelemetryEvent>,
+    samples: Arc<RwLock<HashMap<String, Sample>>>,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut last_state = None;
+
+    loop {
+        ticker.tick().await;
+
+        let state = cm.connection_state().await;
+        if last_state != Some(state.as_str()) {
+            last_state = Some(state.as_str());
+            let _ = events.send(TelemetryEvent::ConnectionState {
+                controller: entry.id().to_string(),
+                state: state.as_str().to_string(),
+            });
+        }
+        // Reconnecting is up to requests and the heartbeat
+        if state != ConnectionState::Connected || events.receiver_count() == 0 {
+            continue;
+        }
+
+        let readings = match read_fans(&cm).await {
+            Ok(readings) => readings,
+            Err(e) => {
+                // Connection problems are reported by the connection manager
+                debug!("Controller '{}': Fan sampling skipped: {}", entry.id(), e);
+                continue;
+            }
+        };
+
+        let sample = Sample::new(readings);
+        let _ = events.send(sample.event(entry.id()));
+        samples.write().await.insert(entry.id().to_string(), sample);
+    }
+}
+
+/// Sensor sampling and configuration change forwarding loop.
+///
+/// Sensors are only read every `sensor_interval` while there are
+/// subscribers, and not at all without an interval.
+async fn run_host(
+    config: Arc<RuntimeConfig>,
+    sensors: Arc<SensorManager>,
+    events: broadcast::Sender<TelemetryEvent>,
+    sensor_interval: Option<Duration>,
+) {
+    let mut changes = config.subscribe_changes();
+    // The ticker is never polled without an interval
+    let mut ticker = tokio::time::interval(sensor_interval.unwrap_or(Duration::from_secs(1)));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            change = changes.recv() => match change {
+                Ok(event) => {
+                    let _ = events.send(event);
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    debug!("Missed {} configuration changes", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            },
+            _ = ticker.tick(), if sensor_interval.is_some() => {
+                if events.receiver_count() > 0 {
+                    let sensors = sensors.list().await;
+                    let _ = events.send(TelemetryEvent::Sensors { sensors });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controllers::{Device, SimulatedDevice, connection};
+    use openfan_core::api::ConfigResource;
+    use openfan_core::config::StaticConfig;
+    use openfan_core::{BoardType, ReconnectConfig};
+    use openfan_hardware::SimulatorConfig;
+    use tempfile::TempDir;
+
+    async fn create_telemetry(temp_dir: &TempDir) -> (Telemetry, SimulatedDevice) {
+        let data_dir = temp_dir.path().join("data");
+        let config_path = temp_dir.path().join("config.toml");
+        let static_config = StaticConfig::with_data_dir(data_dir.clone());
+        tokio::fs::create_dir_all(&data_dir).await.unwrap();
+        tokio::fs::write(&config_path, static_config.to_toml().unwrap())
+            .await
+            .unwrap();
+        let config = Arc::new(RuntimeConfig::load(&config_path).await.unwrap());
+
+        let board = BoardType::Custom { fan_count: 2 }.to_board_info();
+        let simulated = SimulatedDevice::new(
+            "main",
+            SimulatorConfig::new(2)
+                .with_noise(0)
+                .with_time_constant(Duration::ZERO),
+        );
+        let device = Device::Simulated(simulated.clone());
+        let controller = connection::open_device(&device, &board, 1000, false)
+            .await
+            .unwrap();
+        let cm = Arc::new(ConnectionManager::new(
+            controller,
+            ReconnectConfig {
+                max_attempts: 1,
+                ..Default::default()
+            },
+            device,
+            board.clone(),
+            1000,
+            false,
+        ));
+        cm.with_controller(async |ctrl| ctrl.set_fan_pwm(0, 50).await)
+            .await
+            .unwrap();
+        let registry = Arc::new(ControllerRegistry::new());
+        registry
+            .register(
+                ControllerEntry::builder("main", board)
+                    .maybe_connection_manager(Some(cm))
+                    .build(),
+            )
+            .await
+            .unwrap();
+
+        let sensors = Arc::new(SensorManager::new(&config.static_config().sensors));
+        (Telemetry::new(registry, config, sensors), simulated)
+    }
+
+    /// Next event matching `predicate`, skipping the others
+    async fn next_event(
+        events: &mut broadcast::Receiver<TelemetryEvent>,
+        predicate: impl Fn(&TelemetryEvent) -> bool,
+    ) -> TelemetryEvent {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let event = events.recv().await.unwrap();
+                if predicate(&event) {
+                    return event;
+                }
+            }
+        })
+        .await
+        .expect("event should be sent")
+    }
+
+    #[tokio::test]
+    async fn test_sampler_caches_and_broadcasts_readings() {
+        let temp_dir = TempDir::new().unwrap();
+        let (telemetry, simulated) = create_telemetry(&temp_dir).await;
+        assert_eq!(telemetry.snapshot().await.len(), 1);
+
+        let mut events = telemetry.subscribe();
+        telemetry.start_all().await;
+
+        let event = next_event(&mut events, |e| e.name() == "fan_status").await;
+        let TelemetryEvent::FanStatus { status, .. } = event else {
+            unreachable!()
+        };
+        assert_eq!(status.pwms.get(&0), Some(&50));
+        assert!(status.rpms[&0] > 0);
+
+        // New subscribers start from the last readings
+        let snapshot = telemetry.snapshot().await;
+        assert_eq!(snapshot.len(), 2);
+        assert!(matches!(
+            &snapshot[0],
+            TelemetryEvent::ConnectionState { state, .. } if state == "connected"
+        ));
+
+        // Losing the board is announced on the next samples
+        simulated.disconnect();
+        let event = next_event(&mut events, |e| e.name() == "connection_state").await;
+        assert!(matches!(
+            event,
+            TelemetryEvent::ConnectionState { state, .. } if state == "disconnected"
+        ));
+
+        telemetry.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_fan_status_shares_readings() {
+        let temp_dir = TempDir::new().unwrap();
+        let (telemetry, _simulated) = create_telemetry(&temp_dir).await;
+        let entry = telemetry.registry.get_or_err("main").await.unwrap();
+        let cm = entry.connection_manager().unwrap().clone();
+        let transactions = || cm.transaction_stats().transactions();
+
+        // Without subscribers the sampler leaves the fans alone
+        telemetry.start_all().await;
+        let before = transactions();
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert_eq!(transactions(), before);
+
+        // Status requests within an interval read the fans once
+        let status = telemetry.fan_status(&entry).await.unwrap();
+        assert_eq!(status.pwms.get(&0), Some(&50));
+        telemetry.fan_status(&entry).await.unwrap();
+        assert_eq!(transactions(), before + 1);
+
+        // Commanded values are current even when the RPM is cached
+        cm.with_controller(async |ctrl| ctrl.set_fan_pwm(0, 80).await)
+            .await
+            .unwrap();
+        let status = telemetry.fan_status(&entry).await.unwrap();
+        assert_eq!(status.pwms.get(&0), Some(&80));
+        assert_eq!(transactions(), before + 2);
+
+        telemetry.stop_all().await;
+    }
+
+    #[tokio::test]
+    async fn test_status_without_connection_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let (telemetry, _simulated) = create_telemetry(&temp_dir).await;
+        let board = BoardType::Custom { fan_count: 2 }.to_board_info();
+        telemetry
+            .registry
+            .register(ControllerEntry::builder("unplugged", board).build())
+            .await
+            .unwrap();
+        let entry = telemetry.registry.get_or_err("unplugged").await.unwrap();
+
+        let result = telemetry.fan_status(&entry).await;
+        assert!(matches!(result, Err(OpenFanError::DeviceNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_config_changes_are_forwarded() {
+        let temp_dir = TempDir::new().unwrap();
+        let (telemetry, _simulated) = create_telemetry(&temp_dir).await;
+        let mut events = telemetry.subscribe();
+        telemetry.start_all().await;
+
+        let data = telemetry.config.controller_data("main").await.unwrap();
+        data.save_aliases().await.unwrap();
+
+        let event = next_event(&mut events, |e| e.name() == "config_changed").await;
+        assert_eq!(event.controller(), Some("main"));
+        assert!(matches!(
+            event,
+            TelemetryEvent::ConfigChanged {
+                resource: ConfigResource::Aliases,
+                ..
+            }
+        ));
+
+        telemetry.stop_all().await;
+    }
+}